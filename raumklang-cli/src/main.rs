@@ -1,7 +1,12 @@
+mod project;
+mod remote;
+mod template;
+
 use std::{
-    io::{self, Write},
+    io::{self, Read, Write},
+    path::Path,
     sync::mpsc::Receiver,
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime},
 };
 
 use clap::{Parser, Subcommand};
@@ -13,11 +18,18 @@ use plotters::{
     style::RGBColor,
 };
 use raumklang_core::{
-    dbfs, loudness,
+    alignment::SubAlignment,
+    average, bands, dbfs, drc, drift, impedance, loudness, noise_rating,
+    phase::{self, ExcessPhaseCorrection},
     signals::{ExponentialSweep, FiniteSignal, LinearSineSweep, PinkNoise, WhiteNoise},
-    volume_to_amplitude, AudioEngine, ImpulseResponse,
+    spl, volume_to_amplitude, wav, AudioEngine, DeconvolutionMethod, ImpulseResponse, Loopback,
+    Measurement, Rta, StopHandle, TransferFunction,
 };
 use rustfft::{num_complex::Complex, FftPlanner};
+use serde::{Deserialize, Serialize};
+
+/// Path that stands for stdin or stdout, to use the commands in pipes.
+const STDIO: &str = "-";
 
 #[derive(Parser)]
 #[clap(author, version)]
@@ -34,6 +46,43 @@ enum Command {
         #[arg(short, long)]
         input_port: String,
     },
+    Rta {
+        #[arg(short, long)]
+        input_port: String,
+        /// bands per octave, e.g. 3 for third-octave bands
+        #[clap(short, long, default_value_t = 3)]
+        fraction: u8,
+        #[clap(long, default_value_t = 8192)]
+        fft_size: usize,
+        #[clap(long, default_value_t = 8)]
+        averages: usize,
+    },
+    SplLog {
+        #[arg(short, long)]
+        input_port: String,
+        #[clap(short, long, value_enum, default_value_t = Weighting::A)]
+        weighting: Weighting,
+        #[clap(short, long, value_enum, default_value_t = TimeWeighting::Fast)]
+        time_weighting: TimeWeighting,
+        /// sound pressure level in dB that corresponds to 0 dBFS
+        #[clap(short, long, default_value_t = 120.0)]
+        calibration: f32,
+        /// logging interval in seconds
+        #[clap(long, default_value_t = 1)]
+        interval: u64,
+        #[arg(long)]
+        file_path: Option<String>,
+    },
+    /// Rates a recording of the background noise of a room with the noise
+    /// criterion (NC) and noise rating (NR) curves
+    NoiseRating {
+        file_path: String,
+        #[clap(long, default_value_t = 0)]
+        channel: u16,
+        /// sound pressure level in dB that corresponds to 0 dBFS
+        #[clap(short, long, default_value_t = 120.0)]
+        calibration: f32,
+    },
     Signal {
         #[clap(short, long, default_value_t = 5)]
         duration: usize,
@@ -43,8 +92,14 @@ enum Command {
         dest_ports: Vec<String>,
         #[arg(long)]
         file_path: Option<String>,
+        /// measurement template shared with the GUI, sets the signal, the
+        /// duration and the volume
+        #[arg(long)]
+        template: Option<String>,
+        #[command(flatten)]
+        port_options: PortOptions,
         #[command(subcommand)]
-        type_: SignalType,
+        type_: Option<SignalType>,
     },
     RunMeasurement {
         #[clap(short, long, default_value_t = 5)]
@@ -57,20 +112,318 @@ enum Command {
         input_port: String,
         #[arg(long)]
         file_path: String,
+        /// time in ms to keep recording after the signal ended
+        #[clap(long, default_value_t = 1000)]
+        decay: u64,
+        /// silence played before the signal, e.g. `500ms`, for devices that
+        /// mute the first moments of a stream, it is not recorded
+        #[clap(long, value_parser = parse_duration, default_value = "0s")]
+        pre_roll: Duration,
+        /// address of a `remote-serve` instance that plays the signal instead
+        #[arg(long)]
+        remote: Option<String>,
+        /// number of frames buffered between the audio thread and the file writer
+        #[clap(long, default_value_t = 16384)]
+        capture_buffer: usize,
+        /// measurement template shared with the GUI, sets the signal, the
+        /// duration and the volume
+        #[arg(long)]
+        template: Option<String>,
+        #[command(flatten)]
+        port_options: PortOptions,
+        #[command(flatten)]
+        wav_options: WavOptions,
         #[command(subcommand)]
-        type_: SignalType,
+        type_: Option<SignalType>,
+    },
+    /// Plays a sweep into the loopback only and checks, that it comes back as
+    /// a clean impulse, e.g. to detect resampling or DSP of the driver
+    VerifyLoopback {
+        #[clap(short, long, default_value_t = 5)]
+        duration: usize,
+        #[clap(short, long, default_value_t = 0.5)]
+        volume: f32,
+        #[arg(long = "dest-port")]
+        dest_ports: Vec<String>,
+        /// port, that records the loopback
+        #[arg(short, long)]
+        input_port: String,
+        #[clap(long, default_value_t = 20)]
+        start_frequency: u16,
+        #[clap(long, default_value_t = 20_000)]
+        end_frequency: u16,
+        #[command(flatten)]
+        port_options: PortOptions,
     },
+    /// Plays signals on request of another instance, see `run-measurement --remote`.
+    /// Only local clients can connect by default, listen on e.g.
+    /// `0.0.0.0:7878` to accept the measuring machine.
+    RemoteServe {
+        #[clap(long, default_value = "127.0.0.1:7878")]
+        address: String,
+        #[arg(long = "dest-port")]
+        dest_ports: Vec<String>,
+    },
+    /// Paths can be `-` to read the WAV data from stdin, or to write it to
+    /// stdout
     ComputeRIR {
         loopback_path: String,
         measurement_path: String,
         result_path: String,
+        /// channel of the loopback file, that holds the loopback signal
+        #[clap(long, default_value_t = 0)]
+        loopback_channel: u16,
+        /// channel of the measurement file, that holds the microphone signal
+        #[clap(long, default_value_t = 0)]
+        measurement_channel: u16,
+        #[clap(long, value_enum, default_value_t = Deconvolution::SpectralDivision)]
+        method: Deconvolution,
+        #[command(flatten)]
+        wav_options: WavOptions,
+    },
+    /// Computes the impulse response of a recording of a logarithmic sweep
+    /// with the inverse filter of the sweep, no loopback is needed. Paths can
+    /// be `-` like for `compute-rir`
+    ComputeRirFromSweep {
+        measurement_path: String,
+        result_path: String,
+        /// channel of the measurement file, that holds the microphone signal
+        #[clap(long, default_value_t = 0)]
+        measurement_channel: u16,
+        #[clap(short, long, default_value_t = 5)]
+        duration: usize,
+        #[clap(short, long, default_value_t = 0.5)]
+        volume: f32,
+        /// measurement template shared with the GUI, sets the signal, the
+        /// duration and the volume
+        #[arg(long)]
+        template: Option<String>,
+        #[command(flatten)]
+        wav_options: WavOptions,
+        /// the played sweep, has to be a `log-sweep`
+        #[command(subcommand)]
+        type_: Option<SignalType>,
     },
     Spectrogram {
         file_path: String,
     },
+    TransferFunction {
+        #[clap(short, long, default_value_t = 0.5)]
+        volume: f32,
+        #[arg(long = "dest-port")]
+        dest_ports: Vec<String>,
+        #[arg(short, long)]
+        reference_port: String,
+        #[arg(short, long)]
+        input_port: String,
+        #[clap(long, default_value_t = 16384)]
+        fft_size: usize,
+        #[clap(long, default_value_t = 16)]
+        averages: usize,
+    },
+    /// Transfer function between two captured channels, e.g. the input and
+    /// the output of an amplifier or a DSP, printed as CSV
+    TwoPort {
+        /// file, that holds the reference channel
+        reference_path: String,
+        /// file, that holds the response channel, defaults to the reference
+        /// file
+        #[arg(long)]
+        response_path: Option<String>,
+        #[clap(long, default_value_t = 0)]
+        reference_channel: u16,
+        #[clap(long, default_value_t = 1)]
+        response_channel: u16,
+        #[clap(long, default_value_t = 16384)]
+        fft_size: usize,
+        /// latency of the response in ms, estimated if not given
+        #[arg(long)]
+        delay: Option<f32>,
+    },
+    /// Measures the impedance of a driver with a sense resistor in series to
+    /// it and estimates its Thiele-Small parameters
+    Impedance {
+        #[clap(short, long, default_value_t = 5)]
+        duration: usize,
+        #[clap(short, long, default_value_t = 0.5)]
+        volume: f32,
+        #[arg(long = "dest-port")]
+        dest_ports: Vec<String>,
+        /// port, that measures the amplifier output across the whole jig
+        #[arg(short, long)]
+        reference_port: String,
+        /// port, that measures the driver or the sense resistor, see `--jig`
+        #[arg(short, long)]
+        input_port: String,
+        #[clap(long, value_enum, default_value_t = JigTopology::DriverVoltage)]
+        jig: JigTopology,
+        /// sense resistor in ohm
+        #[clap(long, default_value_t = 10.0)]
+        resistance: f32,
+        /// measured DC resistance of the voice coil in ohm, estimated from
+        /// the impedance if not given
+        #[arg(long)]
+        dc_resistance: Option<f32>,
+        #[clap(long, default_value_t = 10)]
+        start_frequency: u16,
+        #[clap(long, default_value_t = 20_000)]
+        end_frequency: u16,
+        /// highest frequency in Hz, that is searched for the resonance
+        #[clap(long, default_value_t = 1000.0)]
+        max_resonance: f32,
+        #[clap(long, default_value_t = 65536)]
+        fft_size: usize,
+        /// writes the impedance magnitude and phase as CSV
+        #[arg(long)]
+        file_path: Option<String>,
+    },
+    /// Averages the frequency responses of several microphone positions,
+    /// printed as CSV
+    Average {
+        loopback_path: String,
+        #[arg(required = true)]
+        measurement_paths: Vec<String>,
+        #[clap(long, value_enum, default_value_t = SpatialWeighting::Equal)]
+        weighting: SpatialWeighting,
+        /// distance of every position to the listening position in m, in the
+        /// order of the measurements, e.g. `0,0.5,0.5,1`
+        #[arg(long, value_delimiter = ',')]
+        distances: Vec<f32>,
+        /// bands per octave, the moving microphone emulation averages over
+        #[clap(long, default_value_t = 3)]
+        fraction: u8,
+    },
+    AlignSub {
+        loopback_path: String,
+        mains_path: String,
+        sub_path: String,
+        #[clap(short, long, default_value_t = 80.0)]
+        crossover_frequency: f32,
+        /// maximum delay in ms searched in both directions
+        #[clap(long, default_value_t = 20)]
+        max_delay: u64,
+    },
+    /// Paths can be `-` to read the WAV data from stdin, or to write it to
+    /// stdout
+    ExcessPhase {
+        loopback_path: String,
+        measurement_path: String,
+        result_path: String,
+        #[clap(long, default_value_t = 20.0)]
+        lower_frequency: f32,
+        #[clap(long, default_value_t = 500.0)]
+        upper_frequency: f32,
+        /// analysed part of the impulse response after its peak in ms
+        #[clap(long, default_value_t = 100)]
+        window: u64,
+        /// maximum level of the filters pre-response in dB
+        #[clap(long, default_value_t = -60.0, allow_hyphen_values = true)]
+        pre_echo_ceiling: f32,
+        /// minimum signal to noise ratio in dB of a corrected third-octave band
+        #[clap(long, default_value_t = 10.0, allow_hyphen_values = true)]
+        min_snr: f32,
+        #[command(flatten)]
+        wav_options: WavOptions,
+    },
+    /// Converts the raw files of a DRC (drc-fir) workflow into WAV files and
+    /// target curves
+    ConvertDrc {
+        /// directory the converted files are written to
+        output_dir: String,
+        /// sweep generated by `glsweep`, used as loopback
+        #[arg(long)]
+        sweep: Option<String>,
+        /// recordings of the sweep, that are deconvolved by `lsconv`
+        #[arg(long = "recording")]
+        recordings: Vec<String>,
+        /// DRC configuration, its target curve, microphone compensation and
+        /// impulse response are converted
+        #[arg(long)]
+        config: Option<String>,
+        /// sample rate of the raw files
+        #[clap(long, default_value_t = 44_100)]
+        sample_rate: u32,
+        #[command(flatten)]
+        wav_options: WavOptions,
+    },
+    /// Prints metrics of all measurements of a project saved by the GUI as
+    /// JSON
+    ProjectStats {
+        project_path: String,
+    },
 }
 
-#[derive(Subcommand)]
+/// Format of the written WAV files.
+#[derive(clap::Args)]
+struct WavOptions {
+    /// write 64 bit instead of 32 bit float samples
+    #[arg(long)]
+    f64: bool,
+    /// always write RF64 (BW64) files, not only when exceeding 4 GB
+    #[arg(long)]
+    rf64: bool,
+}
+
+/// Handling of ports, that don't exist yet.
+#[derive(clap::Args)]
+struct PortOptions {
+    /// wait for missing ports, e.g. of an USB microphone that is still being
+    /// enumerated, instead of failing immediately
+    #[arg(long)]
+    wait_for_ports: bool,
+    /// how long to wait for missing ports, e.g. `30s` or `500ms`
+    #[clap(long, value_parser = parse_duration, default_value = "30s")]
+    timeout: Duration,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum Weighting {
+    A,
+    C,
+    Z,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum TimeWeighting {
+    /// 125 ms
+    Fast,
+    /// 1 s
+    Slow,
+}
+
+/// Weighting of the microphone positions, when averaging their responses.
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum SpatialWeighting {
+    Equal,
+    /// closer positions to the listening position are weighted higher, see
+    /// `--distances`
+    Distance,
+    /// additionally averages the power within fractional octaves, like a
+    /// moving microphone measurement, see `--fraction`
+    MovingMic,
+}
+
+/// What the input port measures, the reference port always measures the
+/// amplifier output.
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum JigTopology {
+    /// voltage across the driver
+    DriverVoltage,
+    /// voltage across the sense resistor
+    SenseVoltage,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum Deconvolution {
+    SpectralDivision,
+    RegularizedDivision,
+    InverseSweep,
+    /// for MLS and noise signals
+    CrossCorrelation,
+}
+
+#[derive(Debug, Subcommand, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 enum SignalType {
     WhiteNoise,
     PinkNoise,
@@ -91,20 +444,62 @@ enum SignalType {
 fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
 
+    // fade out instead of cutting off, a running sweep ends with a click otherwise
+    let stop = StopHandle::default();
+    ctrlc::set_handler({
+        let stop = stop.clone();
+        move || {
+            stop.stop();
+            // leave a few process cycles for the fade out
+            std::thread::sleep(raumklang_core::FADE_OUT + Duration::from_millis(100));
+            std::process::exit(130);
+        }
+    })?;
+
     match cli.subcommand {
         Command::Signal {
             duration,
             volume,
             dest_ports,
             file_path: _,
+            template,
+            port_options,
             type_,
         } => {
-            let engine = init_playback_engine(&dest_ports)?;
+            let (type_, duration, volume) = signal(template, type_, duration, volume)?;
+
+            let engine = init_playback_engine(&dest_ports, port_options.timeout(), &stop)?;
             let response = play_signal(&engine, type_, volume, duration)?;
             response.recv()?;
             Ok(())
         }
         Command::Rms { input_port } => meter_rms(&input_port),
+        Command::Rta {
+            input_port,
+            fraction,
+            fft_size,
+            averages,
+        } => rta(&input_port, fraction, fft_size, averages),
+        Command::SplLog {
+            input_port,
+            weighting,
+            time_weighting,
+            calibration,
+            interval,
+            file_path,
+        } => spl_log(
+            &input_port,
+            weighting.into(),
+            time_weighting.into(),
+            calibration,
+            Duration::from_secs(interval),
+            file_path,
+        ),
+        Command::NoiseRating {
+            file_path,
+            channel,
+            calibration,
+        } => noise_rating(&file_path, channel, calibration),
         Command::RunMeasurement {
             duration,
             volume,
@@ -112,37 +507,118 @@ fn main() -> anyhow::Result<()> {
             input_port,
             type_,
             file_path,
+            decay,
+            pre_roll,
+            remote,
+            capture_buffer,
+            template,
+            port_options,
+            wav_options,
         } => {
-            let engine = init_playback_engine(&dest_ports)?;
-            let mut buf = engine.register_in_port("measurement_in", &input_port)?;
-            let repsose = play_signal(&engine, type_, volume, duration)?;
-
-            let spec = hound::WavSpec {
-                channels: 1,
-                sample_rate: engine.sample_rate() as u32,
-                bits_per_sample: 32,
-                sample_format: hound::SampleFormat::Float,
+            let (type_, duration, volume) = signal(template, type_, duration, volume)?;
+
+            let metadata = wav::Metadata {
+                description: format!("Recording of {input_port}"),
+                stimulus: Some(type_.stimulus(volume, duration)),
+                ..metadata()
+            };
+
+            let engine = init_playback_engine(&dest_ports, port_options.timeout(), &stop)?;
+            if let Some(timeout) = port_options.timeout() {
+                engine.wait_for_ports(&[&input_port], timeout)?;
+            }
+
+            let mut pre_roll =
+                (pre_roll.as_secs_f32() * engine.sample_rate() as f32).round() as usize;
+            let (mut buf, repsose) = match remote {
+                // the remote playback can't be aligned to the local recording
+                Some(address) => {
+                    if pre_roll > 0 {
+                        eprintln!("warning: --pre-roll is ignored with --remote");
+                        pre_roll = 0;
+                    }
+
+                    (
+                        engine.register_in_port("measurement_in", &input_port)?,
+                        remote::play(address, type_, volume, duration)?,
+                    )
+                }
+                None => {
+                    let buf = engine.register_capture_port(
+                        "measurement_in",
+                        &input_port,
+                        capture_buffer,
+                    )?;
+
+                    // the recording starts with the pre-roll, it is skipped below
+                    if pre_roll > 0 {
+                        let silence: Box<dyn FiniteSignal<Item = f32>> =
+                            Box::new(vec![0.0; pre_roll].into_iter());
+                        engine.play_signal(silence)?;
+                    }
+
+                    (buf, play_signal(&engine, type_, volume, duration)?)
+                }
             };
 
-            // FIXME hardcoded window size
-            let mut loudness = loudness::Meter::new(13230); // 44100samples / 1000ms * 300ms
-            let mut writer = hound::WavWriter::create(file_path, spec)?;
+            // streamed to disk, so that an interrupted measurement leaves a readable file
+            let mut writer = wav::StreamWriter::create_with(
+                file_path,
+                engine.sample_rate() as u32,
+                1,
+                wav_options.into(),
+            )?;
+            writer.set_metadata(&metadata)?;
+
+            let mut loudness = loudness::Meter::with_sample_rate(engine.sample_rate());
+            let mut recorded = 0;
+            let mut decay_end = None;
             loop {
                 let iter = buf.pop_iter();
                 for s in iter {
+                    if pre_roll > 0 {
+                        pre_roll -= 1;
+                        continue;
+                    }
+
                     loudness.update(s);
                     writer.write_sample(s)?;
+                    recorded += 1;
                 }
 
-                if repsose.try_recv().is_ok() {
+                // keep recording the decay tail, after the signal has ended
+                if decay_end.is_none() && repsose.try_recv().is_ok() {
+                    decay_end = Some(Instant::now() + Duration::from_millis(decay));
+                }
+
+                if decay_end.is_some_and(|end| Instant::now() >= end) {
                     break;
                 }
 
-                std::thread::sleep(Duration::from_millis(10)); // buf size is 1024, 1 / 44100 *
-                                                               // 1024 = 0,023 s = 23ms / 2 = 11,5
-                                                               //      ~ 10
+                // the capture buffer holds at least 1024 samples, which are
+                // 1024 / 96000 = 10.7 ms at the highest common sample rate
+                std::thread::sleep(Duration::from_millis(10));
+            }
+
+            let sample_rate = engine.sample_rate();
+            let decay = (decay as usize * sample_rate) / 1000;
+            if let Err(truncation) =
+                raumklang_core::check_recording_length(duration * sample_rate, decay, recorded)
+            {
+                eprintln!("warning: {truncation}, padding with silence");
+
+                for _ in 0..truncation.missing {
+                    writer.write_sample(0.0)?;
+                }
             }
+
             writer.finalize()?;
+
+            let dropped = engine.dropped_frames();
+            if dropped > 0 {
+                eprintln!("warning: {dropped} frames were dropped, try a larger --capture-buffer");
+            }
+
             println!(
                 "rms: {} dbfs, peak: {} dbfs",
                 dbfs(loudness.rms()),
@@ -151,46 +627,582 @@ fn main() -> anyhow::Result<()> {
 
             Ok(())
         }
+        Command::VerifyLoopback {
+            duration,
+            volume,
+            dest_ports,
+            input_port,
+            start_frequency,
+            end_frequency,
+            port_options,
+        } => verify_loopback(
+            &dest_ports,
+            &input_port,
+            volume,
+            duration,
+            (start_frequency, end_frequency),
+            port_options.timeout(),
+            &stop,
+        ),
+        Command::RemoteServe {
+            address,
+            dest_ports,
+        } => remote::serve(address, &dest_ports, &stop),
         Command::ComputeRIR {
             loopback_path,
             measurement_path,
             result_path,
+            loopback_channel,
+            measurement_channel,
+            method,
+            wav_options,
         } => {
-            let impulse_respone = ImpulseResponse::from_files(&loopback_path, &measurement_path)?;
+            let (loopback, measurement) = load_signals(
+                &loopback_path,
+                loopback_channel,
+                &measurement_path,
+                measurement_channel,
+            )?;
+
+            let measurement = match drift::estimate(&loopback, &measurement) {
+                Some(ppm) => {
+                    status(&result_path, format!("clock drift: {ppm:+.2} ppm"));
+                    measurement.correct_drift(ppm)
+                }
+                None => measurement,
+            };
+
+            let impulse_response =
+                ImpulseResponse::from_signals_with(&loopback, &measurement, method.into())?;
+
+            write_wav(
+                &result_path,
+                impulse_response.sample_rate,
+                impulse_response.data.iter().map(|s| s.re),
+                wav_options.into(),
+                Some(&wav::Metadata {
+                    description: format!("Impulse response of {measurement_path}"),
+                    ..metadata()
+                }),
+            )?;
+
+            let duration = impulse_response.data.len() as f32 / impulse_response.sample_rate as f32;
+            status(
+                &result_path,
+                format!("Impulse response of {duration}s written to: {result_path}"),
+            );
 
-            let spec = hound::WavSpec {
-                channels: 1,
-                sample_rate: impulse_respone.sample_rate,
-                bits_per_sample: 32,
-                sample_format: hound::SampleFormat::Float,
+            Ok(())
+        }
+        Command::ComputeRirFromSweep {
+            measurement_path,
+            result_path,
+            measurement_channel,
+            duration,
+            volume,
+            template,
+            wav_options,
+            type_,
+        } => {
+            let (type_, duration, volume) = signal(template, type_, duration, volume)?;
+            let SignalType::LogSweep {
+                start_frequency,
+                end_frequency,
+            } = type_
+            else {
+                anyhow::bail!("the inverse filter needs a logarithmic sweep");
             };
 
-            let mut writer = hound::WavWriter::create(&result_path, spec)?;
-            for s in impulse_respone.data.iter().map(|s| s.re) {
-                writer.write_sample(s)?;
-            }
-            writer.finalize()?;
+            let measurement = if measurement_path == STDIO {
+                Measurement::from_wav_bytes(&read_stdin()?, measurement_channel)?
+            } else {
+                Measurement::from_file_channel(&measurement_path, measurement_channel)?
+            };
+
+            let sample_rate = measurement.sample_rate();
+            let sweep = ExponentialSweep::new(
+                start_frequency as f32,
+                end_frequency as f32,
+                volume_to_amplitude(volume),
+                duration * sample_rate as usize,
+                sample_rate as usize,
+            );
+
+            let impulse_response = ImpulseResponse::from_inverse_filter(
+                sample_rate,
+                &sweep.inverse_filter(),
+                measurement.iter().copied(),
+            );
 
-            let duration = impulse_respone.data.len() as f32 / impulse_respone.sample_rate as f32;
-            println!("Impulse response of : {duration}s, written to: {result_path}");
+            write_wav(
+                &result_path,
+                impulse_response.sample_rate,
+                impulse_response.data.iter().map(|s| s.re),
+                wav_options.into(),
+                Some(&wav::Metadata {
+                    description: format!("Impulse response of {measurement_path}"),
+                    ..metadata()
+                }),
+            )?;
+
+            let duration = impulse_response.data.len() as f32 / impulse_response.sample_rate as f32;
+            status(
+                &result_path,
+                format!("Impulse response of {duration}s written to: {result_path}"),
+            );
 
             Ok(())
         }
         Command::Spectrogram { file_path } => {
             let mut reader = hound::WavReader::open(file_path)?;
+            let sample_rate = reader.spec().sample_rate as usize;
             let data: Vec<f32> = reader.samples::<f32>().collect::<Result<Vec<f32>, _>>()?;
 
             let data: Vec<_> = data.iter().map(Complex::from).collect();
 
-            plot_heatmap(data)?;
+            plot_heatmap(data, sample_rate)?;
+
+            Ok(())
+        }
+        Command::TransferFunction {
+            volume,
+            dest_ports,
+            reference_port,
+            input_port,
+            fft_size,
+            averages,
+        } => live_transfer_function(
+            &dest_ports,
+            &reference_port,
+            &input_port,
+            volume,
+            fft_size,
+            averages,
+            &stop,
+        ),
+        Command::TwoPort {
+            reference_path,
+            response_path,
+            reference_channel,
+            response_channel,
+            fft_size,
+            delay,
+        } => two_port(
+            &reference_path,
+            response_path.as_deref().unwrap_or(&reference_path),
+            reference_channel,
+            response_channel,
+            fft_size,
+            delay,
+        ),
+        Command::Impedance {
+            duration,
+            volume,
+            dest_ports,
+            reference_port,
+            input_port,
+            jig,
+            resistance,
+            dc_resistance,
+            start_frequency,
+            end_frequency,
+            max_resonance,
+            fft_size,
+            file_path,
+        } => {
+            let jig = match jig {
+                JigTopology::DriverVoltage => impedance::Jig::DriverVoltage { resistance },
+                JigTopology::SenseVoltage => impedance::Jig::SenseVoltage { resistance },
+            };
+
+            let engine = init_playback_engine(&dest_ports, None, &stop)?;
+            let mut reference_buf =
+                engine.register_capture_port("reference_in", &reference_port, 16384)?;
+            let mut response_buf =
+                engine.register_capture_port("measurement_in", &input_port, 16384)?;
+
+            let sweep = SignalType::LogSweep {
+                start_frequency,
+                end_frequency,
+            };
+            let response = play_signal(&engine, sweep, volume, duration)?;
+
+            let mut reference = vec![];
+            let mut recording = vec![];
+            let mut end = None;
+            while end.is_none_or(|end| Instant::now() < end) {
+                reference.extend(reference_buf.pop_iter());
+                recording.extend(response_buf.pop_iter());
+
+                // the electrical response has no decay worth waiting for
+                if end.is_none() && response.try_recv().is_ok() {
+                    end = Some(Instant::now() + Duration::from_millis(200));
+                }
+
+                std::thread::sleep(Duration::from_millis(10));
+            }
+
+            let sample_rate = engine.sample_rate() as u32;
+            let impedance = impedance::Impedance::from_recordings(
+                jig,
+                &reference,
+                &recording,
+                sample_rate,
+                fft_size,
+            );
+
+            if let Some(file_path) = file_path {
+                let mut file = std::fs::File::create(file_path)?;
+                writeln!(file, "frequency,magnitude,phase")?;
+
+                for ((frequency, magnitude), phase) in impedance
+                    .frequencies
+                    .iter()
+                    .zip(impedance.magnitude())
+                    .zip(impedance.phase())
+                {
+                    writeln!(file, "{frequency:.2},{magnitude:.3},{phase:.1}")?;
+                }
+            }
+
+            let Some(parameters) =
+                impedance.thiele_small(start_frequency.into(), max_resonance, dc_resistance)
+            else {
+                anyhow::bail!("no resonance found in the impedance");
+            };
+
+            println!("Re:   {:.2} ohm", parameters.re);
+            println!("Fs:   {:.1} Hz", parameters.fs);
+            println!("Zmax: {:.1} ohm", parameters.z_max);
+            println!("Qms:  {:.2}", parameters.qms);
+            println!("Qes:  {:.2}", parameters.qes);
+            println!("Qts:  {:.2}", parameters.qts);
+
+            Ok(())
+        }
+        Command::Average {
+            loopback_path,
+            measurement_paths,
+            weighting,
+            distances,
+            fraction,
+        } => {
+            let weighting = match weighting {
+                SpatialWeighting::Equal => average::Weighting::Equal,
+                SpatialWeighting::Distance => average::Weighting::Distance(distances),
+                SpatialWeighting::MovingMic => average::Weighting::MovingMic { fraction },
+            };
+
+            average_positions(&loopback_path, &measurement_paths, &weighting)
+        }
+        Command::AlignSub {
+            loopback_path,
+            mains_path,
+            sub_path,
+            crossover_frequency,
+            max_delay,
+        } => {
+            let mains = ImpulseResponse::from_files(&loopback_path, &mains_path)?;
+            let sub = ImpulseResponse::from_files(&loopback_path, &sub_path)?;
+
+            let alignment = SubAlignment::new(crossover_frequency)
+                .max_delay(Duration::from_millis(max_delay))
+                .find(&mains, &sub)?;
+
+            let delay = alignment.delay_ms(mains.sample_rate);
+            if delay < 0.0 {
+                println!("delay mains by: {:.2} ms", delay.abs());
+            } else {
+                println!("delay sub by: {delay:.2} ms");
+            }
+            println!(
+                "sub polarity: {}, summation around {crossover_frequency} Hz: {:.2} dB",
+                alignment.polarity, alignment.efficiency
+            );
+
+            Ok(())
+        }
+        Command::ExcessPhase {
+            loopback_path,
+            measurement_path,
+            result_path,
+            lower_frequency,
+            upper_frequency,
+            window,
+            pre_echo_ceiling,
+            min_snr,
+            wav_options,
+        } => {
+            let (loopback, measurement) = load_signals(&loopback_path, 0, &measurement_path, 0)?;
+            let impulse_response = ImpulseResponse::from_signals(&loopback, &measurement)?;
+
+            let correction = ExcessPhaseCorrection::new(lower_frequency, upper_frequency)
+                .window(Duration::from_millis(window))
+                .pre_echo_ceiling(pre_echo_ceiling)
+                .min_snr(min_snr);
+
+            let noisy: Vec<_> = correction
+                .band_snr(&impulse_response)
+                .into_iter()
+                .filter(|(band, snr)| {
+                    *snr < min_snr && band.upper > lower_frequency && band.lower < upper_frequency
+                })
+                .map(|(band, snr)| format!("{:.0} Hz ({snr:.1} dB)", band.center))
+                .collect();
+
+            if !noisy.is_empty() {
+                status(
+                    &result_path,
+                    format!(
+                        "not corrected, SNR below {min_snr} dB: {}",
+                        noisy.join(", ")
+                    ),
+                );
+            }
+
+            let filter = correction.filter(&impulse_response);
+
+            write_wav(
+                &result_path,
+                impulse_response.sample_rate,
+                filter.iter().copied(),
+                wav_options.into(),
+                None,
+            )?;
+
+            status(
+                &result_path,
+                format!(
+                    "excess phase correction of {} taps, written to: {result_path}",
+                    filter.len()
+                ),
+            );
+
+            let pre_ringing = phase::pre_ringing(&filter, impulse_response.sample_rate);
+            status(
+                &result_path,
+                format!(
+                    "pre-ringing above {pre_echo_ceiling} dB: {} ms, audibility: {:+.1} dB ({})",
+                    pre_ringing.duration_above(pre_echo_ceiling).as_millis(),
+                    pre_ringing.audibility,
+                    if pre_ringing.audibility > 0.0 {
+                        "likely audible"
+                    } else {
+                        "masked"
+                    }
+                ),
+            );
+
+            Ok(())
+        }
+        Command::ConvertDrc {
+            output_dir,
+            sweep,
+            recordings,
+            config,
+            sample_rate,
+            wav_options,
+        } => convert_drc(
+            Path::new(&output_dir),
+            sweep,
+            &recordings,
+            config,
+            sample_rate,
+            wav_options.into(),
+        ),
+        Command::ProjectStats { project_path } => {
+            let stats = project::Project::load(&project_path)?.stats()?;
+            println!("{}", serde_json::to_string_pretty(&stats)?);
 
             Ok(())
         }
     }
 }
 
-fn plot_heatmap(ir: Vec<Complex<f32>>) -> anyhow::Result<()> {
-    let _sample_rate = 44100;
+impl SignalType {
+    fn stimulus(&self, volume: f32, duration: usize) -> wav::Stimulus {
+        let (signal, frequency_range) = match *self {
+            SignalType::WhiteNoise => ("white noise", None),
+            SignalType::PinkNoise => ("pink noise", None),
+            SignalType::LinearSweep {
+                start_frequency,
+                end_frequency,
+            } => ("linear sweep", Some((start_frequency, end_frequency))),
+            SignalType::LogSweep {
+                start_frequency,
+                end_frequency,
+            } => ("logarithmic sweep", Some((start_frequency, end_frequency))),
+        };
+
+        wav::Stimulus {
+            signal: signal.to_string(),
+            frequency_range,
+            duration: Duration::from_secs(duration as u64),
+            level: Some(dbfs(volume_to_amplitude(volume))).filter(|level| level.is_finite()),
+        }
+    }
+}
+
+/// The signal, its duration and volume of the measurement `template`, if
+/// given, otherwise the ones on the command line.
+fn signal(
+    template: Option<String>,
+    type_: Option<SignalType>,
+    duration: usize,
+    volume: f32,
+) -> anyhow::Result<(SignalType, usize, f32)> {
+    match (template, type_) {
+        (Some(name), _) => template::signal(&name),
+        (None, Some(type_)) => Ok((type_, duration, volume)),
+        (None, None) => anyhow::bail!("either a signal or --template is needed"),
+    }
+}
+
+/// Loads the loopback and the measurement, one of them can be read from
+/// stdin.
+fn load_signals(
+    loopback_path: &str,
+    loopback_channel: u16,
+    measurement_path: &str,
+    measurement_channel: u16,
+) -> anyhow::Result<(Loopback, Measurement)> {
+    if loopback_path == STDIO && measurement_path == STDIO {
+        anyhow::bail!("only one file can be read from stdin");
+    }
+
+    let loopback = if loopback_path == STDIO {
+        Loopback::from_wav_bytes(&read_stdin()?, loopback_channel)?
+    } else {
+        Loopback::from_file_channel(loopback_path, loopback_channel)?
+    };
+
+    let measurement = if measurement_path == STDIO {
+        Measurement::from_wav_bytes(&read_stdin()?, measurement_channel)?
+    } else {
+        Measurement::from_file_channel(measurement_path, measurement_channel)?
+    };
+
+    Ok((loopback, measurement))
+}
+
+fn read_stdin() -> io::Result<Vec<u8>> {
+    let mut bytes = vec![];
+    io::stdin().lock().read_to_end(&mut bytes)?;
+
+    Ok(bytes)
+}
+
+/// Writes a mono WAV file, or the WAV data to stdout if `path` is `-`.
+fn write_wav(
+    path: &str,
+    sample_rate: u32,
+    samples: impl IntoIterator<Item = f32>,
+    format: wav::Format,
+    metadata: Option<&wav::Metadata>,
+) -> anyhow::Result<()> {
+    if path != STDIO {
+        wav::write(path, sample_rate, 1, samples, format, metadata)?;
+        return Ok(());
+    }
+
+    // the header is written last, which needs a seekable writer
+    let mut buffer = io::Cursor::new(vec![]);
+    wav::write_to(&mut buffer, sample_rate, 1, samples, format, metadata)?;
+
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(buffer.get_ref())?;
+    stdout.flush()?;
+
+    Ok(())
+}
+
+/// Prints status messages to stderr, while the result is written to stdout.
+fn status(result_path: &str, message: String) {
+    if result_path == STDIO {
+        eprintln!("{message}");
+    } else {
+        println!("{message}");
+    }
+}
+
+/// Metadata common to all written files.
+fn metadata() -> wav::Metadata {
+    wav::Metadata {
+        originator: concat!("raumklang-cli ", env!("CARGO_PKG_VERSION")).to_string(),
+        date: Some(SystemTime::now()),
+        ..wav::Metadata::default()
+    }
+}
+
+impl PortOptions {
+    fn timeout(&self) -> Option<Duration> {
+        self.wait_for_ports.then_some(self.timeout)
+    }
+}
+
+/// Parses durations like `30s`, `500ms` or `30`, which are seconds.
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+
+    let (number, unit) = match value.strip_suffix("ms") {
+        Some(number) => (number, Duration::from_millis(1)),
+        None => (
+            value.strip_suffix('s').unwrap_or(value),
+            Duration::from_secs(1),
+        ),
+    };
+
+    number
+        .trim()
+        .parse::<u32>()
+        .map(|number| unit * number)
+        .map_err(|err| format!("invalid duration `{value}`: {err}"))
+}
+
+impl From<WavOptions> for wav::Format {
+    fn from(options: WavOptions) -> Self {
+        wav::Format {
+            sample_format: if options.f64 {
+                wav::SampleFormat::Float64
+            } else {
+                wav::SampleFormat::Float32
+            },
+            rf64: options.rf64,
+        }
+    }
+}
+
+impl From<Deconvolution> for DeconvolutionMethod {
+    fn from(method: Deconvolution) -> Self {
+        match method {
+            Deconvolution::SpectralDivision => DeconvolutionMethod::SpectralDivision,
+            Deconvolution::RegularizedDivision => DeconvolutionMethod::RegularizedDivision,
+            Deconvolution::InverseSweep => DeconvolutionMethod::InverseSweep,
+            Deconvolution::CrossCorrelation => DeconvolutionMethod::CrossCorrelation,
+        }
+    }
+}
+
+impl From<Weighting> for spl::Weighting {
+    fn from(weighting: Weighting) -> Self {
+        match weighting {
+            Weighting::A => spl::Weighting::A,
+            Weighting::C => spl::Weighting::C,
+            Weighting::Z => spl::Weighting::Z,
+        }
+    }
+}
+
+impl From<TimeWeighting> for spl::TimeWeighting {
+    fn from(time_weighting: TimeWeighting) -> Self {
+        match time_weighting {
+            TimeWeighting::Fast => spl::TimeWeighting::Fast,
+            TimeWeighting::Slow => spl::TimeWeighting::Slow,
+        }
+    }
+}
+
+fn plot_heatmap(ir: Vec<Complex<f32>>, sample_rate: usize) -> anyhow::Result<()> {
     let window_size = 4 * 1024;
 
     //window = np.append(
@@ -203,7 +1215,7 @@ fn plot_heatmap(ir: Vec<Complex<f32>>) -> anyhow::Result<()> {
     let stop_sample = ir.len();
     //
     let time_shift = 15; // ms
-    let time_shift_samples = 44100 * time_shift / 1000;
+    let time_shift_samples = sample_rate * time_shift / 1000;
     let rem = (stop_sample - start_sample - window_size) % time_shift_samples;
     let _stop_sample = stop_sample + time_shift - rem;
 
@@ -227,14 +1239,14 @@ fn plot_heatmap(ir: Vec<Complex<f32>>) -> anyhow::Result<()> {
     let samples_array = Array::from(ir.clone());
 
     const MAX_FREQ: usize = 1000;
-    const WINDOW_SIZE: usize = 44100 * 300 / 1000;
     const OVERLAP: f64 = 0.9;
-    const SKIP_SIZE: usize = (WINDOW_SIZE as f64 * (1f64 - OVERLAP)) as usize;
+    let fft_size = sample_rate * 300 / 1000;
+    let skip_size = (fft_size as f64 * (1f64 - OVERLAP)) as usize;
 
     let windows = samples_array
-        .windows(ndarray::Dim(WINDOW_SIZE))
+        .windows(ndarray::Dim(fft_size))
         .into_iter()
-        .step_by(SKIP_SIZE)
+        .step_by(skip_size)
         .collect::<Vec<_>>();
     let mut windows = ndarray::stack(Axis(0), &windows).unwrap();
 
@@ -243,7 +1255,7 @@ fn plot_heatmap(ir: Vec<Complex<f32>>) -> anyhow::Result<()> {
 
     // get the FFT up and running
     let mut planner = FftPlanner::new();
-    let fft = planner.plan_fft_forward(WINDOW_SIZE);
+    let fft = planner.plan_fft_forward(fft_size);
 
     // Since we have a 2-D array of our windows with shape [WINDOW_SIZE, (num_samples / WINDOW_SIZE) - 1], we can run an FFT on every row.
     // Next step is to do something multithreaded with Rayon, but we're not cool enough for that yet.
@@ -258,7 +1270,7 @@ fn plot_heatmap(ir: Vec<Complex<f32>>) -> anyhow::Result<()> {
     // And finally, only look at the first half of the spectrogram - the first (n/2)+1 points of each FFT
     // https://dsp.stackexchange.com/questions/4825/why-is-the-fft-mirrored
     //let windows = windows.slice_move(ndarray::s![.., ..(WINDOW_SIZE / 2) + 1]);
-    let windows = windows.slice_move(ndarray::s![.., ..MAX_FREQ * WINDOW_SIZE / 44100 + 1]);
+    let windows = windows.slice_move(ndarray::s![.., ..MAX_FREQ * fft_size / sample_rate + 1]);
 
     // get some dimensions for drawing
     // The shape is in [nrows, ncols], but we want to transpose this.
@@ -392,14 +1404,25 @@ fn plot_heatmap(ir: Vec<Complex<f32>>) -> anyhow::Result<()> {
     //fig.show()
 }
 
-fn init_playback_engine<T, I, J>(dest_ports: &[T]) -> anyhow::Result<AudioEngine<I, J>>
+/// `wait` is the time to wait for missing destination ports, they are
+/// expected to exist, if it is `None`. The engine fades out, once `stop` is
+/// used.
+fn init_playback_engine<T, I, J>(
+    dest_ports: &[T],
+    wait: Option<Duration>,
+    stop: &StopHandle,
+) -> anyhow::Result<AudioEngine<I, J>>
 where
     T: AsRef<str>,
     I: Iterator<Item = f32> + Send + 'static,
     J: IntoIterator<IntoIter = I> + Send + Sync + 'static,
 {
     let jack_client_name = env!("CARGO_BIN_NAME");
-    let engine = AudioEngine::new(jack_client_name)?;
+    let engine = AudioEngine::with_stop_handle(jack_client_name, stop.clone())?;
+
+    if let Some(timeout) = wait {
+        engine.wait_for_ports(dest_ports, timeout)?;
+    }
     engine.register_out_port("signal_out", dest_ports)?;
 
     Ok(engine)
@@ -447,13 +1470,22 @@ fn play_signal(
                 n_samples,
                 sample_rate,
             );
-
-            // Box::new(sweep.iter().collect::<Vec<_>>().into_iter())
-            Box::new(sweep.into_iter())
+            Box::new(sweep)
         }
     };
 
-    Ok(engine.play_signal(signal)?)
+    // protect the DAC and amplifiers from overshooting between the samples
+    let mut signal: Vec<f32> = signal.collect();
+    let true_peak = loudness::true_peak(&signal);
+    if true_peak > 1.0 {
+        eprintln!(
+            "warning: true peak of {:+.2} dBTP, reducing the level to 0 dBTP",
+            dbfs(true_peak)
+        );
+        signal.iter_mut().for_each(|s| *s /= true_peak);
+    }
+
+    Ok(engine.play_signal(Box::new(signal.into_iter()))?)
 }
 
 pub fn meter_rms(source_port_name: &str) -> anyhow::Result<()> {
@@ -469,8 +1501,7 @@ pub fn meter_rms(source_port_name: &str) -> anyhow::Result<()> {
     let mut last_rms = Instant::now();
     let mut last_peak = Instant::now();
 
-    // FIXME hardcoded window size
-    let mut loudness = loudness::Meter::new(13230); // 44100samples / 1000ms * 300ms
+    let mut loudness = loudness::Meter::with_sample_rate(engine.sample_rate());
 
     loop {
         let iter = cons.pop_iter();
@@ -497,3 +1528,440 @@ pub fn meter_rms(source_port_name: &str) -> anyhow::Result<()> {
         std::thread::sleep(Duration::from_millis(75));
     }
 }
+
+pub fn rta(
+    source_port_name: &str,
+    fraction: u8,
+    fft_size: usize,
+    averages: usize,
+) -> anyhow::Result<()> {
+    let jack_client_name = env!("CARGO_BIN_NAME");
+
+    let engine = AudioEngine::new(jack_client_name)?;
+
+    // FIXME: type problem
+    engine.play_signal([0.0])?;
+
+    let mut cons = engine.register_in_port("rta_in", source_port_name)?;
+    let sample_rate = engine.sample_rate() as u32;
+
+    let mut analyzer = Rta::new(fft_size, averages);
+
+    let mut last_draw = Instant::now();
+    let mut last_peak = Instant::now();
+
+    loop {
+        let data: Vec<_> = cons.pop_iter().collect();
+        analyzer.push(&data);
+
+        if last_draw.elapsed() > Duration::from_millis(150) {
+            // clear screen and move the cursor to the top left corner
+            print!("\x1b[2J\x1b[H");
+
+            for level in analyzer.bands(fraction, sample_rate) {
+                let bar = ((level.level + 90.0) / 2.0).clamp(0.0, 45.0) as usize;
+
+                println!(
+                    "{:>8.1} Hz {:>7.1} dBFS (peak {:>7.1}) {}",
+                    level.band.center,
+                    level.level,
+                    level.peak,
+                    "#".repeat(bar)
+                );
+            }
+            io::stdout().flush()?;
+
+            last_draw = Instant::now();
+        }
+
+        if last_peak.elapsed() > Duration::from_secs(2) {
+            analyzer.reset_peak();
+            last_peak = Instant::now();
+        }
+
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+fn spl_log(
+    source_port_name: &str,
+    weighting: spl::Weighting,
+    time_weighting: spl::TimeWeighting,
+    calibration: f32,
+    interval: Duration,
+    file_path: Option<String>,
+) -> anyhow::Result<()> {
+    let jack_client_name = env!("CARGO_BIN_NAME");
+
+    let engine = AudioEngine::new(jack_client_name)?;
+
+    // FIXME: type problem
+    engine.play_signal([0.0])?;
+
+    let mut cons = engine.register_in_port("spl_in", source_port_name)?;
+    let sample_rate = engine.sample_rate() as u32;
+
+    let mut logger = spl::Logger::new(
+        weighting,
+        time_weighting,
+        calibration,
+        interval,
+        sample_rate,
+    );
+
+    let mut file = file_path.map(std::fs::File::create).transpose()?;
+    if let Some(file) = file.as_mut() {
+        writeln!(file, "time,leq,lmax,l10,l50,l90")?;
+    }
+
+    let start = Instant::now();
+    loop {
+        let data: Vec<_> = cons.pop_iter().collect();
+
+        for record in logger.push(&data) {
+            let time = start.elapsed().as_secs_f32();
+
+            println!(
+                "{time:>8.1} s  Leq: {:>5.1} {weighting}, Lmax: {:>5.1}, L10: {:>5.1}, L50: {:>5.1}, L90: {:>5.1}",
+                record.leq, record.lmax, record.l10, record.l50, record.l90
+            );
+
+            if let Some(file) = file.as_mut() {
+                writeln!(
+                    file,
+                    "{time:.1},{:.1},{:.1},{:.1},{:.1},{:.1}",
+                    record.leq, record.lmax, record.l10, record.l50, record.l90
+                )?;
+            }
+        }
+
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+fn verify_loopback(
+    dest_ports: &[String],
+    input_port: &str,
+    volume: f32,
+    duration: usize,
+    (start_frequency, end_frequency): (u16, u16),
+    wait: Option<Duration>,
+    stop: &StopHandle,
+) -> anyhow::Result<()> {
+    let engine = init_playback_engine(dest_ports, wait, stop)?;
+    if let Some(timeout) = wait {
+        engine.wait_for_ports(&[input_port], timeout)?;
+    }
+    let mut buf = engine.register_capture_port("loopback_in", input_port, 16384)?;
+
+    let sample_rate = engine.sample_rate();
+    let sweep: Vec<f32> = ExponentialSweep::new(
+        start_frequency.into(),
+        end_frequency.into(),
+        volume_to_amplitude(volume),
+        duration * sample_rate,
+        sample_rate,
+    )
+    .collect();
+    let response = engine.play_signal(Box::new(sweep.clone().into_iter()))?;
+
+    let mut recording = vec![];
+    let mut end = None;
+    while end.is_none_or(|end| Instant::now() < end) {
+        recording.extend(buf.pop_iter());
+
+        // the loopback has no decay, but the latency of the interface
+        if end.is_none() && response.try_recv().is_ok() {
+            end = Some(Instant::now() + Duration::from_millis(200));
+        }
+
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    let loopback = Loopback::new(Measurement::new(sample_rate as u32, recording));
+    let Some(check) = raumklang_core::loopback::Check::new(
+        &sweep,
+        &loopback,
+        start_frequency.into(),
+        end_frequency.into(),
+    ) else {
+        anyhow::bail!("the loopback is silent, check the connections");
+    };
+
+    println!("Latency:   {:.2} ms", check.latency.as_secs_f32() * 1000.0);
+    println!("SNR:       {:.1} dB", check.snr);
+    println!("Deviation: {:.2} dB", check.deviation);
+    match check.drift {
+        Some(drift) => println!("Drift:     {drift:.1} ppm"),
+        None => println!("Drift:     unknown, the sweep is too short"),
+    }
+
+    let issues = check.issues();
+    if issues.is_empty() {
+        println!("The loopback is clean");
+    }
+
+    for issue in issues {
+        eprintln!("warning: {issue}");
+    }
+
+    Ok(())
+}
+
+fn noise_rating(file_path: &str, channel: u16, calibration: f32) -> anyhow::Result<()> {
+    let recording = Measurement::from_file_channel(file_path, channel)?;
+    let samples: Vec<f32> = recording.iter().copied().collect();
+
+    let rating =
+        noise_rating::NoiseRating::from_recording(&samples, recording.sample_rate(), calibration);
+
+    for (center, level) in noise_rating::OCTAVE_BANDS.iter().zip(rating.levels) {
+        println!("{center:>6.0} Hz: {level:>5.1} dB");
+    }
+
+    // ratings are given as the next higher integer
+    match rating.nc {
+        Some(nc) => println!("NC {:.0}", nc.ceil()),
+        None => println!("NC above 70"),
+    }
+    println!("NR {:.0}", rating.nr.ceil());
+
+    Ok(())
+}
+
+fn two_port(
+    reference_path: &str,
+    response_path: &str,
+    reference_channel: u16,
+    response_channel: u16,
+    fft_size: usize,
+    delay: Option<f32>,
+) -> anyhow::Result<()> {
+    let reference = Measurement::from_file_channel(reference_path, reference_channel)?;
+    let response = Measurement::from_file_channel(response_path, response_channel)?;
+
+    let sample_rate = reference.sample_rate();
+    anyhow::ensure!(
+        sample_rate == response.sample_rate(),
+        "sample rates don't match: {sample_rate} != {}",
+        response.sample_rate()
+    );
+
+    let reference: Vec<f32> = reference.iter().copied().collect();
+    let response: Vec<f32> = response.iter().copied().collect();
+
+    let delay = match delay {
+        Some(delay) => (delay / 1000.0 * sample_rate as f32).round() as usize,
+        None => TransferFunction::estimate_delay(&reference, &response, sample_rate),
+    };
+    eprintln!(
+        "delay: {:.2} ms",
+        delay as f32 / sample_rate as f32 * 1000.0
+    );
+
+    let transfer_function =
+        TransferFunction::from_recordings(&reference, &response, fft_size, delay);
+    anyhow::ensure!(
+        transfer_function.frames() > 0,
+        "the recordings are shorter than the FFT size"
+    );
+
+    println!("frequency,magnitude,phase,coherence");
+    for (((frequency, magnitude), phase), coherence) in transfer_function
+        .frequencies(sample_rate)
+        .zip(transfer_function.magnitude())
+        .zip(transfer_function.phase())
+        .zip(transfer_function.coherence())
+    {
+        println!(
+            "{frequency:.2},{:.2},{:.1},{coherence:.3}",
+            dbfs(magnitude),
+            phase.to_degrees()
+        );
+    }
+
+    Ok(())
+}
+
+fn average_positions(
+    loopback_path: &str,
+    measurement_paths: &[String],
+    weighting: &average::Weighting,
+) -> anyhow::Result<()> {
+    let frequency_responses = measurement_paths
+        .iter()
+        .map(|path| {
+            let impulse_response = ImpulseResponse::from_files(loopback_path, path)?;
+            Ok(project::frequency_response(&impulse_response))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let sample_rate = frequency_responses[0].sample_rate;
+    anyhow::ensure!(
+        frequency_responses
+            .iter()
+            .all(|response| response.sample_rate == sample_rate),
+        "the measurements have different sample rates"
+    );
+
+    let magnitudes: Vec<Vec<f32>> = frequency_responses
+        .iter()
+        .map(|response| response.data.iter().map(|s| s.norm()).collect())
+        .collect();
+    let magnitudes: Vec<&[f32]> = magnitudes.iter().map(Vec::as_slice).collect();
+
+    let resolution = frequency_responses[0].resolution();
+    let average = average::magnitudes(&magnitudes, weighting, resolution)?;
+
+    println!("frequency,magnitude");
+    for (i, magnitude) in average.into_iter().enumerate().skip(1) {
+        println!("{:.2},{:.2}", i as f32 * resolution, dbfs(magnitude));
+    }
+
+    Ok(())
+}
+
+fn convert_drc(
+    output_dir: &Path,
+    sweep: Option<String>,
+    recordings: &[String],
+    config: Option<String>,
+    sample_rate: u32,
+    format: wav::Format,
+) -> anyhow::Result<()> {
+    std::fs::create_dir_all(output_dir)?;
+
+    let convert = |source: &Path, raw_format, name: &str| -> anyhow::Result<()> {
+        let measurement = drc::read_raw(source, sample_rate, raw_format)?;
+        let path = output_dir.join(format!("{name}.wav"));
+
+        let metadata = wav::Metadata {
+            description: format!("Converted from {}", source.display()),
+            ..metadata()
+        };
+        wav::write(
+            &path,
+            sample_rate,
+            1,
+            measurement.iter().copied(),
+            format,
+            Some(&metadata),
+        )?;
+
+        println!("{} -> {}", source.display(), path.display());
+        Ok(())
+    };
+
+    if let Some(sweep) = sweep {
+        convert(Path::new(&sweep), drc::RawFormat::Float, "loopback")?;
+    }
+
+    for recording in recordings {
+        let recording = Path::new(recording);
+        let name = recording
+            .file_stem()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or("measurement".to_string());
+
+        convert(recording, drc::RawFormat::Float, &name)?;
+    }
+
+    let Some(config) = config else {
+        return Ok(());
+    };
+
+    let config_path = Path::new(&config);
+    let drc_config = drc::Config::parse(&std::fs::read_to_string(config_path)?);
+    // DRC is usually run from the directory of its configuration
+    let base_dir = config_path.parent().unwrap_or(Path::new(""));
+
+    if let Some((impulse_response, raw_format)) = drc_config.impulse_response() {
+        convert(
+            &base_dir.join(impulse_response),
+            raw_format,
+            "impulse_response",
+        )?;
+    }
+
+    let curves = [
+        ("target curve", drc_config.target_curve(), "target.txt"),
+        (
+            "microphone compensation",
+            drc_config.microphone_compensation(),
+            "microphone.txt",
+        ),
+    ];
+
+    for (description, source, name) in curves {
+        let Some(source) = source.map(|source| base_dir.join(source)) else {
+            continue;
+        };
+
+        let points = drc::parse_points(&std::fs::read_to_string(&source)?);
+
+        let path = output_dir.join(name);
+        let mut file = std::fs::File::create(&path)?;
+        writeln!(file, "* {description} converted from {}", source.display())?;
+        // 0 Hz can't be shown on a logarithmic axis
+        for (frequency, level) in points.into_iter().filter(|(f, _)| *f > 0.0) {
+            writeln!(file, "{frequency} {level}")?;
+        }
+
+        println!("{} -> {}", source.display(), path.display());
+    }
+
+    Ok(())
+}
+
+fn live_transfer_function(
+    dest_ports: &[String],
+    reference_port: &str,
+    input_port: &str,
+    volume: f32,
+    fft_size: usize,
+    averages: usize,
+    stop: &StopHandle,
+) -> anyhow::Result<()> {
+    let engine = init_playback_engine(dest_ports, None, stop)?;
+    let mut reference_buf = engine.register_in_port("reference_in", reference_port)?;
+    let mut measurement_buf = engine.register_in_port("measurement_in", input_port)?;
+
+    let amplitude = volume_to_amplitude(volume);
+    let signal: Box<dyn FiniteSignal<Item = f32>> = Box::new(PinkNoise::with_amplitude(amplitude));
+    engine.play_signal(signal)?;
+
+    let mut analyzer = TransferFunction::new(fft_size, averages);
+    let resolution = analyzer.frequency_resolution(engine.sample_rate() as u32);
+    let octave_bands = bands::fractional_octave(1, 20.0, 20_000.0);
+
+    let mut last_print = Instant::now();
+    loop {
+        let reference: Vec<_> = reference_buf.pop_iter().collect();
+        let measurement: Vec<_> = measurement_buf.pop_iter().collect();
+        analyzer.push(&reference, &measurement);
+
+        if last_print.elapsed() > Duration::from_secs(1) {
+            let power: Vec<_> = analyzer.magnitude().map(|m| m * m).collect();
+            let coherence: Vec<_> = analyzer.coherence().collect();
+
+            let levels: Vec<_> = octave_bands
+                .iter()
+                .map(|band| {
+                    let power = bands::energy_mean(&power, resolution, band);
+                    let coherence = bands::energy_mean(&coherence, resolution, band);
+
+                    format!(
+                        "{:.0} Hz: {:>6.1} dB ({coherence:.2})",
+                        band.center,
+                        10.0 * power.log10()
+                    )
+                })
+                .collect();
+
+            println!("{}", levels.join(" | "));
+            last_print = Instant::now();
+        }
+
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
@@ -0,0 +1,252 @@
+//! Headless analysis of the projects saved by the GUI, e.g. to track the
+//! response of a room over time.
+//!
+//! Only the parts of the project file, that are needed to load the
+//! measurements, are read, all other fields are ignored.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use raumklang_core::{
+    bands, dbfs, decay::DecayTimes, drift, loudness::LevelReport, rew, FrequencyResponse,
+    ImpulseResponse, Loopback, Measurement, Window, WindowBuilder, WindowedImpulseResponse,
+};
+use serde::{Deserialize, Serialize};
+
+/// Sample rate of the impulse responses of REW text exports, like in the GUI.
+const REW_SAMPLE_RATE: u32 = 48_000;
+
+#[derive(Debug, Deserialize)]
+pub struct Project {
+    loopback: Option<File>,
+    measurements: Vec<File>,
+}
+
+#[derive(Debug, Deserialize)]
+struct File {
+    path: PathBuf,
+    #[serde(default)]
+    levels: Option<LevelReport>,
+    #[serde(default)]
+    source: Source,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+enum Source {
+    #[default]
+    Recording,
+    Rew,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Stats {
+    pub path: PathBuf,
+    /// Peak level of the measurement file in dBFS
+    pub peak_level: f32,
+    /// Length of the causal part of the impulse response in seconds
+    pub impulse_response_length: f32,
+    /// Broadband reverberation times in seconds
+    pub rt60: Rt60,
+    /// Octave band levels of the windowed frequency response
+    pub frequency_response: Vec<Level>,
+    /// Levels stored by the GUI, when it recorded the measurement
+    pub recording: Option<LevelReport>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Rt60 {
+    pub edt: Option<f32>,
+    pub t20: Option<f32>,
+    pub t30: Option<f32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Level {
+    /// Center frequency in Hz
+    pub frequency: f32,
+    /// Level in dB
+    pub level: f32,
+}
+
+impl Project {
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read(path)
+            .with_context(|| format!("could not read project {}", path.display()))?;
+
+        let mut project: Project = serde_json::from_slice(&content)
+            .with_context(|| format!("could not parse project {}", path.display()))?;
+
+        // the GUI stores absolute paths, relative ones are taken from the
+        // directory of the project
+        let dir = path.parent().unwrap_or(Path::new(""));
+        for file in project.loopback.iter_mut().chain(&mut project.measurements) {
+            file.path = dir.join(&file.path);
+        }
+
+        Ok(project)
+    }
+
+    /// Computes the metrics of all measurements in the order of the project.
+    pub fn stats(&self) -> anyhow::Result<Vec<Stats>> {
+        let loopback = self
+            .loopback
+            .as_ref()
+            .map(|loopback| Loopback::from_file(&loopback.path))
+            .transpose()
+            .context("could not load the loopback")?;
+
+        self.measurements
+            .iter()
+            .map(|measurement| {
+                stats(measurement, loopback.as_ref())
+                    .with_context(|| format!("measurement {}", measurement.path.display()))
+            })
+            .collect()
+    }
+}
+
+fn stats(file: &File, loopback: Option<&Loopback>) -> anyhow::Result<Stats> {
+    let (peak, impulse_response) = match file.source {
+        Source::Recording => {
+            let loopback = loopback.context("the project has no loopback")?;
+            let measurement = Measurement::from_file(&file.path)?;
+
+            let measurement = match drift::estimate(loopback, &measurement) {
+                Some(ppm) => measurement.correct_drift(ppm),
+                None => measurement,
+            };
+
+            (
+                peak(measurement.iter()),
+                ImpulseResponse::from_signals(loopback, &measurement)?,
+            )
+        }
+        Source::Rew => {
+            let is_wav = file
+                .path
+                .extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("wav"));
+
+            let impulse_response = if is_wav {
+                rew::impulse_response(&Measurement::from_file(&file.path)?)
+            } else {
+                let export = rew::TextExport::parse(&std::fs::read_to_string(&file.path)?)?;
+                let len = REW_SAMPLE_RATE.next_power_of_two() as usize;
+                export.impulse_response(REW_SAMPLE_RATE, len)
+            };
+
+            let data: Vec<f32> = impulse_response.data.iter().map(|s| s.re).collect();
+            (peak(data.iter()), impulse_response)
+        }
+    };
+
+    let sample_rate = impulse_response.sample_rate;
+    let causal: Vec<f32> = impulse_response.data[..impulse_response.data.len() / 2]
+        .iter()
+        .map(|s| s.re)
+        .collect();
+
+    // the decay starts at the direct sound
+    let direct_sound = causal
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.abs().total_cmp(&b.abs()))
+        .map_or(0, |(position, _)| position);
+    let decay_times = DecayTimes::new(&causal[direct_sound..], sample_rate);
+
+    Ok(Stats {
+        path: file.path.clone(),
+        peak_level: dbfs(peak),
+        impulse_response_length: causal.len() as f32 / sample_rate as f32,
+        rt60: Rt60 {
+            edt: decay_times.edt,
+            t20: decay_times.t20,
+            t30: decay_times.t30,
+        },
+        frequency_response: octave_levels(&impulse_response),
+        recording: file.levels,
+    })
+}
+
+/// Frequency response of the impulse response, windowed like the default
+/// window of the GUI.
+pub fn frequency_response(impulse_response: &ImpulseResponse) -> FrequencyResponse {
+    let sample_rate = impulse_response.sample_rate;
+    let left = sample_rate as usize / 8; // 125 ms
+    let right = sample_rate as usize / 2; // 500 ms
+
+    let window = WindowBuilder::new(Window::Tukey(0.25), left, Window::Tukey(0.25), right).build();
+    let windowed = WindowedImpulseResponse::new(impulse_response, &window, left);
+
+    FrequencyResponse::from_windowed(&windowed)
+}
+
+/// Levels of the impulse response in octave bands, see [`frequency_response`].
+fn octave_levels(impulse_response: &ImpulseResponse) -> Vec<Level> {
+    let sample_rate = impulse_response.sample_rate;
+
+    let frequency_response = frequency_response(impulse_response);
+    let power: Vec<f32> = frequency_response
+        .data
+        .iter()
+        .map(|s| s.norm_sqr())
+        .collect();
+    let resolution = frequency_response.resolution();
+
+    bands::fractional_octave(1, 31.5, 16_000.0)
+        .into_iter()
+        .filter(|band| band.upper < sample_rate as f32 / 2.0)
+        .map(|band| {
+            let bins = ((band.upper - band.lower) / resolution).max(1.0);
+            let power = bands::energy_sum(&power, resolution, &band) / bins;
+
+            Level {
+                frequency: band.center,
+                level: 10.0 * power.log10(),
+            }
+        })
+        .collect()
+}
+
+fn peak<'a>(samples: impl Iterator<Item = &'a f32>) -> f32 {
+    samples.map(|s| s.abs()).fold(0.0, f32::max)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reads_the_measurements_of_a_gui_project() {
+        let content = r#"{
+            "loopback": { "path": "/data/loopback.wav", "playback_level": null },
+            "measurements": [
+                {
+                    "path": "left.wav",
+                    "playback_level": -6.0,
+                    "levels": { "stimulus_crest_factor": 3.0, "crest_factor": 9.5, "peak": -12.0 },
+                    "source": "Recording"
+                },
+                { "path": "/data/rew.txt", "source": "Rew" },
+                { "path": "/data/right.wav" }
+            ],
+            "measurement_operation": "Copy",
+            "wav_format": "Float32",
+            "calibration": null
+        }"#;
+
+        let project: Project = serde_json::from_str(content).unwrap();
+
+        assert!(project.loopback.is_some());
+        assert_eq!(project.measurements.len(), 3);
+        assert_eq!(
+            project.measurements[0]
+                .levels
+                .map(|levels| levels.headroom()),
+            Some(12.0)
+        );
+        assert!(matches!(project.measurements[1].source, Source::Rew));
+        assert!(matches!(project.measurements[2].source, Source::Recording));
+    }
+}
@@ -0,0 +1,200 @@
+//! Remote control of the signal playback, so that the stimulus can be played
+//! on a machine connected to the DAC, while another one captures and analyses.
+//!
+//! The protocol is line delimited JSON over TCP, every request is answered
+//! with one or more responses.
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    sync::mpsc::{self, Receiver},
+    thread,
+};
+
+use serde::{Deserialize, Serialize};
+
+use raumklang_core::{signals::FiniteSignal, AudioEngine, StopHandle};
+
+use crate::{init_playback_engine, play_signal, SignalType};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum Request {
+    Ping,
+    Play {
+        signal: SignalType,
+        /// duration in seconds
+        duration: usize,
+        volume: f32,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Response {
+    Pong,
+    Started { sample_rate: usize },
+    Finished,
+    Error { message: String },
+}
+
+/// Serves playback requests one connection at a time, a running playback
+/// fades out once `stop` is used. A failing connection is logged and closed,
+/// the server keeps accepting new ones.
+pub fn serve(
+    address: impl ToSocketAddrs,
+    dest_ports: &[String],
+    stop: &StopHandle,
+) -> anyhow::Result<()> {
+    let engine = init_playback_engine(dest_ports, None, stop)?;
+    let listener = TcpListener::bind(address)?;
+
+    println!("listening on {}", listener.local_addr()?);
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => {
+                eprintln!("failed to accept connection: {err}");
+                continue;
+            }
+        };
+
+        let peer = match stream.peer_addr() {
+            Ok(peer) => peer.to_string(),
+            Err(_) => "unknown peer".to_string(),
+        };
+        println!("{peer} connected");
+
+        match handle_connection(&engine, stream, &peer) {
+            Ok(()) => println!("{peer} disconnected"),
+            Err(err) => eprintln!("{peer}: connection closed: {err}"),
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_connection(
+    engine: &AudioEngine<Box<dyn FiniteSignal<Item = f32>>, Box<dyn FiniteSignal<Item = f32>>>,
+    stream: TcpStream,
+    peer: &str,
+) -> anyhow::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let mut respond = |response: &Response| -> anyhow::Result<()> {
+        let line = serde_json::to_string(response)?;
+        writeln!(writer, "{line}")?;
+        Ok(())
+    };
+
+    for line in BufReader::new(stream).lines() {
+        let request = match serde_json::from_str(&line?) {
+            Ok(request) => request,
+            Err(err) => {
+                respond(&Response::Error {
+                    message: err.to_string(),
+                })?;
+                continue;
+            }
+        };
+
+        match request {
+            Request::Ping => respond(&Response::Pong)?,
+            Request::Play { volume, .. } if !(0.0..=1.0).contains(&volume) => {
+                respond(&Response::Error {
+                    message: format!("volume must be between 0.0 and 1.0, got {volume}"),
+                })?
+            }
+            Request::Play {
+                signal,
+                duration,
+                volume,
+            } => {
+                println!("{peer}: playing signal for {duration} s");
+
+                match play_signal(engine, signal, volume, duration) {
+                    Ok(finished) => {
+                        respond(&Response::Started {
+                            sample_rate: engine.sample_rate(),
+                        })?;
+                        finished.recv()?;
+                        respond(&Response::Finished)?;
+                    }
+                    Err(err) => respond(&Response::Error {
+                        message: err.to_string(),
+                    })?,
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Requests playback from a remote instance, the returned receiver is
+/// notified once the remote playback has finished, like a local playback.
+pub fn play(
+    address: impl ToSocketAddrs,
+    signal: SignalType,
+    volume: f32,
+    duration: usize,
+) -> anyhow::Result<Receiver<bool>> {
+    let mut stream = TcpStream::connect(address)?;
+
+    let request = serde_json::to_string(&Request::Play {
+        signal,
+        duration,
+        volume,
+    })?;
+    writeln!(stream, "{request}")?;
+
+    let mut lines = BufReader::new(stream).lines();
+
+    match next_response(&mut lines)? {
+        Response::Started { sample_rate } => {
+            println!("remote playback started at {sample_rate} Hz")
+        }
+        Response::Error { message } => anyhow::bail!("remote playback failed: {message}"),
+        response => anyhow::bail!("unexpected response: {response:?}"),
+    }
+
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let finished = matches!(next_response(&mut lines), Ok(Response::Finished));
+        let _ = sender.send(finished);
+    });
+
+    Ok(receiver)
+}
+
+fn next_response(
+    lines: &mut impl Iterator<Item = std::io::Result<String>>,
+) -> anyhow::Result<Response> {
+    let line = lines
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("connection closed by remote"))??;
+
+    Ok(serde_json::from_str(&line)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn play_request_is_tagged_json() {
+        let request = Request::Play {
+            signal: SignalType::LogSweep {
+                start_frequency: 20,
+                end_frequency: 20_000,
+            },
+            duration: 5,
+            volume: 0.5,
+        };
+
+        assert_eq!(
+            serde_json::to_string(&request).unwrap(),
+            r#"{"command":"play","signal":{"type":"log_sweep","start_frequency":20,"end_frequency":20000},"duration":5,"volume":0.5}"#
+        );
+    }
+}
@@ -0,0 +1,42 @@
+//! Measurement templates, that are shared with the GUI, see
+//! [`raumklang_core::template`].
+
+use anyhow::Context;
+use raumklang_core::template::{self, Template};
+
+use crate::SignalType;
+
+/// Signal, duration in seconds and volume of the template `name`.
+pub fn signal(name: &str) -> anyhow::Result<(SignalType, usize, f32)> {
+    let templates = load()?;
+
+    let Some(template) = template::find(&templates, name) else {
+        let names: Vec<_> = templates.iter().map(|t| t.name.as_str()).collect();
+        anyhow::bail!(
+            "no measurement template named `{name}`, available: {}",
+            names.join(", ")
+        );
+    };
+
+    let signal = SignalType::LogSweep {
+        start_frequency: template.start_frequency,
+        end_frequency: template.end_frequency,
+    };
+
+    let duration = template.duration.round().max(1.0) as usize;
+
+    Ok((signal, duration, template.volume()))
+}
+
+fn load() -> anyhow::Result<Vec<Template>> {
+    // the same directory as the one of the GUI
+    let dirs = directories::ProjectDirs::from("de", "henku", "raumklang")
+        .context("could not find the configuration directory")?;
+    let path = dirs.config_dir().join(template::FILE_NAME);
+
+    let content = std::fs::read(&path)
+        .with_context(|| format!("could not read measurement templates {}", path.display()))?;
+
+    serde_json::from_slice(&content)
+        .with_context(|| format!("could not parse measurement templates {}", path.display()))
+}
@@ -0,0 +1,411 @@
+use rustfft::num_complex::Complex32;
+
+use crate::{Error, ImpulseResponse};
+
+use std::{
+    f32::consts::{PI, TAU},
+    fmt,
+    ops::Range,
+    time::Duration,
+};
+
+/// Duration of the fades at the start and the end of a [`tone`].
+pub const TONE_FADE: Duration = Duration::from_millis(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Polarity {
+    Normal,
+    Inverted,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Alignment {
+    /// Delay in samples applied to the sub, negative values mean that the
+    /// mains need to be delayed instead.
+    pub delay: isize,
+    pub polarity: Polarity,
+    /// Mean level in dB of the summed response within the crossover region
+    /// relative to the coherent sum of both magnitudes, 0 dB means perfect
+    /// summation.
+    pub efficiency: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct SubAlignment {
+    crossover_frequency: f32,
+    bandwidth: f32,
+    max_delay: Duration,
+    points: usize,
+}
+
+impl SubAlignment {
+    pub fn new(crossover_frequency: f32) -> Self {
+        Self {
+            crossover_frequency,
+            bandwidth: 1.0,
+            max_delay: Duration::from_millis(20),
+            points: 16,
+        }
+    }
+
+    /// Width of the evaluated region around the crossover frequency in octaves.
+    pub fn bandwidth(mut self, octaves: f32) -> Self {
+        self.bandwidth = octaves;
+        self
+    }
+
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    pub fn find(&self, mains: &ImpulseResponse, sub: &ImpulseResponse) -> Result<Alignment, Error> {
+        crate::check_sample_rates(mains.sample_rate, sub.sample_rate)?;
+
+        let sample_rate = mains.sample_rate as f32;
+        let pair = Pair(
+            self.frequencies()
+                .into_iter()
+                .map(|f| {
+                    (
+                        f,
+                        spectrum_at(&mains.data, f, mains.sample_rate),
+                        spectrum_at(&sub.data, f, sub.sample_rate),
+                    )
+                })
+                .collect(),
+        );
+
+        let max_delay = (self.max_delay.as_secs_f32() * sample_rate) as isize;
+        let delays = (-max_delay..=max_delay).map(|delay| delay as f32 / sample_rate);
+
+        let (delay, polarity, efficiency) = best_candidate(&pair.candidates(delays))
+            .expect("there is at least one delay to evaluate");
+
+        Ok(Alignment {
+            delay: (delay * sample_rate).round() as isize,
+            polarity,
+            efficiency,
+        })
+    }
+
+    /// Alignment at the crossover frequency alone, from the responses of the
+    /// mains and the sub to a [`tone`], see [`tone_response`].
+    ///
+    /// A single frequency only determines the delay up to whole periods, the
+    /// smallest delay, that brings both in phase, is chosen. The polarity is
+    /// inverted, if that needs less delay, so the delay stays within a
+    /// quarter period and `max_delay` doesn't apply.
+    pub fn find_at_crossover(
+        &self,
+        mains: Complex32,
+        sub: Complex32,
+        sample_rate: u32,
+    ) -> Alignment {
+        let frequency = self.crossover_frequency;
+        let omega = 2.0 * PI * frequency / sample_rate as f32;
+
+        [Polarity::Normal, Polarity::Inverted]
+            .into_iter()
+            .map(|polarity| {
+                let phase = (polarity.sign() * sub).arg() - mains.arg();
+                let phase = (phase + PI).rem_euclid(TAU) - PI;
+                let delay = (phase / omega).round() as isize;
+
+                let pair = Pair(vec![(frequency, mains, sub)]);
+
+                Alignment {
+                    delay,
+                    polarity,
+                    efficiency: pair.efficiency(delay as f32 / sample_rate as f32, polarity),
+                }
+            })
+            .min_by_key(|alignment| alignment.delay.unsigned_abs())
+            .expect("there are two polarities to evaluate")
+    }
+
+    fn frequencies(&self) -> Vec<f32> {
+        let lower = self.crossover_frequency * 2f32.powf(-self.bandwidth / 2.0);
+        let upper = self.crossover_frequency * 2f32.powf(self.bandwidth / 2.0);
+
+        let ratio = upper / lower;
+        let points = self.points.max(2);
+
+        (0..points)
+            .map(|i| lower * ratio.powf(i as f32 / (points - 1) as f32))
+            .collect()
+    }
+}
+
+impl Alignment {
+    pub fn delay_ms(&self, sample_rate: u32) -> f32 {
+        self.delay as f32 / sample_rate as f32 * 1000.0
+    }
+}
+
+impl Polarity {
+    fn sign(self) -> f32 {
+        match self {
+            Polarity::Normal => 1.0,
+            Polarity::Inverted => -1.0,
+        }
+    }
+}
+
+/// Responses at the crossover frequency to a [`tone`], played through the
+/// mains, the sub and both at once.
+#[derive(Debug, Clone, Copy)]
+pub struct ToneResponses {
+    pub mains: Complex32,
+    pub sub: Complex32,
+    pub both: Complex32,
+}
+
+impl ToneResponses {
+    /// Magnitude of `both` relative to the coherent sum of the magnitudes of
+    /// the mains and the sub, i.e. the summation as currently set up.
+    pub fn efficiency(&self) -> f32 {
+        20.0 * f32::log10(self.both.norm() / (self.mains.norm() + self.sub.norm()))
+    }
+}
+
+impl fmt::Display for Polarity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Polarity::Normal => "normal",
+            Polarity::Inverted => "inverted",
+        };
+
+        write!(f, "{s}")
+    }
+}
+
+/// Evaluates the spectrum of `data` at a single `frequency`.
+pub fn spectrum_at(data: &[Complex32], frequency: f32, sample_rate: u32) -> Complex32 {
+    let omega = -2.0 * std::f64::consts::PI * frequency as f64 / sample_rate as f64;
+
+    data.iter()
+        .enumerate()
+        .map(|(n, s)| {
+            // NOTE: f32 is not precise enough for the phase of long signals
+            let phase = (omega * n as f64) % std::f64::consts::TAU;
+            s.re * Complex32::from_polar(1.0, phase as f32)
+        })
+        .sum()
+}
+
+/// Sine at `frequency` with an amplitude of 0.8, faded in and out within
+/// [`TONE_FADE`] to avoid clicks.
+pub fn tone(frequency: f32, duration: Duration, sample_rate: u32) -> Vec<f32> {
+    let len = (duration.as_secs_f32() * sample_rate as f32) as usize;
+    let fade = (TONE_FADE.as_secs_f32() * sample_rate as f32).max(1.0);
+    let omega = 2.0 * std::f64::consts::PI * frequency as f64 / sample_rate as f64;
+
+    (0..len)
+        .map(|n| {
+            let gain = ((n.min(len - 1 - n) as f32) / fade).min(1.0);
+            let phase = (omega * n as f64) % std::f64::consts::TAU;
+
+            0.8 * gain * (phase as f32).sin()
+        })
+        .collect()
+}
+
+/// Response at `frequency` of the system, that played the `stimulus` tone
+/// into the `recording`, both starting at the same time.
+///
+/// Only whole periods around the middle are evaluated, where the tone and the
+/// room have settled. Returns `None`, if the stimulus contains no energy at
+/// `frequency`.
+pub fn tone_response(
+    stimulus: &[f32],
+    recording: &[f32],
+    frequency: f32,
+    sample_rate: u32,
+) -> Option<Complex32> {
+    let len = stimulus.len().min(recording.len());
+    let period = sample_rate as f32 / frequency;
+    let periods = (len as f32 / 2.0 / period).floor().max(1.0);
+
+    let start = len / 4;
+    let end = (start + (periods * period).round() as usize).min(len);
+
+    let reference = tone_at(stimulus, start..end, frequency, sample_rate);
+    let response = tone_at(recording, start..end, frequency, sample_rate);
+
+    (reference.norm() > f32::EPSILON).then(|| response / reference)
+}
+
+/// Like [`spectrum_at`], but of a `range` of real `data`, the phase refers to
+/// the start of `data`.
+fn tone_at(data: &[f32], range: Range<usize>, frequency: f32, sample_rate: u32) -> Complex32 {
+    let omega = -2.0 * std::f64::consts::PI * frequency as f64 / sample_rate as f64;
+
+    data[range.clone()]
+        .iter()
+        .zip(range)
+        .map(|(s, n)| {
+            let phase = (omega * n as f64) % std::f64::consts::TAU;
+            s * Complex32::from_polar(1.0, phase as f32)
+        })
+        .sum()
+}
+
+/// Responses of two sources at the same frequencies, e.g. of the mains and
+/// the sub around the crossover, whose sum is evaluated for different delays
+/// and polarities of the second one.
+pub(crate) struct Pair(pub(crate) Vec<(f32, Complex32, Complex32)>);
+
+impl Pair {
+    /// Mean level in dB of the sum, with the second source delayed by
+    /// `delay` seconds, relative to the sum of both magnitudes. 0 dB means,
+    /// that both add up in phase, cancellations make it negative.
+    pub(crate) fn efficiency(&self, delay: f32, polarity: Polarity) -> f32 {
+        let sign = polarity.sign();
+
+        let sum: f32 = self
+            .0
+            .iter()
+            .map(|(frequency, first, second)| {
+                let second = second * Complex32::from_polar(sign, -2.0 * PI * frequency * delay);
+                let coherent = first.norm() + second.norm();
+
+                20.0 * ((first + second).norm() / coherent).max(1e-6).log10()
+            })
+            .sum();
+
+        sum / self.0.len() as f32
+    }
+
+    /// Efficiency of every delay in both polarities, as (delay, normal,
+    /// inverted).
+    pub(crate) fn candidates(&self, delays: impl Iterator<Item = f32>) -> Vec<(f32, f32, f32)> {
+        delays
+            .map(|delay| {
+                (
+                    delay,
+                    self.efficiency(delay, Polarity::Normal),
+                    self.efficiency(delay, Polarity::Inverted),
+                )
+            })
+            .collect()
+    }
+}
+
+/// The (delay, polarity, efficiency) with the highest efficiency among the
+/// [`Pair::candidates`], the first one on a tie.
+pub(crate) fn best_candidate(candidates: &[(f32, f32, f32)]) -> Option<(f32, Polarity, f32)> {
+    candidates
+        .iter()
+        .flat_map(|&(delay, normal, inverted)| {
+            [
+                (delay, Polarity::Normal, normal),
+                (delay, Polarity::Inverted, inverted),
+            ]
+        })
+        .reduce(|best, candidate| {
+            if candidate.2 > best.2 {
+                candidate
+            } else {
+                best
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 48_000;
+
+    /// Plays the tone through a speaker, that only delays it.
+    fn delayed(signal: &[f32], delay: usize, gain: f32) -> Vec<f32> {
+        std::iter::repeat_n(0.0, delay)
+            .chain(signal.iter().map(|s| s * gain))
+            .take(signal.len())
+            .collect()
+    }
+
+    fn responses(mains: (usize, f32), sub: (usize, f32), frequency: f32) -> ToneResponses {
+        let stimulus = tone(frequency, Duration::from_secs(2), SAMPLE_RATE);
+
+        let mains = delayed(&stimulus, mains.0, mains.1);
+        let sub = delayed(&stimulus, sub.0, sub.1);
+        let both: Vec<_> = mains.iter().zip(&sub).map(|(m, s)| m + s).collect();
+
+        let response = |recording: &[f32]| {
+            tone_response(&stimulus, recording, frequency, SAMPLE_RATE).unwrap()
+        };
+
+        ToneResponses {
+            mains: response(&mains),
+            sub: response(&sub),
+            both: response(&both),
+        }
+    }
+
+    #[test]
+    fn late_sub_delays_the_mains() {
+        let responses = responses((100, 1.0), (190, 0.5), 80.0);
+
+        let alignment =
+            SubAlignment::new(80.0).find_at_crossover(responses.mains, responses.sub, SAMPLE_RATE);
+
+        assert_eq!(alignment.polarity, Polarity::Normal);
+        assert_eq!(alignment.delay, -90);
+        assert!(alignment.efficiency > -0.01);
+        assert!(responses.efficiency() < -0.5);
+    }
+
+    #[test]
+    fn sub_out_of_phase_is_inverted() {
+        // half a period at 80 Hz
+        let responses = responses((0, 1.0), (0, -1.0), 80.0);
+
+        let alignment =
+            SubAlignment::new(80.0).find_at_crossover(responses.mains, responses.sub, SAMPLE_RATE);
+
+        assert_eq!(alignment.polarity, Polarity::Inverted);
+        assert_eq!(alignment.delay, 0);
+        assert!(responses.efficiency() < -40.0);
+    }
+
+    #[test]
+    fn late_inverted_sub_is_found() {
+        let impulse_response = |delay: usize, gain: f32| ImpulseResponse {
+            sample_rate: SAMPLE_RATE,
+            data: (0..4_800)
+                .map(|n| Complex32::new(if n == delay { gain } else { 0.0 }, 0.0))
+                .collect(),
+            loopback_fft: None,
+            response_fft: None,
+        };
+
+        let mains = impulse_response(0, 1.0);
+        let sub = impulse_response(48, -0.5);
+
+        let alignment = SubAlignment::new(80.0).find(&mains, &sub).unwrap();
+
+        assert_eq!(alignment.delay, -48);
+        assert_eq!(alignment.polarity, Polarity::Inverted);
+        assert!(alignment.efficiency > -0.01);
+    }
+
+    #[test]
+    fn sample_rates_have_to_match() {
+        let impulse_response = |sample_rate| ImpulseResponse {
+            sample_rate,
+            data: vec![Complex32::new(1.0, 0.0)],
+            loopback_fft: None,
+            response_fft: None,
+        };
+
+        let mains = impulse_response(48_000);
+        let sub = impulse_response(44_100);
+
+        assert!(matches!(
+            SubAlignment::new(80.0).find(&mains, &sub),
+            Err(Error::SampleRateMismatch(48_000, 44_100))
+        ));
+    }
+}
@@ -2,7 +2,21 @@ use jack::PortFlags;
 use ringbuf::{HeapConsumer, HeapProducer, HeapRb};
 use thiserror::Error;
 
-use std::sync::mpsc::{sync_channel, Receiver, SendError, SyncSender};
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc::{sync_channel, Receiver, SendError, SyncSender},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+const QUEUE_CAPACITY: usize = 64;
+pub const DEFAULT_CAPTURE_BUFFER_SIZE: usize = 1024;
+/// Time in which the output is ramped to zero, when the playback is stopped.
+pub const FADE_OUT: Duration = Duration::from_millis(5);
 
 #[derive(Error, Debug)]
 pub enum AudioBackendError {
@@ -10,6 +24,8 @@ pub enum AudioBackendError {
     Stopped,
     #[error("audio backend crashed")]
     Other,
+    #[error("port not found: {0}")]
+    PortNotFound(String),
 }
 
 impl From<jack::Error> for AudioBackendError {
@@ -34,7 +50,7 @@ where
     J: IntoIterator<IntoIter = I>,
 {
     RegisterOutPort(jack::Port<jack::AudioOut>),
-    RegisterInPort(jack::Port<jack::AudioIn>, HeapProducer<f32>),
+    RegisterInPort(Input),
     PlaySignal {
         signal: J,
         respond_to: SyncSender<bool>,
@@ -46,10 +62,11 @@ where
     I: Iterator<Item = f32>,
     J: IntoIterator<IntoIter = I>,
 {
-    respond_to: Option<SyncSender<bool>>,
-    cur_signal: Option<I>,
+    queue: SignalQueue<I>,
     out_port: Option<jack::Port<jack::AudioOut>>,
-    input: Option<(jack::Port<jack::AudioIn>, HeapProducer<f32>)>,
+    inputs: Vec<Input>,
+    dropped_frames: Arc<AtomicUsize>,
+    stop: StopHandle,
     msg_rx: Receiver<Message<I, J>>,
 }
 
@@ -58,45 +75,168 @@ where
     I: Iterator<Item = f32> + Send,
     J: IntoIterator<IntoIter = I> + Send,
 {
-    fn process(&mut self, _: &jack::Client, process_scope: &jack::ProcessScope) -> jack::Control {
-        let mut signal_ended = false;
-
-        if let (Some(out), Some(signal)) = (&mut self.out_port, &mut self.cur_signal) {
-            let out = out.as_mut_slice(process_scope);
-
-            for o in out.iter_mut() {
-                if let Some(sample) = signal.next() {
-                    *o = sample;
-                } else {
-                    *o = 0.0f32;
-                    signal_ended = true;
+    fn process(
+        &mut self,
+        client: &jack::Client,
+        process_scope: &jack::ProcessScope,
+    ) -> jack::Control {
+        // handle all pending messages first, so that queued signals start in this cycle
+        while let Ok(msg) = self.msg_rx.try_recv() {
+            match msg {
+                Message::RegisterOutPort(p) => self.out_port = Some(p),
+                Message::RegisterInPort(input) => self.inputs.push(input),
+                Message::PlaySignal { signal, respond_to } => {
+                    self.queue.push(signal.into_iter(), respond_to)
                 }
             }
+        }
+
+        if self.stop.0.swap(false, Ordering::AcqRel) {
+            let len = client.sample_rate() as f32 * FADE_OUT.as_secs_f32();
+            self.queue.fade_out(len as usize);
+        }
+
+        let signal_start = match &mut self.out_port {
+            Some(out) => self.queue.fill(out.as_mut_slice(process_scope)),
+            None => None,
         };
 
-        if let Some((port, buf)) = &mut self.input {
-            let in_a_p = port.as_slice(process_scope);
-            buf.push_slice(in_a_p);
+        for input in self.inputs.iter_mut() {
+            let samples = input.port.as_slice(process_scope);
+
+            let samples = match (input.start, signal_start) {
+                (Start::Immediately, _) => samples,
+                (Start::WithSignal, Some(offset)) => {
+                    input.start = Start::Immediately;
+                    &samples[offset..]
+                }
+                (Start::WithSignal, None) => continue,
+            };
+
+            // the reader couldn't keep up, the remaining samples are lost
+            let pushed = input.producer.push_slice(samples);
+            if pushed < samples.len() {
+                self.dropped_frames
+                    .fetch_add(samples.len() - pushed, Ordering::Relaxed);
+            }
         }
 
-        if signal_ended {
-            let _ = self.respond_to.as_ref().unwrap().try_send(true);
-            self.respond_to = None;
-            self.cur_signal = None;
+        jack::Control::Continue
+    }
+}
+
+struct Input {
+    port: jack::Port<jack::AudioIn>,
+    producer: HeapProducer<f32>,
+    start: Start,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Start {
+    Immediately,
+    /// Waits for the first sample of the next signal, that starts playing.
+    WithSignal,
+}
+
+/// Signals waiting for playback. A signal starts on the sample right after
+/// the previous one ended, so queued sequences have a deterministic timing.
+struct SignalQueue<I> {
+    signals: VecDeque<Queued<I>>,
+    /// Remaining and total samples of the fade out, all queued signals are
+    /// discarded once it is done.
+    fade: Option<(usize, usize)>,
+}
+
+struct Queued<I> {
+    signal: I,
+    respond_to: SyncSender<bool>,
+    started: bool,
+}
+
+impl<I> SignalQueue<I>
+where
+    I: Iterator<Item = f32>,
+{
+    fn new() -> Self {
+        Self {
+            signals: VecDeque::with_capacity(QUEUE_CAPACITY),
+            fade: None,
         }
+    }
 
-        if let Ok(msg) = self.msg_rx.try_recv() {
-            match msg {
-                Message::RegisterOutPort(p) => self.out_port = Some(p),
-                Message::RegisterInPort(port, prod) => self.input = Some((port, prod)),
-                Message::PlaySignal { signal, respond_to } => {
-                    self.respond_to = Some(respond_to);
-                    self.cur_signal = Some(signal.into_iter());
+    /// Ramps the output to zero within `len` samples and stops the playback.
+    fn fade_out(&mut self, len: usize) {
+        if self.fade.is_none() && !self.signals.is_empty() {
+            self.fade = Some((len.max(1), len.max(1)));
+        }
+    }
+
+    fn push(&mut self, signal: I, respond_to: SyncSender<bool>) {
+        self.signals.push_back(Queued {
+            signal,
+            respond_to,
+            started: false,
+        });
+    }
+
+    /// Writes the next samples to `out` and returns the offset in `out`, where
+    /// the first signal started during this call, if any.
+    fn fill(&mut self, out: &mut [f32]) -> Option<usize> {
+        let mut written = 0;
+        let mut first_start = None;
+
+        while written < out.len() {
+            let Some(queued) = self.signals.front_mut() else {
+                break;
+            };
+
+            match queued.signal.next() {
+                Some(sample) => {
+                    if !queued.started {
+                        queued.started = true;
+                        first_start.get_or_insert(written);
+                    }
+
+                    out[written] = sample;
+                    written += 1;
+                }
+                None => {
+                    let _ = queued.respond_to.try_send(true);
+                    self.signals.pop_front();
                 }
             }
         }
 
-        jack::Control::Continue
+        out[written..].fill(0.0);
+
+        if let Some((remaining, len)) = self.fade.as_mut() {
+            for sample in out.iter_mut() {
+                *sample *= *remaining as f32 / *len as f32;
+                *remaining = remaining.saturating_sub(1);
+            }
+
+            if *remaining == 0 {
+                self.fade = None;
+
+                // the receivers learn, that their signal was not played to the end
+                for queued in self.signals.drain(..) {
+                    let _ = queued.respond_to.try_send(false);
+                }
+            }
+        }
+
+        first_start
+    }
+}
+
+/// Stops the playback of an [`AudioEngine`] from any thread, e.g. a signal
+/// handler. The output fades out within [`FADE_OUT`].
+#[derive(Debug, Clone, Default)]
+pub struct StopHandle(Arc<AtomicBool>);
+
+impl StopHandle {
+    pub fn stop(&self) {
+        self.0.store(true, Ordering::Release);
     }
 }
 
@@ -108,6 +248,8 @@ where
 {
     client: jack::AsyncClient<(), ProcessHandler<I, J>>,
     msg_tx: SyncSender<Message<I, J>>,
+    dropped_frames: Arc<AtomicUsize>,
+    stop: StopHandle,
 }
 
 impl<I, J> AudioEngine<I, J>
@@ -116,15 +258,23 @@ where
     J: IntoIterator<IntoIter = I> + Send + Sync + 'static,
 {
     pub fn new(name: &str) -> Result<Self, AudioBackendError> {
+        Self::with_stop_handle(name, StopHandle::default())
+    }
+
+    /// Creates an engine that is stopped by `stop`, which can be shared by
+    /// several engines, e.g. to stop all of them from one signal handler.
+    pub fn with_stop_handle(name: &str, stop: StopHandle) -> Result<Self, AudioBackendError> {
         let (client, _status) = jack::Client::new(name, jack::ClientOptions::NO_START_SERVER)?;
 
-        let (msg_tx, msg_rx) = sync_channel(64);
+        let (msg_tx, msg_rx) = sync_channel(QUEUE_CAPACITY);
+        let dropped_frames = Arc::new(AtomicUsize::new(0));
 
         let process_handler = ProcessHandler {
-            respond_to: None,
+            queue: SignalQueue::new(),
             out_port: None,
-            input: None,
-            cur_signal: None,
+            inputs: Vec::new(),
+            dropped_frames: Arc::clone(&dropped_frames),
+            stop: stop.clone(),
             msg_rx,
         };
 
@@ -133,6 +283,8 @@ where
         Ok(Self {
             client: active_client,
             msg_tx,
+            dropped_frames,
+            stop,
         })
     }
 
@@ -164,14 +316,42 @@ where
         port_name: &str,
         input_port_name: &str,
     ) -> Result<HeapConsumer<f32>, AudioBackendError> {
-        const BUFF_SIZE: usize = 1024;
+        self.register_input(
+            port_name,
+            input_port_name,
+            Start::Immediately,
+            DEFAULT_CAPTURE_BUFFER_SIZE,
+        )
+    }
 
+    /// Like [`Self::register_in_port`], but recording starts with the first
+    /// sample of the next signal that is played, so that the recording is
+    /// aligned to the playback and delays in it are absolute.
+    ///
+    /// `buffer_size` is the number of frames that can be buffered, until the
+    /// consumer has to read them, see [`Self::dropped_frames`].
+    pub fn register_capture_port(
+        &self,
+        port_name: &str,
+        input_port_name: &str,
+        buffer_size: usize,
+    ) -> Result<HeapConsumer<f32>, AudioBackendError> {
+        self.register_input(port_name, input_port_name, Start::WithSignal, buffer_size)
+    }
+
+    fn register_input(
+        &self,
+        port_name: &str,
+        input_port_name: &str,
+        start: Start,
+        buffer_size: usize,
+    ) -> Result<HeapConsumer<f32>, AudioBackendError> {
         let in_port = self
             .client
             .as_client()
             .register_port(port_name, jack::AudioIn::default())?;
 
-        let rb = HeapRb::<_>::new(BUFF_SIZE);
+        let rb = HeapRb::<_>::new(buffer_size);
         let (prod, cons) = rb.split();
 
         let full_port_name = in_port.name()?;
@@ -179,15 +359,32 @@ where
             .as_client()
             .connect_ports_by_name(input_port_name, &full_port_name)?;
 
-        self.msg_tx.send(Message::RegisterInPort(in_port, prod))?;
+        self.msg_tx.send(Message::RegisterInPort(Input {
+            port: in_port,
+            producer: prod,
+            start,
+        }))?;
 
         Ok(cons)
     }
 
+    /// Number of recorded frames that were lost, because an input's buffer
+    /// was full.
+    pub fn dropped_frames(&self) -> usize {
+        self.dropped_frames.load(Ordering::Relaxed)
+    }
+
     pub fn sample_rate(&self) -> usize {
         self.client.as_client().sample_rate() as usize
     }
 
+    pub fn stop_handle(&self) -> StopHandle {
+        self.stop.clone()
+    }
+
+    /// Queues the signal for playback, it starts right after all previously
+    /// queued signals. The returned receiver is notified when it has ended,
+    /// with `false` if the playback was stopped before.
     pub fn play_signal(&self, signal: J) -> Result<Receiver<bool>, AudioBackendError> {
         let (tx, rx) = sync_channel(1);
         self.msg_tx.send(Message::PlaySignal {
@@ -198,9 +395,83 @@ where
         Ok(rx)
     }
 
+    /// Blocks until all `ports` exist, e.g. of an USB audio interface, that
+    /// is still being enumerated. Fails with the first missing port after
+    /// `timeout`.
+    pub fn wait_for_ports<T: AsRef<str>>(
+        &self,
+        ports: &[T],
+        timeout: Duration,
+    ) -> Result<(), AudioBackendError> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let missing = ports
+                .iter()
+                .map(AsRef::as_ref)
+                .find(|name| self.client.as_client().port_by_name(name).is_none());
+
+            match missing {
+                None => return Ok(()),
+                Some(name) if Instant::now() >= deadline => {
+                    return Err(AudioBackendError::PortNotFound(name.to_string()))
+                }
+                Some(_) => thread::sleep(Duration::from_millis(100)),
+            }
+        }
+    }
+
     pub fn out_ports(&self) -> Vec<String> {
         self.client
             .as_client()
             .ports(None, Some("32 bit float mono audio"), PortFlags::IS_INPUT)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn queued_signals_are_played_without_gaps() {
+        let mut queue = SignalQueue::new();
+
+        let (first_tx, first_rx) = sync_channel(1);
+        let (second_tx, second_rx) = sync_channel(1);
+        queue.push(vec![1.0; 3].into_iter(), first_tx);
+        queue.push(vec![2.0; 3].into_iter(), second_tx);
+
+        let mut out = [-1.0; 4];
+        assert_eq!(queue.fill(&mut out), Some(0));
+        assert_eq!(out, [1.0, 1.0, 1.0, 2.0]);
+        assert_eq!(first_rx.try_recv(), Ok(true));
+        assert!(second_rx.try_recv().is_err());
+
+        assert_eq!(queue.fill(&mut out), None);
+        assert_eq!(out, [2.0, 2.0, 0.0, 0.0]);
+        assert_eq!(second_rx.try_recv(), Ok(true));
+    }
+
+    #[test]
+    fn stopped_playback_fades_out() {
+        let mut queue = SignalQueue::new();
+
+        let (tx, rx) = sync_channel(1);
+        queue.push(std::iter::repeat(1.0), tx);
+
+        let mut out = [0.0; 3];
+        queue.fill(&mut out);
+        queue.fade_out(4);
+
+        queue.fill(&mut out);
+        assert_eq!(out, [1.0, 0.75, 0.5]);
+        assert!(rx.try_recv().is_err());
+
+        queue.fill(&mut out);
+        assert_eq!(out, [0.25, 0.0, 0.0]);
+        assert_eq!(rx.try_recv(), Ok(false));
+
+        queue.fill(&mut out);
+        assert_eq!(out, [0.0; 3]);
+    }
+}
@@ -0,0 +1,128 @@
+use thiserror::Error;
+
+use crate::bands;
+
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    #[error("{distances} distances given for {positions} positions")]
+    DistanceCount { distances: usize, positions: usize },
+}
+
+/// Spatial weighting of the mic positions, when averaging magnitude responses.
+///
+/// Plain averaging over-weights positions close to boundaries, as they show the
+/// strongest room modes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Weighting {
+    Equal,
+    /// Distance of every position to the main listening position in meters,
+    /// closer positions get a higher weight.
+    Distance(Vec<f32>),
+    /// Emulates a moving mic measurement (MMM), by additionally averaging the
+    /// power within fractional octave segments, see [`segments`].
+    MovingMic {
+        fraction: u8,
+    },
+}
+
+impl Weighting {
+    /// Normalized weights for `count` positions, distances have to be given
+    /// for all of them.
+    pub fn weights(&self, count: usize) -> Result<Vec<f32>, Error> {
+        let weights: Vec<f32> = match self {
+            Weighting::Equal | Weighting::MovingMic { .. } => vec![1.0; count],
+            Weighting::Distance(distances) => {
+                if distances.len() != count {
+                    return Err(Error::DistanceCount {
+                        distances: distances.len(),
+                        positions: count,
+                    });
+                }
+
+                // NOTE: avoid infinite weights for the listening position itself
+                distances.iter().map(|d| 1.0 / d.max(0.1)).collect()
+            }
+        };
+
+        let sum: f32 = weights.iter().sum();
+        Ok(weights.into_iter().map(|w| w / sum).collect())
+    }
+}
+
+/// Weighted power average of linear magnitude responses, that share the same
+/// frequency bins.
+pub fn magnitudes(
+    responses: &[&[f32]],
+    weighting: &Weighting,
+    resolution: f32,
+) -> Result<Vec<f32>, Error> {
+    let weights = weighting.weights(responses.len())?;
+    let len = responses.iter().map(|r| r.len()).min().unwrap_or_default();
+
+    let power: Vec<f32> = (0..len)
+        .map(|i| {
+            responses
+                .iter()
+                .zip(weights.iter())
+                .map(|(r, w)| w * r[i] * r[i])
+                .sum()
+        })
+        .collect();
+
+    let power = match weighting {
+        Weighting::MovingMic { fraction } => segments(&power, resolution, *fraction),
+        Weighting::Equal | Weighting::Distance(_) => power,
+    };
+
+    Ok(power.into_iter().map(f32::sqrt).collect())
+}
+
+/// Averages the power of every bin with its neighbours within a fractional
+/// octave band centered around it.
+pub fn segments(power: &[f32], resolution: f32, fraction: u8) -> Vec<f32> {
+    power
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            if i == 0 {
+                return *p;
+            }
+
+            let band = bands::Band::new(i as f32 * resolution, fraction);
+            let first = (band.lower / resolution).round() as usize;
+            let last = ((band.upper / resolution).round() as usize + 1).min(power.len());
+
+            let segment = &power[first..last];
+            segment.iter().sum::<f32>() / segment.len() as f32
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn closer_positions_are_weighted_higher() {
+        let near = [1.0; 4];
+        let far = [2.0; 4];
+
+        let equal = magnitudes(&[&near, &far], &Weighting::Equal, 1.0).unwrap();
+        let weighted =
+            magnitudes(&[&near, &far], &Weighting::Distance(vec![0.5, 2.0]), 1.0).unwrap();
+
+        assert!(weighted[1] < equal[1]);
+        assert!(weighted[1] > 1.0);
+    }
+
+    #[test]
+    fn every_position_needs_a_distance() {
+        assert_eq!(
+            Weighting::Distance(vec![1.0]).weights(2),
+            Err(Error::DistanceCount {
+                distances: 1,
+                positions: 2
+            })
+        );
+    }
+}
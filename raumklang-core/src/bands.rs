@@ -0,0 +1,87 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Band {
+    pub lower: f32,
+    pub center: f32,
+    pub upper: f32,
+}
+
+const REFERENCE_FREQUENCY: f32 = 1000.0;
+
+impl Band {
+    pub fn new(center: f32, fraction: u8) -> Self {
+        let half_width = 2f32.powf(1.0 / (2.0 * fraction as f32));
+
+        Self {
+            lower: center / half_width,
+            center,
+            upper: center * half_width,
+        }
+    }
+
+    pub fn contains(&self, frequency: f32) -> bool {
+        (self.lower..self.upper).contains(&frequency)
+    }
+}
+
+/// Base-two fractional octave bands (e.g. `fraction = 3` for third-octaves),
+/// centered around 1 kHz, whose center frequencies lie within `min` and `max`.
+pub fn fractional_octave(fraction: u8, min: f32, max: f32) -> Vec<Band> {
+    assert!(fraction > 0);
+
+    let fraction_f = fraction as f32;
+    let first = (fraction_f * (min / REFERENCE_FREQUENCY).log2()).ceil() as i32;
+    let last = (fraction_f * (max / REFERENCE_FREQUENCY).log2()).floor() as i32;
+
+    (first..=last)
+        .map(|k| REFERENCE_FREQUENCY * 2f32.powf(k as f32 / fraction_f))
+        .map(|center| Band::new(center, fraction))
+        .collect()
+}
+
+/// Sums the power of all bins of `power` (bin `i` at `i * resolution` Hz)
+/// within the `band`. Falls back to the closest bin, when the band is
+/// narrower than the spectral resolution.
+pub fn energy_sum(power: &[f32], resolution: f32, band: &Band) -> f32 {
+    let first = (band.lower / resolution).ceil() as usize;
+    let last = ((band.upper / resolution).ceil() as usize).min(power.len());
+
+    if first < last {
+        power[first..last].iter().sum()
+    } else {
+        let closest = (band.center / resolution).round() as usize;
+        power.get(closest).copied().unwrap_or_default()
+    }
+}
+
+/// Mean power of the bins of `power` within the `band`, e.g. the level of a
+/// transfer function in that band, independent of the band width.
+pub fn energy_mean(power: &[f32], resolution: f32, band: &Band) -> f32 {
+    let first = (band.lower / resolution).ceil() as usize;
+    let last = ((band.upper / resolution).ceil() as usize).min(power.len());
+
+    energy_sum(power, resolution, band) / last.saturating_sub(first).max(1) as f32
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn third_octave_bands_of_audible_range() {
+        let bands = fractional_octave(3, 17.0, 22_000.0);
+
+        assert_eq!(bands.len(), 31);
+        assert!(bands.iter().any(|b| b.center == 1000.0));
+        assert!(bands
+            .iter()
+            .all(|b| b.lower < b.center && b.center < b.upper));
+    }
+
+    #[test]
+    fn energy_sum_of_narrow_band_uses_closest_bin() {
+        let power = [0.0, 1.0, 2.0, 3.0];
+        let band = Band::new(2.1, 24);
+
+        assert_eq!(energy_sum(&power, 1.0, &band), 2.0);
+    }
+}
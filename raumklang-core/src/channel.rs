@@ -0,0 +1,119 @@
+//! Identifies the outputs of a setup by a short sweep, that is played on
+//! every output in turn, e.g. to catch swapped or inverted speakers.
+
+use std::time::Duration;
+
+use crate::{alignment::Polarity, ImpulseResponse};
+
+/// Minimum peak to noise ratio in dB of a connected speaker.
+const MIN_SNR: f32 = 20.0;
+
+/// Time around the peak, that decides the polarity.
+const POLARITY_WINDOW: Duration = Duration::from_millis(1);
+
+/// What the microphone picked up of a single output.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Check {
+    /// Peak of the impulse response in dBFS
+    pub level: f32,
+    /// Peak to noise ratio of the impulse response in dB
+    pub snr: f32,
+    /// Time of arrival, including the latency of the audio interface
+    pub delay: Duration,
+    pub polarity: Polarity,
+}
+
+impl Check {
+    /// `None` if the impulse response is silent.
+    pub fn new(impulse_response: &ImpulseResponse) -> Option<Self> {
+        // the second half holds negative delays
+        let causal: Vec<f32> = impulse_response.data[..impulse_response.data.len() / 2]
+            .iter()
+            .map(|s| s.re)
+            .collect();
+
+        let (position, peak) = causal
+            .iter()
+            .map(|s| s.abs())
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))?;
+
+        if peak == 0.0 {
+            return None;
+        }
+
+        let noise = &causal[causal.len() * 3 / 4..];
+        let noise = (noise.iter().map(|s| s.powi(2)).sum::<f32>() / noise.len() as f32).sqrt();
+
+        // the area around the peak is less affected by the ringing of a
+        // tweeter, than the sign of the peak itself
+        let sample_rate = impulse_response.sample_rate;
+        let window = (POLARITY_WINDOW.as_secs_f32() * sample_rate as f32) as usize;
+        let area: f32 = causal[position.saturating_sub(window)..]
+            .iter()
+            .take(2 * window + 1)
+            .sum();
+
+        let polarity = if area < 0.0 {
+            Polarity::Inverted
+        } else {
+            Polarity::Normal
+        };
+
+        Some(Self {
+            level: crate::dbfs(peak),
+            snr: 20.0 * (peak / noise).log10(),
+            delay: Duration::from_secs_f32(position as f32 / sample_rate as f32),
+            polarity,
+        })
+    }
+
+    /// A speaker is connected to the output and the microphone picks it up.
+    pub fn is_detected(&self) -> bool {
+        self.snr >= MIN_SNR
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::{room::Room, signals::LinearSineSweep, DeconvolutionMethod};
+
+    fn check(gain: f32) -> Option<Check> {
+        let sample_rate = 8_000;
+        let sweep: Vec<f32> =
+            LinearSineSweep::new(20, 3_900, Duration::from_secs(1), 0.5, sample_rate as usize)
+                .collect();
+
+        let recording = Room::default()
+            .record(&sweep, sample_rate)
+            .into_iter()
+            .map(|s| s * gain);
+
+        let impulse_response = ImpulseResponse::from_samples(
+            sample_rate,
+            sweep.iter().copied(),
+            recording,
+            DeconvolutionMethod::RegularizedDivision,
+        );
+
+        Check::new(&impulse_response)
+    }
+
+    #[test]
+    fn detects_polarity() {
+        let normal = check(0.5).unwrap();
+        let inverted = check(-0.5).unwrap();
+
+        assert!(normal.is_detected());
+        assert_eq!(normal.polarity, Polarity::Normal);
+        assert_eq!(inverted.polarity, Polarity::Inverted);
+        assert_eq!(normal.delay, inverted.delay);
+    }
+
+    #[test]
+    fn silence_is_not_detected() {
+        assert_eq!(check(0.0), None);
+    }
+}
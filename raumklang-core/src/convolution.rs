@@ -0,0 +1,63 @@
+use rustfft::{num_complex::Complex32, FftPlanner};
+
+/// Linear convolution of `signal` with `kernel`, computed with the FFT. The
+/// result is `signal.len() + kernel.len() - 1` samples long.
+pub fn convolve(signal: &[f32], kernel: &[f32]) -> Vec<f32> {
+    if signal.is_empty() || kernel.is_empty() {
+        return vec![];
+    }
+
+    let len = signal.len() + kernel.len() - 1;
+    let fft_len = len.next_power_of_two();
+
+    let spectrum = |data: &[f32]| {
+        let mut spectrum: Vec<_> = data
+            .iter()
+            .copied()
+            .map(Complex32::from)
+            .chain(std::iter::repeat(Complex32::default()))
+            .take(fft_len)
+            .collect();
+
+        FftPlanner::new()
+            .plan_fft_forward(fft_len)
+            .process(&mut spectrum);
+
+        spectrum
+    };
+
+    let mut result: Vec<_> = spectrum(signal)
+        .into_iter()
+        .zip(spectrum(kernel))
+        .map(|(s, k)| s * k)
+        .collect();
+
+    FftPlanner::new()
+        .plan_fft_inverse(fft_len)
+        .process(&mut result);
+
+    result
+        .into_iter()
+        .take(len)
+        .map(|s| s.re / fft_len as f32)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn convolve_matches_direct_form() {
+        let signal = [1.0, 2.0, 3.0];
+        let kernel = [0.0, 1.0, 0.5];
+
+        let result = convolve(&signal, &kernel);
+
+        let expected = [0.0, 1.0, 2.5, 4.0, 1.5];
+        assert_eq!(result.len(), expected.len());
+        for (r, e) in result.iter().zip(expected) {
+            assert!((r - e).abs() < 1e-5, "{result:?}");
+        }
+    }
+}
@@ -0,0 +1,276 @@
+use rustfft::num_complex::Complex32;
+
+use crate::{
+    alignment::{best_candidate, Pair, Polarity},
+    FrequencyResponse,
+};
+
+use std::{f32::consts::PI, fmt};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FilterAlignment {
+    Butterworth,
+    #[default]
+    LinkwitzRiley,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    LowPass,
+    HighPass,
+}
+
+/// An ideal analog crossover filter, used to preview how measured drivers
+/// sum up before building the actual crossover.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Filter {
+    pub kind: Kind,
+    pub alignment: FilterAlignment,
+    /// Order of the filter, from 2 to 8. Linkwitz-Riley filters only exist
+    /// in even orders, odd orders are rounded up.
+    pub order: u8,
+    /// Crossover frequency in Hz
+    pub frequency: f32,
+    pub inverted: bool,
+}
+
+impl FilterAlignment {
+    pub const ALL: [FilterAlignment; 2] =
+        [FilterAlignment::Butterworth, FilterAlignment::LinkwitzRiley];
+
+    pub fn orders(&self) -> &'static [u8] {
+        match self {
+            FilterAlignment::Butterworth => &[2, 3, 4, 5, 6, 7, 8],
+            FilterAlignment::LinkwitzRiley => &[2, 4, 6, 8],
+        }
+    }
+}
+
+impl Filter {
+    pub const MIN_ORDER: u8 = 2;
+    pub const MAX_ORDER: u8 = 8;
+
+    pub fn new(kind: Kind, alignment: FilterAlignment, order: u8, frequency: f32) -> Self {
+        Self {
+            kind,
+            alignment,
+            order: order.clamp(Self::MIN_ORDER, Self::MAX_ORDER),
+            frequency,
+            inverted: false,
+        }
+    }
+
+    /// Complex response of the filter at `frequency` in Hz.
+    pub fn response(&self, frequency: f32) -> Complex32 {
+        // keep clear of the singularity of the high pass at 0 Hz
+        let s = Complex32::new(0.0, (frequency / self.frequency).max(1e-6));
+        // a high pass is a low pass with s mirrored at the crossover frequency
+        let s = match self.kind {
+            Kind::LowPass => s,
+            Kind::HighPass => s.inv(),
+        };
+
+        let order = self.order.clamp(Self::MIN_ORDER, Self::MAX_ORDER);
+        let response = match self.alignment {
+            FilterAlignment::Butterworth => butterworth(order, s),
+            FilterAlignment::LinkwitzRiley => butterworth(order.div_ceil(2), s).powi(2),
+        };
+
+        if self.inverted {
+            -response
+        } else {
+            response
+        }
+    }
+
+    /// Multiplies every bin of `frequency_response` with the response of
+    /// the filter.
+    pub fn apply(&self, frequency_response: &mut FrequencyResponse) {
+        // the last bin of the (even length) FFT was truncated
+        let resolution = frequency_response.resolution();
+
+        for (i, bin) in frequency_response.data.iter_mut().enumerate() {
+            *bin *= self.response(i as f32 * resolution);
+        }
+    }
+}
+
+/// Delay and polarity of the high way, for which it sums best with the low
+/// way around the crossover.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Integration {
+    /// Delay of the high way in seconds, a negative delay is one of the low
+    /// way
+    pub delay: f32,
+    pub inverted: bool,
+    /// Summation loss of the best candidate, see [`summation_loss`]
+    pub loss: f32,
+    /// Summation loss of every candidate delay, as (delay, normal polarity,
+    /// inverted polarity)
+    pub candidates: Vec<(f32, f32, f32)>,
+}
+
+/// Evaluated points per octave of the crossover region.
+const POINTS_PER_OCTAVE: f32 = 48.0;
+
+/// Tries all delays of `high` within `max_delay` seconds in both polarities
+/// and picks the one, whose sum with `low` has the lowest loss between `min`
+/// and `max` Hz. `None` if the responses can't be combined.
+pub fn integrate(
+    low: &FrequencyResponse,
+    high: &FrequencyResponse,
+    min: f32,
+    max: f32,
+    max_delay: f32,
+) -> Option<Integration> {
+    let region = region(low, high, min, max)?;
+
+    // a phase shift of 10° at the upper end of the region
+    let step = 1.0 / (36.0 * max);
+    let steps = (max_delay / step).ceil() as i32;
+
+    let candidates = region.candidates((-steps..=steps).map(|k| k as f32 * step));
+    let (delay, polarity, loss) = best_candidate(&candidates)?;
+
+    Some(Integration {
+        delay,
+        inverted: polarity == Polarity::Inverted,
+        loss,
+        candidates,
+    })
+}
+
+/// Mean level in dB of the sum of `low` and `high` between `min` and `max`
+/// Hz, relative to the sum of their magnitudes. 0 dB means, that both ways
+/// add up in phase, cancellations make it negative.
+pub fn summation_loss(
+    low: &FrequencyResponse,
+    high: &FrequencyResponse,
+    min: f32,
+    max: f32,
+) -> Option<f32> {
+    region(low, high, min, max).map(|region| region.efficiency(0.0, Polarity::Normal))
+}
+
+/// Delays `frequency_response` by `delay` seconds and inverts its polarity.
+pub fn shift(frequency_response: &mut FrequencyResponse, delay: f32, inverted: bool) {
+    let resolution = frequency_response.resolution();
+    let polarity = if inverted { -1.0 } else { 1.0 };
+
+    for (i, bin) in frequency_response.data.iter_mut().enumerate() {
+        *bin *= Complex32::from_polar(polarity, -2.0 * PI * i as f32 * resolution * delay);
+    }
+}
+
+/// Bins of both ways at log spaced frequencies within the crossover region.
+fn region(low: &FrequencyResponse, high: &FrequencyResponse, min: f32, max: f32) -> Option<Pair> {
+    if low.sample_rate != high.sample_rate || low.data.len() != high.data.len() {
+        return None;
+    }
+
+    let resolution = low.resolution();
+
+    let points: Vec<_> = (0..)
+        .map(|i| min * 2f32.powf(i as f32 / POINTS_PER_OCTAVE))
+        .take_while(|frequency| *frequency <= max)
+        .map_while(|frequency| {
+            let i = (frequency / resolution).round() as usize;
+            Some((i as f32 * resolution, *low.data.get(i)?, *high.data.get(i)?))
+        })
+        .collect();
+
+    (!points.is_empty()).then_some(Pair(points))
+}
+
+/// Low pass Butterworth prototype with a cutoff at |s| = 1, as cascade of
+/// second order sections and a first order section for odd orders.
+fn butterworth(order: u8, s: Complex32) -> Complex32 {
+    let n = order as f32;
+
+    let sections = (1..=order / 2).fold(Complex32::new(1.0, 0.0), |response, k| {
+        let angle = (2 * k - 1) as f32 * PI / (2.0 * n);
+        let q = 1.0 / (2.0 * angle.sin());
+
+        response / (s * s + s / q + 1.0)
+    });
+
+    if order % 2 == 1 {
+        sections / (s + 1.0)
+    } else {
+        sections
+    }
+}
+
+impl fmt::Display for FilterAlignment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FilterAlignment::Butterworth => write!(f, "Butterworth"),
+            FilterAlignment::LinkwitzRiley => write!(f, "Linkwitz-Riley"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn level(response: Complex32) -> f32 {
+        20.0 * response.norm().log10()
+    }
+
+    #[test]
+    fn butterworth_is_3db_down_at_crossover() {
+        for order in FilterAlignment::Butterworth.orders() {
+            let filter = Filter::new(Kind::LowPass, FilterAlignment::Butterworth, *order, 1_000.0);
+
+            assert!((level(filter.response(1_000.0)) + 3.01).abs() < 0.01);
+            assert!(level(filter.response(100.0)).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn linkwitz_riley_sums_flat() {
+        let low_pass = Filter::new(Kind::LowPass, FilterAlignment::LinkwitzRiley, 4, 2_000.0);
+        let high_pass = Filter::new(Kind::HighPass, FilterAlignment::LinkwitzRiley, 4, 2_000.0);
+
+        for frequency in [20.0, 500.0, 2_000.0, 5_000.0, 20_000.0] {
+            let sum = low_pass.response(frequency) + high_pass.response(frequency);
+
+            assert!(level(sum).abs() < 0.01, "{frequency} Hz");
+        }
+
+        // at the crossover, each way is 6 dB down
+        assert!((level(low_pass.response(2_000.0)) + 6.02).abs() < 0.01);
+    }
+
+    #[test]
+    fn integration_compensates_delay_and_polarity() {
+        let sample_rate = 48_000;
+        let resolution = 5.0;
+        let len = (sample_rate as f32 / resolution / 2.0) as usize - 1;
+
+        let way = |kind| {
+            let filter = Filter::new(kind, FilterAlignment::LinkwitzRiley, 4, 100.0);
+
+            FrequencyResponse {
+                sample_rate,
+                data: (0..len)
+                    .map(|i| filter.response(i as f32 * resolution))
+                    .collect(),
+            }
+        };
+
+        let low = way(Kind::LowPass);
+        let mut high = way(Kind::HighPass);
+        // the high way is 2 ms late and wired the other way round
+        shift(&mut high, 0.002, true);
+
+        assert!(summation_loss(&low, &high, 50.0, 200.0).unwrap() < -1.0);
+
+        let integration = integrate(&low, &high, 50.0, 200.0, 0.01).unwrap();
+
+        assert!((integration.delay + 0.002).abs() < 1.0 / 7_200.0);
+        assert!(integration.inverted);
+        assert!(integration.loss > -0.1);
+    }
+}
@@ -0,0 +1,171 @@
+//! Reverberation times from the energy decay curve of an impulse response,
+//! following ISO 3382.
+
+use std::time::Duration;
+
+use thiserror::Error;
+
+/// Reverberation times in seconds, extrapolated to a decay of 60 dB.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DecayTimes {
+    /// Early decay time, from 0 to -10 dB
+    pub edt: Option<f32>,
+    /// From -5 to -25 dB
+    pub t20: Option<f32>,
+    /// From -5 to -35 dB
+    pub t30: Option<f32>,
+}
+
+impl DecayTimes {
+    pub fn new(impulse_response: &[f32], sample_rate: u32) -> Self {
+        let edc = energy_decay_curve(impulse_response);
+
+        Self {
+            edt: reverberation_time(&edc, sample_rate, 0.0, -10.0),
+            t20: reverberation_time(&edc, sample_rate, -5.0, -25.0),
+            t30: reverberation_time(&edc, sample_rate, -5.0, -35.0),
+        }
+    }
+}
+
+/// Schroeder's backward integration of the squared impulse response in dB,
+/// relative to its total energy.
+pub fn energy_decay_curve(impulse_response: &[f32]) -> Vec<f32> {
+    let mut energy = 0.0;
+    let mut curve: Vec<f64> = impulse_response
+        .iter()
+        .rev()
+        .map(|&s| {
+            energy += f64::from(s).powi(2);
+            energy
+        })
+        .collect();
+    curve.reverse();
+
+    let total = curve.first().copied().unwrap_or_default();
+
+    curve
+        .into_iter()
+        .map(|e| (10.0 * (e / total).log10()) as f32)
+        .collect()
+}
+
+/// Fits a line into the energy decay curve between `start` and `end` (in
+/// dB) and extrapolates it to a decay of 60 dB. `None` if the curve doesn't
+/// decay far enough.
+pub fn reverberation_time(edc: &[f32], sample_rate: u32, start: f32, end: f32) -> Option<f32> {
+    let first = edc.iter().position(|&level| level <= start)?;
+    let last = edc.iter().position(|&level| level <= end)?;
+
+    if last <= first + 1 {
+        return None;
+    }
+
+    // least squares fit in dB per sample
+    let n = (last - first + 1) as f64;
+    let (sum_x, sum_y, sum_xx, sum_xy) = edc[first..=last].iter().enumerate().fold(
+        (0.0, 0.0, 0.0, 0.0),
+        |(sum_x, sum_y, sum_xx, sum_xy), (x, &y)| {
+            let (x, y) = (x as f64, f64::from(y));
+            (sum_x + x, sum_y + y, sum_xx + x * x, sum_xy + x * y)
+        },
+    );
+    let slope = (n * sum_xy - sum_x * sum_y) / (n * sum_xx - sum_x * sum_x);
+
+    if slope >= 0.0 {
+        return None;
+    }
+
+    Some((-60.0 / slope / f64::from(sample_rate)) as f32)
+}
+
+/// Reverberation time in seconds, that is to be expected in a room of
+/// `volume` m³, following the recommendation of DIN 18041 for music.
+pub fn expected_reverberation_time(volume: f32) -> f32 {
+    0.45 * volume.max(1.0).log10() + 0.07
+}
+
+/// Reasons, why a sweep measurement doesn't capture the whole decay.
+#[derive(Error, Debug, Clone, Copy, PartialEq)]
+pub enum SweepIssue {
+    #[error(
+        "tail of {tail:.2} s is shorter than the expected reverb time of {reverberation_time:.2} s"
+    )]
+    ShortTail { tail: f32, reverberation_time: f32 },
+    #[error(
+        "sweep of {sweep:.1} s is shorter than the expected reverb time of {reverberation_time:.2} s"
+    )]
+    ShortSweep { sweep: f32, reverberation_time: f32 },
+}
+
+/// Checks, that the silence recorded after the sweep covers a decay of 60 dB
+/// and that the sweep itself is longer than it, otherwise the decay of the
+/// low frequencies overlaps the end of the sweep.
+pub fn check_sweep(sweep: Duration, tail: Duration, reverberation_time: f32) -> Vec<SweepIssue> {
+    let sweep = sweep.as_secs_f32();
+    let tail = tail.as_secs_f32();
+
+    let mut issues = vec![];
+
+    if tail < reverberation_time {
+        issues.push(SweepIssue::ShortTail {
+            tail,
+            reverberation_time,
+        });
+    }
+
+    if sweep < reverberation_time {
+        issues.push(SweepIssue::ShortSweep {
+            sweep,
+            reverberation_time,
+        });
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::testing;
+
+    #[test]
+    fn decay_times_match_golden_file() {
+        let sample_rate = 8_000;
+        let impulse_response = testing::exponential_decay(0.4, sample_rate, 8_000);
+
+        let times = DecayTimes::new(&impulse_response, sample_rate);
+
+        testing::assert_golden_decay_times("exponential_decay.txt", &times);
+    }
+
+    #[test]
+    fn short_tail_is_reported() {
+        // about 0.6 s in a living room
+        let reverberation_time = expected_reverberation_time(60.0);
+        assert!((0.5..0.9).contains(&reverberation_time));
+
+        let issues = check_sweep(
+            Duration::from_secs(5),
+            Duration::from_millis(450),
+            reverberation_time,
+        );
+        assert!(matches!(issues[..], [SweepIssue::ShortTail { .. }]));
+
+        let issues = check_sweep(
+            Duration::from_secs(5),
+            Duration::from_secs(1),
+            reverberation_time,
+        );
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn short_decay_has_no_t30() {
+        let edc = [0.0, -10.0, -20.0, -30.0];
+
+        assert_eq!(reverberation_time(&edc, 1, -5.0, -35.0), None);
+        assert!(reverberation_time(&edc, 1, -5.0, -25.0).is_some());
+    }
+}
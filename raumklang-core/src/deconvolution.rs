@@ -0,0 +1,159 @@
+use rustfft::num_complex::Complex32;
+
+use std::fmt;
+
+/// Regularization of [`DeconvolutionMethod::RegularizedDivision`] relative to
+/// the peak power of the loopback spectrum (-60 dB).
+const REGULARIZATION: f32 = 1e-6;
+
+/// How the impulse response is recovered from the recording and the loopback.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DeconvolutionMethod {
+    /// Divides the response spectrum by the loopback spectrum.
+    #[default]
+    SpectralDivision,
+    /// Like [`Self::SpectralDivision`], but limits the gain at frequencies
+    /// the loopback has almost no energy, e.g. outside of the sweep range.
+    RegularizedDivision,
+    /// Convolves the response with the time reversed loopback, which is
+    /// amplitude compensated with +6 dB/octave, assuming an exponential sweep.
+    ///
+    /// The inverse filter is normalized after Farina, the loopback convolved
+    /// with it results in an impulse with a peak of one.
+    InverseSweep,
+    /// Cross-correlates the response with the loopback, suitable for signals
+    /// with a white spectrum like MLS or noise.
+    CrossCorrelation,
+}
+
+impl DeconvolutionMethod {
+    pub const ALL: [DeconvolutionMethod; 4] = [
+        DeconvolutionMethod::SpectralDivision,
+        DeconvolutionMethod::RegularizedDivision,
+        DeconvolutionMethod::InverseSweep,
+        DeconvolutionMethod::CrossCorrelation,
+    ];
+
+    /// Computes the spectrum of the impulse response from the spectra of the
+    /// response and the loopback.
+    pub(crate) fn apply(&self, response: &[Complex32], loopback: &[Complex32]) -> Vec<Complex32> {
+        let power = |l: &Complex32| l.norm_sqr();
+
+        match self {
+            DeconvolutionMethod::SpectralDivision => {
+                response.iter().zip(loopback).map(|(r, l)| r / l).collect()
+            }
+            DeconvolutionMethod::RegularizedDivision => {
+                let peak = loopback.iter().map(power).fold(0.0, f32::max);
+                let epsilon = peak * REGULARIZATION;
+
+                response
+                    .iter()
+                    .zip(loopback)
+                    .map(|(r, l)| r * l.conj() / (power(l) + epsilon))
+                    .collect()
+            }
+            DeconvolutionMethod::InverseSweep => {
+                let len = loopback.len();
+
+                // the power of an exponential sweep falls with 3 dB/octave,
+                // weighting it with the frequency results in a flat spectrum
+                let weight = |k: usize| k.min(len - k) as f32;
+
+                // the peak of the impulse is the mean of its spectrum
+                let gain = loopback
+                    .iter()
+                    .enumerate()
+                    .map(|(k, l)| power(l) * weight(k))
+                    .sum::<f32>()
+                    / len as f32;
+
+                response
+                    .iter()
+                    .zip(loopback)
+                    .enumerate()
+                    .map(|(k, (r, l))| r * l.conj() * weight(k) / gain)
+                    .collect()
+            }
+            DeconvolutionMethod::CrossCorrelation => {
+                let mean_power = loopback.iter().map(power).sum::<f32>() / loopback.len() as f32;
+
+                response
+                    .iter()
+                    .zip(loopback)
+                    .map(|(r, l)| r * l.conj() / mean_power)
+                    .collect()
+            }
+        }
+    }
+}
+
+impl fmt::Display for DeconvolutionMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            DeconvolutionMethod::SpectralDivision => "Spectral division",
+            DeconvolutionMethod::RegularizedDivision => "Regularized division",
+            DeconvolutionMethod::InverseSweep => "Inverse sweep",
+            DeconvolutionMethod::CrossCorrelation => "Cross-correlation",
+        };
+
+        write!(f, "{s}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::{signals::ExponentialSweep, ImpulseResponse};
+
+    #[test]
+    fn methods_agree_for_flat_loopback() {
+        let loopback = vec![Complex32::new(2.0, 0.0); 8];
+        let response: Vec<_> = (0..8).map(|i| Complex32::new(i as f32, 1.0)).collect();
+
+        let expected = DeconvolutionMethod::SpectralDivision.apply(&response, &loopback);
+
+        for method in [
+            DeconvolutionMethod::RegularizedDivision,
+            DeconvolutionMethod::CrossCorrelation,
+        ] {
+            let result = method.apply(&response, &loopback);
+
+            for (a, b) in result.iter().zip(&expected) {
+                assert!((a - b).norm() < 1e-5, "{method}: {a} != {b}");
+            }
+        }
+    }
+
+    #[test]
+    fn methods_agree_on_the_peak_of_a_delayed_sweep() {
+        let loopback: Vec<f32> = ExponentialSweep::new(20.0, 3_999.0, 0.5, 8_000, 8_000).collect();
+
+        let delay = 100;
+        let response: Vec<f32> = std::iter::repeat_n(0.0, delay)
+            .chain(loopback.iter().map(|s| s * 0.5))
+            .collect();
+
+        for method in DeconvolutionMethod::ALL {
+            let ir = ImpulseResponse::from_samples(
+                8_000,
+                loopback.iter().copied(),
+                response.iter().copied(),
+                method,
+            );
+
+            let (peak, value) = ir
+                .data
+                .iter()
+                .map(|s| s.re)
+                .enumerate()
+                .max_by(|(_, a), (_, b)| a.abs().total_cmp(&b.abs()))
+                .unwrap();
+
+            assert_eq!(peak, delay, "{method}");
+            assert!((value - 0.5).abs() < 0.01, "{method}: {value}");
+        }
+    }
+}
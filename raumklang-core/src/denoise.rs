@@ -0,0 +1,249 @@
+//! Removes the noise from an impulse response by spectral subtraction. The
+//! noise is taken from the silence, that is recorded in front of the
+//! measurement signal, which extends the usable range of the decay in noisy
+//! rooms.
+
+use rustfft::{num_complex::Complex32, Fft, FftPlanner};
+
+use std::{f32::consts::PI, sync::Arc};
+
+use crate::{DeconvolutionMethod, ImpulseResponse, Loopback, Measurement};
+
+/// Multiple of the noise power, that is subtracted, to suppress its
+/// fluctuations as well.
+const OVER_SUBTRACTION: f32 = 3.0;
+
+/// Minimum power gain of a bin (-30 dB), it limits musical noise.
+const FLOOR: f32 = 1e-3;
+
+/// Level below the peak of the loopback (-60 dB), that counts as silence.
+const SILENCE: f32 = 1e-3;
+
+/// Power spectrum of the noise in an impulse response.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoiseFloor {
+    /// Per bin of a frame of [`denoise`]
+    power: Vec<f32>,
+}
+
+impl NoiseFloor {
+    /// Estimates the noise in the impulse response of `measurement`, that
+    /// is computed with `method`. `None`, if the silence in front of the
+    /// loopback signal is shorter than a frame.
+    pub fn estimate(
+        loopback: &Loopback,
+        measurement: &Measurement,
+        method: DeconvolutionMethod,
+    ) -> Option<Self> {
+        let sample_rate = measurement.sample_rate();
+        let frame = Frame::new(sample_rate);
+
+        let noise: Vec<f32> = measurement
+            .iter()
+            .copied()
+            .take(lead_in(loopback))
+            .collect();
+
+        if noise.len() < frame.len() {
+            return None;
+        }
+
+        // a noise recording as long as the measurement, deconvolved like it,
+        // the noise is mirrored at the seams to avoid clicks
+        let recording = noise
+            .iter()
+            .chain(noise.iter().rev())
+            .copied()
+            .cycle()
+            .take(measurement.duration());
+
+        let impulse_response =
+            ImpulseResponse::from_samples(sample_rate, loopback.iter().copied(), recording, method);
+
+        let causal = causal(&impulse_response);
+
+        let mut power = vec![0.0; frame.len()];
+        let mut frames = 0;
+        for start in (0..=causal.len().saturating_sub(frame.len())).step_by(frame.hop()) {
+            let spectrum = frame.spectrum(&causal[start..]);
+
+            for (power, s) in power.iter_mut().zip(spectrum) {
+                *power += s.norm_sqr();
+            }
+            frames += 1;
+        }
+
+        power.iter_mut().for_each(|p| *p /= frames as f32);
+
+        Some(Self { power })
+    }
+}
+
+/// Subtracts the `noise_floor` from the causal part of the impulse
+/// response, the acausal part is kept.
+pub fn denoise(impulse_response: &ImpulseResponse, noise_floor: &NoiseFloor) -> ImpulseResponse {
+    let frame = Frame::new(impulse_response.sample_rate);
+    assert_eq!(frame.len(), noise_floor.power.len());
+
+    let hop = frame.hop();
+    let causal = causal(impulse_response);
+
+    // padded by half a frame, so that the overlapping windows sum up to
+    // one over the whole impulse response
+    let mut padded = vec![0.0; hop];
+    padded.extend_from_slice(&causal);
+    padded.resize(causal.len().div_ceil(hop) * hop + 2 * hop, 0.0);
+
+    let inverse = FftPlanner::new().plan_fft_inverse(frame.len());
+    let scale = 1.0 / frame.len() as f32;
+
+    let mut result = vec![0.0; padded.len()];
+    for start in (0..=padded.len() - frame.len()).step_by(hop) {
+        let mut spectrum = frame.spectrum(&padded[start..]);
+
+        for (s, noise) in spectrum.iter_mut().zip(&noise_floor.power) {
+            let power = s.norm_sqr();
+            if power <= 0.0 {
+                continue;
+            }
+
+            let gain = (1.0 - OVER_SUBTRACTION * noise / power).max(FLOOR);
+            *s *= gain.sqrt();
+        }
+
+        inverse.process(&mut spectrum);
+
+        for (r, s) in result[start..].iter_mut().zip(spectrum) {
+            *r += s.re * scale;
+        }
+    }
+
+    let mut denoised = impulse_response.clone();
+    for (s, r) in denoised
+        .data
+        .iter_mut()
+        .zip(&result[hop..hop + causal.len()])
+    {
+        *s = Complex32::from(r);
+    }
+
+    denoised
+}
+
+/// Length of the silence in front of the loopback signal in samples.
+pub fn lead_in(loopback: &Loopback) -> usize {
+    let peak = loopback.iter().map(|s| s.abs()).fold(0.0, f32::max);
+    let threshold = peak * SILENCE;
+
+    loopback
+        .iter()
+        .position(|s| s.abs() > threshold)
+        .unwrap_or_default()
+}
+
+fn causal(impulse_response: &ImpulseResponse) -> Vec<f32> {
+    let data = &impulse_response.data;
+    data[..data.len() / 2].iter().map(|s| s.re).collect()
+}
+
+/// A periodic Hann window with 50 % overlap, the windows sum up to one.
+struct Frame {
+    window: Vec<f32>,
+    fft: Arc<dyn Fft<f32>>,
+}
+
+impl Frame {
+    fn new(sample_rate: u32) -> Self {
+        // about 20 ms
+        let len = (sample_rate as usize / 50).next_power_of_two();
+
+        let window = (0..len)
+            .map(|n| 0.5 - 0.5 * (2.0 * PI * n as f32 / len as f32).cos())
+            .collect();
+
+        Self {
+            window,
+            fft: FftPlanner::new().plan_fft_forward(len),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.window.len()
+    }
+
+    fn hop(&self) -> usize {
+        self.len() / 2
+    }
+
+    /// Spectrum of the windowed frame at the start of `data`, which is
+    /// padded with zeros, if it is too short.
+    fn spectrum(&self, data: &[f32]) -> Vec<Complex32> {
+        let mut buf: Vec<Complex32> = self
+            .window
+            .iter()
+            .enumerate()
+            .map(|(n, w)| Complex32::from(data.get(n).copied().unwrap_or_default() * w))
+            .collect();
+
+        self.fft.process(&mut buf);
+
+        buf
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::{room::Room, signals::LinearSineSweep, testing};
+
+    use std::time::Duration;
+
+    fn energy_db(data: &[f32]) -> f32 {
+        10.0 * data.iter().map(|s| s * s).sum::<f32>().log10()
+    }
+
+    #[test]
+    fn removes_noise_from_the_tail() {
+        let sample_rate = 8_000;
+        let silence = sample_rate as usize / 2;
+
+        let sweep =
+            LinearSineSweep::new(20, 3_900, Duration::from_secs(1), 0.5, sample_rate as usize);
+        let played: Vec<f32> = vec![0.0; silence]
+            .into_iter()
+            .chain(sweep)
+            .chain(vec![0.0; silence])
+            .collect();
+
+        let room = Room::default().record(&played, sample_rate);
+        let recording = testing::record(&room, &[1.0], 0.001, 0);
+
+        let loopback = Loopback::new(Measurement::new(sample_rate, played));
+        let measurement = Measurement::new(sample_rate, recording);
+        let method = DeconvolutionMethod::RegularizedDivision;
+
+        // the sweep starts at zero
+        assert!((silence..silence + 2).contains(&lead_in(&loopback)));
+
+        let impulse_response =
+            ImpulseResponse::from_signals_with(&loopback, &measurement, method).unwrap();
+        let noise_floor = NoiseFloor::estimate(&loopback, &measurement, method).unwrap();
+        let denoised = denoise(&impulse_response, &noise_floor);
+
+        let before = causal(&impulse_response);
+        let after = causal(&denoised);
+
+        // the direct sound and the early reflections are kept
+        let early = sample_rate as usize / 20;
+        let difference = energy_db(&before[..early]) - energy_db(&after[..early]);
+        assert!(
+            difference.abs() < 0.5,
+            "early energy changed by {difference} dB"
+        );
+
+        let tail = before.len() * 3 / 4;
+        let reduction = energy_db(&before[tail..]) - energy_db(&after[tail..]);
+        assert!(reduction > 10.0, "noise only reduced by {reduction} dB");
+    }
+}
@@ -0,0 +1,162 @@
+//! Files of DRC (Digital Room Correction) and its `glsweep` and `lsconv`
+//! tools, to migrate existing measurements.
+
+use std::{collections::BTreeMap, path::Path};
+
+use crate::{Measurement, WavLoadError};
+
+/// Sample format of headerless files, named like in the DRC configuration
+/// (e.g. `BCInFileType`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RawFormat {
+    /// 32 bit float, as written by `glsweep` and `lsconv`
+    #[default]
+    Float,
+    /// 64 bit float
+    Double,
+    /// 16 bit integer
+    Int,
+}
+
+/// Key value pairs of a DRC configuration file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Config(BTreeMap<String, String>);
+
+impl RawFormat {
+    pub fn from_drc(value: &str) -> Option<Self> {
+        match value.trim() {
+            "F" => Some(RawFormat::Float),
+            "D" => Some(RawFormat::Double),
+            "I" => Some(RawFormat::Int),
+            _ => None,
+        }
+    }
+
+    fn bytes(&self) -> usize {
+        match self {
+            RawFormat::Float => 4,
+            RawFormat::Double => 8,
+            RawFormat::Int => 2,
+        }
+    }
+}
+
+/// Reads a headerless little endian file, e.g. the sweep of `glsweep` or a
+/// recording, that is deconvolved by `lsconv`.
+pub fn read_raw(
+    path: impl AsRef<Path>,
+    sample_rate: u32,
+    format: RawFormat,
+) -> Result<Measurement, WavLoadError> {
+    let bytes = std::fs::read(path)?;
+
+    let len = format.bytes();
+    if bytes.len() % len != 0 {
+        return Err(WavLoadError::Other);
+    }
+
+    let data = bytes
+        .chunks_exact(len)
+        .map(|sample| match format {
+            RawFormat::Float => f32::from_le_bytes(sample.try_into().unwrap()),
+            RawFormat::Double => f64::from_le_bytes(sample.try_into().unwrap()) as f32,
+            RawFormat::Int => {
+                f32::from(i16::from_le_bytes(sample.try_into().unwrap())) / -f32::from(i16::MIN)
+            }
+        })
+        .collect();
+
+    Ok(Measurement::new(sample_rate, data))
+}
+
+impl Config {
+    /// Parses `Key = Value` lines, comments start with `#`.
+    pub fn parse(content: &str) -> Self {
+        let entries = content
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or_default())
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+            .filter(|(key, value)| !key.is_empty() && !value.is_empty())
+            .collect();
+
+        Self(entries)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(String::as_str)
+    }
+
+    /// Points file of the target curve, used by the psychoacoustic target
+    /// stage.
+    pub fn target_curve(&self) -> Option<&str> {
+        self.get("PSPointsFile")
+    }
+
+    /// Points file of the microphone compensation.
+    pub fn microphone_compensation(&self) -> Option<&str> {
+        self.get("MCPointsFile")
+    }
+
+    /// Impulse response computed by `lsconv` and its sample format.
+    pub fn impulse_response(&self) -> Option<(&str, RawFormat)> {
+        let format = self
+            .get("BCInFileType")
+            .map_or(Some(RawFormat::Float), RawFormat::from_drc)?;
+
+        Some((self.get("BCInFile")?, format))
+    }
+}
+
+/// Parses a DRC points file into (frequency in Hz, level in dB) pairs. The
+/// points at 0 Hz and the Nyquist frequency, that DRC requires, are kept.
+pub fn parse_points(content: &str) -> Vec<(f32, f32)> {
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default())
+        .filter_map(|line| {
+            let mut values = line.split_whitespace().map(str::parse::<f32>);
+
+            let frequency = values.next()?.ok()?;
+            let level = values.next()?.ok()?;
+
+            Some((frequency, level))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_config() {
+        let config = Config::parse(
+            "# Base configuration\n\
+             BCInFile = rs.pcm\n\
+             BCInFileType = D # double\n\
+             PSPointsFile = ../target/44.1 kHz/pa-44.1.txt\n\
+             MCPointsFile =\n",
+        );
+
+        assert_eq!(
+            config.impulse_response(),
+            Some(("rs.pcm", RawFormat::Double))
+        );
+        assert_eq!(
+            config.target_curve(),
+            Some("../target/44.1 kHz/pa-44.1.txt")
+        );
+        assert_eq!(config.microphone_compensation(), None);
+    }
+
+    #[test]
+    fn parse_points_file() {
+        let points = parse_points("0 -20.0\n10 -10.0\n  20   0.0\n\n22050 -10.0\n");
+
+        assert_eq!(
+            points,
+            [(0.0, -20.0), (10.0, -10.0), (20.0, 0.0), (22_050.0, -10.0)]
+        );
+    }
+}
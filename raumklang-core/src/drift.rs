@@ -0,0 +1,140 @@
+use rustfft::{num_complex::Complex32, FftPlanner};
+
+use crate::{Loopback, Measurement};
+
+/// Length of the segments, that are compared at the start and the end of the
+/// signals.
+const SEGMENT_DURATION: f32 = 0.5;
+
+/// Estimates the clock drift between the playback and the capture device in
+/// ppm.
+///
+/// The delay between loopback and response is measured at the start and at
+/// the end of the signals. A positive drift means, that the capture clock runs
+/// faster than the playback clock and the recording is stretched in time.
+/// Returns `None` if the signals are too short or silent.
+pub fn estimate(loopback: &Loopback, response: &Measurement) -> Option<f32> {
+    let segment_len = (SEGMENT_DURATION * response.sample_rate as f32) as usize;
+
+    let loopback = &loopback.0.data;
+    let response = &response.data;
+    let len = loopback.len().min(response.len());
+
+    if len < 4 * segment_len {
+        return None;
+    }
+
+    let early = len / 4 - segment_len / 2;
+    let late = len * 3 / 4 - segment_len / 2;
+
+    let early_lag = lag(
+        &loopback[early..early + segment_len],
+        &response[early..early + segment_len],
+    )?;
+    let late_lag = lag(
+        &loopback[late..late + segment_len],
+        &response[late..late + segment_len],
+    )?;
+
+    Some((late_lag - early_lag) / (late - early) as f32 * 1e6)
+}
+
+/// Resamples `data` to compensate a clock drift of `ppm`, see [`estimate`].
+pub fn correct(data: &[f32], ppm: f32) -> Vec<f32> {
+    interpolate(data, 1.0 + ppm as f64 * 1e-6)
+}
+
+/// Linearly interpolates `data` at every `ratio`th position.
+fn interpolate(data: &[f32], ratio: f64) -> Vec<f32> {
+    let len = (data.len() as f64 / ratio).floor() as usize;
+
+    (0..len)
+        .map(|n| {
+            let pos = n as f64 * ratio;
+            let i = pos.floor() as usize;
+            let frac = (pos - i as f64) as f32;
+
+            let a = data[i];
+            let b = data.get(i + 1).copied().unwrap_or_default();
+
+            a + (b - a) * frac
+        })
+        .collect()
+}
+
+/// Delay of `response` relative to `reference` in (fractional) samples.
+fn lag(reference: &[f32], response: &[f32]) -> Option<f32> {
+    let len = reference.len() * 2;
+
+    let to_complex = |data: &[f32]| -> Vec<Complex32> {
+        let mut buf: Vec<_> = data.iter().map(Complex32::from).collect();
+        buf.resize(len, Complex32::default());
+        buf
+    };
+
+    let mut reference = to_complex(reference);
+    let mut response = to_complex(response);
+
+    let mut planner = FftPlanner::new();
+    let fft = planner.plan_fft_forward(len);
+    fft.process(&mut reference);
+    fft.process(&mut response);
+
+    let mut correlation: Vec<_> = response
+        .iter()
+        .zip(reference.iter())
+        .map(|(r, l)| r * l.conj())
+        .collect();
+
+    planner.plan_fft_inverse(len).process(&mut correlation);
+
+    let correlation: Vec<f32> = correlation.iter().map(|c| c.re.abs()).collect();
+    let (peak, max) = correlation
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))?;
+
+    if *max <= f32::EPSILON {
+        return None;
+    }
+
+    // parabolic interpolation for sub-sample precision
+    let prev = correlation[(peak + len - 1) % len];
+    let next = correlation[(peak + 1) % len];
+    let denom = prev - 2.0 * max + next;
+    let offset = if denom.abs() > f32::EPSILON {
+        0.5 * (prev - next) / denom
+    } else {
+        0.0
+    };
+
+    let peak = if peak < len / 2 {
+        peak as f32
+    } else {
+        peak as f32 - len as f32
+    };
+
+    Some(peak + offset)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::signals::ExponentialSweep;
+
+    #[test]
+    fn drift_of_stretched_recording() {
+        let sweep: Vec<_> =
+            ExponentialSweep::new(50.0, 10_000.0, 0.8, 5 * 48_000, 48_000).collect();
+
+        // capture clock 50 ppm faster
+        let stretched = correct(&sweep, -50.0);
+
+        let loopback = Loopback::new(Measurement::new(48_000, sweep));
+        let response = Measurement::new(48_000, stretched);
+
+        let drift = estimate(&loopback, &response).unwrap();
+        assert!((drift - 50.0).abs() < 5.0, "drift: {drift}");
+    }
+}
@@ -0,0 +1,216 @@
+//! Impedance of a loudspeaker driver, measured with a sense resistor in
+//! series to it, and the Thiele-Small parameters estimated from it.
+//!
+//! Both channels of the jig are recorded while a sweep is played, the
+//! impedance follows from the transfer function between them.
+
+use rustfft::num_complex::Complex32;
+
+use crate::TransferFunction;
+
+/// How the two recorded channels are connected to the jig. The reference
+/// channel always measures the amplifier output, i.e. the voltage across the
+/// sense resistor and the driver.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Jig {
+    /// The response channel measures the voltage across the driver.
+    DriverVoltage {
+        /// Sense resistor in ohm
+        resistance: f32,
+    },
+    /// The response channel measures the voltage across the sense resistor,
+    /// which is proportional to the current.
+    SenseVoltage {
+        /// Sense resistor in ohm
+        resistance: f32,
+    },
+}
+
+impl Jig {
+    /// Impedance of the driver from the ratio of the response to the
+    /// reference voltage.
+    pub fn impedance(&self, ratio: Complex32) -> Complex32 {
+        match *self {
+            Jig::DriverVoltage { resistance } => resistance * ratio / (1.0 - ratio),
+            Jig::SenseVoltage { resistance } => resistance * (1.0 - ratio) / ratio,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Impedance {
+    /// Frequency of each value of `data` in Hz
+    pub frequencies: Vec<f32>,
+    /// Complex impedance in ohm
+    pub data: Vec<Complex32>,
+}
+
+/// Small signal parameters of a driver, derived from the resonance peak of
+/// its impedance.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThieleSmall {
+    /// DC resistance of the voice coil in ohm
+    pub re: f32,
+    /// Resonance frequency in Hz
+    pub fs: f32,
+    /// Impedance at the resonance in ohm
+    pub z_max: f32,
+    pub qms: f32,
+    pub qes: f32,
+    pub qts: f32,
+}
+
+impl Impedance {
+    /// Computes the impedance from the `reference` and the `response`
+    /// channel of the `jig`, which are recorded at the same time.
+    pub fn from_recordings(
+        jig: Jig,
+        reference: &[f32],
+        response: &[f32],
+        sample_rate: u32,
+        fft_size: usize,
+    ) -> Self {
+        // both channels are captured by the same interface, there is no
+        // delay between them
+        let transfer_function = TransferFunction::from_recordings(reference, response, fft_size, 0);
+
+        let (frequencies, data) = transfer_function
+            .frequencies(sample_rate)
+            .zip(transfer_function.magnitude().zip(transfer_function.phase()))
+            .skip(1)
+            .map(|(frequency, (magnitude, phase))| {
+                let ratio = Complex32::from_polar(magnitude, phase);
+                (frequency, jig.impedance(ratio))
+            })
+            .unzip();
+
+        Self { frequencies, data }
+    }
+
+    /// Magnitude in ohm.
+    pub fn magnitude(&self) -> impl Iterator<Item = f32> + '_ {
+        self.data.iter().map(|z| z.norm())
+    }
+
+    /// Phase in degrees.
+    pub fn phase(&self) -> impl Iterator<Item = f32> + '_ {
+        self.data.iter().map(|z| z.arg().to_degrees())
+    }
+
+    /// Estimates the Thiele-Small parameters from the highest impedance peak
+    /// between `lower` and `upper` Hz. Without a measured `dc_resistance`,
+    /// the lowest impedance below the resonance is taken, which slightly
+    /// overestimates it.
+    pub fn thiele_small(
+        &self,
+        lower: f32,
+        upper: f32,
+        dc_resistance: Option<f32>,
+    ) -> Option<ThieleSmall> {
+        let magnitude: Vec<f32> = self.magnitude().collect();
+        let in_range = |i: &usize| (lower..=upper).contains(&self.frequencies[*i]);
+
+        let resonance = (0..magnitude.len())
+            .filter(in_range)
+            .max_by(|a, b| magnitude[*a].total_cmp(&magnitude[*b]))?;
+
+        let fs = self.frequencies[resonance];
+        let z_max = magnitude[resonance];
+
+        let re = match dc_resistance {
+            Some(re) => re,
+            None => (0..resonance)
+                .filter(in_range)
+                .map(|i| magnitude[i])
+                .min_by(f32::total_cmp)?,
+        };
+
+        let r0 = z_max / re;
+        if r0 <= 1.0 {
+            return None;
+        }
+
+        // frequencies, where the impedance falls to the geometric mean of
+        // the resonance and the DC resistance
+        let threshold = re * r0.sqrt();
+        let f1 = (0..resonance)
+            .rev()
+            .find(|i| magnitude[*i] < threshold)
+            .map(|i| self.crossing(&magnitude, i, i + 1, threshold))?;
+        let f2 = (resonance..magnitude.len())
+            .find(|i| magnitude[*i] < threshold)
+            .map(|i| self.crossing(&magnitude, i - 1, i, threshold))?;
+
+        let qms = fs * r0.sqrt() / (f2 - f1);
+        let qes = qms / (r0 - 1.0);
+        let qts = qms * qes / (qms + qes);
+
+        Some(ThieleSmall {
+            re,
+            fs,
+            z_max,
+            qms,
+            qes,
+            qts,
+        })
+    }
+
+    /// Frequency between the bins `a` and `b`, where the magnitude crosses
+    /// the `threshold`, linearly interpolated.
+    fn crossing(&self, magnitude: &[f32], a: usize, b: usize, threshold: f32) -> f32 {
+        let t = (threshold - magnitude[a]) / (magnitude[b] - magnitude[a]);
+        self.frequencies[a] + t * (self.frequencies[b] - self.frequencies[a])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::f32::consts::PI;
+
+    /// Voice coil resistance in series to the motional impedance, a parallel
+    /// resonant circuit.
+    fn driver(re: f32, fs: f32, qms: f32, qes: f32, frequency: f32) -> Complex32 {
+        let ws = 2.0 * PI * fs;
+        let res = re * qms / qes;
+        let cmes = qes / (ws * re);
+        let lces = 1.0 / (ws * ws * cmes);
+
+        let w = 2.0 * PI * frequency;
+        let admittance =
+            1.0 / res + Complex32::new(0.0, w * cmes) + 1.0 / Complex32::new(0.0, w * lces);
+
+        re + 1.0 / admittance
+    }
+
+    #[test]
+    fn jig_recovers_the_impedance() {
+        let z = Complex32::new(6.0, 2.5);
+        let resistance = 10.0;
+
+        let jig = Jig::DriverVoltage { resistance };
+        assert!((jig.impedance(z / (z + resistance)) - z).norm() < 1e-4);
+
+        let jig = Jig::SenseVoltage { resistance };
+        assert!((jig.impedance(resistance / (z + resistance)) - z).norm() < 1e-4);
+    }
+
+    #[test]
+    fn thiele_small_parameters_of_a_driver() {
+        let frequencies: Vec<f32> = (40..4000).map(|i| i as f32 * 0.25).collect();
+        let data = frequencies
+            .iter()
+            .map(|f| driver(6.0, 50.0, 5.0, 0.5, *f))
+            .collect();
+        let impedance = Impedance { frequencies, data };
+
+        let parameters = impedance.thiele_small(10.0, 500.0, Some(6.0)).unwrap();
+
+        assert!((parameters.fs - 50.0).abs() < 0.5);
+        assert!((parameters.z_max - 66.0).abs() < 0.5);
+        assert!((parameters.qms - 5.0).abs() < 0.1);
+        assert!((parameters.qes - 0.5).abs() < 0.01);
+        assert!((parameters.qts - 5.0 * 0.5 / 5.5).abs() < 0.01);
+    }
+}
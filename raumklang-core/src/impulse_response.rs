@@ -3,17 +3,36 @@ use rustfft::{
     FftPlanner,
 };
 
-use crate::{Error, Loopback, Measurement};
+use crate::{
+    check_sample_rates, combine, convolution, DeconvolutionMethod, Error, Loopback, Measurement,
+};
+
+use std::f32::consts::PI;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ImpulseResponse {
     pub sample_rate: u32,
     pub data: Vec<Complex32>,
-    pub loopback_fft: Vec<Complex<f32>>,
-    pub response_fft: Vec<Complex<f32>>,
+    /// Spectra of the deconvolved signals, only known for impulse responses
+    /// computed with [`ImpulseResponse::from_samples`].
+    pub loopback_fft: Option<Vec<Complex<f32>>>,
+    pub response_fft: Option<Vec<Complex<f32>>>,
 }
 
+/// An impulse response multiplied with a window. All analyses in the
+/// frequency domain are derived from it, so that they see the same data.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WindowedImpulseResponse {
+    pub sample_rate: u32,
+    /// Position of the first sample of the impulse response in `data`.
+    pub offset: usize,
+    pub data: Vec<f32>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FrequencyResponse {
     pub sample_rate: u32,
     pub data: Vec<Complex32>,
@@ -21,11 +40,34 @@ pub struct FrequencyResponse {
 
 impl ImpulseResponse {
     pub fn from_signals(loopback: &Loopback, response: &Measurement) -> Result<Self, Error> {
-        let sample_rate = loopback.0.sample_rate;
-        assert!(sample_rate == response.sample_rate());
+        Self::from_signals_with(loopback, response, DeconvolutionMethod::default())
+    }
 
-        let mut loopback = loopback.0.data.clone();
-        let mut response = response.data.clone();
+    pub fn from_signals_with(
+        loopback: &Loopback,
+        response: &Measurement,
+        method: DeconvolutionMethod,
+    ) -> Result<Self, Error> {
+        check_sample_rates(loopback.sample_rate(), response.sample_rate())?;
+
+        Ok(Self::from_samples(
+            response.sample_rate(),
+            loopback.iter().copied(),
+            response.iter().copied(),
+            method,
+        ))
+    }
+
+    /// Deconvolves the samples of a recording with the ones of the loopback,
+    /// both have to be recorded with `sample_rate`.
+    pub fn from_samples(
+        sample_rate: u32,
+        loopback: impl IntoIterator<Item = f32>,
+        response: impl IntoIterator<Item = f32>,
+        method: DeconvolutionMethod,
+    ) -> Self {
+        let mut loopback: Vec<f32> = loopback.into_iter().collect();
+        let mut response: Vec<f32> = response.into_iter().collect();
 
         let response_len = response.len();
         let loopback_len = loopback.len();
@@ -54,12 +96,7 @@ impl ImpulseResponse {
         fft.process(&mut response);
         fft.process(&mut loopback);
 
-        // devide both
-        let mut result: Vec<Complex<f32>> = response
-            .iter()
-            .zip(loopback.iter())
-            .map(|(r, l)| r / l)
-            .collect();
+        let mut result = method.apply(&response, &loopback);
 
         // back to time domain
         let fft = planner.plan_fft_inverse(result.len());
@@ -68,12 +105,37 @@ impl ImpulseResponse {
         let scale: f32 = 1.0 / (result.len() as f32);
         let impulse_response: Vec<_> = result.into_iter().map(|s| s.scale(scale)).collect();
 
-        Ok(Self {
+        Self {
             sample_rate,
             data: impulse_response,
-            loopback_fft: loopback,
-            response_fft: response,
-        })
+            loopback_fft: Some(loopback),
+            response_fft: Some(response),
+        }
+    }
+
+    /// Convolves a recording of an exponential sweep with its inverse filter
+    /// (see [`crate::signals::ExponentialSweep::inverse_filter`]), which
+    /// needs no loopback. Harmonic distortion ends up at the end of the
+    /// impulse response.
+    pub fn from_inverse_filter(
+        sample_rate: u32,
+        inverse_filter: &[f32],
+        response: impl IntoIterator<Item = f32>,
+    ) -> Self {
+        let response: Vec<f32> = response.into_iter().collect();
+        let mut data = convolution::convolve(&response, inverse_filter);
+
+        // the linear response starts, where the inverse filter fully overlaps
+        // the sweep
+        let start = inverse_filter.len().saturating_sub(1).min(data.len());
+        data.rotate_left(start);
+
+        Self {
+            sample_rate,
+            data: data.into_iter().map(Complex32::from).collect(),
+            loopback_fft: None,
+            response_fft: None,
+        }
     }
 
     pub fn from_files(loopback_path: &str, measurment_path: &str) -> Result<Self, Error> {
@@ -82,30 +144,299 @@ impl ImpulseResponse {
 
         Self::from_signals(&loopback, &measurement)
     }
+
+    /// Sum of both impulse responses, e.g. the summed response of two
+    /// speakers. The shorter one is padded with zeros.
+    pub fn sum(&self, other: &Self) -> Result<Self, Error> {
+        self.combine(other, |a, b| a + b)
+    }
+
+    /// Difference `self - other` of both impulse responses.
+    pub fn difference(&self, other: &Self) -> Result<Self, Error> {
+        self.combine(other, |a, b| a - b)
+    }
+
+    pub fn scale(&self, gain: f32) -> Self {
+        Self {
+            sample_rate: self.sample_rate,
+            data: self.data.iter().map(|s| s * gain).collect(),
+            loopback_fft: self.loopback_fft.clone(),
+            response_fft: self
+                .response_fft
+                .as_ref()
+                .map(|response| response.iter().map(|s| s * gain).collect()),
+        }
+    }
+
+    fn combine(
+        &self,
+        other: &Self,
+        op: impl Fn(Complex32, Complex32) -> Complex32,
+    ) -> Result<Self, Error> {
+        check_sample_rates(self.sample_rate, other.sample_rate)?;
+
+        Ok(Self {
+            sample_rate: self.sample_rate,
+            data: combine(&self.data, &other.data, op),
+            // derived responses are not backed by a recording
+            loopback_fft: None,
+            response_fft: None,
+        })
+    }
 }
 
-impl FrequencyResponse {
-    pub fn new(impulse_response: ImpulseResponse, window: &[f32]) -> Self {
-        let mut windowed_impulse_response: Vec<_> = impulse_response
-            .data
+impl WindowedImpulseResponse {
+    /// Applies `window` to the impulse response, which is rotated by `offset`
+    /// samples beforehand, so that the part before its peak is covered by the
+    /// window, too.
+    pub fn new(impulse_response: &ImpulseResponse, window: &[f32], offset: usize) -> Self {
+        let len = impulse_response.data.len();
+
+        let data = window
             .iter()
-            .take(window.len())
             .enumerate()
-            .map(|(i, s)| s * window[i])
+            .map(|(i, w)| {
+                let i = (i + len - offset % len) % len;
+                impulse_response.data[i].re * w
+            })
             .collect();
 
+        Self {
+            sample_rate: impulse_response.sample_rate,
+            offset,
+            data,
+        }
+    }
+
+    /// The samples starting `before` samples ahead of the impulse response,
+    /// padded with zeros to `len` samples.
+    pub fn around_start(&self, before: usize, len: usize) -> Vec<f32> {
+        let zeros = before.saturating_sub(self.offset);
+        let start = self.offset.saturating_sub(before);
+
+        std::iter::repeat_n(0.0, zeros)
+            .chain(self.data.iter().skip(start).copied())
+            .chain(std::iter::repeat(0.0))
+            .take(len)
+            .collect()
+    }
+}
+
+impl FrequencyResponse {
+    pub fn new(impulse_response: ImpulseResponse, window: &[f32]) -> Self {
+        Self::from_windowed(&WindowedImpulseResponse::new(&impulse_response, window, 0))
+    }
+
+    pub fn from_windowed(windowed: &WindowedImpulseResponse) -> Self {
+        let mut data: Vec<_> = windowed.data.iter().map(Complex32::from).collect();
+
+        // an even length, so that the bins match `fft_len`
+        if data.len() % 2 == 1 {
+            data.push(Complex32::ZERO);
+        }
+
         let mut planner = FftPlanner::<f32>::new();
-        let fft = planner.plan_fft_forward(windowed_impulse_response.len());
+        let fft = planner.plan_fft_forward(data.len());
 
-        fft.process(&mut windowed_impulse_response);
+        fft.process(&mut data);
 
-        let data_len = windowed_impulse_response.len() / 2 - 1;
-        let data = windowed_impulse_response
-            .into_iter()
-            .take(data_len)
-            .collect();
+        let data_len = data.len() / 2 - 1;
+        data.truncate(data_len);
+
+        Self {
+            sample_rate: windowed.sample_rate,
+            data,
+        }
+    }
+
+    /// Length of the FFT, that results in `bins` bins, as the bins up to, but
+    /// without the Nyquist frequency are kept.
+    pub fn fft_len(bins: usize) -> usize {
+        (bins + 1) * 2
+    }
+
+    /// Spacing of the bins in Hz.
+    pub fn resolution(&self) -> f32 {
+        self.sample_rate as f32 / Self::fft_len(self.data.len()) as f32
+    }
+
+    /// Phase of each bin in radians within ±π, relative to the start of the
+    /// impulse response, which lies `offset` samples into the windowed data
+    /// (see [`WindowedImpulseResponse::offset`]).
+    pub fn phase(&self, offset: usize) -> Vec<f32> {
+        let fft_len = Self::fft_len(self.data.len());
+
+        self.data
+            .iter()
+            .enumerate()
+            .map(|(bin, s)| {
+                // in whole turns first, to keep the precision for long delays
+                let turns = (bin * offset % fft_len) as f32 / fft_len as f32;
+                (s * Complex32::from_polar(1.0, 2.0 * PI * turns)).arg()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::{
+        signals::{ExponentialSweep, LinearSineSweep},
+        testing,
+    };
+
+    use std::time::Duration;
+
+    fn impulse_response(sample_rate: u32, data: &[f32]) -> ImpulseResponse {
+        ImpulseResponse {
+            sample_rate,
+            data: data.iter().map(Complex32::from).collect(),
+            loopback_fft: None,
+            response_fft: None,
+        }
+    }
+
+    #[test]
+    fn difference_pads_shorter_response() {
+        let a = impulse_response(48_000, &[1.0, 0.5, 0.25]);
+        let b = impulse_response(48_000, &[0.5]);
+
+        let result = a.difference(&b).unwrap();
+        let result: Vec<_> = result.data.iter().map(|s| s.re).collect();
+
+        assert_eq!(result, vec![0.5, 0.5, 0.25]);
+    }
+
+    #[test]
+    fn sum_rejects_different_sample_rates() {
+        let a = impulse_response(48_000, &[1.0]);
+        let b = impulse_response(44_100, &[1.0]);
+
+        assert!(matches!(
+            a.sum(&b),
+            Err(Error::SampleRateMismatch(48_000, 44_100))
+        ));
+    }
+
+    #[test]
+    fn windowed_response_starts_at_offset() {
+        let ir = impulse_response(48_000, &[1.0, 0.5, 0.0, 0.0, 0.25]);
+
+        let windowed = WindowedImpulseResponse::new(&ir, &[1.0, 1.0, 1.0, 0.5], 1);
+        assert_eq!(windowed.data, vec![0.25, 1.0, 0.5, 0.0]);
+
+        assert_eq!(windowed.around_start(2, 4), vec![0.0, 0.25, 1.0, 0.5]);
+    }
+
+    #[test]
+    fn deconvolution_recovers_synthesized_responses() {
+        let sample_rate = 8_000;
+        let sweep: Vec<f32> =
+            LinearSineSweep::new(1, 3_999, Duration::from_secs(1), 0.5, sample_rate as usize)
+                .collect();
+
+        for seed in 0..testing::CASES {
+            let expected = testing::random_impulse_response(seed, 512);
+            // -60 dB below the sweep
+            let response = testing::record(&sweep, &expected, 0.0005, seed);
+
+            let impulse_response = ImpulseResponse::from_samples(
+                sample_rate,
+                sweep.iter().copied(),
+                response,
+                DeconvolutionMethod::RegularizedDivision,
+            );
+            let actual: Vec<_> = impulse_response.data.iter().map(|s| s.re).collect();
+
+            let error = testing::error_db(&expected, &actual);
+            assert!(error < -20.0, "seed {seed}: error of {error:.1} dB");
+        }
+    }
+
+    #[test]
+    fn inverse_filter_recovers_synthesized_responses() {
+        let sample_rate = 8_000;
+        let sweep = ExponentialSweep::new(20.0, 3_999.0, 0.5, 8_000, sample_rate as usize);
+        let inverse_filter = sweep.inverse_filter();
+        let sweep: Vec<f32> = sweep.collect();
+
+        for seed in 0..testing::CASES {
+            let expected = testing::random_impulse_response(seed, 512);
+            let response = testing::record(&sweep, &expected, 0.0005, seed);
+
+            let impulse_response =
+                ImpulseResponse::from_inverse_filter(sample_rate, &inverse_filter, response);
+            let actual: Vec<_> = impulse_response.data[..512].iter().map(|s| s.re).collect();
+
+            // the sweep covers neither the lowest frequencies nor the Nyquist
+            // frequency
+            let error = testing::error_db(&expected, &actual);
+            assert!(error < -15.0, "seed {seed}: error of {error:.1} dB");
+        }
+    }
+
+    #[test]
+    fn phase_is_relative_to_the_start_of_the_impulse_response() {
+        let mut data = vec![0.0; 1024];
+        data[100] = 1.0;
+        data[103] = 0.5;
+
+        let windowed = WindowedImpulseResponse {
+            sample_rate: 48_000,
+            offset: 100,
+            data,
+        };
+        let frequency_response = FrequencyResponse::from_windowed(&windowed);
+
+        let phase = frequency_response.phase(100);
+        let expected = |bin: usize| {
+            let delay = Complex32::from_polar(0.5, -2.0 * PI * bin as f32 * 3.0 / 1024.0);
+            (delay + 1.0).arg()
+        };
+
+        assert_eq!(phase.len(), frequency_response.data.len());
+        assert!(phase
+            .iter()
+            .enumerate()
+            .all(|(bin, phase)| (phase - expected(bin)).abs() < 1e-3));
+    }
+
+    #[test]
+    fn odd_windows_are_padded_to_the_fft_len() {
+        let mut data = vec![0.0; 1023];
+        data[10] = 1.0;
+
+        let windowed = WindowedImpulseResponse {
+            sample_rate: 48_000,
+            offset: 0,
+            data,
+        };
+        let frequency_response = FrequencyResponse::from_windowed(&windowed);
+
+        assert_eq!(
+            FrequencyResponse::fft_len(frequency_response.data.len()),
+            1024
+        );
+        assert!(frequency_response
+            .phase(10)
+            .iter()
+            .all(|phase| phase.abs() < 1e-3));
+    }
+
+    #[test]
+    fn frequency_response_matches_golden_file() {
+        let sample_rate = 48_000;
+        let fft_len = 1024;
+
+        let windowed = WindowedImpulseResponse {
+            sample_rate,
+            offset: 0,
+            data: testing::comb_impulse_response(sample_rate, fft_len),
+        };
+        let frequency_response = FrequencyResponse::from_windowed(&windowed);
 
-        let sample_rate = impulse_response.sample_rate;
-        Self { sample_rate, data }
+        testing::assert_golden_frd("comb.frd", &frequency_response, fft_len);
     }
 }
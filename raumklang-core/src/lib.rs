@@ -1,12 +1,43 @@
 mod audio;
+mod deconvolution;
 mod impulse_response;
+mod resample;
+mod rta;
+#[cfg(test)]
+mod testing;
+mod transfer_function;
 mod window;
 
+pub mod alignment;
+pub mod average;
+pub mod bands;
+pub mod channel;
+pub mod convolution;
+pub mod crossover;
+pub mod decay;
+pub mod denoise;
+pub mod drc;
+pub mod drift;
+pub mod impedance;
+pub mod loopback;
 pub mod loudness;
+pub mod moving_mic;
+pub mod noise_rating;
+pub mod phase;
+pub mod preprocess;
+pub mod rew;
+pub mod room;
 pub mod signals;
+pub mod spl;
+pub mod store;
+pub mod template;
+pub mod wav;
 
 pub use audio::*;
+pub use deconvolution::*;
 pub use impulse_response::*;
+pub use rta::*;
+pub use transfer_function::*;
 pub use window::*;
 
 use signals::map_hound_error;
@@ -52,6 +83,20 @@ impl Loopback {
 
         Ok(Self(measurement))
     }
+
+    /// Loads a single channel, e.g. when the loopback is recorded alongside
+    /// the microphone in a multi-channel file.
+    pub fn from_file_channel(path: impl AsRef<Path>, channel: u16) -> Result<Self, WavLoadError> {
+        let measurement = Measurement::from_file_channel(path, channel)?;
+
+        Ok(Self(measurement))
+    }
+
+    pub fn from_wav_bytes(bytes: &[u8], channel: u16) -> Result<Self, WavLoadError> {
+        let measurement = Measurement::from_wav_bytes(bytes, channel)?;
+
+        Ok(Self(measurement))
+    }
 }
 
 impl AsRef<Measurement> for Loopback {
@@ -69,25 +114,129 @@ impl Measurement {
         }
     }
 
+    /// Takes `channel` out of interleaved samples with `channels` channels.
+    pub fn from_interleaved(
+        sample_rate: u32,
+        samples: impl IntoIterator<Item = f32>,
+        channels: u16,
+        channel: u16,
+    ) -> Self {
+        let data = samples
+            .into_iter()
+            .skip(channel as usize)
+            .step_by(channels.max(1) as usize)
+            .collect();
+
+        Self::new(sample_rate, data)
+    }
+
+    /// Loads the first channel of the file.
     pub fn from_file(path: impl AsRef<Path>) -> Result<Self, WavLoadError> {
+        Self::from_file_channel(path, 0)
+    }
+
+    pub fn from_file_channel(path: impl AsRef<Path>, channel: u16) -> Result<Self, WavLoadError> {
+        Self::from_file_with_progress(path, channel, |_| {})
+    }
+
+    /// Like [`Measurement::from_file_channel`], but reports the read fraction
+    /// of the file to `progress`, while reading.
+    pub fn from_file_with_progress(
+        path: impl AsRef<Path>,
+        channel: u16,
+        mut progress: impl FnMut(f32),
+    ) -> Result<Self, WavLoadError> {
+        if wav::is_extended(&path)? {
+            let modified = std::fs::metadata(&path)?.modified()?;
+            let (sample_rate, data) = wav::read(path, channel)?;
+            progress(1.0);
+
+            return Ok(Measurement {
+                sample_rate,
+                data,
+                modified,
+            });
+        }
+
         let file = std::fs::File::open(path)?;
         // let mut file = hound::WavReader::open(file).map_err(map_hound_error)?;
         let modified = file.metadata()?.modified()?;
-        let mut file = hound::WavReader::new(file).map_err(map_hound_error)?;
-
-        let sample_rate = file.spec().sample_rate;
-        let data: Vec<f32> = file
-            .samples::<f32>()
-            .collect::<Result<Vec<f32>, _>>()
-            .map_err(map_hound_error)?;
+        let file = hound::WavReader::new(file).map_err(map_hound_error)?;
 
         Ok(Measurement {
-            sample_rate,
-            data,
             modified,
+            ..Self::from_hound(file, channel, progress)?
         })
     }
 
+    /// Loads `channel` of the content of a WAV file in `bytes`, e.g. when it
+    /// is piped into stdin.
+    pub fn from_wav_bytes(bytes: &[u8], channel: u16) -> Result<Self, WavLoadError> {
+        if wav::is_extended_bytes(bytes) {
+            let (sample_rate, data) = wav::read_bytes(bytes, channel)?;
+
+            return Ok(Self::new(sample_rate, data));
+        }
+
+        let reader = hound::WavReader::new(bytes).map_err(map_hound_error)?;
+
+        Self::from_hound(reader, channel, |_| {})
+    }
+
+    fn from_hound(
+        mut file: hound::WavReader<impl io::Read>,
+        channel: u16,
+        mut progress: impl FnMut(f32),
+    ) -> Result<Self, WavLoadError> {
+        const PROGRESS_INTERVAL: usize = 1 << 16;
+
+        let spec = file.spec();
+        if channel >= spec.channels {
+            return Err(WavLoadError::MissingChannel {
+                channel,
+                channels: spec.channels,
+            });
+        }
+
+        let len = file.len().max(1) as f32;
+        let mut report = |i: usize| {
+            if i.is_multiple_of(PROGRESS_INTERVAL) {
+                progress(i as f32 / len);
+            }
+        };
+
+        let samples: Vec<f32> = match spec.sample_format {
+            hound::SampleFormat::Float => file
+                .samples::<f32>()
+                .enumerate()
+                .map(|(i, s)| {
+                    report(i);
+                    s
+                })
+                .collect::<Result<_, _>>(),
+            hound::SampleFormat::Int => {
+                let scale = 1.0 / (1i64 << (spec.bits_per_sample - 1)) as f32;
+
+                file.samples::<i32>()
+                    .enumerate()
+                    .map(|(i, s)| {
+                        report(i);
+                        s.map(|s| s as f32 * scale)
+                    })
+                    .collect::<Result<_, _>>()
+            }
+        }
+        .map_err(map_hound_error)?;
+        progress(1.0);
+
+        Ok(Self::from_interleaved(
+            spec.sample_rate,
+            samples,
+            spec.channels,
+            channel,
+        ))
+    }
+
     pub fn sample_rate(&self) -> u32 {
         self.sample_rate
     }
@@ -99,6 +248,94 @@ impl Measurement {
     pub fn iter(&self) -> Iter<'_, f32> {
         self.data.iter()
     }
+
+    /// Sample wise sum, the shorter signal is padded with silence.
+    ///
+    /// As the deconvolution is linear, the impulse response of the result is
+    /// the sum of both impulse responses, when measured with the same loopback.
+    pub fn sum(&self, other: &Self) -> Result<Self, Error> {
+        check_sample_rates(self.sample_rate, other.sample_rate)?;
+
+        let data = combine(&self.data, &other.data, |a, b| a + b);
+        Ok(Self::new(self.sample_rate, data))
+    }
+
+    /// Sample wise difference `self - other`, see [`Measurement::sum`].
+    pub fn difference(&self, other: &Self) -> Result<Self, Error> {
+        check_sample_rates(self.sample_rate, other.sample_rate)?;
+
+        let data = combine(&self.data, &other.data, |a, b| a - b);
+        Ok(Self::new(self.sample_rate, data))
+    }
+
+    pub fn scale(&self, gain: f32) -> Self {
+        let data = self.data.iter().map(|s| s * gain).collect();
+        Self::new(self.sample_rate, data)
+    }
+
+    /// Delays the signal by the given amount of samples, negative values
+    /// advance it by dropping samples from the start.
+    pub fn shift(&self, samples: isize) -> Self {
+        let data = std::iter::repeat_n(0.0, samples.max(0) as usize)
+            .chain(
+                self.data
+                    .iter()
+                    .skip(samples.min(0).unsigned_abs())
+                    .copied(),
+            )
+            .collect();
+
+        Self::new(self.sample_rate, data)
+    }
+
+    /// Resamples the signal to compensate a clock drift of `ppm`, see
+    /// [`drift::estimate`].
+    pub fn correct_drift(&self, ppm: f32) -> Self {
+        Self::new(self.sample_rate, drift::correct(&self.data, ppm))
+    }
+
+    /// Converts the signal to `sample_rate`, content above the Nyquist
+    /// frequency of the new sample rate is removed.
+    pub fn resample(&self, sample_rate: u32) -> Self {
+        let ratio = f64::from(self.sample_rate) / f64::from(sample_rate);
+
+        Self {
+            sample_rate,
+            data: resample::resample(&self.data, ratio),
+            modified: self.modified,
+        }
+    }
+
+    /// Mean of all samples, which is zero, unless the input adds a constant
+    /// voltage.
+    pub fn dc_offset(&self) -> f32 {
+        if self.data.is_empty() {
+            return 0.0;
+        }
+
+        let sum: f64 = self.data.iter().copied().map(f64::from).sum();
+        (sum / self.data.len() as f64) as f32
+    }
+
+    /// Subtracts the [`Measurement::dc_offset`] from all samples.
+    pub fn remove_dc_offset(&self) -> Self {
+        let dc_offset = self.dc_offset();
+        let data = self.data.iter().map(|s| s - dc_offset).collect();
+
+        Self {
+            sample_rate: self.sample_rate,
+            data,
+            modified: self.modified,
+        }
+    }
+
+    /// Pads the signal with silence up to `len` samples, longer signals are
+    /// left untouched.
+    pub fn pad_to(&mut self, len: usize) {
+        if self.data.len() < len {
+            self.data.resize(len, 0.0);
+        }
+    }
 }
 
 impl From<Loopback> for Measurement {
@@ -123,23 +360,150 @@ pub fn volume_to_amplitude(volume: f32) -> f32 {
     }
 }
 
+/// Inverse of [`volume_to_amplitude`], clamped to the range of the volume.
+pub fn amplitude_to_volume(amplitude: f32) -> f32 {
+    let a = 0.001;
+    let b = 6.908;
+
+    // amplitude at a volume of 0.1, where the curve turns into a line
+    let knee = a * f32::exp(0.1 * b);
+
+    let volume = if amplitude < knee {
+        amplitude / (10.0 * knee)
+    } else {
+        (amplitude / a).ln() / b
+    };
+
+    volume.clamp(0.0, 1.0)
+}
+
 #[inline]
 pub fn dbfs(v: f32) -> f32 {
     20.0 * f32::log10(v.abs())
 }
 
+#[inline]
+pub fn db_to_gain(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// Checks that a recording covers the whole stimulus plus the expected decay
+/// of the measured system, all lengths are given in samples.
+pub fn check_recording_length(
+    stimulus: usize,
+    decay: usize,
+    recording: usize,
+) -> Result<(), TruncatedRecording> {
+    let expected = stimulus + decay;
+
+    if recording < expected {
+        return Err(TruncatedRecording {
+            expected,
+            missing: expected - recording,
+        });
+    }
+
+    Ok(())
+}
+
+fn check_sample_rates(a: u32, b: u32) -> Result<(), Error> {
+    if a != b {
+        return Err(Error::SampleRateMismatch(a, b));
+    }
+
+    Ok(())
+}
+
+/// Combines two signals element wise, the shorter one is padded with zeros.
+fn combine<T>(a: &[T], b: &[T], op: impl Fn(T, T) -> T) -> Vec<T>
+where
+    T: Copy + Default,
+{
+    (0..a.len().max(b.len()))
+        .map(|i| {
+            op(
+                a.get(i).copied().unwrap_or_default(),
+                b.get(i).copied().unwrap_or_default(),
+            )
+        })
+        .collect()
+}
+
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("error laoding a measurement")]
     WavLoadFile(#[from] WavLoadError),
     #[error(transparent)]
     AudioBackend(#[from] AudioBackendError),
+    #[error("sample rates don't match: {0} != {1}")]
+    SampleRateMismatch(u32, u32),
+}
+
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("recording is {missing} samples shorter than expected ({expected} samples)")]
+pub struct TruncatedRecording {
+    pub expected: usize,
+    pub missing: usize,
 }
 
 #[derive(Error, Debug)]
 pub enum WavLoadError {
     #[error(transparent)]
     Io(#[from] io::Error),
+    #[error("channel {channel} doesn't exist, the file has {channels} channel(s)")]
+    MissingChannel { channel: u16, channels: u16 },
     #[error("unknown")]
     Other,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn recording_without_decay_is_truncated() {
+        assert_eq!(check_recording_length(1000, 200, 1200), Ok(()));
+        assert_eq!(
+            check_recording_length(1000, 200, 1050),
+            Err(TruncatedRecording {
+                expected: 1200,
+                missing: 150
+            })
+        );
+    }
+
+    #[test]
+    fn channel_is_taken_from_interleaved_samples() {
+        let interleaved = [1.0, -1.0, 2.0, -2.0, 3.0, -3.0];
+
+        let right = Measurement::from_interleaved(48_000, interleaved, 2, 1);
+
+        assert_eq!(
+            right.iter().copied().collect::<Vec<_>>(),
+            [-1.0, -2.0, -3.0]
+        );
+    }
+
+    #[test]
+    fn resampling_converts_sample_rate() {
+        let measurement = Measurement::new(96_000, vec![0.0; 96]);
+
+        let resampled = measurement.resample(48_000);
+
+        assert_eq!(resampled.sample_rate(), 48_000);
+        assert_eq!(resampled.iter().len(), 48);
+    }
+
+    #[test]
+    fn dc_offset_is_removed() {
+        let measurement = Measurement::new(48_000, vec![0.75, -0.25, 0.75, -0.25]);
+
+        assert_eq!(measurement.dc_offset(), 0.25);
+
+        let removed = measurement.remove_dc_offset();
+        assert_eq!(
+            removed.iter().copied().collect::<Vec<_>>(),
+            [0.5, -0.5, 0.5, -0.5]
+        );
+    }
+}
@@ -0,0 +1,239 @@
+//! Verifies the stimulus path of the audio interface by a sweep, that is
+//! recorded through the loopback only. Its impulse response has to be a clean
+//! band-limited impulse, otherwise resampling or DSP of the driver would
+//! corrupt the acoustic measurements.
+
+use std::time::Duration;
+
+use crate::{dbfs, drift, DeconvolutionMethod, ImpulseResponse, Loopback, Measurement};
+
+/// Minimum peak to noise ratio in dB of the loopback impulse response.
+const MIN_SNR: f32 = 60.0;
+
+/// Maximum deviation in dB of the magnitude from a flat response.
+const MAX_DEVIATION: f32 = 0.5;
+
+/// Maximum clock drift in ppm, the loopback shares the clock of the playback.
+const MAX_DRIFT: f32 = 10.0;
+
+/// Distance of the evaluated band from the ends of the sweep in octaves,
+/// where the sweep is faded in and out.
+const BAND_MARGIN: f32 = 1.0 / 3.0;
+
+/// Result of the loopback verification.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Check {
+    /// Peak to noise ratio of the impulse response in dB
+    pub snr: f32,
+    /// Largest deviation of the magnitude from its median within the sweep
+    /// range in dB
+    pub deviation: f32,
+    /// Clock drift between the stimulus and the loopback in ppm, `None` if the
+    /// recording is too short to estimate it
+    pub drift: Option<f32>,
+    /// Round trip latency of the audio interface
+    pub latency: Duration,
+}
+
+/// Deviation of the loopback from a clean impulse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Issue {
+    /// The loopback is noisy, clipped or not connected.
+    Noise,
+    /// The magnitude is not flat, e.g. an EQ or other DSP is active.
+    Coloration,
+    /// The loopback runs on another clock, e.g. the driver resamples.
+    Drift,
+}
+
+impl Check {
+    /// Compares the `loopback` recording with the `stimulus`, that was swept
+    /// from `start_frequency` to `end_frequency`. `None` if the loopback is
+    /// silent.
+    pub fn new(
+        stimulus: &[f32],
+        loopback: &Loopback,
+        start_frequency: f32,
+        end_frequency: f32,
+    ) -> Option<Self> {
+        let sample_rate = loopback.sample_rate();
+
+        // no gain outside of the sweep range, so the result is band-limited
+        let impulse_response = ImpulseResponse::from_samples(
+            sample_rate,
+            stimulus.iter().copied(),
+            loopback.iter().copied(),
+            DeconvolutionMethod::RegularizedDivision,
+        );
+
+        // the second half holds negative delays
+        let causal = &impulse_response.data[..impulse_response.data.len() / 2];
+        let (position, peak) = causal
+            .iter()
+            .map(|s| s.re.abs())
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))?;
+
+        if peak == 0.0 {
+            return None;
+        }
+
+        let noise = &causal[causal.len() * 3 / 4..];
+        let noise = (noise.iter().map(|s| s.re.powi(2)).sum::<f32>() / noise.len() as f32).sqrt();
+
+        let deviation = deviation(&impulse_response, start_frequency, end_frequency)?;
+
+        let stimulus = Loopback::new(Measurement::new(sample_rate, stimulus.to_vec()));
+        let drift = drift::estimate(&stimulus, loopback.as_ref());
+
+        Some(Self {
+            snr: dbfs(peak / noise),
+            deviation,
+            drift,
+            latency: Duration::from_secs_f32(position as f32 / sample_rate as f32),
+        })
+    }
+
+    /// Everything that is out of tolerance, empty for a clean loopback.
+    pub fn issues(&self) -> Vec<Issue> {
+        let mut issues = vec![];
+
+        if self.snr < MIN_SNR {
+            issues.push(Issue::Noise);
+        }
+
+        if self.deviation > MAX_DEVIATION {
+            issues.push(Issue::Coloration);
+        }
+
+        if self.drift.is_some_and(|drift| drift.abs() > MAX_DRIFT) {
+            issues.push(Issue::Drift);
+        }
+
+        issues
+    }
+}
+
+impl std::fmt::Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let description = match self {
+            Issue::Noise => "the loopback is noisy, clipped or not connected",
+            Issue::Coloration => "the loopback is not flat, DSP like an EQ seems to be active",
+            Issue::Drift => "the loopback runs on another clock, the driver seems to resample",
+        };
+
+        write!(f, "{description}")
+    }
+}
+
+/// Largest deviation of the magnitude from its median within the sweep range,
+/// taken from the spectra, as the impulse response is limited to the range.
+/// `None`, if the impulse response was not deconvolved from its signals.
+fn deviation(
+    impulse_response: &ImpulseResponse,
+    start_frequency: f32,
+    end_frequency: f32,
+) -> Option<f32> {
+    let (Some(response_fft), Some(loopback_fft)) = (
+        &impulse_response.response_fft,
+        &impulse_response.loopback_fft,
+    ) else {
+        return None;
+    };
+
+    let len = response_fft.len();
+    let resolution = impulse_response.sample_rate as f32 / len as f32;
+
+    let margin = 2f32.powf(BAND_MARGIN);
+    let first = (start_frequency * margin / resolution).ceil() as usize;
+    let last = ((end_frequency / margin / resolution).floor() as usize).min(len / 2);
+
+    let mut levels: Vec<f32> = (first.max(1)..=last)
+        .map(|k| {
+            let response = response_fft[k];
+            let loopback = loopback_fft[k];
+
+            dbfs(response.norm() / loopback.norm())
+        })
+        .filter(|level| level.is_finite())
+        .collect();
+
+    if levels.is_empty() {
+        return Some(0.0);
+    }
+
+    levels.sort_by(f32::total_cmp);
+    let median = levels[levels.len() / 2];
+
+    let deviation = levels
+        .iter()
+        .map(|level| (level - median).abs())
+        .fold(0.0, f32::max);
+
+    Some(deviation)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::signals::LinearSineSweep;
+
+    const SAMPLE_RATE: u32 = 8_000;
+
+    fn sweep() -> Vec<f32> {
+        LinearSineSweep::new(20, 3_900, Duration::from_secs(4), 0.5, SAMPLE_RATE as usize).collect()
+    }
+
+    fn check(loopback: Vec<f32>) -> Check {
+        let loopback = Loopback::new(Measurement::new(SAMPLE_RATE, loopback));
+
+        Check::new(&sweep(), &loopback, 20.0, 3_900.0).unwrap()
+    }
+
+    #[test]
+    fn clean_loopback_has_no_issues() {
+        let delayed = std::iter::repeat_n(0.0, 80).chain(sweep()).collect();
+
+        let check = check(delayed);
+
+        assert!(check.issues().is_empty());
+        assert!((check.latency.as_secs_f32() - 0.01).abs() < 1e-4);
+    }
+
+    #[test]
+    fn filtered_loopback_is_colored() {
+        // first order low pass, as if an EQ is in the path
+        let mut last = 0.0;
+        let filtered = sweep()
+            .into_iter()
+            .map(|s| {
+                last = 0.5 * s + 0.5 * last;
+                last
+            })
+            .collect();
+
+        assert!(check(filtered).issues().contains(&Issue::Coloration));
+    }
+
+    #[test]
+    fn resampled_loopback_drifts() {
+        let resampled = drift::correct(&sweep(), 200.0);
+
+        assert!(check(resampled).issues().contains(&Issue::Drift));
+    }
+
+    #[test]
+    fn silent_loopback_is_rejected() {
+        let loopback = Loopback::new(Measurement::new(SAMPLE_RATE, vec![0.0; 32_000]));
+
+        assert_eq!(Check::new(&sweep(), &loopback, 20.0, 3_900.0), None);
+    }
+
+    #[test]
+    fn deviation_needs_the_spectra() {
+        let impulse_response = ImpulseResponse::from_inverse_filter(SAMPLE_RATE, &[1.0], sweep());
+
+        assert_eq!(deviation(&impulse_response, 20.0, 3_900.0), None);
+    }
+}
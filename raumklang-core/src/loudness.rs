@@ -1,5 +1,16 @@
 use ringbuf::Rb;
 
+use std::{f32::consts::PI, time::Duration};
+
+/// Integration time of the RMS measurement.
+pub const RMS_WINDOW: Duration = Duration::from_millis(300);
+
+/// Oversampling of the true peak measurement, as recommended by ITU-R BS.1770.
+const TRUE_PEAK_OVERSAMPLING: usize = 4;
+/// Number of samples on each side of the interpolated position, that are
+/// taken into account.
+const TRUE_PEAK_HALF_WIDTH: isize = 8;
+
 pub struct MeterProd(ringbuf::HeapProducer<f32>);
 
 impl MeterProd {
@@ -38,6 +49,13 @@ impl Meter {
         }
     }
 
+    /// Meter with a window of [`RMS_WINDOW`] at the given sample rate.
+    pub fn with_sample_rate(sample_rate: usize) -> Self {
+        let window_size = sample_rate * RMS_WINDOW.as_millis() as usize / 1000;
+
+        Self::new(window_size.max(1))
+    }
+
     pub fn update_from_iter<I>(&mut self, iter: I) -> bool
     where
         I: IntoIterator<Item = f32>,
@@ -84,3 +102,126 @@ impl Meter {
         }
     }
 }
+
+/// Levels of a recorded sweep compared to the played one, to diagnose level
+/// problems of a measurement without its audio.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LevelReport {
+    /// Crest factor of the played signal in dB
+    pub stimulus_crest_factor: f32,
+    /// Crest factor of the recording in dB
+    pub crest_factor: f32,
+    /// True peak of the recording in dBFS
+    pub peak: f32,
+}
+
+impl LevelReport {
+    pub fn new(stimulus: &[f32], recording: &[f32], sample_rate: u32) -> Self {
+        Self {
+            stimulus_crest_factor: crest_factor(stimulus, sample_rate),
+            crest_factor: crest_factor(recording, sample_rate),
+            peak: crate::dbfs(true_peak(recording)),
+        }
+    }
+
+    /// Distance of the peak to full scale in dB.
+    pub fn headroom(&self) -> f32 {
+        -self.peak
+    }
+}
+
+/// Ratio of the true peak to the RMS in dB. The RMS is taken from the
+/// loudest [`RMS_WINDOW`], so that silence around the signal doesn't count.
+pub fn crest_factor(samples: &[f32], sample_rate: u32) -> f32 {
+    let window_size = (sample_rate as usize * RMS_WINDOW.as_millis() as usize / 1000).max(1);
+
+    let rms = samples
+        .chunks(window_size)
+        .map(|window| window.iter().map(|s| s * s).sum::<f32>() / window.len() as f32)
+        .fold(0.0, f32::max)
+        .sqrt();
+
+    crate::dbfs(true_peak(samples)) - crate::dbfs(rms)
+}
+
+/// Peak of the reconstructed signal, including the overshoots between the
+/// samples, that the reconstruction filter of a DAC produces.
+pub fn true_peak(samples: &[f32]) -> f32 {
+    // only evaluated between the samples, so `x` is never zero
+    let sinc = |x: f32| (PI * x).sin() / (PI * x);
+    let hann = |x: f32| 0.5 + 0.5 * (PI * x / TRUE_PEAK_HALF_WIDTH as f32).cos();
+
+    let taps = -TRUE_PEAK_HALF_WIDTH + 1..=TRUE_PEAK_HALF_WIDTH;
+
+    // interpolation filters for the positions between two samples
+    let filters: Vec<Vec<f32>> = (1..TRUE_PEAK_OVERSAMPLING)
+        .map(|phase| phase as f32 / TRUE_PEAK_OVERSAMPLING as f32)
+        .map(|frac| {
+            taps.clone()
+                .map(|k| k as f32 - frac)
+                .map(|x| sinc(x) * hann(x))
+                .collect()
+        })
+        .collect();
+
+    let sample_peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+
+    let taps = &taps;
+    let inter_sample_peak = (0..samples.len() as isize)
+        .flat_map(|n| {
+            filters.iter().map(move |filter| {
+                filter
+                    .iter()
+                    .zip(taps.clone())
+                    .filter_map(|(h, k)| {
+                        let i = usize::try_from(n + k).ok()?;
+                        samples.get(i).map(|s| s * h)
+                    })
+                    .sum::<f32>()
+                    .abs()
+            })
+        })
+        .fold(0.0f32, f32::max);
+
+    sample_peak.max(inter_sample_peak)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn true_peak_finds_inter_sample_overs() {
+        // quarter of the sample rate, all samples miss the crests by 45°
+        let samples: Vec<_> = (0..256)
+            .map(|n| (PI / 2.0 * n as f32 + PI / 4.0).sin())
+            .collect();
+
+        let sample_peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        assert!((sample_peak - 0.707).abs() < 0.01);
+
+        assert!((true_peak(&samples) - 1.0).abs() < 0.02);
+    }
+
+    #[test]
+    fn crest_factor_ignores_silence() {
+        let sample_rate = 8_000;
+        // faded in and out, an abrupt start overshoots between the samples
+        let fade = |n: u32| (n.min(sample_rate - 1 - n) as f32 / 80.0).min(1.0);
+        let sine: Vec<_> = (0..sample_rate)
+            .map(|n| (2.0 * PI * 1_000.0 * n as f32 / sample_rate as f32 + 0.1).sin() * fade(n))
+            .collect();
+        let recording: Vec<_> = vec![0.0; sample_rate as usize]
+            .into_iter()
+            .chain(sine.iter().map(|s| s * 0.5))
+            .collect();
+
+        let report = LevelReport::new(&sine, &recording, sample_rate);
+
+        // 3 dB for a sine
+        assert!((report.stimulus_crest_factor - 3.01).abs() < 0.1);
+        assert!((report.crest_factor - report.stimulus_crest_factor).abs() < 0.1);
+        assert!((report.headroom() - 6.02).abs() < 0.1);
+    }
+}
@@ -0,0 +1,155 @@
+//! Moving microphone measurement: periodic noise is played, while the
+//! microphone is slowly moved around the listening area. The spectra of all
+//! periods are averaged into a single magnitude response, which is less
+//! dominated by the room modes at a single position than a sweep.
+
+use rustfft::{num_complex::Complex32, Fft, FftPlanner};
+
+use std::sync::Arc;
+
+/// Averages the power spectra of the recorded periods of a periodic
+/// stimulus, e.g. [`crate::signals::PeriodicPinkNoise`].
+pub struct Average {
+    fft: Arc<dyn Fft<f32>>,
+    /// Power of the stimulus per bin
+    stimulus: Vec<f32>,
+
+    buf: Vec<f32>,
+    /// The first period is skipped, the room needs it to reach a steady
+    /// state.
+    skipped: bool,
+
+    power: Vec<f64>,
+    periods: usize,
+}
+
+impl Average {
+    /// `period` is a single period of the played stimulus.
+    pub fn new(period: &[f32]) -> Self {
+        let fft = FftPlanner::new().plan_fft_forward(period.len());
+
+        let mut spectrum: Vec<_> = period.iter().copied().map(Complex32::from).collect();
+        fft.process(&mut spectrum);
+
+        let bins = period.len() / 2 + 1;
+        let stimulus = spectrum.iter().take(bins).map(|s| s.norm_sqr()).collect();
+
+        Self {
+            fft,
+            stimulus,
+            buf: Vec::with_capacity(period.len() * 2),
+            skipped: false,
+            power: vec![0.0; bins],
+            periods: 0,
+        }
+    }
+
+    pub fn push(&mut self, data: &[f32]) {
+        self.buf.extend_from_slice(data);
+
+        let period = self.fft.len();
+        while self.buf.len() >= period {
+            if self.skipped {
+                self.process_period();
+            }
+
+            self.skipped = true;
+            self.buf.drain(..period);
+        }
+    }
+
+    /// Number of averaged periods.
+    pub fn periods(&self) -> usize {
+        self.periods
+    }
+
+    pub fn frequency_resolution(&self, sample_rate: u32) -> f32 {
+        sample_rate as f32 / self.fft.len() as f32
+    }
+
+    /// Magnitude of the averaged response in dB, bin `i` is at
+    /// `i * frequency_resolution`. Bins without energy in the stimulus are
+    /// `None`.
+    pub fn magnitude(&self) -> Vec<Option<f32>> {
+        self.power
+            .iter()
+            .zip(&self.stimulus)
+            .map(|(&power, &stimulus)| {
+                if self.periods == 0 || stimulus <= f32::EPSILON {
+                    return None;
+                }
+
+                let power = power / self.periods as f64 / f64::from(stimulus);
+                Some((10.0 * power.log10()) as f32)
+            })
+            .collect()
+    }
+
+    fn process_period(&mut self) {
+        let period = self.fft.len();
+
+        let mut buf: Vec<_> = self.buf[..period]
+            .iter()
+            .copied()
+            .map(Complex32::from)
+            .collect();
+
+        self.fft.process(&mut buf);
+
+        for (sum, s) in self.power.iter_mut().zip(buf) {
+            *sum += f64::from(s.norm_sqr());
+        }
+
+        self.periods += 1;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::{convolution::convolve, signals::PeriodicPinkNoise};
+
+    use std::f32::consts::PI;
+
+    #[test]
+    fn averages_the_response_of_all_positions() {
+        let period = 1024;
+        let noise = PeriodicPinkNoise::new(period, 0.5, 0);
+
+        let stimulus: Vec<f32> = noise.clone().take(period * 6).collect();
+
+        // two positions with a reflection, that is delayed differently, the
+        // second one continues where the microphone reached steady state
+        let mut average = Average::new(noise.period());
+
+        let first = convolve(&stimulus, &[1.0, 0.0, 0.5, 0.0]);
+        average.push(&first[..period * 6]);
+
+        let second = convolve(&stimulus, &[1.0, 0.0, 0.0, 0.5]);
+        average.push(&second[period..period * 6]);
+
+        assert_eq!(average.periods(), 10);
+
+        let magnitude = average.magnitude();
+        assert_eq!(magnitude[0], None);
+
+        for (bin, level) in magnitude.iter().enumerate().skip(1).take(period / 2 - 1) {
+            let omega = 2.0 * PI * bin as f32 / period as f32;
+            let power = |delay: f32| {
+                let (re, im) = (
+                    1.0 + 0.5 * (omega * delay).cos(),
+                    0.5 * (omega * delay).sin(),
+                );
+                re * re + im * im
+            };
+            let expected = 10.0 * ((power(2.0) + power(3.0)) / 2.0).log10();
+
+            let level = level.unwrap();
+            assert!(
+                (level - expected).abs() < 0.1,
+                "bin {bin}: {level} dB != {expected} dB"
+            );
+        }
+    }
+}
@@ -0,0 +1,190 @@
+//! Rates the background noise of a room with the noise criterion (NC) and
+//! noise rating (NR) curves, based on its octave band levels.
+
+use rustfft::{num_complex::Complex32, FftPlanner};
+
+use crate::{
+    bands::{self, Band},
+    Window, WindowBuilder,
+};
+
+/// Center frequencies of the octave bands, both curves are defined for.
+pub const OCTAVE_BANDS: [f32; 8] = [63.0, 125.0, 250.0, 500.0, 1000.0, 2000.0, 4000.0, 8000.0];
+
+/// NC curves from 15 to 70 in steps of 5 (ANSI S12.2), the maximum level in
+/// dB SPL per octave band.
+const NC_CURVES: [[f32; 8]; 12] = [
+    [47.0, 36.0, 29.0, 22.0, 17.0, 14.0, 12.0, 11.0],
+    [51.0, 40.0, 33.0, 26.0, 22.0, 19.0, 17.0, 16.0],
+    [54.0, 44.0, 37.0, 31.0, 27.0, 24.0, 22.0, 21.0],
+    [57.0, 48.0, 41.0, 35.0, 31.0, 29.0, 28.0, 27.0],
+    [60.0, 52.0, 45.0, 40.0, 36.0, 34.0, 33.0, 32.0],
+    [64.0, 56.0, 50.0, 45.0, 41.0, 39.0, 38.0, 37.0],
+    [67.0, 60.0, 54.0, 49.0, 46.0, 44.0, 43.0, 42.0],
+    [71.0, 64.0, 58.0, 54.0, 51.0, 49.0, 48.0, 47.0],
+    [74.0, 67.0, 62.0, 58.0, 56.0, 54.0, 53.0, 52.0],
+    [77.0, 71.0, 67.0, 63.0, 61.0, 59.0, 58.0, 57.0],
+    [80.0, 75.0, 71.0, 68.0, 66.0, 64.0, 63.0, 62.0],
+    [83.0, 79.0, 75.0, 72.0, 71.0, 70.0, 69.0, 68.0],
+];
+
+const NC_FIRST: f32 = 15.0;
+const NC_STEP: f32 = 5.0;
+
+/// Coefficients `a` and `b` of the NR curves (ISO R 1996), the level of a
+/// band is `a + b * NR`.
+const NR_COEFFICIENTS: [(f32, f32); 8] = [
+    (35.5, 0.790),
+    (22.0, 0.870),
+    (12.0, 0.930),
+    (4.8, 0.974),
+    (0.0, 1.000),
+    (-3.5, 1.015),
+    (-6.1, 1.025),
+    (-8.0, 1.030),
+];
+
+/// Length of the blocks, whose power spectra are averaged.
+const BLOCK_SIZE: usize = 16384;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NoiseRating {
+    /// Level in dB SPL of each of the [`OCTAVE_BANDS`]
+    pub levels: [f32; 8],
+    /// `None`, if the noise exceeds the NC-70 curve
+    pub nc: Option<f32>,
+    pub nr: f32,
+}
+
+impl NoiseRating {
+    /// Rates a recording of the background noise, `calibration` is the sound
+    /// pressure level in dB, that corresponds to 0 dBFS.
+    pub fn from_recording(samples: &[f32], sample_rate: u32, calibration: f32) -> Self {
+        Self::from_levels(octave_levels(samples, sample_rate, calibration))
+    }
+
+    pub fn from_levels(levels: [f32; 8]) -> Self {
+        Self {
+            levels,
+            nc: nc(&levels),
+            nr: nr(&levels),
+        }
+    }
+}
+
+/// Levels in dB SPL of the [`OCTAVE_BANDS`], from the averaged power spectrum
+/// of the recording.
+pub fn octave_levels(samples: &[f32], sample_rate: u32, calibration: f32) -> [f32; 8] {
+    let half = BLOCK_SIZE / 2;
+    let window = WindowBuilder::new(Window::Hann, half, Window::Hann, half).build();
+    let window_power: f32 = window.iter().map(|w| w * w).sum();
+
+    let fft = FftPlanner::new().plan_fft_forward(BLOCK_SIZE);
+
+    let mut power = vec![0.0; half + 1];
+    let mut blocks = 0;
+    // blocks overlap by half, the last incomplete one is padded with silence
+    for start in (0..samples.len().max(1)).step_by(half) {
+        let mut buf: Vec<Complex32> = samples[start..]
+            .iter()
+            .chain(std::iter::repeat(&0.0))
+            .zip(window.iter())
+            .map(|(s, w)| Complex32::from(s * w))
+            .collect();
+
+        fft.process(&mut buf);
+
+        for (i, (p, s)) in power.iter_mut().zip(buf.iter()).enumerate() {
+            // one-sided spectrum, the sum of all bins is the mean square
+            let factor = if i == 0 || i == half { 1.0 } else { 2.0 };
+            *p += factor * s.norm_sqr() / (BLOCK_SIZE as f32 * window_power);
+        }
+
+        blocks += 1;
+        if start + BLOCK_SIZE >= samples.len() {
+            break;
+        }
+    }
+
+    let resolution = sample_rate as f32 / BLOCK_SIZE as f32;
+
+    OCTAVE_BANDS.map(|center| {
+        let energy = bands::energy_sum(&power, resolution, &Band::new(center, 1)) / blocks as f32;
+        calibration + 10.0 * energy.log10()
+    })
+}
+
+/// Noise criterion of the octave band `levels`, the highest rating of any
+/// band, interpolated between the curves.
+pub fn nc(levels: &[f32; 8]) -> Option<f32> {
+    let last = NC_CURVES.len() - 1;
+
+    (0..levels.len())
+        .map(|band| {
+            let level = levels[band];
+
+            // curves below NC-15 are extrapolated with the lowest step
+            let upper = (1..last)
+                .find(|i| level <= NC_CURVES[*i][band])
+                .unwrap_or(last);
+            let lower = NC_CURVES[upper - 1][band];
+            let t = (level - lower) / (NC_CURVES[upper][band] - lower);
+
+            NC_FIRST + NC_STEP * (upper as f32 - 1.0 + t)
+        })
+        .max_by(f32::total_cmp)
+        .filter(|nc| *nc <= NC_FIRST + NC_STEP * last as f32)
+        .map(|nc| nc.max(0.0))
+}
+
+/// Noise rating of the octave band `levels`, the highest rating of any band.
+pub fn nr(levels: &[f32; 8]) -> f32 {
+    levels
+        .iter()
+        .zip(NR_COEFFICIENTS)
+        .map(|(level, (a, b))| (level - a) / b)
+        .fold(f32::NEG_INFINITY, f32::max)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn levels_on_a_curve_are_rated_by_it() {
+        assert_eq!(nc(&NC_CURVES[3]), Some(30.0));
+
+        let nr_40 = NR_COEFFICIENTS.map(|(a, b)| a + b * 40.0);
+        assert!((nr(&nr_40) - 40.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn loudest_band_determines_the_rating() {
+        let mut levels = NC_CURVES[0];
+        levels[4] = 41.0;
+
+        assert_eq!(nc(&levels), Some(40.0));
+
+        levels[0] = 90.0;
+        assert_eq!(nc(&levels), None);
+    }
+
+    #[test]
+    fn octave_levels_of_a_tone() {
+        let sample_rate = 48_000;
+        // 1 kHz sine at -20 dBFS RMS
+        let amplitude = 0.1 * std::f32::consts::SQRT_2;
+        let samples: Vec<f32> = (0..sample_rate * 2)
+            .map(|i| {
+                amplitude
+                    * (2.0 * std::f32::consts::PI * 1000.0 * i as f32 / sample_rate as f32).sin()
+            })
+            .collect();
+
+        let levels = octave_levels(&samples, sample_rate, 100.0);
+
+        assert!((levels[4] - 80.0).abs() < 0.5);
+        assert!(levels[0] < 40.0);
+    }
+}
@@ -0,0 +1,411 @@
+use rustfft::{num_complex::Complex32, FftPlanner};
+
+use crate::{
+    bands::{self, Band},
+    ImpulseResponse, Window, WindowBuilder,
+};
+
+use std::{f32::consts::PI, time::Duration};
+
+/// Minimum phase version of `spectrum` (a full, two-sided FFT), computed with
+/// the real cepstrum.
+pub fn minimum_phase(spectrum: &[Complex32]) -> Vec<Complex32> {
+    let len = spectrum.len();
+    let mut planner = FftPlanner::new();
+
+    let mut cepstrum: Vec<_> = spectrum
+        .iter()
+        .map(|s| Complex32::from(s.norm().max(1e-9).ln()))
+        .collect();
+
+    planner.plan_fft_inverse(len).process(&mut cepstrum);
+
+    // fold the anti-causal part onto the causal part
+    let half = len.div_ceil(2);
+    for (i, c) in cepstrum.iter_mut().enumerate() {
+        let factor = if i == 0 || (len.is_multiple_of(2) && i == len / 2) {
+            1.0
+        } else if i < half {
+            2.0
+        } else {
+            0.0
+        };
+
+        *c *= factor / len as f32;
+    }
+
+    planner.plan_fft_forward(len).process(&mut cepstrum);
+
+    cepstrum.into_iter().map(Complex32::exp).collect()
+}
+
+/// Phase of `spectrum` in excess of its minimum phase version, in radians.
+pub fn excess_phase(spectrum: &[Complex32]) -> Vec<f32> {
+    minimum_phase(spectrum)
+        .iter()
+        .zip(spectrum)
+        .map(|(m, s)| (s * m.conj()).arg())
+        .collect()
+}
+
+/// Generates an all-pass FIR filter, that inverts the excess phase of an
+/// impulse response within a limited frequency band and time window.
+#[derive(Debug, Clone)]
+pub struct ExcessPhaseCorrection {
+    lower: f32,
+    upper: f32,
+    window: Duration,
+    pre_echo_ceiling: f32,
+    min_snr: f32,
+}
+
+/// The analysed part of an impulse response and a part of the same length
+/// from its noise tail, both windowed and transformed.
+struct Analysis {
+    resolution: f32,
+    signal: Vec<Complex32>,
+    /// `None`, if the impulse response is too short to hold only noise at
+    /// its end.
+    noise: Option<Vec<Complex32>>,
+}
+
+impl ExcessPhaseCorrection {
+    pub fn new(lower: f32, upper: f32) -> Self {
+        Self {
+            lower,
+            upper,
+            window: Duration::from_millis(100),
+            pre_echo_ceiling: -60.0,
+            min_snr: 10.0,
+        }
+    }
+
+    /// Minimum signal to noise ratio in dB of a third-octave band, noisier
+    /// bands are not corrected, as their phase is mostly measurement noise.
+    pub fn min_snr(mut self, db: f32) -> Self {
+        self.min_snr = db;
+        self
+    }
+
+    /// Length of the analysed part of the impulse response after its peak,
+    /// later reflections are not corrected.
+    pub fn window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Maximum level of the pre-response of the filter relative to its peak
+    /// in dB, to prevent audible pre-ringing.
+    pub fn pre_echo_ceiling(mut self, db: f32) -> Self {
+        self.pre_echo_ceiling = db;
+        self
+    }
+
+    pub fn filter(&self, impulse_response: &ImpulseResponse) -> Vec<f32> {
+        let analysis = self.analyse(impulse_response);
+        let snr = analysis.band_snr();
+
+        let len = analysis.signal.len();
+        let excess_phase = excess_phase(&analysis.signal);
+
+        let mut correction: Vec<_> = (0..len)
+            .map(|i| {
+                let bin = if i <= len / 2 { i } else { len - i };
+                let frequency = bin as f32 * analysis.resolution;
+                let weight = self.band_weight(frequency) * self.snr_gate(&snr, frequency);
+
+                let phase = -excess_phase[i] * weight;
+                Complex32::from_polar(1.0, phase)
+            })
+            .collect();
+
+        FftPlanner::new()
+            .plan_fft_inverse(len)
+            .process(&mut correction);
+
+        // center the filter, to make it causal
+        correction.rotate_right(len / 2);
+        let mut filter: Vec<f32> = correction.iter().map(|s| s.re / len as f32).collect();
+
+        self.limit_pre_echo(&mut filter);
+
+        filter
+    }
+
+    /// Signal to noise ratio of the analysed part of the impulse response
+    /// in third-octave bands in dB, compared to a part of its noise tail.
+    pub fn band_snr(&self, impulse_response: &ImpulseResponse) -> Vec<(Band, f32)> {
+        self.analyse(impulse_response).band_snr()
+    }
+
+    fn analyse(&self, impulse_response: &ImpulseResponse) -> Analysis {
+        let sample_rate = impulse_response.sample_rate as f32;
+
+        let peak = impulse_response
+            .data
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.re.abs().total_cmp(&b.re.abs()))
+            .map(|(i, _)| i)
+            .unwrap_or_default();
+
+        let window_len = (self.window.as_secs_f32() * sample_rate) as usize;
+        let len = (2 * window_len).next_power_of_two();
+
+        // 1 ms fade in in front of the peak, half of the window as fade out
+        let fade_in = (sample_rate / 1000.0) as usize;
+        let window = WindowBuilder::new(Window::Hann, fade_in, Window::Hann, window_len / 2)
+            .set_offset(window_len - window_len / 2)
+            .build();
+
+        let mut planner = FftPlanner::new();
+        let fft = planner.plan_fft_forward(len);
+
+        let spectrum = |start: usize| {
+            let mut spectrum: Vec<_> = impulse_response
+                .data
+                .iter()
+                .skip(start)
+                .zip(window.iter())
+                .map(|(s, w)| Complex32::from(s.re * w))
+                .collect();
+            spectrum.resize(len, Complex32::default());
+
+            fft.process(&mut spectrum);
+            spectrum
+        };
+
+        let start = peak.saturating_sub(fade_in);
+
+        // the end of the causal half, the second one holds negative delays
+        let noise_start = (impulse_response.data.len() / 2).checked_sub(window.len());
+
+        Analysis {
+            resolution: sample_rate / len as f32,
+            signal: spectrum(start),
+            noise: noise_start
+                .filter(|&noise_start| noise_start >= start + window.len())
+                .map(spectrum),
+        }
+    }
+
+    /// Fades between the bands, that pass the SNR threshold and those, that
+    /// don't, from one band center to the next.
+    fn snr_gate(&self, snr: &[(Band, f32)], frequency: f32) -> f32 {
+        let passed = |i: usize| if snr[i].1 >= self.min_snr { 1.0 } else { 0.0 };
+
+        let index = snr.partition_point(|(band, _)| band.center < frequency);
+        match index {
+            _ if snr.is_empty() => 1.0,
+            0 => passed(0),
+            i if i == snr.len() => passed(i - 1),
+            i => {
+                let (lower, upper) = (snr[i - 1].0.center, snr[i].0.center);
+                let t = (frequency / lower).log2() / (upper / lower).log2();
+
+                passed(i - 1) * (1.0 - t) + passed(i) * t
+            }
+        }
+    }
+
+    /// Raised cosine weighting, that fades out the correction within half an
+    /// octave outside of the band.
+    fn band_weight(&self, frequency: f32) -> f32 {
+        let fade = |distance: f32| 0.5 * (1.0 + f32::cos(PI * (2.0 * distance).min(1.0)));
+
+        if frequency <= 0.0 {
+            0.0
+        } else if frequency < self.lower {
+            fade((self.lower / frequency).log2())
+        } else if frequency > self.upper {
+            fade((frequency / self.upper).log2())
+        } else {
+            1.0
+        }
+    }
+
+    fn limit_pre_echo(&self, filter: &mut [f32]) {
+        let Some((peak, max)) = filter
+            .iter()
+            .map(|s| s.abs())
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        else {
+            return;
+        };
+
+        let ceiling = max * crate::db_to_gain(self.pre_echo_ceiling);
+        for s in filter[..peak].iter_mut() {
+            *s = s.clamp(-ceiling, ceiling);
+        }
+    }
+}
+
+impl Analysis {
+    fn band_snr(&self) -> Vec<(Band, f32)> {
+        let Some(noise) = &self.noise else {
+            return vec![];
+        };
+
+        let half = self.signal.len() / 2 + 1;
+        let power = |spectrum: &[Complex32]| -> Vec<f32> {
+            spectrum[..half].iter().map(|s| s.norm_sqr()).collect()
+        };
+        let (signal, noise) = (power(&self.signal), power(noise));
+
+        let nyquist = (half - 1) as f32 * self.resolution;
+        bands::fractional_octave(3, self.resolution.max(20.0), nyquist)
+            .into_iter()
+            .map(|band| {
+                let signal = bands::energy_sum(&signal, self.resolution, &band);
+                let noise = bands::energy_sum(&noise, self.resolution, &band);
+
+                (band, 10.0 * (signal / noise).log10())
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PreRinging {
+    /// Energy of the pre-response in 1 ms blocks relative to the peak in dB,
+    /// the first block is right in front of the peak.
+    pub envelope: Vec<f32>,
+    /// Largest excess of the envelope over the backward masking threshold in
+    /// dB, positive values are likely audible.
+    pub audibility: f32,
+}
+
+impl PreRinging {
+    /// Duration of the pre-response, that exceeds `level` (in dB relative to
+    /// the peak).
+    pub fn duration_above(&self, level: f32) -> Duration {
+        let blocks = self
+            .envelope
+            .iter()
+            .rposition(|e| *e > level)
+            .map_or(0, |i| i + 1);
+
+        Duration::from_millis(blocks as u64)
+    }
+}
+
+/// Analyses the energy in front of the peak of a filter, e.g. one generated by
+/// [`ExcessPhaseCorrection`].
+pub fn pre_ringing(filter: &[f32], sample_rate: u32) -> PreRinging {
+    let block_len = (sample_rate as usize / 1000).max(1);
+
+    let Some((peak, max)) = filter
+        .iter()
+        .map(|s| s * s)
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+    else {
+        return PreRinging {
+            envelope: vec![],
+            audibility: f32::NEG_INFINITY,
+        };
+    };
+
+    let envelope: Vec<f32> = filter[..peak]
+        .rchunks(block_len)
+        .map(|block| {
+            let energy = block.iter().map(|s| s * s).sum::<f32>() / block_len as f32;
+            10.0 * f32::log10(energy / max)
+        })
+        .collect();
+
+    // NOTE: rough model of backward (pre-)masking, it decays from -10 dB right
+    // in front of the peak to -60 dB after 20 ms
+    let threshold = |ms: usize| -10.0 - 50.0 * (ms as f32 / 20.0).min(1.0);
+
+    let audibility = envelope
+        .iter()
+        .enumerate()
+        .map(|(ms, e)| e - threshold(ms))
+        .max_by(f32::total_cmp)
+        .unwrap_or(f32::NEG_INFINITY);
+
+    PreRinging {
+        envelope,
+        audibility,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::signals::WhiteNoise;
+
+    #[test]
+    fn minimum_phase_keeps_magnitude() {
+        let mut spectrum: Vec<_> = [0.2f32, 1.0, -0.5, 0.1]
+            .into_iter()
+            .chain(std::iter::repeat_n(0.0, 12))
+            .map(Complex32::from)
+            .collect();
+        FftPlanner::new()
+            .plan_fft_forward(spectrum.len())
+            .process(&mut spectrum);
+
+        let minimum = minimum_phase(&spectrum);
+
+        for (m, s) in minimum.iter().zip(spectrum.iter()) {
+            assert!((m.norm() - s.norm()).abs() < 1e-3);
+        }
+    }
+
+    fn impulse_with_noise() -> ImpulseResponse {
+        let sample_rate = 8_000;
+
+        let mut data: Vec<f32> = WhiteNoise::with_seed(0.001, 0)
+            .take(2 * sample_rate as usize)
+            .collect();
+        data[100] += 1.0;
+
+        ImpulseResponse {
+            sample_rate,
+            data: data.into_iter().map(Complex32::from).collect(),
+            loopback_fft: None,
+            response_fft: None,
+        }
+    }
+
+    #[test]
+    fn clean_bands_pass_the_snr_gate() {
+        let impulse_response = impulse_with_noise();
+
+        let snr = ExcessPhaseCorrection::new(20.0, 500.0).band_snr(&impulse_response);
+
+        assert!(!snr.is_empty());
+        assert!(snr.iter().all(|(_, snr)| *snr > 20.0), "{snr:?}");
+    }
+
+    #[test]
+    fn noisy_bands_are_not_corrected() {
+        let impulse_response = impulse_with_noise();
+
+        // every band is too noisy, the filter is a pure delay
+        let filter = ExcessPhaseCorrection::new(20.0, 500.0)
+            .min_snr(100.0)
+            .filter(&impulse_response);
+
+        let center = filter.len() / 2;
+        assert!((filter[center] - 1.0).abs() < 1e-3);
+        assert!(filter
+            .iter()
+            .enumerate()
+            .all(|(i, s)| i == center || s.abs() < 1e-3));
+    }
+
+    #[test]
+    fn minimum_phase_filter_has_no_pre_ringing() {
+        let filter = [1.0, 0.5, 0.25, 0.1];
+
+        let pre_ringing = pre_ringing(&filter, 48_000);
+
+        assert!(pre_ringing.envelope.is_empty());
+        assert_eq!(pre_ringing.duration_above(-60.0), Duration::ZERO);
+    }
+}
@@ -0,0 +1,109 @@
+//! Cleans a recording before the deconvolution. Handling noise and rumble
+//! of the microphone lie far below the measured range, but their energy
+//! ends up as low frequency noise in the impulse response.
+
+use crate::Measurement;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Preprocessing {
+    /// Subtracts the mean of the recording.
+    pub remove_dc_offset: bool,
+    /// Corner frequency of a high-pass in Hz, that removes subsonic rumble.
+    pub high_pass: Option<f32>,
+}
+
+impl Preprocessing {
+    /// Corner frequency of the high-pass, far below the lowest frequency of
+    /// a room measurement.
+    pub const DEFAULT_HIGH_PASS: f32 = 5.0;
+
+    pub fn is_enabled(&self) -> bool {
+        self.remove_dc_offset || self.high_pass.is_some()
+    }
+
+    pub fn apply(&self, measurement: &Measurement) -> Measurement {
+        let mut data = measurement.data.clone();
+
+        if self.remove_dc_offset {
+            let dc_offset = measurement.dc_offset();
+            data.iter_mut().for_each(|s| *s -= dc_offset);
+        }
+
+        if let Some(frequency) = self.high_pass {
+            high_pass(&mut data, measurement.sample_rate, frequency);
+        }
+
+        Measurement {
+            sample_rate: measurement.sample_rate,
+            data,
+            modified: measurement.modified,
+        }
+    }
+}
+
+/// Second order Butterworth high-pass, that is run forwards and backwards.
+/// This doubles its slope and keeps the phase of the remaining signal, so
+/// that the peak of the impulse response does not move.
+pub fn high_pass(data: &mut [f32], sample_rate: u32, frequency: f32) {
+    // the poles are close to one at low corner frequencies, single precision
+    // is not enough for the coefficients and the state
+    let w0 = 2.0 * std::f64::consts::PI * f64::from(frequency) / f64::from(sample_rate);
+    let alpha = w0.sin() / std::f64::consts::SQRT_2;
+    let cos = w0.cos();
+
+    let a0 = 1.0 + alpha;
+    let b = [
+        (1.0 + cos) / 2.0 / a0,
+        -(1.0 + cos) / a0,
+        (1.0 + cos) / 2.0 / a0,
+    ];
+    let a = [-2.0 * cos / a0, (1.0 - alpha) / a0];
+
+    let filter = |samples: &mut dyn Iterator<Item = &mut f32>| {
+        let (mut x1, mut x2, mut y1, mut y2) = (0.0, 0.0, 0.0, 0.0);
+
+        for s in samples {
+            let x = f64::from(*s);
+            let y = b[0] * x + b[1] * x1 + b[2] * x2 - a[0] * y1 - a[1] * y2;
+
+            (x2, x1) = (x1, x);
+            (y2, y1) = (y1, y);
+            *s = y as f32;
+        }
+    };
+
+    filter(&mut data.iter_mut());
+    filter(&mut data.iter_mut().rev());
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::f32::consts::{FRAC_1_SQRT_2, PI};
+
+    #[test]
+    fn high_pass_removes_rumble() {
+        let sample_rate = 48_000;
+        let sine = |frequency: f32| -> Vec<f32> {
+            (0..sample_rate)
+                .map(|i| (2.0 * PI * frequency * i as f32 / sample_rate as f32).sin())
+                .collect()
+        };
+        let rms = |data: &[f32]| {
+            // skip the edges, where the filter settles
+            let data = &data[sample_rate as usize / 4..sample_rate as usize * 3 / 4];
+            (data.iter().map(|s| s * s).sum::<f32>() / data.len() as f32).sqrt()
+        };
+
+        let mut rumble = sine(1.0);
+        high_pass(&mut rumble, sample_rate, 5.0);
+
+        let mut tone = sine(100.0);
+        high_pass(&mut tone, sample_rate, 5.0);
+
+        assert!(rms(&rumble) < 0.01);
+        assert!((rms(&tone) - FRAC_1_SQRT_2).abs() < 0.01);
+    }
+}
@@ -0,0 +1,108 @@
+//! Band-limited sample rate conversion.
+
+use std::f64::consts::PI;
+
+/// Zero crossings of the sinc on each side of the kernel, at the lower of
+/// both sample rates.
+const ZERO_CROSSINGS: f64 = 32.0;
+
+/// Cutoff relative to the lower Nyquist frequency, the transition band of
+/// the window ends below it.
+const CUTOFF: f64 = 0.95;
+
+/// Converts `data` by reading it at every `ratio`th position, e.g. `ratio = 2`
+/// halves the sample rate.
+///
+/// The samples are interpolated with a Blackman windowed sinc, which also
+/// removes all content above the Nyquist frequency of the new sample rate,
+/// that would alias otherwise.
+pub(crate) fn resample(data: &[f32], ratio: f64) -> Vec<f32> {
+    let len = (data.len() as f64 / ratio).floor() as usize;
+
+    // relative to the Nyquist frequency of `data`
+    let cutoff = CUTOFF * ratio.recip().min(1.0);
+    let half_width = ZERO_CROSSINGS / cutoff;
+
+    let sinc = |x: f64| {
+        if x == 0.0 {
+            1.0
+        } else {
+            (PI * x).sin() / (PI * x)
+        }
+    };
+    let blackman = |x: f64| {
+        let t = x / half_width;
+        0.42 + 0.5 * (PI * t).cos() + 0.08 * (2.0 * PI * t).cos()
+    };
+
+    (0..len)
+        .map(|n| {
+            let pos = n as f64 * ratio;
+
+            let first = (pos - half_width).ceil().max(0.0) as usize;
+            let last = ((pos + half_width).floor() as usize).min(data.len() - 1);
+
+            let sum: f64 = (first..=last)
+                .map(|k| {
+                    let x = pos - k as f64;
+                    f64::from(data[k]) * cutoff * sinc(cutoff * x) * blackman(x)
+                })
+                .sum();
+
+            sum as f32
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sine(frequency: f32, sample_rate: u32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|n| {
+                let t = n as f32 / sample_rate as f32;
+                (2.0 * std::f32::consts::PI * frequency * t).sin()
+            })
+            .collect()
+    }
+
+    /// RMS level in dB, the edges are left out, as the kernel is cut there.
+    fn level(data: &[f32]) -> f32 {
+        let data = &data[data.len() / 4..data.len() * 3 / 4];
+        let mean_square = data.iter().map(|s| s * s).sum::<f32>() / data.len() as f32;
+
+        10.0 * mean_square.log10()
+    }
+
+    #[test]
+    fn tone_in_the_passband_is_kept() {
+        let tone = sine(1000.0, 96_000, 9600);
+
+        let resampled = resample(&tone, 2.0);
+
+        assert_eq!(resampled.len(), 4800);
+        // a sine of amplitude 1 has an RMS level of -3.01 dB
+        assert!((level(&resampled) + 3.01).abs() < 0.05);
+    }
+
+    #[test]
+    fn tone_above_the_new_nyquist_frequency_is_removed() {
+        let tone = sine(30_000.0, 96_000, 9600);
+
+        let resampled = resample(&tone, 2.0);
+
+        // the windowed sinc filters it out, instead of folding it down to 18 kHz
+        assert!(level(&resampled) < -60.0);
+    }
+
+    #[test]
+    fn upsampling_keeps_the_samples() {
+        let tone = sine(1000.0, 44_100, 4410);
+
+        let resampled = resample(&tone, 44_100.0 / 48_000.0);
+
+        assert_eq!(resampled.len(), 4800);
+        assert!((level(&resampled) + 3.01).abs() < 0.05);
+    }
+}
@@ -0,0 +1,245 @@
+//! Import of measurements exported by Room EQ Wizard (REW).
+
+use rustfft::{num_complex::Complex32, FftPlanner};
+use thiserror::Error;
+
+use crate::{db_to_gain, phase, ImpulseResponse, Measurement};
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    #[error("file contains no data points")]
+    Empty,
+}
+
+/// A frequency response, as exported by REW with "Export measurement as
+/// text".
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextExport {
+    /// Name of the measurement, if given in the header.
+    pub name: Option<String>,
+    pub points: Vec<Point>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    /// Frequency in Hz
+    pub frequency: f32,
+    /// Level in dB
+    pub level: f32,
+    /// Phase in degrees
+    pub phase: Option<f32>,
+}
+
+impl TextExport {
+    /// Parses one point per line, header lines start with `*`.
+    pub fn parse(content: &str) -> Result<Self, Error> {
+        let mut name = None;
+        let mut points = vec![];
+
+        for line in content.lines().map(str::trim) {
+            if let Some(comment) = line.strip_prefix('*') {
+                if let Some(value) = comment.trim().strip_prefix("Measurement:") {
+                    name = Some(value.trim().to_string()).filter(|name| !name.is_empty());
+                }
+
+                continue;
+            }
+
+            let mut values = line
+                .split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+                .filter(|s| !s.is_empty())
+                .map(str::parse::<f32>);
+
+            let (Some(Ok(frequency)), Some(Ok(level))) = (values.next(), values.next()) else {
+                continue;
+            };
+
+            if frequency <= 0.0 {
+                continue;
+            }
+
+            points.push(Point {
+                frequency,
+                level,
+                phase: values.next().and_then(Result::ok),
+            });
+        }
+
+        if points.is_empty() {
+            return Err(Error::Empty);
+        }
+
+        points.sort_by(|a, b| a.frequency.total_cmp(&b.frequency));
+
+        Ok(Self { name, points })
+    }
+
+    /// Impulse response of `len` samples with this frequency response. The
+    /// points are interpolated on a logarithmic frequency scale, without
+    /// phase information the minimum phase is used.
+    pub fn impulse_response(&self, sample_rate: u32, len: usize) -> ImpulseResponse {
+        let has_phase = self.points.iter().all(|p| p.phase.is_some());
+
+        // REW wraps the phase to +-180 degrees
+        let mut offset = 0.0f32;
+        let mut last: Option<f32> = None;
+        let phases: Vec<f32> = self
+            .points
+            .iter()
+            .map(|p| {
+                let phase = p.phase.unwrap_or_default();
+                if let Some(last) = last {
+                    offset -= 360.0 * ((phase - last) / 360.0f32).round();
+                }
+                last = Some(phase);
+
+                (phase + offset).to_radians()
+            })
+            .collect();
+
+        let resolution = sample_rate as f32 / len as f32;
+        let half = len / 2;
+
+        let mut spectrum = vec![Complex32::default(); len];
+        for (i, bin) in spectrum.iter_mut().enumerate().take(half + 1) {
+            let (level, phase) = self.interpolate(&phases, i as f32 * resolution);
+            let magnitude = db_to_gain(level);
+
+            // DC and Nyquist are real valued
+            *bin = if has_phase && i != 0 && i != half {
+                Complex32::from_polar(magnitude, phase)
+            } else {
+                Complex32::from(magnitude)
+            };
+        }
+
+        for i in 1..half {
+            spectrum[len - i] = spectrum[i].conj();
+        }
+
+        if !has_phase {
+            spectrum = phase::minimum_phase(&spectrum);
+        }
+
+        FftPlanner::new()
+            .plan_fft_inverse(len)
+            .process(&mut spectrum);
+
+        let mut data: Vec<_> = spectrum
+            .into_iter()
+            .map(|s| Complex32::from(s.re / len as f32))
+            .collect();
+
+        // the phase of REW exports may contain the delay of the measurement
+        let peak = peak(data.iter().map(|s| s.re));
+        data.rotate_left(peak);
+
+        ImpulseResponse {
+            sample_rate,
+            data,
+            loopback_fft: None,
+            response_fft: None,
+        }
+    }
+
+    /// Level and phase in radians at `frequency`, held constant beyond the
+    /// first and last point.
+    fn interpolate(&self, phases: &[f32], frequency: f32) -> (f32, f32) {
+        let points = &self.points;
+
+        let i = points.partition_point(|p| p.frequency < frequency);
+        if i == 0 {
+            return (points[0].level, phases[0]);
+        }
+        if i == points.len() {
+            return (points[i - 1].level, phases[i - 1]);
+        }
+
+        let (a, b) = (&points[i - 1], &points[i]);
+        let t = (frequency / a.frequency).ln() / (b.frequency / a.frequency).ln();
+
+        (
+            a.level + (b.level - a.level) * t,
+            phases[i - 1] + (phases[i] - phases[i - 1]) * t,
+        )
+    }
+}
+
+/// Impulse response exported as WAV file by REW. It is padded to twice its
+/// length and the samples in front of the peak are moved to the end, like
+/// in the result of a deconvolution.
+pub fn impulse_response(measurement: &Measurement) -> ImpulseResponse {
+    let data: Vec<f32> = measurement.iter().copied().collect();
+    let peak = peak(data.iter().copied());
+
+    let data = data[peak..]
+        .iter()
+        .chain(&vec![0.0; data.len()])
+        .chain(&data[..peak])
+        .copied()
+        .map(Complex32::from)
+        .collect();
+
+    ImpulseResponse {
+        sample_rate: measurement.sample_rate(),
+        data,
+        loopback_fft: None,
+        response_fft: None,
+    }
+}
+
+fn peak(data: impl Iterator<Item = f32>) -> usize {
+    data.enumerate()
+        .max_by(|(_, a), (_, b)| a.abs().total_cmp(&b.abs()))
+        .map(|(i, _)| i)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXPORT: &str = "* Measurement data measured by REW V5.20.13\n\
+                          * Measurement: Left 80 cm\n\
+                          * Smoothing: 1/48 octave\n\
+                          *\n\
+                          * Freq(Hz), SPL(dB), Phase(degrees)\n\
+                          20.000, 80.0, 0.0\n\
+                          20000.000, 80.0, 0.0\n";
+
+    #[test]
+    fn parse_text_export() {
+        let export = TextExport::parse(EXPORT).unwrap();
+
+        assert_eq!(export.name.as_deref(), Some("Left 80 cm"));
+        assert_eq!(
+            export.points[1],
+            Point {
+                frequency: 20_000.0,
+                level: 80.0,
+                phase: Some(0.0)
+            }
+        );
+    }
+
+    #[test]
+    fn flat_response_is_an_impulse() {
+        let export = TextExport::parse(EXPORT).unwrap();
+
+        let impulse_response = export.impulse_response(48_000, 1024);
+
+        let gain = db_to_gain(80.0);
+        assert!((impulse_response.data[0].re - gain).abs() < gain * 1e-3);
+        assert!(impulse_response.data[1].re.abs() < gain * 1e-3);
+    }
+
+    #[test]
+    fn wav_export_peak_moves_to_time_zero() {
+        let measurement = Measurement::new(48_000, vec![0.0, 0.1, 1.0, 0.5]);
+
+        let impulse_response = impulse_response(&measurement);
+
+        let data: Vec<_> = impulse_response.data.iter().map(|s| s.re).collect();
+        assert_eq!(data, [1.0, 0.5, 0.0, 0.0, 0.0, 0.0, 0.0, 0.1]);
+    }
+}
@@ -0,0 +1,261 @@
+//! Synthesizes impulse responses of a rectangular room, e.g. for demos and
+//! tests without any audio hardware.
+//!
+//! The early reflections are computed with the image-source method, the
+//! late reverberation is exponentially decaying noise. Both decay with the
+//! configured reverberation time.
+
+use std::f32::consts::PI;
+
+use crate::{convolution::convolve, signals::WhiteNoise};
+
+/// Speed of sound in m/s
+const SPEED_OF_SOUND: f32 = 343.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Room {
+    /// Width, depth and height in meters
+    pub dimensions: [f32; 3],
+    /// Position of the loudspeaker in meters
+    pub source: [f32; 3],
+    /// Position of the microphone in meters
+    pub listener: [f32; 3],
+    /// Reverberation time in seconds
+    pub rt60: f32,
+    /// Maximum number of reflections of an image source
+    pub reflection_order: usize,
+    /// Level of the late reverberation in dB relative to the direct sound
+    pub tail_level: f32,
+    pub modes: Vec<Mode>,
+    /// The same seed results in the same noise tail.
+    pub seed: u64,
+}
+
+/// A room resonance, that rings with the reverberation time of the room.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mode {
+    /// Frequency in Hz
+    pub frequency: f32,
+    /// Level in dB relative to the late reverberation
+    pub level: f32,
+}
+
+impl Room {
+    pub fn new(dimensions: [f32; 3], source: [f32; 3], listener: [f32; 3], rt60: f32) -> Self {
+        Self {
+            dimensions,
+            source,
+            listener,
+            rt60,
+            reflection_order: 3,
+            tail_level: -20.0,
+            modes: axial_modes(dimensions, 120.0),
+            seed: 0,
+        }
+    }
+
+    pub fn set_listener(mut self, listener: [f32; 3]) -> Self {
+        self.listener = listener;
+        self
+    }
+
+    pub fn set_modes(mut self, modes: Vec<Mode>) -> Self {
+        self.modes = modes;
+        self
+    }
+
+    pub fn set_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// The impulse response, normalized to a direct sound of 1. It lasts
+    /// until the reverberation decayed by about 70 dB.
+    pub fn impulse_response(&self, sample_rate: u32) -> Vec<f32> {
+        let sample_rate = sample_rate as f32;
+        let direct = distance(self.source, self.listener).max(0.1);
+        let direct_time = direct / SPEED_OF_SOUND;
+
+        let len = (sample_rate * (direct_time + 1.2 * self.rt60)).ceil() as usize;
+        let mut impulse_response = vec![0.0; len];
+
+        let reflection = self.reflection_coefficient();
+        for (position, reflections) in self.image_sources() {
+            let distance = distance(position, self.listener).max(0.1);
+            let delay = (distance / SPEED_OF_SOUND * sample_rate).round() as usize;
+
+            if let Some(sample) = impulse_response.get_mut(delay) {
+                *sample += reflection.powi(reflections) * direct / distance;
+            }
+        }
+
+        // the late reverberation builds up until the mixing time
+        let mixing_time = self.volume().sqrt() / 1000.0;
+        let tail_gain = 10f32.powf(self.tail_level / 20.0);
+        let tail = WhiteNoise::with_seed(3f32.sqrt() * tail_gain, self.seed);
+
+        for (n, (sample, noise)) in impulse_response.iter_mut().zip(tail).enumerate() {
+            let t = n as f32 / sample_rate - direct_time;
+            if t < 0.0 {
+                continue;
+            }
+
+            let build_up = (t / mixing_time).min(1.0);
+            let decay = self.decay(t);
+
+            *sample += noise * build_up * decay;
+
+            for mode in &self.modes {
+                let gain = tail_gain * 10f32.powf(mode.level / 20.0);
+                *sample += gain * decay * (2.0 * PI * mode.frequency * t).sin();
+            }
+        }
+
+        impulse_response
+    }
+
+    /// Plays `stimulus` in the room and returns what the microphone records.
+    pub fn record(&self, stimulus: &[f32], sample_rate: u32) -> Vec<f32> {
+        convolve(stimulus, &self.impulse_response(sample_rate))
+    }
+
+    fn volume(&self) -> f32 {
+        self.dimensions.iter().product()
+    }
+
+    /// Amplitude of the envelope at `t` seconds after the direct sound.
+    fn decay(&self, t: f32) -> f32 {
+        10f32.powf(-3.0 * t / self.rt60)
+    }
+
+    /// The pressure reflection coefficient of the walls, that results in the
+    /// reverberation time by Sabine's formula.
+    fn reflection_coefficient(&self) -> f32 {
+        let [width, depth, height] = self.dimensions;
+        let surface = 2.0 * (width * depth + width * height + depth * height);
+
+        let absorption = 0.161 * self.volume() / (surface * self.rt60);
+
+        (1.0 - absorption.clamp(0.0, 1.0)).sqrt()
+    }
+
+    /// Positions of the mirrored sources and the number of reflections of
+    /// their paths, including the direct sound.
+    fn image_sources(&self) -> Vec<([f32; 3], i32)> {
+        let order = self.reflection_order as i32;
+
+        // mirrored positions and number of reflections per axis
+        let axis = |i: usize| {
+            let length = self.dimensions[i];
+            let source = self.source[i];
+
+            (-order..=order)
+                .flat_map(|m| [(m, 0), (m, 1)])
+                .map(move |(m, q)| {
+                    let position = (1 - 2 * q) as f32 * source + 2.0 * m as f32 * length;
+                    let reflections = (m - q).abs() + m.abs();
+
+                    (position, reflections)
+                })
+                .filter(move |(_, reflections)| *reflections <= order)
+                .collect::<Vec<_>>()
+        };
+
+        let (xs, ys, zs) = (axis(0), axis(1), axis(2));
+
+        let mut sources = vec![];
+        for &(x, rx) in &xs {
+            for &(y, ry) in &ys {
+                for &(z, rz) in &zs {
+                    let reflections = rx + ry + rz;
+
+                    if reflections <= order {
+                        sources.push(([x, y, z], reflections));
+                    }
+                }
+            }
+        }
+
+        sources
+    }
+}
+
+impl Default for Room {
+    /// A living room with a loudspeaker in the front left corner.
+    fn default() -> Self {
+        Self::new([5.0, 4.0, 2.5], [1.0, 0.5, 1.0], [2.5, 2.8, 1.2], 0.45)
+    }
+}
+
+/// Modes between opposite walls up to `max_frequency`, the lower ones are
+/// louder. They stick out by a few dB of the late reverberation.
+pub fn axial_modes(dimensions: [f32; 3], max_frequency: f32) -> Vec<Mode> {
+    let mut modes: Vec<_> = dimensions
+        .into_iter()
+        .flat_map(|length| {
+            let fundamental = SPEED_OF_SOUND / (2.0 * length);
+
+            (1..)
+                .map(move |n| n as f32 * fundamental)
+                .take_while(move |frequency| *frequency <= max_frequency)
+                .enumerate()
+                .map(|(i, frequency)| Mode {
+                    frequency,
+                    level: -30.0 - 6.0 * i as f32,
+                })
+        })
+        .collect();
+
+    modes.sort_by(|a, b| a.frequency.total_cmp(&b.frequency));
+
+    modes
+}
+
+fn distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| (a - b).powi(2))
+        .sum::<f32>()
+        .sqrt()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::decay::DecayTimes;
+
+    #[test]
+    fn decays_with_reverberation_time() {
+        let sample_rate = 16_000;
+
+        for rt60 in [0.3, 0.6, 1.2] {
+            let room = Room {
+                rt60,
+                ..Room::default()
+            };
+
+            let times = DecayTimes::new(&room.impulse_response(sample_rate), sample_rate);
+            let t30 = times.t30.unwrap();
+
+            assert!(
+                (t30 - rt60).abs() < 0.1 * rt60,
+                "T30 of {t30} s instead of {rt60} s"
+            );
+        }
+    }
+
+    #[test]
+    fn direct_sound_arrives_first() {
+        let sample_rate = 48_000;
+        let room = Room::default().set_modes(vec![]);
+
+        let impulse_response = room.impulse_response(sample_rate);
+
+        let delay = distance(room.source, room.listener) / SPEED_OF_SOUND;
+        let first = impulse_response.iter().position(|s| *s != 0.0).unwrap();
+
+        assert_eq!(first, (delay * sample_rate as f32).round() as usize);
+        assert!((impulse_response[first] - 1.0).abs() < 1e-3);
+    }
+}
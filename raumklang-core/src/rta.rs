@@ -0,0 +1,125 @@
+use rustfft::{num_complex::Complex32, Fft, FftPlanner};
+
+use crate::{bands, Window, WindowBuilder};
+
+use std::sync::Arc;
+
+/// Real-time analyzer, computes an averaged power spectrum with peak hold
+/// from a continuous input stream.
+pub struct Rta {
+    fft_size: usize,
+    averages: usize,
+    frames: usize,
+
+    fft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+    scale: f32,
+
+    buf: Vec<f32>,
+
+    power: Vec<f32>,
+    peak: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BandLevel {
+    pub band: bands::Band,
+    /// Level in dBFS, where a full scale sine reads 0 dB
+    pub level: f32,
+    pub peak: f32,
+}
+
+impl Rta {
+    pub fn new(fft_size: usize, averages: usize) -> Self {
+        let fft = FftPlanner::new().plan_fft_forward(fft_size);
+
+        let half = fft_size / 2;
+        let window = WindowBuilder::new(Window::Hann, half, Window::Hann, fft_size - half).build();
+
+        // normalize to sine amplitude and correct for the equivalent noise
+        // bandwidth of the window, so that energy sums over bands are right
+        let sum: f32 = window.iter().sum();
+        let square_sum: f32 = window.iter().map(|w| w * w).sum();
+        let enbw = fft_size as f32 * square_sum / (sum * sum);
+        let scale = (2.0 / sum).powi(2) / enbw;
+
+        let bins = half + 1;
+
+        Self {
+            fft_size,
+            averages: averages.max(1),
+            frames: 0,
+
+            fft,
+            window,
+            scale,
+
+            buf: Vec::with_capacity(fft_size * 2),
+
+            power: vec![0.0; bins],
+            peak: vec![0.0; bins],
+        }
+    }
+
+    pub fn push(&mut self, data: &[f32]) {
+        self.buf.extend_from_slice(data);
+
+        let hop_size = self.fft_size / 2;
+        while self.buf.len() >= self.fft_size {
+            self.process_frame();
+            self.buf.drain(..hop_size);
+        }
+    }
+
+    /// Averaged power spectrum, bin `i` is at `i * frequency_resolution`.
+    pub fn power(&self) -> &[f32] {
+        &self.power
+    }
+
+    pub fn reset_peak(&mut self) {
+        self.peak.copy_from_slice(&self.power);
+    }
+
+    pub fn frequency_resolution(&self, sample_rate: u32) -> f32 {
+        sample_rate as f32 / self.fft_size as f32
+    }
+
+    pub fn bands(&self, fraction: u8, sample_rate: u32) -> Vec<BandLevel> {
+        let resolution = self.frequency_resolution(sample_rate);
+        let nyquist = sample_rate as f32 / 2.0;
+
+        bands::fractional_octave(fraction, 17.0, 22_000f32.min(nyquist))
+            .into_iter()
+            .map(|band| BandLevel {
+                band,
+                level: power_to_db(bands::energy_sum(&self.power, resolution, &band)),
+                peak: power_to_db(bands::energy_sum(&self.peak, resolution, &band)),
+            })
+            .collect()
+    }
+
+    fn process_frame(&mut self) {
+        let mut buf: Vec<_> = self.buf[..self.fft_size]
+            .iter()
+            .zip(self.window.iter())
+            .map(|(s, w)| Complex32::from(s * w))
+            .collect();
+
+        self.fft.process(&mut buf);
+
+        self.frames += 1;
+        let alpha = 1.0 / self.frames.min(self.averages) as f32;
+
+        let bins = self.power.len();
+        for (i, s) in buf.into_iter().take(bins).enumerate() {
+            let power = s.norm_sqr() * self.scale;
+
+            self.power[i] += (power - self.power[i]) * alpha;
+            self.peak[i] = self.peak[i].max(self.power[i]);
+        }
+    }
+}
+
+fn power_to_db(power: f32) -> f32 {
+    10.0 * f32::log10(power)
+}
@@ -1,4 +1,7 @@
-use rand::{distributions, distributions::Distribution, rngs, SeedableRng};
+use rand::{distributions, distributions::Distribution, rngs, Rng, SeedableRng};
+use rustfft::{num_complex::Complex32, FftPlanner};
+
+use std::f32::consts::PI;
 
 #[derive(Debug, Clone)]
 pub struct WhiteNoise {
@@ -14,6 +17,14 @@ impl WhiteNoise {
         }
     }
 
+    /// Noise, that is the same for every `seed`, e.g. for reproducible tests.
+    pub fn with_seed(amplitude: f32, seed: u64) -> Self {
+        WhiteNoise {
+            rng: rngs::SmallRng::seed_from_u64(seed),
+            distribution: distributions::Uniform::new_inclusive(-amplitude, amplitude),
+        }
+    }
+
     pub fn take_duration(self, sample_rate: usize, duration: usize) -> std::iter::Take<WhiteNoise> {
         self.into_iter().take(sample_rate * duration)
     }
@@ -46,8 +57,15 @@ pub struct PinkNoise {
 
 impl PinkNoise {
     pub fn with_amplitude(amplitude: f32) -> Self {
-        let white_noise = WhiteNoise::with_amplitude(amplitude);
+        Self::from_white_noise(WhiteNoise::with_amplitude(amplitude))
+    }
+
+    /// Noise, that is the same for every `seed`, e.g. for reproducible tests.
+    pub fn with_seed(amplitude: f32, seed: u64) -> Self {
+        Self::from_white_noise(WhiteNoise::with_seed(amplitude, seed))
+    }
 
+    fn from_white_noise(white_noise: WhiteNoise) -> Self {
         PinkNoise {
             b0: 0f32,
             b1: 0f32,
@@ -80,3 +98,60 @@ impl Iterator for PinkNoise {
 }
 
 impl ExactSizeIterator for PinkNoise {}
+
+/// Pink noise, that repeats after a period. Every frequency bin of a period
+/// has energy, so that a single period, analyzed with a rectangular window,
+/// shows no leakage.
+#[derive(Debug, Clone)]
+pub struct PeriodicPinkNoise {
+    period: Vec<f32>,
+    index: usize,
+}
+
+impl PeriodicPinkNoise {
+    /// The phases of the bins are random, the same `seed` results in the
+    /// same noise.
+    pub fn new(period: usize, amplitude: f32, seed: u64) -> Self {
+        let mut rng = rngs::SmallRng::seed_from_u64(seed);
+
+        // falls with 3 dB per octave, without DC and nyquist
+        let mut spectrum = vec![Complex32::default(); period];
+        for k in 1..period.div_ceil(2) {
+            let magnitude = 1.0 / (k as f32).sqrt();
+            let phase = rng.gen_range(0.0..2.0 * PI);
+
+            spectrum[k] = Complex32::from_polar(magnitude, phase);
+            spectrum[period - k] = spectrum[k].conj();
+        }
+
+        FftPlanner::new()
+            .plan_fft_inverse(period)
+            .process(&mut spectrum);
+
+        let peak = spectrum
+            .iter()
+            .map(|s| s.re.abs())
+            .fold(0.0, f32::max)
+            .max(f32::EPSILON);
+
+        Self {
+            period: spectrum.iter().map(|s| s.re / peak * amplitude).collect(),
+            index: 0,
+        }
+    }
+
+    pub fn period(&self) -> &[f32] {
+        &self.period
+    }
+}
+
+impl Iterator for PeriodicPinkNoise {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let sample = self.period.get(self.index).copied()?;
+        self.index = (self.index + 1) % self.period.len();
+
+        Some(sample)
+    }
+}
@@ -49,10 +49,6 @@ impl Iterator for LinearSineSweep {
         self.frequency += self.delta_frequency;
         let delta_phase = 2.0 * std::f32::consts::PI * self.frequency / self.sample_rate as f32;
         self.phase = (self.phase + delta_phase) % (2.0 * std::f32::consts::PI);
-        //let frequency = f32::exp(
-        //    f32::ln(self.start_frequency) * (1.0 - self.sample_index / self.duration)
-        //        + f32::ln(self.end_frequency) * (self.sample_index / self.duration),
-        //);
 
         self.sample_index += 1;
 
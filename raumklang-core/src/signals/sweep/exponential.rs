@@ -1,3 +1,5 @@
+/// Exponential (logarithmic) sine sweep after Farina, its frequency doubles
+/// in equal time steps.
 #[derive(Debug, Clone)]
 pub struct ExponentialSweep {
     sample_index: usize,
@@ -25,6 +27,60 @@ impl ExponentialSweep {
             amplitude,
         }
     }
+
+    /// Time in seconds, in which the frequency rises by the factor `e`.
+    fn rate(&self) -> f32 {
+        let duration = self.n_samples as f32 / self.sample_rate as f32;
+        duration / (self.end_frequency / self.start_frequency).ln()
+    }
+
+    /// The time reversed sweep, whose amplitude falls with 6 dB/octave as
+    /// its frequency falls. Convolved with a recording of the sweep, it
+    /// results in the impulse response, without the need of a loopback.
+    ///
+    /// It is normalized to unity gain at the center of the sweep range.
+    pub fn inverse_filter(&self) -> Vec<f32> {
+        let sweep: Vec<f32> = Self {
+            sample_index: 0,
+            ..self.clone()
+        }
+        .collect();
+
+        let rate = self.rate();
+        let mut inverse: Vec<f32> = sweep
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(n, s)| s * (n as f32 / self.sample_rate as f32 / rate).exp())
+            .collect();
+
+        let center = (self.start_frequency * self.end_frequency).sqrt();
+        let gain = response_at(&sweep, center, self.sample_rate)
+            * response_at(&inverse, center, self.sample_rate);
+
+        if gain > 0.0 {
+            inverse.iter_mut().for_each(|s| *s /= gain);
+        }
+
+        inverse
+    }
+}
+
+/// Magnitude of the spectrum of `signal` at `frequency`.
+fn response_at(signal: &[f32], frequency: f32, sample_rate: usize) -> f32 {
+    let omega = 2.0 * std::f64::consts::PI * f64::from(frequency) / sample_rate as f64;
+
+    let (re, im) = signal
+        .iter()
+        .enumerate()
+        .fold((0.0, 0.0), |(re, im), (n, s)| {
+            let phase = omega * n as f64;
+            let s = f64::from(*s);
+
+            (re + s * phase.cos(), im - s * phase.sin())
+        });
+
+    (re * re + im * im).sqrt() as f32
 }
 
 impl Iterator for ExponentialSweep {
@@ -34,8 +90,7 @@ impl Iterator for ExponentialSweep {
         use std::f32::consts::PI;
 
         if self.sample_index < self.n_samples {
-            let c = (self.end_frequency / self.start_frequency).ln();
-            let l = self.n_samples as f32 / self.sample_rate as f32 / c;
+            let l = self.rate();
 
             let t = self.sample_index as f32 / self.sample_rate as f32;
             let s = 2.0 * PI * self.start_frequency * l * (f32::exp(t / l) - 1.0);
@@ -48,16 +103,11 @@ impl Iterator for ExponentialSweep {
             None
         }
     }
-}
 
-impl ExactSizeIterator for ExponentialSweep {
-    fn len(&self) -> usize {
-        let (lower, upper) = (0, Some(self.n_samples));
-        // Note: This assertion is overly defensive, but it checks the invariant
-        // guaranteed by the trait. If this trait were rust-internal,
-        // we could use debug_assert!; assert_eq! will check all Rust user
-        // implementations too.
-        std::assert_eq!(upper, Some(lower));
-        lower
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.n_samples - self.sample_index;
+        (len, Some(len))
     }
 }
+
+impl ExactSizeIterator for ExponentialSweep {}
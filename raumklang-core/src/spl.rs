@@ -0,0 +1,289 @@
+use rustfft::{num_complex::Complex32, Fft, FftPlanner};
+
+use crate::{Window, WindowBuilder};
+
+use std::{fmt, sync::Arc, time::Duration};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weighting {
+    A,
+    C,
+    Z,
+}
+
+impl Weighting {
+    pub const ALL: [Weighting; 3] = [Weighting::A, Weighting::C, Weighting::Z];
+
+    /// Power gain of the weighting curve at `frequency` according to IEC 61672.
+    pub fn power_gain(&self, frequency: f32) -> f32 {
+        const F1: f32 = 20.598_997;
+        const F2: f32 = 107.652_65;
+        const F3: f32 = 737.862_2;
+        const F4: f32 = 12_194.217;
+
+        let f2 = frequency * frequency;
+
+        let amplitude = match self {
+            Weighting::A => {
+                let r = F4 * F4 * f2 * f2
+                    / ((f2 + F1 * F1)
+                        * f32::sqrt((f2 + F2 * F2) * (f2 + F3 * F3))
+                        * (f2 + F4 * F4));
+
+                // +2.00 dB normalizes the gain at 1 kHz to 0 dB
+                r * 1.258_925_4
+            }
+            Weighting::C => {
+                let r = F4 * F4 * f2 / ((f2 + F1 * F1) * (f2 + F4 * F4));
+
+                // +0.06 dB normalizes the gain at 1 kHz to 0 dB
+                r * 1.006_931_7
+            }
+            Weighting::Z => 1.0,
+        };
+
+        amplitude * amplitude
+    }
+}
+
+impl fmt::Display for Weighting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Weighting::A => "dB(A)",
+            Weighting::C => "dB(C)",
+            Weighting::Z => "dB(Z)",
+        };
+
+        write!(f, "{s}")
+    }
+}
+
+/// Time weighting of the levels, the blocks the levels are computed of span
+/// the time constant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeWeighting {
+    Fast,
+    Slow,
+}
+
+impl TimeWeighting {
+    pub const ALL: [TimeWeighting; 2] = [TimeWeighting::Fast, TimeWeighting::Slow];
+
+    /// Time constant according to IEC 61672.
+    pub fn time_constant(&self) -> Duration {
+        match self {
+            TimeWeighting::Fast => Duration::from_millis(125),
+            TimeWeighting::Slow => Duration::from_secs(1),
+        }
+    }
+
+    /// Number of samples that span the time constant, rounded to an even
+    /// number for the symmetric window.
+    fn block_size(&self, sample_rate: u32) -> usize {
+        let samples = (self.time_constant().as_secs_f32() * sample_rate as f32).round() as usize;
+
+        (samples / 2 * 2).max(2)
+    }
+}
+
+impl fmt::Display for TimeWeighting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            TimeWeighting::Fast => "fast",
+            TimeWeighting::Slow => "slow",
+        };
+
+        write!(f, "{s}")
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Record {
+    pub leq: f32,
+    pub lmax: f32,
+    pub l10: f32,
+    pub l50: f32,
+    pub l90: f32,
+}
+
+/// Logs weighted sound pressure levels over fixed intervals.
+///
+/// Levels are computed block wise in the frequency domain, `calibration` is
+/// the sound pressure level in dB that corresponds to 0 dBFS.
+pub struct Logger {
+    block_size: usize,
+    blocks_per_interval: usize,
+    calibration: f32,
+
+    fft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+    gains: Vec<f32>,
+
+    buf: Vec<f32>,
+    block_levels: Vec<f32>,
+    mean_square_sum: f32,
+}
+
+impl Logger {
+    pub fn new(
+        weighting: Weighting,
+        time_weighting: TimeWeighting,
+        calibration: f32,
+        interval: Duration,
+        sample_rate: u32,
+    ) -> Self {
+        let block_size = time_weighting.block_size(sample_rate);
+
+        let fft = FftPlanner::new().plan_fft_forward(block_size);
+
+        let half = block_size / 2;
+        let window = WindowBuilder::new(Window::Hann, half, Window::Hann, half).build();
+
+        let resolution = sample_rate as f32 / block_size as f32;
+        let gains = (0..=half)
+            .map(|i| weighting.power_gain(i as f32 * resolution))
+            .collect();
+
+        let blocks_per_interval =
+            (interval.as_secs_f32() * sample_rate as f32 / block_size as f32).round() as usize;
+
+        Self {
+            block_size,
+            blocks_per_interval: blocks_per_interval.max(1),
+            calibration,
+
+            fft,
+            window,
+            gains,
+
+            buf: Vec::with_capacity(block_size * 2),
+            block_levels: Vec::new(),
+            mean_square_sum: 0.0,
+        }
+    }
+
+    /// Feeds new samples into the logger and returns all intervals that
+    /// have been completed by them.
+    pub fn push(&mut self, data: &[f32]) -> Vec<Record> {
+        self.buf.extend_from_slice(data);
+
+        let mut records = vec![];
+        while self.buf.len() >= self.block_size {
+            let mean_square = self.weighted_mean_square();
+            self.buf.drain(..self.block_size);
+
+            self.mean_square_sum += mean_square;
+            self.block_levels.push(self.level(mean_square));
+
+            if self.block_levels.len() >= self.blocks_per_interval {
+                records.push(self.finish_interval());
+            }
+        }
+
+        records
+    }
+
+    fn finish_interval(&mut self) -> Record {
+        let count = self.block_levels.len();
+        let leq = self.level(self.mean_square_sum / count as f32);
+
+        let mut levels = std::mem::take(&mut self.block_levels);
+        levels.sort_by(f32::total_cmp);
+
+        // L_N is the level exceeded N percent of the time
+        let exceeded = |percent: usize| {
+            let index = (count * (100 - percent) / 100).min(count - 1);
+            levels[index]
+        };
+
+        let record = Record {
+            leq,
+            lmax: levels[count - 1],
+            l10: exceeded(10),
+            l50: exceeded(50),
+            l90: exceeded(90),
+        };
+
+        self.mean_square_sum = 0.0;
+
+        record
+    }
+
+    fn weighted_mean_square(&self) -> f32 {
+        let mut buf: Vec<_> = self.buf[..self.block_size]
+            .iter()
+            .zip(self.window.iter())
+            .map(|(s, w)| Complex32::from(s * w))
+            .collect();
+
+        self.fft.process(&mut buf);
+
+        // one-sided spectrum, Parseval's theorem with window power correction
+        let window_power: f32 = self.window.iter().map(|w| w * w).sum();
+        let power: f32 = buf
+            .iter()
+            .zip(self.gains.iter())
+            .enumerate()
+            .map(|(i, (s, g))| {
+                let factor = if i == 0 || i == self.block_size / 2 {
+                    1.0
+                } else {
+                    2.0
+                };
+
+                factor * s.norm_sqr() * g
+            })
+            .sum();
+
+        power / (self.block_size as f32 * window_power)
+    }
+
+    fn level(&self, mean_square: f32) -> f32 {
+        10.0 * f32::log10(mean_square) + self.calibration
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn weightings_are_normalized_at_1khz() {
+        for weighting in Weighting::ALL {
+            let gain = 10.0 * weighting.power_gain(1000.0).log10();
+            assert!(gain.abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn a_weighting_at_100hz() {
+        let gain = 10.0 * Weighting::A.power_gain(100.0).log10();
+        assert!((gain + 19.1).abs() < 0.1);
+    }
+
+    #[test]
+    fn blocks_span_the_time_constant() {
+        for sample_rate in [44_100, 48_000, 96_000] {
+            let mut logger = Logger::new(
+                Weighting::Z,
+                TimeWeighting::Fast,
+                0.0,
+                Duration::from_secs(1),
+                sample_rate,
+            );
+
+            let sine: Vec<_> = (0..sample_rate)
+                .map(|i| {
+                    let t = i as f32 / sample_rate as f32;
+                    f32::sin(2.0 * std::f32::consts::PI * 1000.0 * t)
+                })
+                .collect();
+
+            let records = logger.push(&sine);
+
+            // 8 blocks of 125 ms make up the interval at every sample rate
+            assert_eq!(records.len(), 1);
+            assert!((records[0].leq + 3.01).abs() < 0.1);
+        }
+    }
+}
@@ -0,0 +1,250 @@
+//! Items addressed by stable, typed identifiers, instead of their position
+//! in a list, which changes when other items are removed.
+
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    sync::atomic::{self, AtomicUsize},
+};
+
+/// Identifier of an item of type `T`. Identifiers are unique within the
+/// process, so that they can be assigned before an item is inserted.
+pub struct Id<T> {
+    value: usize,
+    _item: PhantomData<fn() -> T>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change<T> {
+    Inserted(Id<T>),
+    Updated(Id<T>),
+    Removed(Id<T>),
+}
+
+/// Items in insertion order.
+#[derive(Debug, Clone)]
+pub struct Store<T> {
+    items: Vec<(Id<T>, T)>,
+    /// Only recorded for tracked stores, see [`Store::tracked`].
+    changes: Option<Vec<Change<T>>>,
+}
+
+impl<T> Id<T> {
+    pub fn unique() -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+        Self {
+            value: NEXT.fetch_add(1, atomic::Ordering::Relaxed),
+            _item: PhantomData,
+        }
+    }
+}
+
+impl<T> Store<T> {
+    pub fn new() -> Self {
+        Self {
+            items: vec![],
+            changes: None,
+        }
+    }
+
+    /// A store, that records its changes until they are taken with
+    /// [`Store::take_changes`].
+    pub fn tracked() -> Self {
+        Self {
+            items: vec![],
+            changes: Some(vec![]),
+        }
+    }
+
+    pub fn insert(&mut self, item: T) -> Id<T> {
+        let id = Id::unique();
+        self.insert_with_id(id, item);
+
+        id
+    }
+
+    /// Inserts `item` with an existing identifier, replacing the previous
+    /// item with it.
+    pub fn insert_with_id(&mut self, id: Id<T>, item: T) -> Option<T> {
+        match self.position(id) {
+            Some(i) => {
+                self.record(Change::Updated(id));
+                Some(std::mem::replace(&mut self.items[i].1, item))
+            }
+            None => {
+                self.record(Change::Inserted(id));
+                self.items.push((id, item));
+                None
+            }
+        }
+    }
+
+    pub fn remove(&mut self, id: Id<T>) -> Option<T> {
+        let i = self.position(id)?;
+        self.record(Change::Removed(id));
+
+        Some(self.items.remove(i).1)
+    }
+
+    pub fn get(&self, id: Id<T>) -> Option<&T> {
+        self.items
+            .iter()
+            .find(|(item_id, _)| *item_id == id)
+            .map(|(_, item)| item)
+    }
+
+    /// Mutable access to an item, which is recorded as update.
+    pub fn get_mut(&mut self, id: Id<T>) -> Option<&mut T> {
+        let i = self.position(id)?;
+        self.record(Change::Updated(id));
+
+        Some(&mut self.items[i].1)
+    }
+
+    pub fn contains(&self, id: Id<T>) -> bool {
+        self.position(id).is_some()
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (Id<T>, &T)> + Clone {
+        self.items.iter().map(|(id, item)| (*id, item))
+    }
+
+    pub fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = (Id<T>, &mut T)> {
+        self.items.iter_mut().map(|(id, item)| (*id, item))
+    }
+
+    pub fn ids(&self) -> impl DoubleEndedIterator<Item = Id<T>> + Clone + '_ {
+        self.items.iter().map(|(id, _)| *id)
+    }
+
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &T> + Clone {
+        self.items.iter().map(|(_, item)| item)
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Changes since the last call, empty for untracked stores.
+    pub fn take_changes(&mut self) -> Vec<Change<T>> {
+        self.changes
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    fn position(&self, id: Id<T>) -> Option<usize> {
+        self.items.iter().position(|(item_id, _)| *item_id == id)
+    }
+
+    fn record(&mut self, change: Change<T>) {
+        if let Some(changes) = self.changes.as_mut() {
+            changes.push(change);
+        }
+    }
+}
+
+impl<T> Default for Store<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<T> for Store<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut store = Self::new();
+        for item in iter {
+            store.insert(item);
+        }
+
+        store
+    }
+}
+
+// The traits are implemented by hand, as deriving them would require `T`
+// to implement them, too.
+impl<T> Clone for Id<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Id<T> {}
+
+impl<T> PartialEq for Id<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T> Eq for Id<T> {}
+
+impl<T> PartialOrd for Id<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Id<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.value.cmp(&other.value)
+    }
+}
+
+impl<T> Hash for Id<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for Id<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Id({})", self.value)
+    }
+}
+
+impl<T> fmt::Display for Id<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ids_stay_valid_after_removal() {
+        let mut store = Store::new();
+        let a = store.insert("a");
+        let b = store.insert("b");
+        let c = store.insert("c");
+
+        assert_eq!(store.remove(a), Some("a"));
+
+        assert_eq!(store.get(a), None);
+        assert_eq!(store.get(b), Some(&"b"));
+        assert_eq!(store.get(c), Some(&"c"));
+        assert_eq!(store.ids().collect::<Vec<_>>(), [b, c]);
+    }
+
+    #[test]
+    fn tracked_store_records_changes() {
+        let mut store = Store::tracked();
+        let a = store.insert(1);
+        *store.get_mut(a).unwrap() += 1;
+        store.remove(a);
+
+        assert_eq!(
+            store.take_changes(),
+            [Change::Inserted(a), Change::Updated(a), Change::Removed(a)]
+        );
+        assert!(store.take_changes().is_empty());
+    }
+}
@@ -0,0 +1,69 @@
+//! Named measurement templates, e.g. "Room sweep 20-20k, 30 s, -18 dB",
+//! that are shared by the GUI and the CLI, so that both front-ends measure
+//! with the same sweep.
+
+use std::fmt;
+
+use crate::{amplitude_to_volume, db_to_gain};
+
+/// Name of the file in the configuration directory, that holds a list of
+/// templates.
+pub const FILE_NAME: &str = "measurement_templates.json";
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Template {
+    pub name: String,
+    /// Start frequency of the logarithmic sweep in Hz
+    pub start_frequency: u16,
+    /// End frequency of the logarithmic sweep in Hz
+    pub end_frequency: u16,
+    /// Duration of the sweep in seconds
+    pub duration: f32,
+    /// Playback level in dBFS
+    pub level: f32,
+}
+
+impl Template {
+    /// Playback volume in the range of 0.0 to 1.0, that plays the sweep at
+    /// the level of the template.
+    pub fn volume(&self) -> f32 {
+        amplitude_to_volume(db_to_gain(self.level))
+    }
+}
+
+/// Finds the template with `name`, ignoring the case.
+pub fn find<'a>(templates: &'a [Template], name: &str) -> Option<&'a Template> {
+    templates
+        .iter()
+        .find(|template| template.name.eq_ignore_ascii_case(name.trim()))
+}
+
+impl fmt::Display for Template {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::{dbfs, volume_to_amplitude};
+
+    #[test]
+    fn volume_plays_the_sweep_at_the_level() {
+        for level in [-60.0, -18.0, -6.0, 0.0] {
+            let template = Template {
+                name: "Room sweep".to_string(),
+                start_frequency: 20,
+                end_frequency: 20_000,
+                duration: 30.0,
+                level,
+            };
+
+            let played = dbfs(volume_to_amplitude(template.volume()));
+            assert!((played - level).abs() < 0.01, "{played} dBFS for {level}");
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//! Fixtures and golden files of the DSP tests.
+//!
+//! The golden files in `testdata` are compared with the output of the
+//! analyses, running the tests with `UPDATE_GOLDEN=1` rewrites them instead.
+
+use std::{
+    f32::consts::PI,
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+use rand::{rngs, Rng, SeedableRng};
+
+use crate::{convolution::convolve, decay::DecayTimes, signals::WhiteNoise, FrequencyResponse};
+
+/// Number of random cases of the property tests.
+pub const CASES: u64 = 8;
+
+/// A random impulse response of `len` samples: a delayed direct sound, some
+/// reflections and an exponentially decaying noise tail.
+pub fn random_impulse_response(seed: u64, len: usize) -> Vec<f32> {
+    let mut rng = rngs::SmallRng::seed_from_u64(seed);
+
+    let delay = rng.gen_range(0..len / 4);
+    let decay = rng.gen_range(len as f32 / 20.0..len as f32 / 5.0);
+
+    let mut impulse_response: Vec<f32> = WhiteNoise::with_seed(0.05, seed)
+        .take(len)
+        .enumerate()
+        .map(|(n, s)| match n.checked_sub(delay) {
+            Some(n) => s * (-(n as f32) / decay).exp(),
+            None => 0.0,
+        })
+        .collect();
+
+    impulse_response[delay] += rng.gen_range(0.5f32..1.0);
+    for _ in 0..3 {
+        let position = rng.gen_range(delay + 1..len);
+        impulse_response[position] += rng.gen_range(-0.5f32..0.5);
+    }
+
+    impulse_response
+}
+
+/// Plays `stimulus` through `impulse_response` and adds white noise with
+/// the peak `noise`.
+pub fn record(stimulus: &[f32], impulse_response: &[f32], noise: f32, seed: u64) -> Vec<f32> {
+    convolve(stimulus, impulse_response)
+        .into_iter()
+        .zip(WhiteNoise::with_seed(noise, seed.wrapping_add(1)))
+        .map(|(s, n)| s + n)
+        .collect()
+}
+
+/// Energy of the difference relative to the energy of `expected` in dB,
+/// the shorter signal is padded with zeros.
+pub fn error_db(expected: &[f32], actual: &[f32]) -> f32 {
+    let len = expected.len().max(actual.len());
+    let sample = |signal: &[f32], i: usize| signal.get(i).copied().unwrap_or_default();
+
+    let error: f32 = (0..len)
+        .map(|i| (sample(actual, i) - sample(expected, i)).powi(2))
+        .sum();
+    let energy: f32 = expected.iter().map(|s| s.powi(2)).sum();
+
+    10.0 * (error / energy).log10()
+}
+
+/// Direct sound with a reflection, that adds a comb filter, and a decaying
+/// resonance at 1 kHz.
+pub fn comb_impulse_response(sample_rate: u32, len: usize) -> Vec<f32> {
+    let mut impulse_response: Vec<f32> = (0..len)
+        .map(|n| {
+            let n = n as f32;
+            0.1 * (-n / 200.0).exp() * (2.0 * PI * 1000.0 * n / sample_rate as f32).sin()
+        })
+        .collect();
+
+    impulse_response[10] += 1.0;
+    impulse_response[58] -= 0.5;
+
+    impulse_response
+}
+
+/// Decay with the reverberation time `rt60` in seconds of a few partials.
+pub fn exponential_decay(rt60: f32, sample_rate: u32, len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|n| n as f32 / sample_rate as f32)
+        .map(|t| {
+            let partials = [523.0, 1370.0, 3117.0]
+                .into_iter()
+                .map(|f| (2.0 * PI * f * t).sin())
+                .sum::<f32>();
+
+            10f32.powf(-3.0 * t / rt60) * partials / 3.0
+        })
+        .collect()
+}
+
+/// Compares magnitude (±0.01 dB) and phase (±0.1°) of `response`, computed
+/// with an FFT of `fft_len`, at the frequencies of the golden FRD file.
+pub fn assert_golden_frd(name: &str, response: &FrequencyResponse, fft_len: usize) {
+    let resolution = response.sample_rate as f32 / fft_len as f32;
+
+    let point = |bin: usize| {
+        let value = response.data[bin];
+        (
+            bin as f32 * resolution,
+            20.0 * value.norm().log10(),
+            value.arg().to_degrees(),
+        )
+    };
+
+    if update_golden() {
+        // sixth octaves from 20 Hz
+        let mut bins: Vec<_> = (0..)
+            .map(|i| 20.0 * 2f32.powf(i as f32 / 6.0))
+            .take_while(|f| *f < response.sample_rate as f32 / 2.0)
+            .map(|f| (f / resolution).round() as usize)
+            .filter(|bin| (1..response.data.len()).contains(bin))
+            .collect();
+        bins.dedup();
+
+        let mut content = String::from("* frequency [Hz], magnitude [dB], phase [°]\n");
+        for (frequency, magnitude, phase) in bins.into_iter().map(point) {
+            writeln!(content, "{frequency:.3} {magnitude:.4} {phase:.3}").unwrap();
+        }
+
+        std::fs::write(golden_path(name), content).unwrap();
+        return;
+    }
+
+    let content = std::fs::read_to_string(golden_path(name)).unwrap();
+    let golden = content
+        .lines()
+        .filter(|line| !line.starts_with('*') && !line.trim().is_empty())
+        .map(|line| {
+            let values: Vec<f32> = line
+                .split_whitespace()
+                .map(|value| value.parse().unwrap())
+                .collect();
+
+            (values[0], values[1], values[2])
+        });
+
+    for (frequency, magnitude, phase) in golden {
+        let bin = (frequency / resolution).round() as usize;
+        let (_, actual_magnitude, actual_phase) = point(bin);
+
+        let phase_error = (actual_phase - phase + 540.0).rem_euclid(360.0) - 180.0;
+
+        assert!(
+            (actual_magnitude - magnitude).abs() < 0.01,
+            "{name} at {frequency} Hz: {actual_magnitude} dB != {magnitude} dB"
+        );
+        assert!(
+            phase_error.abs() < 0.1,
+            "{name} at {frequency} Hz: {actual_phase}° != {phase}°"
+        );
+    }
+}
+
+/// Compares the decay times with the golden file (±1 ms).
+pub fn assert_golden_decay_times(name: &str, times: &DecayTimes) {
+    let actual = [("edt", times.edt), ("t20", times.t20), ("t30", times.t30)];
+
+    if update_golden() {
+        let mut content = String::new();
+        for (key, time) in actual {
+            let time = time.map_or("-".to_string(), |time| format!("{time:.4}"));
+            writeln!(content, "{key} {time}").unwrap();
+        }
+
+        std::fs::write(golden_path(name), content).unwrap();
+        return;
+    }
+
+    let content = std::fs::read_to_string(golden_path(name)).unwrap();
+    for (line, (key, time)) in content.lines().zip(actual) {
+        let expected = line.strip_prefix(key).map(str::trim);
+        let expected = expected.and_then(|value| value.parse::<f32>().ok());
+
+        match (time, expected) {
+            (Some(time), Some(expected)) => assert!(
+                (time - expected).abs() < 0.001,
+                "{name}: {key} of {time} s != {expected} s"
+            ),
+            (time, expected) => assert_eq!(time, expected, "{name}: {key}"),
+        }
+    }
+}
+
+fn update_golden() -> bool {
+    std::env::var_os("UPDATE_GOLDEN").is_some()
+}
+
+fn golden_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("testdata")
+        .join(name)
+}
@@ -0,0 +1,246 @@
+use rustfft::{num_complex::Complex32, Fft, FftPlanner};
+
+use crate::{DeconvolutionMethod, ImpulseResponse, Window, WindowBuilder};
+
+use std::sync::Arc;
+
+/// Streaming dual-channel analyzer, estimates the transfer function between a
+/// reference and a measurement channel from continuous (noise) signals.
+pub struct TransferFunction {
+    fft_size: usize,
+    hop_size: usize,
+    averages: usize,
+    frames: usize,
+
+    fft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+
+    reference: Vec<f32>,
+    measurement: Vec<f32>,
+
+    cross_spectrum: Vec<Complex32>,
+    reference_power: Vec<f32>,
+    measurement_power: Vec<f32>,
+}
+
+impl TransferFunction {
+    pub fn new(fft_size: usize, averages: usize) -> Self {
+        let fft = FftPlanner::new().plan_fft_forward(fft_size);
+
+        let half = fft_size / 2;
+        let window = WindowBuilder::new(Window::Hann, half, Window::Hann, fft_size - half).build();
+
+        let bins = half + 1;
+
+        Self {
+            fft_size,
+            hop_size: half,
+            averages: averages.max(1),
+            frames: 0,
+
+            fft,
+            window,
+
+            reference: Vec::with_capacity(fft_size * 2),
+            measurement: Vec::with_capacity(fft_size * 2),
+
+            cross_spectrum: vec![Complex32::default(); bins],
+            reference_power: vec![0.0; bins],
+            measurement_power: vec![0.0; bins],
+        }
+    }
+
+    /// Transfer function between two recorded channels, e.g. the input and
+    /// the output of an amplifier, averaged over the whole recording. The
+    /// `delay` of the response in samples is compensated.
+    pub fn from_recordings(
+        reference: &[f32],
+        response: &[f32],
+        fft_size: usize,
+        delay: usize,
+    ) -> Self {
+        let mut transfer_function = Self::new(fft_size, usize::MAX).with_delay(delay);
+        transfer_function.push(reference, response);
+        transfer_function
+    }
+
+    /// Delay of `response` relative to `reference` in samples, taken from the
+    /// peak of the impulse response between them.
+    pub fn estimate_delay(reference: &[f32], response: &[f32], sample_rate: u32) -> usize {
+        let impulse_response = ImpulseResponse::from_samples(
+            sample_rate,
+            reference.iter().copied(),
+            response.iter().copied(),
+            DeconvolutionMethod::RegularizedDivision,
+        );
+
+        let causal = &impulse_response.data[..impulse_response.data.len() / 2];
+        causal
+            .iter()
+            .map(|s| s.re.abs())
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map_or(0, |(position, _)| position)
+    }
+
+    /// Delays the reference channel by the given amount of samples, to
+    /// compensate the propagation delay of the measured system.
+    pub fn with_delay(mut self, delay: usize) -> Self {
+        self.reference.extend(std::iter::repeat_n(0.0, delay));
+        self
+    }
+
+    pub fn push(&mut self, reference: &[f32], measurement: &[f32]) {
+        self.reference.extend_from_slice(reference);
+        self.measurement.extend_from_slice(measurement);
+
+        while self.reference.len() >= self.fft_size && self.measurement.len() >= self.fft_size {
+            self.process_frame();
+
+            self.reference.drain(..self.hop_size);
+            self.measurement.drain(..self.hop_size);
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.frames = 0;
+        self.cross_spectrum.fill(Complex32::default());
+        self.reference_power.fill(0.0);
+        self.measurement_power.fill(0.0);
+    }
+
+    pub fn magnitude(&self) -> impl Iterator<Item = f32> + '_ {
+        self.transfer_function().map(|h| h.norm())
+    }
+
+    pub fn phase(&self) -> impl Iterator<Item = f32> + '_ {
+        self.transfer_function().map(|h| h.arg())
+    }
+
+    pub fn coherence(&self) -> impl Iterator<Item = f32> + '_ {
+        self.cross_spectrum
+            .iter()
+            .zip(
+                self.reference_power
+                    .iter()
+                    .zip(self.measurement_power.iter()),
+            )
+            .map(|(gxy, (gxx, gyy))| {
+                let denom = gxx * gyy;
+                if denom > 0.0 {
+                    gxy.norm_sqr() / denom
+                } else {
+                    0.0
+                }
+            })
+    }
+
+    pub fn frequency_resolution(&self, sample_rate: u32) -> f32 {
+        sample_rate as f32 / self.fft_size as f32
+    }
+
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    fn transfer_function(&self) -> impl Iterator<Item = Complex32> + '_ {
+        self.cross_spectrum
+            .iter()
+            .zip(self.reference_power.iter())
+            .map(|(gxy, gxx)| {
+                if *gxx > 0.0 {
+                    *gxy / *gxx
+                } else {
+                    Complex32::default()
+                }
+            })
+    }
+
+    /// Frequency of each bin of [`Self::magnitude`] and [`Self::phase`].
+    pub fn frequencies(&self, sample_rate: u32) -> impl Iterator<Item = f32> {
+        let resolution = self.frequency_resolution(sample_rate);
+        (0..self.cross_spectrum.len()).map(move |bin| bin as f32 * resolution)
+    }
+
+    fn process_frame(&mut self) {
+        let spectrum = |data: &[f32]| {
+            let mut buf: Vec<_> = data
+                .iter()
+                .zip(self.window.iter())
+                .map(|(s, w)| Complex32::from(s * w))
+                .collect();
+
+            self.fft.process(&mut buf);
+            buf
+        };
+
+        let x = spectrum(&self.reference[..self.fft_size]);
+        let y = spectrum(&self.measurement[..self.fft_size]);
+
+        // exponential averaging, with a linear start-up phase
+        self.frames += 1;
+        let alpha = 1.0 / self.frames.min(self.averages) as f32;
+
+        let bins = self.cross_spectrum.len();
+        for (i, (x, y)) in x.into_iter().zip(y).take(bins).enumerate() {
+            let gxy = x.conj() * y;
+
+            let delta = (gxy - self.cross_spectrum[i]) * alpha;
+            self.cross_spectrum[i] += delta;
+            self.reference_power[i] += (x.norm_sqr() - self.reference_power[i]) * alpha;
+            self.measurement_power[i] += (y.norm_sqr() - self.measurement_power[i]) * alpha;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::signals::WhiteNoise;
+
+    #[test]
+    fn identical_channels_have_unity_transfer_function() {
+        let noise: Vec<_> = WhiteNoise::default().take(8192).collect();
+
+        let mut tf = TransferFunction::new(1024, 8);
+        tf.push(&noise, &noise);
+
+        assert!(tf.frames() > 0);
+        for (magnitude, coherence) in tf.magnitude().zip(tf.coherence()).skip(1) {
+            assert!((magnitude - 1.0).abs() < 1e-3);
+            assert!((coherence - 1.0).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn scaled_channel_is_measured() {
+        let noise: Vec<_> = WhiteNoise::default().take(8192).collect();
+        let scaled: Vec<_> = noise.iter().map(|s| s * 0.5).collect();
+
+        let mut tf = TransferFunction::new(1024, 8);
+        tf.push(&noise, &scaled);
+
+        for magnitude in tf.magnitude().skip(1) {
+            assert!((magnitude - 0.5).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn delay_of_the_response_is_compensated() {
+        let delay = 100;
+        let noise: Vec<_> = WhiteNoise::default().take(16384).collect();
+        let delayed: Vec<_> = std::iter::repeat_n(0.0, delay)
+            .chain(noise.iter().copied())
+            .collect();
+
+        let estimated = TransferFunction::estimate_delay(&noise, &delayed, 48_000);
+        assert_eq!(estimated, delay);
+
+        let tf = TransferFunction::from_recordings(&noise, &delayed, 1024, estimated);
+        for (magnitude, phase) in tf.magnitude().zip(tf.phase()).skip(1) {
+            assert!((magnitude - 1.0).abs() < 1e-3);
+            assert!(phase.abs() < 1e-2);
+        }
+    }
+}
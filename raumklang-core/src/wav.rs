@@ -0,0 +1,568 @@
+//! Streaming writer for 32 or 64 bit float WAV files.
+//!
+//! The header is updated periodically, so that the file stays readable when
+//! the recording is interrupted. Files exceeding the 4 GB limit of RIFF are
+//! turned into RF64 (EBU Tech 3306) in place.
+
+mod metadata;
+
+pub use metadata::{Metadata, Stimulus};
+
+use crate::WavLoadError;
+
+use std::{
+    fmt,
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+const HEADER_LEN: u64 = 80;
+const FORMAT_IEEE_FLOAT: u16 = 3;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SampleFormat {
+    #[default]
+    Float32,
+    Float64,
+}
+
+/// Sample format and container of written files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Format {
+    pub sample_format: SampleFormat,
+    /// Writes RF64 (BW64) from the start, otherwise files are only turned
+    /// into RF64 when exceeding 4 GB.
+    pub rf64: bool,
+}
+
+pub struct StreamWriter<W>
+where
+    W: Write + Seek,
+{
+    inner: W,
+    sample_rate: u32,
+    channels: u16,
+    format: Format,
+    /// `bext` and `iXML` chunks written between the format and the data
+    metadata: Vec<u8>,
+    samples: u64,
+    /// number of samples after which the header is updated
+    header_interval: u64,
+    unsynced: u64,
+    /// maximum RIFF size, before switching to RF64
+    riff_limit: u64,
+}
+
+impl SampleFormat {
+    pub const ALL: [SampleFormat; 2] = [SampleFormat::Float32, SampleFormat::Float64];
+
+    fn bytes(&self) -> u64 {
+        match self {
+            SampleFormat::Float32 => 4,
+            SampleFormat::Float64 => 8,
+        }
+    }
+}
+
+impl StreamWriter<BufWriter<File>> {
+    pub fn create(path: impl AsRef<Path>, sample_rate: u32) -> io::Result<Self> {
+        Self::create_with(path, sample_rate, 1, Format::default())
+    }
+
+    pub fn create_with(
+        path: impl AsRef<Path>,
+        sample_rate: u32,
+        channels: u16,
+        format: Format,
+    ) -> io::Result<Self> {
+        let file = File::create(path)?;
+
+        Self::with_format(BufWriter::new(file), sample_rate, channels, format)
+    }
+}
+
+impl<W> StreamWriter<W>
+where
+    W: Write + Seek,
+{
+    pub fn new(inner: W, sample_rate: u32) -> io::Result<Self> {
+        Self::with_format(inner, sample_rate, 1, Format::default())
+    }
+
+    /// Writer for `channels` interleaved channels.
+    pub fn with_format(
+        inner: W,
+        sample_rate: u32,
+        channels: u16,
+        format: Format,
+    ) -> io::Result<Self> {
+        let channels = channels.max(1);
+
+        let mut writer = Self {
+            inner,
+            sample_rate,
+            channels,
+            format,
+            metadata: vec![],
+            samples: 0,
+            header_interval: u64::from(sample_rate) * u64::from(channels),
+            unsynced: 0,
+            riff_limit: u64::from(u32::MAX),
+        };
+
+        writer.write_header()?;
+
+        Ok(writer)
+    }
+
+    /// Embeds `metadata` in the header, must be set before writing samples.
+    pub fn set_metadata(&mut self, metadata: &Metadata) -> io::Result<()> {
+        if self.samples > 0 {
+            return Err(io::Error::other(
+                "metadata must be set before writing samples",
+            ));
+        }
+
+        self.metadata = metadata.chunks(self.sample_rate, self.channels, self.format);
+
+        self.inner.seek(SeekFrom::Start(0))?;
+        self.write_header()
+    }
+
+    pub fn write_sample(&mut self, sample: f32) -> io::Result<()> {
+        match self.format.sample_format {
+            SampleFormat::Float32 => self.inner.write_all(&sample.to_le_bytes())?,
+            SampleFormat::Float64 => self.inner.write_all(&f64::from(sample).to_le_bytes())?,
+        }
+
+        self.samples += 1;
+        self.unsynced += 1;
+
+        if self.unsynced >= self.header_interval {
+            self.sync()?;
+        }
+
+        Ok(())
+    }
+
+    pub fn write_samples(&mut self, samples: &[f32]) -> io::Result<()> {
+        for sample in samples {
+            self.write_sample(*sample)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn samples(&self) -> u64 {
+        self.samples
+    }
+
+    /// Updates the header to the current length and flushes all written data.
+    pub fn sync(&mut self) -> io::Result<()> {
+        self.inner.seek(SeekFrom::Start(0))?;
+        self.write_header()?;
+        self.inner.seek(SeekFrom::End(0))?;
+        self.inner.flush()?;
+
+        self.unsynced = 0;
+
+        Ok(())
+    }
+
+    pub fn finalize(mut self) -> io::Result<()> {
+        self.sync()
+    }
+
+    fn write_header(&mut self) -> io::Result<()> {
+        let bytes_per_sample = self.format.sample_format.bytes();
+        let data_size = self.samples * bytes_per_sample;
+        let header_len = HEADER_LEN + self.metadata.len() as u64;
+        let riff_size = header_len - 8 + data_size;
+        let is_rf64 = self.format.rf64 || riff_size > self.riff_limit;
+
+        let w = &mut self.inner;
+        if is_rf64 {
+            w.write_all(b"RF64")?;
+            w.write_all(&u32::MAX.to_le_bytes())?;
+        } else {
+            w.write_all(b"RIFF")?;
+            w.write_all(&(riff_size as u32).to_le_bytes())?;
+        }
+        w.write_all(b"WAVE")?;
+
+        // reserves space for the `ds64` chunk, in case the file grows too large
+        w.write_all(if is_rf64 { b"ds64" } else { b"JUNK" })?;
+        w.write_all(&28u32.to_le_bytes())?;
+        w.write_all(&riff_size.to_le_bytes())?;
+        w.write_all(&data_size.to_le_bytes())?;
+        w.write_all(&(self.samples / u64::from(self.channels)).to_le_bytes())?;
+        w.write_all(&0u32.to_le_bytes())?;
+
+        let block_align = (bytes_per_sample * u64::from(self.channels)) as u16;
+        w.write_all(b"fmt ")?;
+        w.write_all(&16u32.to_le_bytes())?;
+        w.write_all(&FORMAT_IEEE_FLOAT.to_le_bytes())?;
+        w.write_all(&self.channels.to_le_bytes())?;
+        w.write_all(&self.sample_rate.to_le_bytes())?;
+        w.write_all(&(self.sample_rate * u32::from(block_align)).to_le_bytes())?;
+        w.write_all(&block_align.to_le_bytes())?;
+        w.write_all(&(bytes_per_sample as u16 * 8).to_le_bytes())?;
+
+        w.write_all(&self.metadata)?;
+
+        w.write_all(b"data")?;
+        let data_size = if is_rf64 { u32::MAX } else { data_size as u32 };
+        w.write_all(&data_size.to_le_bytes())?;
+
+        debug_assert_eq!(w.stream_position()?, header_len);
+
+        Ok(())
+    }
+}
+
+/// Writes `samples` of `channels` interleaved channels to a new file at
+/// `path`.
+pub fn write(
+    path: impl AsRef<Path>,
+    sample_rate: u32,
+    channels: u16,
+    samples: impl IntoIterator<Item = f32>,
+    format: Format,
+    metadata: Option<&Metadata>,
+) -> io::Result<()> {
+    let file = BufWriter::new(File::create(path)?);
+
+    write_to(file, sample_rate, channels, samples, format, metadata)
+}
+
+/// Like [`write`], but to `inner`, e.g. a buffer that is piped to stdout.
+pub fn write_to(
+    inner: impl Write + Seek,
+    sample_rate: u32,
+    channels: u16,
+    samples: impl IntoIterator<Item = f32>,
+    format: Format,
+    metadata: Option<&Metadata>,
+) -> io::Result<()> {
+    let mut writer = StreamWriter::with_format(inner, sample_rate, channels, format)?;
+    if let Some(metadata) = metadata {
+        writer.set_metadata(metadata)?;
+    }
+
+    for sample in samples {
+        writer.write_sample(sample)?;
+    }
+
+    writer.finalize()
+}
+
+/// Returns true, if the file at `path` is a RF64 file.
+pub fn is_rf64(path: impl AsRef<Path>) -> io::Result<bool> {
+    let mut id = [0; 4];
+    File::open(path)?.read_exact(&mut id)?;
+
+    Ok(&id == b"RF64")
+}
+
+/// Returns true, if the file at `path` needs to be read with [`read`], as it
+/// is a RF64 file, contains 64 bit float samples or odd-sized chunks, whose
+/// pad byte is not skipped by hound.
+pub fn is_extended(path: impl AsRef<Path>) -> io::Result<bool> {
+    if is_rf64(&path)? {
+        return Ok(true);
+    }
+
+    let mut reader = BufReader::new(File::open(path)?);
+    match read_header(&mut reader) {
+        Ok(header) => Ok(header.bits == 64 || header.padded),
+        // not a float WAV file, left to hound
+        Err(_) => Ok(false),
+    }
+}
+
+/// Like [`is_extended`], for the content of a file in `bytes`.
+pub fn is_extended_bytes(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"RF64")
+        || read_header(&mut &bytes[..]).is_ok_and(|header| header.bits == 64 || header.padded)
+}
+
+struct Header {
+    sample_rate: u32,
+    channels: u16,
+    bits: u16,
+    data_size: u64,
+    /// An odd-sized chunk, followed by a pad byte, precedes the data
+    padded: bool,
+}
+
+/// Reads the chunks up to the start of the sample data.
+fn read_header(reader: &mut impl Read) -> Result<Header, WavLoadError> {
+    let mut header = [0; 12];
+    reader.read_exact(&mut header)?;
+
+    let is_rf64 = match &header[0..4] {
+        b"RF64" => true,
+        b"RIFF" => false,
+        _ => return Err(WavLoadError::Other),
+    };
+    if &header[8..12] != b"WAVE" {
+        return Err(WavLoadError::Other);
+    }
+
+    let mut ds64_size = None;
+    let mut format = None;
+    let mut padded = false;
+    loop {
+        let mut chunk = [0; 8];
+        reader.read_exact(&mut chunk)?;
+
+        let id = &chunk[0..4];
+        let size = u32::from_le_bytes(chunk[4..8].try_into().unwrap());
+        padded |= size % 2 == 1;
+
+        let mut content = |len: u32| -> io::Result<Vec<u8>> {
+            // chunks are padded to an even length
+            let mut content = vec![0; (len + len % 2) as usize];
+            reader.read_exact(&mut content)?;
+            Ok(content)
+        };
+
+        match id {
+            b"ds64" => {
+                let ds64 = content(size)?;
+                ds64_size = Some(u64::from_le_bytes(ds64[8..16].try_into().unwrap()));
+            }
+            b"fmt " => {
+                let fmt = content(size)?;
+                let tag = u16::from_le_bytes([fmt[0], fmt[1]]);
+                let channels = u16::from_le_bytes([fmt[2], fmt[3]]);
+                let bits = u16::from_le_bytes([fmt[14], fmt[15]]);
+
+                if tag != FORMAT_IEEE_FLOAT || !(bits == 32 || bits == 64) || channels == 0 {
+                    return Err(WavLoadError::Other);
+                }
+
+                let sample_rate = u32::from_le_bytes(fmt[4..8].try_into().unwrap());
+                format = Some((sample_rate, channels, bits));
+            }
+            b"data" => {
+                let data_size = if is_rf64 {
+                    ds64_size.ok_or(WavLoadError::Other)?
+                } else {
+                    u64::from(size)
+                };
+
+                let (sample_rate, channels, bits) = format.ok_or(WavLoadError::Other)?;
+
+                return Ok(Header {
+                    sample_rate,
+                    channels,
+                    bits,
+                    data_size,
+                    padded,
+                });
+            }
+            _ => {
+                content(size)?;
+            }
+        }
+    }
+}
+
+/// Reads `channel` of a 32 or 64 bit float RIFF or RF64 file, as written
+/// by [`StreamWriter`].
+pub fn read(path: impl AsRef<Path>, channel: u16) -> Result<(u32, Vec<f32>), WavLoadError> {
+    read_from(BufReader::new(File::open(path)?), channel)
+}
+
+/// Like [`read`], for the content of a file in `bytes`.
+pub fn read_bytes(bytes: &[u8], channel: u16) -> Result<(u32, Vec<f32>), WavLoadError> {
+    read_from(bytes, channel)
+}
+
+fn read_from(mut reader: impl Read, channel: u16) -> Result<(u32, Vec<f32>), WavLoadError> {
+    let header = read_header(&mut reader)?;
+
+    if channel >= header.channels {
+        return Err(WavLoadError::MissingChannel {
+            channel,
+            channels: header.channels,
+        });
+    }
+
+    let bytes_per_sample = u64::from(header.bits / 8);
+    let samples = header.data_size / bytes_per_sample;
+    let channels = u64::from(header.channels);
+
+    let mut data = Vec::with_capacity((samples / channels) as usize);
+    let mut buffer = [0; 8];
+    for i in 0..samples {
+        let sample = &mut buffer[..bytes_per_sample as usize];
+        reader.read_exact(sample)?;
+
+        if i % channels != u64::from(channel) {
+            continue;
+        }
+
+        data.push(match header.bits {
+            64 => f64::from_le_bytes(sample.try_into().unwrap()) as f32,
+            _ => f32::from_le_bytes(sample.try_into().unwrap()),
+        });
+    }
+
+    Ok((header.sample_rate, data))
+}
+
+impl fmt::Display for SampleFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SampleFormat::Float32 => write!(f, "32 bit float"),
+            SampleFormat::Float64 => write!(f, "64 bit float"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::io::Cursor;
+
+    #[test]
+    fn interrupted_recording_is_readable() {
+        let mut writer = StreamWriter::new(Cursor::new(vec![]), 48_000).unwrap();
+        writer.header_interval = 4;
+
+        writer.write_samples(&[0.1, 0.2, 0.3, 0.4, 0.5]).unwrap();
+
+        // never finalized, the last sample is not covered by the header yet
+        let file = writer.inner.into_inner();
+        let reader = hound::WavReader::new(Cursor::new(file)).unwrap();
+        assert_eq!(reader.spec().sample_rate, 48_000);
+
+        let samples: Vec<f32> = reader.into_samples().map(Result::unwrap).collect();
+        assert_eq!(samples, [0.1, 0.2, 0.3, 0.4]);
+    }
+
+    #[test]
+    fn piped_data_is_read_from_bytes() {
+        let mut buffer = Cursor::new(vec![]);
+        let format = Format {
+            sample_format: SampleFormat::Float64,
+            rf64: false,
+        };
+        write_to(&mut buffer, 48_000, 2, [0.1, -0.1, 0.2, -0.2], format, None).unwrap();
+
+        let bytes = buffer.into_inner();
+        assert!(is_extended_bytes(&bytes));
+        assert_eq!(read_bytes(&bytes, 1).unwrap(), (48_000, vec![-0.1, -0.2]));
+    }
+
+    #[test]
+    fn large_recordings_are_written_as_rf64() {
+        let dir = std::env::temp_dir().join("raumklang-wav-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("rf64.wav");
+
+        let mut writer = StreamWriter::create(&path, 44_100).unwrap();
+        writer.riff_limit = HEADER_LEN;
+        writer.write_samples(&[0.5, -0.5, 0.25]).unwrap();
+        writer.finalize().unwrap();
+
+        assert!(is_rf64(&path).unwrap());
+        assert_eq!(read(&path, 0).unwrap(), (44_100, vec![0.5, -0.5, 0.25]));
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn double_precision_stereo_round_trip() {
+        let dir = std::env::temp_dir().join("raumklang-wav-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("float64.wav");
+
+        let format = Format {
+            sample_format: SampleFormat::Float64,
+            rf64: false,
+        };
+        write(&path, 48_000, 2, [0.5, -0.5, 0.25, -0.25], format, None).unwrap();
+
+        assert!(!is_rf64(&path).unwrap());
+        assert!(is_extended(&path).unwrap());
+        assert_eq!(read(&path, 1).unwrap(), (48_000, vec![-0.5, -0.25]));
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn metadata_is_skipped_by_readers() {
+        let metadata = Metadata {
+            description: "Impulse response of <Left>".to_string(),
+            originator: "raumklang".to_string(),
+            stimulus: Some(Stimulus {
+                signal: "logarithmic sweep".to_string(),
+                frequency_range: Some((20, 20_000)),
+                duration: std::time::Duration::from_secs(5),
+                level: Some(-12.0),
+            }),
+            ..Metadata::default()
+        };
+
+        let mut writer = StreamWriter::new(Cursor::new(vec![]), 48_000).unwrap();
+        writer.set_metadata(&metadata).unwrap();
+        writer.write_samples(&[0.1, 0.2, 0.3]).unwrap();
+        writer.sync().unwrap();
+
+        let file = writer.inner.into_inner();
+        let ixml = String::from_utf8_lossy(&file);
+        assert!(ixml.contains("<NOTE>Impulse response of &lt;Left&gt;</NOTE>"));
+        assert!(ixml.contains("<END_FREQUENCY>20000</END_FREQUENCY>"));
+
+        let header = read_header(&mut Cursor::new(&file)).unwrap();
+        assert_eq!(header.data_size, 12);
+    }
+
+    #[test]
+    fn padded_metadata_is_read() {
+        let dir = std::env::temp_dir().join("raumklang-wav-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("padded.wav");
+
+        // the descriptions differ by one byte, so one of them makes the iXML
+        // chunk odd-sized
+        let mut padded = 0;
+        for description in ["odd", "even"] {
+            let metadata = Metadata {
+                description: description.to_string(),
+                ..Metadata::default()
+            };
+            let samples = [0.5, -0.5];
+            write(
+                &path,
+                48_000,
+                1,
+                samples,
+                Format::default(),
+                Some(&metadata),
+            )
+            .unwrap();
+
+            if is_padded(&path) {
+                padded += 1;
+                assert!(is_extended(&path).unwrap());
+            }
+            assert_eq!(read(&path, 0).unwrap(), (48_000, samples.to_vec()));
+        }
+        assert_eq!(padded, 1);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    fn is_padded(path: &Path) -> bool {
+        let mut reader = BufReader::new(File::open(path).unwrap());
+        read_header(&mut reader).unwrap().padded
+    }
+}
@@ -0,0 +1,213 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::Format;
+
+/// Size of the `bext` chunk without the coding history, see EBU Tech 3285.
+const BEXT_LEN: usize = 602;
+
+/// Describes how a file was measured, embedded as broadcast WAV `bext` and
+/// `iXML` chunks, so that files stay self-describing outside of a project.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Metadata {
+    pub description: String,
+    /// Name and version of the software, that wrote the file.
+    pub originator: String,
+    /// Date of the measurement, written in UTC.
+    pub date: Option<SystemTime>,
+    pub microphone: Option<String>,
+    pub stimulus: Option<Stimulus>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stimulus {
+    /// Kind of the signal, e.g. `logarithmic sweep`.
+    pub signal: String,
+    /// Start and end frequency of sweeps in Hz
+    pub frequency_range: Option<(u16, u16)>,
+    pub duration: Duration,
+    /// Playback level in dBFS
+    pub level: Option<f32>,
+}
+
+impl Metadata {
+    /// The `bext` and `iXML` chunks, including their headers.
+    pub(super) fn chunks(&self, sample_rate: u32, channels: u16, format: Format) -> Vec<u8> {
+        let bits = format.sample_format.bytes() * 8;
+
+        let mut chunks = vec![];
+        write_chunk(
+            &mut chunks,
+            b"bext",
+            &self.bext(sample_rate, channels, bits),
+        );
+        write_chunk(
+            &mut chunks,
+            b"iXML",
+            self.ixml(sample_rate, bits).as_bytes(),
+        );
+
+        chunks
+    }
+
+    fn bext(&self, sample_rate: u32, channels: u16, bits: u64) -> Vec<u8> {
+        let (date, time) = self.date.map(date_time).unwrap_or_default();
+
+        let mut bext = Vec::with_capacity(BEXT_LEN);
+        write_fixed(&mut bext, &self.description, 256);
+        write_fixed(&mut bext, &self.originator, 32);
+        // originator reference
+        write_fixed(&mut bext, "", 32);
+        write_fixed(&mut bext, &date, 10);
+        write_fixed(&mut bext, &time, 8);
+        // time reference
+        bext.extend_from_slice(&0u64.to_le_bytes());
+        // version 1, without loudness values
+        bext.extend_from_slice(&1u16.to_le_bytes());
+        // UMID and reserved bytes
+        bext.resize(BEXT_LEN, 0);
+
+        let mode = match channels {
+            1 => "mono",
+            2 => "stereo",
+            _ => "multichannel",
+        };
+        let history = format!(
+            "A=PCM,F={sample_rate},W={bits},M={mode},T={}\r\n",
+            self.originator
+        );
+        bext.extend_from_slice(history.as_bytes());
+
+        bext
+    }
+
+    fn ixml(&self, sample_rate: u32, bits: u64) -> String {
+        let mut raumklang = String::new();
+        let mut element = |name: &str, value: &str| {
+            raumklang.push_str(&format!("    <{name}>{}</{name}>\n", escape(value)));
+        };
+
+        element("SOFTWARE", &self.originator);
+        if let Some(date) = self.date {
+            let (date, time) = date_time(date);
+            element("DATE", &format!("{date}T{time}Z"));
+        }
+        if let Some(microphone) = &self.microphone {
+            element("MICROPHONE", microphone);
+        }
+        if let Some(stimulus) = &self.stimulus {
+            element("STIMULUS", &stimulus.signal);
+            if let Some((start, end)) = stimulus.frequency_range {
+                element("START_FREQUENCY", &start.to_string());
+                element("END_FREQUENCY", &end.to_string());
+            }
+            element("DURATION", &stimulus.duration.as_secs_f32().to_string());
+            if let Some(level) = stimulus.level {
+                element("LEVEL", &format!("{level:.2}"));
+            }
+        }
+
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <BWFXML>\n  \
+               <IXML_VERSION>2.10</IXML_VERSION>\n  \
+               <NOTE>{}</NOTE>\n  \
+               <SPEED>\n    \
+                 <FILE_SAMPLE_RATE>{sample_rate}</FILE_SAMPLE_RATE>\n    \
+                 <AUDIO_BIT_DEPTH>{bits}</AUDIO_BIT_DEPTH>\n  \
+               </SPEED>\n  \
+               <RAUMKLANG>\n{raumklang}  </RAUMKLANG>\n\
+             </BWFXML>\n",
+            escape(&self.description)
+        )
+    }
+}
+
+fn write_chunk(buf: &mut Vec<u8>, id: &[u8; 4], content: &[u8]) {
+    buf.extend_from_slice(id);
+    buf.extend_from_slice(&(content.len() as u32).to_le_bytes());
+    buf.extend_from_slice(content);
+
+    // chunks are padded to an even length
+    if content.len() % 2 == 1 {
+        buf.push(0);
+    }
+}
+
+/// Writes `s` as ASCII into a zero padded field of `len` bytes.
+fn write_fixed(buf: &mut Vec<u8>, s: &str, len: usize) {
+    let start = buf.len();
+
+    buf.extend(
+        s.chars()
+            .map(|c| if c.is_ascii() { c as u8 } else { b'?' })
+            .take(len),
+    );
+    buf.resize(start + len, 0);
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Date as `yyyy-mm-dd` and time as `hh:mm:ss` in UTC.
+fn date_time(time: SystemTime) -> (String, String) {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+
+    // days to civil date, see http://howardhinnant.github.io/date_algorithms.html
+    let days = (secs / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    let secs = secs % 86_400;
+
+    (
+        format!("{year:04}-{month:02}-{day:02}"),
+        format!(
+            "{:02}:{:02}:{:02}",
+            secs / 3_600,
+            secs % 3_600 / 60,
+            secs % 60
+        ),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn date_time_in_utc() {
+        assert_eq!(
+            date_time(UNIX_EPOCH),
+            ("1970-01-01".to_string(), "00:00:00".to_string())
+        );
+        assert_eq!(
+            date_time(UNIX_EPOCH + Duration::from_secs(1_709_210_096)),
+            ("2024-02-29".to_string(), "12:34:56".to_string())
+        );
+    }
+
+    #[test]
+    fn odd_sized_chunks_are_padded_outside_of_their_size() {
+        let mut buf = vec![];
+        write_chunk(&mut buf, b"iXML", b"abc");
+
+        assert_eq!(buf.len(), 12);
+        assert_eq!(buf[4..8], 3u32.to_le_bytes());
+        assert_eq!(buf[11], 0);
+    }
+}
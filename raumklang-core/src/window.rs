@@ -42,6 +42,7 @@ impl TukeyWindow {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Window {
     Hann,
     Tukey(f32),
@@ -65,6 +66,7 @@ impl std::fmt::Display for Window {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WindowBuilder {
     pub left_side: Window,
     pub left_side_width: usize,
@@ -1,34 +1,58 @@
 mod loudness;
 mod measurement;
+#[cfg(test)]
+pub mod mock;
+mod moving_mic;
 mod process;
+mod rta;
+mod spectrum;
+mod spl;
+mod transfer_function;
 
 pub use loudness::Loudness;
 pub use measurement::Measurement;
+pub use moving_mic::Average;
 pub use process::Process;
+pub use rta::Rta;
+pub use spectrum::Spectrum;
+pub use spl::INTERVAL as SPL_INTERVAL;
+pub use transfer_function::TransferFunction;
 
 use crate::data;
-use crate::data::audio::{InPort, OutPort};
+use crate::data::audio::{Connections, InPort, OutPort, Playback, Trim};
 use crate::log;
+use crate::remote;
 use loudness::Test;
 
 use ringbuf::traits::{Consumer, Producer, Split};
 use ringbuf::{HeapCons, HeapProd, HeapRb};
 
 use atomic_float::AtomicF32;
-use iced::futures::Stream;
+use iced::futures::future::BoxFuture;
+use iced::futures::{FutureExt, Stream};
 use jack::PortFlags;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::error::TryRecvError;
+use tokio::sync::oneshot;
 use tokio_stream::wrappers::ReceiverStream;
 
+use std::fmt;
 use std::sync::Arc;
-use std::sync::atomic::{self, AtomicBool};
+use std::sync::atomic::{self, AtomicBool, AtomicUsize};
 use std::thread;
 use std::time::Duration;
 
+// TODO: make configurable
+// NOTE: silence in front of the sweep
+const LEAD_IN: Duration = Duration::from_millis(500);
+// NOTE: amplitude of the sweep at full volume
+const SWEEP_AMPLITUDE: f32 = 0.8;
+// NOTE: silence after the sweep, to record the decay of the room
+pub const DECAY_TAIL: Duration = Duration::from_millis(450);
+
 #[derive(Debug, Clone)]
 pub enum Event {
-    Ready(Backend, Arc<mpsc::Receiver<Notification>>),
+    Ready(Arc<dyn Backend>, Arc<mpsc::Receiver<Notification>>),
     Error {
         err: Error,
         retry_tx: std::sync::mpsc::SyncSender<()>,
@@ -42,17 +66,131 @@ pub enum Notification {
     OutPortDisconnected,
     InPortConnected(InPort),
     InPortDisconnected,
+    /// Ports have been registered or unregistered, e.g. a device was plugged in.
+    PortsChanged {
+        in_ports: Vec<InPort>,
+        out_ports: Vec<OutPort>,
+    },
+    Trigger(remote::Trigger),
+}
+
+/// Audio server, the recording screens play and record through.
+///
+/// [`run`] connects to JACK, other servers only have to provide the same
+/// event stream to plug into the screens.
+pub trait Backend: fmt::Debug + Send + Sync {
+    fn sample_rate(&self) -> data::SampleRate;
+
+    /// Capture ports, that were available when the backend got ready, later
+    /// changes are reported by [`Notification::PortsChanged`].
+    fn in_ports(&self) -> &[InPort];
+
+    /// Playback ports, that were available when the backend got ready.
+    fn out_ports(&self) -> &[OutPort];
+
+    /// Starts the loudness test, besides the loudness the spectrum of the
+    /// played signal is reported.
+    fn run_test(&self, duration: Duration) -> (mpsc::Receiver<Loudness>, mpsc::Receiver<Spectrum>);
+
+    /// Starts the measurement, the returned counter holds the number of frames
+    /// that got lost, because the capture buffer was full.
+    ///
+    /// The signal is preceded by `pre_roll` of silence, which is not part of
+    /// the recording.
+    fn run_measurement(
+        &self,
+        config: data::measurement::SignalConfig,
+        capture_buffer: usize,
+        pre_roll: Duration,
+    ) -> (
+        mpsc::Receiver<Loudness>,
+        mpsc::Receiver<Spectrum>,
+        mpsc::Receiver<Box<[f32]>>,
+        Arc<AtomicUsize>,
+    );
+
+    /// Plays `period` of a periodic noise repeatedly for at most `duration`
+    /// and averages the recorded spectra. Dropping the receiver of the
+    /// average stops the measurement.
+    fn run_moving_mic(
+        &self,
+        period: Vec<f32>,
+        duration: Duration,
+    ) -> (mpsc::Receiver<Loudness>, mpsc::Receiver<Average>);
+
+    /// Plays `signal` once and records it, the recording is sent in chunks
+    /// and the channel closes, once the signal was played.
+    fn run_signal(
+        &self,
+        signal: Vec<f32>,
+    ) -> (mpsc::Receiver<Loudness>, mpsc::Receiver<Box<[f32]>>);
+
+    /// Plays pink noise, until the receiver of the transfer function is
+    /// dropped, and estimates the transfer function between the played and
+    /// the recorded signal.
+    fn run_transfer_function(&self)
+    -> (mpsc::Receiver<Loudness>, mpsc::Receiver<TransferFunction>);
+
+    /// Analyzes the recording in fractional octave bands (e.g.
+    /// `fraction = 3` for third-octaves), until the receiver of the levels is
+    /// dropped. Pink noise is played, if `play_noise` is set, silence
+    /// otherwise.
+    fn run_rta(
+        &self,
+        fraction: u8,
+        play_noise: bool,
+    ) -> (mpsc::Receiver<Loudness>, mpsc::Receiver<Rta>);
+
+    /// Logs the sound pressure level of the recording, until the receiver of
+    /// the levels is dropped, one record is sent every [`SPL_INTERVAL`].
+    /// `calibration` is the level in dB that corresponds to 0 dBFS.
+    fn run_spl(
+        &self,
+        weighting: raumklang_core::spl::Weighting,
+        time_weighting: raumklang_core::spl::TimeWeighting,
+        calibration: f32,
+    ) -> (
+        mpsc::Receiver<Loudness>,
+        mpsc::Receiver<raumklang_core::spl::Record>,
+    );
+
+    fn connect_out_port(&self, dest: OutPort) -> BoxFuture<'static, ()>;
+
+    /// Connects the measurement output to all of `dests` at once, e.g. to
+    /// play through the mains and the sub together.
+    fn connect_out_ports(&self, dests: Vec<OutPort>) -> BoxFuture<'static, ()>;
+
+    fn connect_in_port(&self, src: InPort) -> BoxFuture<'static, ()>;
+
+    /// Queries the current connections of the measurement ports.
+    fn connections(&self) -> BoxFuture<'static, Option<Connections>>;
+
+    fn set_volume(&self, volume: f32);
+
+    /// Sets the trim of the connected output port, it applies to test
+    /// signals and measurements alike.
+    fn set_trim(&self, trim: Trim);
+
+    /// Ramps the output to zero within [`raumklang_core::FADE_OUT`] and ends
+    /// the running test or measurement.
+    fn stop(&self);
 }
 
 #[derive(Debug, Clone)]
-pub struct Backend {
-    pub sample_rate: data::SampleRate,
-    pub in_ports: Vec<InPort>,
-    pub out_ports: Vec<OutPort>,
+struct Jack {
+    sample_rate: data::SampleRate,
+    in_ports: Vec<InPort>,
+    out_ports: Vec<OutPort>,
     volume: Arc<AtomicF32>,
+    /// Gain factor of the connected output port, see [`Trim::factor`].
+    trim: Arc<AtomicF32>,
+    /// Fades out the current playback, see [`Backend::stop`].
+    stop: Arc<AtomicBool>,
     sender: mpsc::Sender<Command>,
 }
 
+/// Connects to the JACK server, the stream starts over with
+/// [`Event::Ready`] whenever the server was restarted.
 pub fn run() -> impl Stream<Item = Event> {
     let (sender, receiver) = mpsc::channel(1024);
 
@@ -61,26 +199,49 @@ pub fn run() -> impl Stream<Item = Event> {
     ReceiverStream::new(receiver)
 }
 
-impl Backend {
-    pub fn run_test(&self, duration: Duration) -> mpsc::Receiver<Loudness> {
+impl Backend for Jack {
+    fn sample_rate(&self) -> data::SampleRate {
+        self.sample_rate
+    }
+
+    fn in_ports(&self) -> &[InPort] {
+        &self.in_ports
+    }
+
+    fn out_ports(&self) -> &[OutPort] {
+        &self.out_ports
+    }
+
+    fn run_test(&self, duration: Duration) -> (mpsc::Receiver<Loudness>, mpsc::Receiver<Spectrum>) {
         let (loudness_sender, loudness_receiver) = mpsc::channel(128);
+        let (spectrum_sender, spectrum_receiver) = mpsc::channel(8);
 
         let command = Command::RunTest {
             duration,
             loudness: loudness_sender,
+            spectrum: spectrum_sender,
         };
 
         self.sender.try_send(command).unwrap();
 
-        loudness_receiver
+        (loudness_receiver, spectrum_receiver)
     }
 
-    pub fn run_measurement(
+    fn run_measurement(
         &self,
         config: data::measurement::SignalConfig,
-    ) -> (mpsc::Receiver<Loudness>, mpsc::Receiver<Box<[f32]>>) {
+        capture_buffer: usize,
+        pre_roll: Duration,
+    ) -> (
+        mpsc::Receiver<Loudness>,
+        mpsc::Receiver<Spectrum>,
+        mpsc::Receiver<Box<[f32]>>,
+        Arc<AtomicUsize>,
+    ) {
         let (loudness_sender, loudness_receiver) = mpsc::channel(1024);
+        let (spectrum_sender, spectrum_receiver) = mpsc::channel(8);
         let (data_sender, data_receiver) = mpsc::channel(1024);
+        let dropped_frames = Arc::new(AtomicUsize::new(0));
 
         let command = Command::RunMeasurement {
             duration: config.duration().into_inner(),
@@ -88,6 +249,53 @@ impl Backend {
             end_frequency: config.end_frequency(),
             data_sender,
             loudness_sender,
+            spectrum_sender,
+            capture_buffer,
+            pre_roll,
+            dropped_frames: Arc::clone(&dropped_frames),
+        };
+
+        self.sender.try_send(command).unwrap();
+
+        (
+            loudness_receiver,
+            spectrum_receiver,
+            data_receiver,
+            dropped_frames,
+        )
+    }
+
+    fn run_moving_mic(
+        &self,
+        period: Vec<f32>,
+        duration: Duration,
+    ) -> (mpsc::Receiver<Loudness>, mpsc::Receiver<Average>) {
+        let (loudness_sender, loudness_receiver) = mpsc::channel(128);
+        let (average_sender, average_receiver) = mpsc::channel(8);
+
+        let command = Command::RunMovingMic {
+            period,
+            duration,
+            loudness_sender,
+            average_sender,
+        };
+
+        self.sender.try_send(command).unwrap();
+
+        (loudness_receiver, average_receiver)
+    }
+
+    fn run_signal(
+        &self,
+        signal: Vec<f32>,
+    ) -> (mpsc::Receiver<Loudness>, mpsc::Receiver<Box<[f32]>>) {
+        let (loudness_sender, loudness_receiver) = mpsc::channel(128);
+        let (data_sender, data_receiver) = mpsc::channel(1024);
+
+        let command = Command::RunSignal {
+            signal,
+            loudness_sender,
+            data_sender,
         };
 
         self.sender.try_send(command).unwrap();
@@ -95,44 +303,369 @@ impl Backend {
         (loudness_receiver, data_receiver)
     }
 
-    pub async fn connect_out_port(self, dest: OutPort) {
-        let command = Command::ConnectOutPort(dest);
+    fn run_transfer_function(
+        &self,
+    ) -> (mpsc::Receiver<Loudness>, mpsc::Receiver<TransferFunction>) {
+        let (loudness_sender, loudness_receiver) = mpsc::channel(128);
+        let (transfer_function_sender, transfer_function_receiver) = mpsc::channel(8);
+
+        let command = Command::RunTransferFunction {
+            loudness_sender,
+            transfer_function_sender,
+        };
+
+        self.sender.try_send(command).unwrap();
+
+        (loudness_receiver, transfer_function_receiver)
+    }
+
+    fn run_rta(
+        &self,
+        fraction: u8,
+        play_noise: bool,
+    ) -> (mpsc::Receiver<Loudness>, mpsc::Receiver<Rta>) {
+        let (loudness_sender, loudness_receiver) = mpsc::channel(128);
+        let (rta_sender, rta_receiver) = mpsc::channel(8);
+
+        let command = Command::RunRta {
+            fraction,
+            play_noise,
+            loudness_sender,
+            rta_sender,
+        };
+
+        self.sender.try_send(command).unwrap();
+
+        (loudness_receiver, rta_receiver)
+    }
+
+    fn run_spl(
+        &self,
+        weighting: raumklang_core::spl::Weighting,
+        time_weighting: raumklang_core::spl::TimeWeighting,
+        calibration: f32,
+    ) -> (
+        mpsc::Receiver<Loudness>,
+        mpsc::Receiver<raumklang_core::spl::Record>,
+    ) {
+        let (loudness_sender, loudness_receiver) = mpsc::channel(128);
+        let (record_sender, record_receiver) = mpsc::channel(8);
+
+        let command = Command::RunSpl {
+            weighting,
+            time_weighting,
+            calibration,
+            loudness_sender,
+            record_sender,
+        };
+
+        self.sender.try_send(command).unwrap();
+
+        (loudness_receiver, record_receiver)
+    }
+
+    fn connect_out_port(&self, dest: OutPort) -> BoxFuture<'static, ()> {
+        self.connect_out_ports(vec![dest])
+    }
+
+    fn connect_out_ports(&self, dests: Vec<OutPort>) -> BoxFuture<'static, ()> {
+        let sender = self.sender.clone();
+
+        async move {
+            let _ = sender.send(Command::ConnectOutPorts(dests)).await;
+        }
+        .boxed()
+    }
+
+    fn connect_in_port(&self, src: InPort) -> BoxFuture<'static, ()> {
+        let sender = self.sender.clone();
 
-        let _ = self.sender.send(command).await;
+        async move {
+            let _ = sender.send(Command::ConnectInPort(src)).await;
+        }
+        .boxed()
     }
 
-    pub async fn connect_in_port(self, src: InPort) {
-        let command = Command::ConnectInPort(src);
+    fn connections(&self) -> BoxFuture<'static, Option<Connections>> {
+        let sender = self.sender.clone();
+
+        async move {
+            let (connections_sender, receiver) = oneshot::channel();
 
-        let _ = self.sender.send(command).await;
+            sender
+                .send(Command::QueryConnections(connections_sender))
+                .await
+                .ok()?;
+
+            receiver.await.ok()
+        }
+        .boxed()
     }
 
-    pub async fn set_volume(self, volume: f32) {
+    fn set_volume(&self, volume: f32) {
         self.volume.store(volume, atomic::Ordering::Release)
     }
+
+    fn set_trim(&self, trim: Trim) {
+        self.trim.store(trim.factor(), atomic::Ordering::Release)
+    }
+
+    fn stop(&self) {
+        self.stop.store(true, atomic::Ordering::Release)
+    }
+}
+
+/// Length of the played measurement signal in samples, without the decay tail.
+pub fn stimulus_len(
+    config: &data::measurement::SignalConfig,
+    sample_rate: data::SampleRate,
+) -> usize {
+    let sweep_len = config.duration().into_inner().as_secs() * u32::from(sample_rate) as u64;
+    let lead_in = data::Samples::from_duration(LEAD_IN, sample_rate);
+
+    usize::from(lead_in) + sweep_len as usize
+}
+
+/// The played measurement signal: silence for [`LEAD_IN`] followed by the
+/// faded in and out sweep.
+fn stimulus(
+    start_frequency: u16,
+    end_frequency: u16,
+    duration: Duration,
+    sample_rate: u32,
+) -> impl Iterator<Item = f32> {
+    let sweep = raumklang_core::signals::ExponentialSweep::new(
+        start_frequency.into(),
+        end_frequency.into(),
+        SWEEP_AMPLITUDE,
+        (duration.as_secs() * sample_rate as u64) as usize,
+        sample_rate as usize,
+    );
+
+    // TODO make window configureable
+    let left = (sample_rate as f32 * 0.01) as usize;
+    let right = (sample_rate as f32 * 0.01) as usize;
+    let offset = sweep.clone().count() - left - right;
+
+    let window = raumklang_core::WindowBuilder::new(
+        raumklang_core::Window::Hann,
+        left,
+        raumklang_core::Window::Hann,
+        right,
+    )
+    .set_offset(offset)
+    .build();
+
+    let sweep = sweep
+        .into_iter()
+        .enumerate()
+        .map(move |(i, s)| s * window[i]);
+
+    let lead_in = data::Samples::from_duration(LEAD_IN, data::SampleRate::new(sample_rate));
+
+    (0..usize::from(lead_in)).map(|_| 0.0).chain(sweep)
+}
+
+/// Round trip latency of the audio interface, estimated from the peak of the
+/// impulse response between the played signal and a loopback recording made
+/// with `config`.
+pub fn loopback_latency(
+    config: &data::measurement::SignalConfig,
+    loopback: &raumklang_core::Loopback,
+) -> Duration {
+    let sample_rate = loopback.sample_rate();
+
+    let stimulus = stimulus(
+        config.start_frequency(),
+        config.end_frequency(),
+        config.duration().into_inner(),
+        sample_rate,
+    );
+
+    let impulse_response = raumklang_core::ImpulseResponse::from_samples(
+        sample_rate,
+        stimulus,
+        loopback.iter().copied(),
+        raumklang_core::DeconvolutionMethod::default(),
+    );
+
+    // the second half holds negative delays
+    let causal = &impulse_response.data[..impulse_response.data.len() / 2];
+    let peak = causal
+        .iter()
+        .map(|s| s.norm())
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map_or(0, |(i, _)| i);
+
+    Duration::from_secs_f32(peak as f32 / sample_rate as f32)
+}
+
+/// Impulse response of a `recording` of the signal played with `config`,
+/// convolved with the inverse filter of the sweep instead of deconvolved with
+/// a loopback. The `playback_level` in dB is compensated, the latency of the
+/// audio interface is part of the result.
+pub fn impulse_response_from_sweep(
+    config: &data::measurement::SignalConfig,
+    recording: &raumklang_core::Measurement,
+    playback_level: Option<f32>,
+) -> raumklang_core::ImpulseResponse {
+    let sample_rate = recording.sample_rate();
+    let gain = raumklang_core::db_to_gain(playback_level.unwrap_or_default());
+
+    let sweep = raumklang_core::signals::ExponentialSweep::new(
+        config.start_frequency().into(),
+        config.end_frequency().into(),
+        SWEEP_AMPLITUDE * gain,
+        (config.duration().into_inner().as_secs() * sample_rate as u64) as usize,
+        sample_rate as usize,
+    );
+
+    // the sweep starts after the lead-in
+    let lead_in = data::Samples::from_duration(LEAD_IN, data::SampleRate::new(sample_rate));
+
+    raumklang_core::ImpulseResponse::from_inverse_filter(
+        sample_rate,
+        &sweep.inverse_filter(),
+        recording.iter().copied().skip(usize::from(lead_in)),
+    )
+}
+
+/// Verifies, that the `loopback` recorded with `config` is a clean impulse,
+/// see [`raumklang_core::loopback::Check`].
+pub fn verify_loopback(
+    config: &data::measurement::SignalConfig,
+    loopback: &raumklang_core::Loopback,
+) -> Option<raumklang_core::loopback::Check> {
+    let stimulus: Vec<f32> = stimulus(
+        config.start_frequency(),
+        config.end_frequency(),
+        config.duration().into_inner(),
+        loopback.sample_rate(),
+    )
+    .collect();
+
+    raumklang_core::loopback::Check::new(
+        &stimulus,
+        loopback,
+        config.start_frequency().into(),
+        config.end_frequency().into(),
+    )
+}
+
+/// Analyses the `recording` of the signal played with `config` during the
+/// channel check.
+pub fn check_channel(
+    config: &data::measurement::SignalConfig,
+    recording: &[f32],
+    sample_rate: u32,
+) -> Option<raumklang_core::channel::Check> {
+    let stimulus = stimulus(
+        config.start_frequency(),
+        config.end_frequency(),
+        config.duration().into_inner(),
+        sample_rate,
+    );
+
+    let impulse_response = raumklang_core::ImpulseResponse::from_samples(
+        sample_rate,
+        stimulus,
+        recording.iter().copied(),
+        raumklang_core::DeconvolutionMethod::default(),
+    );
+
+    raumklang_core::channel::Check::new(&impulse_response)
+}
+
+/// Crest factors and peak of the `recording` of the signal played with
+/// `config`.
+pub fn level_report(
+    config: &data::measurement::SignalConfig,
+    recording: &[f32],
+    sample_rate: u32,
+) -> raumklang_core::loudness::LevelReport {
+    let stimulus: Vec<f32> = stimulus(
+        config.start_frequency(),
+        config.end_frequency(),
+        config.duration().into_inner(),
+        sample_rate,
+    )
+    .collect();
+
+    raumklang_core::loudness::LevelReport::new(&stimulus, recording, sample_rate)
+}
+
+/// Length of the silence after the measurement signal in samples.
+pub fn decay_tail_len(sample_rate: data::SampleRate) -> usize {
+    data::Samples::from_duration(DECAY_TAIL, sample_rate).into()
 }
 
 enum Command {
     RunTest {
         duration: Duration,
         loudness: mpsc::Sender<Loudness>,
+        spectrum: mpsc::Sender<Spectrum>,
     },
-    ConnectOutPort(OutPort),
+    ConnectOutPorts(Vec<OutPort>),
     ConnectInPort(InPort),
+    QueryConnections(oneshot::Sender<Connections>),
     RunMeasurement {
         duration: Duration,
         loudness_sender: mpsc::Sender<Loudness>,
+        spectrum_sender: mpsc::Sender<Spectrum>,
         data_sender: mpsc::Sender<Box<[f32]>>,
         start_frequency: u16,
         end_frequency: u16,
+        capture_buffer: usize,
+        pre_roll: Duration,
+        dropped_frames: Arc<AtomicUsize>,
+    },
+    RunMovingMic {
+        period: Vec<f32>,
+        duration: Duration,
+        loudness_sender: mpsc::Sender<Loudness>,
+        average_sender: mpsc::Sender<Average>,
+    },
+    RunSignal {
+        signal: Vec<f32>,
+        loudness_sender: mpsc::Sender<Loudness>,
+        data_sender: mpsc::Sender<Box<[f32]>>,
+    },
+    RunTransferFunction {
+        loudness_sender: mpsc::Sender<Loudness>,
+        transfer_function_sender: mpsc::Sender<TransferFunction>,
+    },
+    RunRta {
+        fraction: u8,
+        play_noise: bool,
+        loudness_sender: mpsc::Sender<Loudness>,
+        rta_sender: mpsc::Sender<Rta>,
+    },
+    RunSpl {
+        weighting: raumklang_core::spl::Weighting,
+        time_weighting: raumklang_core::spl::TimeWeighting,
+        calibration: f32,
+        loudness_sender: mpsc::Sender<Loudness>,
+        record_sender: mpsc::Sender<raumklang_core::spl::Record>,
     },
 }
 
+impl Command {
+    /// Whether the command starts a new playback.
+    fn is_run(&self) -> bool {
+        !matches!(
+            self,
+            Command::ConnectOutPorts(_) | Command::ConnectInPort(_) | Command::QueryConnections(_)
+        )
+    }
+}
+
 enum State {
     Connecting(u64),
     Connected {
         client: jack::AsyncClient<Notifications, ProcessHandler>,
         is_server_shutdown: Arc<AtomicBool>,
+        stop: Arc<AtomicBool>,
         command_rx: mpsc::Receiver<Command>,
         process_tx: HeapProd<ProcessHandlerMessage>,
     },
@@ -152,45 +685,41 @@ fn run_audio_backend(sender: mpsc::Sender<Event>) {
                 let is_server_shutdown = Arc::new(AtomicBool::new(false));
                 // TODO: make configurable
                 let volume = Arc::new(AtomicF32::new(0.5));
+                let trim = Arc::new(AtomicF32::new(1.0));
+                let stop = Arc::new(AtomicBool::new(false));
 
                 match start_jack_client(
                     notification_sender,
                     Arc::clone(&volume),
+                    Arc::clone(&trim),
+                    Arc::clone(&stop),
                     Arc::clone(&is_server_shutdown),
                 ) {
                     Ok((client, process_sender)) => {
                         let sample_rate = client.as_client().sample_rate().into();
-
-                        let out_ports = client
-                            .as_client()
-                            .ports(None, Some("32 bit float mono audio"), PortFlags::IS_INPUT)
-                            .into_iter()
-                            .map(OutPort::new)
-                            .collect();
-
-                        let in_ports = client
-                            .as_client()
-                            .ports(None, Some("32 bit float mono audio"), PortFlags::IS_OUTPUT)
-                            .into_iter()
-                            .map(InPort::new)
-                            .collect();
+                        let (in_ports, out_ports) = list_ports(client.as_client());
 
                         let (command_sender, command_receiver) = mpsc::channel(64);
-                        let backend = Backend {
+                        let backend = Jack {
                             sample_rate,
                             in_ports,
                             out_ports,
                             volume,
+                            trim,
+                            stop: Arc::clone(&stop),
                             sender: command_sender,
                         };
-                        let _ = sender
-                            .blocking_send(Event::Ready(backend, Arc::new(notification_receiver)));
+                        let _ = sender.blocking_send(Event::Ready(
+                            Arc::new(backend),
+                            Arc::new(notification_receiver),
+                        ));
 
                         state = State::Connected {
                             client,
                             command_rx: command_receiver,
                             process_tx: process_sender,
                             is_server_shutdown,
+                            stop,
                         };
                     }
                     Err(err) => {
@@ -203,11 +732,20 @@ fn run_audio_backend(sender: mpsc::Sender<Event>) {
                 mut command_rx,
                 mut process_tx,
                 is_server_shutdown,
+                stop,
             } => {
                 while !is_server_shutdown.load(std::sync::atomic::Ordering::Relaxed) {
                     // FIXME: wrong channel type
-                    match command_rx.try_recv() {
-                        Ok(Command::ConnectOutPort(dest)) => {
+                    let command = command_rx.try_recv();
+
+                    // a stop of an earlier playback must not end the new one, a stop
+                    // from now on is kept until the signal reaches the audio thread
+                    if command.as_ref().is_ok_and(Command::is_run) {
+                        stop.store(false, atomic::Ordering::Release);
+                    }
+
+                    match command {
+                        Ok(Command::ConnectOutPorts(dests)) => {
                             let client_name = env!("CARGO_BIN_NAME");
                             let port_name = format!("{client_name}:measurement_out");
 
@@ -215,10 +753,12 @@ fn run_audio_backend(sender: mpsc::Sender<Event>) {
                                 client.as_client().disconnect(&out_port).unwrap();
                             }
 
-                            client
-                                .as_client()
-                                .connect_ports_by_name(&port_name, dest.as_ref())
-                                .unwrap();
+                            for dest in dests {
+                                client
+                                    .as_client()
+                                    .connect_ports_by_name(&port_name, dest.as_ref())
+                                    .unwrap();
+                            }
                         }
                         Ok(Command::ConnectInPort(source)) => {
                             let client_name = env!("CARGO_BIN_NAME");
@@ -232,9 +772,13 @@ fn run_audio_backend(sender: mpsc::Sender<Event>) {
                                 .connect_ports_by_name(source.as_ref(), &port_name)
                                 .unwrap();
                         }
+                        Ok(Command::QueryConnections(respond_to)) => {
+                            let _ = respond_to.send(connections(client.as_client()));
+                        }
                         Ok(Command::RunTest {
                             duration,
                             loudness: sender,
+                            spectrum,
                         }) => {
                             let sample_rate = client.as_client().sample_rate();
                             let signal = raumklang_core::signals::PinkNoise::with_amplitude(0.8)
@@ -248,15 +792,17 @@ fn run_audio_backend(sender: mpsc::Sender<Event>) {
                                 );
 
                             let buf_size = client.as_client().buffer_size() as usize;
-                            let (producer, consumer) = measurement::create(buf_size);
+                            let (producer, consumer) =
+                                measurement::create(buf_size, buf_size, Arc::default());
 
                             let process_msg = ProcessHandlerMessage::Measurement(producer);
                             // TODO refactor
                             let _ = process_tx.try_push(process_msg);
 
-                            let test_process = Test::new(sender);
+                            let test_process = Test::new(sender, sample_rate as usize);
+                            let analyzer = spectrum::Analyzer::new(spectrum, sample_rate);
                             std::thread::spawn(move || {
-                                consumer.run(signal, test_process);
+                                consumer.run(signal, test_process, analyzer);
                             });
                         }
                         Ok(Command::RunMeasurement {
@@ -264,46 +810,44 @@ fn run_audio_backend(sender: mpsc::Sender<Event>) {
                             end_frequency,
                             duration,
                             loudness_sender,
+                            spectrum_sender,
                             data_sender,
+                            capture_buffer,
+                            pre_roll,
+                            dropped_frames,
                         }) => {
                             let sample_rate = client.as_client().sample_rate();
-                            let sweep = raumklang_core::signals::ExponentialSweep::new(
-                                start_frequency.into(),
-                                end_frequency.into(),
-                                0.8,
-                                (duration.as_secs() * sample_rate as u64) as usize,
-                                sample_rate as usize,
-                            );
-
-                            // TODO make window configureable
-                            let left = (sample_rate as f32 * 0.01) as usize;
-                            let right = (sample_rate as f32 * 0.01) as usize;
-                            let offset = sweep.clone().count() - left - right;
-
-                            let window = raumklang_core::WindowBuilder::new(
-                                raumklang_core::Window::Hann,
-                                left,
-                                raumklang_core::Window::Hann,
-                                right,
-                            )
-                            .set_offset(offset)
-                            .build();
-
-                            let sweep = sweep
-                                .into_iter()
-                                .enumerate()
-                                .map(move |(i, s)| s * window[i]);
-
-                            // TODO: make configurable
-                            // NOTE: this adds some silence in front of the sweep
-                            let sweep = (0..22_000)
+                            let rate = data::SampleRate::new(sample_rate);
+                            let pre_roll: usize =
+                                data::Samples::from_duration(pre_roll, rate).into();
+                            let sweep = (0..pre_roll)
                                 .map(|_| 0.0)
-                                .chain(sweep)
-                                .chain((0..20_000).map(|_| 0.0));
+                                .chain(stimulus(
+                                    start_frequency,
+                                    end_frequency,
+                                    duration,
+                                    sample_rate,
+                                ))
+                                .chain((0..decay_tail_len(rate)).map(|_| 0.0));
 
                             let buf_size = client.as_client().buffer_size() as usize;
 
-                            let (producer, consumer) = measurement::create(buf_size);
+                            let (mut producer, consumer) = measurement::create(
+                                buf_size,
+                                capture_buffer.max(buf_size),
+                                dropped_frames,
+                            );
+
+                            let sweep: Vec<_> = sweep.collect();
+                            let true_peak = raumklang_core::loudness::true_peak(&sweep);
+                            if true_peak > 1.0 {
+                                log::warn!(
+                                    "Sweep has a true peak of {:+.2} dBTP at full volume, the output level is limited",
+                                    raumklang_core::dbfs(true_peak)
+                                );
+                            }
+                            producer.limit_true_peak(true_peak);
+
                             let process_msg = ProcessHandlerMessage::Measurement(producer);
 
                             // FIXME: this is experimental
@@ -313,10 +857,159 @@ fn run_audio_backend(sender: mpsc::Sender<Event>) {
                             // TODO: refactor
                             let _ = process_tx.try_push(process_msg);
 
-                            let loudness = loudness::Test::new(loudness_sender);
-                            let measurement = Measurement::new(loudness, data_sender);
+                            let loudness =
+                                loudness::Test::new(loudness_sender, sample_rate as usize);
+                            let measurement = Measurement::new(loudness, data_sender, pre_roll);
+                            let analyzer = spectrum::Analyzer::new(spectrum_sender, sample_rate);
+                            std::thread::spawn(move || {
+                                consumer.run(sweep, measurement, analyzer);
+                            });
+                        }
+                        Ok(Command::RunMovingMic {
+                            period,
+                            duration,
+                            loudness_sender,
+                            average_sender,
+                        }) => {
+                            let sample_rate = client.as_client().sample_rate();
+                            let len: usize = data::Samples::from_duration(
+                                duration,
+                                data::SampleRate::new(sample_rate),
+                            )
+                            .into();
+
+                            let buf_size = client.as_client().buffer_size() as usize;
+                            let capture_buffer =
+                                data::measurement::config::DEFAULT_CAPTURE_BUFFER.max(buf_size);
+                            let (mut producer, consumer) =
+                                measurement::create(buf_size, capture_buffer, Arc::default());
+                            producer.limit_true_peak(raumklang_core::loudness::true_peak(&period));
+
+                            let _ =
+                                process_tx.try_push(ProcessHandlerMessage::Measurement(producer));
+
+                            let loudness = Test::new(loudness_sender, sample_rate as usize);
+                            let averager = moving_mic::Averager::new(
+                                loudness,
+                                &period,
+                                sample_rate,
+                                average_sender,
+                            );
+                            let signal = period.into_iter().cycle().take(len);
+
+                            std::thread::spawn(move || {
+                                consumer.run(signal, averager, process::Discard);
+                            });
+                        }
+                        Ok(Command::RunSignal {
+                            signal,
+                            loudness_sender,
+                            data_sender,
+                        }) => {
+                            let sample_rate = client.as_client().sample_rate();
+                            let buf_size = client.as_client().buffer_size() as usize;
+                            let capture_buffer =
+                                data::measurement::config::DEFAULT_CAPTURE_BUFFER.max(buf_size);
+                            let (mut producer, consumer) =
+                                measurement::create(buf_size, capture_buffer, Arc::default());
+                            producer.limit_true_peak(raumklang_core::loudness::true_peak(&signal));
+
+                            let _ =
+                                process_tx.try_push(ProcessHandlerMessage::Measurement(producer));
+
+                            let loudness = Test::new(loudness_sender, sample_rate as usize);
+                            let recording = Measurement::new(loudness, data_sender, 0);
+
+                            std::thread::spawn(move || {
+                                consumer.run(signal, recording, process::Discard);
+                            });
+                        }
+                        Ok(Command::RunTransferFunction {
+                            loudness_sender,
+                            transfer_function_sender,
+                        }) => {
+                            let sample_rate = client.as_client().sample_rate();
+                            let buf_size = client.as_client().buffer_size() as usize;
+                            let capture_buffer =
+                                data::measurement::config::DEFAULT_CAPTURE_BUFFER.max(buf_size);
+                            let (producer, consumer) =
+                                measurement::create(buf_size, capture_buffer, Arc::default());
+
+                            let _ =
+                                process_tx.try_push(ProcessHandlerMessage::Measurement(producer));
+
+                            let loudness = Test::new(loudness_sender, sample_rate as usize);
+                            let (analyzer, reference) = transfer_function::analyzer(
+                                loudness,
+                                sample_rate,
+                                transfer_function_sender,
+                            );
+                            let signal = raumklang_core::signals::PinkNoise::with_amplitude(0.8);
+
+                            std::thread::spawn(move || {
+                                consumer.run(signal, analyzer, reference);
+                            });
+                        }
+                        Ok(Command::RunRta {
+                            fraction,
+                            play_noise,
+                            loudness_sender,
+                            rta_sender,
+                        }) => {
+                            let sample_rate = client.as_client().sample_rate();
+                            let buf_size = client.as_client().buffer_size() as usize;
+                            let capture_buffer =
+                                data::measurement::config::DEFAULT_CAPTURE_BUFFER.max(buf_size);
+                            let (producer, consumer) =
+                                measurement::create(buf_size, capture_buffer, Arc::default());
+
+                            let _ =
+                                process_tx.try_push(ProcessHandlerMessage::Measurement(producer));
+
+                            // the same meter as in the loudness test
+                            let loudness = Test::new(loudness_sender, sample_rate as usize);
+                            let analyzer =
+                                rta::Analyzer::new(loudness, fraction, sample_rate, rta_sender);
+                            let noise = raumklang_core::signals::PinkNoise::with_amplitude(0.8);
+                            let signal: Box<dyn Iterator<Item = f32> + Send> = if play_noise {
+                                Box::new(noise)
+                            } else {
+                                Box::new(std::iter::repeat(0.0))
+                            };
+
+                            std::thread::spawn(move || {
+                                consumer.run(signal, analyzer, process::Discard);
+                            });
+                        }
+                        Ok(Command::RunSpl {
+                            weighting,
+                            time_weighting,
+                            calibration,
+                            loudness_sender,
+                            record_sender,
+                        }) => {
+                            let sample_rate = client.as_client().sample_rate();
+                            let buf_size = client.as_client().buffer_size() as usize;
+                            let capture_buffer =
+                                data::measurement::config::DEFAULT_CAPTURE_BUFFER.max(buf_size);
+                            let (producer, consumer) =
+                                measurement::create(buf_size, capture_buffer, Arc::default());
+
+                            let _ =
+                                process_tx.try_push(ProcessHandlerMessage::Measurement(producer));
+
+                            let loudness = Test::new(loudness_sender, sample_rate as usize);
+                            let logger = spl::Logger::new(
+                                loudness,
+                                weighting,
+                                time_weighting,
+                                calibration,
+                                sample_rate,
+                                record_sender,
+                            );
+
                             std::thread::spawn(move || {
-                                consumer.run(sweep, measurement);
+                                consumer.run(std::iter::repeat(0.0), logger, process::Discard);
                             });
                         }
                         Err(TryRecvError::Disconnected) => {
@@ -369,9 +1062,98 @@ fn run_audio_backend(sender: mpsc::Sender<Event>) {
     }
 }
 
+/// Describes the state of the audio server, e.g. for bug reports.
+pub fn diagnostics() -> String {
+    match probe() {
+        Ok(probe) => format!(
+            "JACK: running at {} Hz, buffer size {} frames, {} capture and {} playback ports",
+            probe.sample_rate,
+            probe.buffer_size,
+            probe.in_ports.len(),
+            probe.out_ports.len()
+        ),
+        Err(err) => format!("JACK: not available, {err}"),
+    }
+}
+
+/// State of the audio server, as seen by a temporary client.
+#[derive(Debug, Clone)]
+pub struct Probe {
+    pub sample_rate: u32,
+    pub buffer_size: u32,
+    pub in_ports: Vec<InPort>,
+    pub out_ports: Vec<OutPort>,
+}
+
+/// Connects a temporary client to the audio server, without starting one.
+pub fn probe() -> Result<Probe, Error> {
+    let (client, _status) = jack::Client::new(
+        "raumklang_diagnostics",
+        jack::ClientOptions::NO_START_SERVER,
+    )?;
+
+    let (in_ports, out_ports) = list_ports(&client);
+
+    Ok(Probe {
+        sample_rate: client.sample_rate(),
+        buffer_size: client.buffer_size(),
+        in_ports,
+        out_ports,
+    })
+}
+
+fn list_ports(client: &jack::Client) -> (Vec<InPort>, Vec<OutPort>) {
+    let in_ports = client
+        .ports(None, Some("32 bit float mono audio"), PortFlags::IS_OUTPUT)
+        .into_iter()
+        .map(InPort::new)
+        .collect();
+
+    let out_ports = client
+        .ports(None, Some("32 bit float mono audio"), PortFlags::IS_INPUT)
+        .into_iter()
+        .map(OutPort::new)
+        .collect();
+
+    (in_ports, out_ports)
+}
+
+fn connections(client: &jack::Client) -> Connections {
+    let client_name = env!("CARGO_BIN_NAME");
+    let out_port = format!("{client_name}:measurement_out");
+    let in_port = format!("{client_name}:measurement_in");
+
+    let connected_to = |name: &str| {
+        client
+            .port_by_name(name)
+            .map(|port| port.get_connections())
+            .unwrap_or_default()
+    };
+
+    let playback = connected_to(&out_port)
+        .into_iter()
+        .map(|port| {
+            let shared_with = connected_to(&port)
+                .into_iter()
+                .filter(|source| *source != out_port)
+                .collect();
+
+            Playback { port, shared_with }
+        })
+        .collect();
+
+    Connections {
+        playback,
+        capture: connected_to(&in_port),
+        out_port,
+    }
+}
+
 fn start_jack_client(
     notify_sender: mpsc::Sender<Notification>,
     volume: Arc<AtomicF32>,
+    trim: Arc<AtomicF32>,
+    stop: Arc<AtomicBool>,
     has_server_shutdown: Arc<AtomicBool>,
 ) -> Result<
     (
@@ -386,10 +1168,12 @@ fn start_jack_client(
     // TODO: make configureable
     let out_port = client.register_port("measurement_out", jack::AudioOut::default())?;
     let in_port = client.register_port("measurement_in", jack::AudioIn::default())?;
+    let trigger_port = client.register_port("trigger_in", jack::MidiIn::default())?;
 
     let out_port_name = out_port.name()?.to_string();
     let in_port_name = in_port.name()?.to_string();
 
+    let trigger_sender = notify_sender.clone();
     let notification_handler = Notifications::new(
         in_port_name,
         out_port_name,
@@ -397,7 +1181,15 @@ fn start_jack_client(
         has_server_shutdown,
     );
 
-    let (process_handler, process_sender) = ProcessHandler::new(out_port, in_port, volume);
+    let (process_handler, process_sender) = ProcessHandler::new(
+        out_port,
+        in_port,
+        trigger_port,
+        trigger_sender,
+        volume,
+        trim,
+        stop,
+    );
     let client = client.activate_async(notification_handler, process_handler)?;
 
     Ok((client, process_sender))
@@ -406,7 +1198,11 @@ fn start_jack_client(
 struct ProcessHandler {
     out_port: jack::Port<jack::AudioOut>,
     in_port: jack::Port<jack::AudioIn>,
+    trigger_port: jack::Port<jack::MidiIn>,
+    trigger_sender: mpsc::Sender<Notification>,
     volume: Arc<AtomicF32>,
+    trim: Arc<AtomicF32>,
+    stop: Arc<AtomicBool>,
 
     msg_receiver: HeapCons<ProcessHandlerMessage>,
 
@@ -428,7 +1224,11 @@ impl ProcessHandler {
     fn new(
         out_port: jack::Port<jack::AudioOut>,
         in_port: jack::Port<jack::AudioIn>,
+        trigger_port: jack::Port<jack::MidiIn>,
+        trigger_sender: mpsc::Sender<Notification>,
         volume: Arc<AtomicF32>,
+        trim: Arc<AtomicF32>,
+        stop: Arc<AtomicBool>,
     ) -> (Self, HeapProd<ProcessHandlerMessage>) {
         let (msg_sender, msg_receiver) = HeapRb::new(32).split();
 
@@ -436,7 +1236,11 @@ impl ProcessHandler {
             Self {
                 out_port,
                 in_port,
+                trigger_port,
+                trigger_sender,
                 volume,
+                trim,
+                stop,
 
                 msg_receiver,
                 state: ProcessHandlerState::Idle,
@@ -447,7 +1251,11 @@ impl ProcessHandler {
 }
 
 impl jack::ProcessHandler for ProcessHandler {
-    fn process(&mut self, _: &jack::Client, process_scope: &jack::ProcessScope) -> jack::Control {
+    fn process(
+        &mut self,
+        client: &jack::Client,
+        process_scope: &jack::ProcessScope,
+    ) -> jack::Control {
         if let Some(msg) = self.msg_receiver.try_pop() {
             match msg {
                 ProcessHandlerMessage::Measurement(producer) => {
@@ -456,6 +1264,22 @@ impl jack::ProcessHandler for ProcessHandler {
             }
         }
 
+        // NOTE: only a playing signal consumes the stop, otherwise it would be
+        // lost, while the signal is still on its way to the audio thread
+        if let ProcessHandlerState::Measurement(producer) = &mut self.state
+            && self.stop.swap(false, atomic::Ordering::AcqRel)
+        {
+            let len = client.sample_rate() as f32 * raumklang_core::FADE_OUT.as_secs_f32();
+            producer.fade_out(len as usize);
+        }
+
+        for event in self.trigger_port.iter(process_scope) {
+            if let Some(trigger) = remote::Trigger::from_midi(event.bytes) {
+                // never block the audio thread, triggers are dropped if the UI lags behind
+                let _ = self.trigger_sender.try_send(Notification::Trigger(trigger));
+            }
+        }
+
         let state = std::mem::take(&mut self.state);
         let out_port = self.out_port.as_mut_slice(process_scope);
         self.state = match state {
@@ -470,7 +1294,9 @@ impl jack::ProcessHandler for ProcessHandler {
                 let _ = producer.record_chunk(chunk);
 
                 let volume = self.volume.load(atomic::Ordering::Acquire);
-                let amplitude = raumklang_core::volume_to_amplitude(volume);
+                let trim = self.trim.load(atomic::Ordering::Acquire);
+                // the true peak limit of the producer also covers positive trims
+                let amplitude = raumklang_core::volume_to_amplitude(volume) * trim;
 
                 match producer.play_signal_chunk(out_port, amplitude) {
                     Some(measurement::SignalState::NotExhausted) => {
@@ -528,7 +1354,22 @@ impl jack::NotificationHandler for Notifications {
 
     fn client_registration(&mut self, _: &jack::Client, _name: &str, _is_reg: bool) {}
 
-    fn port_registration(&mut self, _: &jack::Client, _port_id: jack::PortId, _is_reg: bool) {}
+    fn port_registration(&mut self, client: &jack::Client, port_id: jack::PortId, is_reg: bool) {
+        let (mut in_ports, mut out_ports) = list_ports(client);
+
+        // the unregistered port might still be listed at this point
+        if !is_reg && let Some(name) = client.port_by_id(port_id).and_then(|p| p.name().ok()) {
+            in_ports.retain(|port| port.as_ref() != name);
+            out_ports.retain(|port| port.as_ref() != name);
+        }
+
+        let _ = self
+            .notification_sender
+            .blocking_send(Notification::PortsChanged {
+                in_ports,
+                out_ports,
+            });
+    }
 
     fn port_rename(
         &mut self,
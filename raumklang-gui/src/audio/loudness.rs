@@ -29,12 +29,11 @@ pub struct Test {
 }
 
 impl Test {
-    pub fn new(sender: tokio::sync::mpsc::Sender<Loudness>) -> Self {
+    pub fn new(sender: tokio::sync::mpsc::Sender<Loudness>, sample_rate: usize) -> Self {
         let last_rms = Instant::now();
         let last_peak = Instant::now();
 
-        // FIXME hardcoded sample rate dependency
-        let meter = loudness::Meter::new(13230); // 44100samples / 1000ms * 300ms
+        let meter = loudness::Meter::with_sample_rate(sample_rate);
 
         Self {
             last_rms,
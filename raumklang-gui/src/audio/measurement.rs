@@ -10,14 +10,23 @@ use ringbuf::{
 use std::{
     sync::{
         Arc,
-        atomic::{self, AtomicBool},
+        atomic::{self, AtomicBool, AtomicUsize},
     },
     time::Duration,
 };
 
-pub fn create(buf_size: usize) -> (Producer, Consumer) {
+/// Creates the buffers between the real-time thread and the processing
+/// thread. Recorded frames that don't fit into the capture buffer are counted
+/// in `dropped_frames`. Played frames that don't fit into the monitor buffer
+/// are discarded.
+pub fn create(
+    buf_size: usize,
+    capture_size: usize,
+    dropped_frames: Arc<AtomicUsize>,
+) -> (Producer, Consumer) {
     let (signal_prod, signal_cons) = HeapRb::new(buf_size).split();
-    let (recording_prod, recording_cons) = HeapRb::new(buf_size).split();
+    let (recording_prod, recording_cons) = HeapRb::new(capture_size).split();
+    let (monitor_prod, monitor_cons) = HeapRb::new(MONITOR_SIZE.max(buf_size)).split();
 
     let state = State {
         signal_exhausted: AtomicBool::new(false),
@@ -29,27 +38,44 @@ pub fn create(buf_size: usize) -> (Producer, Consumer) {
     let producer = Producer {
         signal_cons,
         recording_prod,
+        monitor_prod,
+        dropped_frames,
+        max_amplitude: 1.0,
+        fade: None,
         state: Arc::clone(&state),
     };
 
     let consumer = Consumer {
         signal_prod,
         recording_cons,
+        monitor_cons,
         state,
     };
 
     (producer, consumer)
 }
 
+/// Size of the buffer holding the played signal for monitoring.
+const MONITOR_SIZE: usize = 16_384;
+
 pub struct Producer {
     signal_cons: HeapCons<f32>,
     pub recording_prod: HeapProd<f32>,
+    /// The signal as it is written to the output port.
+    monitor_prod: HeapProd<f32>,
+    dropped_frames: Arc<AtomicUsize>,
+    /// Upper bound of the amplitude, the signal is played with.
+    max_amplitude: f32,
+    /// Remaining and total samples of the fade out, after the playback was
+    /// stopped.
+    fade: Option<(usize, usize)>,
     state: Arc<State>,
 }
 
 pub struct Consumer {
     signal_prod: HeapProd<f32>,
     recording_cons: HeapCons<f32>,
+    monitor_cons: HeapCons<f32>,
     state: Arc<State>,
 }
 
@@ -60,12 +86,49 @@ pub struct State {
 }
 
 impl Producer {
+    /// Limits the amplitude the signal is played with, so that the true peak
+    /// of the signal stays below 0 dBFS, see [`raumklang_core::loudness::true_peak`].
+    pub fn limit_true_peak(&mut self, true_peak: f32) {
+        if true_peak > 0.0 {
+            self.max_amplitude = (1.0 / true_peak).min(1.0);
+        }
+    }
+
+    /// Ramps the output to zero within `len` samples, the signal is fully
+    /// consumed afterwards.
+    pub fn fade_out(&mut self, len: usize) {
+        if self.fade.is_none() {
+            self.fade = Some((len.max(1), len.max(1)));
+        }
+    }
+
     #[must_use]
     pub fn play_signal_chunk(
         &mut self,
         out_port: &mut [f32],
         amplitude: f32,
     ) -> Option<SignalState> {
+        let amplitude = amplitude.min(self.max_amplitude);
+
+        // the fade out continues, even if the consumer is gone already
+        if let Some((remaining, len)) = self.fade.as_mut() {
+            let mut signal = self.signal_cons.pop_iter();
+            for o in out_port.iter_mut() {
+                let gain = *remaining as f32 / *len as f32;
+                *o = signal.next().unwrap_or_default() * amplitude * gain;
+                *remaining = remaining.saturating_sub(1);
+            }
+            drop(signal);
+
+            let _ = self.monitor_prod.push_slice(out_port);
+
+            return Some(if *remaining == 0 {
+                SignalState::FullyConsumed
+            } else {
+                SignalState::Exhausted
+            });
+        }
+
         let mut write_signal = || {
             let mut signal = self.signal_cons.pop_iter();
             let mut buf_empty = false;
@@ -81,21 +144,26 @@ impl Producer {
             buf_empty
         };
 
-        if self.state.consumer_dropped.load(atomic::Ordering::Acquire) {
+        let state = if self.state.consumer_dropped.load(atomic::Ordering::Acquire) {
             out_port.fill(0.0);
-            None
+            return None;
         } else if self.state.signal_exhausted.load(atomic::Ordering::Acquire) {
             let buf_empty = write_signal();
 
             if buf_empty {
-                Some(SignalState::FullyConsumed)
+                SignalState::FullyConsumed
             } else {
-                Some(SignalState::Exhausted)
+                SignalState::Exhausted
             }
         } else {
             write_signal();
-            Some(SignalState::NotExhausted)
-        }
+            SignalState::NotExhausted
+        };
+
+        // monitoring is optional, dropping samples is fine here
+        let _ = self.monitor_prod.push_slice(out_port);
+
+        Some(state)
     }
 
     pub fn record_chunk(&mut self, chunk: &[f32]) -> Result<(), Error> {
@@ -103,7 +171,11 @@ impl Producer {
             return Err(Error::ConsumerDropped);
         }
 
-        self.recording_prod.push_slice(chunk);
+        let pushed = self.recording_prod.push_slice(chunk);
+        if pushed < chunk.len() {
+            self.dropped_frames
+                .fetch_add(chunk.len() - pushed, atomic::Ordering::Relaxed);
+        }
 
         Ok(())
     }
@@ -128,10 +200,13 @@ impl Drop for Producer {
 }
 
 impl Consumer {
-    pub fn run<S, P>(mut self, signal: S, mut processor: P)
+    /// Feeds the recorded data to `processor` and the played signal to
+    /// `monitor`, until the signal is played and all data is processed.
+    pub fn run<S, P, M>(mut self, signal: S, mut processor: P, mut monitor: M)
     where
         S: IntoIterator<Item = f32>,
         P: Process,
+        M: Process,
     {
         let mut signal = signal.into_iter().peekable();
 
@@ -159,6 +234,11 @@ impl Consumer {
                 break;
             }
 
+            let played: Vec<f32> = self.monitor_cons.pop_iter().collect();
+            // NOTE: the monitor is optional, stopping here would truncate
+            // the recording
+            let _ = monitor.process(&played);
+
             // FIXME: calculate sleep duration from buf size and sample_rate
             std::thread::sleep(Duration::from_millis(10));
         }
@@ -176,28 +256,41 @@ impl Drop for Consumer {
 pub struct Measurement {
     loudness: loudness::Test,
     data_sender: tokio::sync::mpsc::Sender<Box<[f32]>>,
+    /// Remaining samples of the pre-roll, they are not recorded.
+    pre_roll: usize,
 }
 
 impl Measurement {
     pub fn new(
         loudness: loudness::Test,
         data_sender: tokio::sync::mpsc::Sender<Box<[f32]>>,
+        pre_roll: usize,
     ) -> Self {
         Self {
             loudness,
             data_sender,
+            pre_roll,
         }
     }
 }
 
 impl Process for Measurement {
     fn process(&mut self, data: &[f32]) -> Control {
-        if let Control::Stop = self.loudness.process(data) {
-            return Control::Stop;
-        }
-
-        if let Err(err) = self.data_sender.try_send(data.to_vec().into_boxed_slice()) {
+        let skip = self.pre_roll.min(data.len());
+        self.pre_roll -= skip;
+        let data = &data[skip..];
+
+        // NOTE: the loudness meter is optional, stopping here would truncate
+        // the recording
+        let _ = self.loudness.process(data);
+
+        // block instead of dropping chunks, otherwise samples get lost
+        if let Err(err) = self
+            .data_sender
+            .blocking_send(data.to_vec().into_boxed_slice())
+        {
             log::error!("failed to send measurement data to UI {err}");
+            return Control::Stop;
         }
 
         Control::Continue
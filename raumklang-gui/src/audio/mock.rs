@@ -0,0 +1,260 @@
+//! Backend without an audio server, it records what the screens ask for, so
+//! that their state machines can be tested.
+
+use super::{Average, Backend, Event, Loudness, Notification, Rta, Spectrum, TransferFunction};
+
+use crate::data::{
+    self,
+    audio::{Connections, InPort, OutPort, Trim},
+};
+
+use iced::futures::{FutureExt, future::BoxFuture};
+use raumklang_core::spl;
+use tokio::sync::mpsc;
+
+use std::{
+    any::Any,
+    sync::{Arc, Mutex, atomic::AtomicUsize},
+    time::Duration,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Call {
+    RunTest,
+    RunMeasurement,
+    RunMovingMic,
+    RunSignal(usize),
+    RunTransferFunction,
+    RunRta {
+        fraction: u8,
+        play_noise: bool,
+    },
+    RunSpl {
+        weighting: spl::Weighting,
+        time_weighting: spl::TimeWeighting,
+        calibration: f32,
+    },
+    ConnectOutPort(OutPort),
+    ConnectOutPorts(Vec<OutPort>),
+    ConnectInPort(InPort),
+    Connections,
+    SetVolume(f32),
+    SetTrim(Trim),
+    Stop,
+}
+
+#[derive(Debug)]
+pub struct Mock {
+    in_ports: Vec<InPort>,
+    out_ports: Vec<OutPort>,
+    calls: Mutex<Vec<Call>>,
+    /// Senders of the started runs, they are kept so that the streams of
+    /// the screens stay open.
+    senders: Mutex<Vec<Box<dyn Any + Send>>>,
+}
+
+impl Mock {
+    pub const SAMPLE_RATE: u32 = 48_000;
+
+    pub fn new(in_ports: Vec<InPort>, out_ports: Vec<OutPort>) -> Arc<Self> {
+        Arc::new(Self {
+            in_ports,
+            out_ports,
+            calls: Mutex::new(vec![]),
+            senders: Mutex::new(vec![]),
+        })
+    }
+
+    /// The event the screens receive, when the backend got ready. The
+    /// returned sender delivers the notifications.
+    pub fn ready(self: &Arc<Self>) -> (Event, mpsc::Sender<Notification>) {
+        let (sender, receiver) = mpsc::channel(16);
+
+        (Event::Ready(self.clone(), Arc::new(receiver)), sender)
+    }
+
+    /// Takes the calls that were made since the last time.
+    pub fn calls(&self) -> Vec<Call> {
+        std::mem::take(&mut self.calls.lock().unwrap())
+    }
+
+    fn record(&self, call: Call) {
+        self.calls.lock().unwrap().push(call)
+    }
+
+    fn keep<T: Send + 'static>(&self, sender: mpsc::Sender<T>) {
+        self.senders.lock().unwrap().push(Box::new(sender))
+    }
+}
+
+impl Backend for Mock {
+    fn sample_rate(&self) -> data::SampleRate {
+        data::SampleRate::new(Self::SAMPLE_RATE)
+    }
+
+    fn in_ports(&self) -> &[InPort] {
+        &self.in_ports
+    }
+
+    fn out_ports(&self) -> &[OutPort] {
+        &self.out_ports
+    }
+
+    fn run_test(
+        &self,
+        _duration: Duration,
+    ) -> (mpsc::Receiver<Loudness>, mpsc::Receiver<Spectrum>) {
+        self.record(Call::RunTest);
+
+        let (loudness_sender, loudness_receiver) = mpsc::channel(1);
+        let (spectrum_sender, spectrum_receiver) = mpsc::channel(1);
+        self.keep(loudness_sender);
+        self.keep(spectrum_sender);
+
+        (loudness_receiver, spectrum_receiver)
+    }
+
+    fn run_measurement(
+        &self,
+        _config: data::measurement::SignalConfig,
+        _capture_buffer: usize,
+        _pre_roll: Duration,
+    ) -> (
+        mpsc::Receiver<Loudness>,
+        mpsc::Receiver<Spectrum>,
+        mpsc::Receiver<Box<[f32]>>,
+        Arc<AtomicUsize>,
+    ) {
+        self.record(Call::RunMeasurement);
+
+        let (loudness_sender, loudness_receiver) = mpsc::channel(1);
+        let (spectrum_sender, spectrum_receiver) = mpsc::channel(1);
+        let (data_sender, data_receiver) = mpsc::channel(1);
+        self.keep(loudness_sender);
+        self.keep(spectrum_sender);
+        self.keep(data_sender);
+
+        (
+            loudness_receiver,
+            spectrum_receiver,
+            data_receiver,
+            Arc::new(AtomicUsize::new(0)),
+        )
+    }
+
+    fn run_moving_mic(
+        &self,
+        _period: Vec<f32>,
+        _duration: Duration,
+    ) -> (mpsc::Receiver<Loudness>, mpsc::Receiver<Average>) {
+        self.record(Call::RunMovingMic);
+
+        let (loudness_sender, loudness_receiver) = mpsc::channel(1);
+        let (average_sender, average_receiver) = mpsc::channel(1);
+        self.keep(loudness_sender);
+        self.keep(average_sender);
+
+        (loudness_receiver, average_receiver)
+    }
+
+    fn run_signal(
+        &self,
+        signal: Vec<f32>,
+    ) -> (mpsc::Receiver<Loudness>, mpsc::Receiver<Box<[f32]>>) {
+        self.record(Call::RunSignal(signal.len()));
+
+        let (loudness_sender, loudness_receiver) = mpsc::channel(1);
+        let (data_sender, data_receiver) = mpsc::channel(1);
+        self.keep(loudness_sender);
+        self.keep(data_sender);
+
+        (loudness_receiver, data_receiver)
+    }
+
+    fn connect_out_port(&self, dest: OutPort) -> BoxFuture<'static, ()> {
+        self.record(Call::ConnectOutPort(dest));
+
+        async {}.boxed()
+    }
+
+    fn run_transfer_function(
+        &self,
+    ) -> (mpsc::Receiver<Loudness>, mpsc::Receiver<TransferFunction>) {
+        self.record(Call::RunTransferFunction);
+
+        let (loudness_sender, loudness_receiver) = mpsc::channel(1);
+        let (transfer_function_sender, transfer_function_receiver) = mpsc::channel(1);
+        self.keep(loudness_sender);
+        self.keep(transfer_function_sender);
+
+        (loudness_receiver, transfer_function_receiver)
+    }
+
+    fn run_rta(
+        &self,
+        fraction: u8,
+        play_noise: bool,
+    ) -> (mpsc::Receiver<Loudness>, mpsc::Receiver<Rta>) {
+        self.record(Call::RunRta {
+            fraction,
+            play_noise,
+        });
+
+        let (loudness_sender, loudness_receiver) = mpsc::channel(1);
+        let (rta_sender, rta_receiver) = mpsc::channel(1);
+        self.keep(loudness_sender);
+        self.keep(rta_sender);
+
+        (loudness_receiver, rta_receiver)
+    }
+
+    fn run_spl(
+        &self,
+        weighting: spl::Weighting,
+        time_weighting: spl::TimeWeighting,
+        calibration: f32,
+    ) -> (mpsc::Receiver<Loudness>, mpsc::Receiver<spl::Record>) {
+        self.record(Call::RunSpl {
+            weighting,
+            time_weighting,
+            calibration,
+        });
+
+        let (loudness_sender, loudness_receiver) = mpsc::channel(1);
+        let (record_sender, record_receiver) = mpsc::channel(1);
+        self.keep(loudness_sender);
+        self.keep(record_sender);
+
+        (loudness_receiver, record_receiver)
+    }
+
+    fn connect_out_ports(&self, dests: Vec<OutPort>) -> BoxFuture<'static, ()> {
+        self.record(Call::ConnectOutPorts(dests));
+
+        async {}.boxed()
+    }
+
+    fn connect_in_port(&self, src: InPort) -> BoxFuture<'static, ()> {
+        self.record(Call::ConnectInPort(src));
+
+        async {}.boxed()
+    }
+
+    fn connections(&self) -> BoxFuture<'static, Option<Connections>> {
+        self.record(Call::Connections);
+
+        async { Some(Connections::default()) }.boxed()
+    }
+
+    fn set_volume(&self, volume: f32) {
+        self.record(Call::SetVolume(volume))
+    }
+
+    fn set_trim(&self, trim: Trim) {
+        self.record(Call::SetTrim(trim))
+    }
+
+    fn stop(&self) {
+        self.record(Call::Stop)
+    }
+}
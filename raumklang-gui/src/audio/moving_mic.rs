@@ -0,0 +1,104 @@
+use crate::data::{self, curve::Curve};
+
+use raumklang_core::moving_mic;
+use tokio::sync::mpsc::error::TrySendError;
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use super::{Process, loudness, process::Control};
+
+const UPDATE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The averaged response of a moving microphone measurement so far.
+#[derive(Debug, Clone)]
+pub struct Average {
+    pub periods: usize,
+    /// Frequency resolution in Hz
+    pub resolution: f32,
+    /// Level in dB per bin, relative to the played noise
+    pub levels: Arc<[Option<f32>]>,
+}
+
+impl Average {
+    /// The response as (frequency in Hz, level in dB) pairs from 20 Hz on,
+    /// smoothed with 1/12 octave and corrected by the microphone
+    /// `calibration`.
+    pub fn points(&self, calibration: Option<&Curve>) -> Vec<(f32, f32)> {
+        let magnitudes: Vec<f32> = self
+            .levels
+            .iter()
+            .map(|level| level.map_or(0.0, raumklang_core::db_to_gain))
+            .collect();
+
+        let smoothed = data::smooth_fractional_octave(&magnitudes, 12);
+        let max_frequency = (smoothed.len().saturating_sub(1)) as f32 * self.resolution;
+
+        (0..)
+            .map(|i| 20.0 * 2f32.powf(i as f32 / 48.0))
+            .take_while(|frequency| *frequency < max_frequency)
+            .map(|frequency| {
+                let bin = (frequency / self.resolution).round() as usize;
+                let level = raumklang_core::dbfs(smoothed[bin]);
+                let correction = calibration.map_or(0.0, |curve| curve.level_at(frequency));
+
+                (frequency, level - correction)
+            })
+            .collect()
+    }
+}
+
+/// Feeds the recording into the average and reports it periodically.
+pub struct Averager {
+    loudness: loudness::Test,
+    average: moving_mic::Average,
+    sample_rate: u32,
+    last_update: Instant,
+    sender: tokio::sync::mpsc::Sender<Average>,
+}
+
+impl Averager {
+    pub fn new(
+        loudness: loudness::Test,
+        period: &[f32],
+        sample_rate: u32,
+        sender: tokio::sync::mpsc::Sender<Average>,
+    ) -> Self {
+        Self {
+            loudness,
+            average: moving_mic::Average::new(period),
+            sample_rate,
+            last_update: Instant::now(),
+            sender,
+        }
+    }
+}
+
+impl Process for Averager {
+    fn process(&mut self, data: &[f32]) -> Control {
+        // NOTE: the loudness meter is optional
+        let _ = self.loudness.process(data);
+
+        self.average.push(data);
+
+        if self.last_update.elapsed() < UPDATE_INTERVAL {
+            return Control::Continue;
+        }
+
+        self.last_update = Instant::now();
+
+        let average = Average {
+            periods: self.average.periods(),
+            resolution: self.average.frequency_resolution(self.sample_rate),
+            levels: self.average.magnitude().into(),
+        };
+
+        match self.sender.try_send(average) {
+            Ok(_) | Err(TrySendError::Full(_)) => Control::Continue,
+            // stopped by the user
+            Err(TrySendError::Closed(_)) => Control::Stop,
+        }
+    }
+}
@@ -7,3 +7,12 @@ pub enum Control {
     Continue,
     Stop,
 }
+
+/// Ignores the data, e.g. if the played signal is not monitored.
+pub struct Discard;
+
+impl Process for Discard {
+    fn process(&mut self, _data: &[f32]) -> Control {
+        Control::Continue
+    }
+}
@@ -0,0 +1,73 @@
+use raumklang_core::BandLevel;
+use tokio::sync::mpsc::error::TrySendError;
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use super::{Process, loudness, process::Control};
+
+const FFT_SIZE: usize = 16_384;
+const AVERAGES: usize = 8;
+const UPDATE_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Levels of the recording in fractional octave bands.
+#[derive(Debug, Clone)]
+pub struct Rta {
+    pub bands: Arc<[BandLevel]>,
+}
+
+/// Feeds the recording into the real-time analyzer, besides the loudness
+/// meter, and reports the band levels periodically.
+pub struct Analyzer {
+    loudness: loudness::Test,
+    rta: raumklang_core::Rta,
+    fraction: u8,
+    sample_rate: u32,
+    last_update: Instant,
+    sender: tokio::sync::mpsc::Sender<Rta>,
+}
+
+impl Analyzer {
+    pub fn new(
+        loudness: loudness::Test,
+        fraction: u8,
+        sample_rate: u32,
+        sender: tokio::sync::mpsc::Sender<Rta>,
+    ) -> Self {
+        Self {
+            loudness,
+            rta: raumklang_core::Rta::new(FFT_SIZE, AVERAGES),
+            fraction,
+            sample_rate,
+            last_update: Instant::now(),
+            sender,
+        }
+    }
+}
+
+impl Process for Analyzer {
+    fn process(&mut self, data: &[f32]) -> Control {
+        // NOTE: the loudness meter is optional
+        let _ = self.loudness.process(data);
+
+        self.rta.push(data);
+
+        if self.last_update.elapsed() < UPDATE_INTERVAL {
+            return Control::Continue;
+        }
+
+        self.last_update = Instant::now();
+
+        let rta = Rta {
+            bands: self.rta.bands(self.fraction, self.sample_rate).into(),
+        };
+
+        match self.sender.try_send(rta) {
+            Ok(_) | Err(TrySendError::Full(_)) => Control::Continue,
+            // stopped by the user
+            Err(TrySendError::Closed(_)) => Control::Stop,
+        }
+    }
+}
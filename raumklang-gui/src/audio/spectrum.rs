@@ -0,0 +1,132 @@
+use raumklang_core::dbfs;
+
+use rustfft::{Fft, FftPlanner, num_complex::Complex32};
+use tokio::sync::mpsc::error::TrySendError;
+
+use std::{
+    collections::VecDeque,
+    f32::consts::PI,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use super::{Process, process::Control};
+
+const FFT_LEN: usize = 4096;
+const UPDATE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Magnitude spectrum of the played signal in dBFS, a full scale sine
+/// shows up with 0 dBFS.
+#[derive(Debug, Clone)]
+pub struct Spectrum {
+    pub sample_rate: u32,
+    pub levels: Arc<[f32]>,
+}
+
+impl Spectrum {
+    /// Frequency of the bin with the given index.
+    pub fn frequency(&self, bin: usize) -> f32 {
+        bin as f32 * self.sample_rate as f32 / FFT_LEN as f32
+    }
+}
+
+/// Computes the spectrum of the last [`FFT_LEN`] samples periodically.
+pub struct Analyzer {
+    sample_rate: u32,
+    samples: VecDeque<f32>,
+    window: Vec<f32>,
+    fft: Arc<dyn Fft<f32>>,
+    last_update: Instant,
+    sender: tokio::sync::mpsc::Sender<Spectrum>,
+}
+
+impl Analyzer {
+    pub fn new(sender: tokio::sync::mpsc::Sender<Spectrum>, sample_rate: u32) -> Self {
+        let window = (0..FFT_LEN)
+            .map(|n| 0.5 - 0.5 * (2.0 * PI * n as f32 / FFT_LEN as f32).cos())
+            .collect();
+
+        let fft = FftPlanner::new().plan_fft_forward(FFT_LEN);
+
+        Self {
+            sample_rate,
+            samples: VecDeque::with_capacity(FFT_LEN),
+            window,
+            fft,
+            last_update: Instant::now(),
+            sender,
+        }
+    }
+
+    fn spectrum(&self) -> Spectrum {
+        let mut buffer: Vec<_> = self
+            .samples
+            .iter()
+            .zip(&self.window)
+            .map(|(s, w)| Complex32::new(s * w, 0.0))
+            .collect();
+
+        self.fft.process(&mut buffer);
+
+        // coherent gain of the window and the energy of the negative frequencies
+        let scale = 2.0 / self.window.iter().sum::<f32>();
+        let levels = buffer[..FFT_LEN / 2 + 1]
+            .iter()
+            .map(|c| dbfs(c.norm() * scale))
+            .collect();
+
+        Spectrum {
+            sample_rate: self.sample_rate,
+            levels,
+        }
+    }
+}
+
+impl Process for Analyzer {
+    fn process(&mut self, data: &[f32]) -> Control {
+        let skip = data.len().saturating_sub(FFT_LEN);
+        for s in &data[skip..] {
+            if self.samples.len() == FFT_LEN {
+                self.samples.pop_front();
+            }
+            self.samples.push_back(*s);
+        }
+
+        if self.samples.len() < FFT_LEN || self.last_update.elapsed() < UPDATE_INTERVAL {
+            return Control::Continue;
+        }
+
+        self.last_update = Instant::now();
+
+        match self.sender.try_send(self.spectrum()) {
+            Ok(_) | Err(TrySendError::Full(_)) => Control::Continue,
+            Err(TrySendError::Closed(_)) => Control::Stop,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn full_scale_sine_has_zero_dbfs() {
+        let (sender, _receiver) = tokio::sync::mpsc::channel(1);
+        let sample_rate = 48_000;
+        let mut analyzer = Analyzer::new(sender, sample_rate);
+
+        // frequency of bin 256, so that there is no leakage
+        let bin = 256;
+        let freq = bin as f32 * sample_rate as f32 / FFT_LEN as f32;
+        let sine: Vec<_> = (0..FFT_LEN)
+            .map(|n| (2.0 * PI * freq * n as f32 / sample_rate as f32).sin())
+            .collect();
+        let _ = analyzer.process(&sine);
+
+        let spectrum = analyzer.spectrum();
+
+        assert!(spectrum.levels[bin].abs() < 0.1);
+        assert!(spectrum.levels[bin + 10] < -60.0);
+        assert_eq!(spectrum.frequency(bin), freq);
+    }
+}
@@ -0,0 +1,57 @@
+use raumklang_core::spl::{self, Record, TimeWeighting, Weighting};
+use tokio::sync::mpsc::error::TrySendError;
+
+use std::time::Duration;
+
+use super::{Process, loudness, process::Control};
+
+/// Interval of the levels that are reported.
+pub const INTERVAL: Duration = Duration::from_millis(500);
+
+/// Feeds the recording into the sound pressure level logger, besides the
+/// loudness meter, and reports the levels of every interval.
+pub struct Logger {
+    loudness: loudness::Test,
+    logger: spl::Logger,
+    sender: tokio::sync::mpsc::Sender<Record>,
+}
+
+impl Logger {
+    pub fn new(
+        loudness: loudness::Test,
+        weighting: Weighting,
+        time_weighting: TimeWeighting,
+        calibration: f32,
+        sample_rate: u32,
+        sender: tokio::sync::mpsc::Sender<Record>,
+    ) -> Self {
+        Self {
+            loudness,
+            logger: spl::Logger::new(
+                weighting,
+                time_weighting,
+                calibration,
+                INTERVAL,
+                sample_rate,
+            ),
+            sender,
+        }
+    }
+}
+
+impl Process for Logger {
+    fn process(&mut self, data: &[f32]) -> Control {
+        // NOTE: the loudness meter is optional
+        let _ = self.loudness.process(data);
+
+        for record in self.logger.push(data) {
+            match self.sender.try_send(record) {
+                Ok(_) | Err(TrySendError::Full(_)) => {}
+                // stopped by the user
+                Err(TrySendError::Closed(_)) => return Control::Stop,
+            }
+        }
+
+        Control::Continue
+    }
+}
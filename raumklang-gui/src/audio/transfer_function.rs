@@ -0,0 +1,223 @@
+use crate::data;
+
+use raumklang_core::{bands, dbfs};
+use tokio::sync::mpsc::error::TrySendError;
+
+use std::{
+    sync::{Arc, mpsc},
+    time::{Duration, Instant},
+};
+
+use super::{Process, loudness, process::Control};
+
+const FFT_SIZE: usize = 16_384;
+const AVERAGES: usize = 16;
+const UPDATE_INTERVAL: Duration = Duration::from_millis(500);
+/// Length of the start of both signals, the delay of the recording is
+/// estimated from.
+const PROBE_LENGTH: Duration = Duration::from_secs(1);
+
+/// Transfer function between the played and the recorded signal so far,
+/// smoothed with 1/12 octave bands.
+#[derive(Debug, Clone)]
+pub struct TransferFunction {
+    /// Compensated delay of the recording.
+    pub delay: Duration,
+    pub frames: usize,
+    pub points: Arc<[Point]>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Point {
+    /// Center frequency of the band in Hz.
+    pub frequency: f32,
+    /// Level in dB relative to the played signal.
+    pub level: f32,
+    /// Mean coherence within the band, from 0 to 1.
+    pub coherence: f32,
+}
+
+/// Creates the processes for the recorded and for the played signal, the
+/// [`Reference`] hands the played signal over to the [`Analyzer`].
+pub fn analyzer(
+    loudness: loudness::Test,
+    sample_rate: u32,
+    sender: tokio::sync::mpsc::Sender<TransferFunction>,
+) -> (Analyzer, Reference) {
+    let (reference_sender, reference) = mpsc::channel();
+
+    let analyzer = Analyzer {
+        loudness,
+        reference,
+        sample_rate,
+        probe_len: data::Samples::from_duration(PROBE_LENGTH, data::SampleRate::new(sample_rate))
+            .into(),
+        state: State::Probing {
+            reference: vec![],
+            recording: vec![],
+        },
+        last_update: Instant::now(),
+        sender,
+    };
+
+    (analyzer, Reference(reference_sender))
+}
+
+/// Passes the played signal on to the [`Analyzer`].
+pub struct Reference(mpsc::Sender<Vec<f32>>);
+
+impl Process for Reference {
+    fn process(&mut self, data: &[f32]) -> Control {
+        match self.0.send(data.to_vec()) {
+            Ok(_) => Control::Continue,
+            Err(_) => Control::Stop,
+        }
+    }
+}
+
+/// Estimates the transfer function from the recording and the played signal
+/// and reports it periodically.
+pub struct Analyzer {
+    loudness: loudness::Test,
+    reference: mpsc::Receiver<Vec<f32>>,
+    sample_rate: u32,
+    /// [`PROBE_LENGTH`] in samples.
+    probe_len: usize,
+    state: State,
+    last_update: Instant,
+    sender: tokio::sync::mpsc::Sender<TransferFunction>,
+}
+
+enum State {
+    Probing {
+        reference: Vec<f32>,
+        recording: Vec<f32>,
+    },
+    Running {
+        delay: usize,
+        estimator: raumklang_core::TransferFunction,
+    },
+}
+
+impl Analyzer {
+    fn transfer_function(&self) -> Option<TransferFunction> {
+        let State::Running { delay, estimator } = &self.state else {
+            return None;
+        };
+
+        let resolution = estimator.frequency_resolution(self.sample_rate);
+        let power: Vec<f32> = estimator.magnitude().map(|m| m * m).collect();
+        let coherence: Vec<f32> = estimator.coherence().collect();
+        let nyquist = self.sample_rate as f32 / 2.0;
+
+        let points = bands::fractional_octave(12, 20.0, 20_000f32.min(nyquist))
+            .into_iter()
+            .map(|band| Point {
+                frequency: band.center,
+                level: dbfs(bands::energy_mean(&power, resolution, &band).sqrt()),
+                coherence: bands::energy_mean(&coherence, resolution, &band),
+            })
+            .collect();
+
+        Some(TransferFunction {
+            delay: data::Samples::new(*delay, data::SampleRate::new(self.sample_rate)).into(),
+            frames: estimator.frames(),
+            points,
+        })
+    }
+}
+
+impl Process for Analyzer {
+    fn process(&mut self, data: &[f32]) -> Control {
+        // NOTE: the loudness meter is optional
+        let _ = self.loudness.process(data);
+
+        let reference: Vec<f32> = self.reference.try_iter().flatten().collect();
+
+        match &mut self.state {
+            State::Probing {
+                reference: probed_reference,
+                recording,
+            } => {
+                probed_reference.extend(reference);
+                recording.extend_from_slice(data);
+
+                let len = self.probe_len;
+                if probed_reference.len() >= len && recording.len() >= len {
+                    let delay = raumklang_core::TransferFunction::estimate_delay(
+                        &probed_reference[..len],
+                        &recording[..len],
+                        self.sample_rate,
+                    );
+
+                    let mut estimator =
+                        raumklang_core::TransferFunction::new(FFT_SIZE, AVERAGES).with_delay(delay);
+                    estimator.push(probed_reference, recording);
+
+                    self.state = State::Running { delay, estimator };
+                }
+            }
+            State::Running { estimator, .. } => estimator.push(&reference, data),
+        }
+
+        if self.last_update.elapsed() < UPDATE_INTERVAL {
+            return Control::Continue;
+        }
+
+        self.last_update = Instant::now();
+
+        let Some(transfer_function) = self.transfer_function() else {
+            return Control::Continue;
+        };
+
+        match self.sender.try_send(transfer_function) {
+            Ok(_) | Err(TrySendError::Full(_)) => Control::Continue,
+            // stopped by the user
+            Err(TrySendError::Closed(_)) => Control::Stop,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn delayed_recording_is_compensated() {
+        let sample_rate = 48_000;
+        let (loudness_sender, _loudness) = tokio::sync::mpsc::channel(1);
+        let (sender, _receiver) = tokio::sync::mpsc::channel(1);
+
+        let (mut analyzer, mut reference) = analyzer(
+            loudness::Test::new(loudness_sender, sample_rate as usize),
+            sample_rate,
+            sender,
+        );
+
+        let delay = 240;
+        let noise: Vec<f32> = raumklang_core::signals::PinkNoise::with_amplitude(0.5)
+            .take(sample_rate as usize * 3)
+            .collect();
+        let recording: Vec<f32> = std::iter::repeat_n(0.0, delay)
+            .chain(noise.iter().map(|s| s * 0.5))
+            .take(noise.len())
+            .collect();
+
+        for (played, recorded) in noise.chunks(480).zip(recording.chunks(480)) {
+            let _ = analyzer.process(recorded);
+            let _ = reference.process(played);
+        }
+
+        let transfer_function = analyzer.transfer_function().unwrap();
+
+        assert_eq!(transfer_function.delay, Duration::from_millis(5));
+        for point in transfer_function
+            .points
+            .iter()
+            .filter(|point| (100.0..10_000.0).contains(&point.frequency))
+        {
+            assert!((point.level - dbfs(0.5)).abs() < 0.5, "{point:?}");
+            assert!(point.coherence > 0.95, "{point:?}");
+        }
+    }
+}
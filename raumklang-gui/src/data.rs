@@ -1,26 +1,39 @@
 pub mod audio;
+pub mod auralization;
 pub mod chart;
+pub mod curve;
+pub mod demo;
 pub mod directory;
+pub mod export_hook;
 pub mod frequency_response;
 pub mod impulse_response;
+pub mod logging;
 pub mod measurement;
+pub mod onboarding;
 pub mod project;
+pub mod quality;
 mod recent_projects;
 pub mod recording;
+pub mod remote_control;
 mod sample_rate;
 mod samples;
+pub mod scheduler;
+pub mod snapshot;
 pub mod spectral_decay;
 pub mod spectrogram;
+pub mod watch_folder;
 pub mod window;
 
 pub use frequency_response::FrequencyResponse;
 pub use impulse_response::ImpulseResponse;
 pub use project::Project;
+pub use quality::Quality;
 pub use recent_projects::RecentProjects;
 pub use sample_rate::SampleRate;
 pub use samples::Samples;
 pub use spectral_decay::SpectralDecay;
 pub use spectrogram::Spectrogram;
+pub use watch_folder::WatchFolder;
 pub use window::Window;
 
 use ndarray::{Array, Array1, ArrayView, Axis, concatenate};
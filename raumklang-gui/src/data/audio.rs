@@ -1,6 +1,6 @@
 use std::fmt;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct OutPort(String);
 
 impl OutPort {
@@ -41,3 +41,110 @@ impl AsRef<str> for InPort {
         self.0.as_ref()
     }
 }
+
+/// Gain trim of an output port, e.g. to lower the level of a subwoofer.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Trim {
+    /// Gain in dB
+    pub gain: f32,
+    pub muted: bool,
+}
+
+impl Trim {
+    pub const MIN_GAIN: f32 = -40.0;
+    pub const MAX_GAIN: f32 = 12.0;
+
+    /// Linear factor, the signal is multiplied with.
+    pub fn factor(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            10.0f32.powf(self.gain / 20.0)
+        }
+    }
+}
+
+/// Connections of the measurement ports, queried right before a measurement.
+#[derive(Debug, Clone, Default)]
+pub struct Connections {
+    /// Full name of the own output port
+    pub out_port: String,
+    pub playback: Vec<Playback>,
+    /// Ports, the own input port records from.
+    pub capture: Vec<String>,
+}
+
+/// Port, the output is connected to.
+#[derive(Debug, Clone)]
+pub struct Playback {
+    pub port: String,
+    /// Other ports, that are playing to `port` as well.
+    pub shared_with: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ConnectionIssue {
+    #[error("the output is not connected")]
+    OutputNotConnected,
+    #[error("the input is not connected")]
+    InputNotConnected,
+    #[error("the output is looped straight back to the input")]
+    DirectLoop,
+    #[error("the input records from {0} ports at once")]
+    MultipleInputs(usize),
+    #[error("{0} is also fed by other ports")]
+    SharedOutput(String),
+}
+
+impl Connections {
+    /// Returns the issues of the connection graph, a direct loop from the
+    /// output to the input is only expected when recording a loopback.
+    pub fn issues(&self, is_loopback: bool) -> Vec<ConnectionIssue> {
+        let mut issues = vec![];
+
+        if self.playback.is_empty() {
+            issues.push(ConnectionIssue::OutputNotConnected);
+        }
+
+        if self.capture.is_empty() {
+            issues.push(ConnectionIssue::InputNotConnected);
+        }
+
+        if self.capture.contains(&self.out_port) && !is_loopback {
+            issues.push(ConnectionIssue::DirectLoop);
+        }
+
+        if self.capture.len() > 1 {
+            issues.push(ConnectionIssue::MultipleInputs(self.capture.len()));
+        }
+
+        issues.extend(
+            self.playback
+                .iter()
+                .filter(|playback| !playback.shared_with.is_empty())
+                .map(|playback| ConnectionIssue::SharedOutput(playback.port.clone())),
+        );
+
+        issues
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn direct_loop_is_only_expected_for_loopbacks() {
+        let connections = Connections {
+            out_port: "raumklang:measurement_out".to_string(),
+            playback: vec![Playback {
+                port: "system:playback_1".to_string(),
+                shared_with: vec![],
+            }],
+            capture: vec!["raumklang:measurement_out".to_string()],
+        };
+
+        assert_eq!(connections.issues(true), []);
+        assert_eq!(connections.issues(false), [ConnectionIssue::DirectLoop]);
+    }
+}
@@ -0,0 +1,143 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Level of the loudest sample of the result in dBFS.
+const PEAK_LEVEL: f32 = -1.0;
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum Error {
+    #[error("could not load the dry signal: {0}")]
+    Signal(String),
+    #[error("could not load the correction filter: {0}")]
+    Filter(String),
+    #[error("could not write file: {0}")]
+    Write(String),
+}
+
+/// Convolves the dry signal with the impulse response and optionally with a
+/// correction filter, to listen to the room. All channels of the dry signal
+/// are kept, the result is normalized to a peak level of -1 dBFS.
+pub async fn export(
+    impulse_response: Arc<raumklang_core::WindowedImpulseResponse>,
+    dry: PathBuf,
+    filter: Option<PathBuf>,
+    path: PathBuf,
+    format: raumklang_core::wav::Format,
+) -> Result<PathBuf, Error> {
+    tokio::task::spawn_blocking(move || {
+        let sample_rate = impulse_response.sample_rate;
+
+        let kernel = match filter {
+            Some(filter) => {
+                let filter = raumklang_core::Measurement::from_file(filter)
+                    .map_err(|err| Error::Filter(err.to_string()))?
+                    .resample(sample_rate);
+                let filter: Vec<_> = filter.iter().copied().collect();
+
+                raumklang_core::convolution::convolve(&impulse_response.data, &filter)
+            }
+            None => impulse_response.data.clone(),
+        };
+
+        let (dry_rate, channels) =
+            read_channels(&dry).map_err(|err| Error::Signal(err.to_string()))?;
+
+        let channels: Vec<Vec<f32>> = channels
+            .into_iter()
+            .map(|samples| {
+                let samples = if dry_rate != sample_rate {
+                    raumklang_core::Measurement::new(dry_rate, samples)
+                        .resample(sample_rate)
+                        .iter()
+                        .copied()
+                        .collect()
+                } else {
+                    samples
+                };
+
+                // the window starts before the impulse, drop the delay it adds
+                raumklang_core::convolution::convolve(&samples, &kernel)
+                    .into_iter()
+                    .skip(impulse_response.offset)
+                    .collect()
+            })
+            .collect();
+
+        let peak = channels
+            .iter()
+            .flatten()
+            .fold(0.0f32, |peak, s| peak.max(s.abs()));
+        let gain = if peak > 0.0 {
+            raumklang_core::db_to_gain(PEAK_LEVEL) / peak
+        } else {
+            1.0
+        };
+
+        write(&path, sample_rate, &channels, gain, format)
+            .map_err(|err| Error::Write(err.to_string()))?;
+
+        Ok(path)
+    })
+    .await
+    .unwrap()
+}
+
+/// Reads all channels of a WAV file with integer or float samples.
+fn read_channels(path: &Path) -> Result<(u32, Vec<Vec<f32>>), hound::Error> {
+    let mut reader = hound::WavReader::open(path)?;
+    let spec = reader.spec();
+
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>()?,
+        hound::SampleFormat::Int => {
+            let scale = 1.0 / (1i64 << (spec.bits_per_sample - 1)) as f32;
+
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|s| s as f32 * scale))
+                .collect::<Result<_, _>>()?
+        }
+    };
+
+    let channels = spec.channels.max(1) as usize;
+
+    let channels = (0..channels)
+        .map(|channel| {
+            samples
+                .iter()
+                .skip(channel)
+                .step_by(channels)
+                .copied()
+                .collect()
+        })
+        .collect();
+
+    Ok((spec.sample_rate, channels))
+}
+
+fn write(
+    path: &Path,
+    sample_rate: u32,
+    channels: &[Vec<f32>],
+    gain: f32,
+    format: raumklang_core::wav::Format,
+) -> std::io::Result<()> {
+    let len = channels.iter().map(Vec::len).max().unwrap_or_default();
+
+    let samples = (0..len).flat_map(|i| {
+        channels
+            .iter()
+            .map(move |channel| channel.get(i).copied().unwrap_or_default() * gain)
+    });
+
+    raumklang_core::wav::write(
+        path,
+        sample_rate,
+        channels.len() as u16,
+        samples,
+        format,
+        None,
+    )
+}
@@ -1,11 +1,16 @@
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum TimeSeriesUnit {
-    #[default]
-    Time,
     Samples,
+    #[default]
+    #[serde(alias = "Time")]
+    Milliseconds,
+    Seconds,
+    /// Distance, the sound travels within the time, only meaningful for
+    /// impulse responses.
+    Meters,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
 pub enum AmplitudeUnit {
     PercentFullScale,
     #[default]
@@ -32,8 +37,44 @@ impl std::fmt::Display for AmplitudeUnit {
     }
 }
 
+/// Speed of sound in m/s at room temperature.
+const SPEED_OF_SOUND: f32 = 343.0;
+
 impl TimeSeriesUnit {
-    pub const ALL: [Self; 2] = [TimeSeriesUnit::Samples, TimeSeriesUnit::Time];
+    pub const ALL: [Self; 4] = [
+        TimeSeriesUnit::Samples,
+        TimeSeriesUnit::Milliseconds,
+        TimeSeriesUnit::Seconds,
+        TimeSeriesUnit::Meters,
+    ];
+
+    /// Units of plain time series, like recorded signals.
+    pub const TIME: [Self; 3] = [
+        TimeSeriesUnit::Samples,
+        TimeSeriesUnit::Milliseconds,
+        TimeSeriesUnit::Seconds,
+    ];
+
+    /// Converts a position in `samples` at the `sample_rate` into this unit.
+    pub fn from_samples(&self, samples: f32, sample_rate: f32) -> f32 {
+        let seconds = samples / sample_rate;
+
+        match self {
+            TimeSeriesUnit::Samples => samples,
+            TimeSeriesUnit::Milliseconds => seconds * 1000.0,
+            TimeSeriesUnit::Seconds => seconds,
+            TimeSeriesUnit::Meters => seconds * SPEED_OF_SOUND,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            TimeSeriesUnit::Samples => "samples",
+            TimeSeriesUnit::Milliseconds => "ms",
+            TimeSeriesUnit::Seconds => "s",
+            TimeSeriesUnit::Meters => "m",
+        }
+    }
 }
 
 impl std::fmt::Display for TimeSeriesUnit {
@@ -43,7 +84,131 @@ impl std::fmt::Display for TimeSeriesUnit {
             "{}",
             match self {
                 TimeSeriesUnit::Samples => "Samples",
-                TimeSeriesUnit::Time => "Time",
+                TimeSeriesUnit::Milliseconds => "Milliseconds",
+                TimeSeriesUnit::Seconds => "Seconds",
+                TimeSeriesUnit::Meters => "Meters",
+            }
+        )
+    }
+}
+
+/// Display preferences of the charts, stored in the data directory.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Preferences {
+    #[serde(default)]
+    pub colormap: Colormap,
+    /// Unit of the time axis of recorded signals and spectrograms.
+    #[serde(default)]
+    pub time_unit: TimeSeriesUnit,
+    /// Amplitude unit of the measurement waveform, linear by default.
+    #[serde(default = "linear")]
+    pub amplitude_unit: AmplitudeUnit,
+    /// Draws the RMS envelope over the measurement waveform.
+    #[serde(default)]
+    pub rms_envelope: bool,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            colormap: Colormap::default(),
+            time_unit: TimeSeriesUnit::default(),
+            amplitude_unit: linear(),
+            rms_envelope: false,
+        }
+    }
+}
+
+fn linear() -> AmplitudeUnit {
+    AmplitudeUnit::PercentFullScale
+}
+
+/// Color map of heat-map style charts, like spectrograms and waterfalls.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Colormap {
+    pub gradient: Gradient,
+    pub inverted: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Gradient {
+    #[default]
+    Magma,
+    Turbo,
+    Viridis,
+    Grayscale,
+}
+
+impl Preferences {
+    async fn path() -> Result<std::path::PathBuf, super::Error> {
+        let path = super::directory::data();
+        tokio::fs::create_dir_all(path).await?;
+
+        Ok(path.join("chart.json"))
+    }
+
+    pub async fn load() -> Result<Self, super::Error> {
+        let content = match tokio::fs::read(Self::path().await?).await {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err.into()),
+        };
+
+        Ok(serde_json::from_slice(&content)?)
+    }
+
+    pub async fn save(self) -> Result<(), super::Error> {
+        let content = serde_json::to_string_pretty(&self)?;
+        tokio::fs::write(Self::path().await?, content).await?;
+
+        Ok(())
+    }
+}
+
+impl Colormap {
+    /// Color at `t` in the range of `0.0` to `1.0`.
+    pub fn eval(&self, t: f64) -> colorous::Color {
+        let t = t.clamp(0.0, 1.0);
+        let t = if self.inverted { 1.0 - t } else { t };
+
+        self.gradient.eval(t)
+    }
+
+    /// Color of the `i`th of `n` evenly spaced items.
+    pub fn eval_rational(&self, i: usize, n: usize) -> colorous::Color {
+        self.eval(i as f64 / n.saturating_sub(1).max(1) as f64)
+    }
+}
+
+impl Gradient {
+    pub const ALL: [Gradient; 4] = [
+        Gradient::Magma,
+        Gradient::Turbo,
+        Gradient::Viridis,
+        Gradient::Grayscale,
+    ];
+
+    fn eval(&self, t: f64) -> colorous::Color {
+        match self {
+            Gradient::Magma => colorous::MAGMA.eval_continuous(t),
+            Gradient::Turbo => colorous::TURBO.eval_continuous(t),
+            Gradient::Viridis => colorous::VIRIDIS.eval_continuous(t),
+            // runs from white to black, but dark should be low
+            Gradient::Grayscale => colorous::GREYS.eval_continuous(1.0 - t),
+        }
+    }
+}
+
+impl std::fmt::Display for Gradient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Gradient::Magma => "Magma",
+                Gradient::Turbo => "Turbo",
+                Gradient::Viridis => "Viridis",
+                Gradient::Grayscale => "Grayscale",
             }
         )
     }
@@ -0,0 +1,98 @@
+use std::{io, path::Path};
+
+/// A magnitude curve given by (frequency in Hz, level in dB) pairs, e.g. a
+/// headphone compensation or target curve.
+#[derive(Debug, Clone, Default)]
+pub struct Curve(pub Vec<(f32, f32)>);
+
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum Error {
+    #[error("could not load file: {0}")]
+    Io(io::ErrorKind),
+    #[error("file contains no data points")]
+    Empty,
+}
+
+impl Curve {
+    pub async fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let content = tokio::fs::read_to_string(path)
+            .await
+            .map_err(|err| Error::Io(err.kind()))?;
+
+        Self::parse(&content)
+    }
+
+    /// Parses text files with one point per line, as exported by most
+    /// measurement tools (REW, AutoEq, ...). Lines that don't start with two
+    /// numbers, like headers or comments, are skipped.
+    pub fn parse(content: &str) -> Result<Self, Error> {
+        let mut points: Vec<_> = content
+            .lines()
+            .filter_map(|line| {
+                let mut values = line
+                    .split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+                    .filter(|s| !s.is_empty())
+                    .map(str::parse::<f32>);
+
+                let frequency = values.next()?.ok()?;
+                let level = values.next()?.ok()?;
+
+                Some((frequency, level))
+            })
+            .filter(|(frequency, _)| *frequency > 0.0)
+            .collect();
+
+        if points.is_empty() {
+            return Err(Error::Empty);
+        }
+
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        Ok(Self(points))
+    }
+
+    /// Level at `frequency`, linearly interpolated between the points and
+    /// held constant beyond the first and last one.
+    pub fn level_at(&self, frequency: f32) -> f32 {
+        let Some((first, last)) = self.0.first().zip(self.0.last()) else {
+            return 0.0;
+        };
+
+        if frequency <= first.0 {
+            return first.1;
+        }
+
+        if frequency >= last.0 {
+            return last.1;
+        }
+
+        let i = self.0.partition_point(|(f, _)| *f < frequency);
+        let (f0, l0) = self.0[i - 1];
+        let (f1, l1) = self.0[i];
+
+        l0 + (l1 - l0) * (frequency - f0) / (f1 - f0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_skips_header_and_comments() {
+        let content = "* exported curve\nfrequency,raw\n20,1.5\n1000;0\n20000 -3.0\n";
+
+        let curve = Curve::parse(content).unwrap();
+
+        assert_eq!(curve.0, vec![(20.0, 1.5), (1000.0, 0.0), (20000.0, -3.0)]);
+    }
+
+    #[test]
+    fn level_at_interpolates_between_points() {
+        let curve = Curve(vec![(100.0, 2.0), (200.0, -2.0)]);
+
+        assert_eq!(curve.level_at(50.0), 2.0);
+        assert_eq!(curve.level_at(150.0), 0.0);
+        assert_eq!(curve.level_at(1000.0), -2.0);
+    }
+}
@@ -0,0 +1,48 @@
+//! Measurements of a simulated room, to explore the analyses without any
+//! audio hardware.
+
+use raumklang_core::{Loopback, Measurement, room::Room, signals::ExponentialSweep};
+
+const SAMPLE_RATE: u32 = 48_000;
+
+/// Listening positions of the measurements.
+const POSITIONS: [(&str, [f32; 3]); 3] = [
+    ("Listening position", [2.5, 2.8, 1.2]),
+    ("Left seat", [1.9, 2.9, 1.2]),
+    ("Right seat", [3.1, 2.9, 1.2]),
+];
+
+/// The played sweep.
+pub fn loopback() -> Loopback {
+    let sweep = ExponentialSweep::new(
+        20.0,
+        20_000.0,
+        0.8,
+        3 * SAMPLE_RATE as usize,
+        SAMPLE_RATE as usize,
+    );
+
+    Loopback::new(Measurement::new(SAMPLE_RATE, sweep.collect()))
+}
+
+/// The sweep recorded at every listening position.
+pub fn measurements(loopback: &Loopback) -> Vec<(String, Measurement)> {
+    let sweep: Vec<f32> = loopback.iter().copied().collect();
+
+    POSITIONS
+        .into_iter()
+        .enumerate()
+        .map(|(seed, (name, listener))| {
+            let room = Room::default().set_listener(listener).set_seed(seed as u64);
+
+            // keep some headroom, the reflections add up to more than the sweep
+            let recording = room
+                .record(&sweep, SAMPLE_RATE)
+                .into_iter()
+                .map(|s| s * 0.25)
+                .collect();
+
+            (name.to_string(), Measurement::new(SAMPLE_RATE, recording))
+        })
+        .collect()
+}
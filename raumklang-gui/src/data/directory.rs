@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::LazyLock;
 
 pub fn data() -> &'static Path {
@@ -8,5 +8,18 @@ pub fn data() -> &'static Path {
         .unwrap_or(Path::new("./data"))
 }
 
+/// Directory of the configuration, that is shared with the CLI.
+pub fn config() -> &'static Path {
+    PROJECT
+        .as_ref()
+        .map(directories::ProjectDirs::config_dir)
+        .unwrap_or(Path::new("./config"))
+}
+
+/// Directory of the rotating log files.
+pub fn logs() -> PathBuf {
+    data().join("logs")
+}
+
 static PROJECT: LazyLock<Option<directories::ProjectDirs>> =
     LazyLock::new(|| directories::ProjectDirs::from("de", "henku", "raumklang"));
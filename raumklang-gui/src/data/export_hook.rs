@@ -0,0 +1,99 @@
+use std::{
+    path::PathBuf,
+    process::{Command, ExitStatus},
+};
+
+/// Command, that is run after files have been exported, e.g. to copy
+/// filters to a streamer. The exported files are passed as arguments
+/// (`$1`, `$@`) to the shell.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ExportHook {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub command: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Output {
+    pub command: String,
+    pub status: ExitStatus,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl ExportHook {
+    async fn path() -> Result<PathBuf, super::Error> {
+        let path = super::directory::data();
+        tokio::fs::create_dir_all(path).await?;
+
+        Ok(path.join("export_hook.json"))
+    }
+
+    pub async fn load() -> Result<Self, super::Error> {
+        let content = match tokio::fs::read(Self::path().await?).await {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err.into()),
+        };
+
+        Ok(serde_json::from_slice(&content)?)
+    }
+
+    pub async fn save(self) -> Result<(), super::Error> {
+        let content = serde_json::to_string_pretty(&self)?;
+        tokio::fs::write(Self::path().await?, content).await?;
+
+        Ok(())
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.enabled && !self.command.trim().is_empty()
+    }
+}
+
+/// Runs the saved hook with the exported `paths`, if it is enabled.
+pub async fn run(paths: Vec<PathBuf>) -> Result<Option<Output>, super::Error> {
+    let hook = ExportHook::load().await?;
+    if !hook.is_active() {
+        return Ok(None);
+    }
+
+    let output = tokio::task::spawn_blocking(move || {
+        shell(&hook.command)
+            .args(&paths)
+            .output()
+            .map(|output| Output {
+                command: hook.command,
+                status: output.status,
+                stdout: String::from_utf8_lossy(&output.stdout)
+                    .trim_end()
+                    .to_string(),
+                stderr: String::from_utf8_lossy(&output.stderr)
+                    .trim_end()
+                    .to_string(),
+            })
+    })
+    .await
+    .map_err(std::io::Error::other)??;
+
+    Ok(Some(output))
+}
+
+#[cfg(not(windows))]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    // the first argument after the command becomes `$0`
+    shell.arg("-c").arg(command).arg("raumklang");
+
+    shell
+}
+
+/// The paths are appended to the command.
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+
+    shell
+}
@@ -1,43 +1,376 @@
-use std::sync::Arc;
+use std::{
+    f32::consts::{PI, TAU},
+    fmt, io,
+    path::PathBuf,
+    sync::Arc,
+};
 
-use super::{Samples, Window};
+use super::{scheduler, smooth_fractional_octave};
+use crate::unit;
+
+use raumklang_core::bands::{self, Band};
 
 #[derive(Debug, Clone)]
 pub struct FrequencyResponse {
     pub sample_rate: u32,
+    /// Magnitude of each bin
     pub data: Arc<Vec<f32>>,
+    /// Phase of each bin in radians relative to the start of the impulse
+    /// response, `None` for magnitudes only, e.g. a simulated sum.
+    pub phase: Option<Arc<Vec<f32>>>,
 }
 
 impl FrequencyResponse {
-    pub fn from_data(frequency_response: raumklang_core::FrequencyResponse) -> Self {
+    /// Takes magnitude and phase of the complex bins, the impulse response
+    /// starts `offset` samples into the windowed data.
+    pub fn from_data(frequency_response: raumklang_core::FrequencyResponse, offset: usize) -> Self {
         let sample_rate = frequency_response.sample_rate;
-        let data = frequency_response
+        let phase = frequency_response.phase(offset);
+        let data = frequency_response.data.iter().map(|s| s.norm()).collect();
+
+        Self {
+            sample_rate,
+            data: Arc::new(data),
+            phase: Some(Arc::new(phase)),
+        }
+    }
+
+    /// Adds `gain` in dB to all bins.
+    pub fn with_gain(self, gain: f32) -> Self {
+        let factor = raumklang_core::db_to_gain(gain);
+
+        Self {
+            sample_rate: self.sample_rate,
+            data: Arc::new(self.data.iter().map(|s| s * factor).collect()),
+            phase: self.phase,
+        }
+    }
+
+    /// Removes the deviation of the measurement microphone, given by its
+    /// calibration curve. The calibration holds no phase, so it is kept.
+    pub fn calibrated(self, calibration: &super::curve::Curve) -> Self {
+        let resolution = self.resolution();
+
+        let data = self
             .data
-            .into_iter()
-            .map(|s| s.re.abs())
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                let level = calibration.level_at(i as f32 * resolution);
+                s / 10f32.powf(level / 20.0)
+            })
             .collect();
 
         Self {
-            sample_rate,
+            sample_rate: self.sample_rate,
             data: Arc::new(data),
+            phase: self.phase,
         }
     }
 }
 
-pub async fn compute(
-    mut impulse_response: raumklang_core::ImpulseResponse,
-    window: Window<Samples>,
-) -> FrequencyResponse {
-    let offset = window.offset().into();
+impl FrequencyResponse {
+    /// Spacing of the bins in Hz, see [`raumklang_core::FrequencyResponse::fft_len`].
+    pub fn resolution(&self) -> f32 {
+        self.sample_rate as f32 / raumklang_core::FrequencyResponse::fft_len(self.data.len()) as f32
+    }
+
+    /// Levels in dB of the fractional octave bands (e.g. `fraction = 3` for
+    /// third-octaves), the energy of all bins within a band is averaged.
+    pub fn band_levels(&self, fraction: u8) -> Vec<(Band, f32)> {
+        let resolution = self.resolution();
+        let power: Vec<f32> = self.data.iter().map(|s| s * s).collect();
+        let nyquist = self.sample_rate as f32 / 2.0;
+
+        bands::fractional_octave(fraction, 17.0, 22_000f32.min(nyquist))
+            .into_iter()
+            .map(|band| {
+                let power = bands::energy_mean(&power, resolution, &band);
+                (band, 10.0 * power.log10())
+            })
+            .collect()
+    }
+}
+
+/// Frequencies at which the frequency response is written on export.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Grid {
+    /// Every FFT bin
+    #[default]
+    Raw,
+    /// Logarithmically spaced points starting at 20 Hz
+    LogSpaced { points_per_octave: u16 },
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ExportError {
+    #[error("unsupported file format, use .frd or .csv")]
+    UnknownFormat,
+    #[error("could not write file: {0}")]
+    Io(io::ErrorKind),
+}
+
+impl FrequencyResponse {
+    /// Frequency response as (frequency in Hz, level in dB) pairs, optionally
+    /// smoothed with a fractional octave `smoothing`.
+    pub fn points(&self, smoothing: Option<u8>, grid: Grid) -> Vec<(f32, f32)> {
+        let data = match smoothing {
+            Some(fraction) => smooth_fractional_octave(&self.data, fraction),
+            None => self.data.to_vec(),
+        };
+
+        if data.is_empty() {
+            return vec![];
+        }
+
+        let resolution = self.resolution();
+
+        match grid {
+            Grid::Raw => data
+                .iter()
+                .enumerate()
+                .map(|(i, s)| (i as f32 * resolution, raumklang_core::dbfs(*s)))
+                .collect(),
+            Grid::LogSpaced { points_per_octave } => {
+                let max_frequency = (data.len() - 1) as f32 * resolution;
+
+                (0..)
+                    .map(|i| 20.0 * 2f32.powf(i as f32 / points_per_octave as f32))
+                    .take_while(|frequency| *frequency <= max_frequency)
+                    .map(|frequency| {
+                        let pos = frequency / resolution;
+                        let i = pos.floor() as usize;
+                        let frac = pos - i as f32;
+
+                        let a = data[i];
+                        let b = data.get(i + 1).copied().unwrap_or(a);
+
+                        (frequency, raumklang_core::dbfs(a + (b - a) * frac))
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+impl FrequencyResponse {
+    /// Phase as (frequency in Hz, phase in degrees) pairs, at the same
+    /// frequencies as [`FrequencyResponse::points`]. The phase is never
+    /// smoothed.
+    pub fn phase_points(&self, grid: Grid) -> Option<Vec<(f32, f32)>> {
+        let phase = self.phase.as_ref()?;
+
+        if phase.is_empty() {
+            return Some(vec![]);
+        }
+
+        let resolution = self.resolution();
+
+        let points = match grid {
+            Grid::Raw => phase
+                .iter()
+                .enumerate()
+                .map(|(i, phase)| (i as f32 * resolution, phase.to_degrees()))
+                .collect(),
+            Grid::LogSpaced { points_per_octave } => {
+                let max_frequency = (phase.len() - 1) as f32 * resolution;
+
+                (0..)
+                    .map(|i| 20.0 * 2f32.powf(i as f32 / points_per_octave as f32))
+                    .take_while(|frequency| *frequency <= max_frequency)
+                    .map(|frequency| {
+                        let pos = frequency / resolution;
+                        let i = pos.floor() as usize;
+                        let frac = pos - i as f32;
+
+                        let a = phase[i];
+                        let b = phase.get(i + 1).copied().unwrap_or(a);
+
+                        // along the shorter way around the circle
+                        let delta = wrap(b - a);
+                        (frequency, wrap(a + delta * frac).to_degrees())
+                    })
+                    .collect()
+            }
+        };
+
+        Some(points)
+    }
+}
+
+/// Wraps the `phase` in radians into ±π.
+fn wrap(phase: f32) -> f32 {
+    (phase + PI).rem_euclid(TAU) - PI
+}
 
-    impulse_response.data.rotate_right(offset);
+/// Exports the frequency response either as FRD (frequency, level and phase
+/// separated by spaces) or as CSV, depending on the file extension of `path`.
+///
+/// The phase is written in degrees, or as zero, if it is not known.
+pub async fn export(
+    path: PathBuf,
+    frequency_response: FrequencyResponse,
+    smoothing: Option<u8>,
+    grid: Grid,
+) -> Result<PathBuf, ExportError> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
 
-    let window: Vec<_> = window.curve().map(|(_x, y)| y).collect();
+    let is_frd = match extension.as_deref() {
+        Some("frd") => true,
+        Some("csv") => false,
+        _ => return Err(ExportError::UnknownFormat),
+    };
 
-    tokio::task::spawn_blocking(move || {
-        raumklang_core::FrequencyResponse::new(impulse_response, &window)
+    let content = tokio::task::spawn_blocking(move || {
+        let points = frequency_response.points(smoothing, grid);
+        let phases = frequency_response
+            .phase_points(grid)
+            .map(|points| points.into_iter().map(|(_, phase)| phase).collect())
+            .unwrap_or_else(|| vec![0.0; points.len()]);
+
+        // FRD files are read by other tools and always use a decimal point
+        let locale = unit::Locale::current();
+        let delimiter = locale.csv_delimiter();
+
+        let mut content = if is_frd {
+            String::from("* frequency level phase\n")
+        } else {
+            format!("frequency{delimiter}level\n")
+        };
+
+        for ((frequency, level), phase) in points.into_iter().zip(phases) {
+            if is_frd {
+                content.push_str(&format!("{frequency:.3} {level:.3} {phase:.3}\n"));
+            } else {
+                content.push_str(&format!(
+                    "{}{delimiter}{}\n",
+                    locale.number(frequency, 3),
+                    locale.number(level, 3)
+                ));
+            }
+        }
+
+        content
     })
     .await
-    .map(FrequencyResponse::from_data)
-    .unwrap()
+    .unwrap();
+
+    tokio::fs::write(&path, content)
+        .await
+        .map_err(|err| ExportError::Io(err.kind()))?;
+
+    Ok(path)
+}
+
+/// Computes the frequency response of the windowed impulse response, after
+/// delaying it by `time_shift` samples (negative values advance it).
+pub async fn compute(
+    subject: scheduler::Subject,
+    impulse_response: Arc<raumklang_core::WindowedImpulseResponse>,
+) -> FrequencyResponse {
+    let offset = impulse_response.offset;
+    let frequency_response = scheduler::run("frequency response", subject, move || {
+        raumklang_core::FrequencyResponse::from_windowed(&impulse_response)
+    })
+    .await;
+
+    FrequencyResponse::from_data(frequency_response, offset)
+}
+
+impl Grid {
+    pub const ALL: [Grid; 4] = [
+        Grid::Raw,
+        Grid::LogSpaced {
+            points_per_octave: 24,
+        },
+        Grid::LogSpaced {
+            points_per_octave: 48,
+        },
+        Grid::LogSpaced {
+            points_per_octave: 96,
+        },
+    ];
+}
+
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Grid::Raw => write!(f, "All FFT bins"),
+            Grid::LogSpaced { points_per_octave } => {
+                write!(f, "{points_per_octave} points/octave")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn log_spaced_grid_is_much_smaller_than_raw_bins() {
+        let frequency_response = FrequencyResponse {
+            sample_rate: 48_000,
+            data: Arc::new(vec![1.0; 48_000]),
+            phase: None,
+        };
+
+        let raw = frequency_response.points(None, Grid::Raw);
+        let log_spaced = frequency_response.points(
+            None,
+            Grid::LogSpaced {
+                points_per_octave: 96,
+            },
+        );
+
+        assert_eq!(raw.len(), 48_000);
+        // 20 Hz to ~24 kHz are ~10.2 octaves
+        assert!((980..=985).contains(&log_spaced.len()));
+        assert!(log_spaced.iter().all(|(_, level)| level.abs() < 1e-3));
+    }
+
+    #[test]
+    fn band_levels_of_a_flat_response() {
+        let frequency_response = FrequencyResponse {
+            sample_rate: 48_000,
+            data: Arc::new(vec![0.5; 24_000]),
+            phase: None,
+        };
+
+        let octaves = frequency_response.band_levels(1);
+        let third_octaves = frequency_response.band_levels(3);
+
+        assert_eq!(octaves.len(), 10);
+        assert_eq!(third_octaves.len(), 31);
+        assert!(
+            octaves
+                .iter()
+                .chain(third_octaves.iter())
+                .all(|(_, level)| (level + 6.02).abs() < 0.01)
+        );
+    }
+
+    #[test]
+    fn phase_is_interpolated_across_the_wrap() {
+        let frequency_response = FrequencyResponse {
+            sample_rate: 48_000,
+            data: Arc::new(vec![1.0; 24_000]),
+            phase: Some(Arc::new(
+                (0..24_000)
+                    .map(|i| if i % 2 == 0 { PI - 0.1 } else { -PI + 0.1 })
+                    .collect(),
+            )),
+        };
+
+        let grid = Grid::LogSpaced {
+            points_per_octave: 24,
+        };
+        let points = frequency_response.phase_points(grid).unwrap();
+
+        assert_eq!(points.len(), frequency_response.points(None, grid).len());
+        assert!(points.iter().all(|(_, phase)| phase.abs() > 170.0));
+    }
 }
@@ -1,7 +1,9 @@
-use std::sync::Arc;
+use std::{fmt, path::Path, sync::Arc};
 
 use iced::task::{Sipper, sipper};
 
+use super::{Samples, Window, measurement::SignalConfig, scheduler};
+
 #[derive(Debug, Clone, Default)]
 pub struct ImpulseResponse(State);
 
@@ -10,20 +12,69 @@ enum State {
     #[default]
     None,
     Computing,
-    Computed(Arc<raumklang_core::ImpulseResponse>),
+    Computed(Arc<raumklang_core::ImpulseResponse>, Option<f32>),
+}
+
+/// How the impulse responses are computed from the recordings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    /// Deconvolution of the recording with the loopback.
+    Deconvolution(raumklang_core::DeconvolutionMethod),
+    /// Convolution of the recording with the inverse filter of the played
+    /// sweep, the loopback is not used.
+    InverseFilter(SignalConfig),
+}
+
+/// Drifts below this value (in ppm) are not worth resampling the recording.
+const DRIFT_THRESHOLD: f32 = 0.5;
+
+impl Method {
+    /// All deconvolution methods, followed by the inverse filter of `sweep`.
+    pub fn all(sweep: SignalConfig) -> Vec<Self> {
+        raumklang_core::DeconvolutionMethod::ALL
+            .into_iter()
+            .map(Method::Deconvolution)
+            .chain([Method::InverseFilter(sweep)])
+            .collect()
+    }
+
+    /// The deconvolution method, `None` for the inverse filter.
+    pub fn deconvolution(&self) -> Option<raumklang_core::DeconvolutionMethod> {
+        match self {
+            Method::Deconvolution(method) => Some(*method),
+            Method::InverseFilter(_) => None,
+        }
+    }
+}
+
+impl Default for Method {
+    fn default() -> Self {
+        Self::Deconvolution(raumklang_core::DeconvolutionMethod::default())
+    }
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Method::Deconvolution(method) => method.fmt(f),
+            Method::InverseFilter(_) => write!(f, "Inverse filter of the sweep"),
+        }
+    }
 }
 
 impl ImpulseResponse {
     pub fn compute(
         self,
+        subject: scheduler::Subject,
         loopback: &raumklang_core::Loopback,
         measurement: &raumklang_core::Measurement,
+        method: raumklang_core::DeconvolutionMethod,
     ) -> Option<impl Sipper<Self, Self> + use<>> {
         if let State::Computing = self.0 {
             return None;
         }
 
-        if let State::Computed(_) = self.0 {
+        if let State::Computed(..) = self.0 {
             return None;
         }
 
@@ -33,24 +84,82 @@ impl ImpulseResponse {
         let sipper = sipper(async move |mut progress| {
             progress.send(ImpulseResponse(State::Computing)).await;
 
-            let impulse_response = tokio::task::spawn_blocking(move || {
-                raumklang_core::ImpulseResponse::from_signals(&loopback, &measurement)
-            })
-            .await
-            .unwrap()
-            .unwrap();
+            let (impulse_response, drift) =
+                scheduler::run("impulse response", subject, move || {
+                    let drift = raumklang_core::drift::estimate(&loopback, &measurement);
+
+                    let impulse_response = match drift {
+                        Some(ppm) if ppm.abs() > DRIFT_THRESHOLD => {
+                            let measurement = measurement.correct_drift(ppm);
+                            raumklang_core::ImpulseResponse::from_signals_with(
+                                &loopback,
+                                &measurement,
+                                method,
+                            )
+                        }
+                        _ => raumklang_core::ImpulseResponse::from_signals_with(
+                            &loopback,
+                            &measurement,
+                            method,
+                        ),
+                    };
 
-            ImpulseResponse(State::Computed(Arc::new(impulse_response)))
+                    (Arc::new(impulse_response.unwrap()), drift)
+                })
+                .await;
+
+            ImpulseResponse(State::Computed(impulse_response, drift))
         });
 
         Some(sipper)
     }
 
+    /// Like [`ImpulseResponse::compute`], but the impulse response is
+    /// computed by `compute` without a loopback, e.g. with the inverse
+    /// filter of the sweep.
+    pub fn compute_with<F>(
+        self,
+        subject: scheduler::Subject,
+        compute: F,
+    ) -> Option<impl Sipper<Self, Self> + use<F>>
+    where
+        F: FnOnce() -> raumklang_core::ImpulseResponse + Send + 'static,
+    {
+        if let State::Computing | State::Computed(..) = self.0 {
+            return None;
+        }
+
+        let sipper = sipper(async move |mut progress| {
+            progress.send(ImpulseResponse(State::Computing)).await;
+
+            let impulse_response =
+                scheduler::run("impulse response", subject, move || Arc::new(compute())).await;
+
+            ImpulseResponse(State::Computed(impulse_response, None))
+        });
+
+        Some(sipper)
+    }
+
+    /// An impulse response, that was not computed by us, e.g. imported from
+    /// another tool.
+    pub fn imported(impulse_response: raumklang_core::ImpulseResponse) -> Self {
+        Self(State::Computed(Arc::new(impulse_response), None))
+    }
+
     pub fn result(&self) -> Option<&raumklang_core::ImpulseResponse> {
         match self.0 {
             State::None => None,
             State::Computing => None,
-            State::Computed(ref impulse_response) => Some(impulse_response),
+            State::Computed(ref impulse_response, _) => Some(impulse_response),
+        }
+    }
+
+    /// Estimated clock drift between playback and capture in ppm.
+    pub fn drift(&self) -> Option<f32> {
+        match self.0 {
+            State::None | State::Computing => None,
+            State::Computed(_, drift) => drift,
         }
     }
 
@@ -58,11 +167,98 @@ impl ImpulseResponse {
         match self.0 {
             State::None => Progress::None,
             State::Computing => Progress::Computing,
-            State::Computed(_) => Progress::Computed,
+            State::Computed(..) => Progress::Computed,
         }
     }
 }
 
+/// Subtracts the noise, that is recorded in front of the measurement
+/// signal, from the `impulse_response`. `None`, if the silence is too short
+/// to estimate the noise.
+pub async fn denoise(
+    subject: scheduler::Subject,
+    impulse_response: raumklang_core::ImpulseResponse,
+    loopback: raumklang_core::Loopback,
+    measurement: raumklang_core::Measurement,
+    method: raumklang_core::DeconvolutionMethod,
+) -> Option<Arc<raumklang_core::ImpulseResponse>> {
+    scheduler::run("denoise", subject, move || {
+        let noise_floor =
+            raumklang_core::denoise::NoiseFloor::estimate(&loopback, &measurement, method)?;
+
+        Some(Arc::new(raumklang_core::denoise::denoise(
+            &impulse_response,
+            &noise_floor,
+        )))
+    })
+    .await
+}
+
+/// Applies the analysis `window` to the impulse response, after shifting it by
+/// `time_shift` samples.
+pub fn windowed(
+    impulse_response: &raumklang_core::ImpulseResponse,
+    window: &Window<Samples>,
+    time_shift: isize,
+) -> raumklang_core::WindowedImpulseResponse {
+    let offset: usize = window.offset().into();
+
+    let len = impulse_response.data.len() as isize;
+    let offset = (offset as isize + time_shift).rem_euclid(len);
+
+    let window: Vec<_> = window.curve().map(|(_x, y)| y).collect();
+
+    raumklang_core::WindowedImpulseResponse::new(impulse_response, &window, offset as usize)
+}
+
+/// Sum of two windowed impulse responses, e.g. to predict the response of
+/// both speakers playing at once. The relative delay between them is kept,
+/// `None` if they don't share the sample rate or the window.
+pub fn sum(
+    a: &raumklang_core::WindowedImpulseResponse,
+    b: &raumklang_core::WindowedImpulseResponse,
+) -> Option<raumklang_core::WindowedImpulseResponse> {
+    if a.sample_rate != b.sample_rate || a.data.len() != b.data.len() {
+        return None;
+    }
+
+    Some(raumklang_core::WindowedImpulseResponse {
+        sample_rate: a.sample_rate,
+        offset: a.offset,
+        data: a.data.iter().zip(&b.data).map(|(a, b)| a + b).collect(),
+    })
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ExportError {
+    #[error("could not write file: {0}")]
+    Write(String),
+}
+
+/// Writes the windowed impulse response as float WAV file.
+pub async fn export_windowed(
+    path: Arc<Path>,
+    impulse_response: Arc<raumklang_core::WindowedImpulseResponse>,
+    format: raumklang_core::wav::Format,
+    metadata: raumklang_core::wav::Metadata,
+) -> Result<Arc<Path>, ExportError> {
+    tokio::task::spawn_blocking(move || {
+        raumklang_core::wav::write(
+            &path,
+            impulse_response.sample_rate,
+            1,
+            impulse_response.data.iter().copied(),
+            format,
+            Some(&metadata),
+        )?;
+
+        Ok::<_, std::io::Error>(path)
+    })
+    .await
+    .unwrap()
+    .map_err(|err| ExportError::Write(err.to_string()))
+}
+
 #[derive(Debug, Clone)]
 pub enum Progress {
     None,
@@ -0,0 +1,80 @@
+use super::{Error, directory};
+
+use serde::{Deserialize, Serialize};
+
+use std::{fmt, path::PathBuf};
+
+/// User settings of the logger, stored in the data directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Settings {
+    pub level: Level,
+    /// Writes the log to rotating files in [`directory::logs`].
+    #[serde(default)]
+    pub write_file: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    #[default]
+    Debug,
+    Trace,
+}
+
+impl Settings {
+    fn path() -> PathBuf {
+        directory::data().join("logging.json")
+    }
+
+    /// Loads the settings synchronously, as they are needed before the logger
+    /// is initialized.
+    pub fn load() -> Result<Self, Error> {
+        let content = std::fs::read(Self::path())?;
+
+        Ok(serde_json::from_slice(&content)?)
+    }
+
+    pub async fn save(self) -> Result<(), Error> {
+        tokio::fs::create_dir_all(directory::data()).await?;
+
+        let content = serde_json::to_string_pretty(&self)?;
+        tokio::fs::write(Self::path(), content).await?;
+
+        Ok(())
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            level: Level::default(),
+            write_file: true,
+        }
+    }
+}
+
+impl Level {
+    pub const ALL: &[Level] = &[
+        Level::Error,
+        Level::Warn,
+        Level::Info,
+        Level::Debug,
+        Level::Trace,
+    ];
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Level::Error => "Error",
+            Level::Warn => "Warning",
+            Level::Info => "Info",
+            Level::Debug => "Debug",
+            Level::Trace => "Trace",
+        };
+
+        write!(f, "{s}")
+    }
+}
@@ -1,2 +1,7 @@
 pub mod config;
+pub mod info;
+pub mod name;
+pub mod template;
 pub use config::{Config, SignalConfig};
+pub use info::Info;
+pub use template::Templates;
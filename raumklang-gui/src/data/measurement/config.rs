@@ -1,24 +1,44 @@
 use std::{
+    collections::BTreeMap,
+    io,
     num::{ParseFloatError, ParseIntError},
+    path::{Path, PathBuf},
     time,
 };
 
-use crate::data::audio::{InPort, OutPort};
+use crate::data::{
+    audio::{InPort, OutPort, Trim},
+    project,
+};
+
+use super::name;
 
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// Default number of frames buffered between the audio and the recording thread.
+pub const DEFAULT_CAPTURE_BUFFER: usize = 16_384;
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Config {
     pub out_port: Option<OutPort>,
     pub in_port: Option<InPort>,
     pub signal: SignalConfig,
+    /// Playback volume in the range of 0.0 to 1.0
+    pub volume: f32,
+    pub name_template: name::Template,
+    /// Size of the capture buffer in frames
+    pub capture_buffer: usize,
+    /// Silence played before every signal, while the playback device
+    /// unmutes. It is not part of the recording.
+    pub pre_roll: time::Duration,
+    pub output_trims: BTreeMap<OutPort, Trim>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct SignalConfig {
     frequency_range: FrequencyRange,
     duration: Duration,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct FrequencyRange {
     from: u16,
     to: u16,
@@ -90,6 +110,17 @@ impl SignalConfig {
         }
     }
 
+    /// A short sweep, that is played on every output to identify it.
+    pub fn channel_check() -> Self {
+        Self::new(
+            FrequencyRange {
+                from: 100,
+                to: 10_000,
+            },
+            Duration::from_secs(1),
+        )
+    }
+
     pub fn duration(&self) -> Duration {
         self.duration
     }
@@ -103,6 +134,150 @@ impl SignalConfig {
     }
 }
 
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            out_port: None,
+            in_port: None,
+            signal: SignalConfig::default(),
+            volume: 0.5,
+            name_template: name::Template::default(),
+            capture_buffer: DEFAULT_CAPTURE_BUFFER,
+            pre_roll: time::Duration::ZERO,
+            output_trims: BTreeMap::new(),
+        }
+    }
+}
+
+/// Errors of exchanging a [`Config`] as TOML file with other users.
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum ExchangeError {
+    #[error("could not access file: {0}")]
+    Io(io::ErrorKind),
+    #[error("invalid configuration: {0}")]
+    Toml(String),
+}
+
+impl Config {
+    /// Playback level in dB relative to full volume, including the trim of
+    /// the output port. `None`, if the output is muted.
+    pub fn playback_level(&self) -> Option<f32> {
+        let trim = self
+            .out_port
+            .as_ref()
+            .and_then(|port| self.output_trims.get(port))
+            .copied()
+            .unwrap_or_default();
+
+        let amplitude = raumklang_core::volume_to_amplitude(self.volume) * trim.factor();
+
+        Some(raumklang_core::dbfs(amplitude)).filter(|level| level.is_finite())
+    }
+
+    pub fn to_toml(&self) -> Result<String, ExchangeError> {
+        toml::to_string_pretty(&project::Recording::from(self))
+            .map_err(|err| ExchangeError::Toml(err.to_string()))
+    }
+
+    pub fn from_toml(content: &str) -> Result<Self, ExchangeError> {
+        toml::from_str::<project::Recording>(content)
+            .map(Self::from)
+            .map_err(|err| ExchangeError::Toml(err.to_string()))
+    }
+
+    pub async fn import(path: impl AsRef<Path>) -> Result<Self, ExchangeError> {
+        let content = tokio::fs::read_to_string(path.as_ref())
+            .await
+            .map_err(|err| ExchangeError::Io(err.kind()))?;
+
+        Self::from_toml(&content)
+    }
+
+    pub async fn export(self, path: PathBuf) -> Result<PathBuf, ExchangeError> {
+        tokio::fs::write(&path, self.to_toml()?)
+            .await
+            .map_err(|err| ExchangeError::Io(err.kind()))?;
+
+        Ok(path)
+    }
+}
+
+impl From<&Config> for project::Recording {
+    fn from(config: &Config) -> Self {
+        Self {
+            out_port: config.out_port.as_ref().map(OutPort::to_string),
+            in_port: config.in_port.as_ref().map(InPort::to_string),
+            start_frequency: config.signal.start_frequency(),
+            end_frequency: config.signal.end_frequency(),
+            duration: config.signal.duration().into_inner().as_secs_f32(),
+            volume: config.volume,
+            name_template: config.name_template.as_str().to_string(),
+            capture_buffer: Some(config.capture_buffer),
+            pre_roll: Some(config.pre_roll.as_secs_f32()),
+            output_trims: config
+                .output_trims
+                .iter()
+                .map(|(port, trim)| {
+                    let trim = project::OutputTrim {
+                        gain: trim.gain,
+                        muted: trim.muted,
+                    };
+
+                    (port.to_string(), trim)
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<project::Recording> for Config {
+    fn from(recording: project::Recording) -> Self {
+        let frequency_range = if recording.start_frequency < recording.end_frequency {
+            FrequencyRange {
+                from: recording.start_frequency,
+                to: recording.end_frequency,
+            }
+        } else {
+            FrequencyRange::default()
+        };
+
+        let duration = if recording.duration > 0.0 {
+            Duration(time::Duration::from_secs_f32(recording.duration))
+        } else {
+            Duration::from_secs(5)
+        };
+
+        Self {
+            out_port: recording.out_port.map(OutPort::new),
+            in_port: recording.in_port.map(InPort::new),
+            signal: SignalConfig::new(frequency_range, duration),
+            volume: recording.volume.clamp(0.0, 1.0),
+            name_template: name::Template::new(recording.name_template),
+            capture_buffer: recording
+                .capture_buffer
+                .filter(|size| *size > 0)
+                .unwrap_or(DEFAULT_CAPTURE_BUFFER),
+            pre_roll: recording
+                .pre_roll
+                .filter(|secs| secs.is_finite() && *secs >= 0.0)
+                .map(time::Duration::from_secs_f32)
+                .unwrap_or_default(),
+            output_trims: recording
+                .output_trims
+                .into_iter()
+                .map(|(port, trim)| {
+                    let trim = Trim {
+                        gain: trim.gain.clamp(Trim::MIN_GAIN, Trim::MAX_GAIN),
+                        muted: trim.muted,
+                    };
+
+                    (OutPort::new(port), trim)
+                })
+                .collect(),
+        }
+    }
+}
+
 impl Default for FrequencyRange {
     fn default() -> Self {
         Self {
@@ -120,3 +295,58 @@ impl Default for SignalConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn toml_round_trip() {
+        let mut config = Config {
+            out_port: Some(OutPort::new("system:playback_1".to_string())),
+            in_port: Some(InPort::new("system:capture_1".to_string())),
+            volume: 0.25,
+            pre_roll: time::Duration::from_millis(250),
+            ..Config::default()
+        };
+        config.output_trims.insert(
+            OutPort::new("system:playback_1".to_string()),
+            Trim {
+                gain: -3.0,
+                muted: false,
+            },
+        );
+
+        let toml = config.to_toml().unwrap();
+
+        assert_eq!(Config::from_toml(&toml).unwrap(), config);
+    }
+
+    #[test]
+    fn playback_level_includes_trim() {
+        let port = OutPort::new("system:playback_1".to_string());
+        let mut config = Config {
+            out_port: Some(port.clone()),
+            volume: 1.0,
+            ..Config::default()
+        };
+
+        config.output_trims.insert(
+            port.clone(),
+            Trim {
+                gain: -6.0,
+                muted: false,
+            },
+        );
+        assert!((config.playback_level().unwrap() + 6.0).abs() < 0.01);
+
+        config.output_trims.insert(
+            port,
+            Trim {
+                gain: 0.0,
+                muted: true,
+            },
+        );
+        assert_eq!(config.playback_level(), None);
+    }
+}
@@ -0,0 +1,42 @@
+use std::time::Duration;
+
+/// Properties of a recording, shown in the info panel of a measurement.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Info {
+    pub samples: usize,
+    pub sample_rate: u32,
+    /// Highest sample in dBFS.
+    pub peak: f32,
+    /// Level of the whole recording in dBFS.
+    pub rms: f32,
+    /// Mean of all samples relative to full scale.
+    pub dc_offset: f32,
+}
+
+impl Info {
+    /// DC offsets below are not worth to be removed.
+    const DC_OFFSET_THRESHOLD: f32 = 1e-4;
+
+    pub fn from_signal(signal: &raumklang_core::Measurement) -> Self {
+        let peak = signal.iter().map(|s| s.abs()).fold(0.0, f32::max);
+
+        let square_sum: f64 = signal.iter().map(|s| f64::from(*s).powi(2)).sum();
+        let rms = (square_sum / signal.duration().max(1) as f64).sqrt() as f32;
+
+        Self {
+            samples: signal.duration(),
+            sample_rate: signal.sample_rate(),
+            peak: raumklang_core::dbfs(peak),
+            rms: raumklang_core::dbfs(rms),
+            dc_offset: signal.dc_offset(),
+        }
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.samples as f64 / f64::from(self.sample_rate.max(1)))
+    }
+
+    pub fn has_dc_offset(&self) -> bool {
+        self.dc_offset.abs() >= Self::DC_OFFSET_THRESHOLD
+    }
+}
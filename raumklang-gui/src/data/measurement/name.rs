@@ -0,0 +1,72 @@
+use chrono::{DateTime, Local};
+
+/// Template for the names of new measurements, supports the placeholders
+/// `{channel}`, `{position}`, `{date}` and `{time}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template(String);
+
+/// Values that are filled into a [`Template`].
+#[derive(Debug, Clone)]
+pub struct Fields<'a> {
+    pub channel: &'a str,
+    pub position: usize,
+    pub date_time: DateTime<Local>,
+}
+
+impl Template {
+    pub fn new(template: impl Into<String>) -> Self {
+        Self(template.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn render(&self, fields: &Fields<'_>) -> String {
+        let name = self
+            .0
+            .replace("{channel}", fields.channel)
+            .replace("{position}", &fields.position.to_string())
+            .replace("{date}", &fields.date_time.format("%Y-%m-%d").to_string())
+            .replace("{time}", &fields.date_time.format("%H:%M:%S").to_string());
+
+        let name = name.trim();
+        if name.is_empty() {
+            fields.channel.to_string()
+        } else {
+            name.to_string()
+        }
+    }
+}
+
+impl Default for Template {
+    fn default() -> Self {
+        Self("{channel}-{position}-{date}".to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use chrono::TimeZone;
+
+    #[test]
+    fn placeholders_are_replaced() {
+        let fields = Fields {
+            channel: "Left",
+            position: 3,
+            date_time: Local.with_ymd_and_hms(2025, 3, 14, 9, 5, 0).unwrap(),
+        };
+
+        assert_eq!(
+            Template::default().render(&fields),
+            "Left-3-2025-03-14".to_string()
+        );
+        assert_eq!(
+            Template::new("{time} {unknown}").render(&fields),
+            "09:05:00 {unknown}".to_string()
+        );
+        assert_eq!(Template::new(" ").render(&fields), "Left".to_string());
+    }
+}
@@ -0,0 +1,100 @@
+use super::Config;
+use crate::data::directory;
+
+use raumklang_core::template::{self, Template};
+
+use serde::{Deserialize, Serialize};
+
+use std::{io, path::PathBuf};
+
+/// Measurement templates, that are shared with the CLI, see
+/// [`raumklang_core::template`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Templates(Vec<Template>);
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum Error {
+    #[error("could not access file: {0}")]
+    Io(io::ErrorKind),
+    #[error("invalid template: {0}")]
+    Json(String),
+}
+
+/// Template of the signal and the playback volume of `config`, the trim of
+/// the output port is not included.
+pub fn from_config(name: String, config: &Config) -> Template {
+    Template {
+        name,
+        start_frequency: config.signal.start_frequency(),
+        end_frequency: config.signal.end_frequency(),
+        duration: config.signal.duration().into_inner().as_secs_f32(),
+        level: raumklang_core::dbfs(raumklang_core::volume_to_amplitude(config.volume)),
+    }
+}
+
+impl Templates {
+    async fn path() -> Result<PathBuf, Error> {
+        let path = directory::config();
+
+        tokio::fs::create_dir_all(&path)
+            .await
+            .map_err(|err| Error::Io(err.kind()))?;
+
+        Ok(path.join(template::FILE_NAME))
+    }
+
+    pub async fn load() -> Result<Self, Error> {
+        let path = Self::path().await?;
+
+        let content = match tokio::fs::read(path).await {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(Error::Io(err.kind())),
+        };
+
+        serde_json::from_slice(&content).map_err(|err| Error::Json(err.to_string()))
+    }
+
+    pub async fn save(self) -> Result<(), Error> {
+        let path = Self::path().await?;
+
+        let json =
+            serde_json::to_string_pretty(&self).map_err(|err| Error::Json(err.to_string()))?;
+
+        tokio::fs::write(path, json)
+            .await
+            .map_err(|err| Error::Io(err.kind()))
+    }
+
+    /// Adds the template, an existing template with the same name is
+    /// replaced.
+    pub fn insert(&mut self, template: Template) {
+        self.0
+            .retain(|t| !t.name.eq_ignore_ascii_case(&template.name));
+        self.0.push(template);
+        self.0.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    pub fn as_slice(&self) -> &[Template] {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn template_keeps_the_playback_level() {
+        let config = Config {
+            volume: 0.7,
+            ..Config::default()
+        };
+
+        let template = from_config("Room".to_string(), &config);
+
+        assert_eq!(template.start_frequency, 20);
+        assert_eq!(template.end_frequency, 20_000);
+        assert!((template.volume() - config.volume).abs() < 0.001);
+    }
+}
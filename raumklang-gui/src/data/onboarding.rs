@@ -0,0 +1,32 @@
+/// State of the first-run setup wizard.
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
+pub struct Onboarding {
+    #[serde(default)]
+    pub completed: bool,
+}
+
+impl Onboarding {
+    async fn path() -> Result<std::path::PathBuf, super::Error> {
+        let path = super::directory::data();
+        tokio::fs::create_dir_all(path).await?;
+
+        Ok(path.join("onboarding.json"))
+    }
+
+    pub async fn load() -> Result<Self, super::Error> {
+        let content = match tokio::fs::read(Self::path().await?).await {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err.into()),
+        };
+
+        Ok(serde_json::from_slice(&content)?)
+    }
+
+    pub async fn save(self) -> Result<(), super::Error> {
+        let content = serde_json::to_string_pretty(&self)?;
+        tokio::fs::write(Self::path().await?, content).await?;
+
+        Ok(())
+    }
+}
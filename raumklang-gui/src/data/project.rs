@@ -1,7 +1,12 @@
+use super::chart::{AmplitudeUnit, TimeSeriesUnit};
+
+use raumklang_core::preprocess::Preprocessing;
+
 use serde::{Deserialize, Serialize};
 use tokio::fs;
 
 use std::{
+    collections::BTreeMap,
     fmt, io,
     path::{Path, PathBuf},
 };
@@ -14,6 +19,109 @@ pub struct Project {
     pub measurement_operation: Operation,
     #[serde(default)]
     pub export_from_memory: bool,
+    /// Format of exported WAV files.
+    #[serde(default)]
+    pub wav_format: WavFormat,
+    #[serde(default)]
+    pub mode: Mode,
+    #[serde(default)]
+    pub recording: Option<Recording>,
+    /// Calibration file of the measurement microphone.
+    #[serde(default)]
+    pub calibration: Option<PathBuf>,
+    #[serde(default)]
+    pub charts: Charts,
+    /// Applied to the recordings before the deconvolution.
+    #[serde(default)]
+    pub preprocessing: Preprocessing,
+}
+
+/// View state of the analysis tabs, restored when the project is opened.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Charts {
+    /// Index of the measurement, that is selected in the analysis tabs
+    pub selected: Option<usize>,
+    pub impulse_response: ImpulseResponseView,
+    /// Visible range of the chart, the default range if not set
+    pub frequency_response: Option<FrequencyResponseView>,
+    pub spectrogram: SpectrogramView,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImpulseResponseView {
+    pub zoom: f32,
+    /// Offset in samples
+    pub offset: i64,
+    pub amplitude_unit: AmplitudeUnit,
+    pub time_unit: TimeSeriesUnit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FrequencyResponseView {
+    /// Frequency range in Hz
+    pub frequency: (f32, f32),
+    /// Level range in dB
+    pub level: (f32, f32),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpectrogramView {
+    pub zoom: f32,
+    /// Offset in samples
+    pub offset: isize,
+}
+
+impl Default for ImpulseResponseView {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            offset: 0,
+            amplitude_unit: AmplitudeUnit::default(),
+            time_unit: TimeSeriesUnit::default(),
+        }
+    }
+}
+
+impl Default for SpectrogramView {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            offset: 0,
+        }
+    }
+}
+
+/// Last used recording configuration, to pre-fill the recording dialog.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recording {
+    pub out_port: Option<String>,
+    pub in_port: Option<String>,
+    pub start_frequency: u16,
+    pub end_frequency: u16,
+    /// Sweep duration in seconds
+    pub duration: f32,
+    pub volume: f32,
+    pub name_template: String,
+    /// Capture buffer size in frames
+    #[serde(default)]
+    pub capture_buffer: Option<usize>,
+    /// Silence in seconds, that is played before the signal
+    #[serde(default)]
+    pub pre_roll: Option<f32>,
+    /// Gain trims by output port name
+    #[serde(default)]
+    pub output_trims: BTreeMap<String, OutputTrim>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct OutputTrim {
+    /// Gain in dB
+    pub gain: f32,
+    #[serde(default)]
+    pub muted: bool,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -21,7 +129,7 @@ pub struct Loopback(pub Measurement);
 
 impl Loopback {
     pub fn new(path: PathBuf) -> Self {
-        Self(Measurement { path })
+        Self(Measurement::new(path))
     }
 
     pub async fn copy(&mut self, dest: impl AsRef<Path>) {
@@ -36,11 +144,36 @@ impl Loopback {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Measurement {
     pub path: PathBuf,
+    /// Playback level in dB relative to full volume, see
+    /// [`crate::ui::Measurement::playback_level`].
+    #[serde(default)]
+    pub playback_level: Option<f32>,
+    /// Crest factors and peak of the recording, see
+    /// [`raumklang_core::loudness::LevelReport`].
+    #[serde(default)]
+    pub levels: Option<raumklang_core::loudness::LevelReport>,
+    #[serde(default)]
+    pub source: Source,
+}
+
+/// Where the file of a measurement comes from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Source {
+    /// A recording of the sweep, that is deconvolved with the loopback.
+    #[default]
+    Recording,
+    /// An impulse response or a measurement text export of REW.
+    Rew,
 }
 
 impl Measurement {
     pub fn new(path: PathBuf) -> Self {
-        Self { path }
+        Self {
+            path,
+            playback_level: None,
+            levels: None,
+            source: Source::Recording,
+        }
     }
 
     pub async fn copy(&mut self, dest: impl AsRef<Path>) {
@@ -154,3 +287,74 @@ impl fmt::Display for Operation {
         write!(f, "{}", s)
     }
 }
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WavFormat {
+    #[default]
+    Float32,
+    Float64,
+    Float32Rf64,
+    Float64Rf64,
+}
+
+impl WavFormat {
+    pub const ALL: &[WavFormat] = &[
+        WavFormat::Float32,
+        WavFormat::Float64,
+        WavFormat::Float32Rf64,
+        WavFormat::Float64Rf64,
+    ];
+}
+
+impl fmt::Display for WavFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            WavFormat::Float32 => "32 bit float",
+            WavFormat::Float64 => "64 bit float",
+            WavFormat::Float32Rf64 => "32 bit float, RF64",
+            WavFormat::Float64Rf64 => "64 bit float, RF64",
+        };
+
+        write!(f, "{}", s)
+    }
+}
+
+impl From<WavFormat> for raumklang_core::wav::Format {
+    fn from(format: WavFormat) -> Self {
+        use raumklang_core::wav::SampleFormat;
+
+        let (sample_format, rf64) = match format {
+            WavFormat::Float32 => (SampleFormat::Float32, false),
+            WavFormat::Float64 => (SampleFormat::Float64, false),
+            WavFormat::Float32Rf64 => (SampleFormat::Float32, true),
+            WavFormat::Float64Rf64 => (SampleFormat::Float64, true),
+        };
+
+        Self {
+            sample_format,
+            rf64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Mode {
+    #[default]
+    Room,
+    Headphone,
+}
+
+impl Mode {
+    pub const ALL: &[Mode] = &[Mode::Room, Mode::Headphone];
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Mode::Room => "Room",
+            Mode::Headphone => "Headphone",
+        };
+
+        write!(f, "{}", s)
+    }
+}
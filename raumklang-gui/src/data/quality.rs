@@ -0,0 +1,160 @@
+use std::fmt;
+
+/// Samples at or above this level are considered clipped.
+const CLIPPING_LEVEL: f32 = 0.999;
+
+/// Block length in ms used to estimate signal and noise levels.
+const BLOCK_DURATION: u32 = 100;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Quality {
+    /// Level difference between the loudest and the quietest block of the
+    /// recording in dB.
+    pub snr: Option<f32>,
+    pub clipped_samples: usize,
+    /// Clock drift between playback and capture in ppm.
+    pub drift: Option<f32>,
+    /// Distance of the impulse response peak to its noise floor in dB.
+    pub noise_floor_margin: Option<f32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Rating {
+    Good,
+    Fair,
+    Poor,
+}
+
+impl Quality {
+    pub fn from_signal(signal: &raumklang_core::Measurement) -> Self {
+        let clipped_samples = signal.iter().filter(|s| s.abs() >= CLIPPING_LEVEL).count();
+
+        let block_len = (signal.sample_rate() * BLOCK_DURATION / 1000) as usize;
+        let data: Vec<f32> = signal.iter().copied().collect();
+        let levels: Vec<f32> = data
+            .chunks_exact(block_len.max(1))
+            .map(|block| block.iter().map(|s| s * s).sum::<f32>() / block.len() as f32)
+            .collect();
+
+        let loudest = levels.iter().copied().max_by(f32::total_cmp);
+        let quietest = levels
+            .iter()
+            .copied()
+            .filter(|l| *l > 0.0)
+            .min_by(f32::total_cmp);
+
+        let snr = loudest
+            .zip(quietest)
+            .map(|(loudest, quietest)| 10.0 * f32::log10(loudest / quietest));
+
+        Self {
+            snr,
+            clipped_samples,
+            drift: None,
+            noise_floor_margin: None,
+        }
+    }
+
+    pub fn with_impulse_response(mut self, noise_floor_margin: f32, drift: Option<f32>) -> Self {
+        self.noise_floor_margin = Some(noise_floor_margin);
+        self.drift = drift;
+        self
+    }
+
+    pub fn rating(&self) -> Rating {
+        self.issues()
+            .iter()
+            .map(|(rating, _)| *rating)
+            .max()
+            .unwrap_or(Rating::Good)
+    }
+
+    /// Everything that degraded the rating, with a short explanation.
+    pub fn issues(&self) -> Vec<(Rating, String)> {
+        let mut issues = vec![];
+
+        if self.clipped_samples > 0 {
+            issues.push((
+                Rating::Poor,
+                format!("{} clipped samples", self.clipped_samples),
+            ));
+        }
+
+        if let Some(snr) = self.snr {
+            if snr < 20.0 {
+                issues.push((Rating::Poor, format!("low SNR ({snr:.0} dB)")));
+            } else if snr < 40.0 {
+                issues.push((Rating::Fair, format!("moderate SNR ({snr:.0} dB)")));
+            }
+        }
+
+        if let Some(margin) = self.noise_floor_margin {
+            if margin < 40.0 {
+                issues.push((
+                    Rating::Poor,
+                    format!("noise floor only {margin:.0} dB below peak"),
+                ));
+            } else if margin < 60.0 {
+                issues.push((
+                    Rating::Fair,
+                    format!("noise floor {margin:.0} dB below peak"),
+                ));
+            }
+        }
+
+        if let Some(drift) = self.drift
+            && drift.abs() > 5.0
+        {
+            issues.push((Rating::Fair, format!("clock drift of {drift:+.1} ppm")));
+        }
+
+        issues
+    }
+}
+
+/// Distance of the impulse response peak to the noise floor, which is
+/// estimated from the last tenth of the impulse response, in dB.
+pub fn noise_floor_margin(impulse_response: &raumklang_core::ImpulseResponse) -> f32 {
+    let data = &impulse_response.data;
+
+    let peak = data
+        .iter()
+        .map(|s| s.re * s.re)
+        .max_by(f32::total_cmp)
+        .unwrap_or_default();
+
+    let tail = &data[data.len() - data.len() / 10..];
+    let noise = tail.iter().map(|s| s.re * s.re).sum::<f32>() / tail.len().max(1) as f32;
+
+    10.0 * f32::log10(peak / noise)
+}
+
+impl fmt::Display for Rating {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Rating::Good => "Good",
+            Rating::Fair => "Fair",
+            Rating::Poor => "Poor",
+        };
+
+        write!(f, "{s}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn clipping_rates_poor() {
+        let data = (0..48_000)
+            .map(|i| if i % 1000 == 0 { 1.0 } else { 0.5 })
+            .collect();
+        let signal = raumklang_core::Measurement::new(48_000, data);
+
+        let quality = Quality::from_signal(&signal);
+
+        assert_eq!(quality.clipped_samples, 48);
+        assert_eq!(quality.rating(), Rating::Poor);
+    }
+}
@@ -0,0 +1,72 @@
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    path::PathBuf,
+};
+
+/// Settings of the OSC remote control. OSC messages are not authenticated,
+/// so the listener is off by default and only accepts local clients, unless
+/// the network is allowed explicitly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct RemoteControl {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Accepts messages from other machines, e.g. a tablet in the room.
+    #[serde(default)]
+    pub allow_network: bool,
+    /// UDP port, that is listened on.
+    #[serde(default = "default_port")]
+    pub port: u16,
+}
+
+impl RemoteControl {
+    pub const DEFAULT_PORT: u16 = 9_000;
+
+    async fn path() -> Result<PathBuf, super::Error> {
+        let path = super::directory::data();
+        tokio::fs::create_dir_all(path).await?;
+
+        Ok(path.join("remote_control.json"))
+    }
+
+    pub async fn load() -> Result<Self, super::Error> {
+        let content = match tokio::fs::read(Self::path().await?).await {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err.into()),
+        };
+
+        Ok(serde_json::from_slice(&content)?)
+    }
+
+    pub async fn save(self) -> Result<(), super::Error> {
+        let content = serde_json::to_string_pretty(&self)?;
+        tokio::fs::write(Self::path().await?, content).await?;
+
+        Ok(())
+    }
+
+    /// Address of the OSC socket.
+    pub fn address(&self) -> SocketAddr {
+        let ip = if self.allow_network {
+            Ipv4Addr::UNSPECIFIED
+        } else {
+            Ipv4Addr::LOCALHOST
+        };
+
+        SocketAddr::from((ip, self.port))
+    }
+}
+
+impl Default for RemoteControl {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            allow_network: false,
+            port: Self::DEFAULT_PORT,
+        }
+    }
+}
+
+fn default_port() -> u16 {
+    RemoteControl::DEFAULT_PORT
+}
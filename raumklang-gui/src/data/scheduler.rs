@@ -0,0 +1,250 @@
+//! Runs the analyses on a fixed number of worker threads, instead of a
+//! blocking task per request.
+//!
+//! Queued jobs of the measurements on screen are picked first, the most
+//! recent one first. Submitting a job replaces a queued job of the same
+//! kind for the same subject, its callers get the result of the newer job.
+
+use std::{
+    any::Any,
+    collections::BTreeSet,
+    hash::{DefaultHasher, Hash, Hasher},
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Condvar, LazyLock, Mutex},
+    thread,
+};
+
+use tokio::sync::oneshot;
+
+static SCHEDULER: LazyLock<Arc<Scheduler>> = LazyLock::new(|| {
+    let scheduler = Arc::new(Scheduler::default());
+
+    for i in 0..workers() {
+        let scheduler = Arc::clone(&scheduler);
+
+        thread::Builder::new()
+            .name(format!("analysis-{i}"))
+            .spawn(move || scheduler.work())
+            .expect("spawn analysis worker");
+    }
+
+    scheduler
+});
+
+/// What a job is computed for, e.g. a measurement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Subject(u64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Priority {
+    Background,
+    Visible,
+}
+
+#[derive(Default)]
+struct Scheduler {
+    queue: Mutex<Queue>,
+    available: Condvar,
+}
+
+#[derive(Default)]
+struct Queue {
+    /// In order of submission.
+    jobs: Vec<Job>,
+    visible: BTreeSet<Subject>,
+}
+
+struct Job {
+    kind: &'static str,
+    subject: Subject,
+    /// The [`Pending`] job, its output type is only known to `execute`.
+    pending: Box<dyn Any + Send>,
+    run: fn(Box<dyn Any + Send>),
+}
+
+struct Pending<T> {
+    work: Box<dyn FnOnce() -> T + Send>,
+    waiters: Vec<oneshot::Sender<T>>,
+}
+
+impl Subject {
+    pub fn new(subject: impl Hash) -> Self {
+        let mut hasher = DefaultHasher::new();
+        subject.hash(&mut hasher);
+
+        Self(hasher.finish())
+    }
+}
+
+/// Gives the jobs of the `subjects` precedence over all others, e.g. of
+/// the measurements on screen.
+pub fn focus(subjects: impl IntoIterator<Item = Subject>) {
+    let mut queue = SCHEDULER.queue.lock().unwrap();
+    queue.visible = subjects.into_iter().collect();
+}
+
+/// Runs the `kind` of job for `subject` on one of the worker threads.
+pub async fn run<T>(
+    kind: &'static str,
+    subject: Subject,
+    work: impl FnOnce() -> T + Send + 'static,
+) -> T
+where
+    T: Clone + Send + 'static,
+{
+    let (sender, receiver) = oneshot::channel();
+
+    SCHEDULER.queue.lock().unwrap().push(
+        kind,
+        subject,
+        Pending {
+            work: Box::new(work),
+            waiters: vec![sender],
+        },
+    );
+    SCHEDULER.available.notify_one();
+
+    receiver.await.expect("analysis job panicked")
+}
+
+impl Scheduler {
+    fn work(&self) {
+        loop {
+            let job = {
+                let mut queue = self.queue.lock().unwrap();
+
+                loop {
+                    if let Some(job) = queue.next() {
+                        break job;
+                    }
+
+                    queue = self.available.wait(queue).unwrap();
+                }
+            };
+
+            (job.run)(job.pending);
+        }
+    }
+}
+
+impl Queue {
+    fn push<T>(&mut self, kind: &'static str, subject: Subject, mut pending: Pending<T>)
+    where
+        T: Clone + Send + 'static,
+    {
+        let replaced = self.jobs.iter().position(|job| {
+            job.kind == kind && job.subject == subject && job.pending.is::<Pending<T>>()
+        });
+
+        if let Some(i) = replaced {
+            let job = self.jobs.remove(i);
+
+            if let Ok(replaced) = job.pending.downcast::<Pending<T>>() {
+                pending.waiters.extend(replaced.waiters);
+            }
+        }
+
+        self.jobs.push(Job {
+            kind,
+            subject,
+            pending: Box::new(pending),
+            run: execute::<T>,
+        });
+    }
+
+    fn next(&mut self) -> Option<Job> {
+        let (i, _) = self
+            .jobs
+            .iter()
+            .enumerate()
+            .max_by_key(|(i, job)| (self.priority(job.subject), *i))?;
+
+        Some(self.jobs.remove(i))
+    }
+
+    fn priority(&self, subject: Subject) -> Priority {
+        if self.visible.contains(&subject) {
+            Priority::Visible
+        } else {
+            Priority::Background
+        }
+    }
+}
+
+fn execute<T: Clone + Send + 'static>(pending: Box<dyn Any + Send>) {
+    let Ok(pending) = pending.downcast::<Pending<T>>() else {
+        return;
+    };
+
+    let Pending { work, mut waiters } = *pending;
+
+    // a panicking job drops its waiters, but keeps the worker alive
+    let Ok(output) = panic::catch_unwind(AssertUnwindSafe(work)) else {
+        return;
+    };
+
+    if let Some(last) = waiters.pop() {
+        for waiter in waiters {
+            let _ = waiter.send(output.clone());
+        }
+
+        let _ = last.send(output);
+    }
+}
+
+/// Leaves one core to the user interface and audio.
+fn workers() -> usize {
+    thread::available_parallelism()
+        .map_or(1, |n| n.get().saturating_sub(1))
+        .max(1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn pending(output: u32) -> (Pending<u32>, oneshot::Receiver<u32>) {
+        let (sender, receiver) = oneshot::channel();
+
+        let pending = Pending {
+            work: Box::new(move || output),
+            waiters: vec![sender],
+        };
+
+        (pending, receiver)
+    }
+
+    #[test]
+    fn newer_job_replaces_queued_one() {
+        let mut queue = Queue::default();
+        let subject = Subject::new(1);
+
+        let (first, mut first_receiver) = pending(1);
+        let (second, mut second_receiver) = pending(2);
+        queue.push("frequency response", subject, first);
+        queue.push("frequency response", subject, second);
+
+        assert_eq!(queue.jobs.len(), 1);
+
+        let job = queue.next().unwrap();
+        (job.run)(job.pending);
+
+        assert_eq!(first_receiver.try_recv(), Ok(2));
+        assert_eq!(second_receiver.try_recv(), Ok(2));
+    }
+
+    #[test]
+    fn visible_jobs_are_picked_first() {
+        let mut queue = Queue::default();
+        let visible = Subject::new(1);
+        let background = Subject::new(2);
+
+        queue.push("spectrogram", visible, pending(1).0);
+        queue.push("spectrogram", background, pending(2).0);
+        queue.visible = BTreeSet::from([visible]);
+
+        assert_eq!(queue.next().map(|job| job.subject), Some(visible));
+        assert_eq!(queue.next().map(|job| job.subject), Some(background));
+        assert!(queue.next().is_none());
+    }
+}
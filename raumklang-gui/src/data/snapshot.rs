@@ -0,0 +1,180 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use super::Project;
+
+/// Number of sections of the measurement thumbnails.
+const THUMBNAIL_LEN: usize = 500;
+
+/// State of the application, that can be attached to bug reports. File
+/// paths are replaced by generic names and the recordings are reduced to
+/// their envelopes.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Snapshot {
+    pub version: String,
+    pub os: String,
+    pub project: Project,
+    pub signals: Vec<Thumbnail>,
+    pub settings: BTreeMap<String, String>,
+    pub log: Vec<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Thumbnail {
+    pub name: String,
+    pub sample_rate: u32,
+    /// Length in samples
+    pub len: usize,
+    /// Minimum and maximum of each section.
+    pub envelope: Vec<(f32, f32)>,
+}
+
+impl Snapshot {
+    pub fn new(
+        mut project: Project,
+        signals: Vec<Thumbnail>,
+        settings: BTreeMap<String, String>,
+        log: Vec<String>,
+    ) -> Self {
+        let mut replacements = vec![];
+        let mut replace = |path: &mut PathBuf, name: String| {
+            let name = match path.extension() {
+                Some(extension) => format!("{name}.{}", extension.to_string_lossy()),
+                None => name,
+            };
+
+            let anonymized = PathBuf::from(name);
+            replacements.push((path.display().to_string(), anonymized.display().to_string()));
+            *path = anonymized;
+        };
+
+        if let Some(loopback) = project.loopback.as_mut() {
+            replace(&mut loopback.0.path, "loopback".to_string());
+        }
+        for (i, measurement) in project.measurements.iter_mut().enumerate() {
+            replace(&mut measurement.path, format!("measurement_{i}"));
+        }
+        if let Some(calibration) = project.calibration.as_mut() {
+            replace(calibration, "calibration".to_string());
+        }
+
+        // longer paths first, in case one contains another
+        replacements.sort_by_key(|(path, _)| std::cmp::Reverse(path.len()));
+        if let Some(home) = directories::UserDirs::new() {
+            replacements.push((home.home_dir().display().to_string(), "~".to_string()));
+        }
+
+        let anonymize = |mut s: String| {
+            for (path, anonymized) in &replacements {
+                s = s.replace(path, anonymized);
+            }
+            s
+        };
+
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            os: format!("{} ({})", std::env::consts::OS, std::env::consts::ARCH),
+            project,
+            signals,
+            settings: settings
+                .into_iter()
+                .map(|(key, value)| (key, anonymize(value)))
+                .collect(),
+            log: log.into_iter().map(anonymize).collect(),
+        }
+    }
+
+    pub async fn save(self, path: PathBuf) -> Result<PathBuf, super::Error> {
+        let content = serde_json::to_string_pretty(&self)?;
+        tokio::fs::write(&path, content).await?;
+
+        Ok(path)
+    }
+}
+
+impl Thumbnail {
+    pub fn new(name: impl Into<String>, signal: &raumklang_core::Measurement) -> Self {
+        let data: Vec<f32> = signal.iter().copied().collect();
+        let section = data.len().div_ceil(THUMBNAIL_LEN).max(1);
+
+        let envelope = data
+            .chunks(section)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), &s| {
+                        (min.min(s), max.max(s))
+                    })
+            })
+            .collect();
+
+        Self {
+            name: name.into(),
+            sample_rate: signal.sample_rate(),
+            len: data.len(),
+            envelope,
+        }
+    }
+}
+
+/// File name of a snapshot for the project at `path`.
+pub fn file_name(path: Option<&Path>) -> String {
+    let stem = path
+        .and_then(Path::file_stem)
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "raumklang".to_string());
+
+    format!("{stem}-snapshot.json")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::data::project;
+
+    #[test]
+    fn paths_are_anonymized() {
+        let project = Project {
+            loopback: Some(project::Loopback::new(PathBuf::from(
+                "/home/jane/room/loopback.wav",
+            ))),
+            measurements: vec![project::Measurement::new(PathBuf::from(
+                "/home/jane/room/left.wav",
+            ))],
+            measurement_operation: Default::default(),
+            export_from_memory: false,
+            wav_format: Default::default(),
+            mode: Default::default(),
+            recording: None,
+            calibration: None,
+            charts: Default::default(),
+            preprocessing: Default::default(),
+        };
+
+        let snapshot = Snapshot::new(
+            project,
+            vec![],
+            BTreeMap::new(),
+            vec!["Measurement loaded: /home/jane/room/left.wav".to_string()],
+        );
+
+        assert_eq!(
+            snapshot.project.measurements[0].path,
+            PathBuf::from("measurement_0.wav")
+        );
+        assert_eq!(snapshot.log, ["Measurement loaded: measurement_0.wav"]);
+    }
+
+    #[test]
+    fn thumbnail_keeps_envelope() {
+        let signal = raumklang_core::Measurement::new(48_000, vec![0.5; 1_000]);
+
+        let thumbnail = Thumbnail::new("Left", &signal);
+
+        assert_eq!(thumbnail.envelope.len(), THUMBNAIL_LEN);
+        assert_eq!(thumbnail.envelope[0], (0.5, 0.5));
+    }
+}
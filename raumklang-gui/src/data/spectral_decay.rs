@@ -1,4 +1,9 @@
-use std::{fmt, sync::Arc, time::Duration};
+use std::{
+    fmt, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
 use raumklang_core::{Window, WindowBuilder};
 use rustfft::{
@@ -6,7 +11,10 @@ use rustfft::{
     num_complex::{Complex, Complex32},
 };
 
-use crate::data::{SampleRate, Samples, smooth_fractional_octave};
+use crate::{
+    data::{SampleRate, Samples, scheduler, smooth_fractional_octave},
+    unit,
+};
 
 #[derive(Clone)]
 pub struct SpectralDecay(Vec<super::FrequencyResponse>);
@@ -28,7 +36,8 @@ impl fmt::Debug for SpectralDecay {
 }
 
 pub(crate) async fn compute(
-    ir: raumklang_core::ImpulseResponse,
+    subject: scheduler::Subject,
+    ir: Arc<raumklang_core::WindowedImpulseResponse>,
     preferences: Config,
 ) -> SpectralDecay {
     let sample_rate = SampleRate::from(ir.sample_rate);
@@ -46,13 +55,13 @@ pub(crate) async fn compute(
     );
     let window = window.build();
 
-    let ir: Vec<_> = (0..usize::from(left_width))
-        .map(|_| Complex32::from(0.0))
-        .chain(
-            ir.data
-                .into_iter()
-                .take(usize::from(analysis_width + right_width)),
+    let ir: Vec<_> = ir
+        .around_start(
+            left_width.into(),
+            usize::from(left_width + analysis_width + right_width),
         )
+        .into_iter()
+        .map(Complex32::from)
         .collect();
 
     let mut start = 0;
@@ -60,7 +69,7 @@ pub(crate) async fn compute(
 
     let analysis_width: usize = analysis_width.into();
 
-    tokio::task::spawn_blocking(move || {
+    scheduler::run("spectral decay", subject, move || {
         let mut frequency_responses =
             Vec::with_capacity((analysis_width - usize::from(left_width)) / shift);
 
@@ -90,6 +99,7 @@ pub(crate) async fn compute(
             frequency_responses.push(super::FrequencyResponse {
                 sample_rate: u32::from(sample_rate),
                 data: Arc::new(data),
+                phase: None,
             });
 
             start += shift;
@@ -98,7 +108,137 @@ pub(crate) async fn compute(
         SpectralDecay(frequency_responses)
     })
     .await
-    .unwrap()
+}
+
+/// Spectral decay slices prepared for export, each slice is a list of
+/// (frequency in Hz, level in dB) pairs.
+#[derive(Debug, Clone)]
+pub struct Export {
+    pub shift: Duration,
+    pub slices: Vec<Vec<(f32, f32)>>,
+    pub colormap: super::chart::Colormap,
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ExportError {
+    #[error("unsupported file format, use .csv or .png")]
+    UnknownFormat,
+    #[error("could not write file: {0}")]
+    Io(io::ErrorKind),
+    #[error("could not render image: {0}")]
+    Render(String),
+}
+
+/// Exports the slices either as CSV (frequency x time matrix) or as PNG
+/// image, depending on the file extension of `path`.
+pub async fn export(path: PathBuf, export: Export) -> Result<PathBuf, ExportError> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+
+    match extension.as_deref() {
+        Some("csv") => {
+            tokio::fs::write(&path, export.to_csv(unit::Locale::current()))
+                .await
+                .map_err(|err| ExportError::Io(err.kind()))?;
+        }
+        Some("png") => {
+            let path = path.clone();
+            tokio::task::spawn_blocking(move || export.render_png(&path))
+                .await
+                .unwrap()?;
+        }
+        _ => return Err(ExportError::UnknownFormat),
+    }
+
+    Ok(path)
+}
+
+impl Export {
+    /// The decimals and columns are separated like in spreadsheets of the
+    /// `locale`.
+    fn to_csv(&self, locale: unit::Locale) -> String {
+        let delimiter = locale.csv_delimiter();
+
+        let mut csv = String::from("frequency");
+        for i in 0..self.slices.len() {
+            let time = self.shift * i as u32;
+            csv.push_str(&format!("{delimiter}{} ms", time.as_millis()));
+        }
+        csv.push('\n');
+
+        let Some(first) = self.slices.first() else {
+            return csv;
+        };
+
+        for (bin, (frequency, _)) in first.iter().enumerate() {
+            csv.push_str(&locale.number(*frequency, 2));
+
+            for slice in self.slices.iter() {
+                csv.push(delimiter);
+                if let Some((_, level)) = slice.get(bin) {
+                    csv.push_str(&locale.number(*level, 2));
+                }
+            }
+
+            csv.push('\n');
+        }
+
+        csv
+    }
+
+    fn render_png(&self, path: &Path) -> Result<(), ExportError> {
+        use plotters::prelude::*;
+
+        const DYNAMIC_RANGE: f32 = 60.0;
+
+        let render_err = |err: DrawingAreaErrorKind<_>| ExportError::Render(err.to_string());
+
+        let max = self
+            .slices
+            .iter()
+            .flatten()
+            .map(|(_, level)| *level)
+            .fold(f32::NEG_INFINITY, f32::max);
+        let max = if max.is_finite() { max.ceil() } else { 0.0 };
+        let min = max - DYNAMIC_RANGE;
+
+        let root = BitMapBackend::new(path, (1280, 720)).into_drawing_area();
+        root.fill(&WHITE).map_err(render_err)?;
+
+        let mut chart = ChartBuilder::on(&root)
+            .margin(20)
+            .x_label_area_size(40)
+            .y_label_area_size(50)
+            .build_cartesian_2d((20f32..20_000f32).log_scale(), min..max)
+            .map_err(render_err)?;
+
+        chart
+            .configure_mesh()
+            .x_desc("Frequency [Hz]")
+            .y_desc("Level [dB]")
+            .draw()
+            .map_err(render_err)?;
+
+        for (i, slice) in self.slices.iter().enumerate() {
+            let color = self.colormap.eval_rational(i, self.slices.len());
+            let color = RGBColor(color.r, color.g, color.b);
+
+            let points = slice
+                .iter()
+                .filter(|(frequency, _)| (20.0..=20_000.0).contains(frequency))
+                .map(|(frequency, level)| (*frequency, level.max(min)));
+
+            chart
+                .draw_series(LineSeries::new(points, &color))
+                .map_err(render_err)?;
+        }
+
+        root.present().map_err(render_err)?;
+
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
@@ -185,3 +325,22 @@ pub enum ValidationError {
     #[error("Not a number.")]
     NotANumber,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn csv_export_is_frequency_by_time_matrix() {
+        let export = Export {
+            shift: Duration::from_millis(20),
+            slices: vec![vec![(100.0, -1.0), (200.0, -2.0)], vec![(100.0, -3.0)]],
+            colormap: Default::default(),
+        };
+
+        assert_eq!(
+            export.to_csv(unit::Locale::POSIX),
+            "frequency,0 ms,20 ms\n100.00,-1.00,-3.00\n200.00,-2.00,\n"
+        );
+    }
+}
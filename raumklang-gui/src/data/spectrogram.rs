@@ -6,7 +6,7 @@ use rustfft::{
     num_complex::{Complex, Complex32},
 };
 
-use crate::data::{SampleRate, Samples};
+use crate::data::{SampleRate, Samples, scheduler};
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub struct Config {
@@ -15,11 +15,33 @@ pub struct Config {
     pub window_width: Duration,
 }
 
+/// Reference level, the spectrogram slices are normalized to.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Normalization {
+    /// Peak of the whole spectrogram
+    #[default]
+    Global,
+    /// Peak of every time slice, reveals the decay structure in the tail
+    PerSlice,
+}
+
 #[derive(Clone)]
 pub struct Spectrogram {
     pub span_before_peak: Samples,
     pub span_after_peak: Samples,
     slices: Vec<super::FrequencyResponse>,
+    matrix: Arc<Matrix>,
+}
+
+/// Levels of all slices in one block, as they are uploaded to the GPU.
+#[derive(Debug)]
+pub struct Matrix {
+    pub rows: usize,
+    pub columns: usize,
+    /// Levels in dB relative to the global peak, row by row.
+    pub levels: Vec<f32>,
+    /// Peak of every row in dB relative to the global peak.
+    pub peaks: Vec<f32>,
 }
 
 impl Spectrogram {
@@ -30,10 +52,102 @@ impl Spectrogram {
     pub fn iter(&self) -> slice::Iter<'_, super::FrequencyResponse> {
         self.slices.iter()
     }
+
+    pub fn matrix(&self) -> &Arc<Matrix> {
+        &self.matrix
+    }
+
+    /// Levels of all slices in dB relative to the peak given by
+    /// `normalization`.
+    pub fn levels(&self, normalization: Normalization) -> Vec<Vec<f32>> {
+        let global_peak = self.slices.iter().map(peak).max_by(f32::total_cmp);
+
+        self.slices
+            .iter()
+            .map(|slice| {
+                let reference = match normalization {
+                    Normalization::Global => global_peak.unwrap_or_default(),
+                    Normalization::PerSlice => peak(slice),
+                };
+
+                slice
+                    .data
+                    .iter()
+                    .map(|s| raumklang_core::dbfs(s / reference))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Level of a single bin in dB relative to the peak given by
+    /// `normalization`.
+    pub fn level(&self, normalization: Normalization, slice: usize, bin: usize) -> Option<f32> {
+        let data = self.slices.get(slice)?;
+        let value = data.data.get(bin)?;
+
+        let reference = match normalization {
+            Normalization::Global => self.slices.iter().map(peak).max_by(f32::total_cmp)?,
+            Normalization::PerSlice => peak(data),
+        };
+
+        Some(raumklang_core::dbfs(value / reference))
+    }
+
+    /// Position of the slice relative to the peak of the impulse response in
+    /// samples, negative before the peak.
+    pub fn position(&self, slice: usize) -> f32 {
+        let before = f32::from(self.span_before_peak);
+        let after = f32::from(self.span_after_peak);
+        let step = (before + after) / self.len().saturating_sub(1).max(1) as f32;
+
+        slice as f32 * step - before
+    }
+
+    pub fn sample_rate(&self) -> f32 {
+        self.slices
+            .first()
+            .map_or(0.0, |slice| slice.sample_rate as f32)
+    }
+}
+
+impl Matrix {
+    /// Lower bound of the levels in dB, silent bins would be infinite
+    /// otherwise.
+    const FLOOR: f32 = -200.0;
+
+    fn new(slices: &[super::FrequencyResponse]) -> Self {
+        let global_peak = slices.iter().map(peak).max_by(f32::total_cmp);
+        let global_peak = global_peak.unwrap_or_default();
+
+        let level = |s: f32| raumklang_core::dbfs(s / global_peak).max(Self::FLOOR);
+
+        let columns = slices.iter().map(|slice| slice.data.len()).min();
+        let columns = columns.unwrap_or_default();
+
+        Self {
+            rows: slices.len(),
+            columns,
+            levels: slices
+                .iter()
+                .flat_map(|slice| slice.data.iter().take(columns).copied().map(level))
+                .collect(),
+            peaks: slices.iter().map(peak).map(level).collect(),
+        }
+    }
+}
+
+fn peak(slice: &super::FrequencyResponse) -> f32 {
+    slice
+        .data
+        .iter()
+        .copied()
+        .max_by(f32::total_cmp)
+        .unwrap_or_default()
 }
 
 pub(crate) async fn compute(
-    ir: raumklang_core::ImpulseResponse,
+    subject: scheduler::Subject,
+    ir: Arc<raumklang_core::WindowedImpulseResponse>,
     preferences: Config,
 ) -> Spectrogram {
     let sample_rate = SampleRate::from(ir.sample_rate);
@@ -52,13 +166,11 @@ pub(crate) async fn compute(
     let span_before_peak = Samples::from_duration(preferences.span_before_peak, sample_rate);
     let span_after_peak = Samples::from_duration(preferences.span_after_peak, sample_rate);
 
-    let ir: Vec<_> = (0..half_window_size + usize::from(span_before_peak))
-        .map(|_| Complex32::from(0.0))
-        .chain(
-            ir.data
-                .into_iter()
-                .take(window_size + usize::from(span_after_peak)),
-        )
+    let before = half_window_size + usize::from(span_before_peak);
+    let ir: Vec<_> = ir
+        .around_start(before, before + window_size + usize::from(span_after_peak))
+        .into_iter()
+        .map(Complex32::from)
         .collect();
 
     let slices = 200;
@@ -66,7 +178,7 @@ pub(crate) async fn compute(
     let shift = usize::from(analysed_with) / (slices - 1);
 
     let mut start = 0;
-    tokio::task::spawn_blocking(move || {
+    scheduler::run("spectrogram", subject, move || {
         let mut slices = Vec::with_capacity(slices);
 
         let mut planner = FftPlanner::<f32>::new();
@@ -93,6 +205,7 @@ pub(crate) async fn compute(
             slices.push(super::FrequencyResponse {
                 sample_rate: sample_rate.into(),
                 data: Arc::new(data),
+                phase: None,
             });
 
             start += shift;
@@ -101,11 +214,11 @@ pub(crate) async fn compute(
         Spectrogram {
             span_before_peak,
             span_after_peak,
+            matrix: Arc::new(Matrix::new(&slices)),
             slices,
         }
     })
     .await
-    .unwrap()
 }
 
 impl fmt::Debug for Spectrogram {
@@ -114,6 +227,21 @@ impl fmt::Debug for Spectrogram {
     }
 }
 
+impl Normalization {
+    pub const ALL: [Normalization; 2] = [Normalization::Global, Normalization::PerSlice];
+}
+
+impl fmt::Display for Normalization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Normalization::Global => "Global normalization",
+            Normalization::PerSlice => "Per slice normalization",
+        };
+
+        write!(f, "{s}")
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -0,0 +1,156 @@
+use std::{
+    collections::BTreeSet,
+    io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Interval in which the size of a new file is checked, until it stopped
+/// changing.
+const WRITE_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Directory, that is watched for new WAV files, e.g. dropped by an external
+/// recorder. Imported files are deconvolved with the loopback like any other
+/// measurement.
+#[derive(Debug, Clone)]
+pub struct WatchFolder {
+    path: PathBuf,
+    /// Files that already existed when watching started or were imported.
+    known: BTreeSet<PathBuf>,
+    /// New files, that are still being written.
+    pending: BTreeSet<PathBuf>,
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum Error {
+    #[error("could not access watch folder: {0}")]
+    Io(io::ErrorKind),
+}
+
+impl WatchFolder {
+    /// Starts watching `path`, `files` present at that time are ignored.
+    pub fn new(path: PathBuf, files: Vec<PathBuf>) -> Self {
+        Self {
+            path,
+            known: files.into_iter().collect(),
+            pending: BTreeSet::new(),
+        }
+    }
+
+    /// Scans `path` once and starts watching it.
+    pub async fn open(path: PathBuf) -> Result<Self, Error> {
+        let files = scan(path.clone()).await?;
+
+        Ok(Self::new(path, files))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Takes a file, the file watcher reported, and returns `true` for a new
+    /// WAV file. The file is pending until it is [imported] or [abandoned].
+    ///
+    /// [imported]: Self::import
+    /// [abandoned]: Self::abandon
+    pub fn notice(&mut self, path: &Path) -> bool {
+        if !is_wav(path) || self.known.contains(path) || self.pending.contains(path) {
+            return false;
+        }
+
+        self.pending.insert(path.to_path_buf())
+    }
+
+    /// Marks a pending file as imported, it is ignored from now on.
+    pub fn import(&mut self, path: &Path) {
+        self.pending.remove(path);
+        self.known.insert(path.to_path_buf());
+    }
+
+    /// Forgets a pending file, that could not be read, it is noticed again
+    /// with the next change.
+    pub fn abandon(&mut self, path: &Path) {
+        self.pending.remove(path);
+    }
+}
+
+/// Lists all WAV files in `path`.
+async fn scan(path: PathBuf) -> Result<Vec<PathBuf>, Error> {
+    let mut entries = tokio::fs::read_dir(&path)
+        .await
+        .map_err(|err| Error::Io(err.kind()))?;
+
+    let mut files = vec![];
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .map_err(|err| Error::Io(err.kind()))?
+    {
+        let path = entry.path();
+
+        if !is_wav(&path) {
+            continue;
+        }
+
+        let Ok(metadata) = entry.metadata().await else {
+            continue;
+        };
+
+        if metadata.is_file() {
+            files.push(path);
+        }
+    }
+
+    Ok(files)
+}
+
+/// Waits until the size of the file at `path` stopped changing, i.e. the
+/// file has been written completely.
+pub async fn written(path: PathBuf) -> Result<(), Error> {
+    let mut previous = None;
+
+    loop {
+        let size = tokio::fs::metadata(&path)
+            .await
+            .map_err(|err| Error::Io(err.kind()))?
+            .len();
+
+        if size > 0 && previous == Some(size) {
+            return Ok(());
+        }
+
+        previous = Some(size);
+        tokio::time::sleep(WRITE_CHECK_INTERVAL).await;
+    }
+}
+
+fn is_wav(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| matches!(ext.to_ascii_lowercase().as_str(), "wav" | "wave"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn new_files_are_noticed_until_imported() {
+        let existing = PathBuf::from("existing.wav");
+        let new = PathBuf::from("new.wav");
+
+        let mut folder = WatchFolder::new(PathBuf::from("."), vec![existing.clone()]);
+
+        assert!(!folder.notice(&existing));
+        assert!(!folder.notice(Path::new("notes.txt")));
+
+        assert!(folder.notice(&new));
+        assert!(!folder.notice(&new));
+
+        folder.abandon(&new);
+        assert!(folder.notice(&new));
+
+        folder.import(&new);
+        assert!(!folder.notice(&new));
+    }
+}
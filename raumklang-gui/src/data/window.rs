@@ -1,8 +1,10 @@
 pub mod handle;
+pub mod preset;
 
 pub use handle::Handle;
+pub use preset::{Preset, Presets};
 
-use super::{SampleRate, Samples};
+use super::{SampleRate, Samples, project};
 
 use std::time::Duration;
 
@@ -41,6 +43,26 @@ impl Window<Duration> {
         }
     }
 
+    /// Short gating for headphone measurements, removes reflections of the
+    /// coupler and the measurement rig instead of windowing a room response.
+    pub fn headphone(sample_rate: SampleRate) -> Self {
+        Self {
+            sample_rate,
+            left_type: raumklang_core::Window::Tukey(0.25),
+            left_width: Duration::from_millis(1),
+            position: Duration::from_millis(0),
+            right_type: raumklang_core::Window::Hann,
+            right_width: Duration::from_millis(10),
+        }
+    }
+
+    pub fn for_mode(mode: project::Mode, sample_rate: SampleRate) -> Self {
+        match mode {
+            project::Mode::Room => Self::new(sample_rate),
+            project::Mode::Headphone => Self::headphone(sample_rate),
+        }
+    }
+
     pub fn update(&mut self, handles: Handles) {
         let left_width = handles.center.x() - handles.left.x();
         self.left_width = Duration::from_millis(left_width as u64);
@@ -99,6 +121,20 @@ impl Window<Samples> {
     pub fn offset(&self) -> Samples {
         self.left_width - self.position
     }
+
+    /// Distance of the right window edge to the window position.
+    pub fn gate(&self) -> Duration {
+        Duration::from(self.right_width)
+    }
+
+    /// Copy of the window with the right edge moved to `gate` after the window
+    /// position, shorter gates exclude more of the room reflections.
+    pub fn gated(&self, gate: Duration) -> Self {
+        Self {
+            right_width: Samples::from_duration(gate, self.sample_rate),
+            ..self.clone()
+        }
+    }
 }
 
 impl From<Window<Samples>> for Window<Duration> {
@@ -0,0 +1,153 @@
+use super::Window;
+use crate::data::{SampleRate, directory};
+
+use serde::{Deserialize, Serialize};
+
+use std::{
+    fmt, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Named window configuration, widths and position are stored in ms so that
+/// presets are independent of the sample rate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    left_shape: raumklang_core::Window,
+    left_width: f32,
+    position: f32,
+    right_shape: raumklang_core::Window,
+    right_width: f32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Presets(Vec<Preset>);
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum Error {
+    #[error("could not access file: {0}")]
+    Io(io::ErrorKind),
+    #[error("invalid preset: {0}")]
+    Json(String),
+}
+
+impl Preset {
+    pub fn new(name: String, window: &Window<Duration>) -> Self {
+        Self {
+            name,
+            left_shape: window.left_type,
+            left_width: window.left_width.as_secs_f32() * 1000.0,
+            position: window.position.as_secs_f32() * 1000.0,
+            right_shape: window.right_type,
+            right_width: window.right_width.as_secs_f32() * 1000.0,
+        }
+    }
+
+    pub fn window(&self, sample_rate: SampleRate) -> Window<Duration> {
+        let duration = |ms: f32| Duration::from_secs_f32(ms.max(0.0) / 1000.0);
+
+        Window {
+            sample_rate,
+            left_type: self.left_shape,
+            left_width: duration(self.left_width),
+            position: duration(self.position),
+            right_type: self.right_shape,
+            right_width: duration(self.right_width),
+        }
+    }
+
+    pub async fn import(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let content = tokio::fs::read(path.as_ref())
+            .await
+            .map_err(|err| Error::Io(err.kind()))?;
+
+        serde_json::from_slice(&content).map_err(|err| Error::Json(err.to_string()))
+    }
+
+    pub async fn export(self, path: PathBuf) -> Result<PathBuf, Error> {
+        let json =
+            serde_json::to_string_pretty(&self).map_err(|err| Error::Json(err.to_string()))?;
+
+        tokio::fs::write(&path, json)
+            .await
+            .map_err(|err| Error::Io(err.kind()))?;
+
+        Ok(path)
+    }
+}
+
+impl Presets {
+    async fn path() -> Result<PathBuf, Error> {
+        let path = directory::data();
+
+        tokio::fs::create_dir_all(&path)
+            .await
+            .map_err(|err| Error::Io(err.kind()))?;
+
+        Ok(path.join("window_presets.json"))
+    }
+
+    pub async fn load() -> Result<Self, Error> {
+        let path = Self::path().await?;
+
+        let content = match tokio::fs::read(path).await {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(Error::Io(err.kind())),
+        };
+
+        serde_json::from_slice(&content).map_err(|err| Error::Json(err.to_string()))
+    }
+
+    pub async fn save(self) -> Result<(), Error> {
+        let path = Self::path().await?;
+
+        let json =
+            serde_json::to_string_pretty(&self).map_err(|err| Error::Json(err.to_string()))?;
+
+        tokio::fs::write(path, json)
+            .await
+            .map_err(|err| Error::Io(err.kind()))
+    }
+
+    /// Adds the preset, an existing preset with the same name is replaced.
+    pub fn insert(&mut self, preset: Preset) {
+        self.0.retain(|p| p.name != preset.name);
+        self.0.push(preset);
+        self.0.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    pub fn as_slice(&self) -> &[Preset] {
+        &self.0
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn insert_replaces_preset_with_same_name() {
+        let window = Window::new(SampleRate::new(48_000));
+
+        let mut presets = Presets::default();
+        presets.insert(Preset::new("Room".to_string(), &window));
+        presets.insert(Preset::new(
+            "Room".to_string(),
+            &Window::headphone(SampleRate::new(48_000)),
+        ));
+
+        assert_eq!(presets.as_slice().len(), 1);
+        assert_ne!(
+            presets.as_slice()[0].window(SampleRate::new(48_000)),
+            window
+        );
+    }
+}
@@ -0,0 +1,81 @@
+use crate::log;
+
+use iced::futures::{Stream, StreamExt};
+use notify::{RecursiveMode, Watcher};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
+
+/// Reports the `files`, that were modified, as the file system notifies
+/// about them. The parent directories are watched, as many programs replace
+/// a file instead of writing into it.
+///
+/// The watcher lives as long as the stream.
+pub fn watch(files: &BTreeSet<PathBuf>) -> impl Stream<Item = PathBuf> + use<> {
+    let files = files.clone();
+    let directories: BTreeSet<PathBuf> = files
+        .iter()
+        .filter_map(|file| file.parent())
+        .map(PathBuf::from)
+        .collect();
+
+    events(directories, move |path| files.contains(path))
+}
+
+/// Reports all files in `directory`, that were created or modified.
+pub fn watch_directory(directory: &Path) -> impl Stream<Item = PathBuf> + use<> {
+    events(BTreeSet::from([directory.to_path_buf()]), |_| true)
+}
+
+fn events<F>(directories: BTreeSet<PathBuf>, filter: F) -> impl Stream<Item = PathBuf> + use<F>
+where
+    F: Fn(&Path) -> bool + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel(64);
+
+    let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let event = match event {
+            Ok(event) => event,
+            Err(err) => {
+                log::warn!("File watcher failed: {err}");
+                return;
+            }
+        };
+
+        if !(event.kind.is_modify() || event.kind.is_create()) {
+            return;
+        }
+
+        for path in event.paths.into_iter().filter(|path| filter(path)) {
+            // NOTE: a full channel drops the path, the file is reported
+            // again with the next write anyway
+            let _ = sender.try_send(path);
+        }
+    });
+
+    let watcher = match watcher {
+        Ok(mut watcher) => {
+            for directory in &directories {
+                if let Err(err) = watcher.watch(directory, RecursiveMode::NonRecursive) {
+                    log::warn!("Could not watch {directory:?}: {err}");
+                }
+            }
+
+            Some(watcher)
+        }
+        Err(err) => {
+            log::warn!("Changes on disk are not detected: {err}");
+            None
+        }
+    };
+
+    ReceiverStream::new(receiver).map(move |path| {
+        // keeps the watcher alive
+        let _ = &watcher;
+        path
+    })
+}
@@ -1,41 +1,62 @@
-use std::{fs::File, sync::Arc};
-use tracing::Level;
+use crate::data::{directory, logging};
+
+use std::{
+    collections::VecDeque,
+    fmt::{self as std_fmt, Write as _},
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, AtomicU8, Ordering},
+    },
+};
+use tracing::{Level, Metadata, field};
 use tracing_subscriber::{
-    filter::{LevelFilter, Targets},
+    Layer,
+    filter::{self, LevelFilter, Targets},
     fmt,
+    layer::Context,
     prelude::*,
 };
 
 #[allow(unused_imports)]
 pub use tracing::{debug, error, info, trace, warn};
 
+/// Size of a log file, before it is rotated.
+const LOG_FILE_SIZE: u64 = 5 * 1024 * 1024;
+/// Number of log files kept, including the current one.
+const LOG_FILES: usize = 3;
+
+static LEVEL: AtomicU8 = AtomicU8::new(verbosity(logging::Level::Debug));
+static WRITE_FILE: AtomicBool = AtomicBool::new(false);
+
 // From the excellent example in:
 // https://docs.rs/tracing-subscriber/latest/tracing_subscriber/filter/targets/struct.Targets.html
-pub fn init() -> Result<(), std::io::Error> {
+pub fn init(settings: logging::Settings) {
+    apply(settings);
+
     // A layer that logs events to stdout using the human-readable "pretty"
     // format.
     let stdout_log = fmt::layer().compact();
 
-    // A layer that logs events to a file, using the JSON format.
-    let file = File::create("debug_log.json")?;
-    let debug_log = fmt::layer().with_writer(Arc::new(file)).json();
+    // A layer that logs events to rotating files in the data directory.
+    let file_log = fmt::layer()
+        .with_ansi(false)
+        .with_writer(Mutex::new(RotatingFile::new(directory::logs())));
 
     tracing_subscriber::registry()
-        // Only log INFO and above to stdout, unless the span or event
-        // has the `my_crate::cool_module` target prefix.
-        .with(
-            stdout_log.with_filter(
-                Targets::default()
-                    // .with_target("raumklang", Level::DEBUG)
-                    .with_default(Level::DEBUG),
-            ),
-        )
-        // Log everything enabled by the global filter to `debug_log.json`.
-        .with(debug_log)
+        // Log to stdout and file with the level of the settings, which can
+        // be changed at runtime.
+        .with(stdout_log.with_filter(filter::filter_fn(is_enabled)))
+        .with(file_log.with_filter(filter::filter_fn(|metadata| {
+            WRITE_FILE.load(Ordering::Relaxed) && is_enabled(metadata)
+        })))
+        // Keep the actions of this session for the session log view.
+        .with(SessionLayer.with_filter(Targets::default().with_target("raumklang", Level::INFO)))
         // Configure a global filter for the whole subscriber stack. This will
-        // control what spans and events are recorded by both the `debug_log`
-        // and the `stdout_log` layers, and `stdout_log` will *additionally* be
-        // filtered by its per-layer filter.
+        // control what spans and events are recorded by all layers, which
+        // will *additionally* be filtered by their per-layer filters.
         .with(
             Targets::default()
                 .with_target("raumklang", Level::TRACE)
@@ -44,6 +65,229 @@ pub fn init() -> Result<(), std::io::Error> {
                 .with_target("wgpu_core", LevelFilter::OFF),
         )
         .init();
+}
+
+/// Applies the settings to the running logger.
+pub fn apply(settings: logging::Settings) {
+    LEVEL.store(verbosity(settings.level), Ordering::Relaxed);
+    WRITE_FILE.store(settings.write_file, Ordering::Relaxed);
+}
+
+pub fn settings() -> logging::Settings {
+    let level = LEVEL.load(Ordering::Relaxed);
+
+    logging::Settings {
+        level: logging::Level::ALL
+            .iter()
+            .copied()
+            .find(|l| verbosity(*l) == level)
+            .unwrap_or_default(),
+        write_file: WRITE_FILE.load(Ordering::Relaxed),
+    }
+}
+
+const fn verbosity(level: logging::Level) -> u8 {
+    match level {
+        logging::Level::Error => 1,
+        logging::Level::Warn => 2,
+        logging::Level::Info => 3,
+        logging::Level::Debug => 4,
+        logging::Level::Trace => 5,
+    }
+}
+
+fn is_enabled(metadata: &Metadata<'_>) -> bool {
+    let verbosity = match *metadata.level() {
+        Level::ERROR => 1,
+        Level::WARN => 2,
+        Level::INFO => 3,
+        Level::DEBUG => 4,
+        Level::TRACE => 5,
+    };
+
+    verbosity <= LEVEL.load(Ordering::Relaxed)
+}
+
+/// Log file, that is moved aside once it exceeds [`LOG_FILE_SIZE`]. The file
+/// is opened on the first write, so that nothing is created if file logging
+/// is disabled.
+struct RotatingFile {
+    dir: PathBuf,
+    file: Option<File>,
+    written: u64,
+}
+
+impl RotatingFile {
+    fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            file: None,
+            written: 0,
+        }
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file = None;
+
+        for index in (1..LOG_FILES).rev() {
+            let from = log_file(&self.dir, index - 1);
+            if from.exists() {
+                fs::rename(from, log_file(&self.dir, index))?;
+            }
+        }
+
+        self.open()
+    }
+
+    fn open(&mut self) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+
+        let file = File::options()
+            .create(true)
+            .append(true)
+            .open(log_file(&self.dir, 0))?;
+
+        self.written = file.metadata()?.len();
+        self.file = Some(file);
+
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.file.is_none() {
+            self.open()?;
+        }
+
+        if self.written > 0 && self.written + buf.len() as u64 > LOG_FILE_SIZE {
+            self.rotate()?;
+        }
+
+        let Some(file) = self.file.as_mut() else {
+            return Err(io::ErrorKind::NotFound.into());
+        };
+
+        let written = file.write(buf)?;
+        self.written += written as u64;
+
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.file.as_mut() {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Path of the log file with the given index, `0` is the current one.
+fn log_file(dir: &Path, index: usize) -> PathBuf {
+    match index {
+        0 => dir.join("raumklang.log"),
+        index => dir.join(format!("raumklang.{index}.log")),
+    }
+}
+
+/// Maximum number of entries kept in the session log.
+const SESSION_CAPACITY: usize = 10_000;
+
+static SESSION: Mutex<VecDeque<Entry>> = Mutex::new(VecDeque::new());
+
+/// Logged action of the current session.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub time: chrono::DateTime<chrono::Local>,
+    pub level: Level,
+    pub message: String,
+}
+
+impl std_fmt::Display for Entry {
+    fn fmt(&self, f: &mut std_fmt::Formatter<'_>) -> std_fmt::Result {
+        write!(
+            f,
+            "{} {:>5} {}",
+            self.time.format("%Y-%m-%d %H:%M:%S%.3f"),
+            self.level,
+            self.message
+        )
+    }
+}
+
+/// Returns all entries logged in this session, oldest first.
+pub fn session() -> Vec<Entry> {
+    SESSION
+        .lock()
+        .map(|entries| entries.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+struct SessionLayer;
+
+impl<S> Layer<S> for SessionLayer
+where
+    S: tracing::Subscriber,
+{
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+
+        let entry = Entry {
+            time: chrono::Local::now(),
+            level: *event.metadata().level(),
+            message: visitor.0,
+        };
+
+        if let Ok(mut entries) = SESSION.lock() {
+            if entries.len() == SESSION_CAPACITY {
+                entries.pop_front();
+            }
+
+            entries.push_back(entry);
+        }
+    }
+}
+
+#[derive(Default)]
+struct MessageVisitor(String);
+
+impl field::Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &field::Field, value: &dyn std_fmt::Debug) {
+        if !self.0.is_empty() {
+            self.0.push(' ');
+        }
+
+        if field.name() == "message" {
+            let _ = write!(self.0, "{value:?}");
+        } else {
+            let _ = write!(self.0, "{}={value:?}", field.name());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn log_files_are_rotated() {
+        let dir = std::env::temp_dir().join("raumklang-log-test");
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut file = RotatingFile::new(dir.clone());
+        let line = vec![b'x'; LOG_FILE_SIZE as usize / 2 + 1];
+        for _ in 0..=LOG_FILES {
+            file.write_all(&line).unwrap();
+        }
+
+        assert!(log_file(&dir, LOG_FILES - 1).exists());
+        assert!(!log_file(&dir, LOG_FILES).exists());
+        assert_eq!(
+            fs::metadata(log_file(&dir, 0)).unwrap().len(),
+            line.len() as u64
+        );
 
-    Ok(())
+        fs::remove_dir_all(dir).unwrap();
+    }
 }
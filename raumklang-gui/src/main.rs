@@ -1,10 +1,13 @@
 mod audio;
 mod data;
+mod file_watcher;
 #[rustfmt::skip]
 mod icon;
 mod log;
+mod remote;
 mod screen;
 mod ui;
+mod unit;
 mod widget;
 
 use screen::{
@@ -24,9 +27,8 @@ use std::{
 const MAX_RECENT_PROJECTS_ENTRIES: usize = 10;
 
 fn main() -> iced::Result {
-    if let Err(err) = log::init() {
-        eprintln!("Raumklang: failed to initialize logger: {err}")
-    }
+    let log_settings = data::logging::Settings::load().unwrap_or_default();
+    log::init(log_settings);
 
     iced::application(Raumklang::new, Raumklang::update, Raumklang::view)
         .title(Raumklang::title)
@@ -41,6 +43,7 @@ fn main() -> iced::Result {
 #[derive(Debug, Clone)]
 enum Message {
     RecentProjectsLoaded(Result<data::RecentProjects, data::Error>),
+    OnboardingLoaded(Result<data::onboarding::Onboarding, data::Error>),
     ProjectLoaded(Result<(Arc<data::Project>, PathBuf), PickAndLoadError>),
 
     Landing(landing::Message),
@@ -58,7 +61,13 @@ impl Raumklang {
             screen: Screen::Loading,
             recent_projects: RecentProjects::new(MAX_RECENT_PROJECTS_ENTRIES),
         };
-        let task = Task::perform(RecentProjects::load(), Message::RecentProjectsLoaded);
+        let task = Task::batch([
+            Task::perform(RecentProjects::load(), Message::RecentProjectsLoaded),
+            Task::perform(
+                data::onboarding::Onboarding::load(),
+                Message::OnboardingLoaded,
+            ),
+        ]);
 
         (app, task)
     }
@@ -79,14 +88,31 @@ impl Raumklang {
                     self.recent_projects.insert(path);
                 }
 
-                self.screen = Screen::Landing;
+                if let Screen::Loading = self.screen {
+                    self.screen = Screen::Landing;
+                }
 
                 Task::none()
             }
             Message::RecentProjectsLoaded(Err(err)) => {
                 log::debug!("Loading recent project failed: {err}");
 
-                self.screen = Screen::Landing;
+                if let Screen::Loading = self.screen {
+                    self.screen = Screen::Landing;
+                }
+
+                Task::none()
+            }
+            Message::OnboardingLoaded(Ok(onboarding)) => {
+                if onboarding.completed || !matches!(self.screen, Screen::Loading | Screen::Landing)
+                {
+                    return Task::none();
+                }
+
+                self.start_wizard()
+            }
+            Message::OnboardingLoaded(Err(err)) => {
+                log::debug!("Loading onboarding state failed: {err}");
 
                 Task::none()
             }
@@ -99,6 +125,13 @@ impl Raumklang {
                 landing::Message::Load => Task::future(pick_project_file())
                     .and_then(|path| Task::future(load_project(path)))
                     .map(Message::ProjectLoaded),
+                landing::Message::Wizard => self.start_wizard(),
+                landing::Message::Demo => {
+                    let (screen, task) = screen::Main::demo();
+                    self.screen = Screen::Main(screen);
+
+                    task.map(Message::Main)
+                }
                 landing::Message::Recent(id) => match self.recent_projects.get(id) {
                     Some(path) => Task::perform(load_project(path.clone()), Message::ProjectLoaded),
                     None => Task::none(),
@@ -135,6 +168,13 @@ impl Raumklang {
         }
     }
 
+    fn start_wizard(&mut self) -> Task<Message> {
+        let (screen, task) = screen::Main::with_wizard();
+        self.screen = Screen::Main(screen);
+
+        task.map(Message::Main)
+    }
+
     fn view(&self) -> Element<'_, Message> {
         match &self.screen {
             Screen::Loading => screen::loading(),
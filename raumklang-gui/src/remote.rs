@@ -0,0 +1,149 @@
+use crate::{data::remote_control::RemoteControl, log};
+
+use iced::futures::{Stream, StreamExt, future, stream};
+use tokio::net::UdpSocket;
+
+/// Remote control action, sent by an OSC client or a MIDI footswitch, so that
+/// measurements can be triggered while holding the microphone in position.
+///
+/// OSC addresses are `/raumklang/next` (or `/raumklang/start`),
+/// `/raumklang/back` and `/raumklang/abort`. On MIDI, the control changes 64
+/// (sustain), 67 (soft) and 66 (sostenuto) are used respectively.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trigger {
+    /// Starts a recording or advances to its next step.
+    Next,
+    /// Stops the running step or declines the recorded measurement.
+    Back,
+    /// Aborts the recording.
+    Abort,
+}
+
+impl Trigger {
+    pub fn from_osc(packet: &[u8]) -> Option<Self> {
+        let (address, rest) = osc_string(packet)?;
+
+        let trigger = match address {
+            "/raumklang/next" | "/raumklang/start" => Trigger::Next,
+            "/raumklang/back" => Trigger::Back,
+            "/raumklang/abort" => Trigger::Abort,
+            _ => return None,
+        };
+
+        // buttons of control surfaces send 1 when pressed and 0 when released
+        let is_pressed = match osc_string(rest) {
+            Some((tags, args)) => match (tags.as_bytes().get(1), args.get(..4)) {
+                (Some(b'i'), Some(arg)) => i32::from_be_bytes(arg.try_into().ok()?) != 0,
+                (Some(b'f'), Some(arg)) => f32::from_be_bytes(arg.try_into().ok()?) != 0.0,
+                _ => true,
+            },
+            None => true,
+        };
+
+        is_pressed.then_some(trigger)
+    }
+
+    pub fn from_midi(bytes: &[u8]) -> Option<Self> {
+        let [status, controller, value] = bytes else {
+            return None;
+        };
+
+        let is_control_change = status & 0xf0 == 0xb0;
+        if !is_control_change || *value < 64 {
+            return None;
+        }
+
+        match controller {
+            64 => Some(Trigger::Next),
+            67 => Some(Trigger::Back),
+            66 => Some(Trigger::Abort),
+            _ => None,
+        }
+    }
+}
+
+/// Listens for OSC messages, if the remote control is enabled. The saved
+/// settings are loaded, unless `settings` have been changed in this session.
+pub fn osc(settings: &Option<RemoteControl>) -> impl Stream<Item = Trigger> + use<> {
+    let settings = *settings;
+
+    stream::once(async move {
+        let settings = match settings {
+            Some(settings) => settings,
+            None => match RemoteControl::load().await {
+                Ok(settings) => settings,
+                Err(err) => {
+                    log::warn!("remote control disabled, could not load its settings: {err}");
+                    return None;
+                }
+            },
+        };
+
+        if !settings.enabled {
+            return None;
+        }
+
+        let address = settings.address();
+        match UdpSocket::bind(address).await {
+            Ok(socket) => {
+                log::info!("Listening for OSC messages on {address}");
+                Some(socket)
+            }
+            Err(err) => {
+                log::warn!("remote control disabled, could not bind OSC port: {err}");
+                None
+            }
+        }
+    })
+    .filter_map(future::ready)
+    .flat_map(|socket| {
+        stream::unfold(socket, |socket| async move {
+            let mut buf = [0; 1024];
+            loop {
+                match socket.recv(&mut buf).await {
+                    Ok(len) => {
+                        if let Some(trigger) = Trigger::from_osc(&buf[..len]) {
+                            return Some((trigger, socket));
+                        }
+                    }
+                    Err(err) => {
+                        log::error!("receiving OSC message failed: {err}");
+                        return None;
+                    }
+                }
+            }
+        })
+    })
+}
+
+/// Splits a null terminated, 4 byte aligned OSC string from the front of `bytes`.
+fn osc_string(bytes: &[u8]) -> Option<(&str, &[u8])> {
+    let len = bytes.iter().position(|b| *b == 0)?;
+    let s = std::str::from_utf8(&bytes[..len]).ok()?;
+
+    let padded = (len + 4) & !3;
+
+    Some((s, bytes.get(padded..).unwrap_or_default()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn osc_button_release_is_ignored() {
+        let message = |value: f32| {
+            let mut packet = b"/raumklang/next\0,f\0\0".to_vec();
+            packet.extend_from_slice(&value.to_be_bytes());
+            packet
+        };
+
+        assert_eq!(Trigger::from_osc(&message(1.0)), Some(Trigger::Next));
+        assert_eq!(Trigger::from_osc(&message(0.0)), None);
+        assert_eq!(
+            Trigger::from_osc(b"/raumklang/abort\0\0\0\0,\0\0\0"),
+            Some(Trigger::Abort)
+        );
+        assert_eq!(Trigger::from_osc(b"/other\0\0,\0\0\0"), None);
+    }
+}
@@ -11,6 +11,8 @@ use iced::{
 pub enum Message {
     New,
     Load,
+    Wizard,
+    Demo,
     Recent(usize),
 }
 
@@ -53,6 +55,14 @@ pub fn landing<'a>(recent_projects: &'a RecentProjects) -> Element<'a, Message>
                         button("Load ...")
                             .on_press(Message::Load)
                             .width(Length::Fill)
+                            .style(button::subtle),
+                        button("Setup wizard ...")
+                            .on_press(Message::Wizard)
+                            .width(Length::Fill)
+                            .style(button::subtle),
+                        button("Demo project")
+                            .on_press(Message::Demo)
+                            .width(Length::Fill)
                             .style(button::subtle)
                     ]
                     .spacing(2)
@@ -1,38 +1,46 @@
 mod chart;
+mod crossover;
 mod frequency_response;
 mod impulse_response;
 mod modal;
 mod recording;
+mod split;
 mod tab;
 
 use iced::Pixels;
 use iced::mouse::ScrollDelta;
 use iced_aksel::axis::{MarkerPosition, Position, TickContext, TickLine, TickResult};
-use iced_aksel::scale;
+use iced_aksel::{PlotPoint, scale};
 use modal::Modal;
 use tab::Tab;
 use tokio::fs;
 
 use crate::data::{
-    self, Project, RecentProjects, SampleRate, Samples, Window, project, spectral_decay,
+    self, Project, RecentProjects, SampleRate, Samples, Window, project, scheduler, spectral_decay,
     spectrogram, window,
 };
 use crate::ui::frequency_response::SpectrumLayer;
 use crate::{
-    PickAndLoadError, icon, load_project, log,
+    PickAndLoadError, audio, file_watcher, icon, load_project, log, remote,
     screen::main::{
         chart::waveform,
         modal::{
-            SpectralDecayConfig, pending_window, save_project, spectral_decay_config,
-            spectrogram_config,
+            SpectralDecayConfig, auralization, channel_check, duplicate_measurement, export_hook,
+            moving_mic, operation, pending_window, recompute, remote_control, rta,
+            sample_rate_mismatch, save_project, session_log, spectral_decay_config,
+            spectrogram_config, spl_meter, sub_alignment, transfer_function, wizard,
         },
     },
-    ui::{self, Analysis, Loopback, Measurement, measurement},
-    widget::{processing_overlay, sidebar},
+    ui::{self, Analysis, Loopback, Measurement, help, measurement},
+    unit,
+    widget::{number_input, processing_overlay, sidebar},
 };
 
+use crossover::Crossover;
 use impulse_response::ChartOperation;
+use raumklang_core::preprocess::Preprocessing;
 use recording::Recording;
+use split::Split;
 
 use chrono::{DateTime, Utc};
 
@@ -42,17 +50,18 @@ use iced::{
     alignment::{Horizontal, Vertical},
     keyboard, padding,
     widget::{
-        Button, button, canvas, center, column, container, opaque, pick_list, row, rule,
-        scrollable, stack, text,
+        Button, button, canvas, center, checkbox, column, container, mouse_area, opaque, pick_list,
+        progress_bar, row, rule, scrollable, slider, space, stack, text, text_input, tooltip,
     },
 };
 use rfd::FileHandle;
 
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     mem,
     path::{Path, PathBuf},
     sync::Arc,
+    time::{Duration, SystemTime},
 };
 use std::{fmt, io};
 
@@ -63,31 +72,88 @@ pub struct Main {
     selected: Option<measurement::Selected>,
     loopback: Option<Loopback>,
     measurements: measurement::List,
+    /// Filters the measurements in the sidebars of all tabs.
+    sidebar_filter: String,
+    /// Progress of the measurement files, that are being loaded.
+    loading: BTreeMap<PathBuf, f32>,
 
     project_path: Option<PathBuf>,
     measurement_operation: project::Operation,
     export_from_memory: bool,
+    wav_format: project::WavFormat,
+    mode: project::Mode,
 
     zoom: chart::Zoom,
     offset: chart::Offset,
     signal_cache: canvas::Cache,
 
     smoothing: frequency_response::Smoothing,
+    fr_display: frequency_response::Display,
+    export_grid: data::frequency_response::Grid,
+    /// Overrides the right edge of the window on the frequency response tab.
+    gate: Option<Duration>,
     window: Option<Window<Samples>>,
+    window_presets: window::Presets,
+    window_preset_name: String,
 
     ir_chart: impulse_response::Chart,
+    time_shift_input: String,
+    deconvolution: data::impulse_response::Method,
+    preprocessing: Preprocessing,
     spectrogram: Spectrogram,
+    chart_preferences: data::chart::Preferences,
+    /// Shows the levels at harmonically related frequencies of the cursor.
+    show_harmonics: bool,
+    /// Compensates different playback levels of the measurements.
+    normalize_playback_level: bool,
 
     spectral_decay_config: spectral_decay::Config,
     spectrogram_config: spectrogram::Config,
     fr_state: iced_aksel::State<AxisId, f32>,
+    /// Chart of the frequency responses, while their phase is shown.
+    fr_phase_state: iced_aksel::State<AxisId, f32>,
+    /// Visible range of the frequency response chart, after it was zoomed
+    /// or panned.
+    fr_view: Option<project::FrequencyResponseView>,
+    /// Measurement, whose frequency response is the only one shown.
+    fr_solo: Option<measurement::Id>,
+    /// Measurement, whose frequency response is highlighted in the chart.
+    fr_hovered: Option<measurement::Id>,
+    /// Measurement of an opened project, that is selected once it is loaded.
+    pending_selection: Option<PathBuf>,
+    split: Option<Split>,
     measurement_config: data::measurement::Config,
+    watch_folder: Option<data::WatchFolder>,
+    /// Settings of the OSC listener, if they were changed in this session.
+    remote_control: Option<data::remote_control::RemoteControl>,
+
+    compensation: Option<ui::Curve>,
+    channel_difference: Option<ui::Curve>,
+    /// Averaged response of the last moving microphone measurement.
+    moving_mic: Option<ui::Curve>,
+    spread: frequency_response::Spread,
+    spread_band: Option<ui::curve::Band>,
+    /// Target curve, that the frequency responses are compared to.
+    target: Option<(data::curve::Curve, ui::Curve)>,
+    /// Deviation of the first shown frequency response from the target, by
+    /// the name of its measurement.
+    target_error: Option<(String, ui::curve::TargetError)>,
+    correction_limits: ui::curve::CorrectionLimits,
+    /// Where a correction within the limits can move the compared response.
+    correction_envelope: Option<ui::curve::Band>,
+    stereo_sum: Option<frequency_response::StereoSum>,
+    crossover: Option<Crossover>,
+    /// Calibration of the measurement microphone, applied to all frequency
+    /// responses.
+    calibration: Option<(PathBuf, data::curve::Curve)>,
+    wizard: Option<wizard::Wizard>,
 }
 
 type AxisId = &'static str;
 
 const FREQ_AXIS_ID: AxisId = "freq";
 const DB_AXIS_ID: AxisId = "db";
+const PHASE_AXIS_ID: AxisId = "phase";
 
 #[allow(clippy::large_enum_variant)]
 #[derive(Default)]
@@ -122,6 +188,8 @@ impl State {
 struct Spectrogram {
     pub zoom: chart::Zoom,
     pub offset: chart::Offset,
+    pub normalization: spectrogram::Normalization,
+    pub level_offset: f32,
     pub cache: canvas::Cache,
 }
 
@@ -138,22 +206,68 @@ pub enum Message {
     LoadLoopback,
     LoopbackLoaded(Loopback),
     LoadMeasurement,
+    ImportRew,
+    MeasurementFilePicked(PathBuf),
+    MeasurementLoading(PathBuf, f32),
     MeasurementLoaded(Measurement),
+    DuplicateMeasurement(duplicate_measurement::Message),
+    MeasurementReloaded(measurement::Id, Measurement),
+    FileChanged(PathBuf),
+    FilesChanged(Vec<measurement::Id>),
+    WatchFolder,
+    WatchFolderOpened(Result<data::WatchFolder, data::watch_folder::Error>),
+    StopWatchFolder,
+    WatchFolderChanged(PathBuf),
+    WatchFolderWritten(PathBuf, Result<(), data::watch_folder::Error>),
     Measurement(measurement::Message),
+    SidebarFilterChanged(String),
+    SampleRateMismatch(sample_rate_mismatch::Message),
+    MeasurementResampled(measurement::Id, Arc<raumklang_core::Measurement>),
+    DcOffsetRemoved(measurement::Id, Arc<raumklang_core::Measurement>),
 
     OpenTab(tab::Id),
+    ToggleSplit,
+    Split(split::Message),
     ImpulseResponseComputed(measurement::Id, data::ImpulseResponse),
+    DenoiseToggled(measurement::Id, bool),
+    Denoised(
+        measurement::Id,
+        Option<Arc<raumklang_core::ImpulseResponse>>,
+    ),
     SaveImpulseResponseToFile(measurement::Id, Option<Arc<Path>>),
 
     ImpulseResponseSaved(measurement::Id, Arc<Path>),
     ImpulseResponseChart(impulse_response::ChartOperation),
+    TimeShiftChanged(measurement::Id, isize),
+    TimeShiftInput(measurement::Id, String),
+    DeconvolutionMethodChanged(data::impulse_response::Method),
+    PreprocessingChanged(Preprocessing),
     ImpulseResponse(ui::measurement::Id, ui::impulse_response::Message),
 
     FrequencyResponseComputed(measurement::Id, data::FrequencyResponse),
     FrequencyResponseToggled(measurement::Id, bool),
+    FrequencyResponseSoloed(measurement::Id),
+    FrequencyResponseHovered(Option<measurement::Id>),
     ChangeSmoothing(frequency_response::Smoothing),
+    FrequencyResponseDisplayChanged(frequency_response::Display),
     FrequencyResponseSmoothed(measurement::Id, Box<[f32]>),
     FrequencyResponseChart(frequency_response::Message),
+    GateChanged(f32),
+    ResetGate,
+    ExportGridChanged(data::frequency_response::Grid),
+    ExportFrequencyResponse(operation::Operand),
+    FrequencyResponseExported(Result<PathBuf, data::frequency_response::ExportError>),
+    ExportWindowedImpulseResponse(operation::Operand),
+    WindowedImpulseResponseExported(Result<Arc<Path>, data::impulse_response::ExportError>),
+
+    ChangeMode(project::Mode),
+    LoadCompensationCurve,
+    CompensationCurveLoaded(Result<data::curve::Curve, data::curve::Error>),
+    LoadTargetCurve,
+    TargetCurveLoaded(Result<data::curve::Curve, data::curve::Error>),
+    TargetCurveRemoved,
+    CorrectionLimitsChanged(ui::curve::CorrectionLimits),
+    CalibrationLoaded(PathBuf, Result<data::curve::Curve, data::curve::Error>),
 
     ShiftKeyPressed,
     ShiftKeyReleased,
@@ -162,20 +276,94 @@ pub enum Message {
 
     StartRecording(recording::Kind),
     Recording(recording::Message),
+    Remote(remote::Trigger),
 
     OpenSpectralDecayConfig,
     SpectralDecayConfig(spectral_decay_config::Message),
     SpectralDecayComputed(measurement::Id, data::SpectralDecay),
+    ExportSpectralDecay,
+    SpectralDecayExported(Result<PathBuf, spectral_decay::ExportError>),
 
     OpenSpectrogramConfig,
     SpectrogramConfig(spectrogram_config::Message),
     SpectrogramComputed(measurement::Id, data::Spectrogram),
     Spectrogram(chart::spectrogram::Interaction),
+    SpectrogramNormalizationChanged(spectrogram::Normalization),
+    SpectrogramLevelOffsetChanged(f32),
+    ChartPreferencesLoaded(Result<data::chart::Preferences, data::Error>),
+    ColormapChanged(data::chart::Colormap),
+    TimeUnitChanged(data::chart::TimeSeriesUnit),
+    WaveformAmplitudeUnitChanged(data::chart::AmplitudeUnit),
+    RmsEnvelopeToggled(bool),
+    ChartPreferencesSaved(Result<(), data::Error>),
+    ShowHarmonicsToggled(bool),
+    NormalizePlaybackLevelToggled(bool),
+    StereoSumToggled(bool),
+    SpreadChanged(frequency_response::Spread),
+    StereoSumChannelSelected(frequency_response::Channel, operation::Operand),
+    StereoSumComputed(Option<Vec<(f32, f32)>>),
+    Crossover(crossover::Message),
+    CrossoverSimulated(Option<crossover::SimulatedPoints>),
+
+    OpenOperation(operation::Kind),
+    Operation(operation::Message),
 
     PendingWindow(pending_window::Message),
+    RecomputeAll,
+    Recompute(recompute::Message),
+    WindowPresetsLoaded(Result<window::Presets, window::preset::Error>),
+    WindowPresetSelected(window::Preset),
+    WindowPresetNameChanged(String),
+    SaveWindowPreset,
+    WindowPresetsSaved(Result<(), window::preset::Error>),
+    ImportWindowPreset,
+    WindowPresetImported(Result<window::Preset, window::preset::Error>),
+    ExportWindowPreset,
+    WindowPresetExported(Result<PathBuf, window::preset::Error>),
     ProjectSaveDialog(save_project::Message),
     OpenRecentDialog,
+    OpenAuralization(measurement::Id),
+    Auralization(auralization::Message),
+    AuralizationDrySignalPicked(PathBuf),
+    AuralizationFilterPicked(PathBuf),
+    ExportAuralization(auralization::Request, PathBuf),
+    AuralizationExported(Result<PathBuf, data::auralization::Error>),
+    OpenSessionLog,
+    SessionLog(session_log::Message),
+    SessionLogExported(Result<PathBuf, io::ErrorKind>),
+    LoggingSettingsSaved(Result<(), data::Error>),
+    DiagnosticInfoCollected(String),
+    OpenWizard,
+    Wizard(wizard::Message),
+    OpenChannelCheck,
+    ChannelCheck(channel_check::Message),
+    OpenMovingMic,
+    MovingMic(moving_mic::Message),
+    MovingMicRemoved,
+    OpenSubAlignment,
+    SubAlignment(sub_alignment::Message),
+    OpenSplMeter,
+    SplMeter(spl_meter::Message),
+    OpenRta,
+    Rta(rta::Message),
+    OpenTransferFunction,
+    TransferFunction(transfer_function::Message),
+    LoopbackLatencyEstimated(Duration),
+    LoopbackVerified(Option<raumklang_core::loopback::Check>),
+    OnboardingSaved(Result<(), data::Error>),
+    OpenExportHookDialog,
+    ExportHookLoaded(Result<data::export_hook::ExportHook, data::Error>),
+    ExportHook(export_hook::Message),
+    ExportHookSaved(Result<(), data::Error>),
+    ExportHookFinished(Result<Option<data::export_hook::Output>, data::Error>),
+    OpenRemoteControlDialog,
+    RemoteControlLoaded(Result<data::remote_control::RemoteControl, data::Error>),
+    RemoteControl(remote_control::Message),
+    RemoteControlSaved(Result<(), data::Error>),
+    ExportSnapshot,
+    SnapshotExported(Result<PathBuf, data::Error>),
     EscapeKeyReleased,
+    StopAudio,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -185,10 +373,26 @@ enum ProjectMenu {
     Load,
     LoadRecent,
     SaveAs,
+    SetupWizard,
+    ChannelCheck,
+    MovingMic,
+    SubAlignment,
+    SplMeter,
+    Rta,
+    TransferFunction,
+    ExportHook,
+    RemoteControl,
+    ExportSnapshot,
 }
 
 impl Main {
     pub fn from_project(path: impl AsRef<Path>, project: Project) -> (Self, Task<Message>) {
+        let pending_selection = project
+            .charts
+            .selected
+            .and_then(|index| project.measurements.get(index))
+            .map(|measurement| measurement.path.clone());
+
         let load_loopback = project
             .loopback
             .map(|loopback| {
@@ -201,21 +405,113 @@ impl Main {
 
         let load_measurements = project.measurements.into_iter().map(|measurement| {
             Task::perform(
-                Measurement::from_file(measurement.path),
+                async move {
+                    let mut loaded = match measurement.source {
+                        project::Source::Recording => {
+                            Measurement::from_file(measurement.path).await
+                        }
+                        project::Source::Rew => {
+                            Measurement::from_rew(measurement.path, REW_SAMPLE_RATE).await
+                        }
+                    };
+                    loaded.playback_level = measurement.playback_level;
+                    loaded.levels = measurement.levels;
+                    loaded
+                },
                 Message::MeasurementLoaded,
             )
         });
 
+        let load_calibration = project
+            .calibration
+            .map(|path| {
+                Task::perform(
+                    data::curve::Curve::load(path.clone()),
+                    Message::CalibrationLoaded.with(path),
+                )
+            })
+            .unwrap_or_default();
+
+        let mut main = Self {
+            project_path: Some(path.as_ref().to_path_buf()),
+            measurement_operation: project.measurement_operation,
+            wav_format: project.wav_format,
+            mode: project.mode,
+            measurement_config: project.recording.map(Into::into).unwrap_or_default(),
+            preprocessing: project.preprocessing,
+            pending_selection,
+            ..Default::default()
+        };
+        main.restore_charts(&project.charts);
+
         (
-            Self {
-                project_path: Some(path.as_ref().to_path_buf()),
-                measurement_operation: project.measurement_operation,
-                ..Default::default()
-            },
-            Task::batch([load_loopback, Task::batch(load_measurements)]),
+            main,
+            Task::batch([
+                load_loopback,
+                Task::batch(load_measurements),
+                load_calibration,
+            ]),
         )
     }
 
+    /// Starts with the setup wizard, e.g. on the first start.
+    pub fn with_wizard() -> (Self, Task<Message>) {
+        let mut main = Self::default();
+        let task = main.open_wizard();
+
+        (main, task)
+    }
+
+    /// Measurements of a simulated room, e.g. to try the analyses without
+    /// any audio hardware.
+    pub fn demo() -> (Self, Task<Message>) {
+        let task = Task::future(async {
+            tokio::task::spawn_blocking(|| {
+                let loopback = data::demo::loopback();
+                let measurements = data::demo::measurements(&loopback);
+
+                (loopback, measurements)
+            })
+            .await
+            .unwrap()
+        })
+        .then(|(loopback, measurements)| {
+            let loopback = Loopback::new("Sweep".to_string(), loopback);
+            let measurements = measurements.into_iter().map(|(name, signal)| {
+                Task::done(Message::MeasurementLoaded(Measurement::new(
+                    name,
+                    None,
+                    Some(signal),
+                )))
+            });
+
+            Task::done(Message::LoopbackLoaded(loopback)).chain(Task::batch(measurements))
+        });
+
+        (Self::default(), task)
+    }
+
+    fn open_wizard(&mut self) -> Task<Message> {
+        let (wizard, task) = wizard::Wizard::new(
+            self.measurement_config.out_port.clone(),
+            self.measurement_config.in_port.clone(),
+        );
+
+        self.wizard = Some(wizard);
+        self.modal = Modal::Wizard;
+
+        task.map(Message::Wizard)
+    }
+
+    /// Returns to the wizard, if the current dialog was opened by it.
+    fn close_modal(&mut self) {
+        self.modal = if self.wizard.is_some() {
+            Modal::Wizard
+        } else {
+            Modal::None
+        };
+    }
+
     pub fn update(&mut self, recent_projects: &mut RecentProjects, msg: Message) -> Task<Message> {
         match msg {
             Message::NewProject => {
@@ -254,7 +550,13 @@ impl Main {
                         Task::none()
                     }
                     save_project::Action::Task(task) => task.map(Message::ProjectSaveDialog),
-                    save_project::Action::Save(path_buf, operation, export_from_memory) => {
+                    save_project::Action::Save(
+                        path_buf,
+                        operation,
+                        export_from_memory,
+                        wav_format,
+                    ) => {
+                        self.wav_format = wav_format;
                         self.save_project(path_buf, operation, export_from_memory)
                     }
                 }
@@ -297,7 +599,7 @@ impl Main {
                     return Task::none();
                 }
 
-                match tab {
+                let task = match tab {
                     tab::Id::Measurements => {
                         let State::Analysing {
                             active_tab: ref mut tab,
@@ -328,7 +630,7 @@ impl Main {
                             pending_window: window.clone(),
                         };
 
-                        Task::none()
+                        Task::perform(window::Presets::load(), Message::WindowPresetsLoaded)
                     }
                     tab::Id::FrequencyResponses => {
                         let State::Analysing {
@@ -350,7 +652,8 @@ impl Main {
                                 id,
                                 self.loopback.as_ref(),
                                 &self.measurements,
-                                self.window.as_ref().cloned().unwrap(),
+                                self.deconvolution,
+                                analysis_window(self.window.as_ref(), self.gate),
                             )
                         });
 
@@ -371,17 +674,27 @@ impl Main {
                             cache: canvas::Cache::new(),
                         };
 
-                        if let Some(id) = selected {
+                        let compute = if let Some(id) = selected {
                             compute_spectral_decay(
                                 id,
                                 analyses,
                                 self.spectral_decay_config,
+                                analysis_window(self.window.as_ref(), self.gate),
                                 self.loopback.as_ref(),
                                 &self.measurements,
+                                self.deconvolution,
                             )
                         } else {
                             Task::none()
-                        }
+                        };
+
+                        Task::batch([
+                            compute,
+                            Task::perform(
+                                data::chart::Preferences::load(),
+                                Message::ChartPreferencesLoaded,
+                            ),
+                        ])
                     }
                     tab::Id::Spectrograms => {
                         let State::Analysing {
@@ -397,62 +710,408 @@ impl Main {
                         *active_tab = Tab::Spectrograms;
                         self.spectrogram.cache.clear();
 
-                        if let Some(id) = selected {
+                        let compute = if let Some(id) = selected {
                             compute_spectrogram(
                                 id,
                                 analyses,
                                 &self.spectrogram_config,
+                                analysis_window(self.window.as_ref(), self.gate),
                                 self.loopback.as_ref(),
                                 &self.measurements,
+                                self.deconvolution,
                             )
                         } else {
                             Task::none()
-                        }
+                        };
+
+                        Task::batch([
+                            compute,
+                            Task::perform(
+                                data::chart::Preferences::load(),
+                                Message::ChartPreferencesLoaded,
+                            ),
+                        ])
                     }
-                }
+                    tab::Id::Crossover => {
+                        let State::Analysing {
+                            ref mut active_tab,
+                            ref mut analyses,
+                            ..
+                        } = self.state
+                        else {
+                            return Task::none();
+                        };
+
+                        *active_tab = Tab::Crossover;
+
+                        let crossover = self.crossover.get_or_insert_with(|| {
+                            let mut ids = self.measurements.loaded().map(Measurement::id);
+                            Crossover::new(ids.next(), ids.next())
+                        });
+
+                        let tasks = [crossover.low.measurement, crossover.high.measurement]
+                            .into_iter()
+                            .flatten()
+                            .map(|id| {
+                                compute_frequency_response(
+                                    analyses,
+                                    id,
+                                    self.loopback.as_ref(),
+                                    &self.measurements,
+                                    self.deconvolution,
+                                    analysis_window(self.window.as_ref(), self.gate),
+                                )
+                            })
+                            .collect::<Vec<_>>();
+
+                        Task::batch(tasks.into_iter().chain([self.compute_crossover()]))
+                    }
+                };
+
+                self.focus_jobs();
+
+                Task::batch([task, self.compute_split()])
+            }
+            Message::ToggleSplit => {
+                self.split = match self.split {
+                    Some(_) => None,
+                    None => Some(Split::default()),
+                };
+
+                Task::none()
+            }
+            Message::Split(split::Message::Close) => {
+                self.split = None;
+                Task::none()
+            }
+            Message::Split(message) => {
+                let Some(split) = &mut self.split else {
+                    return Task::none();
+                };
+
+                split.update(&message);
+
+                let load_preferences = if let split::Message::ViewSelected(_) = message {
+                    Task::perform(
+                        data::chart::Preferences::load(),
+                        Message::ChartPreferencesLoaded,
+                    )
+                } else {
+                    Task::none()
+                };
+
+                self.focus_jobs();
+
+                Task::batch([self.compute_split(), load_preferences])
             }
             Message::LoadLoopback => Task::future(pick_measurement_file("Load Loopback ..."))
                 .and_then(|path| Task::perform(Loopback::from_file(path), Message::LoopbackLoaded)),
             Message::LoadMeasurement => Task::future(pick_measurement_file("Load measurement ..."))
-                .and_then(|path| {
-                    Task::perform(Measurement::from_file(path), Message::MeasurementLoaded)
-                }),
+                .and_then(|path| Task::done(Message::MeasurementFilePicked(path))),
+            Message::MeasurementFilePicked(path) => {
+                let existing = self
+                    .measurements
+                    .iter()
+                    .find(|measurement| measurement.path.as_ref() == Some(&path));
+
+                if let Some(existing) = existing {
+                    self.modal = Modal::DuplicateMeasurement {
+                        path,
+                        existing: existing.id(),
+                    };
+
+                    return Task::none();
+                }
+
+                self.load_measurement(path)
+            }
+            Message::MeasurementLoading(path, fraction) => {
+                if let Some(progress) = self.loading.get_mut(&path) {
+                    *progress = fraction;
+                }
+
+                Task::none()
+            }
+            Message::DuplicateMeasurement(action) => {
+                let Modal::DuplicateMeasurement { path, existing } = mem::take(&mut self.modal)
+                else {
+                    return Task::none();
+                };
+
+                match action {
+                    duplicate_measurement::Message::Reload => {
+                        self.reload_measurement(existing, path)
+                    }
+                    duplicate_measurement::Message::AddAnyway => self.load_measurement(path),
+                    duplicate_measurement::Message::Cancel => Task::none(),
+                }
+            }
+            Message::MeasurementReloaded(id, measurement) => {
+                if let Some(path) = &measurement.path {
+                    self.loading.remove(path);
+                }
+
+                if !measurement.is_loaded() {
+                    log::error!("Could not reload measurement: {}", measurement.name);
+                    return Task::none();
+                }
+
+                let Some(existing) = self.measurements.get_mut(id) else {
+                    return Task::none();
+                };
+
+                log::info!("Measurement reloaded: {}", existing.name);
+                existing.reload(measurement);
+                existing.preprocess(self.preprocessing);
+
+                if let State::Analysing {
+                    ref mut analyses, ..
+                } = self.state
+                {
+                    analyses.remove(&id);
+                }
+
+                self.ir_chart.data_cache.clear();
+                self.signal_cache.clear();
+                self.check_sample_rates();
+
+                self.compute_shown(id)
+            }
+            Message::FileChanged(path) => {
+                // the modification time tells apart our own writes
+                let files = self
+                    .measurements
+                    .iter()
+                    .filter(|measurement| !measurement.is_changed_on_disk())
+                    .filter(|measurement| measurement.path.as_ref() == Some(&path))
+                    .filter_map(|measurement| {
+                        let path = measurement.path.clone()?;
+                        let loaded = measurement.signal()?.modified;
+
+                        Some((measurement.id(), path, loaded))
+                    })
+                    .collect();
+
+                Task::perform(measurement::changed_on_disk(files), Message::FilesChanged)
+            }
+            Message::FilesChanged(ids) => {
+                for id in ids {
+                    if let Some(measurement) = self.measurements.get_mut(id) {
+                        log::info!("{} was changed on disk", measurement.name);
+                        measurement.mark_changed_on_disk();
+                    }
+                }
+
+                Task::none()
+            }
+            Message::ImportRew => {
+                // text exports are turned into impulse responses, that match the loopback
+                let sample_rate = self
+                    .loopback
+                    .as_ref()
+                    .and_then(Loopback::loaded)
+                    .map_or(REW_SAMPLE_RATE, raumklang_core::Loopback::sample_rate);
+
+                Task::future(pick_rew_file()).and_then(move |path| {
+                    Task::perform(
+                        Measurement::from_rew(path, sample_rate),
+                        Message::MeasurementLoaded,
+                    )
+                })
+            }
+            Message::WatchFolder => Task::future(pick_watch_folder()).and_then(|path| {
+                Task::perform(data::WatchFolder::open(path), Message::WatchFolderOpened)
+            }),
+            Message::WatchFolderOpened(Ok(watch_folder)) => {
+                log::info!("Watching {:?} for new measurements", watch_folder.path());
+                self.watch_folder = Some(watch_folder);
+                Task::none()
+            }
+            Message::WatchFolderOpened(Err(err)) => {
+                log::error!("Could not watch folder: {err}");
+                Task::none()
+            }
+            Message::StopWatchFolder => {
+                self.watch_folder = None;
+                Task::none()
+            }
+            Message::WatchFolderChanged(path) => {
+                let Some(watch_folder) = &mut self.watch_folder else {
+                    return Task::none();
+                };
+
+                if !watch_folder.notice(&path) {
+                    return Task::none();
+                }
+
+                Task::perform(
+                    data::watch_folder::written(path.clone()),
+                    Message::WatchFolderWritten.with(path),
+                )
+            }
+            Message::WatchFolderWritten(path, Ok(())) => {
+                let Some(watch_folder) = &mut self.watch_folder else {
+                    return Task::none();
+                };
+
+                watch_folder.import(&path);
+
+                log::info!("Importing {path:?} from watch folder");
+                self.load_measurement(path)
+            }
+            Message::WatchFolderWritten(path, Err(err)) => {
+                if let Some(watch_folder) = &mut self.watch_folder {
+                    watch_folder.abandon(&path);
+                }
+
+                log::error!("Could not import {path:?} from watch folder: {err}");
+                Task::none()
+            }
             Message::LoopbackLoaded(loopback) => {
-                self.window = loopback
-                    .loaded()
-                    .map(raumklang_core::Loopback::sample_rate)
-                    .map(SampleRate::from)
-                    .map(Window::new)
-                    .map(Into::into);
+                log::info!("Loopback loaded");
 
                 self.loopback = Some(loopback);
+                self.window = self
+                    .analysis_sample_rate()
+                    .map(|sample_rate| Window::for_mode(self.mode, sample_rate))
+                    .map(Into::into);
 
                 if !self.measurements.is_empty() {
                     self.state = State::analysis();
                 }
 
+                self.restore_selection();
+                self.check_sample_rates();
+
                 Task::none()
             }
-            Message::MeasurementLoaded(measurement) => {
+            Message::MeasurementLoaded(mut measurement) => {
+                log::info!("Measurement loaded: {}", measurement.name);
+
+                if let Some(path) = &measurement.path {
+                    self.loading.remove(path);
+                }
+
+                if let Some(existing) = self
+                    .measurements
+                    .iter()
+                    .find(|existing| existing.is_duplicate_of(&measurement))
+                {
+                    log::warn!(
+                        "{} contains the same recording as {}",
+                        measurement.name,
+                        existing.name
+                    );
+                }
+
                 let is_loopback_loaded = self.loopback.as_ref().is_some_and(Loopback::is_loaded);
+                // imported impulse responses don't need a loopback
+                let is_imported = measurement.source == project::Source::Rew;
 
-                if is_loopback_loaded && self.measurements.is_empty() {
+                if (is_loopback_loaded || is_imported) && matches!(self.state, State::Collecting) {
                     self.state = State::analysis();
                 }
 
+                measurement.preprocess(self.preprocessing);
                 self.measurements.push(measurement);
+                self.restore_selection();
+                self.check_sample_rates();
+
+                if self.window.is_none() {
+                    self.window = self
+                        .analysis_sample_rate()
+                        .map(|sample_rate| Window::for_mode(self.mode, sample_rate))
+                        .map(Into::into);
+                }
+
+                Task::none()
+            }
+            Message::SampleRateMismatch(action) => {
+                let Modal::SampleRateMismatch { sample_rate, ids } = mem::take(&mut self.modal)
+                else {
+                    return Task::none();
+                };
+
+                match action {
+                    sample_rate_mismatch::Message::Remove => {
+                        let tasks: Vec<_> = ids
+                            .into_iter()
+                            .map(|id| {
+                                self.update(
+                                    recent_projects,
+                                    Message::Measurement(measurement::Message::Remove(id)),
+                                )
+                            })
+                            .collect();
+
+                        Task::batch(tasks)
+                    }
+                    sample_rate_mismatch::Message::Resample => {
+                        Task::batch(ids.into_iter().filter_map(|id| {
+                            let signal = self.measurements.get(id)?.signal()?.clone();
+
+                            Some(Task::perform(
+                                resample_measurement(signal, sample_rate),
+                                Message::MeasurementResampled.with(id),
+                            ))
+                        }))
+                    }
+                }
+            }
+            Message::MeasurementResampled(id, signal) => {
+                log::info!("Measurement {id} resampled to {} Hz", signal.sample_rate());
+
+                self.replace_signal(id, signal);
 
                 Task::none()
             }
+            Message::DcOffsetRemoved(id, signal) => {
+                log::info!("DC offset of measurement {id} removed");
+
+                self.replace_signal(id, signal);
+
+                Task::none()
+            }
+            Message::SidebarFilterChanged(filter) => {
+                self.sidebar_filter = filter;
+                Task::none()
+            }
             Message::Measurement(msg) => {
                 match msg {
                     measurement::Message::Select(selected) => {
                         self.selected = Some(selected);
                         self.signal_cache.clear();
                     }
+                    measurement::Message::Reload(id) => {
+                        let Some(path) = self.measurements.get(id).and_then(|m| m.path.clone())
+                        else {
+                            return Task::none();
+                        };
+
+                        return self.reload_measurement(id, path);
+                    }
+                    measurement::Message::RemoveDcOffset(id) => {
+                        let Some(signal) = self.measurements.get(id).and_then(|m| m.signal())
+                        else {
+                            return Task::none();
+                        };
+
+                        return Task::perform(
+                            remove_dc_offset(signal.clone()),
+                            Message::DcOffsetRemoved.with(id),
+                        );
+                    }
                     measurement::Message::Remove(id) => {
                         self.measurements.remove(id);
 
+                        if self.ir_chart.comparison == Some(id) {
+                            self.ir_chart.comparison = None;
+                        }
+
+                        if self.fr_solo == Some(id) {
+                            self.fr_solo = None;
+                        }
+
                         if self.measurements.loaded().next().is_none() {
                             self.state = State::Collecting
                         }
@@ -478,7 +1137,94 @@ impl Main {
 
                 Task::none()
             }
+            Message::DeconvolutionMethodChanged(method) => {
+                if self.deconvolution == method {
+                    return Task::none();
+                }
+
+                self.deconvolution = method;
+
+                let State::Analysing {
+                    selected,
+                    ref mut analyses,
+                    ..
+                } = self.state
+                else {
+                    return Task::none();
+                };
+
+                analyses.values_mut().for_each(|a| *a = Analysis::default());
+                self.ir_chart.data_cache.clear();
+
+                selected
+                    .map(|id| {
+                        compute_impulse_response(
+                            analyses,
+                            id,
+                            self.loopback.as_ref(),
+                            &self.measurements,
+                            method,
+                        )
+                    })
+                    .unwrap_or_default()
+            }
+            Message::PreprocessingChanged(preprocessing) => {
+                self.preprocessing = preprocessing;
+
+                let ids: Vec<_> = self.measurements.iter().map(Measurement::id).collect();
+                for id in ids {
+                    if let Some(measurement) = self.measurements.get_mut(id) {
+                        measurement.preprocess(preprocessing);
+                    }
+                }
+                self.signal_cache.clear();
+
+                let State::Analysing {
+                    selected,
+                    ref mut analyses,
+                    ..
+                } = self.state
+                else {
+                    return Task::none();
+                };
+
+                analyses.values_mut().for_each(|a| *a = Analysis::default());
+                self.ir_chart.data_cache.clear();
+
+                selected
+                    .map(|id| {
+                        compute_impulse_response(
+                            analyses,
+                            id,
+                            self.loopback.as_ref(),
+                            &self.measurements,
+                            self.deconvolution,
+                        )
+                    })
+                    .unwrap_or_default()
+            }
+            Message::TimeShiftChanged(id, time_shift) => {
+                self.time_shift_input = time_shift.to_string();
+                self.set_time_shift(id, time_shift);
+
+                Task::none()
+            }
+            Message::TimeShiftInput(id, input) => {
+                if let Ok(time_shift) = input.parse() {
+                    self.set_time_shift(id, time_shift);
+                }
+
+                self.time_shift_input = input;
+
+                Task::none()
+            }
             Message::ImpulseResponse(id, ui::impulse_response::Message::Select) => {
+                self.time_shift_input = self
+                    .measurements
+                    .get(id)
+                    .map_or(0, |m| m.time_shift)
+                    .to_string();
+
                 let State::Analysing {
                     active_tab: tab,
                     selected,
@@ -492,31 +1238,40 @@ impl Main {
                 *selected = Some(id);
                 self.ir_chart.data_cache.clear();
 
-                match tab {
+                let task = match tab {
                     Tab::Measurements => Task::none(),
                     Tab::ImpulseResponses { .. } => compute_impulse_response(
                         analyses,
                         id,
                         self.loopback.as_ref(),
                         &self.measurements,
+                        self.deconvolution,
                     ),
-                    Tab::FrequencyResponses { .. } => Task::none(),
+                    Tab::FrequencyResponses { .. } | Tab::Crossover => Task::none(),
                     Tab::SpectralDecays { .. } => compute_spectral_decay(
                         id,
                         analyses,
                         self.spectral_decay_config,
+                        analysis_window(self.window.as_ref(), self.gate),
                         self.loopback.as_ref(),
                         &self.measurements,
+                        self.deconvolution,
                     ),
 
                     Tab::Spectrograms => compute_spectrogram(
                         id,
                         analyses,
                         &self.spectrogram_config,
+                        analysis_window(self.window.as_ref(), self.gate),
                         self.loopback.as_ref(),
                         &self.measurements,
+                        self.deconvolution,
                     ),
-                }
+                };
+
+                self.focus_jobs();
+
+                task
             }
             Message::ImpulseResponse(id, ui::impulse_response::Message::Save) => {
                 let State::Analysing { .. } = self.state else {
@@ -542,15 +1297,24 @@ impl Main {
 
                 let analysis = analyses.entry(id).or_default();
                 if let Some(ir) = analysis.impulse_response.result().cloned() {
-                    Task::perform(save_impulse_response(path.clone(), ir.clone()), move |_| {
-                        Message::ImpulseResponseSaved(id, path)
-                    })
-                } else {
-                    compute_impulse_response(
+                    let metadata = self.impulse_response_metadata(id);
+
+                    Task::perform(
+                        save_impulse_response(
+                            path.clone(),
+                            ir.clone(),
+                            self.wav_format.into(),
+                            metadata,
+                        ),
+                        move |_| Message::ImpulseResponseSaved(id, path),
+                    )
+                } else {
+                    compute_impulse_response(
                         analyses,
                         id,
                         self.loopback.as_ref(),
                         &self.measurements,
+                        self.deconvolution,
                     )
                     .chain(Task::done(Message::SaveImpulseResponseToFile(
                         id,
@@ -561,9 +1325,94 @@ impl Main {
             Message::ImpulseResponseSaved(id, path) => {
                 eprintln!("IR (#{:?}) saved to: {:?}", id, path);
 
+                run_export_hook(vec![path.to_path_buf()])
+            }
+            Message::DenoiseToggled(id, enabled) => {
+                let State::Analysing {
+                    ref mut analyses, ..
+                } = self.state
+                else {
+                    return Task::none();
+                };
+
+                let Some(analysis) = analyses.get_mut(&id) else {
+                    return Task::none();
+                };
+
+                self.ir_chart.data_cache.clear();
+
+                if !enabled {
+                    analysis.denoised = None;
+                    return Task::none();
+                }
+
+                let Some(impulse_response) = analysis.impulse_response() else {
+                    return Task::none();
+                };
+
+                let Some(method) = self.deconvolution.deconvolution() else {
+                    log::warn!("Denoising needs a deconvolution with the loopback");
+                    return Task::none();
+                };
+
+                let loopback = self.loopback.as_ref().and_then(Loopback::loaded);
+                let measurement = self.measurements.get(id).and_then(Measurement::signal);
+                let (Some(loopback), Some(measurement)) = (loopback, measurement) else {
+                    log::warn!("Denoising needs the recording of measurement {id}");
+                    return Task::none();
+                };
+
+                let denoise = data::impulse_response::denoise(
+                    scheduler::Subject::new(id),
+                    impulse_response.data.clone(),
+                    loopback.clone(),
+                    measurement.clone(),
+                    method,
+                );
+
+                analysis.denoised = Some(ui::impulse_response::Denoised::Computing);
+
+                Task::perform(denoise, Message::Denoised.with(id))
+            }
+            Message::Denoised(id, impulse_response) => {
+                let State::Analysing {
+                    ref mut analyses, ..
+                } = self.state
+                else {
+                    return Task::none();
+                };
+
+                let Some(analysis) = analyses.get_mut(&id) else {
+                    return Task::none();
+                };
+
+                // turned off in the meantime
+                if !matches!(
+                    analysis.denoised,
+                    Some(ui::impulse_response::Denoised::Computing)
+                ) {
+                    return Task::none();
+                }
+
+                let drift = analysis.impulse_response().and_then(|ir| ir.drift);
+                let denoised = match impulse_response {
+                    Some(impulse_response) => ui::impulse_response::Denoised::Computed(Box::new(
+                        ui::ImpulseResponse::new(Arc::unwrap_or_clone(impulse_response), drift),
+                    )),
+                    None => {
+                        log::warn!("Measurement {id} has too little silence to estimate the noise");
+                        ui::impulse_response::Denoised::Failed
+                    }
+                };
+
+                analysis.denoised = Some(denoised);
+                self.ir_chart.data_cache.clear();
+
                 Task::none()
             }
             Message::ImpulseResponseComputed(id, impulse_response) => {
+                log::info!("Impulse response computed: {id}");
+
                 let State::Analysing {
                     ref active_tab,
                     ref mut analyses,
@@ -576,31 +1425,83 @@ impl Main {
                 analyses.entry(id).and_modify(|analysis| {
                     analysis.impulse_response =
                         ui::impulse_response::State::from_data(impulse_response);
+                    analysis.denoised = None;
                 });
 
+                if self.ir_chart.comparison == Some(id) {
+                    self.ir_chart.data_cache.clear();
+                }
+
+                if let Modal::Recompute(progress) = &mut self.modal
+                    && progress.is_pending(id, recompute::Stage::ImpulseResponse)
+                {
+                    if analyses
+                        .get(&id)
+                        .is_none_or(|a| a.impulse_response.result().is_none())
+                    {
+                        return Task::none();
+                    }
+
+                    progress.finish(id, recompute::Stage::ImpulseResponse);
+
+                    return Task::batch([
+                        compute_frequency_response(
+                            analyses,
+                            id,
+                            self.loopback.as_ref(),
+                            &self.measurements,
+                            self.deconvolution,
+                            analysis_window(self.window.as_ref(), self.gate),
+                        ),
+                        compute_spectral_decay(
+                            id,
+                            analyses,
+                            self.spectral_decay_config,
+                            analysis_window(self.window.as_ref(), self.gate),
+                            self.loopback.as_ref(),
+                            &self.measurements,
+                            self.deconvolution,
+                        ),
+                        compute_spectrogram(
+                            id,
+                            analyses,
+                            &self.spectrogram_config,
+                            analysis_window(self.window.as_ref(), self.gate),
+                            self.loopback.as_ref(),
+                            &self.measurements,
+                            self.deconvolution,
+                        ),
+                    ]);
+                }
+
                 match active_tab {
                     Tab::Measurements => Task::none(),
-                    Tab::ImpulseResponses { .. } => Task::none(),
+                    Tab::ImpulseResponses { .. } | Tab::Crossover => Task::none(),
                     Tab::FrequencyResponses { .. } => compute_frequency_response(
                         analyses,
                         id,
                         self.loopback.as_ref(),
                         &self.measurements,
-                        self.window.as_ref().cloned().unwrap(),
+                        self.deconvolution,
+                        analysis_window(self.window.as_ref(), self.gate),
                     ),
                     Tab::SpectralDecays { .. } => compute_spectral_decay(
                         id,
                         analyses,
                         self.spectral_decay_config,
+                        analysis_window(self.window.as_ref(), self.gate),
                         self.loopback.as_ref(),
                         &self.measurements,
+                        self.deconvolution,
                     ),
                     Tab::Spectrograms => compute_spectrogram(
                         id,
                         analyses,
                         &self.spectrogram_config,
+                        analysis_window(self.window.as_ref(), self.gate),
                         self.loopback.as_ref(),
                         &self.measurements,
+                        self.deconvolution,
                     ),
                 }
             }
@@ -624,6 +1525,7 @@ impl Main {
                         pending_window::Message::Discard => self.ir_chart.overlay_cache.clear(),
                         pending_window::Message::Apply => {
                             self.window = Some(pending_window);
+                            self.gate = None;
                             analyses.values_mut().for_each(|a| *a = Analysis::default());
                         }
                     }
@@ -632,11 +1534,27 @@ impl Main {
                 self.update(recent_projects, Message::OpenTab(goto_tab))
             }
             Message::FrequencyResponseComputed(id, new_fr) => {
-                log::debug!("Frequency response computed: {id}");
+                log::info!("Frequency response computed: {id}");
+
+                let new_fr = match &self.calibration {
+                    Some((_, calibration)) => new_fr.calibrated(calibration),
+                    None => new_fr,
+                };
+
+                let playback_level = self
+                    .measurements
+                    .get(id)
+                    .and_then(|m| m.playback_level)
+                    .filter(|_| self.normalize_playback_level);
+
+                let new_fr = match playback_level {
+                    Some(level) => new_fr.with_gain(-level),
+                    None => new_fr,
+                };
 
                 let State::Analysing {
                     ref mut analyses,
-                    active_tab: Tab::FrequencyResponses { ref cache },
+                    ref active_tab,
                     ..
                 } = self.state
                 else {
@@ -654,9 +1572,45 @@ impl Main {
 
                 let analysis = analyses.entry(id).or_default();
                 analysis.frequency_response.set_result(new_fr);
-                cache.clear();
+                analysis
+                    .frequency_response
+                    .set_band_fraction(self.fr_display.band_fraction());
+                analysis
+                    .frequency_response
+                    .set_phase_shown(self.fr_display.shows_phase());
+
+                if let Tab::FrequencyResponses { cache } = active_tab {
+                    cache.clear();
+                }
 
-                task
+                self.update_channel_difference();
+                self.update_spread();
+                self.update_target_error();
+                self.finish_recompute(id, recompute::Stage::FrequencyResponse);
+
+                let is_summed = self
+                    .stereo_sum
+                    .as_ref()
+                    .is_some_and(|sum| sum.left == Some(id) || sum.right == Some(id));
+
+                let is_crossed_over = self
+                    .crossover
+                    .as_ref()
+                    .is_some_and(|crossover| crossover.contains(id));
+
+                let stereo_sum = if is_summed {
+                    self.compute_stereo_sum()
+                } else {
+                    Task::none()
+                };
+
+                let crossover = if is_crossed_over {
+                    self.compute_crossover()
+                } else {
+                    Task::none()
+                };
+
+                Task::batch([task, stereo_sum, crossover])
             }
             Message::FrequencyResponseToggled(id, state) => {
                 let State::Analysing {
@@ -675,6 +1629,53 @@ impl Main {
                 fr.is_shown = state;
                 cache.clear();
 
+                self.update_emphasis();
+                self.update_spread();
+                self.update_target_error();
+
+                Task::none()
+            }
+            Message::FrequencyResponseSoloed(id) => {
+                self.fr_solo = if self.fr_solo == Some(id) {
+                    None
+                } else {
+                    Some(id)
+                };
+
+                self.update_emphasis();
+                self.update_spread();
+                self.update_target_error();
+
+                Task::none()
+            }
+            Message::FrequencyResponseHovered(id) => {
+                self.fr_hovered = id;
+                self.update_emphasis();
+
+                Task::none()
+            }
+            Message::FrequencyResponseDisplayChanged(display) => {
+                self.fr_display = display;
+
+                if display.shows_phase() {
+                    // the phase starts at the frequencies shown before
+                    let (&min, &max) = self.fr_state.axis_mut(&FREQ_AXIS_ID).domain();
+                    self.fr_phase_state
+                        .axis_mut(&FREQ_AXIS_ID)
+                        .set_domain(min, max);
+                }
+
+                if let State::Analysing {
+                    ref mut analyses, ..
+                } = self.state
+                {
+                    for analysis in analyses.values_mut() {
+                        let frequency_response = analysis.frequency_response_mut();
+                        frequency_response.set_band_fraction(display.band_fraction());
+                        frequency_response.set_phase_shown(display.shows_phase());
+                    }
+                }
+
                 Task::none()
             }
             Message::ChangeSmoothing(smoothing) => {
@@ -690,19 +1691,22 @@ impl Main {
                 self.smoothing = smoothing;
 
                 if let Some(fraction) = smoothing.fraction() {
-                    let tasks = analyses.iter().flat_map(|(id, analysis)| {
-                        let fr = analysis.frequency_response.result()?;
-
-                        Some(Task::perform(
-                            frequency_response::smooth_frequency_response(
-                                fr.origin.clone(),
-                                fraction,
-                            ),
-                            Message::FrequencyResponseSmoothed.with(*id),
-                        ))
-                    });
-
-                    Task::batch(tasks)
+                    let tasks: Vec<_> = analyses
+                        .iter()
+                        .flat_map(|(id, analysis)| {
+                            let fr = analysis.frequency_response.result()?;
+
+                            Some(Task::perform(
+                                frequency_response::smooth_frequency_response(
+                                    fr.origin.clone(),
+                                    fraction,
+                                ),
+                                Message::FrequencyResponseSmoothed.with(*id),
+                            ))
+                        })
+                        .collect();
+
+                    Task::batch(tasks.into_iter().chain([self.compute_stereo_sum()]))
                 } else {
                     analyses
                         .values_mut()
@@ -710,14 +1714,17 @@ impl Main {
                         .for_each(|fr| fr.reset_smoothing());
 
                     cache.clear();
+                    self.update_channel_difference();
+                    self.update_spread();
+                    self.update_target_error();
 
-                    Task::none()
+                    self.compute_stereo_sum()
                 }
             }
             Message::FrequencyResponseSmoothed(id, smoothed) => {
                 let State::Analysing {
                     ref mut analyses,
-                    active_tab: Tab::FrequencyResponses { ref cache },
+                    ref active_tab,
                     ..
                 } = self.state
                 else {
@@ -733,59 +1740,175 @@ impl Main {
                         smoothed,
                         SampleRate::from(data.origin.sample_rate),
                     ));
-                    cache.clear();
+
+                    if let Tab::FrequencyResponses { cache } = active_tab {
+                        cache.clear();
+                    }
                 }
 
+                self.update_channel_difference();
+                self.update_spread();
+                self.update_target_error();
+
                 Task::none()
             }
             Message::FrequencyResponseChart(msg) => {
+                let shows_phase = self.fr_display.shows_phase();
+                let (state, y_axis_id, (min_y, max_y)) = if shows_phase {
+                    (
+                        &mut self.fr_phase_state,
+                        PHASE_AXIS_ID,
+                        (MIN_PHASE, MAX_PHASE),
+                    )
+                } else {
+                    (&mut self.fr_state, DB_AXIS_ID, (MIN_DB, MAX_DB))
+                };
+
                 match msg {
                     frequency_response::Message::OnPlotScroll(cursor_pos, delta) => match delta {
                         ScrollDelta::Lines { x: _, y } => {
                             let factor = 1.1f32.powf(y);
 
-                            self.fr_state
+                            state
                                 .axis_mut(&FREQ_AXIS_ID)
                                 .zoom(factor, Some(cursor_pos.x));
-                            self.fr_state
-                                .axis_mut(&DB_AXIS_ID)
-                                .zoom(factor, Some(cursor_pos.y));
+                            state.axis_mut(&y_axis_id).zoom(factor, Some(cursor_pos.y));
                         }
                         ScrollDelta::Pixels { x: _, y } => {
                             // For pixel-based scrolling (touchpad)
                             // Divide by larger number for less sensitive zooming
                             let factor = 1.0 + y / 500.0;
 
-                            self.fr_state
+                            state
                                 .axis_mut(&FREQ_AXIS_ID)
                                 .zoom(factor, Some(cursor_pos.x));
-                            self.fr_state
-                                .axis_mut(&DB_AXIS_ID)
-                                .zoom(factor, Some(cursor_pos.y));
+                            state.axis_mut(&y_axis_id).zoom(factor, Some(cursor_pos.y));
                         }
                     },
                     frequency_response::Message::OnPlotDrag(delta) => {
                         // --- Pan X-Axis ---
-                        self.fr_state.axis_mut(&FREQ_AXIS_ID).pan(delta.x);
+                        state.axis_mut(&FREQ_AXIS_ID).pan(delta.x);
                         // self.clamp_x_axis();
-                        self.fr_state.axis_mut(&DB_AXIS_ID).pan(delta.y);
+                        state.axis_mut(&y_axis_id).pan(delta.y);
                     }
                 }
                 // clamp
-                let x_axis = self.fr_state.axis_mut(&FREQ_AXIS_ID);
+                let x_axis = state.axis_mut(&FREQ_AXIS_ID);
                 let (&min, &max) = x_axis.domain();
                 x_axis.set_domain(min.max(MIN_FREQ), max.min(MAX_FREQ));
 
-                let y_axis = self.fr_state.axis_mut(&DB_AXIS_ID);
+                let y_axis = state.axis_mut(&y_axis_id);
                 let (&min, &max) = y_axis.domain();
-                y_axis.set_domain(min.max(MIN_DB), max.min(MAX_DB));
+                y_axis.set_domain(min.max(min_y), max.min(max_y));
+
+                // only the level view is stored in the project
+                if !shows_phase {
+                    self.fr_view = Some(project::FrequencyResponseView {
+                        frequency: {
+                            let (&min, &max) = self.fr_state.axis_mut(&FREQ_AXIS_ID).domain();
+                            (min, max)
+                        },
+                        level: (min.max(MIN_DB), max.min(MAX_DB)),
+                    });
+                }
+
+                Task::none()
+            }
+            Message::ChangeMode(mode) => {
+                if self.mode == mode {
+                    return Task::none();
+                }
+
+                self.mode = mode;
+                self.window = self
+                    .analysis_sample_rate()
+                    .map(|sample_rate| Window::for_mode(mode, sample_rate))
+                    .map(Into::into);
 
+                if let State::Analysing { .. } = self.state {
+                    self.state = State::analysis();
+                }
+
+                self.channel_difference = None;
+
+                Task::none()
+            }
+            Message::LoadCompensationCurve => {
+                Task::future(pick_curve_file("Load compensation curve ...")).and_then(|path| {
+                    Task::perform(
+                        data::curve::Curve::load(path),
+                        Message::CompensationCurveLoaded,
+                    )
+                })
+            }
+            Message::CompensationCurveLoaded(Ok(curve)) => {
+                let points = curve
+                    .0
+                    .into_iter()
+                    .map(|(frequency, level)| PlotPoint::new(frequency, level));
+
+                self.compensation = Some(ui::Curve::new(COMPENSATION_COLOR, points));
+
+                Task::none()
+            }
+            Message::CompensationCurveLoaded(Err(err)) => {
+                log::error!("Could not load compensation curve: {err}");
+                Task::none()
+            }
+            Message::LoadTargetCurve => Task::future(pick_curve_file("Load target curve ..."))
+                .and_then(|path| {
+                    Task::perform(data::curve::Curve::load(path), Message::TargetCurveLoaded)
+                }),
+            Message::TargetCurveLoaded(Ok(curve)) => {
+                let points = curve
+                    .0
+                    .iter()
+                    .map(|&(frequency, level)| PlotPoint::new(frequency, level));
+
+                let line = ui::Curve::new(TARGET_COLOR, points);
+
+                self.target = Some((curve, line));
+                self.update_target_error();
+
+                Task::none()
+            }
+            Message::TargetCurveLoaded(Err(err)) => {
+                log::error!("Could not load target curve: {err}");
+                Task::none()
+            }
+            Message::TargetCurveRemoved => {
+                self.target = None;
+                self.target_error = None;
+                self.correction_envelope = None;
+
+                Task::none()
+            }
+            Message::CorrectionLimitsChanged(limits) => {
+                self.correction_limits = limits;
+                self.update_target_error();
+
+                Task::none()
+            }
+            Message::CalibrationLoaded(path, Ok(curve)) => {
+                log::info!("Microphone calibration loaded: {path:?}");
+
+                if let Some(wizard) = &mut self.wizard {
+                    wizard.calibration_loaded(path.clone());
+                }
+
+                self.calibration = Some((path, curve));
+                self.recompute_frequency_responses()
+            }
+            Message::CalibrationLoaded(path, Err(err)) => {
+                log::error!("Could not load microphone calibration {path:?}: {err}");
                 Task::none()
             }
             Message::SpectralDecayComputed(id, sd) => {
+                log::info!("Spectral decay computed: {id}");
+
                 let State::Analysing {
                     ref mut analyses,
-                    active_tab: Tab::SpectralDecays { ref cache },
+                    ref active_tab,
                     ..
                 } = self.state
                 else {
@@ -797,241 +1920,1834 @@ impl Main {
                 };
 
                 analysis.spectral_decay.set_result(sd);
-                cache.clear();
+                analysis
+                    .spectral_decay
+                    .set_colormap(self.chart_preferences.colormap);
 
-                Task::none()
-            }
-            Message::OpenSpectralDecayConfig => {
-                self.modal = Modal::SpectralDecayConfig(SpectralDecayConfig::new(
-                    self.spectral_decay_config,
-                ));
+                if let Tab::SpectralDecays { cache } = active_tab {
+                    cache.clear();
+                }
+
+                self.finish_recompute(id, recompute::Stage::SpectralDecay);
 
                 Task::none()
             }
-            Message::SpectralDecayConfig(msg) => {
-                let Modal::SpectralDecayConfig(modal) = &mut self.modal else {
-                    return Task::none();
-                };
-
+            Message::ExportSpectralDecay => {
                 let State::Analysing {
-                    selected,
-                    ref mut analyses,
+                    selected: Some(id),
+                    ref analyses,
                     ..
                 } = self.state
                 else {
                     return Task::none();
                 };
 
-                match modal.update(msg) {
-                    spectral_decay_config::Action::None => Task::none(),
-                    spectral_decay_config::Action::Discard => {
-                        self.modal = Modal::None;
-                        Task::none()
-                    }
-                    spectral_decay_config::Action::Apply(config) => {
-                        self.modal = Modal::None;
-                        self.spectral_decay_config = config;
+                let Some(slices) = analyses.get(&id).and_then(|a| a.spectral_decay.result()) else {
+                    return Task::none();
+                };
 
-                        analyses.values_mut().for_each(|a| a.spectral_decay.reset());
+                let export = spectral_decay::Export {
+                    colormap: self.chart_preferences.colormap,
+                    shift: (&self.spectral_decay_config.shift).into(),
+                    slices: slices
+                        .iter()
+                        .map(|slice| slice.0.iter().map(|p| (p.x, p.y)).collect())
+                        .collect(),
+                };
 
-                        selected
-                            .map(|id| {
-                                compute_spectral_decay(
-                                    id,
-                                    analyses,
-                                    config,
-                                    self.loopback.as_ref(),
-                                    &self.measurements,
-                                )
-                            })
-                            .unwrap_or_default()
-                    }
-                }
+                Task::future(choose_spectral_decay_file_path()).and_then(move |path| {
+                    Task::perform(
+                        spectral_decay::export(path, export.clone()),
+                        Message::SpectralDecayExported,
+                    )
+                })
             }
-            Message::SpectrogramComputed(id, spectrogram) => {
+            Message::WindowPresetsLoaded(Ok(presets)) => {
+                self.window_presets = presets;
+                Task::none()
+            }
+            Message::WindowPresetsLoaded(Err(err)) => {
+                log::error!("Could not load window presets: {err}");
+                Task::none()
+            }
+            Message::WindowPresetSelected(preset) => {
                 let State::Analysing {
-                    selected,
-                    ref mut analyses,
+                    active_tab: Tab::ImpulseResponses { pending_window },
                     ..
-                } = self.state
+                } = &mut self.state
                 else {
                     return Task::none();
                 };
 
-                let analysis = analyses.entry(id).or_default();
-                analysis.spectrogram.set_result(spectrogram);
-
-                if selected.is_some_and(|selected| selected == id) {
-                    self.spectrogram.cache.clear();
-                }
+                *pending_window = preset.window(pending_window.sample_rate()).into();
+                self.window_preset_name = preset.name;
+                self.ir_chart.overlay_cache.clear();
 
                 Task::none()
             }
-            Message::Spectrogram(interaction) => {
-                match interaction {
-                    chart::spectrogram::Interaction::ZoomChanged(zoom) => {
-                        self.spectrogram.zoom = zoom
-                    }
-                    chart::spectrogram::Interaction::OffsetChanged(offset) => {
-                        self.spectrogram.offset = offset
-                    }
-                }
-
-                self.spectrogram.cache.clear();
-
+            Message::WindowPresetsSaved(Ok(())) => Task::none(),
+            Message::WindowPresetsSaved(Err(err)) => {
+                log::error!("Could not save window presets: {err}");
                 Task::none()
             }
-            Message::OpenSpectrogramConfig => {
-                self.modal = Modal::SpectrogramConfig(modal::SpectrogramConfig::new(
-                    self.spectrogram_config.clone(),
-                ));
-
+            Message::WindowPresetNameChanged(name) => {
+                self.window_preset_name = name;
                 Task::none()
             }
-            Message::SpectrogramConfig(message) => {
-                let Modal::SpectrogramConfig(config) = &mut self.modal else {
-                    return Task::none();
-                };
-
+            Message::SaveWindowPreset => {
                 let State::Analysing {
-                    selected, analyses, ..
-                } = &mut self.state
+                    active_tab: Tab::ImpulseResponses { pending_window },
+                    ..
+                } = &self.state
                 else {
                     return Task::none();
                 };
 
-                match config.update(message) {
-                    spectrogram_config::Action::None => Task::none(),
-                    spectrogram_config::Action::Close => {
-                        self.modal = Modal::None;
-
-                        Task::none()
-                    }
-                    spectrogram_config::Action::ConfigChanged(preferences) => {
-                        self.modal = Modal::None;
+                let name = self.window_preset_name.trim().to_string();
+                if name.is_empty() {
+                    return Task::none();
+                }
 
-                        let task = if let Some(id) = selected {
-                            analyses
-                                .get_mut(id)
-                                .and_then(|a| {
-                                    a.spectrogram.compute(&a.impulse_response, &preferences)
-                                })
-                                .map(|f| Task::perform(f, Message::SpectrogramComputed.with(*id)))
-                                .unwrap_or_default()
-                        } else {
-                            Task::none()
-                        };
+                let window = Window::<Duration>::from(pending_window.clone());
+                self.window_presets
+                    .insert(window::Preset::new(name, &window));
 
-                        self.spectrogram_config = preferences;
-                        analyses.values_mut().for_each(|a| a.spectrogram.reset());
+                Task::perform(
+                    self.window_presets.clone().save(),
+                    Message::WindowPresetsSaved,
+                )
+            }
+            Message::ImportWindowPreset => {
+                Task::future(pick_window_preset_file()).and_then(|path| {
+                    Task::perform(window::Preset::import(path), Message::WindowPresetImported)
+                })
+            }
+            Message::WindowPresetImported(Ok(preset)) => {
+                self.window_presets.insert(preset.clone());
 
-                        task
-                    }
-                }
+                Task::batch([
+                    Task::perform(
+                        self.window_presets.clone().save(),
+                        Message::WindowPresetsSaved,
+                    ),
+                    Task::done(Message::WindowPresetSelected(preset)),
+                ])
             }
-            Message::ImpulseResponseChart(operation) => {
+            Message::WindowPresetImported(Err(err)) => {
+                log::error!("Could not import window preset: {err}");
+                Task::none()
+            }
+            Message::ExportWindowPreset => {
                 let State::Analysing {
                     active_tab: Tab::ImpulseResponses { pending_window },
                     ..
-                } = &mut self.state
+                } = &self.state
                 else {
                     return Task::none();
                 };
 
-                if let ChartOperation::Interaction(ref interaction) = operation {
-                    match interaction {
-                        chart::Interaction::HandleMoved(index, new_pos) => {
-                            let mut handles = window::Handles::from(&*pending_window);
-                            handles.update(*index, *new_pos);
-                            pending_window.update(handles);
-                        }
-                        chart::Interaction::ZoomChanged(zoom) => {
-                            self.ir_chart.zoom = *zoom;
-                        }
-                        chart::Interaction::OffsetChanged(offset) => {
-                            self.ir_chart.offset = *offset;
-                        }
-                    }
-                }
-                self.ir_chart.update(operation);
+                let name = match self.window_preset_name.trim() {
+                    "" => "Window".to_string(),
+                    name => name.to_string(),
+                };
 
+                let window = Window::<Duration>::from(pending_window.clone());
+                let preset = window::Preset::new(name, &window);
+
+                Task::future(choose_window_preset_file_path()).and_then(move |path| {
+                    Task::perform(preset.clone().export(path), Message::WindowPresetExported)
+                })
+            }
+            Message::WindowPresetExported(Ok(path)) => {
+                log::info!("Window preset exported to: {path:?}");
                 Task::none()
             }
-            Message::StartRecording(kind) => {
-                self.modal =
-                    Modal::Recording(Recording::new(kind, self.measurement_config.clone()));
+            Message::WindowPresetExported(Err(err)) => {
+                log::error!("Could not export window preset: {err}");
                 Task::none()
             }
-            Message::Recording(msg) => {
-                let Modal::Recording(recording) = &mut self.modal else {
+            Message::GateChanged(ms) => {
+                self.gate = Some(Duration::from_secs_f32(ms / 1000.0));
+                self.recompute_gated_frequency_responses()
+            }
+            Message::ResetGate => {
+                self.gate = None;
+                self.recompute_gated_frequency_responses()
+            }
+            Message::ExportGridChanged(grid) => {
+                self.export_grid = grid;
+                Task::none()
+            }
+            Message::ExportFrequencyResponse(operand) => {
+                let State::Analysing { ref analyses, .. } = self.state else {
                     return Task::none();
                 };
 
-                match recording.update(msg) {
-                    recording::Action::None => Task::none(),
-                    recording::Action::Cancel => {
+                let Some(fr) = analyses
+                    .get(&operand.id)
+                    .and_then(|a| a.frequency_response.result())
+                else {
+                    return Task::none();
+                };
+
+                let origin = fr.origin.clone();
+                let smoothing = self.smoothing.fraction();
+                let grid = self.export_grid;
+
+                Task::future(choose_frequency_response_file_path()).and_then(move |path| {
+                    Task::perform(
+                        data::frequency_response::export(path, origin.clone(), smoothing, grid),
+                        Message::FrequencyResponseExported,
+                    )
+                })
+            }
+            Message::FrequencyResponseExported(Ok(path)) => {
+                log::info!("Frequency response exported to: {path:?}");
+                run_export_hook(vec![path])
+            }
+            Message::FrequencyResponseExported(Err(err)) => {
+                log::error!("Could not export frequency response: {err}");
+                Task::none()
+            }
+            Message::ExportWindowedImpulseResponse(operand) => {
+                let State::Analysing { ref analyses, .. } = self.state else {
+                    return Task::none();
+                };
+
+                let Some(ir) = analyses
+                    .get(&operand.id)
+                    .and_then(|a| a.windowed_impulse_response.clone())
+                else {
+                    return Task::none();
+                };
+
+                let format = self.wav_format.into();
+                let metadata = self.impulse_response_metadata(operand.id);
+                Task::future(choose_impulse_response_file_path()).and_then(move |path| {
+                    Task::perform(
+                        data::impulse_response::export_windowed(
+                            path,
+                            ir.clone(),
+                            format,
+                            metadata.clone(),
+                        ),
+                        Message::WindowedImpulseResponseExported,
+                    )
+                })
+            }
+            Message::WindowedImpulseResponseExported(Ok(path)) => {
+                log::info!("Windowed impulse response exported to: {path:?}");
+                run_export_hook(vec![path.to_path_buf()])
+            }
+            Message::WindowedImpulseResponseExported(Err(err)) => {
+                log::error!("Could not export windowed impulse response: {err}");
+                Task::none()
+            }
+            Message::SpectralDecayExported(Ok(path)) => {
+                log::info!("Spectral decay exported to: {path:?}");
+                Task::none()
+            }
+            Message::SpectralDecayExported(Err(err)) => {
+                log::error!("Could not export spectral decay: {err}");
+                Task::none()
+            }
+            Message::OpenSpectralDecayConfig => {
+                self.modal = Modal::SpectralDecayConfig(SpectralDecayConfig::new(
+                    self.spectral_decay_config,
+                ));
+
+                Task::none()
+            }
+            Message::SpectralDecayConfig(msg) => {
+                let Modal::SpectralDecayConfig(modal) = &mut self.modal else {
+                    return Task::none();
+                };
+
+                let State::Analysing {
+                    selected,
+                    ref mut analyses,
+                    ..
+                } = self.state
+                else {
+                    return Task::none();
+                };
+
+                match modal.update(msg) {
+                    spectral_decay_config::Action::None => Task::none(),
+                    spectral_decay_config::Action::Discard => {
                         self.modal = Modal::None;
                         Task::none()
                     }
-                    recording::Action::Task(task) => task.map(Message::Recording),
-                    recording::Action::Finished(config, result) => {
-                        self.measurement_config = config;
-                        match result {
-                            recording::Result::Loopback(loopback) => {
-                                self.loopback =
-                                    Some(ui::Loopback::new("Loopback".to_string(), loopback));
-                            }
-                            recording::Result::Measurement(measurement) => {
-                                self.measurements.push(ui::Measurement::new(
-                                    "Measurement".to_string(),
-                                    None,
-                                    Some(measurement),
-                                ));
-                            }
-                        }
-
+                    spectral_decay_config::Action::Apply(config) => {
                         self.modal = Modal::None;
-                        Task::none()
+                        self.spectral_decay_config = config;
+
+                        analyses.values_mut().for_each(|a| a.spectral_decay.reset());
+
+                        let task = selected
+                            .map(|id| {
+                                compute_spectral_decay(
+                                    id,
+                                    analyses,
+                                    config,
+                                    analysis_window(self.window.as_ref(), self.gate),
+                                    self.loopback.as_ref(),
+                                    &self.measurements,
+                                    self.deconvolution,
+                                )
+                            })
+                            .unwrap_or_default();
+
+                        Task::batch([task, self.compute_split()])
                     }
                 }
             }
-            Message::ShiftKeyPressed => {
-                self.ir_chart.shift_key_pressed();
+            Message::SpectrogramComputed(id, spectrogram) => {
+                log::info!("Spectrogram computed: {id}");
+
+                let State::Analysing {
+                    selected,
+                    ref mut analyses,
+                    ..
+                } = self.state
+                else {
+                    return Task::none();
+                };
+
+                let analysis = analyses.entry(id).or_default();
+                analysis.spectrogram.set_result(spectrogram);
+
+                if selected.is_some_and(|selected| selected == id) {
+                    self.spectrogram.cache.clear();
+                }
+
+                if let Some(split) = &self.split
+                    && split.selected == Some(id)
+                {
+                    split.cache.clear();
+                }
+
+                self.finish_recompute(id, recompute::Stage::Spectrogram);
+
                 Task::none()
             }
-            Message::ShiftKeyReleased => {
-                self.ir_chart.shift_key_released();
+            Message::SpectrogramNormalizationChanged(normalization) => {
+                self.spectrogram.normalization = normalization;
+                self.spectrogram.cache.clear();
+                self.clear_split_cache();
+
                 Task::none()
             }
-            Message::EscapeKeyReleased => {
-                if let Modal::OpenRecentProject = self.modal {
-                    self.modal = Modal::None;
-                }
+            Message::SpectrogramLevelOffsetChanged(offset) => {
+                self.spectrogram.level_offset = offset;
+                self.spectrogram.cache.clear();
+                self.clear_split_cache();
 
                 Task::none()
             }
-            Message::ProjectLoaded(Err(err)) => {
-                log::error!("{err}");
+            Message::ChartPreferencesLoaded(Ok(preferences)) => {
+                self.set_colormap(preferences.colormap);
+                self.set_time_unit(preferences.time_unit);
+                self.chart_preferences.amplitude_unit = preferences.amplitude_unit;
+                self.chart_preferences.rms_envelope = preferences.rms_envelope;
+                self.signal_cache.clear();
                 Task::none()
             }
-            Message::ProjectSaved(Err(err)) => {
-                log::error!("Could not save project to {:?} - {err}", self.project_path);
+            Message::ChartPreferencesLoaded(Err(err)) => {
+                log::error!("Could not load chart preferences: {err}");
                 Task::none()
             }
-            Message::OpenRecentDialog => {
-                self.modal = Modal::OpenRecentProject;
+            Message::ColormapChanged(colormap) => {
+                self.set_colormap(colormap);
+
+                Task::perform(
+                    self.chart_preferences.save(),
+                    Message::ChartPreferencesSaved,
+                )
+            }
+            Message::TimeUnitChanged(time_unit) => {
+                self.set_time_unit(time_unit);
+
+                Task::perform(
+                    self.chart_preferences.save(),
+                    Message::ChartPreferencesSaved,
+                )
+            }
+            Message::WaveformAmplitudeUnitChanged(amplitude_unit) => {
+                self.chart_preferences.amplitude_unit = amplitude_unit;
+                self.signal_cache.clear();
+
+                Task::perform(
+                    self.chart_preferences.save(),
+                    Message::ChartPreferencesSaved,
+                )
+            }
+            Message::RmsEnvelopeToggled(rms_envelope) => {
+                self.chart_preferences.rms_envelope = rms_envelope;
+                self.signal_cache.clear();
+
+                Task::perform(
+                    self.chart_preferences.save(),
+                    Message::ChartPreferencesSaved,
+                )
+            }
+            Message::ShowHarmonicsToggled(show_harmonics) => {
+                self.show_harmonics = show_harmonics;
                 Task::none()
             }
-        }
-    }
+            Message::NormalizePlaybackLevelToggled(normalize) => {
+                self.normalize_playback_level = normalize;
+                self.recompute_frequency_responses()
+            }
+            Message::SpreadChanged(spread) => {
+                self.spread = spread;
+                self.update_spread();
 
-    fn open_project_dialog(&mut self) -> Task<Message> {
-        self.modal = Modal::SaveProjectDialog(save_project::View::new(
-            self.measurement_operation,
-            self.export_from_memory,
-        ));
+                Task::none()
+            }
+            Message::StereoSumToggled(false) => {
+                self.stereo_sum = None;
+                Task::none()
+            }
+            Message::StereoSumToggled(true) => {
+                let mut ids = self.measurements.loaded().map(Measurement::id);
+
+                self.stereo_sum = Some(frequency_response::StereoSum {
+                    left: ids.next(),
+                    right: ids.next(),
+                    curve: None,
+                });
+
+                self.compute_stereo_sum()
+            }
+            Message::StereoSumChannelSelected(channel, operand) => {
+                let Some(sum) = &mut self.stereo_sum else {
+                    return Task::none();
+                };
+
+                match channel {
+                    frequency_response::Channel::Left => sum.left = Some(operand.id),
+                    frequency_response::Channel::Right => sum.right = Some(operand.id),
+                }
+                sum.curve = None;
+
+                self.compute_stereo_sum()
+            }
+            Message::StereoSumComputed(points) => {
+                let Some(sum) = &mut self.stereo_sum else {
+                    return Task::none();
+                };
+
+                sum.curve = points.map(|points| {
+                    ui::Curve::new(
+                        STEREO_SUM_COLOR,
+                        points
+                            .into_iter()
+                            .map(|(frequency, level)| PlotPoint::new(frequency, level)),
+                    )
+                });
+
+                if let State::Analysing {
+                    active_tab: Tab::FrequencyResponses { ref cache },
+                    ..
+                } = self.state
+                {
+                    cache.clear();
+                }
+
+                Task::none()
+            }
+            Message::Crossover(crossover::Message::Chart(message)) => {
+                self.update(recent_projects, Message::FrequencyResponseChart(message))
+            }
+            Message::Crossover(message) => {
+                let Some(crossover) = &mut self.crossover else {
+                    return Task::none();
+                };
+
+                if crossover.update(message) {
+                    self.focus_jobs();
+                    self.compute_crossover()
+                } else {
+                    Task::none()
+                }
+            }
+            Message::CrossoverSimulated(points) => {
+                if let Some(crossover) = &mut self.crossover {
+                    crossover.set_simulation(points);
+                }
+
+                Task::none()
+            }
+            Message::ChartPreferencesSaved(Ok(())) => Task::none(),
+            Message::ChartPreferencesSaved(Err(err)) => {
+                log::error!("Could not save chart preferences: {err}");
+                Task::none()
+            }
+            Message::Spectrogram(interaction) => {
+                match interaction {
+                    chart::spectrogram::Interaction::ZoomChanged(zoom) => {
+                        self.spectrogram.zoom = zoom
+                    }
+                    chart::spectrogram::Interaction::OffsetChanged(offset) => {
+                        self.spectrogram.offset = offset
+                    }
+                }
+
+                self.spectrogram.cache.clear();
+
+                Task::none()
+            }
+            Message::OpenSpectrogramConfig => {
+                self.modal = Modal::SpectrogramConfig(modal::SpectrogramConfig::new(
+                    self.spectrogram_config.clone(),
+                ));
+
+                Task::none()
+            }
+            Message::SpectrogramConfig(message) => {
+                let Modal::SpectrogramConfig(config) = &mut self.modal else {
+                    return Task::none();
+                };
+
+                let State::Analysing {
+                    selected, analyses, ..
+                } = &mut self.state
+                else {
+                    return Task::none();
+                };
+
+                match config.update(message) {
+                    spectrogram_config::Action::None => Task::none(),
+                    spectrogram_config::Action::Close => {
+                        self.modal = Modal::None;
+
+                        Task::none()
+                    }
+                    spectrogram_config::Action::ConfigChanged(preferences) => {
+                        self.modal = Modal::None;
+
+                        let task = if let Some(id) = selected {
+                            let window = analysis_window(self.window.as_ref(), self.gate);
+                            let time_shift = self.measurements.get(*id).map_or(0, |m| m.time_shift);
+
+                            analyses
+                                .get_mut(id)
+                                .and_then(|a| {
+                                    let impulse_response =
+                                        a.windowed_impulse_response(&window, time_shift);
+                                    a.spectrogram.compute(
+                                        scheduler::Subject::new(id),
+                                        impulse_response,
+                                        &preferences,
+                                    )
+                                })
+                                .map(|f| Task::perform(f, Message::SpectrogramComputed.with(*id)))
+                                .unwrap_or_default()
+                        } else {
+                            Task::none()
+                        };
+
+                        self.spectrogram_config = preferences;
+                        analyses.values_mut().for_each(|a| a.spectrogram.reset());
+
+                        Task::batch([task, self.compute_split()])
+                    }
+                }
+            }
+            Message::ImpulseResponseChart(operation) => {
+                let State::Analysing {
+                    active_tab: Tab::ImpulseResponses { pending_window },
+                    analyses,
+                    ..
+                } = &mut self.state
+                else {
+                    return Task::none();
+                };
+
+                let task = if let ChartOperation::CompareWith(Some(id)) = operation {
+                    compute_impulse_response(
+                        analyses,
+                        id,
+                        self.loopback.as_ref(),
+                        &self.measurements,
+                        self.deconvolution,
+                    )
+                } else {
+                    Task::none()
+                };
+
+                if let ChartOperation::Interaction(ref interaction) = operation {
+                    match interaction {
+                        chart::Interaction::HandleMoved(index, new_pos) => {
+                            let mut handles = window::Handles::from(&*pending_window);
+                            handles.update(*index, *new_pos);
+                            pending_window.update(handles);
+                        }
+                        chart::Interaction::ZoomChanged(zoom) => {
+                            self.ir_chart.zoom = *zoom;
+                        }
+                        chart::Interaction::OffsetChanged(offset) => {
+                            self.ir_chart.offset = *offset;
+                        }
+                    }
+                }
+                self.ir_chart.update(operation);
+
+                task
+            }
+            Message::StartRecording(kind) => {
+                self.modal =
+                    Modal::Recording(Recording::new(kind, self.measurement_config.clone()));
+
+                Task::perform(
+                    data::measurement::Templates::load(),
+                    recording::Message::TemplatesLoaded,
+                )
+                .map(Message::Recording)
+            }
+            Message::Remote(trigger) => {
+                let msg = match (&self.modal, trigger) {
+                    (Modal::Recording(_), trigger) => {
+                        Message::Recording(recording::Message::Trigger(trigger))
+                    }
+                    (Modal::None, remote::Trigger::Next) => {
+                        Message::StartRecording(match self.loopback {
+                            Some(_) => recording::Kind::Measurement,
+                            None => recording::Kind::Loopback,
+                        })
+                    }
+                    _ => return Task::none(),
+                };
+
+                self.update(recent_projects, msg)
+            }
+            Message::Recording(msg) => {
+                let Modal::Recording(recording) = &mut self.modal else {
+                    return Task::none();
+                };
+
+                match recording.update(msg) {
+                    recording::Action::None => Task::none(),
+                    recording::Action::Cancel => {
+                        self.close_modal();
+                        Task::none()
+                    }
+                    recording::Action::Task(task) => task.map(Message::Recording),
+                    recording::Action::Finished(config, result) => {
+                        self.measurement_config = config;
+                        let mut task = Task::none();
+
+                        match result {
+                            recording::Result::Loopback(loopback) => {
+                                log::info!("Loopback recorded");
+
+                                let verification = {
+                                    let signal = self.measurement_config.signal;
+                                    let loopback = loopback.clone();
+
+                                    Task::perform(
+                                        async move {
+                                            tokio::task::spawn_blocking(move || {
+                                                audio::verify_loopback(&signal, &loopback)
+                                            })
+                                            .await
+                                            .ok()
+                                            .flatten()
+                                        },
+                                        Message::LoopbackVerified,
+                                    )
+                                };
+
+                                if self.wizard.is_some() {
+                                    let signal = self.measurement_config.signal;
+                                    let loopback = loopback.clone();
+
+                                    task = Task::perform(
+                                        async move {
+                                            tokio::task::spawn_blocking(move || {
+                                                audio::loopback_latency(&signal, &loopback)
+                                            })
+                                            .await
+                                            .unwrap_or_default()
+                                        },
+                                        Message::LoopbackLatencyEstimated,
+                                    );
+                                }
+
+                                task = Task::batch([task, verification]);
+
+                                self.loopback =
+                                    Some(ui::Loopback::new("Loopback".to_string(), loopback));
+                            }
+                            recording::Result::Measurement(measurement, levels) => {
+                                let count = self.measurements.iter().count();
+                                let (channel, position) = match self.mode {
+                                    project::Mode::Room => ("Measurement", count + 1),
+                                    // L/R coupler measurements are taken in pairs
+                                    project::Mode::Headphone if count % 2 == 0 => {
+                                        ("Left", count / 2 + 1)
+                                    }
+                                    project::Mode::Headphone => ("Right", count / 2 + 1),
+                                };
+
+                                let name = self.measurement_config.name_template.render(
+                                    &data::measurement::name::Fields {
+                                        channel,
+                                        position,
+                                        date_time: chrono::Local::now(),
+                                    },
+                                );
+
+                                log::info!("Measurement recorded: {name}");
+                                let mut measurement =
+                                    ui::Measurement::new(name, None, Some(measurement));
+                                measurement.playback_level =
+                                    self.measurement_config.playback_level();
+                                measurement.levels = levels;
+                                measurement.preprocess(self.preprocessing);
+                                self.measurements.push(measurement);
+
+                                if let Some(wizard) = &mut self.wizard {
+                                    wizard.test_sweep_recorded();
+                                }
+                            }
+                        }
+
+                        self.close_modal();
+                        task
+                    }
+                }
+            }
+            Message::ShiftKeyPressed => {
+                self.ir_chart.shift_key_pressed();
+                Task::none()
+            }
+            Message::ShiftKeyReleased => {
+                self.ir_chart.shift_key_released();
+                Task::none()
+            }
+            Message::EscapeKeyReleased => match self.modal {
+                Modal::OpenRecentProject
+                | Modal::SessionLog(_)
+                | Modal::Auralization(_)
+                | Modal::ExportHook(_)
+                | Modal::RemoteControl(_)
+                | Modal::DuplicateMeasurement { .. } => {
+                    self.modal = Modal::None;
+                    Task::none()
+                }
+                // closing the others stops their playback
+                _ => match self.modal.escape() {
+                    Some(escape) => self.update(recent_projects, escape),
+                    None => Task::none(),
+                },
+            },
+            Message::StopAudio => match self.modal.stop() {
+                Some(stop) => self.update(recent_projects, stop),
+                None => Task::none(),
+            },
+            Message::OpenWizard => self.open_wizard(),
+            Message::OpenChannelCheck => {
+                self.modal =
+                    Modal::ChannelCheck(channel_check::View::new(&self.measurement_config));
+                Task::none()
+            }
+            Message::ChannelCheck(msg) => {
+                let Modal::ChannelCheck(view) = &mut self.modal else {
+                    return Task::none();
+                };
+
+                match view.update(msg) {
+                    channel_check::Action::None => Task::none(),
+                    channel_check::Action::Task(task) => task.map(Message::ChannelCheck),
+                    channel_check::Action::Close => {
+                        self.modal = Modal::None;
+                        Task::none()
+                    }
+                }
+            }
+            Message::MovingMicRemoved => {
+                self.moving_mic = None;
+                Task::none()
+            }
+            Message::OpenMovingMic => {
+                self.modal = Modal::MovingMic(moving_mic::View::new(&self.measurement_config));
+                Task::none()
+            }
+            Message::MovingMic(msg) => {
+                let Modal::MovingMic(view) = &mut self.modal else {
+                    return Task::none();
+                };
+
+                match view.update(msg) {
+                    moving_mic::Action::None => Task::none(),
+                    moving_mic::Action::Task(task) => task.map(Message::MovingMic),
+                    moving_mic::Action::Finished(average) => {
+                        let calibration = self.calibration.as_ref().map(|(_, curve)| curve);
+                        let points = average
+                            .points(calibration)
+                            .into_iter()
+                            .map(|(frequency, level)| PlotPoint::new(frequency, level));
+
+                        self.moving_mic = Some(ui::Curve::new(MOVING_MIC_COLOR, points));
+                        self.modal = Modal::None;
+
+                        Task::none()
+                    }
+                    moving_mic::Action::Close => {
+                        self.modal = Modal::None;
+                        Task::none()
+                    }
+                }
+            }
+            Message::OpenSubAlignment => {
+                self.modal =
+                    Modal::SubAlignment(sub_alignment::View::new(&self.measurement_config));
+                Task::none()
+            }
+            Message::SubAlignment(msg) => {
+                let Modal::SubAlignment(view) = &mut self.modal else {
+                    return Task::none();
+                };
+
+                match view.update(msg) {
+                    sub_alignment::Action::None => Task::none(),
+                    sub_alignment::Action::Task(task) => task.map(Message::SubAlignment),
+                    sub_alignment::Action::Close => {
+                        self.modal = Modal::None;
+                        Task::none()
+                    }
+                }
+            }
+            Message::OpenTransferFunction => {
+                self.modal =
+                    Modal::TransferFunction(transfer_function::View::new(&self.measurement_config));
+                Task::none()
+            }
+            Message::TransferFunction(msg) => {
+                let Modal::TransferFunction(view) = &mut self.modal else {
+                    return Task::none();
+                };
+
+                match view.update(msg) {
+                    transfer_function::Action::None => Task::none(),
+                    transfer_function::Action::Task(task) => task.map(Message::TransferFunction),
+                    transfer_function::Action::Close => {
+                        self.modal = Modal::None;
+                        Task::none()
+                    }
+                }
+            }
+            Message::OpenRta => {
+                self.modal = Modal::Rta(rta::View::new(&self.measurement_config));
+                Task::none()
+            }
+            Message::Rta(msg) => {
+                let Modal::Rta(view) = &mut self.modal else {
+                    return Task::none();
+                };
+
+                match view.update(msg) {
+                    rta::Action::None => Task::none(),
+                    rta::Action::Task(task) => task.map(Message::Rta),
+                    rta::Action::Close => {
+                        self.modal = Modal::None;
+                        Task::none()
+                    }
+                }
+            }
+            Message::OpenSplMeter => {
+                self.modal = Modal::SplMeter(spl_meter::View::new(&self.measurement_config));
+                Task::none()
+            }
+            Message::SplMeter(msg) => {
+                let Modal::SplMeter(view) = &mut self.modal else {
+                    return Task::none();
+                };
+
+                match view.update(msg) {
+                    spl_meter::Action::None => Task::none(),
+                    spl_meter::Action::Task(task) => task.map(Message::SplMeter),
+                    spl_meter::Action::Close => {
+                        self.modal = Modal::None;
+                        Task::none()
+                    }
+                }
+            }
+            Message::Wizard(msg) => {
+                let Some(wizard) = &mut self.wizard else {
+                    return Task::none();
+                };
+
+                match wizard.update(msg) {
+                    wizard::Action::None => Task::none(),
+                    wizard::Action::Task(task) => task.map(Message::Wizard),
+                    wizard::Action::PortsSelected(out_port, in_port) => {
+                        self.measurement_config.out_port = Some(out_port);
+                        self.measurement_config.in_port = Some(in_port);
+                        Task::none()
+                    }
+                    wizard::Action::RecordLoopback => self.update(
+                        recent_projects,
+                        Message::StartRecording(recording::Kind::Loopback),
+                    ),
+                    wizard::Action::LoadCalibration => Task::future(pick_calibration_file())
+                        .and_then(|path| {
+                            Task::perform(
+                                data::curve::Curve::load(path.clone()),
+                                Message::CalibrationLoaded.with(path),
+                            )
+                        }),
+                    wizard::Action::RunTestSweep => self.update(
+                        recent_projects,
+                        Message::StartRecording(recording::Kind::Measurement),
+                    ),
+                    wizard::Action::Close => {
+                        self.wizard = None;
+                        self.modal = Modal::None;
+
+                        Task::perform(
+                            data::onboarding::Onboarding { completed: true }.save(),
+                            Message::OnboardingSaved,
+                        )
+                    }
+                }
+            }
+            Message::LoopbackVerified(None) => {
+                log::warn!("Loopback verification failed: the loopback is silent");
+                Task::none()
+            }
+            Message::LoopbackVerified(Some(check)) => {
+                let issues = check.issues();
+                if issues.is_empty() {
+                    log::info!(
+                        "Loopback verified: {:.0} dB SNR, flat within {:.2} dB",
+                        check.snr,
+                        check.deviation
+                    );
+                }
+
+                for issue in issues {
+                    log::warn!(
+                        "Loopback verification failed: {issue} ({:.0} dB SNR, {:.2} dB deviation, {:.1} ppm drift)",
+                        check.snr,
+                        check.deviation,
+                        check.drift.unwrap_or_default()
+                    );
+                }
+
+                Task::none()
+            }
+            Message::LoopbackLatencyEstimated(latency) => {
+                log::info!("Loopback latency: {latency:?}");
+
+                if let Some(wizard) = &mut self.wizard {
+                    wizard.loopback_recorded(latency);
+                }
+
+                Task::none()
+            }
+            Message::OnboardingSaved(Ok(())) => Task::none(),
+            Message::OnboardingSaved(Err(err)) => {
+                log::error!("Could not save onboarding state: {err}");
+                Task::none()
+            }
+            Message::OpenExportHookDialog => Task::perform(
+                data::export_hook::ExportHook::load(),
+                Message::ExportHookLoaded,
+            ),
+            Message::ExportHookLoaded(Ok(hook)) => {
+                self.modal = Modal::ExportHook(export_hook::View::new(hook));
+                Task::none()
+            }
+            Message::ExportHookLoaded(Err(err)) => {
+                log::error!("Could not load export hook: {err}");
+                Task::none()
+            }
+            Message::ExportHook(msg) => {
+                let Modal::ExportHook(view) = &mut self.modal else {
+                    return Task::none();
+                };
+
+                match view.update(msg) {
+                    export_hook::Action::None => Task::none(),
+                    export_hook::Action::Save(hook) => {
+                        self.modal = Modal::None;
+                        Task::perform(hook.save(), Message::ExportHookSaved)
+                    }
+                    export_hook::Action::Cancel => {
+                        self.modal = Modal::None;
+                        Task::none()
+                    }
+                }
+            }
+            Message::OpenRemoteControlDialog => match self.remote_control {
+                Some(settings) => {
+                    self.modal = Modal::RemoteControl(remote_control::View::new(settings));
+                    Task::none()
+                }
+                None => Task::perform(
+                    data::remote_control::RemoteControl::load(),
+                    Message::RemoteControlLoaded,
+                ),
+            },
+            Message::RemoteControlLoaded(Ok(settings)) => {
+                self.modal = Modal::RemoteControl(remote_control::View::new(settings));
+                Task::none()
+            }
+            Message::RemoteControlLoaded(Err(err)) => {
+                log::error!("Could not load remote control settings: {err}");
+                Task::none()
+            }
+            Message::RemoteControl(msg) => {
+                let Modal::RemoteControl(view) = &mut self.modal else {
+                    return Task::none();
+                };
+
+                match view.update(msg) {
+                    remote_control::Action::None => Task::none(),
+                    remote_control::Action::Save(settings) => {
+                        self.modal = Modal::None;
+                        self.remote_control = Some(settings);
+                        Task::perform(settings.save(), Message::RemoteControlSaved)
+                    }
+                    remote_control::Action::Cancel => {
+                        self.modal = Modal::None;
+                        Task::none()
+                    }
+                }
+            }
+            Message::RemoteControlSaved(Ok(())) => Task::none(),
+            Message::RemoteControlSaved(Err(err)) => {
+                log::error!("Could not save remote control settings: {err}");
+                Task::none()
+            }
+            Message::ExportHookSaved(Ok(())) => Task::none(),
+            Message::ExportHookSaved(Err(err)) => {
+                log::error!("Could not save export hook: {err}");
+                Task::none()
+            }
+            Message::ExportHookFinished(Ok(None)) => Task::none(),
+            Message::ExportHookFinished(Ok(Some(output))) => {
+                if !output.stdout.is_empty() {
+                    log::info!("Export hook output:\n{}", output.stdout);
+                }
+                if !output.stderr.is_empty() {
+                    log::warn!("Export hook error output:\n{}", output.stderr);
+                }
+
+                if output.status.success() {
+                    log::info!("Export hook `{}` finished", output.command);
+                } else {
+                    log::error!("Export hook `{}` failed: {}", output.command, output.status);
+                }
+
+                Task::none()
+            }
+            Message::ExportHookFinished(Err(err)) => {
+                log::error!("Could not run export hook: {err}");
+                Task::none()
+            }
+            Message::ExportSnapshot => {
+                let snapshot = self.snapshot();
+                let file_name = data::snapshot::file_name(self.project_path.as_deref());
+
+                Task::future(choose_snapshot_file_path(file_name)).and_then(move |path| {
+                    Task::perform(snapshot.clone().save(path), Message::SnapshotExported)
+                })
+            }
+            Message::SnapshotExported(Ok(path)) => {
+                log::info!("Snapshot exported to: {path:?}");
+                Task::none()
+            }
+            Message::SnapshotExported(Err(err)) => {
+                log::error!("Could not export snapshot: {err}");
+                Task::none()
+            }
+            Message::ProjectLoaded(Err(err)) => {
+                log::error!("{err}");
+                Task::none()
+            }
+            Message::ProjectSaved(Err(err)) => {
+                log::error!("Could not save project to {:?} - {err}", self.project_path);
+                Task::none()
+            }
+            Message::OpenRecentDialog => {
+                self.modal = Modal::OpenRecentProject;
+                Task::none()
+            }
+            Message::OpenAuralization(id) => {
+                let State::Analysing { ref analyses, .. } = self.state else {
+                    return Task::none();
+                };
+
+                // only impulse responses, that are already computed
+                let operands: Vec<_> = self
+                    .measurements
+                    .loaded()
+                    .filter(|m| {
+                        analyses
+                            .get(&m.id())
+                            .is_some_and(|a| a.impulse_response.result().is_some())
+                    })
+                    .map(|m| operation::Operand {
+                        id: m.id(),
+                        name: m.name.clone(),
+                    })
+                    .collect();
+
+                let selected = operands.iter().find(|operand| operand.id == id).cloned();
+
+                self.modal = Modal::Auralization(auralization::View::new(operands, selected));
+                Task::none()
+            }
+            Message::Auralization(msg) => {
+                let Modal::Auralization(view) = &mut self.modal else {
+                    return Task::none();
+                };
+
+                match view.update(msg) {
+                    auralization::Action::None => Task::none(),
+                    auralization::Action::PickDrySignal => Task::future(pick_dry_signal_file())
+                        .and_then(|path| Task::done(Message::AuralizationDrySignalPicked(path))),
+                    auralization::Action::PickFilter => Task::future(pick_filter_file())
+                        .and_then(|path| Task::done(Message::AuralizationFilterPicked(path))),
+                    auralization::Action::Export(request) => {
+                        Task::future(choose_auralization_file_path()).and_then(move |path| {
+                            Task::done(Message::ExportAuralization(request.clone(), path))
+                        })
+                    }
+                    auralization::Action::Close => {
+                        self.modal = Modal::None;
+                        Task::none()
+                    }
+                }
+            }
+            Message::ExportAuralization(request, path) => {
+                let (Modal::Auralization(view), State::Analysing { analyses, .. }) =
+                    (&mut self.modal, &mut self.state)
+                else {
+                    return Task::none();
+                };
+
+                let window = analysis_window(self.window.as_ref(), self.gate);
+                let time_shift = self
+                    .measurements
+                    .get(request.operand.id)
+                    .map_or(0, |m| m.time_shift);
+
+                let Some(impulse_response) = analyses
+                    .get_mut(&request.operand.id)
+                    .and_then(|a| a.windowed_impulse_response(&window, time_shift))
+                else {
+                    return Task::none();
+                };
+
+                view.exporting();
+
+                Task::perform(
+                    data::auralization::export(
+                        impulse_response,
+                        request.dry,
+                        request.filter,
+                        path,
+                        self.wav_format.into(),
+                    ),
+                    Message::AuralizationExported,
+                )
+            }
+            Message::AuralizationDrySignalPicked(path) => {
+                if let Modal::Auralization(view) = &mut self.modal {
+                    view.dry_signal_picked(path);
+                }
+
+                Task::none()
+            }
+            Message::AuralizationFilterPicked(path) => {
+                if let Modal::Auralization(view) = &mut self.modal {
+                    view.filter_picked(path);
+                }
+
+                Task::none()
+            }
+            Message::AuralizationExported(result) => {
+                let task = match &result {
+                    Ok(path) => {
+                        log::info!("Auralization exported to {}", path.display());
+                        run_export_hook(vec![path.clone()])
+                    }
+                    Err(err) => {
+                        log::error!("Auralization failed: {err}");
+                        Task::none()
+                    }
+                };
+
+                if let Modal::Auralization(view) = &mut self.modal {
+                    view.exported(result);
+                }
+
+                task
+            }
+            Message::OpenSessionLog => {
+                self.modal = Modal::SessionLog(session_log::View::new());
+                Task::none()
+            }
+            Message::SessionLog(msg) => {
+                let Modal::SessionLog(view) = &mut self.modal else {
+                    return Task::none();
+                };
+
+                match view.update(msg) {
+                    session_log::Action::None => Task::none(),
+                    session_log::Action::Export(log) => {
+                        Task::future(choose_session_log_file_path()).and_then(move |path| {
+                            Task::perform(
+                                session_log::export(path, log.clone()),
+                                Message::SessionLogExported,
+                            )
+                        })
+                    }
+                    session_log::Action::SettingsChanged(settings) => {
+                        log::apply(settings);
+                        Task::perform(settings.save(), Message::LoggingSettingsSaved)
+                    }
+                    session_log::Action::CopyDiagnosticInfo => Task::perform(
+                        session_log::diagnostic_info(),
+                        Message::DiagnosticInfoCollected,
+                    ),
+                    session_log::Action::Close => {
+                        self.modal = Modal::None;
+                        Task::none()
+                    }
+                }
+            }
+            Message::SessionLogExported(Ok(path)) => {
+                log::info!("Session log exported to: {path:?}");
+                Task::none()
+            }
+            Message::SessionLogExported(Err(err)) => {
+                log::error!("Could not export session log: {err}");
+                Task::none()
+            }
+            Message::LoggingSettingsSaved(Ok(())) => Task::none(),
+            Message::LoggingSettingsSaved(Err(err)) => {
+                log::error!("Could not save logging settings: {err}");
+                Task::none()
+            }
+            Message::DiagnosticInfoCollected(info) => {
+                log::info!("Diagnostic info copied to the clipboard");
+                iced::clipboard::write(info)
+            }
+            Message::RecomputeAll => {
+                let State::Analysing {
+                    ref mut analyses, ..
+                } = self.state
+                else {
+                    return Task::none();
+                };
+
+                // the inverse filter of the sweep needs no loopback
+                if self.deconvolution.deconvolution().is_some()
+                    && self.loopback.as_ref().and_then(Loopback::loaded).is_none()
+                {
+                    return Task::none();
+                }
+
+                let ids: Vec<_> = self.measurements.loaded().map(Measurement::id).collect();
+
+                analyses.values_mut().for_each(|a| *a = Analysis::default());
+                self.ir_chart.data_cache.clear();
+                self.spectrogram.cache.clear();
+                self.channel_difference = None;
+                self.spread_band = None;
+                self.target_error = None;
+                self.correction_envelope = None;
+
+                self.modal = Modal::Recompute(recompute::View::new(ids.iter().copied()));
+
+                Task::batch(ids.into_iter().map(|id| {
+                    compute_impulse_response(
+                        analyses,
+                        id,
+                        self.loopback.as_ref(),
+                        &self.measurements,
+                        self.deconvolution,
+                    )
+                }))
+            }
+            Message::Recompute(recompute::Message::Cancel) => {
+                self.modal = Modal::None;
+                Task::none()
+            }
+            Message::OpenOperation(kind) => {
+                let operands = self
+                    .measurements
+                    .loaded()
+                    .map(|measurement| operation::Operand {
+                        id: measurement.id(),
+                        name: measurement.name.clone(),
+                    })
+                    .collect();
+
+                self.modal = Modal::Operation(operation::View::new(kind, operands));
+                Task::none()
+            }
+            Message::Operation(msg) => {
+                let Modal::Operation(view) = &mut self.modal else {
+                    return Task::none();
+                };
+
+                match view.update(msg) {
+                    operation::Action::None => Task::none(),
+                    operation::Action::Cancel => {
+                        self.modal = Modal::None;
+                        Task::none()
+                    }
+                    operation::Action::Apply(operation) => {
+                        self.modal = Modal::None;
+
+                        match apply_operation(&self.measurements, operation) {
+                            Some(Ok((name, signal))) => {
+                                self.measurements.push(ui::Measurement::new(
+                                    name,
+                                    None,
+                                    Some(signal),
+                                ));
+                            }
+                            Some(Err(err)) => log::error!("Operation failed: {err}"),
+                            None => {}
+                        }
+
+                        Task::none()
+                    }
+                }
+            }
+        }
+    }
+
+    fn open_project_dialog(&mut self) -> Task<Message> {
+        self.modal = Modal::SaveProjectDialog(save_project::View::new(
+            self.measurement_operation,
+            self.export_from_memory,
+            self.wav_format,
+        ));
+
+        Task::none()
+    }
+
+    /// Asks to resample or remove all measurements, that were recorded with
+    /// another sample rate than the loopback.
+    /// Sample rate of the analysis window, given by the loopback or, without
+    /// one, by the first imported impulse response.
+    fn analysis_sample_rate(&self) -> Option<SampleRate> {
+        self.loopback
+            .as_ref()
+            .and_then(Loopback::loaded)
+            .map(raumklang_core::Loopback::sample_rate)
+            .or_else(|| {
+                self.measurements
+                    .loaded()
+                    .filter(|m| m.source == project::Source::Rew)
+                    .find_map(Measurement::signal)
+                    .map(|signal| signal.sample_rate())
+            })
+            .map(SampleRate::from)
+    }
+
+    fn check_sample_rates(&mut self) {
+        let Some(sample_rate) = self
+            .loopback
+            .as_ref()
+            .and_then(Loopback::loaded)
+            .map(raumklang_core::Loopback::sample_rate)
+        else {
+            return;
+        };
+
+        let ids: Vec<_> = self
+            .measurements
+            .loaded()
+            .filter(|m| m.signal().is_some_and(|s| s.sample_rate() != sample_rate))
+            .map(Measurement::id)
+            .collect();
+
+        if ids.is_empty() {
+            return;
+        }
+
+        match self.modal {
+            Modal::None | Modal::SampleRateMismatch { .. } => {
+                self.modal = Modal::SampleRateMismatch { sample_rate, ids };
+            }
+            _ => log::warn!(
+                "{} measurement(s) don't match the loopback's sample rate",
+                ids.len()
+            ),
+        }
+    }
+
+    fn set_time_shift(&mut self, id: measurement::Id, time_shift: isize) {
+        let Some(measurement) = self.measurements.get_mut(id) else {
+            return;
+        };
+
+        if measurement.time_shift == time_shift {
+            return;
+        }
+
+        measurement.time_shift = time_shift;
+
+        if let State::Analysing {
+            ref mut analyses, ..
+        } = self.state
+            && let Some(analysis) = analyses.get_mut(&id)
+        {
+            analysis.windowed_impulse_response = None;
+            analysis.frequency_response.state = ui::frequency_response::State::None;
+            analysis.spectral_decay.reset();
+            analysis.spectrogram.reset();
+        }
+    }
+
+    /// In headphone mode the first two measurements are treated as the left
+    /// and right channel of a coupler measurement.
+    /// Computes the L+R sum from the windowed impulse responses of both
+    /// channels, once they are available.
+    fn compute_stereo_sum(&mut self) -> Task<Message> {
+        let (
+            Some(frequency_response::StereoSum {
+                left: Some(left),
+                right: Some(right),
+                ..
+            }),
+            State::Analysing { analyses, .. },
+        ) = (&self.stereo_sum, &mut self.state)
+        else {
+            return Task::none();
+        };
+
+        let (left, right) = (*left, *right);
+
+        if self.window.is_none() {
+            return Task::none();
+        }
+
+        let window = analysis_window(self.window.as_ref(), self.gate);
+        let mut windowed = |id| {
+            let time_shift = self.measurements.get(id).map_or(0, |m| m.time_shift);
+            analyses
+                .get_mut(&id)?
+                .windowed_impulse_response(&window, time_shift)
+        };
+
+        let (Some(left), Some(right)) = (windowed(left), windowed(right)) else {
+            return Task::none();
+        };
+
+        Task::perform(
+            frequency_response::stereo_sum(
+                left,
+                right,
+                self.calibration.as_ref().map(|(_, curve)| curve.clone()),
+                self.smoothing.fraction(),
+            ),
+            Message::StereoSumComputed,
+        )
+    }
+
+    /// Simulates the crossover from the windowed impulse responses of both
+    /// ways, once they are available.
+    fn compute_crossover(&mut self) -> Task<Message> {
+        let (Some(crossover), State::Analysing { analyses, .. }) =
+            (&self.crossover, &mut self.state)
+        else {
+            return Task::none();
+        };
+
+        let (Some(low), Some(high)) = (crossover.low.measurement, crossover.high.measurement)
+        else {
+            return Task::none();
+        };
+
+        if self.window.is_none() {
+            return Task::none();
+        }
+
+        let window = analysis_window(self.window.as_ref(), self.gate);
+        let mut windowed = |id| {
+            let time_shift = self.measurements.get(id).map_or(0, |m| m.time_shift);
+            analyses
+                .get_mut(&id)?
+                .windowed_impulse_response(&window, time_shift)
+        };
+
+        let (Some(low), Some(high)) = (windowed(low), windowed(high)) else {
+            return Task::none();
+        };
+
+        Task::perform(
+            crossover::simulate(
+                (low, crossover.low.settings(crossover.filters_enabled)),
+                (high, crossover.high.settings(crossover.filters_enabled)),
+                crossover.region(),
+                self.calibration.as_ref().map(|(_, curve)| curve.clone()),
+                self.smoothing.fraction(),
+            ),
+            Message::CrossoverSimulated,
+        )
+    }
+
+    fn update_channel_difference(&mut self) {
+        self.channel_difference = None;
+
+        if self.mode != project::Mode::Headphone {
+            return;
+        }
+
+        let State::Analysing { ref analyses, .. } = self.state else {
+            return;
+        };
+
+        let mut curves = self
+            .measurements
+            .loaded()
+            .map(Measurement::id)
+            .map(|id| analyses.get(&id).and_then(|a| a.frequency_response.curve()));
+
+        let (Some(Some(left)), Some(Some(right))) = (curves.next(), curves.next()) else {
+            return;
+        };
+
+        self.channel_difference = Some(ui::Curve::new(
+            CHANNEL_DIFFERENCE_COLOR,
+            ui::curve::difference(&left.0, &right.0),
+        ));
+    }
+
+    /// Whether a frequency response is drawn, it is neither muted nor hidden
+    /// by another one, that is soloed.
+    fn is_frequency_response_shown(
+        &self,
+        id: &measurement::Id,
+        fr: &ui::FrequencyResponse,
+    ) -> bool {
+        fr.is_shown && self.fr_solo.is_none_or(|solo| solo == *id)
+    }
+
+    /// Thickens the hovered frequency response and dims the others.
+    fn update_emphasis(&mut self) {
+        let highlighted = self.fr_hovered.filter(|id| {
+            let State::Analysing { ref analyses, .. } = self.state else {
+                return false;
+            };
+
+            analyses
+                .get(id)
+                .is_some_and(|a| self.is_frequency_response_shown(id, &a.frequency_response))
+        });
+
+        let State::Analysing {
+            ref mut analyses, ..
+        } = self.state
+        else {
+            return;
+        };
+
+        for (id, analysis) in analyses.iter_mut() {
+            analysis.frequency_response_mut().emphasis = match highlighted {
+                None => ui::frequency_response::Emphasis::Normal,
+                Some(highlighted) if highlighted == *id => {
+                    ui::frequency_response::Emphasis::Highlighted
+                }
+                Some(_) => ui::frequency_response::Emphasis::Dimmed,
+            };
+        }
+    }
+
+    /// Shades the spread of the shown frequency responses.
+    fn update_spread(&mut self) {
+        let State::Analysing { ref analyses, .. } = self.state else {
+            self.spread_band = None;
+            return;
+        };
+
+        let curves: Vec<_> = analyses
+            .iter()
+            .filter(|(id, a)| self.is_frequency_response_shown(id, &a.frequency_response))
+            .filter_map(|(_, a)| a.frequency_response.curve())
+            .collect();
+
+        self.spread_band = self.spread.band(SPREAD_COLOR, &curves);
+    }
+
+    /// Compares the first shown frequency response to the target curve.
+    fn update_target_error(&mut self) {
+        self.target_error = None;
+        self.correction_envelope = None;
+
+        let (Some((target, _)), State::Analysing { analyses, .. }) = (&self.target, &self.state)
+        else {
+            return;
+        };
+
+        let first_shown = self.measurements.loaded().find_map(|measurement| {
+            let id = measurement.id();
+            let fr = &analyses.get(&id)?.frequency_response;
+            let curve = fr
+                .curve()
+                .filter(|_| self.is_frequency_response_shown(&id, fr))?;
+
+            Some((measurement.name.clone(), curve))
+        });
+
+        let Some((name, curve)) = first_shown else {
+            return;
+        };
+
+        self.correction_envelope = Some(ui::curve::correction_envelope(
+            TARGET_COLOR,
+            curve,
+            target,
+            &self.correction_limits,
+        ));
+        self.target_error = Some((
+            name,
+            ui::curve::target_error(TARGET_ERROR_COLOR, curve, target),
+        ));
+    }
+
+    /// Recomputes all frequency responses with the current gate, the previous
+    /// results are kept until the new ones arrive, to animate the change.
+    /// Spectral decays and spectrograms are computed again, when shown.
+    fn recompute_gated_frequency_responses(&mut self) -> Task<Message> {
+        let State::Analysing {
+            ref mut analyses,
+            active_tab: Tab::FrequencyResponses { .. },
+            ..
+        } = self.state
+        else {
+            return Task::none();
+        };
+
+        if self.window.is_none() {
+            return Task::none();
+        }
+
+        let window = analysis_window(self.window.as_ref(), self.gate);
+
+        Task::batch(analyses.iter_mut().filter_map(|(id, analysis)| {
+            let time_shift = self.measurements.get(*id).map_or(0, |m| m.time_shift);
+
+            analysis.windowed_impulse_response = None;
+            analysis.spectral_decay.reset();
+            analysis.spectrogram.reset();
+
+            let ir = analysis.windowed_impulse_response(&window, time_shift)?;
+
+            Some(Task::perform(
+                data::frequency_response::compute(scheduler::Subject::new(id), ir),
+                Message::FrequencyResponseComputed.with(*id),
+            ))
+        }))
+    }
+
+    /// Recomputes the already computed frequency responses, e.g. after the
+    /// microphone calibration changed.
+    fn recompute_frequency_responses(&mut self) -> Task<Message> {
+        let State::Analysing {
+            ref mut analyses, ..
+        } = self.state
+        else {
+            return Task::none();
+        };
+
+        if self.window.is_none() {
+            return Task::none();
+        }
+
+        let window = analysis_window(self.window.as_ref(), self.gate);
+
+        Task::batch(
+            analyses
+                .iter_mut()
+                .filter(|(_, analysis)| analysis.frequency_response.result().is_some())
+                .filter_map(|(id, analysis)| {
+                    let time_shift = self.measurements.get(*id).map_or(0, |m| m.time_shift);
+                    let ir = analysis.windowed_impulse_response(&window, time_shift)?;
+
+                    Some(Task::perform(
+                        data::frequency_response::compute(scheduler::Subject::new(id), ir),
+                        Message::FrequencyResponseComputed.with(*id),
+                    ))
+                }),
+        )
+    }
+
+    /// Summary of the deviation from the target curve.
+    fn target_controls(&self) -> Option<Element<'_, Message>> {
+        self.target.as_ref()?;
+
+        let summary = match &self.target_error {
+            Some((name, error)) => {
+                let max = error.max.map_or(String::new(), |max| {
+                    format!(
+                        ", max {} at {}",
+                        unit::signed_level(max.y, 1),
+                        format_frequency_label(max.x)
+                    )
+                });
+
+                let bands: Vec<_> = error
+                    .bands
+                    .iter()
+                    .map(|(center, rms)| format!("{}: {rms:.1}", format_frequency_label(*center)))
+                    .collect();
+
+                column![
+                    text!("{name} vs. target{max}").color(TARGET_ERROR_COLOR),
+                    text!("RMS error in dB: {}", bands.join(" · ")).size(12),
+                ]
+                .spacing(2)
+            }
+            None => column![text("Target: please select a frequency response.")],
+        };
+
+        let limits = self.correction_limits;
+        let correction = row![
+            text("Max boost").size(12),
+            slider(0.0..=24.0, limits.max_boost, move |max_boost| {
+                Message::CorrectionLimitsChanged(ui::curve::CorrectionLimits {
+                    max_boost,
+                    ..limits
+                })
+            })
+            .step(0.5)
+            .width(Length::Fill),
+            text(unit::signed_level(limits.max_boost, 1)).size(12),
+            text("Max cut").size(12),
+            slider(0.0..=24.0, limits.max_cut, move |max_cut| {
+                Message::CorrectionLimitsChanged(ui::curve::CorrectionLimits { max_cut, ..limits })
+            })
+            .step(0.5)
+            .width(Length::Fill),
+            text(unit::signed_level(-limits.max_cut, 1)).size(12),
+            text("Up to").size(12),
+            // logarithmic, in octaves above 20 Hz
+            slider(
+                0.0..=10.0,
+                (limits.upper_frequency / 20.0).log2(),
+                move |octaves| {
+                    Message::CorrectionLimitsChanged(ui::curve::CorrectionLimits {
+                        upper_frequency: 20.0 * 2f32.powf(octaves),
+                        ..limits
+                    })
+                }
+            )
+            .step(1.0 / 6.0)
+            .width(Length::Fill),
+            text(format_frequency_label(limits.upper_frequency)).size(12),
+        ]
+        .spacing(8)
+        .align_y(Center);
+
+        Some(
+            column![
+                row![
+                    summary,
+                    space::horizontal(),
+                    button(text("Remove target").size(12))
+                        .style(button::secondary)
+                        .on_press(Message::TargetCurveRemoved),
+                ]
+                .spacing(10)
+                .align_y(Center),
+                correction,
+            ]
+            .spacing(6)
+            .into(),
+        )
+    }
+
+    fn stereo_sum_controls(&self) -> Option<Element<'_, Message>> {
+        let sum = self.stereo_sum.as_ref()?;
+
+        let operands: Vec<_> = self
+            .measurements
+            .loaded()
+            .map(|m| operation::Operand {
+                id: m.id(),
+                name: m.name.clone(),
+            })
+            .collect();
+
+        let channel = |label, id: Option<measurement::Id>, channel| {
+            let selected = id
+                .and_then(|id| operands.iter().find(|operand| operand.id == id))
+                .cloned();
+
+            row![
+                text(label),
+                pick_list(selected, operands.clone(), operation::Operand::to_string)
+                    .placeholder("Measurement ...")
+                    .on_select(move |operand| Message::StereoSumChannelSelected(channel, operand)),
+            ]
+            .spacing(6)
+            .align_y(Center)
+        };
+
+        let status = match sum.curve {
+            Some(_) => text("L+R sum"),
+            None => text("Waiting for both impulse responses ..."),
+        };
+
+        Some(
+            row![
+                status.color(STEREO_SUM_COLOR),
+                channel("L", sum.left, frequency_response::Channel::Left),
+                channel("R", sum.right, frequency_response::Channel::Right),
+            ]
+            .spacing(10)
+            .align_y(Center)
+            .into(),
+        )
+    }
+
+    fn gate_controls(&self) -> Option<Element<'_, Message>> {
+        let window = self.window.as_ref()?;
+
+        let max = window.gate().as_secs_f32() * 1000.0;
+        let gate = self.gate.map_or(max, |gate| gate.as_secs_f32() * 1000.0);
+
+        Some(
+            row![
+                text("Gate"),
+                help::info(help::Topic::Gating),
+                slider(1.0..=max.max(1.0), gate, Message::GateChanged)
+                    .step(1.0)
+                    .width(Length::Fill),
+                text(unit::duration_ms(gate)),
+                button("Reset")
+                    .style(button::secondary)
+                    .on_press_maybe(self.gate.map(|_| Message::ResetGate)),
+            ]
+            .spacing(10)
+            .align_y(Center)
+            .into(),
+        )
+    }
 
-        Task::none()
+    /// Closes the recompute progress once every analysis has been computed.
+    fn finish_recompute(&mut self, id: measurement::Id, stage: recompute::Stage) {
+        let Modal::Recompute(progress) = &mut self.modal else {
+            return;
+        };
+
+        progress.finish(id, stage);
+
+        if progress.is_done() {
+            self.modal = Modal::None;
+        }
     }
 
     pub fn view<'a>(&'a self, recent_projects: &'a RecentProjects) -> Element<'a, Message> {
@@ -1050,6 +3766,17 @@ impl Main {
                 .padding(5)
             };
 
+            let mode = container(
+                pick_list(
+                    Some(&self.mode),
+                    project::Mode::ALL,
+                    project::Mode::to_string,
+                )
+                .text_size(20)
+                .on_select(Message::ChangeMode),
+            )
+            .padding(5);
+
             let tab = |s, is_active, id: Option<_>| {
                 button(text(s).size(20))
                     .padding(10)
@@ -1098,13 +3825,67 @@ impl Main {
                     matches!(active_tab, Some(Tab::Spectrograms)),
                     active_tab.is_some().then_some(tab::Id::Spectrograms)
                 ),
+                tab(
+                    "Crossover",
+                    matches!(active_tab, Some(Tab::Crossover)),
+                    active_tab.is_some().then_some(tab::Id::Crossover)
+                ),
             ]
             .spacing(5)
             .align_y(Center);
 
-            container(row![project_menu, tabs,].align_y(Center))
-                .width(Length::Fill)
-                .style(container::dark)
+            let operations_menu = container(
+                pick_list(
+                    None::<operation::Kind>,
+                    operation::Kind::ALL,
+                    operation::Kind::to_string,
+                )
+                .text_size(20)
+                .placeholder("Operations")
+                .on_select(Message::OpenOperation),
+            )
+            .padding(5);
+
+            let recompute = container(
+                button("Recompute all")
+                    .style(button::secondary)
+                    .on_press_maybe(active_tab.is_some().then_some(Message::RecomputeAll)),
+            )
+            .padding(5);
+
+            let split = container(
+                button(if self.split.is_some() {
+                    "Close split"
+                } else {
+                    "Split view"
+                })
+                .style(button::secondary)
+                .on_press_maybe(active_tab.is_some().then_some(Message::ToggleSplit)),
+            )
+            .padding(5);
+
+            let session_log = container(
+                button("Log")
+                    .style(button::secondary)
+                    .on_press(Message::OpenSessionLog),
+            )
+            .padding(5);
+
+            container(
+                row![
+                    project_menu,
+                    operations_menu,
+                    recompute,
+                    mode,
+                    tabs,
+                    space::horizontal(),
+                    split,
+                    session_log
+                ]
+                .align_y(Center),
+            )
+            .width(Length::Fill)
+            .style(container::dark)
         };
 
         let content = {
@@ -1133,33 +3914,128 @@ impl Main {
                     Tab::Spectrograms => {
                         self.spectrogram_tab(selected, analyses, &self.spectrogram)
                     }
+                    Tab::Crossover => self.crossover_tab(),
                 },
             }
         };
 
+        let content = match (&self.split, &self.state) {
+            (Some(split), State::Analysing { analyses, .. }) => row![
+                container(content).width(Length::FillPortion(1)),
+                container(
+                    split
+                        .view(
+                            &self.measurements,
+                            analyses,
+                            &self.fr_state,
+                            self.spectrogram.normalization,
+                            self.spectrogram.level_offset,
+                            self.chart_preferences.colormap,
+                            self.chart_preferences.time_unit,
+                        )
+                        .map(Message::Split)
+                )
+                .width(Length::FillPortion(1)),
+            ]
+            .spacing(10)
+            .into(),
+            _ => content,
+        };
+
         let content = container(column![header, container(content).padding(10)]);
 
-        match &self.modal {
+        let view: Element<'a, Message> = match &self.modal {
             Modal::None => content.into(),
             Modal::PendingWindow { .. } => {
                 modal(content, modal::pending_window().map(Message::PendingWindow))
             }
+            Modal::DuplicateMeasurement { path, existing } => {
+                let name = self
+                    .measurements
+                    .get(*existing)
+                    .map_or("", |measurement| measurement.name.as_str());
+
+                modal(
+                    content,
+                    modal::duplicate_measurement(name, path).map(Message::DuplicateMeasurement),
+                )
+            }
+            Modal::SampleRateMismatch { sample_rate, ids } => {
+                let measurements = ids.iter().filter_map(|id| {
+                    let measurement = self.measurements.get(*id)?;
+                    let rate = measurement.signal()?.sample_rate();
+
+                    Some((measurement.name.as_str(), rate))
+                });
+
+                modal(
+                    content,
+                    modal::sample_rate_mismatch(*sample_rate, measurements)
+                        .map(Message::SampleRateMismatch),
+                )
+            }
             Modal::SpectralDecayConfig(config) => {
                 modal(content, config.view().map(Message::SpectralDecayConfig))
             }
             Modal::SpectrogramConfig(config) => {
                 modal(content, config.view().map(Message::SpectrogramConfig))
             }
+            Modal::Operation(view) => modal(content, view.view().map(Message::Operation)),
+            Modal::Auralization(view) => modal(content, view.view().map(Message::Auralization)),
+            Modal::Recompute(progress) => modal(content, progress.view().map(Message::Recompute)),
             Modal::SaveProjectDialog(dialog) => {
                 modal(content, dialog.view().map(Message::ProjectSaveDialog))
             }
             Modal::Recording(recording) => modal(content, recording.view().map(Message::Recording)),
+            Modal::SessionLog(view) => modal(content, view.view().map(Message::SessionLog)),
+            Modal::ChannelCheck(view) => modal(content, view.view().map(Message::ChannelCheck)),
+            Modal::MovingMic(view) => modal(content, view.view().map(Message::MovingMic)),
+            Modal::SubAlignment(view) => modal(content, view.view().map(Message::SubAlignment)),
+            Modal::SplMeter(view) => modal(content, view.view().map(Message::SplMeter)),
+            Modal::Rta(view) => modal(content, view.view().map(Message::Rta)),
+            Modal::TransferFunction(view) => {
+                modal(content, view.view().map(Message::TransferFunction))
+            }
+            Modal::ExportHook(view) => modal(content, view.view().map(Message::ExportHook)),
+            Modal::RemoteControl(view) => modal(content, view.view().map(Message::RemoteControl)),
+            Modal::Wizard => match &self.wizard {
+                Some(wizard) => modal(content, wizard.view().map(Message::Wizard)),
+                None => content.into(),
+            },
             // TODO: make modal closable by clicking into the free space
             Modal::OpenRecentProject => modal(
                 content,
                 modal::load_recent_project(recent_projects, Message::LoadRecentProject),
             ),
+        };
+
+        if !self.modal.plays_audio() {
+            return view;
         }
+
+        // the global stop stays on top of the toolbar, above the dialog
+        let stop = container(
+            button("Stop audio")
+                .style(button::danger)
+                .on_press(Message::StopAudio),
+        )
+        .padding(5)
+        .align_right(Length::Fill);
+
+        stack![view, stop].into()
+    }
+
+    fn filtered_measurements(&self) -> impl Iterator<Item = &Measurement> + Clone {
+        self.measurements
+            .iter()
+            .filter(|measurement| measurement.matches(&self.sidebar_filter))
+    }
+
+    fn sidebar_filter(&self) -> Element<'_, Message> {
+        text_input("Filter by name or path ...", &self.sidebar_filter)
+            .on_input(Message::SidebarFilterChanged)
+            .size(14)
+            .into()
     }
 
     fn measurements_tab<'a>(&'a self) -> Element<'a, Message> {
@@ -1187,6 +4063,11 @@ impl Main {
             .into();
         }
 
+        let analyses = match &self.state {
+            State::Collecting => None,
+            State::Analysing { analyses, .. } => Some(analyses),
+        };
+
         let sidebar = {
             let loopback = Category::new("Loopback")
                 .push_button(sidebar::button(icon::plus()).on_press(Message::LoadLoopback))
@@ -1199,22 +4080,70 @@ impl Main {
                     loopback.view(active).map(Message::Measurement)
                 }));
 
+            let loopback_sample_rate = self
+                .loopback
+                .as_ref()
+                .and_then(Loopback::loaded)
+                .map(raumklang_core::Loopback::sample_rate);
+
             let measurements = Category::new("Measurements")
                 .push_button(sidebar::button(icon::plus()).on_press(Message::LoadMeasurement))
+                .push_button(sidebar::button(icon::download()).on_press(Message::ImportRew))
                 .push_button(
                     sidebar::button(icon::record())
                         .on_press(Message::StartRecording(recording::Kind::Measurement)),
                 )
-                .extend_entries(self.measurements.iter().map(|measurement| {
+                .extend_entries(self.filtered_measurements().map(|measurement| {
                     let active =
                         self.selected == Some(measurement::Selected::Measurement(measurement.id()));
-                    measurement.view(active).map(Message::Measurement)
+                    let analysis = analyses.and_then(|a| a.get(&measurement.id()));
+                    measurement
+                        .view(active, quality(measurement, analysis), loopback_sample_rate)
+                        .map(Message::Measurement)
+                }))
+                .extend_entries(self.loading.iter().map(|(path, fraction)| {
+                    let name = path
+                        .file_name()
+                        .map(|name| name.to_string_lossy())
+                        .unwrap_or_default();
+
+                    column![
+                        text(name).size(12),
+                        progress_bar(0.0..=1.0, *fraction).girth(4)
+                    ]
+                    .spacing(2)
+                    .padding(5)
+                    .into()
                 }));
 
+            let watch_folder = match &self.watch_folder {
+                Some(watch_folder) => row![
+                    text!("Watching {}", watch_folder.path().display())
+                        .size(12)
+                        .width(Length::Fill),
+                    button("Stop")
+                        .style(button::secondary)
+                        .on_press(Message::StopWatchFolder)
+                ]
+                .spacing(5)
+                .align_y(Center),
+                None => row![
+                    button("Watch folder ...")
+                        .style(button::secondary)
+                        .on_press(Message::WatchFolder)
+                ],
+            };
+
             container(scrollable(
-                column![loopback, rule::horizontal(1), measurements]
-                    .spacing(10)
-                    .padding(10),
+                column![
+                    loopback,
+                    rule::horizontal(1),
+                    self.sidebar_filter(),
+                    measurements,
+                    watch_folder
+                ]
+                .spacing(10)
+                .padding(10),
             ))
             .style(|theme| {
                 container::rounded_box(theme)
@@ -1256,8 +4185,45 @@ impl Main {
                         .and_then(Measurement::signal)
                         .map(AsRef::as_ref),
                 }) {
-                chart::waveform(measurement, &self.signal_cache, self.zoom, self.offset)
-                    .map(Message::MeasurementChart)
+                let preferences = self.chart_preferences;
+
+                let controls = row![
+                    checkbox(preferences.rms_envelope)
+                        .label("RMS envelope")
+                        .on_toggle(Message::RmsEnvelopeToggled),
+                    space::horizontal(),
+                    pick_list(
+                        Some(preferences.amplitude_unit),
+                        &data::chart::AmplitudeUnit::ALL[..],
+                        data::chart::AmplitudeUnit::to_string,
+                    )
+                    .on_select(Message::WaveformAmplitudeUnitChanged),
+                    time_unit_controls(preferences.time_unit)
+                ]
+                .spacing(10)
+                .align_y(Center);
+
+                let chart = chart::waveform(
+                    measurement,
+                    &self.signal_cache,
+                    self.zoom,
+                    self.offset,
+                    preferences.time_unit,
+                    preferences.amplitude_unit,
+                    preferences.rms_envelope,
+                )
+                .map(Message::MeasurementChart);
+
+                let info = match self.selected {
+                    Some(measurement::Selected::Measurement(id)) => self
+                        .measurements
+                        .get(id)
+                        .and_then(Measurement::info_panel)
+                        .map(|panel| panel.map(Message::Measurement)),
+                    _ => None,
+                };
+
+                column![controls, chart].push(info).spacing(10).into()
             } else {
                 welcome_text(text("Select a signal to view its data."))
             };
@@ -1283,17 +4249,21 @@ impl Main {
         let sidebar = {
             let header = sidebar::header("Impulse Responses");
 
-            let entries = self.measurements.iter().flat_map(|measurement| {
+            let entries = self.filtered_measurements().flat_map(|measurement| {
                 let active = selected == Some(measurement.id());
                 let signal = measurement.signal()?;
-                let progress = analyses
-                    .get(&measurement.id())
-                    .map(|a| a.impulse_response.progress());
+                let analysis = analyses.get(&measurement.id());
+                let progress = analysis.map(|a| a.impulse_response.progress());
+                let drift = analysis
+                    .and_then(Analysis::impulse_response)
+                    .and_then(|ir| ir.drift);
 
                 let entry = ui::impulse_response::view(
                     &measurement.name,
                     signal.modified,
                     progress,
+                    drift,
+                    quality(measurement, analysis),
                     active,
                 )
                 .map(Message::ImpulseResponse.with(measurement.id()));
@@ -1301,25 +4271,145 @@ impl Main {
                 Some(entry)
             });
 
-            container(column![header, scrollable(column(entries))].spacing(6))
-                .padding(6)
-                .style(|theme| {
-                    container::rounded_box(theme)
-                        .background(theme.extended_palette().background.weakest.color)
-                })
+            container(
+                column![header, self.sidebar_filter(), scrollable(column(entries))].spacing(6),
+            )
+            .padding(6)
+            .style(|theme| {
+                container::rounded_box(theme)
+                    .background(theme.extended_palette().background.weakest.color)
+            })
         };
 
         let content = {
             let placeholder = center(text("Impulse response not computed, yet."));
 
+            let comparison = chart
+                .comparison
+                .and_then(|id| analyses.get(&id))
+                .and_then(Analysis::impulse_response);
+
+            let candidates = self
+                .measurements
+                .loaded()
+                .filter(|measurement| Some(measurement.id()) != selected)
+                .map(|measurement| {
+                    impulse_response::Comparison::Measurement(
+                        measurement.id(),
+                        measurement.name.clone(),
+                    )
+                })
+                .collect();
+
             selected
                 .as_ref()
-                .and_then(|id| analyses.get(id))
-                .and_then(Analysis::impulse_response)
-                .map(|impulse_response| {
-                    chart
-                        .view(impulse_response, window)
-                        .map(Message::ImpulseResponseChart)
+                .and_then(|id| Some((*id, analyses.get(id)?)))
+                .and_then(|(id, analysis)| Some((id, analysis, analysis.impulse_response()?)))
+                .map(|(id, analysis, impulse_response)| {
+                    // the denoised impulse response is compared with the original
+                    let denoised = match &analysis.denoised {
+                        Some(ui::impulse_response::Denoised::Computed(denoised)) => {
+                            Some(denoised.as_ref())
+                        }
+                        _ => None,
+                    };
+
+                    let chart = match denoised {
+                        Some(denoised) => {
+                            chart.view(denoised, window, Some(impulse_response), candidates)
+                        }
+                        None => chart.view(impulse_response, window, comparison, candidates),
+                    }
+                    .map(Message::ImpulseResponseChart);
+
+                    let denoise = row![
+                        checkbox(analysis.denoised.is_some())
+                            .label("Denoise")
+                            .on_toggle(Message::DenoiseToggled.with(id)),
+                    ]
+                    .push(match &analysis.denoised {
+                        None => None,
+                        Some(ui::impulse_response::Denoised::Computing) => {
+                            Some(text("Estimating the noise ...").size(12))
+                        }
+                        Some(ui::impulse_response::Denoised::Failed) => Some(
+                            text("Not enough silence in front of the measurement signal")
+                                .size(12)
+                                .style(text::danger),
+                        ),
+                        Some(ui::impulse_response::Denoised::Computed(denoised)) => Some(
+                            text(decay_times_label(
+                                &impulse_response.decay_times,
+                                &denoised.decay_times,
+                            ))
+                            .size(12),
+                        ),
+                    })
+                    .spacing(10)
+                    .align_y(Center);
+
+                    let time_shift = self.measurements.get(id).map_or(0, |m| m.time_shift);
+                    let tenth_ms =
+                        (f32::from(impulse_response.sample_rate) / 10_000.0).round() as isize;
+
+                    let nudge = |label, delta: isize| {
+                        button(text(label).size(12))
+                            .style(button::secondary)
+                            .on_press(Message::TimeShiftChanged(id, time_shift + delta))
+                    };
+
+                    let time_shift_ms =
+                        time_shift as f32 / f32::from(impulse_response.sample_rate) * 1000.0;
+
+                    let controls = row![
+                        text("Time shift"),
+                        nudge("-0.1 ms", -tenth_ms),
+                        nudge("-1", -1),
+                        number_input(
+                            &self.time_shift_input,
+                            self.time_shift_input.parse::<isize>().err(),
+                            Message::TimeShiftInput.with(id),
+                        ),
+                        nudge("+1", 1),
+                        nudge("+0.1 ms", tenth_ms),
+                        text!("samples ({} ms)", unit::signed_number(time_shift_ms, 2)),
+                    ]
+                    .spacing(6)
+                    .align_y(Center);
+
+                    let deconvolution = row![
+                        text("Deconvolution"),
+                        pick_list(
+                            Some(&self.deconvolution),
+                            data::impulse_response::Method::all(self.measurement_config.signal),
+                            data::impulse_response::Method::to_string,
+                        )
+                        .on_select(Message::DeconvolutionMethodChanged),
+                        help::info(help::Topic::Regularization),
+                    ]
+                    .spacing(6)
+                    .align_y(Center);
+
+                    Element::from(
+                        column![
+                            self.window_preset_controls(),
+                            row![
+                                controls,
+                                space::horizontal(),
+                                button(text("Auralize ...").size(12))
+                                    .style(button::secondary)
+                                    .on_press(Message::OpenAuralization(id)),
+                                deconvolution
+                            ]
+                            .spacing(10)
+                            .align_y(Center),
+                            row![denoise, space::horizontal(), self.preprocessing_controls()]
+                                .spacing(10)
+                                .align_y(Center),
+                            chart
+                        ]
+                        .spacing(8),
+                    )
                 })
                 .unwrap_or(placeholder.into())
         };
@@ -1334,6 +4424,38 @@ impl Main {
         .into()
     }
 
+    fn window_preset_controls(&self) -> Element<'_, Message> {
+        let name = self.window_preset_name.trim();
+
+        row![
+            text("Window"),
+            help::info(help::Topic::Window),
+            pick_list(
+                None::<&window::Preset>,
+                self.window_presets.as_slice(),
+                window::Preset::to_string
+            )
+            .placeholder("Presets")
+            .on_select(Message::WindowPresetSelected),
+            text_input("Preset name", &self.window_preset_name)
+                .on_input(Message::WindowPresetNameChanged)
+                .on_submit(Message::SaveWindowPreset)
+                .width(160),
+            button(text("Save").size(12))
+                .style(button::secondary)
+                .on_press_maybe((!name.is_empty()).then_some(Message::SaveWindowPreset)),
+            button(text("Import ...").size(12))
+                .style(button::secondary)
+                .on_press(Message::ImportWindowPreset),
+            button(text("Export ...").size(12))
+                .style(button::secondary)
+                .on_press(Message::ExportWindowPreset),
+        ]
+        .spacing(6)
+        .align_y(Center)
+        .into()
+    }
+
     fn frequency_responses_tab<'a>(
         &'a self,
         _cache: &'a canvas::Cache,
@@ -1342,43 +4464,159 @@ impl Main {
         let sidebar = {
             let header = sidebar::header("Frequency Responses");
 
-            let entries = self.measurements.iter().flat_map(|measurement| {
+            let entries = self.filtered_measurements().flat_map(|measurement| {
                 let analysis = analyses.get(&measurement.id())?;
 
+                let id = measurement.id();
                 let content = analysis.frequency_response.view(
                     &measurement.name,
-                    Message::FrequencyResponseToggled.with(measurement.id()),
+                    self.fr_solo == Some(id),
+                    Message::FrequencyResponseToggled.with(id),
+                    Message::FrequencyResponseSoloed(id),
                 );
 
-                Some(content)
+                Some(
+                    mouse_area(content)
+                        .on_enter(Message::FrequencyResponseHovered(Some(id)))
+                        .on_exit(Message::FrequencyResponseHovered(None))
+                        .into(),
+                )
             });
 
-            container(column![header, scrollable(column(entries).spacing(6))].spacing(6))
-                .padding(6)
-                .style(|theme| {
-                    container::rounded_box(theme)
-                        .background(theme.extended_palette().background.weakest.color)
-                })
+            container(
+                column![
+                    header,
+                    self.sidebar_filter(),
+                    scrollable(column(entries).spacing(6))
+                ]
+                .spacing(6),
+            )
+            .padding(6)
+            .style(|theme| {
+                container::rounded_box(theme)
+                    .background(theme.extended_palette().background.weakest.color)
+            })
         };
 
         let header = {
-            row![
+            let exportable = |is_exportable: fn(&Analysis) -> bool| -> Vec<_> {
+                self.measurements
+                    .loaded()
+                    .filter(|m| analyses.get(&m.id()).is_some_and(is_exportable))
+                    .map(|m| operation::Operand {
+                        id: m.id(),
+                        name: m.name.clone(),
+                    })
+                    .collect()
+            };
+
+            let header = row![
                 pick_list(
                     Some(&self.smoothing),
                     frequency_response::Smoothing::ALL,
                     frequency_response::Smoothing::to_string,
                 )
-                .on_select(Message::ChangeSmoothing)
+                .on_select(Message::ChangeSmoothing),
+                help::info(help::Topic::Smoothing),
+                pick_list(
+                    Some(&self.fr_display),
+                    frequency_response::Display::ALL,
+                    frequency_response::Display::to_string,
+                )
+                .on_select(Message::FrequencyResponseDisplayChanged),
+                checkbox(self.show_harmonics)
+                    .label("Harmonics")
+                    .on_toggle(Message::ShowHarmonicsToggled),
+                tooltip(
+                    checkbox(self.normalize_playback_level)
+                        .label("Normalize level")
+                        .on_toggle(Message::NormalizePlaybackLevelToggled),
+                    container(text(
+                        "Compensates the playback volume and output trim of recorded measurements."
+                    ))
+                    .padding(5)
+                    .style(container::bordered_box),
+                    tooltip::Position::Bottom,
+                ),
+                checkbox(self.stereo_sum.is_some())
+                    .label("L+R sum")
+                    .on_toggle(Message::StereoSumToggled),
+                pick_list(
+                    Some(&self.spread),
+                    frequency_response::Spread::ALL,
+                    frequency_response::Spread::to_string,
+                )
+                .on_select(Message::SpreadChanged),
+                space::horizontal(),
+                pick_list(
+                    Some(&self.export_grid),
+                    data::frequency_response::Grid::ALL,
+                    data::frequency_response::Grid::to_string,
+                )
+                .on_select(Message::ExportGridChanged),
+                pick_list(
+                    None::<&operation::Operand>,
+                    exportable(|a| a.windowed_impulse_response.is_some()),
+                    operation::Operand::to_string
+                )
+                .placeholder("Export windowed IR ...")
+                .on_select(Message::ExportWindowedImpulseResponse),
+                pick_list(
+                    None::<&operation::Operand>,
+                    exportable(|a| a.frequency_response.result().is_some()),
+                    operation::Operand::to_string
+                )
+                .placeholder("Export ...")
+                .on_select(Message::ExportFrequencyResponse),
             ]
+            .spacing(10);
+
+            let header = if self.moving_mic.is_some() {
+                header.push(
+                    button("Remove moving mic")
+                        .style(button::secondary)
+                        .on_press(Message::MovingMicRemoved),
+                )
+            } else {
+                header
+            };
+
+            let header = header.push(
+                button("Load target curve ...")
+                    .style(button::secondary)
+                    .on_press(Message::LoadTargetCurve),
+            );
+
+            if self.mode == project::Mode::Headphone {
+                header.push(
+                    button("Load compensation curve ...").on_press(Message::LoadCompensationCurve),
+                )
+            } else {
+                header
+            }
         };
 
-        let frequency_responses = analyses.values().map(|a| &a.frequency_response);
-        let chart_needed = frequency_responses
-            .clone()
-            .any(|fr| fr.result().is_some() && fr.is_shown);
+        let frequency_responses = analyses
+            .iter()
+            .filter(|(id, a)| self.is_frequency_response_shown(id, &a.frequency_response))
+            .map(|(_, a)| &a.frequency_response);
+        let chart_needed = self.moving_mic.is_some()
+            || frequency_responses.clone().any(|fr| fr.result().is_some());
 
         let content = if chart_needed {
-            let chart = iced_aksel::Chart::new(&self.fr_state)
+            let curves: Vec<_> = frequency_responses
+                .clone()
+                .filter_map(|fr| fr.curve())
+                .collect();
+            let show_harmonics = self.show_harmonics;
+            let shows_phase = self.fr_display.shows_phase();
+            let (state, y_axis_id) = if shows_phase {
+                (&self.fr_phase_state, PHASE_AXIS_ID)
+            } else {
+                (&self.fr_state, DB_AXIS_ID)
+            };
+
+            let chart = iced_aksel::Chart::new(state)
                 .style(Box::new(|theme| {
                     let mut base = iced_aksel::style::default(theme);
                     let palette = theme.extended_palette();
@@ -1390,21 +4628,64 @@ impl Main {
 
                     base
                 }))
-                .marker(&FREQ_AXIS_ID, MarkerPosition::Cursor, |ctx| {
-                    Some(ctx.marker(format_frequency_label(ctx.value)))
-                })
-                .marker(&DB_AXIS_ID, MarkerPosition::Cursor, |ctx| {
-                    Some(ctx.marker(format_db_label(ctx.value)))
+                .marker(&FREQ_AXIS_ID, MarkerPosition::Cursor, move |ctx| {
+                    let label = format_frequency_label(ctx.value);
+
+                    Some(ctx.marker(if show_harmonics {
+                        format!("{label}\n{}", harmonics_label(ctx.value, &curves))
+                    } else {
+                        label
+                    }))
                 })
+                .marker(
+                    if shows_phase {
+                        &PHASE_AXIS_ID
+                    } else {
+                        &DB_AXIS_ID
+                    },
+                    MarkerPosition::Cursor,
+                    move |ctx| {
+                        Some(ctx.marker(if shows_phase {
+                            format_phase_label(ctx.value)
+                        } else {
+                            format_db_label(ctx.value)
+                        }))
+                    },
+                )
                 .on_scroll(frequency_response::Message::OnPlotScroll)
                 .on_drag(frequency_response::Message::OnPlotDrag);
 
-            let chart = frequency_responses
-                .filter(|fr| fr.is_shown)
-                .fold(chart, |chart, fr| {
-                    chart.plot_data(fr, FREQ_AXIS_ID, DB_AXIS_ID)
+            // behind the frequency responses, like the curves below they
+            // hold levels only and are left out for the phase
+            let chart = [self.spread_band.as_ref(), self.correction_envelope.as_ref()]
+                .into_iter()
+                .flatten()
+                .filter(|_| !shows_phase)
+                .fold(chart, |chart, band| {
+                    chart.plot_data(band, FREQ_AXIS_ID, DB_AXIS_ID)
                 });
 
+            let chart = frequency_responses.fold(chart, |chart, fr| {
+                chart.plot_data(fr, FREQ_AXIS_ID, y_axis_id)
+            });
+
+            let stereo_sum = self.stereo_sum.as_ref().and_then(|sum| sum.curve.as_ref());
+
+            let chart = [
+                self.compensation.as_ref(),
+                self.target.as_ref().map(|(_, curve)| curve),
+                self.target_error.as_ref().map(|(_, error)| &error.curve),
+                self.channel_difference.as_ref(),
+                self.moving_mic.as_ref(),
+                stereo_sum,
+            ]
+            .into_iter()
+            .flatten()
+            .filter(|_| !shows_phase)
+            .fold(chart, |chart, curve| {
+                chart.plot_data(curve, FREQ_AXIS_ID, DB_AXIS_ID)
+            });
+
             container(chart)
         } else {
             container(text("Please select a frequency respone.")).center(Length::Fill)
@@ -1416,12 +4697,26 @@ impl Main {
             container(sidebar)
                 .width(Length::FillPortion(2))
                 .style(container::bordered_box),
-            column![header, container(content).width(Length::FillPortion(5))].spacing(12)
+            column![header]
+                .push(self.stereo_sum_controls())
+                .push(self.target_controls())
+                .push(self.gate_controls())
+                .push(container(content).width(Length::FillPortion(5)))
+                .spacing(12)
         ]
         .spacing(10)
         .into()
     }
 
+    fn crossover_tab(&self) -> Element<'_, Message> {
+        match &self.crossover {
+            Some(crossover) => crossover
+                .view(&self.measurements, &self.fr_state)
+                .map(Message::Crossover),
+            None => center(text("Please select a measurement.")).into(),
+        }
+    }
+
     pub fn spectral_decay_tab<'a>(
         &'a self,
         selected: Option<ui::measurement::Id>,
@@ -1433,10 +4728,20 @@ impl Main {
                 let config_btn = button(icon::settings().center())
                     .style(button::subtle)
                     .on_press(Message::OpenSpectralDecayConfig);
-                Category::new("Spectral Decays").push_button(config_btn)
+
+                let is_computed = selected
+                    .and_then(|id| analyses.get(&id))
+                    .is_some_and(|a| a.spectral_decay.result().is_some());
+                let export_btn = button(icon::download().center())
+                    .style(button::subtle)
+                    .on_press_maybe(is_computed.then_some(Message::ExportSpectralDecay));
+
+                Category::new("Spectral Decays")
+                    .push_button(export_btn)
+                    .push_button(config_btn)
             };
 
-            let entries = self.measurements.iter().flat_map(|measurement| {
+            let entries = self.filtered_measurements().flat_map(|measurement| {
                 let id = measurement.id();
                 let is_active = selected.is_some_and(|s| s == id);
 
@@ -1492,12 +4797,14 @@ impl Main {
                 Some(entry)
             });
 
-            container(column![header, scrollable(column(entries))].spacing(6))
-                .padding(6)
-                .style(|theme| {
-                    container::rounded_box(theme)
-                        .background(theme.extended_palette().background.weakest.color)
-                })
+            container(
+                column![header, self.sidebar_filter(), scrollable(column(entries))].spacing(6),
+            )
+            .padding(6)
+            .style(|theme| {
+                container::rounded_box(theme)
+                    .background(theme.extended_palette().background.weakest.color)
+            })
         };
 
         let spectral_decay = selected
@@ -1525,7 +4832,16 @@ impl Main {
                 })
                 .plot_data(decay, FREQ_AXIS_ID, DB_AXIS_ID);
 
-            container(chart)
+            let controls = row![
+                colormap_controls(self.chart_preferences.colormap),
+                space::horizontal(),
+                help::info(help::Topic::SpectralDecay),
+                help::info(help::Topic::Rt60),
+            ]
+            .spacing(6)
+            .align_y(Center);
+
+            container(column![controls, chart].spacing(10))
         } else {
             center(text("Please select a frequency respone.").size(18))
         };
@@ -1540,6 +4856,248 @@ impl Main {
         .into()
     }
 
+    /// Computes the analysis, that is shown in the split view.
+    /// Lets the scheduler run the analyses of the measurements on screen
+    /// before all others.
+    fn focus_jobs(&self) {
+        let State::Analysing {
+            active_tab,
+            selected,
+            ..
+        } = &self.state
+        else {
+            return;
+        };
+
+        let visible: Vec<_> = match active_tab {
+            Tab::FrequencyResponses { .. } => {
+                self.measurements.loaded().map(Measurement::id).collect()
+            }
+            Tab::Crossover => self
+                .crossover
+                .iter()
+                .flat_map(|crossover| [crossover.low.measurement, crossover.high.measurement])
+                .flatten()
+                .collect(),
+            _ => selected.iter().copied().collect(),
+        };
+
+        let split = self.split.as_ref().and_then(|split| split.selected);
+
+        scheduler::focus(
+            visible
+                .into_iter()
+                .chain(split)
+                .map(scheduler::Subject::new),
+        );
+    }
+
+    fn compute_split(&mut self) -> Task<Message> {
+        let (Some(split), State::Analysing { analyses, .. }) = (&self.split, &mut self.state)
+        else {
+            return Task::none();
+        };
+
+        let Some(id) = split.selected else {
+            return Task::none();
+        };
+
+        let window = analysis_window(self.window.as_ref(), self.gate);
+
+        match split.view {
+            split::View::FrequencyResponse => compute_frequency_response(
+                analyses,
+                id,
+                self.loopback.as_ref(),
+                &self.measurements,
+                self.deconvolution,
+                window,
+            ),
+            split::View::SpectralDecay => compute_spectral_decay(
+                id,
+                analyses,
+                self.spectral_decay_config,
+                window,
+                self.loopback.as_ref(),
+                &self.measurements,
+                self.deconvolution,
+            ),
+            split::View::Spectrogram => compute_spectrogram(
+                id,
+                analyses,
+                &self.spectrogram_config,
+                window,
+                self.loopback.as_ref(),
+                &self.measurements,
+                self.deconvolution,
+            ),
+        }
+    }
+
+    /// Replaces the recording of a measurement, its analysis is computed
+    /// again.
+    fn replace_signal(&mut self, id: measurement::Id, signal: Arc<raumklang_core::Measurement>) {
+        if let Some(measurement) = self.measurements.get_mut(id) {
+            measurement.set_signal(signal);
+        }
+
+        if let State::Analysing {
+            ref mut analyses, ..
+        } = self.state
+        {
+            analyses.remove(&id);
+        }
+
+        self.signal_cache.clear();
+    }
+
+    /// Computes the analyses of the measurement, that the active tab shows,
+    /// e.g. after it has been reloaded from disk.
+    fn compute_shown(&mut self, id: measurement::Id) -> Task<Message> {
+        let State::Analysing {
+            ref active_tab,
+            selected,
+            ref mut analyses,
+            ..
+        } = self.state
+        else {
+            return Task::none();
+        };
+
+        let is_selected = selected == Some(id);
+        let is_crossed_over = self
+            .crossover
+            .as_ref()
+            .is_some_and(|crossover| crossover.contains(id));
+
+        let task = match active_tab {
+            Tab::ImpulseResponses { .. } if is_selected => compute_impulse_response(
+                analyses,
+                id,
+                self.loopback.as_ref(),
+                &self.measurements,
+                self.deconvolution,
+            ),
+            Tab::FrequencyResponses { .. } => compute_frequency_response(
+                analyses,
+                id,
+                self.loopback.as_ref(),
+                &self.measurements,
+                self.deconvolution,
+                analysis_window(self.window.as_ref(), self.gate),
+            ),
+            // the crossover is simulated again, once the response is computed
+            Tab::Crossover if is_crossed_over => compute_frequency_response(
+                analyses,
+                id,
+                self.loopback.as_ref(),
+                &self.measurements,
+                self.deconvolution,
+                analysis_window(self.window.as_ref(), self.gate),
+            ),
+            Tab::SpectralDecays { .. } if is_selected => compute_spectral_decay(
+                id,
+                analyses,
+                self.spectral_decay_config,
+                analysis_window(self.window.as_ref(), self.gate),
+                self.loopback.as_ref(),
+                &self.measurements,
+                self.deconvolution,
+            ),
+            Tab::Spectrograms if is_selected => compute_spectrogram(
+                id,
+                analyses,
+                &self.spectrogram_config,
+                analysis_window(self.window.as_ref(), self.gate),
+                self.loopback.as_ref(),
+                &self.measurements,
+                self.deconvolution,
+            ),
+            _ => Task::none(),
+        };
+
+        self.focus_jobs();
+
+        Task::batch([task, self.compute_split()])
+    }
+
+    fn preprocessing_controls(&self) -> Element<'_, Message> {
+        let preprocessing = self.preprocessing;
+
+        let high_pass = preprocessing.high_pass.map(|frequency| {
+            row![
+                slider(1.0..=20.0, frequency, move |frequency| {
+                    Message::PreprocessingChanged(Preprocessing {
+                        high_pass: Some(frequency),
+                        ..preprocessing
+                    })
+                })
+                .step(1.0)
+                .width(100),
+                text(unit::frequency(frequency)),
+            ]
+            .spacing(6)
+            .align_y(Center)
+        });
+
+        row![
+            checkbox(preprocessing.remove_dc_offset)
+                .label("Remove DC")
+                .on_toggle(move |remove_dc_offset| {
+                    Message::PreprocessingChanged(Preprocessing {
+                        remove_dc_offset,
+                        ..preprocessing
+                    })
+                }),
+            checkbox(preprocessing.high_pass.is_some())
+                .label("High-pass")
+                .on_toggle(move |enabled| {
+                    Message::PreprocessingChanged(Preprocessing {
+                        high_pass: enabled.then_some(Preprocessing::DEFAULT_HIGH_PASS),
+                        ..preprocessing
+                    })
+                }),
+        ]
+        .push(high_pass)
+        .spacing(10)
+        .align_y(Center)
+        .into()
+    }
+
+    fn clear_split_cache(&self) {
+        if let Some(split) = &self.split {
+            split.cache.clear();
+        }
+    }
+
+    fn set_time_unit(&mut self, time_unit: data::chart::TimeSeriesUnit) {
+        self.chart_preferences.time_unit = time_unit;
+        self.signal_cache.clear();
+        self.spectrogram.cache.clear();
+        self.clear_split_cache();
+    }
+
+    fn set_colormap(&mut self, colormap: data::chart::Colormap) {
+        self.chart_preferences.colormap = colormap;
+        self.spectrogram.cache.clear();
+        self.clear_split_cache();
+
+        if let State::Analysing {
+            ref active_tab,
+            ref mut analyses,
+            ..
+        } = self.state
+        {
+            for analysis in analyses.values_mut() {
+                analysis.spectral_decay.set_colormap(colormap);
+            }
+
+            if let Tab::SpectralDecays { cache } = active_tab {
+                cache.clear();
+            }
+        }
+    }
+
     fn spectrogram_tab<'a>(
         &'a self,
         selected: Option<measurement::Id>,
@@ -1554,7 +5112,7 @@ impl Main {
                 Category::new("Spectrograms").push_button(config_btn)
             };
 
-            let entries = self.measurements.iter().flat_map(|measurement| {
+            let entries = self.filtered_measurements().flat_map(|measurement| {
                 let id = measurement.id();
                 let is_active = selected.is_some_and(|selected| selected == id);
 
@@ -1608,12 +5166,14 @@ impl Main {
                 Some(entry)
             });
 
-            container(column![header, scrollable(column(entries))].spacing(6))
-                .padding(6)
-                .style(|theme| {
-                    container::rounded_box(theme)
-                        .background(theme.extended_palette().background.weakest.color)
-                })
+            container(
+                column![header, self.sidebar_filter(), scrollable(column(entries))].spacing(6),
+            )
+            .padding(6)
+            .style(|theme| {
+                container::rounded_box(theme)
+                    .background(theme.extended_palette().background.weakest.color)
+            })
         };
 
         let spectrogram_data = selected
@@ -1626,10 +5186,38 @@ impl Main {
                 &spectrogram.cache,
                 spectrogram.zoom,
                 spectrogram.offset,
+                spectrogram.normalization,
+                spectrogram.level_offset,
+                self.chart_preferences.colormap,
+                self.chart_preferences.time_unit,
             )
             .map(Message::Spectrogram);
 
-            container(chart)
+            let controls = row![
+                pick_list(
+                    Some(&spectrogram.normalization),
+                    spectrogram::Normalization::ALL,
+                    spectrogram::Normalization::to_string,
+                )
+                .on_select(Message::SpectrogramNormalizationChanged),
+                help::info(help::Topic::Spectrogram),
+                colormap_controls(self.chart_preferences.colormap),
+                time_unit_controls(self.chart_preferences.time_unit),
+                space::horizontal(),
+                text("Offset"),
+                slider(
+                    -30.0..=30.0,
+                    spectrogram.level_offset,
+                    Message::SpectrogramLevelOffsetChanged
+                )
+                .step(1.0)
+                .width(200),
+                text(unit::signed_level(spectrogram.level_offset, 0)),
+            ]
+            .spacing(10)
+            .align_y(Center);
+
+            container(column![controls, chart].spacing(10))
         } else {
             container(text("Please select a frequency respone."))
         };
@@ -1667,13 +5255,255 @@ impl Main {
             _ => None,
         });
 
-        let recording = if let Modal::Recording(recording) = &self.modal {
-            recording.subscription()
-        } else {
+        let watch_folder = match &self.watch_folder {
+            Some(watch_folder) => {
+                Subscription::run_with(watch_folder.path().to_path_buf(), |path: &PathBuf| {
+                    file_watcher::watch_directory(path)
+                })
+                .map(Message::WatchFolderChanged)
+            }
+            None => Subscription::none(),
+        };
+
+        let files: BTreeSet<PathBuf> = self
+            .measurements
+            .loaded()
+            .filter_map(|measurement| measurement.path.clone())
+            .collect();
+        let file_changes = if files.is_empty() {
             Subscription::none()
+        } else {
+            Subscription::run_with(files, file_watcher::watch).map(Message::FileChanged)
+        };
+
+        let remote = Subscription::run_with(self.remote_control, remote::osc).map(Message::Remote);
+
+        Subscription::batch([
+            hotkeys,
+            self.modal.subscription(),
+            watch_folder,
+            file_changes,
+            remote,
+        ])
+    }
+
+    /// Metadata embedded in exported WAV files, the stimulus is taken from
+    /// the recording configuration.
+    fn wav_metadata(&self) -> raumklang_core::wav::Metadata {
+        let signal = &self.measurement_config.signal;
+
+        raumklang_core::wav::Metadata {
+            originator: concat!("raumklang ", env!("CARGO_PKG_VERSION")).to_string(),
+            date: Some(SystemTime::now()),
+            microphone: self
+                .calibration
+                .as_ref()
+                .and_then(|(path, _)| path.file_stem())
+                .map(|name| name.to_string_lossy().to_string()),
+            stimulus: Some(raumklang_core::wav::Stimulus {
+                signal: "logarithmic sweep".to_string(),
+                frequency_range: Some((signal.start_frequency(), signal.end_frequency())),
+                duration: signal.duration().into_inner(),
+                level: self.measurement_config.playback_level(),
+            }),
+            ..raumklang_core::wav::Metadata::default()
+        }
+    }
+
+    fn impulse_response_metadata(&self, id: measurement::Id) -> raumklang_core::wav::Metadata {
+        let metadata = self.wav_metadata();
+
+        match self.measurements.get(id) {
+            Some(measurement) => raumklang_core::wav::Metadata {
+                description: format!("Impulse response of {}", measurement.name),
+                ..measurement.wav_metadata(&metadata)
+            },
+            None => raumklang_core::wav::Metadata {
+                description: "Impulse response".to_string(),
+                stimulus: None,
+                ..metadata
+            },
+        }
+    }
+
+    /// Loads the measurement at `path` in the background.
+    fn load_measurement(&mut self, path: PathBuf) -> Task<Message> {
+        if !self.start_loading(&path) {
+            return Task::none();
+        }
+
+        Task::sip(
+            Measurement::load(path.clone()),
+            Message::MeasurementLoading.with(path),
+            Message::MeasurementLoaded,
+        )
+    }
+
+    /// Replaces the recording of the measurement `id` by the file at `path`.
+    fn reload_measurement(&mut self, id: measurement::Id, path: PathBuf) -> Task<Message> {
+        if !self.start_loading(&path) {
+            return Task::none();
+        }
+
+        Task::sip(
+            Measurement::load(path.clone()),
+            Message::MeasurementLoading.with(path),
+            Message::MeasurementReloaded.with(id),
+        )
+    }
+
+    /// Whether loading of `path` can be started, it isn't if the file is
+    /// already being loaded.
+    fn start_loading(&mut self, path: &Path) -> bool {
+        if self.loading.contains_key(path) {
+            log::warn!("{path:?} is already being loaded");
+            return false;
+        }
+
+        self.loading.insert(path.to_path_buf(), 0.0);
+        true
+    }
+
+    /// View state of the charts, that is saved with the project. The
+    /// selected measurement is set, when the measurements are saved.
+    fn charts(&self) -> project::Charts {
+        project::Charts {
+            selected: None,
+            impulse_response: project::ImpulseResponseView {
+                zoom: self.ir_chart.zoom.into(),
+                offset: self.ir_chart.offset,
+                amplitude_unit: self.ir_chart.amplitude_unit,
+                time_unit: self.ir_chart.time_unit,
+            },
+            frequency_response: self.fr_view,
+            spectrogram: project::SpectrogramView {
+                zoom: self.spectrogram.zoom.into(),
+                offset: self.spectrogram.offset.into(),
+            },
+        }
+    }
+
+    fn restore_charts(&mut self, charts: &project::Charts) {
+        self.ir_chart.zoom = charts.impulse_response.zoom.into();
+        self.ir_chart.offset = charts.impulse_response.offset;
+        self.ir_chart.amplitude_unit = charts.impulse_response.amplitude_unit;
+        self.ir_chart.time_unit = charts.impulse_response.time_unit;
+
+        self.spectrogram.zoom = charts.spectrogram.zoom.into();
+        self.spectrogram.offset = charts.spectrogram.offset.into();
+
+        if let Some(view) = charts.frequency_response {
+            let (min, max) = view.frequency;
+            self.fr_state
+                .axis_mut(&FREQ_AXIS_ID)
+                .set_domain(min.max(MIN_FREQ), max.min(MAX_FREQ));
+
+            let (min, max) = view.level;
+            self.fr_state
+                .axis_mut(&DB_AXIS_ID)
+                .set_domain(min.max(MIN_DB), max.min(MAX_DB));
+
+            self.fr_view = Some(view);
+        }
+    }
+
+    /// Selects the measurement, that was selected when the project was
+    /// saved, as soon as it is loaded and the analysis has started.
+    fn restore_selection(&mut self) {
+        let State::Analysing {
+            ref mut selected, ..
+        } = self.state
+        else {
+            return;
+        };
+
+        let Some(path) = self.pending_selection.as_ref() else {
+            return;
+        };
+
+        if let Some(measurement) = self
+            .measurements
+            .loaded()
+            .find(|measurement| measurement.path.as_ref() == Some(path))
+        {
+            *selected = Some(measurement.id());
+            self.pending_selection = None;
+        }
+    }
+
+    /// Anonymized state for bug reports, see [`data::snapshot::Snapshot`].
+    fn snapshot(&self) -> data::snapshot::Snapshot {
+        let project = Project {
+            loopback: self
+                .loopback
+                .as_ref()
+                .and_then(|loopback| loopback.path.clone())
+                .map(project::Loopback::new),
+            measurements: self
+                .measurements
+                .iter()
+                .filter_map(|measurement| {
+                    Some(project::Measurement {
+                        path: measurement.path.clone()?,
+                        playback_level: measurement.playback_level,
+                        levels: measurement.levels,
+                        source: measurement.source,
+                    })
+                })
+                .collect(),
+            measurement_operation: self.measurement_operation,
+            export_from_memory: self.export_from_memory,
+            wav_format: self.wav_format,
+            mode: self.mode,
+            recording: Some(project::Recording::from(&self.measurement_config)),
+            calibration: self.calibration.as_ref().map(|(path, _)| path.clone()),
+            charts: self.charts(),
+            preprocessing: self.preprocessing,
         };
 
-        Subscription::batch([hotkeys, recording.map(Message::Recording)])
+        let loopback = self.loopback.as_ref().and_then(|loopback| {
+            loopback
+                .loaded()
+                .map(|signal| data::snapshot::Thumbnail::new(&loopback.name, signal.as_ref()))
+        });
+        let measurements = self.measurements.iter().filter_map(|measurement| {
+            measurement
+                .signal()
+                .map(|signal| data::snapshot::Thumbnail::new(&measurement.name, signal))
+        });
+
+        let settings = [
+            ("deconvolution", format!("{:?}", self.deconvolution)),
+            ("smoothing", format!("{:?}", self.smoothing)),
+            ("gate", format!("{:?}", self.gate)),
+            ("window", format!("{:?}", self.window)),
+            (
+                "spectral_decay",
+                format!("{:?}", self.spectral_decay_config),
+            ),
+            ("spectrogram", format!("{:?}", self.spectrogram_config)),
+            ("measurement", format!("{:?}", self.measurement_config)),
+            ("chart", format!("{:?}", self.chart_preferences)),
+            (
+                "normalize_playback_level",
+                self.normalize_playback_level.to_string(),
+            ),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect();
+
+        let log = log::session()
+            .iter()
+            .map(|entry| entry.to_string())
+            .collect();
+
+        data::snapshot::Snapshot::new(
+            project,
+            loopback.into_iter().chain(measurements).collect(),
+            settings,
+            log,
+        )
     }
 
     fn save_project(
@@ -1692,6 +5522,17 @@ impl Main {
                 measurements,
                 export_from_memory,
                 measurement_operation,
+                self.wav_format,
+                self.wav_metadata(),
+                self.mode,
+                project::Recording::from(&self.measurement_config),
+                self.calibration.as_ref().map(|(path, _)| path.clone()),
+                self.charts(),
+                self.preprocessing,
+                match self.state {
+                    State::Analysing { selected, .. } => selected,
+                    State::Collecting => None,
+                },
             ),
             Message::ProjectSaved,
         )
@@ -1699,12 +5540,22 @@ impl Main {
 }
 
 impl ProjectMenu {
-    const ALL: [ProjectMenu; 5] = [
+    const ALL: [ProjectMenu; 15] = [
         ProjectMenu::New,
         ProjectMenu::Save,
         ProjectMenu::Load,
         ProjectMenu::LoadRecent,
         ProjectMenu::SaveAs,
+        ProjectMenu::SetupWizard,
+        ProjectMenu::ChannelCheck,
+        ProjectMenu::MovingMic,
+        ProjectMenu::SubAlignment,
+        ProjectMenu::TransferFunction,
+        ProjectMenu::Rta,
+        ProjectMenu::SplMeter,
+        ProjectMenu::ExportHook,
+        ProjectMenu::RemoteControl,
+        ProjectMenu::ExportSnapshot,
     ];
 }
 
@@ -1716,6 +5567,16 @@ impl fmt::Display for ProjectMenu {
             ProjectMenu::Save => "Save",
             ProjectMenu::SaveAs => "Save as ...",
             ProjectMenu::LoadRecent => "Load recent ...",
+            ProjectMenu::SetupWizard => "Setup wizard ...",
+            ProjectMenu::ChannelCheck => "Channel check ...",
+            ProjectMenu::MovingMic => "Moving microphone ...",
+            ProjectMenu::SubAlignment => "Subwoofer alignment ...",
+            ProjectMenu::SplMeter => "Sound pressure level ...",
+            ProjectMenu::Rta => "Real-time analyzer ...",
+            ProjectMenu::TransferFunction => "Live transfer function ...",
+            ProjectMenu::ExportHook => "Export hook ...",
+            ProjectMenu::RemoteControl => "Remote control ...",
+            ProjectMenu::ExportSnapshot => "Export snapshot ...",
         };
 
         write!(f, "{}", title)
@@ -1730,6 +5591,16 @@ impl From<ProjectMenu> for Message {
             ProjectMenu::Save => Message::SaveProject,
             ProjectMenu::SaveAs => Message::OpenSaveProjectDialog,
             ProjectMenu::LoadRecent => Message::OpenRecentDialog,
+            ProjectMenu::SetupWizard => Message::OpenWizard,
+            ProjectMenu::ChannelCheck => Message::OpenChannelCheck,
+            ProjectMenu::MovingMic => Message::OpenMovingMic,
+            ProjectMenu::SubAlignment => Message::OpenSubAlignment,
+            ProjectMenu::SplMeter => Message::OpenSplMeter,
+            ProjectMenu::Rta => Message::OpenRta,
+            ProjectMenu::TransferFunction => Message::OpenTransferFunction,
+            ProjectMenu::ExportHook => Message::OpenExportHookDialog,
+            ProjectMenu::RemoteControl => Message::OpenRemoteControlDialog,
+            ProjectMenu::ExportSnapshot => Message::ExportSnapshot,
         }
     }
 }
@@ -1748,12 +5619,21 @@ impl From<io::Error> for ProjectError {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn save_project(
     path: impl AsRef<Path>,
     loopback: Option<Loopback>,
     measurements: impl IntoIterator<Item = Measurement>,
     export_from_memory: bool,
     measurement_operation: project::Operation,
+    wav_format: project::WavFormat,
+    metadata: raumklang_core::wav::Metadata,
+    mode: project::Mode,
+    recording: project::Recording,
+    calibration: Option<PathBuf>,
+    mut charts: project::Charts,
+    preprocessing: Preprocessing,
+    selected: Option<measurement::Id>,
 ) -> Result<(PathBuf, Project), ProjectError> {
     let path = path.as_ref();
     let project_dir = path.parent().ok_or(ProjectError::NoSubDirectory)?;
@@ -1765,7 +5645,16 @@ async fn save_project(
             Some(path.clone())
         } else if export_from_memory {
             let path = path.with_file_name("loopback.wav");
-            loopback.clone().save(path).await
+            let metadata = raumklang_core::wav::Metadata {
+                description: loopback.name.clone(),
+                date: loopback.loaded().map(|signal| signal.as_ref().modified),
+                ..metadata.clone()
+            };
+
+            loopback
+                .clone()
+                .save(path, wav_format.into(), metadata)
+                .await
         } else {
             None
         }
@@ -1773,62 +5662,165 @@ async fn save_project(
         None
     };
 
-    let mut measurement_paths = vec![];
+    let mut project_measurements = vec![];
     for measurement in measurements {
+        let playback_level = measurement.playback_level;
+        let levels = measurement.levels;
+        let source = measurement.source;
+
         let path = if let Some(path) = measurement.path.as_ref() {
             Some(path.clone())
         } else if export_from_memory {
             let path = path.with_file_name(format!("measurement_{}.wav", measurement.id()));
-            measurement.save(path).await
+            let metadata = measurement.wav_metadata(&metadata);
+            measurement.save(path, wav_format.into(), metadata).await
         } else {
             None
         };
 
-        measurement_paths.extend(path);
+        if path.is_some() && selected == Some(measurement.id()) {
+            charts.selected = Some(project_measurements.len());
+        }
+
+        project_measurements.extend(path.map(|path| project::Measurement {
+            path,
+            playback_level,
+            levels,
+            source,
+        }));
     }
 
     let project = Project {
         loopback: loopback_path.map(project::Loopback::new),
-        measurements: measurement_paths
-            .into_iter()
-            .map(project::Measurement::new)
-            .collect(),
+        measurements: project_measurements,
         measurement_operation,
         export_from_memory,
+        wav_format,
+        mode,
+        recording: Some(recording),
+        calibration,
+        charts,
+        preprocessing,
     };
 
     let project = project.save(path).await.unwrap();
     Ok((path.to_path_buf(), project))
 }
 
+/// Quality of a measurement, including the impulse response, once computed.
+fn quality(measurement: &Measurement, analysis: Option<&Analysis>) -> Option<data::Quality> {
+    let quality = measurement.quality()?;
+
+    let quality = match analysis.and_then(Analysis::impulse_response) {
+        Some(ir) => quality.with_impulse_response(ir.noise_floor_margin, ir.drift),
+        None => quality,
+    };
+
+    Some(quality)
+}
+
+fn apply_operation(
+    measurements: &measurement::List,
+    operation: operation::Operation,
+) -> Option<Result<(String, raumklang_core::Measurement), raumklang_core::Error>> {
+    let get = |id| {
+        let measurement = measurements.get(id)?;
+        let signal = measurement.signal()?.shift(measurement.time_shift);
+
+        Some((&measurement.name, signal))
+    };
+
+    let result = match operation {
+        operation::Operation::Sum(a, b) => {
+            let ((a_name, a), (b_name, b)) = get(a).zip(get(b))?;
+            a.sum(&b)
+                .map(|signal| (format!("{a_name} + {b_name}"), signal))
+        }
+        operation::Operation::Difference(a, b) => {
+            let ((a_name, a), (b_name, b)) = get(a).zip(get(b))?;
+            a.difference(&b)
+                .map(|signal| (format!("{a_name} - {b_name}"), signal))
+        }
+        operation::Operation::Scale(a, gain) => {
+            let (name, a) = get(a)?;
+            let signal = a.scale(raumklang_core::db_to_gain(gain));
+            Ok((format!("{name} ({gain:+.1} dB)"), signal))
+        }
+    };
+
+    Some(result)
+}
+
 fn compute_impulse_response(
     analyses: &mut BTreeMap<measurement::Id, Analysis>,
     id: measurement::Id,
     loopback: Option<&Loopback>,
     measurements: &measurement::List,
+    deconvolution: data::impulse_response::Method,
 ) -> Task<Message> {
-    let Some(loopback) = loopback.and_then(Loopback::loaded) else {
+    if let Some(impulse_response) = measurements
+        .get(id)
+        .and_then(Measurement::imported_impulse_response)
+    {
+        return Task::done(Message::ImpulseResponseComputed(id, impulse_response));
+    }
+
+    let Some(measurement) = measurements.get(id) else {
         return Task::none();
     };
 
-    let Some(measurement) = measurements.get(id).and_then(Measurement::signal) else {
+    let playback_level = measurement.playback_level;
+
+    let Some(measurement) = measurement.signal() else {
         return Task::none();
     };
 
     let analysis = analyses.entry(id).or_default();
+    let subject = scheduler::Subject::new(id);
 
-    analysis
-        .impulse_response
-        .clone()
-        .compute(loopback, measurement)
-        .map(|sipper| {
-            Task::sip(
-                sipper,
-                Message::ImpulseResponseComputed.with(id),
-                Message::ImpulseResponseComputed.with(id),
-            )
-        })
-        .unwrap_or_default()
+    match deconvolution {
+        data::impulse_response::Method::Deconvolution(method) => {
+            let Some(loopback) = loopback.and_then(Loopback::loaded) else {
+                return Task::none();
+            };
+
+            if measurement.sample_rate() != loopback.sample_rate() {
+                log::warn!("Sample rate of measurement {id} doesn't match the loopback");
+                return Task::none();
+            }
+
+            analysis
+                .impulse_response
+                .clone()
+                .compute(subject, loopback, measurement, method)
+                .map(|sipper| {
+                    Task::sip(
+                        sipper,
+                        Message::ImpulseResponseComputed.with(id),
+                        Message::ImpulseResponseComputed.with(id),
+                    )
+                })
+                .unwrap_or_default()
+        }
+        data::impulse_response::Method::InverseFilter(sweep) => {
+            let measurement = measurement.clone();
+
+            analysis
+                .impulse_response
+                .clone()
+                .compute_with(subject, move || {
+                    audio::impulse_response_from_sweep(&sweep, &measurement, playback_level)
+                })
+                .map(|sipper| {
+                    Task::sip(
+                        sipper,
+                        Message::ImpulseResponseComputed.with(id),
+                        Message::ImpulseResponseComputed.with(id),
+                    )
+                })
+                .unwrap_or_default()
+        }
+    }
 }
 
 fn compute_frequency_response(
@@ -1836,25 +5828,38 @@ fn compute_frequency_response(
     id: measurement::Id,
     loopback: Option<&Loopback>,
     measurements: &measurement::List,
+    deconvolution: data::impulse_response::Method,
     window: data::Window<data::Samples>,
 ) -> Task<Message> {
+    let time_shift = measurements.get(id).map_or(0, |m| m.time_shift);
     let analysis = analyses.entry(id).or_default();
 
     if analysis.frequency_response.result().is_some() {
         return Task::none();
     }
 
-    if let Some(ir) = analysis.impulse_response.result() {
+    if let Some(ir) = analysis.windowed_impulse_response(&window, time_shift) {
         // TODO move into analysis itself
         analysis.frequency_response.state = ui::frequency_response::State::Computing;
         Task::perform(
-            data::frequency_response::compute(ir.data.clone(), window),
+            data::frequency_response::compute(scheduler::Subject::new(id), ir),
             Message::FrequencyResponseComputed.with(id),
         )
     } else {
         analysis.frequency_response.state =
             ui::frequency_response::State::WaitingForImpulseResponse;
-        compute_impulse_response(analyses, id, loopback, measurements)
+        compute_impulse_response(analyses, id, loopback, measurements, deconvolution)
+    }
+}
+
+/// The window applied to the impulse responses before all analyses in the
+/// frequency domain, with the right edge moved to the `gate` if set.
+fn analysis_window(window: Option<&Window<Samples>>, gate: Option<Duration>) -> Window<Samples> {
+    let window = window.cloned().unwrap();
+
+    match gate {
+        Some(gate) => window.gated(gate),
+        None => window,
     }
 }
 
@@ -1862,18 +5867,23 @@ fn compute_spectral_decay(
     id: measurement::Id,
     analyses: &mut BTreeMap<measurement::Id, Analysis>,
     config: data::spectral_decay::Config,
+    window: Window<Samples>,
     loopback: Option<&Loopback>,
     measurements: &measurement::List,
+    deconvolution: data::impulse_response::Method,
 ) -> Task<Message> {
+    let time_shift = measurements.get(id).map_or(0, |m| m.time_shift);
     let analysis = analyses.entry(id).or_default();
 
-    if let Some(computation) = analysis
-        .spectral_decay
-        .compute(&analysis.impulse_response, config)
+    let impulse_response = analysis.windowed_impulse_response(&window, time_shift);
+    if let Some(computation) =
+        analysis
+            .spectral_decay
+            .compute(scheduler::Subject::new(id), impulse_response, config)
     {
         Task::perform(computation, Message::SpectralDecayComputed.with(id))
     } else {
-        compute_impulse_response(analyses, id, loopback, measurements)
+        compute_impulse_response(analyses, id, loopback, measurements, deconvolution)
     }
 }
 
@@ -1881,18 +5891,23 @@ fn compute_spectrogram(
     id: measurement::Id,
     analyses: &mut BTreeMap<measurement::Id, Analysis>,
     config: &spectrogram::Config,
+    window: Window<Samples>,
     loopback: Option<&ui::Loopback>,
     measurements: &measurement::List,
+    deconvolution: data::impulse_response::Method,
 ) -> Task<Message> {
+    let time_shift = measurements.get(id).map_or(0, |m| m.time_shift);
     let analysis = analyses.entry(id).or_default();
 
-    if let Some(computation) = analysis
-        .spectrogram
-        .compute(&analysis.impulse_response, config)
+    let impulse_response = analysis.windowed_impulse_response(&window, time_shift);
+    if let Some(computation) =
+        analysis
+            .spectrogram
+            .compute(scheduler::Subject::new(id), impulse_response, config)
     {
         Task::perform(computation, Message::SpectrogramComputed.with(id))
     } else {
-        compute_impulse_response(analyses, id, loopback, measurements)
+        compute_impulse_response(analyses, id, loopback, measurements, deconvolution)
     }
 }
 
@@ -1903,6 +5918,11 @@ impl Default for Main {
         fr_state.set_axis(FREQ_AXIS_ID, create_frequency_axis());
         fr_state.set_axis(DB_AXIS_ID, create_db_axis());
 
+        let mut fr_phase_state = iced_aksel::State::new();
+
+        fr_phase_state.set_axis(FREQ_AXIS_ID, create_frequency_axis());
+        fr_phase_state.set_axis(PHASE_AXIS_ID, create_phase_axis());
+
         Self {
             state: State::default(),
             modal: Modal::None,
@@ -1910,34 +5930,85 @@ impl Default for Main {
 
             loopback: None,
             measurements: measurement::List::default(),
+            sidebar_filter: String::new(),
+            loading: BTreeMap::new(),
 
             project_path: None,
             measurement_operation: project::Operation::Copy,
             export_from_memory: true,
+            wav_format: project::WavFormat::default(),
+            mode: project::Mode::default(),
 
             spectral_decay_config: data::spectral_decay::Config::default(),
 
             zoom: chart::Zoom::default(),
             offset: chart::Offset::default(),
             smoothing: frequency_response::Smoothing::default(),
+            fr_display: frequency_response::Display::default(),
+            export_grid: data::frequency_response::Grid::default(),
+            gate: None,
             window: None,
+            window_presets: window::Presets::default(),
+            window_preset_name: String::new(),
 
             signal_cache: canvas::Cache::default(),
 
             ir_chart: impulse_response::Chart::default(),
+            time_shift_input: "0".to_string(),
+            deconvolution: data::impulse_response::Method::default(),
+            preprocessing: Preprocessing::default(),
             spectrogram: Spectrogram::default(),
+            chart_preferences: data::chart::Preferences::default(),
+            show_harmonics: false,
+            normalize_playback_level: false,
             spectrogram_config: spectrogram::Config::default(),
 
             fr_state,
+            fr_phase_state,
+            fr_view: None,
+            fr_solo: None,
+            fr_hovered: None,
+            pending_selection: None,
+            split: None,
             measurement_config: data::measurement::Config::default(),
+            watch_folder: None,
+            remote_control: None,
+
+            compensation: None,
+            channel_difference: None,
+            moving_mic: None,
+            spread: frequency_response::Spread::default(),
+            spread_band: None,
+            target: None,
+            target_error: None,
+            correction_limits: ui::curve::CorrectionLimits::default(),
+            correction_envelope: None,
+            stereo_sum: None,
+            crossover: None,
+            calibration: None,
+            wizard: None,
         }
     }
 }
 
+const COMPENSATION_COLOR: Color = Color::from_rgb(0.6, 0.6, 0.6);
+const CHANNEL_DIFFERENCE_COLOR: Color = Color::from_rgb(1.0, 0.84, 0.0);
+const STEREO_SUM_COLOR: Color = Color::from_rgb(0.0, 0.8, 0.8);
+const MOVING_MIC_COLOR: Color = Color::from_rgb(0.9, 0.4, 0.9);
+const SPREAD_COLOR: Color = Color::from_rgb(0.5, 0.7, 1.0);
+const TARGET_COLOR: Color = Color::from_rgb(0.9, 0.9, 0.9);
+const TARGET_ERROR_COLOR: Color = Color::from_rgb(1.0, 0.5, 0.3);
+
+/// Sample rate of impulse responses, generated from REW text exports
+/// without a loopback.
+const REW_SAMPLE_RATE: u32 = 48_000;
+
 const MIN_FREQ: f32 = 15.0;
 const MAX_FREQ: f32 = 22_000.0;
 const MIN_DB: f32 = -90.0;
 const MAX_DB: f32 = 12.0;
+const MIN_PHASE: f32 = -180.0;
+const MAX_PHASE: f32 = 180.0;
 
 fn create_frequency_axis() -> iced_aksel::Axis<f32> {
     iced_aksel::Axis::new(
@@ -1955,6 +6026,13 @@ fn create_db_axis() -> iced_aksel::Axis<f32> {
         .skip_overlapping_labels(8.0)
 }
 
+fn create_phase_axis() -> iced_aksel::Axis<f32> {
+    iced_aksel::Axis::new(scale::Linear::new(MIN_PHASE, MAX_PHASE), Position::Left)
+        .with_tick_renderer(phase_tick_renderer)
+        .with_thickness(80.0)
+        .skip_overlapping_labels(8.0)
+}
+
 fn frequency_tick_renderer(ctx: TickContext<f32, Theme>) -> TickResult {
     let line = TickLine {
         length: Pixels(if ctx.tick.level == 0 { 12.0 } else { 6.0 }),
@@ -1973,18 +6051,69 @@ fn db_tick_renderer(ctx: TickContext<f32, Theme>) -> TickResult {
         .grid_line(ctx.gridline())
 }
 
+fn phase_tick_renderer(ctx: TickContext<f32, Theme>) -> TickResult {
+    let label = format_phase_label(ctx.tick.value);
+    TickResult::with_label(ctx.label(label))
+        .tick_line(ctx.tickline())
+        .grid_line(ctx.gridline())
+}
+
 fn format_frequency_label(value: f32) -> String {
-    if value >= 10_000.0 {
-        format!("{:.0} kHz", value / 1000.0)
-    } else if value >= 1000.0 {
-        format!("{:.1} kHz", value / 1000.0)
-    } else {
-        format!("{:.0} Hz", value)
-    }
+    unit::frequency(value)
 }
 
 fn format_db_label(value: f32) -> String {
-    format!("{:+.0} dB", value)
+    unit::signed_level(value, 0)
+}
+
+fn format_phase_label(value: f32) -> String {
+    format!("{value:.0}°")
+}
+
+/// Reverberation times of the impulse response `before` and `after`
+/// denoising.
+fn decay_times_label(
+    before: &raumklang_core::decay::DecayTimes,
+    after: &raumklang_core::decay::DecayTimes,
+) -> String {
+    let time = |t: Option<f32>| t.map_or("-".to_string(), |t| format!("{t:.2} s"));
+
+    format!(
+        "T20 {} → {}, T30 {} → {}",
+        time(before.t20),
+        time(after.t20),
+        time(before.t30),
+        time(after.t30)
+    )
+}
+
+/// Lists the frequencies, that are harmonically related to the `frequency`
+/// under the cursor, with the levels of the `curves` at them.
+fn harmonics_label(frequency: f32, curves: &[&ui::frequency_response::SpectrumLayer]) -> String {
+    const HARMONICS: [(&str, f32); 3] = [("½×", 0.5), ("2×", 2.0), ("3×", 3.0)];
+
+    HARMONICS
+        .iter()
+        .map(|(label, factor)| {
+            let frequency = frequency * factor;
+            let levels: Vec<_> = curves
+                .iter()
+                .filter_map(|curve| curve.level_at(frequency))
+                .map(|level| format!("{level:+.1}"))
+                .collect();
+
+            if levels.is_empty() {
+                format!("{label} {}", format_frequency_label(frequency))
+            } else {
+                format!(
+                    "{label} {}: {} dB",
+                    format_frequency_label(frequency),
+                    levels.join(" / ")
+                )
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 async fn choose_impulse_response_file_path() -> Option<Arc<Path>> {
@@ -1998,26 +6127,191 @@ async fn choose_impulse_response_file_path() -> Option<Arc<Path>> {
         .map(|h| h.path().into())
 }
 
+async fn pick_dry_signal_file() -> Option<PathBuf> {
+    let handle = rfd::AsyncFileDialog::new()
+        .set_title("Choose dry signal ...")
+        .add_filter("wav", &["wav", "wave"])
+        .add_filter("all", &["*"])
+        .pick_file()
+        .await?;
+
+    Some(handle.path().to_path_buf())
+}
+
+async fn pick_filter_file() -> Option<PathBuf> {
+    let handle = rfd::AsyncFileDialog::new()
+        .set_title("Choose correction filter ...")
+        .add_filter("wav", &["wav", "wave"])
+        .add_filter("all", &["*"])
+        .pick_file()
+        .await?;
+
+    Some(handle.path().to_path_buf())
+}
+
+async fn choose_auralization_file_path() -> Option<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .set_title("Export auralization ...")
+        .add_filter("wav", &["wav", "wave"])
+        .add_filter("all", &["*"])
+        .save_file()
+        .await
+        .map(|handle| handle.path().to_path_buf())
+}
+
+async fn pick_window_preset_file() -> Option<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .set_title("Import Window Preset ...")
+        .add_filter("json", &["json"])
+        .pick_file()
+        .await
+        .as_ref()
+        .map(|h| h.path().to_path_buf())
+}
+
+async fn choose_window_preset_file_path() -> Option<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .set_title("Export Window Preset ...")
+        .add_filter("json", &["json"])
+        .save_file()
+        .await
+        .as_ref()
+        .map(|h| h.path().to_path_buf())
+}
+
+async fn pick_watch_folder() -> Option<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .set_title("Watch folder for new measurements ...")
+        .pick_folder()
+        .await
+        .as_ref()
+        .map(|h| h.path().to_path_buf())
+}
+
+async fn choose_frequency_response_file_path() -> Option<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .set_title("Export Frequency Response ...")
+        .add_filter("frd", &["frd"])
+        .add_filter("csv", &["csv"])
+        .save_file()
+        .await
+        .as_ref()
+        .map(|h| h.path().to_path_buf())
+}
+
+async fn choose_session_log_file_path() -> Option<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .set_title("Export Session Log ...")
+        .add_filter("text", &["txt", "log"])
+        .save_file()
+        .await
+        .as_ref()
+        .map(|h| h.path().to_path_buf())
+}
+
+async fn choose_snapshot_file_path(file_name: String) -> Option<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .set_title("Export Snapshot ...")
+        .set_file_name(file_name)
+        .add_filter("json", &["json"])
+        .save_file()
+        .await
+        .as_ref()
+        .map(|h| h.path().to_path_buf())
+}
+
+async fn choose_spectral_decay_file_path() -> Option<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .set_title("Export Spectral Decay ...")
+        .add_filter("csv", &["csv"])
+        .add_filter("png", &["png"])
+        .save_file()
+        .await
+        .as_ref()
+        .map(|h| h.path().to_path_buf())
+}
+
 // TODO: error handling
-async fn save_impulse_response(path: Arc<Path>, ir: ui::ImpulseResponse) {
-    tokio::task::spawn_blocking(move || {
-        let spec = hound::WavSpec {
-            channels: 1,
-            sample_rate: ir.sample_rate.into(),
-            bits_per_sample: 32,
-            sample_format: hound::SampleFormat::Float,
-        };
+async fn resample_measurement(
+    signal: Arc<raumklang_core::Measurement>,
+    sample_rate: u32,
+) -> Arc<raumklang_core::Measurement> {
+    tokio::task::spawn_blocking(move || Arc::new(signal.resample(sample_rate)))
+        .await
+        .unwrap()
+}
 
-        let mut writer = hound::WavWriter::create(path, spec).unwrap();
-        for s in ir.normalized {
-            writer.write_sample(s).unwrap();
-        }
-        writer.finalize().unwrap();
+async fn remove_dc_offset(
+    signal: Arc<raumklang_core::Measurement>,
+) -> Arc<raumklang_core::Measurement> {
+    tokio::task::spawn_blocking(move || Arc::new(signal.remove_dc_offset()))
+        .await
+        .unwrap()
+}
+
+async fn save_impulse_response(
+    path: Arc<Path>,
+    ir: ui::ImpulseResponse,
+    format: raumklang_core::wav::Format,
+    metadata: raumklang_core::wav::Metadata,
+) {
+    tokio::task::spawn_blocking(move || {
+        raumklang_core::wav::write(
+            path,
+            ir.sample_rate.into(),
+            1,
+            ir.normalized,
+            format,
+            Some(&metadata),
+        )
+        .unwrap();
     })
     .await
     .unwrap();
 }
 
+/// Runs the configured export hook with the exported files.
+fn run_export_hook(paths: Vec<PathBuf>) -> Task<Message> {
+    Task::perform(data::export_hook::run(paths), Message::ExportHookFinished)
+}
+
+fn time_unit_controls<'a>(time_unit: data::chart::TimeSeriesUnit) -> Element<'a, Message> {
+    pick_list(
+        Some(time_unit),
+        &data::chart::TimeSeriesUnit::TIME[..],
+        data::chart::TimeSeriesUnit::to_string,
+    )
+    .on_select(Message::TimeUnitChanged)
+    .into()
+}
+
+fn colormap_controls<'a>(colormap: data::chart::Colormap) -> Element<'a, Message> {
+    row![
+        pick_list(
+            Some(colormap.gradient),
+            &data::chart::Gradient::ALL[..],
+            data::chart::Gradient::to_string,
+        )
+        .on_select(
+            move |gradient| Message::ColormapChanged(data::chart::Colormap {
+                gradient,
+                ..colormap
+            })
+        ),
+        checkbox(colormap.inverted)
+            .label("Invert")
+            .on_toggle(
+                move |inverted| Message::ColormapChanged(data::chart::Colormap {
+                    inverted,
+                    ..colormap
+                })
+            ),
+    ]
+    .spacing(10)
+    .align_y(Center)
+    .into()
+}
+
 fn modal<'a, Message>(
     base: impl Into<Element<'a, Message>>,
     content: impl Into<Element<'a, Message>>,
@@ -2109,6 +6403,17 @@ where
     }
 }
 
+async fn pick_rew_file() -> Option<PathBuf> {
+    let handle = rfd::AsyncFileDialog::new()
+        .set_title("Import from REW ...")
+        .add_filter("REW export", &["wav", "wave", "txt"])
+        .add_filter("all", &["*"])
+        .pick_file()
+        .await?;
+
+    Some(handle.path().to_path_buf())
+}
+
 pub async fn pick_measurement_file(title: impl AsRef<str>) -> Option<PathBuf> {
     let handle = rfd::AsyncFileDialog::new()
         .set_title(title.as_ref())
@@ -2120,6 +6425,28 @@ pub async fn pick_measurement_file(title: impl AsRef<str>) -> Option<PathBuf> {
     handle.as_ref().map(FileHandle::path).map(Path::to_path_buf)
 }
 
+async fn pick_curve_file(title: &str) -> Option<PathBuf> {
+    let handle = rfd::AsyncFileDialog::new()
+        .set_title(title)
+        .add_filter("text", &["txt", "csv"])
+        .add_filter("all", &["*"])
+        .pick_file()
+        .await?;
+
+    Some(handle.path().to_path_buf())
+}
+
+async fn pick_calibration_file() -> Option<PathBuf> {
+    let handle = rfd::AsyncFileDialog::new()
+        .set_title("Load microphone calibration ...")
+        .add_filter("text", &["txt", "cal", "frd", "csv"])
+        .add_filter("all", &["*"])
+        .pick_file()
+        .await?;
+
+    Some(handle.path().to_path_buf())
+}
+
 async fn pick_project_file_to_load() -> Option<PathBuf> {
     let handle = rfd::AsyncFileDialog::new()
         .set_title("Load project...")
@@ -7,7 +7,7 @@ use waveform::Waveform;
 
 use crate::{
     data::{self, Samples, Window, chart, window::Handles},
-    screen::main::chart::spectrogram::Spectrogram,
+    screen::main::chart::spectrogram::{Plane, Spectrogram},
     ui,
 };
 
@@ -23,7 +23,7 @@ use iced::{
     mouse::{self, ScrollDelta},
     widget::{
         canvas::{self, Frame, Path, Stroke},
-        container,
+        container, shader, stack,
         text::{Fragment, IntoFragment},
     },
     window,
@@ -39,21 +39,24 @@ pub fn waveform<'a>(
     cache: &'a canvas::Cache,
     zoom: Zoom,
     offset: Offset,
+    time_unit: chart::TimeSeriesUnit,
+    amplitude_unit: chart::AmplitudeUnit,
+    rms_envelope: bool,
 ) -> Element<'a, waveform::Interaction, iced::Theme> {
+    let sample_rate = measurement.sample_rate() as f32;
+
     canvas::Canvas::new(Waveform {
         datapoints: measurement.iter().copied(),
         cache,
-        cmp: |a, b| a.total_cmp(b),
         y_to_float: |s| s,
-        to_x_scale: |i| i,
-        // to_x_scale: move |i| match time_unit {
-        //     chart::TimeSeriesUnit::Time => time_scale(i, impulse_response.sample_rate.into()),
-        //     chart::TimeSeriesUnit::Samples => i as f32,
-        // },
-        // to_y_scale: move |s| match amplitude_unit {
-        //     chart::AmplitudeUnit::PercentFullScale => percent_full_scale(s),
-        //     chart::AmplitudeUnit::DezibelFullScale => db_full_scale(s),
-        // },
+        to_x_scale: move |i| time_unit.from_samples(i, sample_rate),
+        // keeps the sign, unlike the magnitude of impulse responses
+        to_y_scale: move |s| match amplitude_unit {
+            chart::AmplitudeUnit::PercentFullScale => s.clamp(-1.0, 1.0) * 100.0,
+            chart::AmplitudeUnit::DezibelFullScale => db_full_scale(s),
+        },
+        rms_envelope,
+        sample_rate,
         zoom,
         offset,
         y_range: None,
@@ -68,33 +71,44 @@ pub fn spectrogram<'a>(
     cache: &'a canvas::Cache,
     zoom: Zoom,
     offset: Offset,
+    normalization: data::spectrogram::Normalization,
+    level_offset: f32,
+    colormap: chart::Colormap,
+    time_unit: chart::TimeSeriesUnit,
 ) -> Element<'a, spectrogram::Interaction, iced::Theme> {
-    canvas::Canvas::new(Spectrogram {
+    // the plane is rendered below the axes, the legend and the readout
+    let plane = shader(Plane {
+        datapoints: data,
+        zoom,
+        offset,
+        normalization,
+        level_offset,
+        colormap,
+    })
+    .width(Fill)
+    .height(Fill);
+
+    let overlay = canvas::Canvas::new(Spectrogram {
         datapoints: data,
         cache,
-        // cmp: |a, b| a.total_cmp(b),
-        // y_to_float: |s| s,
-        // to_x_scale: move |i| i as f32,
-        // to_x_scale: move |i| match time_unit {
-        //     chart::TimeSeriesUnit::Time => time_scale(i, impulse_response.sample_rate.into()),
-        //     chart::TimeSeriesUnit::Samples => i as f32,
-        // },
-        // to_y_scale: move |s| match amplitude_unit {
-        //     chart::AmplitudeUnit::PercentFullScale => percent_full_scale(s),
-        //     chart::AmplitudeUnit::DezibelFullScale => db_full_scale(s),
-        // },
         zoom,
         offset,
+        normalization,
+        level_offset,
+        colormap,
+        time_unit,
     })
     .width(Fill)
-    .height(Fill)
-    .into()
+    .height(Fill);
+
+    stack![plane, overlay].into()
 }
 
 #[allow(clippy::too_many_arguments)]
 pub fn impulse_response<'a>(
     window: &'a Window<Samples>,
     impulse_response: &'a ui::ImpulseResponse,
+    comparison: Option<(&'a ui::ImpulseResponse, bool)>,
     time_unit: &'a chart::TimeSeriesUnit,
     amplitude_unit: &'a chart::AmplitudeUnit,
     zoom: Zoom,
@@ -106,21 +120,30 @@ pub fn impulse_response<'a>(
         canvas::Canvas::new(BarChart {
             window,
             datapoints: impulse_response
-                .normalized
+                .causal()
                 .iter()
                 .copied()
                 .map(f32::abs)
                 .enumerate(),
+            acausal: impulse_response.acausal(),
             cmp: |a, b| a.total_cmp(b),
-            to_x_scale: move |i| match time_unit {
-                chart::TimeSeriesUnit::Time => time_scale(i, impulse_response.sample_rate.into()),
-                chart::TimeSeriesUnit::Samples => i,
-            },
+            to_x_scale: move |i| time_unit.from_samples(i, impulse_response.sample_rate.into()),
             y_to_float: |s| s,
             to_y_scale: move |s| match amplitude_unit {
                 chart::AmplitudeUnit::PercentFullScale => percent_full_scale(s),
                 chart::AmplitudeUnit::DezibelFullScale => db_full_scale(s),
             },
+            comparison: comparison.map(|(comparison, align_by_peak)| {
+                let shift = if align_by_peak {
+                    peak_index(&impulse_response.normalized) as isize
+                        - peak_index(&comparison.normalized) as isize
+                } else {
+                    0
+                };
+
+                (comparison.normalized.as_slice(), shift)
+            }),
+            sample_rate: impulse_response.sample_rate.into(),
             zoom,
             offset,
             data_cache,
@@ -132,6 +155,15 @@ pub fn impulse_response<'a>(
     .into()
 }
 
+fn peak_index(data: &[f32]) -> usize {
+    data.iter()
+        .map(|s| s.abs())
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(i, _)| i)
+        .unwrap_or_default()
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Zoom(f32);
 
@@ -157,6 +189,12 @@ impl Sub<f32> for Zoom {
     }
 }
 
+impl From<f32> for Zoom {
+    fn from(zoom: f32) -> Self {
+        Zoom(zoom)
+    }
+}
+
 impl From<Zoom> for f32 {
     fn from(zoom: Zoom) -> Self {
         zoom.0
@@ -176,6 +214,12 @@ impl Offset {
     }
 }
 
+impl From<isize> for Offset {
+    fn from(offset: isize) -> Self {
+        Offset(offset)
+    }
+}
+
 impl From<Offset> for f32 {
     fn from(value: Offset) -> Self {
         value.0 as f32
@@ -195,10 +239,15 @@ where
 {
     window: &'a Window<Samples>,
     datapoints: I,
+    /// Drawn in front of time zero, the last sample right before it.
+    acausal: &'a [f32],
     cmp: fn(&Y, &Y) -> Ordering,
     to_x_scale: ScaleX,
     y_to_float: fn(Y) -> f32,
     to_y_scale: ScaleY,
+    /// Second trace, drawn on top and shifted by the given amount of samples.
+    comparison: Option<(&'a [f32], isize)>,
+    sample_rate: f32,
     zoom: Zoom,
     offset: i64,
     data_cache: &'a canvas::Cache,
@@ -252,11 +301,10 @@ where
         cursor: mouse::Cursor,
     ) -> Option<canvas::Action<Interaction>> {
         if let Event::Window(window::Event::RedrawRequested(_)) = event {
-            // FIXME: hardcoded values
-            let x_min = 0.250 * 44_100.0 * f32::from(self.zoom);
+            let x_min = 0.250 * self.sample_rate * f32::from(self.zoom);
             let x_min = -x_min + self.offset as f32;
 
-            let x_max = 0.6 * 44_100.0 * f32::from(self.zoom);
+            let x_max = 0.6 * self.sample_rate * f32::from(self.zoom);
             let x_max = x_max.ceil() as u64;
 
             let datapoints = self
@@ -515,9 +563,9 @@ where
 
                 if *shift_pressed {
                     let new_offset = if y.is_sign_positive() {
-                        self.offset + (0.05 * f32::from(self.zoom) * 44_100_f32).ceil() as i64
+                        self.offset + (0.05 * f32::from(self.zoom) * self.sample_rate).ceil() as i64
                     } else {
-                        self.offset - (0.05 * f32::from(self.zoom) * 44_100_f32).ceil() as i64
+                        self.offset - (0.05 * f32::from(self.zoom) * self.sample_rate).ceil() as i64
                     };
 
                     if self.offset != new_offset {
@@ -585,43 +633,89 @@ where
         let pixels_per_unit = y_target_length / y_axis.length;
 
         let data = self.data_cache.draw(renderer, bounds.size(), |frame| {
-            // FIXME: hard-coded values
-            let x_min = 0.250 * 44_100.0 * f32::from(self.zoom);
+            let x_min = 0.250 * self.sample_rate * f32::from(self.zoom);
             let x_min = -x_min + self.offset as f32;
 
-            let x_max = 0.6 * 44_100.0 * f32::from(self.zoom);
+            let x_max = 0.6 * self.sample_rate * f32::from(self.zoom);
             let x_max = x_max.ceil() as u64;
 
+            let skip = if x_min > 0.0 {
+                x_min.ceil() as usize
+            } else {
+                0
+            };
+            let take = x_max.saturating_add_signed(self.offset) as usize;
+
             let datapoints = self
                 .datapoints
                 .clone()
-                .skip(if x_min > 0.0 {
-                    x_min.ceil() as usize
-                } else {
-                    0
-                })
-                .take(x_max.saturating_add_signed(self.offset) as usize)
+                .skip(skip)
+                .take(take)
                 .map(|(_i, datapoint)| datapoint);
 
-            let x_min = -x_axis.min;
-            for (i, datapoint) in datapoints.enumerate() {
-                let value = datapoint;
+            let visible_before_zero = (-x_min).max(0.0).ceil() as usize;
 
-                let value = (self.y_to_float)(value);
+            let x_min = -x_axis.min;
+            let bar = |i: isize, value: f32| {
                 let value = (self.to_y_scale)(value);
-
                 let bar_height = (value - y_axis.min) * pixels_per_unit;
 
-                let bar = Rectangle {
+                Rectangle {
                     x: y_axis.width + (x_min * pixels_per_unit_x) + (i as f32 * pixels_per_unit_x),
                     y: plane.height - bar_height,
                     width: bar_width,
                     height: bar_height,
-                };
+                }
+            };
+
+            // samples sharing a pixel column are drawn as one bar of the tallest
+            let column = window_size.unwrap_or(1).max(1);
+            let columns = |values: Vec<f32>| -> Vec<(isize, f32)> {
+                values
+                    .chunks(column)
+                    .enumerate()
+                    .filter_map(|(i, chunk)| {
+                        let tallest = chunk.iter().copied().max_by(|a, b| {
+                            (self.to_y_scale)(*a).total_cmp(&(self.to_y_scale)(*b))
+                        })?;
+
+                        Some(((i * column) as isize, tallest))
+                    })
+                    .collect()
+            };
+
+            let acausal = self
+                .acausal
+                .iter()
+                .rev()
+                .take(visible_before_zero)
+                .map(|value| value.abs())
+                .collect();
+            for (i, value) in columns(acausal) {
+                let bar = bar(-i - 1, value);
+                frame.fill_rectangle(bar.position(), bar.size(), palette.secondary.weak.color);
+            }
 
+            for (i, value) in columns(datapoints.map(|d| (self.y_to_float)(d)).collect()) {
+                let bar = bar(i, value);
                 frame.fill_rectangle(bar.position(), bar.size(), palette.secondary.weak.color);
             }
 
+            if let Some((comparison, shift)) = self.comparison {
+                let color = palette.primary.base.color.scale_alpha(0.5);
+
+                let datapoints = std::iter::repeat_n(0.0, shift.max(0) as usize)
+                    .chain(comparison.iter().skip(shift.min(0).unsigned_abs()).copied())
+                    .map(f32::abs)
+                    .skip(skip)
+                    .take(take);
+
+                for (i, value) in columns(datapoints.collect()) {
+                    let bar = bar(i, value);
+                    frame.fill_rectangle(bar.position(), bar.size(), color);
+                }
+            }
+
             frame.with_save(|frame| {
                 frame.translate(Vector::new(y_axis.width, 0.0));
 
@@ -741,6 +835,15 @@ impl<'a> HorizontalAxis<'a> {
         range: RangeInclusive<f32>,
         to_scale: F,
         labels: I,
+    ) -> Self {
+        Self::with_precision(range, to_scale, labels, 0)
+    }
+
+    fn with_precision<F: Fn(f32) -> f32, I: IntoIterator<Item = f32>>(
+        range: RangeInclusive<f32>,
+        to_scale: F,
+        labels: I,
+        decimals: usize,
     ) -> Self {
         let length = range.end() - range.start();
 
@@ -748,7 +851,7 @@ impl<'a> HorizontalAxis<'a> {
             .into_iter()
             .map(|t| {
                 let l = (to_scale)(t);
-                Label::new(t, format!("{:.0}", l), 12.0)
+                Label::new(t, format!("{l:.decimals$}"), 12.0)
             })
             .collect();
 
@@ -779,7 +882,10 @@ impl<'a> HorizontalAxis<'a> {
         let offset = -min % tick_distance;
         let labels = (0..=tick_amount).map(|t| offset + min + t as f32 * tick_distance);
 
-        Self::with_labels(range, to_scale, labels)
+        // the scaled ticks can be less than one apart, e.g. in seconds
+        let decimals = decimals(to_scale(tick_distance) - to_scale(0.0));
+
+        Self::with_precision(range, to_scale, labels, decimals)
     }
 
     pub fn scale(mut self, scale: Scale) -> Self {
@@ -846,9 +952,10 @@ impl<'a> VerticalAxis<'a> {
 
         let min = *range.start();
         let offset = -min % tick_distance;
+        let decimals = decimals(tick_distance);
         let labels = (0..=tick_amount)
             .map(|t| offset + min + t as f32 * tick_distance)
-            .map(|l| Label::new(l, format!("{:.0}", l), 12.0));
+            .map(|l| Label::new(l, format!("{l:.decimals$}"), 12.0));
 
         let min_label_width = labels
             .clone()
@@ -957,8 +1064,14 @@ fn min_bounds(content: &str, font_size: Pixels) -> iced::Size {
     paragraph.min_bounds()
 }
 
-fn time_scale(index: f32, sample_rate: f32) -> f32 {
-    index / sample_rate * 1000.0
+/// Decimals of labels, that are `step` apart.
+fn decimals(step: f32) -> usize {
+    let step = step.abs();
+    if step == 0.0 || !step.is_finite() {
+        return 0;
+    }
+
+    (-step.log10().floor()).clamp(0.0, 6.0) as usize
 }
 
 fn percent_full_scale(s: f32) -> f32 {
@@ -3,8 +3,8 @@ use iced::{
 };
 
 use crate::{
-    data::SampleRate,
-    screen::main::chart::{HorizontalAxis, VerticalAxis, Zoom, db_full_scale, time_scale},
+    data::{SampleRate, chart::TimeSeriesUnit},
+    screen::main::chart::{HorizontalAxis, VerticalAxis, Zoom, db_full_scale},
 };
 
 pub fn record_waveform<'a>(
@@ -15,7 +15,7 @@ pub fn record_waveform<'a>(
     canvas::Canvas::new(Recording {
         datapoints: signal.iter().copied().enumerate(),
         y_to_float: |s| s,
-        to_x_scale: move |i| time_scale(i, sample_rate.into()),
+        to_x_scale: move |i| TimeSeriesUnit::Milliseconds.from_samples(i, sample_rate.into()),
         to_y_scale: move |s| db_full_scale(s),
         zoom: Zoom::default(),
         offset: 0,
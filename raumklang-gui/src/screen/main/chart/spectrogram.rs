@@ -1,18 +1,28 @@
-use std::time::Duration;
+mod plane;
+
+pub use plane::Plane;
 
 use crate::{
-    data::{self},
+    data::{
+        self,
+        chart::{Colormap, TimeSeriesUnit},
+    },
     screen::main::chart::Scale,
+    unit,
 };
 
 use super::{HorizontalAxis, Offset, VerticalAxis, Zoom};
 
 use iced::{
-    Event, Point, Rectangle, Renderer, Size, Vector,
+    Event, Font, Pixels, Point, Rectangle, Renderer, Size, Vector,
+    advanced::text,
+    alignment,
     mouse::{self, ScrollDelta},
     widget::canvas::{self},
 };
-use raumklang_core::dbfs;
+
+/// Level range in dB, that is mapped onto the color gradient.
+const DYNAMIC_RANGE: f32 = 50.0;
 
 #[derive(Debug, Clone)]
 pub enum Interaction {
@@ -28,6 +38,11 @@ pub struct Spectrogram<'a> {
     // pub to_x_scale: ScaleX,
     pub zoom: Zoom,
     pub offset: Offset,
+    pub normalization: data::spectrogram::Normalization,
+    /// Added to the normalized levels in dB, before they are mapped to colors.
+    pub level_offset: f32,
+    pub colormap: Colormap,
+    pub time_unit: TimeSeriesUnit,
 }
 
 #[derive(Default)]
@@ -91,6 +106,10 @@ impl<'a> canvas::Program<Interaction, iced::Theme> for Spectrogram<'a> {
                     Some(canvas::Action::publish(Interaction::ZoomChanged(new_zoom)))
                 }
             }
+            // the cursor readout is drawn on every redraw
+            Event::Mouse(mouse::Event::CursorMoved { .. } | mouse::Event::CursorLeft) => {
+                Some(canvas::Action::request_redraw())
+            }
             _ => None,
         }
     }
@@ -99,103 +118,247 @@ impl<'a> canvas::Program<Interaction, iced::Theme> for Spectrogram<'a> {
         &self,
         _state: &Self::State,
         renderer: &Renderer,
-        _theme: &iced::Theme,
+        theme: &iced::Theme,
         bounds: Rectangle,
-        _cursor: iced::advanced::mouse::Cursor,
+        cursor: iced::advanced::mouse::Cursor,
     ) -> Vec<canvas::Geometry<Renderer>> {
+        let Some(layout) = Layout::new(
+            self.datapoints,
+            self.zoom,
+            self.offset,
+            self.time_unit,
+            bounds,
+        ) else {
+            return vec![];
+        };
+
+        // the plane itself is rendered on the GPU, see [`Plane`]
         let geometry = self.cache.draw(renderer, bounds.size(), |frame| {
-            let frequency_responses = self.datapoints.iter();
+            let Layout {
+                ref x_axis,
+                ref y_axis,
+                plane,
+                ..
+            } = layout;
+
+            frame.with_save(|frame| {
+                frame.translate(Vector::new(y_axis.width, 0.0));
+
+                x_axis.draw(frame, plane.width);
+            });
+
+            y_axis.draw(frame, plane.height);
 
-            let Some(first) = frequency_responses.clone().next() else {
-                return;
+            let title = |content: &str, position, align_x| canvas::Text {
+                content: content.to_string(),
+                position,
+                size: Pixels(12.0),
+                color: iced::Color::WHITE,
+                align_x,
+                font: Font::MONOSPACE,
+                ..canvas::Text::default()
             };
 
-            let max_bin = first.data.iter().count();
-            let sample_rate = first.sample_rate;
-            let len = max_bin * 2 + 1;
-            let resolution = sample_rate as f32 / len as f32;
+            frame.fill_text(title(
+                &format!("Time [{}]", self.time_unit.symbol()),
+                Point::new(y_axis.width + 4.0, 4.0),
+                text::Alignment::Left,
+            ));
+            frame.fill_text(title(
+                "Frequency [Hz]",
+                Point::new(y_axis.width + plane.width - 4.0, plane.height - 16.0),
+                text::Alignment::Right,
+            ));
+
+            self.draw_legend(frame, &layout);
+        });
 
-            let x_min = f32::from(self.offset) * f32::from(self.zoom);
-            let x_max = (max_bin as f32 + f32::from(self.offset)) * f32::from(self.zoom);
+        let Some(position) = cursor.position_in(bounds) else {
+            return vec![geometry];
+        };
+
+        let Some((slice, bin)) = layout.locate(position) else {
+            return vec![geometry];
+        };
+
+        let mut overlay = canvas::Frame::new(renderer, bounds.size());
+        let palette = theme.extended_palette();
+
+        overlay.fill_rectangle(
+            Point::new(position.x, 0.0),
+            Size::new(1.0, layout.plane.height),
+            palette.background.base.text.scale_alpha(0.6),
+        );
+        overlay.fill_rectangle(
+            Point::new(layout.y_axis.width, position.y),
+            Size::new(layout.plane.width, 1.0),
+            palette.background.base.text.scale_alpha(0.6),
+        );
+
+        let level = self
+            .datapoints
+            .level(self.normalization, slice, bin)
+            .map_or_else(|| "-".to_string(), |level| unit::level(level, 1));
+
+        let readout = format!(
+            "{}\n{}\n{level}",
+            unit::time(
+                self.time_unit.from_samples(
+                    self.datapoints.position(slice),
+                    self.datapoints.sample_rate()
+                ),
+                self.time_unit
+            ),
+            unit::frequency(bin as f32 * layout.resolution),
+        );
+
+        let size = Size::new(110.0, 48.0);
+        // keep the readout inside of the plane
+        let x = if position.x + size.width + 8.0 > layout.y_axis.width + layout.plane.width {
+            position.x - size.width - 8.0
+        } else {
+            position.x + 8.0
+        };
+        let y = (position.y - size.height - 8.0).max(0.0);
+
+        overlay.fill_rectangle(
+            Point::new(x, y),
+            size,
+            palette.background.base.color.scale_alpha(0.8),
+        );
+        overlay.fill_text(canvas::Text {
+            content: readout,
+            position: Point::new(x + 4.0, y + 4.0),
+            size: Pixels(12.0),
+            color: palette.background.base.text,
+            font: Font::MONOSPACE,
+            ..canvas::Text::default()
+        });
 
-            let min_index = x_min.floor() as usize;
-            let max_index = x_max.floor() as usize;
+        vec![geometry, overlay.into_geometry()]
+    }
+}
 
-            let x_min = x_min * resolution;
-            let x_max = x_max * resolution;
+impl Spectrogram<'_> {
+    /// Color scale right of the plane, the labels take the level offset into
+    /// account.
+    fn draw_legend(&self, frame: &mut canvas::Frame, layout: &Layout) {
+        let x = layout.y_axis.width + layout.plane.width + 8.0;
+        let height = layout.plane.height;
+        let steps = 50;
+        let step_height = height / steps as f32;
+
+        for step in 0..steps {
+            let s = 1.0 - step as f32 / steps as f32;
+            let color = self.colormap.eval(s.into());
+
+            frame.fill_rectangle(
+                Point::new(x, step as f32 * step_height),
+                Size::new(12.0, step_height.ceil()),
+                iced::Color::from_rgb8(color.r, color.g, color.b),
+            );
+        }
 
-            let x_range = x_min..=x_max;
+        for level in (0..=DYNAMIC_RANGE as usize).step_by(10) {
+            let y = level as f32 / DYNAMIC_RANGE * height;
+
+            frame.fill_text(canvas::Text {
+                content: format!("{:.0}", -(level as f32) - self.level_offset),
+                position: Point::new(x + 16.0, y.clamp(6.0, height - 6.0)),
+                size: Pixels(12.0),
+                color: iced::Color::WHITE,
+                align_y: alignment::Vertical::Center,
+                font: Font::MONOSPACE,
+                ..canvas::Text::default()
+            });
+        }
+    }
+}
 
-            let labels = [0, 20, 50, 100, 1000, 10_000, 20_000]
-                .into_iter()
-                .map(|l| l as f32);
+/// Width of the color scale legend including its labels.
+const LEGEND_WIDTH: f32 = 56.0;
+
+/// Placement of the spectrogram on the canvas.
+struct Layout<'a> {
+    x_axis: HorizontalAxis<'a>,
+    y_axis: VerticalAxis<'a>,
+    plane: Rectangle,
+    /// Lower frequency of the visible range in Hz.
+    x_min: f32,
+    /// Frequency resolution in Hz per bin.
+    resolution: f32,
+    row_height: f32,
+    rows: usize,
+}
 
-            let x_axis = HorizontalAxis::with_labels(x_range, |s| s, labels).scale(Scale::Log);
+impl Layout<'_> {
+    fn new(
+        datapoints: &data::Spectrogram,
+        zoom: Zoom,
+        offset: Offset,
+        time_unit: TimeSeriesUnit,
+        bounds: Rectangle,
+    ) -> Option<Self> {
+        let first = datapoints.iter().next()?;
 
-            let y_min = -(Duration::from(self.datapoints.span_before_peak).as_millis() as f32);
-            let y_max = Duration::from(self.datapoints.span_after_peak).as_millis() as f32;
+        let max_bin = first.data.iter().count();
+        let sample_rate = first.sample_rate;
+        let len = raumklang_core::FrequencyResponse::fft_len(max_bin);
+        let resolution = sample_rate as f32 / len as f32;
 
-            let y_range = y_min..=y_max;
-            let y_axis = VerticalAxis::new(y_range, 10);
+        let x_min = f32::from(offset) * f32::from(zoom);
+        let x_max = (max_bin as f32 + f32::from(offset)) * f32::from(zoom);
 
-            let plane = Rectangle::new(
-                Point::new(bounds.x, bounds.y),
-                Size::new(bounds.width, bounds.height - x_axis.height),
-            );
+        let x_min = x_min * resolution;
+        let x_max = x_max * resolution;
 
-            let pixels_per_unit_x = plane.width / x_axis.length;
-            // let pixels_per_unit_y = plane.height / y_axis.length;
-            let pixels_per_unit_y = plane.height / frequency_responses.clone().count() as f32;
-
-            let gradient = colorous::TURBO;
-
-            let log_scale = |p: f32| (p.log10() / x_axis.length.log10()) * x_axis.length;
-
-            for (si, fr) in frequency_responses.enumerate() {
-                for (i, s) in fr
-                    .data
-                    .iter()
-                    .skip(min_index)
-                    .take(max_index)
-                    .copied()
-                    .map(dbfs)
-                    .map(|s| 1.0 - s.clamp(-50.0, 0.0) / -40.0)
-                    .enumerate()
-                {
-                    let color = gradient.eval_continuous(s.into());
-
-                    let y = plane.height
-                        - x_axis.height
-                        - si as f32 * pixels_per_unit_y
-                        - pixels_per_unit_y;
-
-                    let width = log_scale((i + 1) as f32)
-                        - log_scale(i as f32).clamp(0.0, f32::MAX) * pixels_per_unit_x;
-
-                    let pixel = Rectangle {
-                        x: log_scale(i as f32 * resolution) * pixels_per_unit_x + y_axis.width,
-                        y,
-                        width,
-                        height: pixels_per_unit_y,
-                    };
+        let x_range = x_min..=x_max;
 
-                    frame.fill_rectangle(
-                        pixel.position(),
-                        pixel.size(),
-                        iced::Color::from_rgb8(color.r, color.g, color.b),
-                    );
-                }
-            }
+        let labels = [0, 20, 50, 100, 1000, 10_000, 20_000]
+            .into_iter()
+            .map(|l| l as f32);
 
-            frame.with_save(|frame| {
-                frame.translate(Vector::new(y_axis.width, 0.0));
+        let x_axis = HorizontalAxis::with_labels(x_range, |s| s, labels).scale(Scale::Log);
 
-                x_axis.draw(frame, plane.width);
-            });
+        let sample_rate = sample_rate as f32;
+        let y_min = time_unit.from_samples(-f32::from(datapoints.span_before_peak), sample_rate);
+        let y_max = time_unit.from_samples(f32::from(datapoints.span_after_peak), sample_rate);
 
-            y_axis.draw(frame, plane.height);
-        });
+        let y_axis = VerticalAxis::new(y_min..=y_max, 10);
+
+        let plane = Rectangle::new(
+            Point::new(bounds.x, bounds.y),
+            Size::new(
+                bounds.width - y_axis.width - LEGEND_WIDTH,
+                bounds.height - x_axis.height,
+            ),
+        );
+
+        let rows = datapoints.len();
+
+        Some(Self {
+            row_height: plane.height / rows as f32,
+            x_axis,
+            y_axis,
+            plane,
+            x_min,
+            resolution,
+            rows,
+        })
+    }
+
+    /// Slice and bin at the `position` relative to the canvas.
+    fn locate(&self, position: Point) -> Option<(usize, usize)> {
+        let x = (position.x - self.y_axis.width) / self.plane.width;
+        if !(0.0..=1.0).contains(&x) || !(0.0..self.plane.height).contains(&position.y) {
+            return None;
+        }
+
+        let frequency = self.x_min + self.x_axis.length.powf(x);
+        let bin = (frequency / self.resolution).round() as usize;
+
+        let row = ((self.plane.height - position.y) / self.row_height) as usize;
 
-        vec![geometry]
+        Some((row.min(self.rows - 1), bin))
     }
 }
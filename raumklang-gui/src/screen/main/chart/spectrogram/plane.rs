@@ -0,0 +1,487 @@
+//! Renders the spectrogram plane on the GPU. The level matrix is uploaded
+//! once as texture, zooming, scrolling and changing the colors only update
+//! a few uniforms.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Weak},
+};
+
+use crate::data::{
+    self,
+    chart::Colormap,
+    spectrogram::{Matrix, Normalization},
+};
+
+use super::{DYNAMIC_RANGE, Layout, Offset, Zoom};
+
+use iced::{
+    Rectangle, mouse,
+    widget::shader::{self, Viewport, wgpu, wgpu::util::DeviceExt},
+};
+
+/// Number of colors of the color map texture.
+const COLORMAP_STEPS: u32 = 256;
+
+/// Frames an unused plane is kept on the GPU.
+const RETENTION: u64 = 64;
+
+pub struct Plane<'a> {
+    pub datapoints: &'a data::Spectrogram,
+    pub zoom: Zoom,
+    pub offset: Offset,
+    pub normalization: Normalization,
+    pub level_offset: f32,
+    pub colormap: Colormap,
+}
+
+#[derive(Debug)]
+pub struct Primitive {
+    matrix: Arc<Matrix>,
+    colormap: Colormap,
+    /// Position and size of the plane in logical pixels.
+    plane: Rectangle,
+    x_min: f32,
+    x_length: f32,
+    resolution: f32,
+    level_offset: f32,
+    per_slice: bool,
+}
+
+pub struct Pipeline {
+    pipeline: wgpu::RenderPipeline,
+    layout: wgpu::BindGroupLayout,
+    srgb: bool,
+    max_width: usize,
+    matrices: Vec<Uploaded>,
+    colormaps: Vec<(Colormap, wgpu::TextureView)>,
+    instances: BTreeMap<(u32, u32), Instance>,
+    frame: u64,
+}
+
+struct Uploaded {
+    matrix: Weak<Matrix>,
+    levels: wgpu::TextureView,
+    peaks: wgpu::TextureView,
+}
+
+/// A plane on the screen, identified by its position.
+struct Instance {
+    matrix: Weak<Matrix>,
+    colormap: Colormap,
+    uniforms: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    /// Part of the plane, that is visible, in physical pixels.
+    plane: Rectangle,
+    last_used: u64,
+}
+
+impl<Message> shader::Program<Message> for Plane<'_> {
+    type State = ();
+    type Primitive = Primitive;
+
+    fn draw(&self, _state: &(), _cursor: mouse::Cursor, bounds: Rectangle) -> Primitive {
+        let layout = Layout::new(self.datapoints, self.zoom, self.offset, bounds);
+
+        let (plane, x_min, x_length, resolution) = match layout {
+            Some(layout) => (
+                Rectangle {
+                    x: bounds.x + layout.y_axis.width,
+                    ..layout.plane
+                },
+                layout.x_min,
+                layout.x_axis.length,
+                layout.resolution,
+            ),
+            None => (Rectangle::default(), 0.0, 1.0, 1.0),
+        };
+
+        Primitive {
+            matrix: self.datapoints.matrix().clone(),
+            colormap: self.colormap,
+            plane,
+            x_min,
+            x_length,
+            resolution,
+            level_offset: self.level_offset,
+            per_slice: self.normalization == Normalization::PerSlice,
+        }
+    }
+}
+
+impl Primitive {
+    fn key(&self) -> (u32, u32) {
+        (self.plane.x.to_bits(), self.plane.y.to_bits())
+    }
+
+    fn uniforms(&self, plane: Rectangle) -> Vec<u8> {
+        [
+            plane.x,
+            plane.y,
+            plane.width,
+            plane.height,
+            self.x_min,
+            self.x_length,
+            self.resolution,
+            self.matrix.columns as f32,
+            self.level_offset,
+            if self.per_slice { 1.0 } else { 0.0 },
+            DYNAMIC_RANGE,
+            0.0,
+        ]
+        .into_iter()
+        .flat_map(f32::to_le_bytes)
+        .collect()
+    }
+}
+
+impl shader::Primitive for Primitive {
+    type Pipeline = Pipeline;
+
+    fn prepare(
+        &self,
+        pipeline: &mut Pipeline,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        _bounds: &Rectangle,
+        viewport: &Viewport,
+    ) {
+        pipeline.frame += 1;
+        let frame = pipeline.frame;
+        pipeline.matrices.retain(|u| u.matrix.strong_count() > 0);
+        pipeline
+            .instances
+            .retain(|_, instance| instance.last_used + RETENTION > frame);
+
+        if self.matrix.rows == 0 || self.matrix.columns == 0 {
+            pipeline.instances.remove(&self.key());
+            return;
+        }
+
+        let plane = self.plane * viewport.scale_factor() as f32;
+        let uniforms = self.uniforms(plane);
+
+        let is_current = |instance: &Instance| {
+            instance.colormap == self.colormap
+                && instance
+                    .matrix
+                    .upgrade()
+                    .is_some_and(|matrix| Arc::ptr_eq(&matrix, &self.matrix))
+        };
+
+        let instance = pipeline.instances.get_mut(&self.key());
+        if let Some(instance) = instance.filter(|instance| is_current(instance)) {
+            queue.write_buffer(&instance.uniforms, 0, &uniforms);
+            instance.plane = plane;
+            instance.last_used = frame;
+
+            return;
+        }
+
+        let instance = pipeline.instance(device, queue, self, plane, &uniforms);
+        pipeline.instances.insert(self.key(), instance);
+    }
+
+    fn render(
+        &self,
+        pipeline: &Pipeline,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        clip_bounds: &Rectangle<u32>,
+    ) {
+        let Some(instance) = pipeline.instances.get(&self.key()) else {
+            return;
+        };
+
+        let clip = Rectangle {
+            x: clip_bounds.x as f32,
+            y: clip_bounds.y as f32,
+            width: clip_bounds.width as f32,
+            height: clip_bounds.height as f32,
+        };
+
+        let Some(scissor) = instance.plane.intersection(&clip).and_then(Rectangle::snap) else {
+            return;
+        };
+        if scissor.width == 0 || scissor.height == 0 {
+            return;
+        }
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("spectrogram"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+                depth_slice: None,
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        pass.set_scissor_rect(scissor.x, scissor.y, scissor.width, scissor.height);
+        pass.set_pipeline(&pipeline.pipeline);
+        pass.set_bind_group(0, &instance.bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+}
+
+impl shader::Pipeline for Pipeline {
+    fn new(device: &wgpu::Device, _queue: &wgpu::Queue, format: wgpu::TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("spectrogram"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("plane.wgsl").into()),
+        });
+
+        let texture = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("spectrogram"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                texture(1),
+                texture(2),
+                texture(3),
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("spectrogram"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("spectrogram"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[],
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            pipeline,
+            layout,
+            srgb: format.is_srgb(),
+            max_width: device.limits().max_texture_dimension_2d as usize,
+            matrices: vec![],
+            colormaps: vec![],
+            instances: BTreeMap::new(),
+            frame: 0,
+        }
+    }
+}
+
+impl Pipeline {
+    fn instance(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        primitive: &Primitive,
+        plane: Rectangle,
+        uniforms: &[u8],
+    ) -> Instance {
+        let (levels, peaks) = self.matrix(device, queue, &primitive.matrix);
+        let colormap = self.colormap(device, queue, primitive.colormap);
+
+        let uniforms = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("spectrogram uniforms"),
+            contents: uniforms,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("spectrogram"),
+            layout: &self.layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniforms.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&levels),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(&peaks),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::TextureView(&colormap),
+                },
+            ],
+        });
+
+        Instance {
+            matrix: Arc::downgrade(&primitive.matrix),
+            colormap: primitive.colormap,
+            uniforms,
+            bind_group,
+            plane,
+            last_used: self.frame,
+        }
+    }
+
+    /// Textures of the levels and the peaks of each row, uploaded on first
+    /// use.
+    fn matrix(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        matrix: &Arc<Matrix>,
+    ) -> (wgpu::TextureView, wgpu::TextureView) {
+        let uploaded = self.matrices.iter().find(|uploaded| {
+            uploaded
+                .matrix
+                .upgrade()
+                .is_some_and(|m| Arc::ptr_eq(&m, matrix))
+        });
+
+        if let Some(uploaded) = uploaded {
+            return (uploaded.levels.clone(), uploaded.peaks.clone());
+        }
+
+        // the bins are combined by their maximum, if there are more than
+        // the GPU supports as texture width
+        let group = matrix.columns.div_ceil(self.max_width);
+        let levels: Vec<f32> = matrix
+            .levels
+            .chunks(matrix.columns)
+            .flat_map(|row| {
+                row.chunks(group)
+                    .map(|bins| bins.iter().copied().fold(f32::MIN, f32::max))
+            })
+            .collect();
+
+        let texture = |label, width: usize, height: usize, data: &[f32]| {
+            let data: Vec<u8> = data.iter().copied().flat_map(f32::to_le_bytes).collect();
+
+            device
+                .create_texture_with_data(
+                    queue,
+                    &wgpu::TextureDescriptor {
+                        label: Some(label),
+                        size: wgpu::Extent3d {
+                            width: width as u32,
+                            height: height as u32,
+                            depth_or_array_layers: 1,
+                        },
+                        mip_level_count: 1,
+                        sample_count: 1,
+                        dimension: wgpu::TextureDimension::D2,
+                        format: wgpu::TextureFormat::R32Float,
+                        usage: wgpu::TextureUsages::TEXTURE_BINDING,
+                        view_formats: &[],
+                    },
+                    wgpu::util::TextureDataOrder::LayerMajor,
+                    &data,
+                )
+                .create_view(&wgpu::TextureViewDescriptor::default())
+        };
+
+        let levels = texture(
+            "spectrogram levels",
+            matrix.columns.div_ceil(group),
+            matrix.rows,
+            &levels,
+        );
+        let peaks = texture("spectrogram peaks", matrix.rows, 1, &matrix.peaks);
+
+        self.matrices.push(Uploaded {
+            matrix: Arc::downgrade(matrix),
+            levels: levels.clone(),
+            peaks: peaks.clone(),
+        });
+
+        (levels, peaks)
+    }
+
+    fn colormap(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        colormap: Colormap,
+    ) -> wgpu::TextureView {
+        if let Some((_, view)) = self.colormaps.iter().find(|(c, _)| *c == colormap) {
+            return view.clone();
+        }
+
+        let data: Vec<u8> = (0..COLORMAP_STEPS)
+            .map(|step| colormap.eval_rational(step as usize, COLORMAP_STEPS as usize))
+            .flat_map(|color| [color.r, color.g, color.b, 255])
+            .collect();
+
+        let view = device
+            .create_texture_with_data(
+                queue,
+                &wgpu::TextureDescriptor {
+                    label: Some("spectrogram colormap"),
+                    size: wgpu::Extent3d {
+                        width: COLORMAP_STEPS,
+                        height: 1,
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    // the colors are given in sRGB, converting them lets the
+                    // GPU encode them correctly for the target
+                    format: if self.srgb {
+                        wgpu::TextureFormat::Rgba8UnormSrgb
+                    } else {
+                        wgpu::TextureFormat::Rgba8Unorm
+                    },
+                    usage: wgpu::TextureUsages::TEXTURE_BINDING,
+                    view_formats: &[],
+                },
+                wgpu::util::TextureDataOrder::LayerMajor,
+                &data,
+            )
+            .create_view(&wgpu::TextureViewDescriptor::default());
+
+        self.colormaps.push((colormap, view.clone()));
+
+        view
+    }
+}
@@ -0,0 +1,56 @@
+struct Uniforms {
+    // position and size of the plane in physical pixels
+    plane: vec4<f32>,
+    // lower frequency of the visible range in Hz
+    x_min: f32,
+    // frequency range of the visible range in Hz
+    x_length: f32,
+    // frequency resolution in Hz per bin
+    resolution: f32,
+    // number of bins before they were decimated to fit into the texture
+    bins: f32,
+    level_offset: f32,
+    // 1.0 if the levels are normalized to the peak of their row
+    per_slice: f32,
+    dynamic_range: f32,
+    _padding: f32,
+}
+
+@group(0) @binding(0) var<uniform> uniforms: Uniforms;
+@group(0) @binding(1) var levels: texture_2d<f32>;
+@group(0) @binding(2) var peaks: texture_2d<f32>;
+@group(0) @binding(3) var colormap: texture_2d<f32>;
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    // a triangle covering the whole target, it is cut to the plane by the
+    // scissor rectangle
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+
+    return vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+}
+
+@fragment
+fn fs_main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    let uv = (position.xy - uniforms.plane.xy) / uniforms.plane.zw;
+    let size = textureDimensions(levels);
+
+    let frequency = uniforms.x_min + pow(uniforms.x_length, uv.x);
+    let bin = round(frequency / uniforms.resolution);
+    if bin >= uniforms.bins {
+        discard;
+    }
+
+    let column = min(u32(bin / uniforms.bins * f32(size.x)), size.x - 1u);
+    let row = min(u32((1.0 - uv.y) * f32(size.y)), size.y - 1u);
+
+    var level = textureLoad(levels, vec2<u32>(column, row), 0).r;
+    level -= uniforms.per_slice * textureLoad(peaks, vec2<u32>(row, 0u), 0).r;
+    level += uniforms.level_offset;
+
+    let t = 1.0 - clamp(level, -uniforms.dynamic_range, 0.0) / -uniforms.dynamic_range;
+    let steps = textureDimensions(colormap).x;
+    let step = min(u32(t * f32(steps)), steps - 1u);
+
+    return textureLoad(colormap, vec2<u32>(step, 0u), 0);
+}
@@ -3,10 +3,13 @@ use super::{HorizontalAxis, Offset, VerticalAxis, Zoom};
 use iced::{
     Event, Point, Rectangle, Renderer, Size, Vector,
     mouse::{self, ScrollDelta},
-    widget::canvas::{self},
+    widget::canvas::{self, Path, Stroke},
 };
 
-use std::{cmp::Ordering, ops::RangeInclusive};
+use std::{ops::RangeInclusive, time::Duration};
+
+/// Window of the RMS envelope.
+const RMS_WINDOW: Duration = Duration::from_millis(10);
 
 #[derive(Debug, Clone)]
 pub enum Interaction {
@@ -14,15 +17,18 @@ pub enum Interaction {
     OffsetChanged(Offset),
 }
 
-pub struct Waveform<'a, I, Y, ScaleX>
+pub struct Waveform<'a, I, Y, ScaleX, ScaleY>
 where
     I: Iterator<Item = Y>,
 {
     pub datapoints: I,
     pub cache: &'a canvas::Cache,
-    pub cmp: fn(&Y, &Y) -> Ordering,
     pub y_to_float: fn(Y) -> f32,
     pub to_x_scale: ScaleX,
+    pub to_y_scale: ScaleY,
+    /// Draws the moving RMS over the samples.
+    pub rms_envelope: bool,
+    pub sample_rate: f32,
     pub zoom: Zoom,
     pub offset: Offset,
     pub y_range: Option<RangeInclusive<Y>>,
@@ -33,11 +39,13 @@ pub struct State {
     shift_pressed: bool,
 }
 
-impl<'a, I, Y, ScaleX> canvas::Program<Interaction, iced::Theme> for Waveform<'a, I, Y, ScaleX>
+impl<'a, I, Y, ScaleX, ScaleY> canvas::Program<Interaction, iced::Theme>
+    for Waveform<'a, I, Y, ScaleX, ScaleY>
 where
     I: Iterator<Item = Y> + Clone + 'a,
-    Y: Copy + std::iter::Sum + PartialOrd,
+    Y: Copy,
     ScaleX: Fn(f32) -> f32,
+    ScaleY: Fn(f32) -> f32,
 {
     type State = State;
 
@@ -69,7 +77,7 @@ where
                 };
 
                 if state.shift_pressed {
-                    let diff = (f32::from(self.zoom) * 44_100_f32).ceil() as isize;
+                    let diff = (f32::from(self.zoom) * self.sample_rate).ceil() as isize;
 
                     let new_offset = if y.is_sign_positive() {
                         self.offset.saturating_add(diff)
@@ -128,23 +136,28 @@ where
             let x_range = x_min..=x_max;
             let x_axis = HorizontalAxis::new(x_range, &self.to_x_scale, 10);
 
-            let datapoints = self.datapoints.clone().skip(min_index).take(max_index);
+            let datapoints: Vec<f32> = self
+                .datapoints
+                .clone()
+                .skip(min_index)
+                .take(max_index)
+                .map(self.y_to_float)
+                .collect();
 
-            let Some(y_min) = datapoints.clone().min_by(self.cmp) else {
-                return;
-            };
-            let Some(y_max) = datapoints.clone().max_by(self.cmp) else {
-                return;
-            };
+            let scaled = datapoints.iter().map(|s| (self.to_y_scale)(*s));
 
             let y_range = if let Some(range) = self.y_range.as_ref() {
-                let y_min = (self.y_to_float)(*range.start());
-                let y_max = (self.y_to_float)(*range.end());
+                let y_min = (self.to_y_scale)((self.y_to_float)(*range.start()));
+                let y_max = (self.to_y_scale)((self.y_to_float)(*range.end()));
 
                 y_min..=y_max
             } else {
-                let y_min = (self.y_to_float)(y_min);
-                let y_max = (self.y_to_float)(y_max);
+                let Some(y_min) = scaled.clone().min_by(f32::total_cmp) else {
+                    return;
+                };
+                let Some(y_max) = scaled.clone().max_by(f32::total_cmp) else {
+                    return;
+                };
 
                 y_min..=y_max
             };
@@ -152,7 +165,7 @@ where
 
             let plane = Rectangle::new(
                 Point::new(bounds.x, bounds.y),
-                Size::new(bounds.width, bounds.height - x_axis.height),
+                Size::new(bounds.width - y_axis.width, bounds.height - x_axis.height),
             );
 
             let pixels_per_unit_x = plane.width / x_axis.length;
@@ -164,27 +177,107 @@ where
                 pixels_per_unit_x
             };
 
-            for (i, datapoint) in datapoints.enumerate() {
-                let value = (self.y_to_float)(datapoint);
-
-                let bar_height = value * pixels_per_unit_y;
-                let bar = Rectangle {
-                    x: (i as f32 - x_min) * pixels_per_unit_x,
-                    y: plane.height + y_axis.min * pixels_per_unit_y,
-                    width: bar_width,
-                    height: bar_height,
-                };
+            // signed values grow from zero, levels from the bottom
+            let y_max = y_axis.min + y_axis.length;
+            let baseline = if y_axis.min < 0.0 && y_max > 0.0 {
+                0.0
+            } else {
+                y_axis.min
+            };
 
-                frame.fill_rectangle(bar.position(), bar.size(), palette.secondary.weak.color);
-            }
+            let to_x = |i: usize| (i as f32 - x_min) * pixels_per_unit_x;
+            let to_y = |value: f32| plane.height - (value - y_axis.min) * pixels_per_unit_y;
 
             frame.with_save(|frame| {
                 frame.translate(Vector::new(y_axis.width, 0.0));
 
+                for (i, value) in scaled.enumerate() {
+                    let top = to_y(value.max(baseline));
+                    let bottom = to_y(value.min(baseline));
+
+                    frame.fill_rectangle(
+                        Point::new(to_x(i), top),
+                        Size::new(bar_width, bottom - top),
+                        palette.secondary.weak.color,
+                    );
+                }
+
+                if self.rms_envelope {
+                    let window = (RMS_WINDOW.as_secs_f32() * self.sample_rate) as usize;
+                    let envelope = rms_envelope(&datapoints, window);
+
+                    let signs: &[f32] = if baseline == 0.0 {
+                        &[1.0, -1.0]
+                    } else {
+                        &[1.0]
+                    };
+                    for sign in signs {
+                        let path = Path::new(|b| {
+                            for (i, rms) in envelope.iter().enumerate() {
+                                let point =
+                                    Point::new(to_x(i), to_y((self.to_y_scale)(sign * rms)));
+
+                                if i == 0 {
+                                    b.move_to(point);
+                                } else {
+                                    b.line_to(point);
+                                }
+                            }
+                        });
+
+                        frame.stroke(
+                            &path,
+                            Stroke::default()
+                                .with_width(1.5)
+                                .with_color(palette.primary.strong.color),
+                        );
+                    }
+                }
+
                 x_axis.draw(frame, plane.width);
             });
+
+            y_axis.draw(frame, plane.height);
         });
 
         vec![geometry]
     }
 }
+
+/// Moving RMS of the `samples` over a centered `window`.
+fn rms_envelope(samples: &[f32], window: usize) -> Vec<f32> {
+    let half = window / 2;
+
+    let mut sums = Vec::with_capacity(samples.len() + 1);
+    sums.push(0.0f64);
+    for s in samples {
+        let sum = sums[sums.len() - 1] + f64::from(*s).powi(2);
+        sums.push(sum);
+    }
+
+    (0..samples.len())
+        .map(|i| {
+            let start = i.saturating_sub(half);
+            let end = (i + half + 1).min(samples.len());
+
+            ((sums[end] - sums[start]) / (end - start) as f64).sqrt() as f32
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rms_envelope_of_a_square_wave() {
+        let samples: Vec<f32> = (0..100)
+            .map(|i| if i % 2 == 0 { 0.5 } else { -0.5 })
+            .collect();
+
+        let envelope = rms_envelope(&samples, 10);
+
+        assert_eq!(envelope.len(), samples.len());
+        assert!(envelope.iter().all(|rms| (rms - 0.5).abs() < 1e-6));
+    }
+}
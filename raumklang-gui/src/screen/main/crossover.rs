@@ -0,0 +1,536 @@
+use std::{fmt, sync::Arc};
+
+use iced::{
+    Alignment::Center,
+    Color, Element,
+    Length::{self, Fill},
+    widget::{button, center, checkbox, column, container, pick_list, row, slider, text},
+};
+use iced_aksel::{axis::MarkerPosition, plot::PlotPoint};
+use raumklang_core::crossover::{self, Filter, FilterAlignment, Integration, Kind};
+
+use crate::{
+    data,
+    screen::main::{
+        AxisId, DB_AXIS_ID, FREQ_AXIS_ID, format_db_label, format_frequency_label,
+        frequency_response, modal::operation,
+    },
+    ui::{self, measurement},
+};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    MeasurementSelected(Way, operation::Operand),
+    AlignmentSelected(Way, FilterAlignment),
+    OrderSelected(Way, u8),
+    FrequencyChanged(Way, f32),
+    PolarityToggled(Way, bool),
+    DelayChanged(Way, f32),
+    FiltersToggled(bool),
+    IntegrationApplied,
+    Chart(frequency_response::Message),
+}
+
+/// Preview of two measured drivers, e.g. woofer and tweeter, combined with
+/// ideal crossover filters.
+#[derive(Debug)]
+pub struct Crossover {
+    pub low: Driver,
+    pub high: Driver,
+    /// Disabled for measurements, that already contain the crossover, e.g.
+    /// a subwoofer and the mains measured on their own.
+    pub filters_enabled: bool,
+    pub simulation: Option<Simulation>,
+}
+
+#[derive(Debug)]
+pub struct Driver {
+    pub measurement: Option<measurement::Id>,
+    pub filter: Filter,
+    /// Delay in seconds
+    pub delay: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Way {
+    Low,
+    High,
+}
+
+/// Filtered responses of both ways and their sum as (frequency, level)
+/// points.
+#[derive(Debug, Clone)]
+pub struct Simulation {
+    low: ui::Curve,
+    high: ui::Curve,
+    sum: ui::Curve,
+    /// Sum with the delay and polarity of the integration
+    aligned: Option<ui::Curve>,
+    /// Best delay and polarity of the high way
+    integration: Option<Integration>,
+    /// Summation loss of the current settings
+    loss: Option<f32>,
+}
+
+const DEFAULT_FREQUENCY: f32 = 2_000.0;
+const MIN_FREQUENCY: f32 = 20.0;
+const MAX_FREQUENCY: f32 = 20_000.0;
+
+const LOW_COLOR: Color = Color::from_rgb(0.3, 0.6, 1.0);
+const HIGH_COLOR: Color = Color::from_rgb(1.0, 0.5, 0.2);
+const SUM_COLOR: Color = Color::from_rgb(0.3, 0.9, 0.4);
+const ALIGNED_COLOR: Color = Color::from_rgb(0.8, 0.4, 0.9);
+
+/// Largest delay of either way, that is tried by the integration.
+const MAX_DELAY: f32 = 0.02;
+
+impl Crossover {
+    pub fn new(low: Option<measurement::Id>, high: Option<measurement::Id>) -> Self {
+        let driver = |measurement, kind| Driver {
+            measurement,
+            filter: Filter::new(kind, FilterAlignment::default(), 4, DEFAULT_FREQUENCY),
+            delay: 0.0,
+        };
+
+        Self {
+            low: driver(low, Kind::LowPass),
+            high: driver(high, Kind::HighPass),
+            filters_enabled: true,
+            simulation: None,
+        }
+    }
+
+    /// Range in Hz, in which both ways overlap, an octave around the
+    /// crossover frequencies.
+    pub fn region(&self) -> (f32, f32) {
+        let (low, high) = (self.low.filter.frequency, self.high.filter.frequency);
+
+        (
+            (low.min(high) / 2.0).max(MIN_FREQUENCY),
+            (low.max(high) * 2.0).min(MAX_FREQUENCY),
+        )
+    }
+
+    pub fn contains(&self, id: measurement::Id) -> bool {
+        self.low.measurement == Some(id) || self.high.measurement == Some(id)
+    }
+
+    /// Applies the change of the settings, returns `true` if the simulation
+    /// needs to be computed again.
+    pub fn update(&mut self, message: Message) -> bool {
+        match message {
+            Message::MeasurementSelected(way, operand) => {
+                self.driver_mut(way).measurement = Some(operand.id);
+                self.simulation = None;
+            }
+            Message::AlignmentSelected(way, alignment) => {
+                let filter = &mut self.driver_mut(way).filter;
+
+                filter.alignment = alignment;
+                // Linkwitz-Riley filters only exist in even orders
+                if alignment == FilterAlignment::LinkwitzRiley {
+                    filter.order = filter.order.next_multiple_of(2).min(Filter::MAX_ORDER);
+                }
+            }
+            Message::OrderSelected(way, order) => self.driver_mut(way).filter.order = order,
+            Message::FrequencyChanged(way, frequency) => {
+                self.driver_mut(way).filter.frequency = frequency
+            }
+            Message::PolarityToggled(way, inverted) => {
+                self.driver_mut(way).filter.inverted = inverted
+            }
+            Message::DelayChanged(way, delay) => self.driver_mut(way).delay = delay,
+            Message::FiltersToggled(enabled) => self.filters_enabled = enabled,
+            Message::IntegrationApplied => {
+                let Some(integration) = self
+                    .simulation
+                    .as_ref()
+                    .and_then(|simulation| simulation.integration.as_ref())
+                else {
+                    return false;
+                };
+
+                self.low.delay = (-integration.delay).max(0.0);
+                self.high.delay = integration.delay.max(0.0);
+                self.high.filter.inverted = integration.inverted;
+            }
+            Message::Chart(_) => return false,
+        }
+
+        true
+    }
+
+    pub fn set_simulation(&mut self, points: Option<SimulatedPoints>) {
+        let curve = |color, points: Vec<(f32, f32)>| {
+            ui::Curve::new(
+                color,
+                points
+                    .into_iter()
+                    .map(|(frequency, level)| PlotPoint::new(frequency, level)),
+            )
+        };
+
+        self.simulation = points.map(|points| Simulation {
+            low: curve(LOW_COLOR, points.low),
+            high: curve(HIGH_COLOR, points.high),
+            sum: curve(SUM_COLOR, points.sum),
+            aligned: points.aligned.map(|aligned| curve(ALIGNED_COLOR, aligned)),
+            integration: points.integration,
+            loss: points.loss,
+        });
+    }
+
+    fn driver_mut(&mut self, way: Way) -> &mut Driver {
+        match way {
+            Way::Low => &mut self.low,
+            Way::High => &mut self.high,
+        }
+    }
+
+    pub fn view<'a>(
+        &'a self,
+        measurements: &'a measurement::List,
+        fr_state: &'a iced_aksel::State<AxisId, f32>,
+    ) -> Element<'a, Message> {
+        let operands: Vec<_> = measurements
+            .loaded()
+            .map(|m| operation::Operand {
+                id: m.id(),
+                name: m.name.clone(),
+            })
+            .collect();
+
+        let controls = column![
+            checkbox(self.filters_enabled)
+                .label("Apply crossover filters")
+                .on_toggle(Message::FiltersToggled),
+            driver_controls(Way::Low, &self.low, &operands),
+            driver_controls(Way::High, &self.high, &operands),
+        ]
+        .push(
+            self.simulation
+                .as_ref()
+                .map(|simulation| integration_report(simulation, self.region())),
+        )
+        .spacing(12);
+
+        let content: Element<'a, Message> = match &self.simulation {
+            Some(simulation) => {
+                let chart = iced_aksel::Chart::new(fr_state)
+                    .style(Box::new(|theme| {
+                        let mut base = iced_aksel::style::default(theme);
+                        let palette = theme.extended_palette();
+
+                        base.axis.label.color = palette.secondary.base.color;
+                        base.axis.tick.color = palette.secondary.base.color;
+                        base.axis.spine.color = palette.secondary.base.color;
+                        base.axis.grid.color = palette.background.weaker.color;
+
+                        base
+                    }))
+                    .marker(&FREQ_AXIS_ID, MarkerPosition::Cursor, |ctx| {
+                        Some(ctx.marker(format_frequency_label(ctx.value)))
+                    })
+                    .marker(&DB_AXIS_ID, MarkerPosition::Cursor, |ctx| {
+                        Some(ctx.marker(format_db_label(ctx.value)))
+                    })
+                    .on_scroll(frequency_response::Message::OnPlotScroll)
+                    .on_drag(frequency_response::Message::OnPlotDrag);
+
+                let chart = [
+                    Some(&simulation.low),
+                    Some(&simulation.high),
+                    simulation.aligned.as_ref(),
+                    Some(&simulation.sum),
+                ]
+                .into_iter()
+                .flatten()
+                .fold(chart, |chart, curve| {
+                    chart.plot_data(curve, FREQ_AXIS_ID, DB_AXIS_ID)
+                });
+
+                Element::from(chart).map(Message::Chart)
+            }
+            None => {
+                let hint = if self.low.measurement.is_some() && self.high.measurement.is_some() {
+                    "Computing ..."
+                } else {
+                    "Please select a measurement for both ways."
+                };
+
+                center(text(hint).size(18)).into()
+            }
+        };
+
+        let legend = row![
+            text("Low").color(LOW_COLOR),
+            text("High").color(HIGH_COLOR),
+            text("Sum").color(SUM_COLOR),
+            text("Aligned sum").color(ALIGNED_COLOR),
+        ]
+        .spacing(12);
+
+        row![
+            container(controls)
+                .padding(6)
+                .width(Fill)
+                .style(container::bordered_box),
+            column![legend, content]
+                .spacing(10)
+                .width(Length::FillPortion(3)),
+        ]
+        .spacing(10)
+        .into()
+    }
+}
+
+/// Best delay and polarity for the measured ways, compared to the current
+/// settings.
+fn integration_report<'a>(simulation: &Simulation, (min, max): (f32, f32)) -> Element<'a, Message> {
+    let Some(integration) = &simulation.integration else {
+        return text("The ways can't be integrated.").into();
+    };
+
+    let delay = if integration.delay < 0.0 {
+        format!("Delay low pass by {:.2} ms", -integration.delay * 1_000.0)
+    } else {
+        format!("Delay high pass by {:.2} ms", integration.delay * 1_000.0)
+    };
+
+    let polarity = if integration.inverted {
+        "Invert polarity of the high pass"
+    } else {
+        "Keep polarity of the high pass"
+    };
+
+    let loss = |loss: f32| format!("{loss:.1} dB");
+
+    column![
+        text("Integration").size(18),
+        text(format!(
+            "Evaluated from {} to {}",
+            format_frequency_label(min),
+            format_frequency_label(max)
+        ))
+        .size(12),
+        text(delay),
+        text(polarity),
+        text(format!(
+            "Summation loss: {} (currently {})",
+            loss(integration.loss),
+            simulation.loss.map_or("-".to_string(), loss)
+        )),
+        button("Apply").on_press(Message::IntegrationApplied),
+    ]
+    .spacing(6)
+    .into()
+}
+
+fn driver_controls<'a>(
+    way: Way,
+    driver: &'a Driver,
+    operands: &[operation::Operand],
+) -> Element<'a, Message> {
+    let filter = driver.filter;
+
+    let selected = driver
+        .measurement
+        .and_then(|id| operands.iter().find(|operand| operand.id == id))
+        .cloned();
+
+    // the slider works on a logarithmic scale
+    let position = filter.frequency.log10();
+
+    column![
+        text(way.to_string()).size(18),
+        pick_list(selected, operands.to_vec(), operation::Operand::to_string)
+            .placeholder("Measurement ...")
+            .on_select(move |operand| Message::MeasurementSelected(way, operand))
+            .width(Fill),
+        row![
+            pick_list(
+                Some(filter.alignment),
+                FilterAlignment::ALL,
+                FilterAlignment::to_string
+            )
+            .on_select(move |alignment| Message::AlignmentSelected(way, alignment)),
+            pick_list(
+                Some(filter.order),
+                filter.alignment.orders(),
+                |order: &u8| format!("{} dB/oct", *order as u16 * 6)
+            )
+            .on_select(move |order| Message::OrderSelected(way, order)),
+        ]
+        .spacing(6),
+        row![
+            text(format_frequency_label(filter.frequency)).width(70),
+            slider(
+                MIN_FREQUENCY.log10()..=MAX_FREQUENCY.log10(),
+                position,
+                move |position| Message::FrequencyChanged(way, 10f32.powf(position))
+            )
+            .step(0.005),
+        ]
+        .spacing(6)
+        .align_y(Center),
+        row![
+            text(format!("{:.2} ms", driver.delay * 1_000.0)).width(70),
+            slider(
+                0.0..=MAX_DELAY * 1_000.0,
+                driver.delay * 1_000.0,
+                move |delay| Message::DelayChanged(way, delay / 1_000.0)
+            )
+            .step(0.01),
+        ]
+        .spacing(6)
+        .align_y(Center),
+        checkbox(filter.inverted)
+            .label("Invert polarity")
+            .on_toggle(move |inverted| Message::PolarityToggled(way, inverted)),
+    ]
+    .spacing(6)
+    .into()
+}
+
+impl fmt::Display for Way {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Way::Low => write!(f, "Low pass"),
+            Way::High => write!(f, "High pass"),
+        }
+    }
+}
+
+/// Result of [`simulate`] as (frequency, level) points.
+#[derive(Debug, Clone)]
+pub struct SimulatedPoints {
+    low: Vec<(f32, f32)>,
+    high: Vec<(f32, f32)>,
+    sum: Vec<(f32, f32)>,
+    aligned: Option<Vec<(f32, f32)>>,
+    integration: Option<Integration>,
+    loss: Option<f32>,
+}
+
+/// Settings of a way, that are applied to its windowed impulse response.
+#[derive(Debug, Clone, Copy)]
+pub struct WaySettings {
+    pub filter: Option<Filter>,
+    pub inverted: bool,
+    pub delay: f32,
+}
+
+impl Driver {
+    pub fn settings(&self, filters_enabled: bool) -> WaySettings {
+        WaySettings {
+            filter: filters_enabled.then_some(self.filter),
+            inverted: self.filter.inverted,
+            delay: self.delay,
+        }
+    }
+}
+
+/// Filters both windowed impulse responses and sums them up with their
+/// phase, `None` if they can't be combined. Also searches the delay and
+/// polarity of the high way, that sum best within the `region`.
+pub async fn simulate(
+    low: (Arc<raumklang_core::WindowedImpulseResponse>, WaySettings),
+    high: (Arc<raumklang_core::WindowedImpulseResponse>, WaySettings),
+    region: (f32, f32),
+    calibration: Option<data::curve::Curve>,
+    smoothing: Option<u8>,
+) -> Option<SimulatedPoints> {
+    tokio::task::spawn_blocking(move || {
+        // without delay and polarity, which are applied separately
+        let filtered = |(impulse_response, settings): &(
+            Arc<raumklang_core::WindowedImpulseResponse>,
+            WaySettings,
+        )| {
+            let mut frequency_response =
+                raumklang_core::FrequencyResponse::from_windowed(impulse_response);
+
+            if let Some(filter) = settings.filter {
+                Filter {
+                    inverted: false,
+                    ..filter
+                }
+                .apply(&mut frequency_response);
+            }
+
+            frequency_response
+        };
+
+        let shifted =
+            |frequency_response: &raumklang_core::FrequencyResponse, delay: f32, inverted: bool| {
+                let mut frequency_response = frequency_response.clone();
+                crossover::shift(&mut frequency_response, delay, inverted);
+
+                frequency_response
+            };
+
+        let add = |a: &raumklang_core::FrequencyResponse, b: &raumklang_core::FrequencyResponse| {
+            raumklang_core::FrequencyResponse {
+                sample_rate: a.sample_rate,
+                data: a.data.iter().zip(&b.data).map(|(a, b)| a + b).collect(),
+            }
+        };
+
+        let (low_base, high_base) = (filtered(&low), filtered(&high));
+
+        if low_base.sample_rate != high_base.sample_rate
+            || low_base.data.len() != high_base.data.len()
+        {
+            return None;
+        }
+
+        let (low_settings, high_settings) = (low.1, high.1);
+        let (min, max) = region;
+
+        let low = shifted(&low_base, low_settings.delay, low_settings.inverted);
+        let high = shifted(&high_base, high_settings.delay, high_settings.inverted);
+        let sum = add(&low, &high);
+        let loss = crossover::summation_loss(&low, &high, min, max);
+
+        // the low way keeps its polarity, the high way is searched for both
+        let reference = shifted(&low_base, 0.0, low_settings.inverted);
+        let integration = crossover::integrate(&reference, &high_base, min, max, MAX_DELAY);
+        let aligned = integration.as_ref().map(|integration| {
+            add(
+                &shifted(&reference, (-integration.delay).max(0.0), false),
+                &shifted(&high_base, integration.delay.max(0.0), integration.inverted),
+            )
+        });
+
+        let points = |frequency_response: raumklang_core::FrequencyResponse| {
+            let frequency_response = data::FrequencyResponse {
+                sample_rate: frequency_response.sample_rate,
+                data: Arc::new(frequency_response.data.iter().map(|s| s.norm()).collect()),
+                phase: None,
+            };
+
+            let frequency_response = match &calibration {
+                Some(calibration) => frequency_response.calibrated(calibration),
+                None => frequency_response,
+            };
+
+            frequency_response.points(
+                smoothing,
+                data::frequency_response::Grid::LogSpaced {
+                    points_per_octave: 48,
+                },
+            )
+        };
+
+        Some(SimulatedPoints {
+            low: points(low),
+            high: points(high),
+            sum: points(sum),
+            aligned: aligned.map(points),
+            integration,
+            loss,
+        })
+    })
+    .await
+    .ok()
+    .flatten()
+}
@@ -1,9 +1,15 @@
-use std::fmt::{self};
+use std::{
+    fmt::{self},
+    sync::Arc,
+};
 
 use iced::mouse::ScrollDelta;
-use iced_aksel::plot::DragDelta;
+use iced_aksel::{PlotPoint, plot::DragDelta};
 
-use crate::data;
+use crate::{
+    data,
+    ui::{self, measurement},
+};
 
 #[derive(Debug, Clone)]
 pub enum Message {
@@ -69,6 +75,152 @@ impl fmt::Display for Smoothing {
     }
 }
 
+/// How the frequency responses are drawn, as curves, as the levels of
+/// fractional octave bands or as their phase.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Display {
+    #[default]
+    Curve,
+    OctaveBands,
+    ThirdOctaveBands,
+    Phase,
+}
+
+impl Display {
+    pub const ALL: [Display; 4] = [
+        Display::Curve,
+        Display::OctaveBands,
+        Display::ThirdOctaveBands,
+        Display::Phase,
+    ];
+
+    pub fn band_fraction(&self) -> Option<u8> {
+        match self {
+            Display::Curve | Display::Phase => None,
+            Display::OctaveBands => Some(1),
+            Display::ThirdOctaveBands => Some(3),
+        }
+    }
+
+    pub fn shows_phase(&self) -> bool {
+        *self == Display::Phase
+    }
+}
+
+impl fmt::Display for Display {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Display::Curve => write!(f, "Curve"),
+            Display::OctaveBands => write!(f, "1/1 octave bars"),
+            Display::ThirdOctaveBands => write!(f, "1/3 octave bars"),
+            Display::Phase => write!(f, "Phase"),
+        }
+    }
+}
+
+/// Shows how much the shown frequency responses vary, e.g. between the
+/// microphone positions around the listening position.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Spread {
+    #[default]
+    None,
+    MinMax,
+    StandardDeviation,
+}
+
+impl Spread {
+    pub const ALL: [Spread; 3] = [Spread::None, Spread::MinMax, Spread::StandardDeviation];
+
+    /// The spread of the `curves` around their mean, `None` for less than
+    /// two curves.
+    pub fn band(
+        &self,
+        color: iced::Color,
+        curves: &[&ui::frequency_response::SpectrumLayer],
+    ) -> Option<ui::curve::Band> {
+        if *self == Spread::None || curves.len() < 2 {
+            return None;
+        }
+
+        let statistics = ui::curve::statistics(curves);
+        let point = |s: &ui::curve::Statistics, level| PlotPoint::new(s.frequency, level);
+
+        let (lower, upper) = statistics
+            .iter()
+            .map(|s| match self {
+                Spread::MinMax => (point(s, s.min), point(s, s.max)),
+                Spread::StandardDeviation | Spread::None => (
+                    point(s, s.mean - s.deviation),
+                    point(s, s.mean + s.deviation),
+                ),
+            })
+            .unzip();
+
+        Some(ui::curve::Band {
+            color,
+            center: statistics.iter().map(|s| point(s, s.mean)).collect(),
+            lower,
+            upper,
+        })
+    }
+}
+
+impl fmt::Display for Spread {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Spread::None => "No spread",
+            Spread::MinMax => "Min–max spread",
+            Spread::StandardDeviation => "±1σ spread",
+        };
+
+        write!(f, "{s}")
+    }
+}
+
+/// Predicted response of two measurements playing at once, e.g. the left
+/// and right speaker at the listening position.
+#[derive(Debug, Default)]
+pub struct StereoSum {
+    pub left: Option<measurement::Id>,
+    pub right: Option<measurement::Id>,
+    pub curve: Option<ui::Curve>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    Left,
+    Right,
+}
+
+/// Frequency response of the sum of both impulse responses as (frequency,
+/// level) points.
+pub async fn stereo_sum(
+    left: Arc<raumklang_core::WindowedImpulseResponse>,
+    right: Arc<raumklang_core::WindowedImpulseResponse>,
+    calibration: Option<data::curve::Curve>,
+    smoothing: Option<u8>,
+) -> Option<Vec<(f32, f32)>> {
+    let sum = Arc::new(data::impulse_response::sum(&left, &right)?);
+    let subject = data::scheduler::Subject::new("stereo sum");
+    let frequency_response = data::frequency_response::compute(subject, sum).await;
+
+    tokio::task::spawn_blocking(move || {
+        let frequency_response = match &calibration {
+            Some(calibration) => frequency_response.calibrated(calibration),
+            None => frequency_response,
+        };
+
+        frequency_response.points(
+            smoothing,
+            data::frequency_response::Grid::LogSpaced {
+                points_per_octave: 48,
+            },
+        )
+    })
+    .await
+    .ok()
+}
+
 pub async fn smooth_frequency_response(
     frequency_response: data::FrequencyResponse,
     fraction: u8,
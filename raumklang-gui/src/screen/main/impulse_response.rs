@@ -5,26 +5,38 @@ use crate::{
         self, Window,
         chart::{AmplitudeUnit, TimeSeriesUnit},
     },
-    ui::ImpulseResponse,
+    ui::{ImpulseResponse, measurement},
 };
 
 use iced::{
     Alignment, Element, Length,
-    widget::{canvas, column, container, pick_list, row},
+    widget::{canvas, checkbox, column, container, pick_list, row},
 };
 
+use std::fmt;
+
 #[derive(Debug, Clone)]
 pub enum ChartOperation {
     TimeUnitChanged(data::chart::TimeSeriesUnit),
     AmplitudeUnitChanged(data::chart::AmplitudeUnit),
+    CompareWith(Option<measurement::Id>),
+    AlignByPeak(bool),
     Interaction(chart::Interaction),
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum Comparison {
+    None,
+    Measurement(measurement::Id, String),
+}
+
 #[derive(Debug, Default)]
 pub struct Chart {
     shift_key_pressed: bool,
     pub amplitude_unit: data::chart::AmplitudeUnit,
     pub time_unit: data::chart::TimeSeriesUnit,
+    pub comparison: Option<measurement::Id>,
+    pub align_by_peak: bool,
     pub zoom: chart::Zoom,
     pub offset: i64,
     pub data_cache: canvas::Cache,
@@ -44,6 +56,14 @@ impl Chart {
                 self.data_cache.clear();
                 self.overlay_cache.clear();
             }
+            ChartOperation::CompareWith(comparison) => {
+                self.comparison = comparison;
+                self.data_cache.clear();
+            }
+            ChartOperation::AlignByPeak(align_by_peak) => {
+                self.align_by_peak = align_by_peak;
+                self.data_cache.clear();
+            }
             ChartOperation::Interaction(_) => {}
         }
     }
@@ -52,14 +72,31 @@ impl Chart {
         &'a self,
         impulse_response: &'a ImpulseResponse,
         window: &'a Window,
+        comparison: Option<&'a ImpulseResponse>,
+        candidates: Vec<Comparison>,
     ) -> Element<'a, ChartOperation> {
         let header = {
-            pick_list(
-                Some(&self.amplitude_unit),
-                &AmplitudeUnit::ALL[..],
-                AmplitudeUnit::to_string,
-            )
-            .on_select(ChartOperation::AmplitudeUnitChanged)
+            let comparisons: Vec<_> = [Comparison::None].into_iter().chain(candidates).collect();
+            let selected = comparisons
+                .iter()
+                .find(|c| c.id() == self.comparison)
+                .cloned();
+
+            row![
+                pick_list(
+                    Some(&self.amplitude_unit),
+                    &AmplitudeUnit::ALL[..],
+                    AmplitudeUnit::to_string,
+                )
+                .on_select(ChartOperation::AmplitudeUnitChanged),
+                pick_list(selected, comparisons, Comparison::to_string)
+                    .on_select(|comparison| ChartOperation::CompareWith(comparison.id())),
+                checkbox(self.align_by_peak)
+                    .label("Align by peak")
+                    .on_toggle(ChartOperation::AlignByPeak),
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center)
         };
 
         let chart = {
@@ -67,6 +104,7 @@ impl Chart {
                 chart::impulse_response(
                     window,
                     impulse_response,
+                    comparison.map(|comparison| (comparison, self.align_by_peak)),
                     &self.time_unit,
                     &self.amplitude_unit,
                     self.zoom,
@@ -105,3 +143,21 @@ impl Chart {
         self.shift_key_pressed = true
     }
 }
+
+impl Comparison {
+    fn id(&self) -> Option<measurement::Id> {
+        match self {
+            Comparison::None => None,
+            Comparison::Measurement(id, _) => Some(*id),
+        }
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Comparison::None => write!(f, "No comparison"),
+            Comparison::Measurement(_, name) => write!(f, "Compare with {name}"),
+        }
+    }
+}
@@ -1,19 +1,41 @@
+pub mod auralization;
+pub mod channel_check;
+pub mod duplicate_measurement;
+pub mod export_hook;
+pub mod moving_mic;
+pub mod operation;
 pub mod pending_window;
+pub mod recompute;
+pub mod remote_control;
+pub mod rta;
+pub mod sample_rate_mismatch;
 pub mod save_project;
+pub mod session_log;
 pub mod spectral_decay_config;
 pub mod spectrogram_config;
+pub mod spl_meter;
+pub mod sub_alignment;
+pub mod transfer_function;
+pub mod wizard;
 
+pub use duplicate_measurement::duplicate_measurement;
 use iced::{
     Element, Font,
     Length::Fill,
-    font,
+    Subscription, font,
     widget::{button, column, container, scrollable, text},
 };
 pub use pending_window::pending_window;
+pub use sample_rate_mismatch::sample_rate_mismatch;
 pub use spectral_decay_config::SpectralDecayConfig;
 pub use spectrogram_config::SpectrogramConfig;
 
-use crate::screen::main::{recording::Recording, tab};
+use std::path::PathBuf;
+
+use crate::{
+    screen::main::{Message, recording, recording::Recording, tab},
+    ui::measurement,
+};
 
 #[allow(clippy::large_enum_variant)]
 #[derive(Default, Debug)]
@@ -29,6 +51,87 @@ pub enum Modal {
     Recording(Recording),
     SaveProjectDialog(save_project::View),
     OpenRecentProject,
+    Operation(operation::View),
+    Auralization(auralization::View),
+    Recompute(recompute::View),
+    SampleRateMismatch {
+        sample_rate: u32,
+        ids: Vec<measurement::Id>,
+    },
+    DuplicateMeasurement {
+        path: PathBuf,
+        existing: measurement::Id,
+    },
+    SessionLog(session_log::View),
+    ChannelCheck(channel_check::View),
+    MovingMic(moving_mic::View),
+    SubAlignment(sub_alignment::View),
+    SplMeter(spl_meter::View),
+    Rta(rta::View),
+    TransferFunction(transfer_function::View),
+    ExportHook(export_hook::View),
+    RemoteControl(remote_control::View),
+    /// The wizard itself is kept outside, as it opens recordings on its own.
+    Wizard,
+}
+
+impl Modal {
+    /// Whether the modal can play audio, which the global stop ends.
+    pub fn plays_audio(&self) -> bool {
+        self.stop().is_some()
+    }
+
+    /// Message, that stops the playback of the modal.
+    pub fn stop(&self) -> Option<Message> {
+        let message = match self {
+            Modal::Recording(_) => Message::Recording(recording::Message::StopAudio),
+            Modal::ChannelCheck(_) => Message::ChannelCheck(channel_check::Message::Stop),
+            Modal::MovingMic(_) => Message::MovingMic(moving_mic::Message::Stop),
+            Modal::SubAlignment(_) => Message::SubAlignment(sub_alignment::Message::Stop),
+            Modal::SplMeter(_) => Message::SplMeter(spl_meter::Message::Stop),
+            Modal::Rta(_) => Message::Rta(rta::Message::Stop),
+            Modal::TransferFunction(_) => {
+                Message::TransferFunction(transfer_function::Message::Stop)
+            }
+            _ => return None,
+        };
+
+        Some(message)
+    }
+
+    /// Message sent to the modal on Esc, it closes the modal after stopping
+    /// its playback, a running recording is only stopped.
+    pub fn escape(&self) -> Option<Message> {
+        let message = match self {
+            Modal::Recording(_) => Message::Recording(recording::Message::StopAudio),
+            Modal::ChannelCheck(_) => Message::ChannelCheck(channel_check::Message::Close),
+            Modal::MovingMic(_) => Message::MovingMic(moving_mic::Message::Close),
+            Modal::SubAlignment(_) => Message::SubAlignment(sub_alignment::Message::Close),
+            Modal::SplMeter(_) => Message::SplMeter(spl_meter::Message::Close),
+            Modal::Rta(_) => Message::Rta(rta::Message::Close),
+            Modal::TransferFunction(_) => {
+                Message::TransferFunction(transfer_function::Message::Close)
+            }
+            Modal::Wizard => Message::Wizard(wizard::Message::Close),
+            _ => return None,
+        };
+
+        Some(message)
+    }
+
+    /// Subscriptions of the modal, e.g. to the audio backend.
+    pub fn subscription(&self) -> Subscription<Message> {
+        match self {
+            Modal::Recording(recording) => recording.subscription().map(Message::Recording),
+            Modal::ChannelCheck(view) => view.subscription().map(Message::ChannelCheck),
+            Modal::MovingMic(view) => view.subscription().map(Message::MovingMic),
+            Modal::SubAlignment(view) => view.subscription().map(Message::SubAlignment),
+            Modal::SplMeter(view) => view.subscription().map(Message::SplMeter),
+            Modal::Rta(view) => view.subscription().map(Message::Rta),
+            Modal::TransferFunction(view) => view.subscription().map(Message::TransferFunction),
+            _ => Subscription::none(),
+        }
+    }
 }
 
 pub fn load_recent_project<'a, Message>(
@@ -0,0 +1,200 @@
+use crate::{data::auralization, screen::main::modal::operation::Operand};
+
+use iced::{
+    Alignment::Center,
+    Element,
+    Length::Fill,
+    widget::{button, column, container, pick_list, row, rule, space, text},
+};
+
+use std::path::PathBuf;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    MeasurementSelected(Operand),
+    PickDrySignal,
+    PickFilter,
+    ClearFilter,
+    Export,
+    Close,
+}
+
+pub enum Action {
+    None,
+    PickDrySignal,
+    PickFilter,
+    Export(Request),
+    Close,
+}
+
+/// What to auralize, the output file is chosen afterwards.
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub operand: Operand,
+    pub dry: PathBuf,
+    pub filter: Option<PathBuf>,
+}
+
+/// Lets the user listen to the room, by convolving a dry recording of music
+/// or speech with a measured impulse response.
+#[derive(Debug)]
+pub struct View {
+    operands: Vec<Operand>,
+    selected: Option<Operand>,
+    dry: Option<PathBuf>,
+    filter: Option<PathBuf>,
+    state: State,
+}
+
+#[derive(Debug)]
+enum State {
+    Idle,
+    Exporting,
+    Exported(PathBuf),
+    Failed(auralization::Error),
+}
+
+impl View {
+    pub fn new(operands: Vec<Operand>, selected: Option<Operand>) -> Self {
+        Self {
+            selected: selected.or_else(|| operands.first().cloned()),
+            operands,
+            dry: None,
+            filter: None,
+            state: State::Idle,
+        }
+    }
+
+    pub fn dry_signal_picked(&mut self, path: PathBuf) {
+        self.dry = Some(path);
+    }
+
+    pub fn filter_picked(&mut self, path: PathBuf) {
+        self.filter = Some(path);
+    }
+
+    pub fn exporting(&mut self) {
+        self.state = State::Exporting;
+    }
+
+    pub fn exported(&mut self, result: Result<PathBuf, auralization::Error>) {
+        self.state = match result {
+            Ok(path) => State::Exported(path),
+            Err(err) => State::Failed(err),
+        };
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::MeasurementSelected(operand) => {
+                self.selected = Some(operand);
+                Action::None
+            }
+            Message::PickDrySignal => Action::PickDrySignal,
+            Message::PickFilter => Action::PickFilter,
+            Message::ClearFilter => {
+                self.filter = None;
+                Action::None
+            }
+            Message::Export => {
+                let (Some(operand), Some(dry)) = (self.selected.clone(), self.dry.clone()) else {
+                    return Action::None;
+                };
+
+                Action::Export(Request {
+                    operand,
+                    dry,
+                    filter: self.filter.clone(),
+                })
+            }
+            Message::Close => Action::Close,
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let file_name = |path: &Option<PathBuf>| {
+            path.as_ref()
+                .and_then(|path| path.file_name())
+                .map_or("None".to_string(), |name| {
+                    name.to_string_lossy().to_string()
+                })
+        };
+
+        let status = match &self.state {
+            State::Idle => None,
+            State::Exporting => Some(text("Convolving ...")),
+            State::Exported(path) => {
+                Some(text!("Exported to {}", path.display()).style(text::success))
+            }
+            State::Failed(err) => Some(text!("{err}").style(text::danger)),
+        };
+
+        let is_exporting = matches!(self.state, State::Exporting);
+        let can_export = self.selected.is_some() && self.dry.is_some() && !is_exporting;
+
+        container(
+            column![
+                text("Auralization").size(18),
+                rule::horizontal(1),
+                text(
+                    "Convolves a dry recording of music or speech with the impulse \
+                     response, to listen to the room. Optionally, a correction filter \
+                     is applied as well."
+                )
+                .size(14),
+                row![
+                    text("Impulse response").width(140),
+                    pick_list(
+                        self.selected.as_ref(),
+                        &self.operands[..],
+                        Operand::to_string
+                    )
+                    .on_select(Message::MeasurementSelected)
+                    .width(Fill),
+                ]
+                .spacing(10)
+                .align_y(Center),
+                row![
+                    text("Dry signal").width(140),
+                    text(file_name(&self.dry)).width(Fill),
+                    button("Choose ...")
+                        .style(button::secondary)
+                        .on_press(Message::PickDrySignal),
+                ]
+                .spacing(10)
+                .align_y(Center),
+                row![
+                    text("Correction filter").width(140),
+                    text(file_name(&self.filter)).width(Fill),
+                    button("Clear")
+                        .style(button::secondary)
+                        .on_press_maybe(self.filter.is_some().then_some(Message::ClearFilter)),
+                    button("Choose ...")
+                        .style(button::secondary)
+                        .on_press(Message::PickFilter),
+                ]
+                .spacing(10)
+                .align_y(Center),
+            ]
+            .push(status)
+            .push(rule::horizontal(1))
+            .push(
+                row![
+                    space::horizontal(),
+                    button("Close")
+                        .style(button::secondary)
+                        .on_press(Message::Close),
+                    button("Export ...")
+                        .style(button::success)
+                        .on_press_maybe(can_export.then_some(Message::Export)),
+                ]
+                .spacing(5),
+            )
+            .spacing(12),
+        )
+        .padding(20)
+        .width(520)
+        .style(container::bordered_box)
+        .into()
+    }
+}
@@ -0,0 +1,496 @@
+use crate::{
+    audio,
+    data::{
+        audio::OutPort,
+        measurement::{self, SignalConfig},
+    },
+    log,
+};
+
+use raumklang_core::{alignment::Polarity, channel::Check};
+
+use iced::{
+    Alignment::Center,
+    Element,
+    Length::{Fill, Shrink},
+    Subscription, Task, task,
+    widget::{button, checkbox, column, container, pick_list, right, row, rule, scrollable, text},
+};
+use tokio_stream::wrappers::ReceiverStream;
+
+use std::{collections::BTreeMap, fmt, sync::Arc, time::Duration};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    AudioBackend(audio::Event),
+    Notification(audio::Notification),
+    Toggled(usize, bool),
+    SpeakerSelected(usize, Speaker),
+    Start,
+    RecordingChunk(Box<[f32]>),
+    RecordingFinished,
+    Checked(usize, Option<Check>),
+    Stop,
+    Close,
+}
+
+pub enum Action {
+    None,
+    Task(Task<Message>),
+    Close,
+}
+
+/// Plays a short sweep on every selected output in turn and shows, whether
+/// a speaker was picked up and its polarity.
+#[derive(Debug)]
+pub struct View {
+    backend: Backend,
+    /// Connected again, when the check is finished.
+    out_port: Option<OutPort>,
+    /// Selected before the backend is ready.
+    preselected: Vec<OutPort>,
+    /// Device warm-up of the recording configuration.
+    pre_roll: Duration,
+    channels: Vec<Channel>,
+    state: State,
+}
+
+#[derive(Debug)]
+enum Backend {
+    Connecting(Option<(audio::Error, std::sync::mpsc::SyncSender<()>)>),
+    Connected(Arc<dyn audio::Backend>),
+}
+
+#[derive(Debug, Default)]
+enum State {
+    #[default]
+    Idle,
+    Playing {
+        channel: usize,
+        recording: Vec<f32>,
+        _handle: task::Handle,
+    },
+    Analysing(usize),
+}
+
+#[derive(Debug)]
+struct Channel {
+    port: OutPort,
+    selected: bool,
+    /// The speaker the user heard.
+    speaker: Option<Speaker>,
+    result: Option<Option<Check>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Speaker {
+    Left,
+    Right,
+    Center,
+    Subwoofer,
+    SurroundLeft,
+    SurroundRight,
+}
+
+impl Speaker {
+    const ALL: [Speaker; 6] = [
+        Speaker::Left,
+        Speaker::Right,
+        Speaker::Center,
+        Speaker::Subwoofer,
+        Speaker::SurroundLeft,
+        Speaker::SurroundRight,
+    ];
+}
+
+impl fmt::Display for Speaker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Speaker::Left => "Left",
+            Speaker::Right => "Right",
+            Speaker::Center => "Center",
+            Speaker::Subwoofer => "Subwoofer",
+            Speaker::SurroundLeft => "Surround left",
+            Speaker::SurroundRight => "Surround right",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+impl View {
+    pub fn new(config: &measurement::Config) -> Self {
+        let preselected = config
+            .output_trims
+            .keys()
+            .chain(config.out_port.as_ref())
+            .cloned()
+            .collect();
+
+        Self {
+            backend: Backend::Connecting(None),
+            out_port: config.out_port.clone(),
+            preselected,
+            pre_roll: config.pre_roll,
+            channels: vec![],
+            state: State::Idle,
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::AudioBackend(audio::Event::Ready(backend, receiver)) => {
+                let Some(receiver) = Arc::into_inner(receiver) else {
+                    return Action::None;
+                };
+
+                self.channels = backend
+                    .out_ports()
+                    .iter()
+                    .map(|port| Channel {
+                        port: port.clone(),
+                        selected: self.preselected.contains(port),
+                        speaker: None,
+                        result: None,
+                    })
+                    .collect();
+                self.backend = Backend::Connected(backend);
+
+                Action::Task(Task::stream(ReceiverStream::new(receiver)).map(Message::Notification))
+            }
+            Message::AudioBackend(audio::Event::Error { err, retry_tx, .. }) => {
+                self.backend = Backend::Connecting(Some((err, retry_tx)));
+                self.state = State::Idle;
+
+                Action::None
+            }
+            Message::Notification(audio::Notification::PortsChanged { out_ports, .. }) => {
+                self.channels
+                    .retain(|channel| out_ports.contains(&channel.port));
+
+                for port in out_ports {
+                    if !self.channels.iter().any(|channel| channel.port == port) {
+                        self.channels.push(Channel {
+                            port,
+                            selected: false,
+                            speaker: None,
+                            result: None,
+                        });
+                    }
+                }
+
+                Action::None
+            }
+            Message::Notification(_) => Action::None,
+            Message::Toggled(index, selected) => {
+                if let Some(channel) = self.channels.get_mut(index) {
+                    channel.selected = selected;
+                }
+
+                Action::None
+            }
+            Message::SpeakerSelected(index, speaker) => {
+                if let Some(channel) = self.channels.get_mut(index) {
+                    channel.speaker = Some(speaker);
+                }
+
+                Action::None
+            }
+            Message::Start => {
+                for channel in &mut self.channels {
+                    channel.result = None;
+                }
+
+                self.play_next(0)
+            }
+            Message::RecordingChunk(chunk) => {
+                if let State::Playing { recording, .. } = &mut self.state {
+                    recording.extend_from_slice(&chunk);
+                }
+
+                Action::None
+            }
+            Message::RecordingFinished => {
+                let (Backend::Connected(backend), State::Playing { .. }) =
+                    (&self.backend, &self.state)
+                else {
+                    return Action::None;
+                };
+
+                let State::Playing {
+                    channel, recording, ..
+                } = std::mem::take(&mut self.state)
+                else {
+                    return Action::None;
+                };
+
+                self.state = State::Analysing(channel);
+                let sample_rate = u32::from(backend.sample_rate());
+
+                Action::Task(Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || {
+                            audio::check_channel(
+                                &SignalConfig::channel_check(),
+                                &recording,
+                                sample_rate,
+                            )
+                        })
+                        .await
+                        .ok()
+                        .flatten()
+                    },
+                    move |check| Message::Checked(channel, check),
+                ))
+            }
+            Message::Checked(index, check) => {
+                if let Some(channel) = self.channels.get_mut(index) {
+                    match &check {
+                        Some(check) if check.is_detected() => log::info!(
+                            "Channel check of {}: {:.1} dBFS after {:.1} ms, {} polarity",
+                            channel.port,
+                            check.level,
+                            check.delay.as_secs_f32() * 1000.0,
+                            check.polarity
+                        ),
+                        _ => log::warn!("Channel check of {}: no speaker detected", channel.port),
+                    }
+
+                    channel.result = Some(check);
+                }
+
+                self.play_next(index + 1)
+            }
+            Message::Stop => {
+                let State::Playing { channel, .. } = self.state else {
+                    return Action::None;
+                };
+
+                if let Backend::Connected(backend) = &self.backend {
+                    backend.stop();
+                }
+
+                log::info!("Channel check stopped at {}", self.channels[channel].port);
+
+                // dropping the handle ends the recording, the remaining
+                // channels are skipped
+                self.play_next(self.channels.len())
+            }
+            Message::Close => {
+                if let State::Playing { .. } = self.state
+                    && let Backend::Connected(backend) = &self.backend
+                {
+                    backend.stop();
+                }
+
+                self.state = State::Idle;
+                Action::Close
+            }
+        }
+    }
+
+    /// Plays the signal on the next selected output from `index` on, or
+    /// connects the original output again, if all are checked.
+    fn play_next(&mut self, index: usize) -> Action {
+        let Backend::Connected(backend) = &self.backend else {
+            return Action::None;
+        };
+
+        let next = self
+            .channels
+            .iter()
+            .enumerate()
+            .skip(index)
+            .find(|(_, channel)| channel.selected);
+
+        let Some((channel, Channel { port, .. })) = next else {
+            self.state = State::Idle;
+
+            return match self.out_port.clone() {
+                Some(port) => Action::Task(Task::future(backend.connect_out_port(port)).discard()),
+                None => Action::None,
+            };
+        };
+
+        log::info!("Channel check: playing on {port}");
+
+        let backend = backend.clone();
+        let pre_roll = self.pre_roll;
+        let play = Task::future(backend.connect_out_port(port.clone())).then(move |()| {
+            let (loudness, spectrum, mut data, _dropped_frames) = backend.run_measurement(
+                SignalConfig::channel_check(),
+                measurement::config::DEFAULT_CAPTURE_BUFFER,
+                pre_roll,
+            );
+
+            let recording = iced::task::sipper(async move |mut progress| {
+                while let Some(chunk) = data.recv().await {
+                    progress.send(chunk).await;
+                }
+            });
+
+            // the meters are not shown, but stop the recording once dropped
+            Task::batch([
+                Task::stream(ReceiverStream::new(loudness)).discard(),
+                Task::stream(ReceiverStream::new(spectrum)).discard(),
+                Task::sip(recording, Message::RecordingChunk, |_| {
+                    Message::RecordingFinished
+                }),
+            ])
+        });
+
+        let (play, handle) = play.abortable();
+
+        self.state = State::Playing {
+            channel,
+            recording: vec![],
+            _handle: handle.abort_on_drop(),
+        };
+
+        Action::Task(play)
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let header = column![text("Channel check").size(20), rule::horizontal(1.0)].spacing(4);
+
+        let content: Element<_> = match &self.backend {
+            Backend::Connecting(None) => text("Connecting to the audio server ...").into(),
+            Backend::Connecting(Some((err, _))) => text!("Audio server not available: {err}")
+                .style(text::danger)
+                .into(),
+            Backend::Connected(_) => self.channels(),
+        };
+
+        let is_running = !matches!(self.state, State::Idle);
+        let can_start = !is_running && self.channels.iter().any(|channel| channel.selected);
+
+        let footer = row![
+            button("Close")
+                .style(button::secondary)
+                .on_press(Message::Close),
+            right(if let State::Playing { .. } = self.state {
+                button("Stop").style(button::danger).on_press(Message::Stop)
+            } else {
+                button(if is_running { "Checking ..." } else { "Start" })
+                    .style(button::success)
+                    .on_press_maybe(can_start.then_some(Message::Start))
+            }),
+        ];
+
+        let issues = self
+            .issues()
+            .into_iter()
+            .map(|issue| text(issue).style(text::warning).size(14).into());
+
+        container(column![header, content, column(issues).spacing(4), footer].spacing(18))
+            .style(container::bordered_box)
+            .padding(18)
+            .width(700)
+            .into()
+    }
+
+    fn channels(&self) -> Element<'_, Message> {
+        let playing = match self.state {
+            State::Playing { channel, .. } | State::Analysing(channel) => Some(channel),
+            State::Idle => None,
+        };
+
+        let rows = self.channels.iter().enumerate().map(|(index, channel)| {
+            let result: Element<_> = match (&channel.result, playing == Some(index)) {
+                (_, true) => text("Playing ...").into(),
+                (None, false) => text("-").into(),
+                (Some(Some(check)), false) if check.is_detected() => {
+                    let polarity = text!("{} polarity", check.polarity).style(move |theme| {
+                        match check.polarity {
+                            Polarity::Normal => text::success(theme),
+                            Polarity::Inverted => text::danger(theme),
+                        }
+                    });
+
+                    row![
+                        text!(
+                            "{:.1} dBFS, {:.1} ms",
+                            check.level,
+                            check.delay.as_secs_f32() * 1000.0
+                        ),
+                        polarity
+                    ]
+                    .spacing(10)
+                    .into()
+                }
+                (Some(_), false) => text("No speaker detected").style(text::danger).into(),
+            };
+
+            row![
+                checkbox(channel.selected)
+                    .label(channel.port.to_string())
+                    .on_toggle_maybe(
+                        matches!(self.state, State::Idle)
+                            .then_some(move |selected| Message::Toggled(index, selected))
+                    )
+                    .width(Fill),
+                container(result).width(Fill),
+                pick_list(channel.speaker, Speaker::ALL, Speaker::to_string)
+                    .placeholder("Heard ...")
+                    .on_select(move |speaker| Message::SpeakerSelected(index, speaker))
+                    .width(Shrink),
+            ]
+            .spacing(10)
+            .align_y(Center)
+            .into()
+        });
+
+        column![
+            text(
+                "Place the microphone at the listening position. A short sweep \
+                 is played on every selected output in turn, select the speaker \
+                 you heard to confirm the wiring."
+            ),
+            scrollable(column(rows).spacing(6)).height(Shrink),
+        ]
+        .spacing(12)
+        .into()
+    }
+
+    /// Miswired outputs, that should be fixed before measuring.
+    fn issues(&self) -> Vec<String> {
+        let mut issues = vec![];
+        let mut speakers: BTreeMap<Speaker, Vec<&OutPort>> = BTreeMap::new();
+
+        for channel in &self.channels {
+            match &channel.result {
+                Some(Some(check)) if check.is_detected() => {
+                    if check.polarity == Polarity::Inverted {
+                        issues.push(format!(
+                            "The speaker on {} has an inverted polarity, check the wiring.",
+                            channel.port
+                        ));
+                    }
+                }
+                Some(_) => issues.push(format!(
+                    "Nothing was picked up on {}, check if the speaker is connected and on.",
+                    channel.port
+                )),
+                None => {}
+            }
+
+            if let Some(speaker) = channel.speaker {
+                speakers.entry(speaker).or_default().push(&channel.port);
+            }
+        }
+
+        for (speaker, ports) in speakers {
+            if let [first, second, ..] = ports.as_slice() {
+                issues.push(format!(
+                    "{first} and {second} both play on the {speaker} speaker."
+                ));
+            }
+        }
+
+        issues
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        Subscription::run(audio::run).map(Message::AudioBackend)
+    }
+}
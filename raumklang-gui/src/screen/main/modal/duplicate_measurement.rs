@@ -0,0 +1,42 @@
+use std::path::Path;
+
+use iced::{
+    Element,
+    widget::{button, column, container, row, space, text},
+};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Reload,
+    AddAnyway,
+    Cancel,
+}
+
+/// Asks what to do with a file, that was already loaded as `name`.
+pub fn duplicate_measurement<'a>(name: &'a str, path: &'a Path) -> Element<'a, Message> {
+    container(
+        column![
+            text("Measurement already loaded").size(18),
+            text!("{} is already loaded as \"{name}\".", path.display()),
+            text("Reloading keeps its settings, e.g. the name and time shift.").size(12),
+            row![
+                space::horizontal(),
+                button("Cancel")
+                    .style(button::secondary)
+                    .on_press(Message::Cancel),
+                button("Add anyway")
+                    .style(button::secondary)
+                    .on_press(Message::AddAnyway),
+                button("Reload")
+                    .style(button::success)
+                    .on_press(Message::Reload)
+            ]
+            .spacing(5)
+        ]
+        .spacing(10),
+    )
+    .padding(20)
+    .width(400)
+    .style(container::bordered_box)
+    .into()
+}
@@ -0,0 +1,78 @@
+use crate::data::export_hook::ExportHook;
+
+use iced::{
+    Element,
+    Length::Fill,
+    widget::{button, checkbox, column, container, row, rule, space, text, text_input},
+};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    ToggleEnabled(bool),
+    CommandChanged(String),
+    Save,
+    Cancel,
+}
+
+pub enum Action {
+    None,
+    Save(ExportHook),
+    Cancel,
+}
+
+#[derive(Debug)]
+pub struct View {
+    hook: ExportHook,
+}
+
+impl View {
+    pub fn new(hook: ExportHook) -> Self {
+        Self { hook }
+    }
+
+    #[must_use]
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::ToggleEnabled(enabled) => {
+                self.hook.enabled = enabled;
+                Action::None
+            }
+            Message::CommandChanged(command) => {
+                self.hook.command = command;
+                Action::None
+            }
+            Message::Save => Action::Save(self.hook.clone()),
+            Message::Cancel => Action::Cancel,
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        container(
+            column![
+                text("Export hook").size(18),
+                rule::horizontal(1),
+                checkbox(self.hook.enabled)
+                    .label("Run command after exporting files")
+                    .on_toggle(Message::ToggleEnabled),
+                text_input("scp \"$@\" streamer:/etc/camilladsp/", &self.hook.command)
+                    .on_input(Message::CommandChanged)
+                    .width(Fill),
+                text("The exported files are passed as arguments, the output is written to the session log.")
+                    .size(12),
+                row![
+                    space::horizontal(),
+                    button("Cancel")
+                        .style(button::secondary)
+                        .on_press(Message::Cancel),
+                    button("Save").on_press(Message::Save)
+                ]
+                .spacing(10)
+            ]
+            .spacing(10),
+        )
+        .padding(20)
+        .width(500)
+        .style(container::bordered_box)
+        .into()
+    }
+}
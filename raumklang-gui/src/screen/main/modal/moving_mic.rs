@@ -0,0 +1,391 @@
+use crate::{
+    audio,
+    data::{
+        audio::{InPort, OutPort, Trim},
+        measurement,
+    },
+    log,
+};
+
+use raumklang_core::signals::PeriodicPinkNoise;
+
+use iced::{
+    Alignment::Center,
+    Element,
+    Length::Fill,
+    Subscription, Task, task, time,
+    widget::{button, column, container, pick_list, progress_bar, right, row, rule, text},
+};
+use tokio_stream::wrappers::ReceiverStream;
+
+use std::{fmt, sync::Arc, time::Duration};
+
+/// Length of a period of the played noise in samples, the frequency
+/// resolution is below 1.5 Hz up to 48 kHz.
+const PERIOD: usize = 32_768;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    AudioBackend(audio::Event),
+    Notification(audio::Notification),
+    DurationSelected(Length),
+    Start,
+    Loudness(audio::Loudness),
+    Averaged(audio::Average),
+    Stop,
+    Tick(time::Instant),
+    Accept,
+    Close,
+}
+
+pub enum Action {
+    None,
+    Task(Task<Message>),
+    /// The averaged response should be shown.
+    Finished(audio::Average),
+    Close,
+}
+
+/// Plays periodic pink noise, while the microphone is moved slowly around
+/// the listening area, and averages the response of all positions.
+#[derive(Debug)]
+pub struct View {
+    backend: Backend,
+    out_port: Option<OutPort>,
+    in_port: Option<InPort>,
+    volume: f32,
+    trim: Trim,
+    length: Length,
+    state: State,
+}
+
+#[derive(Debug)]
+enum Backend {
+    Connecting(Option<(audio::Error, std::sync::mpsc::SyncSender<()>)>),
+    Connected(Arc<dyn audio::Backend>),
+}
+
+#[derive(Debug, Default)]
+enum State {
+    #[default]
+    Idle,
+    Running {
+        started: time::Instant,
+        elapsed: Duration,
+        loudness: audio::Loudness,
+        average: Option<audio::Average>,
+        _handle: task::Handle,
+    },
+    Finished(audio::Average),
+}
+
+/// How long the noise is played at most.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Length(Duration);
+
+impl Length {
+    const ALL: [Length; 4] = [
+        Length(Duration::from_secs(20)),
+        Length(Duration::from_secs(30)),
+        Length(Duration::from_secs(45)),
+        Length(Duration::from_secs(60)),
+    ];
+}
+
+impl Default for Length {
+    fn default() -> Self {
+        Self(Duration::from_secs(30))
+    }
+}
+
+impl fmt::Display for Length {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} s", self.0.as_secs())
+    }
+}
+
+impl View {
+    pub fn new(config: &measurement::Config) -> Self {
+        Self {
+            backend: Backend::Connecting(None),
+            out_port: config.out_port.clone(),
+            in_port: config.in_port.clone(),
+            volume: config.volume,
+            trim: config
+                .out_port
+                .as_ref()
+                .and_then(|port| config.output_trims.get(port))
+                .copied()
+                .unwrap_or_default(),
+            length: Length::default(),
+            state: State::Idle,
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::AudioBackend(audio::Event::Ready(backend, receiver)) => {
+                let Some(receiver) = Arc::into_inner(receiver) else {
+                    return Action::None;
+                };
+
+                let mut tasks =
+                    vec![Task::stream(ReceiverStream::new(receiver)).map(Message::Notification)];
+
+                if let Some(port) = self.out_port.clone() {
+                    tasks.push(Task::future(backend.connect_out_port(port)).discard());
+                }
+
+                if let Some(port) = self.in_port.clone() {
+                    tasks.push(Task::future(backend.connect_in_port(port)).discard());
+                }
+
+                backend.set_volume(self.volume);
+                backend.set_trim(self.trim);
+
+                self.backend = Backend::Connected(backend);
+
+                Action::Task(Task::batch(tasks))
+            }
+            Message::AudioBackend(audio::Event::Error { err, retry_tx, .. }) => {
+                self.backend = Backend::Connecting(Some((err, retry_tx)));
+                self.state = State::Idle;
+
+                Action::None
+            }
+            Message::Notification(_) => Action::None,
+            Message::DurationSelected(length) => {
+                self.length = length;
+                Action::None
+            }
+            Message::Start => {
+                let Backend::Connected(backend) = &self.backend else {
+                    return Action::None;
+                };
+
+                log::info!(
+                    "Moving microphone measurement started for at most {}",
+                    self.length
+                );
+
+                let noise = PeriodicPinkNoise::new(PERIOD, 0.5, rand_seed());
+                let (loudness, average) =
+                    backend.run_moving_mic(noise.period().to_vec(), self.length.0);
+
+                let (task, handle) = Task::batch([
+                    Task::stream(ReceiverStream::new(loudness)).map(Message::Loudness),
+                    Task::run(ReceiverStream::new(average), Message::Averaged)
+                        .chain(Task::done(Message::Stop)),
+                ])
+                .abortable();
+
+                self.state = State::Running {
+                    started: time::Instant::now(),
+                    elapsed: Duration::ZERO,
+                    loudness: audio::Loudness::default(),
+                    average: None,
+                    _handle: handle.abort_on_drop(),
+                };
+
+                Action::Task(task)
+            }
+            Message::Loudness(new_loudness) => {
+                if let State::Running { loudness, .. } = &mut self.state {
+                    *loudness = new_loudness;
+                }
+
+                Action::None
+            }
+            Message::Averaged(new_average) => {
+                if let State::Running { average, .. } = &mut self.state {
+                    *average = Some(new_average);
+                }
+
+                Action::None
+            }
+            Message::Tick(now) => {
+                if let State::Running {
+                    started, elapsed, ..
+                } = &mut self.state
+                {
+                    *elapsed = now - *started;
+                }
+
+                Action::None
+            }
+            Message::Stop => {
+                if let Backend::Connected(backend) = &self.backend {
+                    backend.stop();
+                }
+
+                // dropping the handle ends the averaging
+                self.state = match std::mem::take(&mut self.state) {
+                    State::Running {
+                        average: Some(average),
+                        ..
+                    } if average.periods > 0 => {
+                        log::info!(
+                            "Moving microphone measurement averaged {} periods",
+                            average.periods
+                        );
+                        State::Finished(average)
+                    }
+                    State::Running { .. } => {
+                        log::warn!(
+                            "Moving microphone measurement stopped before a period was averaged"
+                        );
+                        State::Idle
+                    }
+                    state => state,
+                };
+
+                Action::None
+            }
+            Message::Accept => match std::mem::take(&mut self.state) {
+                State::Finished(average) => Action::Finished(average),
+                state => {
+                    self.state = state;
+                    Action::None
+                }
+            },
+            Message::Close => {
+                if let State::Running { .. } = self.state
+                    && let Backend::Connected(backend) = &self.backend
+                {
+                    backend.stop();
+                }
+
+                Action::Close
+            }
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let header = column![text("Moving microphone").size(20), rule::horizontal(1.0)].spacing(4);
+
+        let status: Element<_> = match (&self.backend, &self.state) {
+            (Backend::Connecting(None), _) => text("Connecting to the audio server ...").into(),
+            (Backend::Connecting(Some((err, _))), _) => text!("Audio server not available: {err}")
+                .style(text::danger)
+                .into(),
+            (Backend::Connected(_), State::Idle) => row![
+                text("Play noise for at most"),
+                pick_list(Some(self.length), Length::ALL, Length::to_string)
+                    .on_select(Message::DurationSelected),
+            ]
+            .spacing(10)
+            .align_y(Center)
+            .into(),
+            (
+                Backend::Connected(_),
+                State::Running {
+                    elapsed,
+                    loudness,
+                    average,
+                    ..
+                },
+            ) => column![
+                progress_bar(0.0..=self.length.0.as_secs_f32(), elapsed.as_secs_f32()),
+                text!(
+                    "{:.0} s, {} periods averaged, level {:.1} dBFS RMS",
+                    elapsed.as_secs_f32(),
+                    average.as_ref().map_or(0, |average| average.periods),
+                    loudness.rms
+                ),
+            ]
+            .spacing(6)
+            .into(),
+            (Backend::Connected(_), State::Finished(average)) => text!(
+                "{} periods averaged, the response is shown with the frequency responses.",
+                average.periods
+            )
+            .style(text::success)
+            .into(),
+        };
+
+        let action = match &self.state {
+            State::Idle => button("Start").style(button::success).on_press_maybe(
+                matches!(self.backend, Backend::Connected(_)).then_some(Message::Start),
+            ),
+            State::Running { .. } => button("Stop").style(button::danger).on_press(Message::Stop),
+            State::Finished(_) => button("Show")
+                .style(button::success)
+                .on_press(Message::Accept),
+        };
+
+        let footer = row![
+            button("Close")
+                .style(button::secondary)
+                .on_press(Message::Close),
+            right(action),
+        ];
+
+        container(
+            column![
+                header,
+                text(
+                    "Place the microphone at the listening position and press start. \
+                     Move it slowly around the listening area, while the noise is \
+                     played, covering the positions of the listeners' heads."
+                ),
+                status,
+                footer
+            ]
+            .spacing(18),
+        )
+        .style(container::bordered_box)
+        .padding(18)
+        .width(Fill)
+        .max_width(600)
+        .into()
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        let audio_backend = Subscription::run(audio::run).map(Message::AudioBackend);
+
+        let tick = match self.state {
+            State::Running { .. } => time::every(Duration::from_millis(250)).map(Message::Tick),
+            _ => Subscription::none(),
+        };
+
+        Subscription::batch([audio_backend, tick])
+    }
+}
+
+/// A new noise for every measurement, the spectrum is the same anyway.
+fn rand_seed() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |time| time.as_nanos() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::audio::mock::{Call, Mock};
+
+    fn connected(mock: &Arc<Mock>) -> View {
+        let mut view = View::new(&measurement::Config::default());
+
+        let (event, _notifications) = mock.ready();
+        let _ = view.update(Message::AudioBackend(event));
+
+        view
+    }
+
+    #[test]
+    fn close_stops_the_playback() {
+        let mock = Mock::new(vec![], vec![]);
+        let mut view = connected(&mock);
+        mock.calls();
+
+        let _ = view.update(Message::Start);
+        assert_eq!(mock.calls(), [Call::RunMovingMic]);
+
+        let action = view.update(Message::Close);
+
+        assert!(matches!(action, Action::Close));
+        assert_eq!(mock.calls(), [Call::Stop]);
+    }
+}
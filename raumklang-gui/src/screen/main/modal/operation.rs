@@ -0,0 +1,203 @@
+use crate::{ui::measurement, widget::number_input};
+
+use iced::{
+    Alignment::Center,
+    Element,
+    widget::{button, column, container, pick_list, row, rule, space, text},
+};
+
+use std::fmt;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    KindSelected(Kind),
+    FirstSelected(Operand),
+    SecondSelected(Operand),
+    GainChanged(String),
+    Cancel,
+    Apply(Operation),
+}
+
+pub enum Action {
+    None,
+    Cancel,
+    Apply(Operation),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Sum,
+    Difference,
+    Scale,
+}
+
+/// A measurement that can be used as an operand.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Operand {
+    pub id: measurement::Id,
+    pub name: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum Operation {
+    Sum(measurement::Id, measurement::Id),
+    Difference(measurement::Id, measurement::Id),
+    /// Gain in dB
+    Scale(measurement::Id, f32),
+}
+
+#[derive(Debug)]
+pub struct View {
+    kind: Kind,
+    operands: Vec<Operand>,
+    first: Option<Operand>,
+    second: Option<Operand>,
+    gain: String,
+}
+
+impl View {
+    pub fn new(kind: Kind, operands: Vec<Operand>) -> Self {
+        Self {
+            kind,
+            first: operands.first().cloned(),
+            second: operands.get(1).cloned(),
+            operands,
+            gain: "0.0".to_string(),
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::KindSelected(kind) => {
+                self.kind = kind;
+                Action::None
+            }
+            Message::FirstSelected(operand) => {
+                self.first = Some(operand);
+                Action::None
+            }
+            Message::SecondSelected(operand) => {
+                self.second = Some(operand);
+                Action::None
+            }
+            Message::GainChanged(gain) => {
+                self.gain = gain;
+                Action::None
+            }
+            Message::Cancel => Action::Cancel,
+            Message::Apply(operation) => Action::Apply(operation),
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let gain = parse_gain(&self.gain);
+
+        let first = self.first.as_ref().map(|o| o.id);
+        let second = self.second.as_ref().map(|o| o.id);
+        let operation = match self.kind {
+            Kind::Sum => first.zip(second).map(|(a, b)| Operation::Sum(a, b)),
+            Kind::Difference => first.zip(second).map(|(a, b)| Operation::Difference(a, b)),
+            Kind::Scale => first
+                .zip(gain.as_ref().ok())
+                .map(|(a, gain)| Operation::Scale(a, *gain)),
+        };
+
+        let operand = |label, selected: &Option<Operand>, on_select: fn(Operand) -> Message| {
+            row![
+                text(label),
+                space::horizontal(),
+                pick_list(selected.as_ref(), &self.operands[..], Operand::to_string)
+                    .on_select(on_select)
+            ]
+            .align_y(Center)
+        };
+
+        let arguments = match self.kind {
+            Kind::Sum | Kind::Difference => column![
+                operand("First", &self.first, Message::FirstSelected),
+                operand("Second", &self.second, Message::SecondSelected)
+            ],
+            Kind::Scale => column![
+                operand("Measurement", &self.first, Message::FirstSelected),
+                row![
+                    "Gain",
+                    space::horizontal(),
+                    number_input(&self.gain, gain.as_ref().err(), Message::GainChanged),
+                    " dB"
+                ]
+                .align_y(Center)
+            ],
+        }
+        .spacing(10);
+
+        container(
+            column![
+                row![
+                    text("Operations").size(18),
+                    space::horizontal(),
+                    pick_list(Some(&self.kind), Kind::ALL, Kind::to_string)
+                        .on_select(Message::KindSelected)
+                ]
+                .align_y(Center),
+                rule::horizontal(1),
+                arguments,
+                rule::horizontal(1),
+                row![
+                    space::horizontal(),
+                    button("Cancel")
+                        .style(button::secondary)
+                        .on_press(Message::Cancel),
+                    button("Apply")
+                        .style(button::success)
+                        .on_press_maybe(operation.map(Message::Apply))
+                ]
+                .spacing(5)
+            ]
+            .spacing(20),
+        )
+        .padding(20)
+        .width(400)
+        .style(container::bordered_box)
+        .into()
+    }
+}
+
+impl Kind {
+    pub const ALL: [Kind; 3] = [Kind::Sum, Kind::Difference, Kind::Scale];
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Kind::Sum => "Sum",
+            Kind::Difference => "Difference",
+            Kind::Scale => "Scale",
+        };
+
+        write!(f, "{s}")
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+enum GainError {
+    #[error("Not a number.")]
+    NotANumber,
+    #[error("Must be in range: -60..60")]
+    Range,
+}
+
+fn parse_gain(gain: &str) -> Result<f32, GainError> {
+    let gain: f32 = gain.parse().map_err(|_| GainError::NotANumber)?;
+
+    if !(-60.0..=60.0).contains(&gain) {
+        return Err(GainError::Range);
+    }
+
+    Ok(gain)
+}
@@ -0,0 +1,98 @@
+use crate::ui::measurement;
+
+use iced::{
+    Element,
+    widget::{button, column, container, progress_bar, row, space, text},
+};
+
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Cancel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Stage {
+    ImpulseResponse,
+    FrequencyResponse,
+    SpectralDecay,
+    Spectrogram,
+}
+
+/// Progress of recomputing all analyses of the loaded measurements.
+#[derive(Debug)]
+pub struct View {
+    total: usize,
+    pending: BTreeMap<measurement::Id, BTreeSet<Stage>>,
+}
+
+impl View {
+    pub fn new(ids: impl IntoIterator<Item = measurement::Id>) -> Self {
+        let pending: BTreeMap<_, _> = ids
+            .into_iter()
+            .map(|id| (id, BTreeSet::from(Stage::ALL)))
+            .collect();
+
+        Self {
+            total: pending.len() * Stage::ALL.len(),
+            pending,
+        }
+    }
+
+    pub fn is_pending(&self, id: measurement::Id, stage: Stage) -> bool {
+        self.pending
+            .get(&id)
+            .is_some_and(|stages| stages.contains(&stage))
+    }
+
+    pub fn finish(&mut self, id: measurement::Id, stage: Stage) {
+        if let Some(stages) = self.pending.get_mut(&id) {
+            stages.remove(&stage);
+
+            if stages.is_empty() {
+                self.pending.remove(&id);
+            }
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    fn remaining(&self) -> usize {
+        self.pending.values().map(BTreeSet::len).sum()
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let done = self.total - self.remaining();
+
+        container(
+            column![
+                text("Recomputing analyses").size(18),
+                progress_bar(0.0..=self.total as f32, done as f32),
+                text!("{done} of {total} analyses computed", total = self.total),
+                row![
+                    space::horizontal(),
+                    button("Cancel")
+                        .style(button::secondary)
+                        .on_press(Message::Cancel)
+                ]
+            ]
+            .spacing(10),
+        )
+        .padding(20)
+        .width(400)
+        .style(container::bordered_box)
+        .into()
+    }
+}
+
+impl Stage {
+    pub const ALL: [Stage; 4] = [
+        Stage::ImpulseResponse,
+        Stage::FrequencyResponse,
+        Stage::SpectralDecay,
+        Stage::Spectrogram,
+    ];
+}
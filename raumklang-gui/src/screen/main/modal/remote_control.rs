@@ -0,0 +1,105 @@
+use crate::data::remote_control::RemoteControl;
+
+use iced::{
+    Alignment::Center,
+    Element,
+    widget::{button, checkbox, column, container, row, rule, space, text, text_input},
+};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    ToggleEnabled(bool),
+    ToggleAllowNetwork(bool),
+    PortChanged(String),
+    Save,
+    Cancel,
+}
+
+pub enum Action {
+    None,
+    Save(RemoteControl),
+    Cancel,
+}
+
+#[derive(Debug)]
+pub struct View {
+    settings: RemoteControl,
+    port: String,
+}
+
+impl View {
+    pub fn new(settings: RemoteControl) -> Self {
+        Self {
+            settings,
+            port: settings.port.to_string(),
+        }
+    }
+
+    #[must_use]
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::ToggleEnabled(enabled) => {
+                self.settings.enabled = enabled;
+                Action::None
+            }
+            Message::ToggleAllowNetwork(allow_network) => {
+                self.settings.allow_network = allow_network;
+                Action::None
+            }
+            Message::PortChanged(port) => {
+                self.port = port;
+                Action::None
+            }
+            Message::Save => match self.port() {
+                Some(port) => Action::Save(RemoteControl {
+                    port,
+                    ..self.settings
+                }),
+                None => Action::None,
+            },
+            Message::Cancel => Action::Cancel,
+        }
+    }
+
+    fn port(&self) -> Option<u16> {
+        self.port.trim().parse().ok().filter(|port| *port != 0)
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        container(
+            column![
+                text("Remote control").size(18),
+                rule::horizontal(1),
+                checkbox(self.settings.enabled)
+                    .label("Listen for OSC messages")
+                    .on_toggle(Message::ToggleEnabled),
+                checkbox(self.settings.allow_network)
+                    .label("Accept messages from other machines")
+                    .on_toggle_maybe(self.settings.enabled.then_some(Message::ToggleAllowNetwork)),
+                row![
+                    text("UDP port"),
+                    text_input(&RemoteControl::DEFAULT_PORT.to_string(), &self.port)
+                        .on_input_maybe(self.settings.enabled.then_some(Message::PortChanged))
+                        .width(100),
+                ]
+                .spacing(10)
+                .align_y(Center),
+                text("/raumklang/next starts a recording or advances it, /raumklang/back and /raumklang/abort stop it. The messages are not authenticated, anyone who can reach the port can play the measurement signal.")
+                    .size(12),
+                row![
+                    space::horizontal(),
+                    button("Cancel")
+                        .style(button::secondary)
+                        .on_press(Message::Cancel),
+                    button("Save").on_press_maybe(self.port().map(|_| Message::Save))
+                ]
+                .spacing(10)
+            ]
+            .spacing(10),
+        )
+        .padding(20)
+        .width(500)
+        .style(container::bordered_box)
+        .into()
+    }
+}
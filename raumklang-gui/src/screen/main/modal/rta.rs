@@ -0,0 +1,300 @@
+use crate::{
+    audio,
+    data::{
+        audio::{InPort, OutPort, Trim},
+        measurement,
+    },
+    log,
+    widget::rta::Rta,
+};
+
+use iced::{
+    Alignment::Center,
+    Element,
+    Length::Fill,
+    Subscription, Task, task,
+    widget::{button, canvas, checkbox, column, container, pick_list, right, row, rule, text},
+};
+use tokio_stream::wrappers::ReceiverStream;
+
+use std::{fmt, sync::Arc};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    AudioBackend(audio::Event),
+    Notification(audio::Notification),
+    ResolutionSelected(Resolution),
+    PlayNoiseToggled(bool),
+    Start,
+    Loudness(audio::Loudness),
+    Updated(audio::Rta),
+    Stop,
+    Close,
+}
+
+pub enum Action {
+    None,
+    Task(Task<Message>),
+    Close,
+}
+
+/// Real-time analyzer of the measurement input, shows the levels in
+/// fractional octave bands, e.g. of the background noise or of pink noise
+/// played through the speakers.
+#[derive(Debug)]
+pub struct View {
+    backend: Backend,
+    out_port: Option<OutPort>,
+    in_port: Option<InPort>,
+    volume: f32,
+    trim: Trim,
+    resolution: Resolution,
+    play_noise: bool,
+    state: State,
+    cache: canvas::Cache,
+}
+
+#[derive(Debug)]
+enum Backend {
+    Connecting(Option<(audio::Error, std::sync::mpsc::SyncSender<()>)>),
+    Connected(Arc<dyn audio::Backend>),
+}
+
+#[derive(Debug, Default)]
+enum State {
+    #[default]
+    Idle,
+    Running {
+        loudness: audio::Loudness,
+        rta: Option<audio::Rta>,
+        _handle: task::Handle,
+    },
+    Stopped(audio::Rta),
+}
+
+/// Width of the bands as fraction of an octave.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Resolution(u8);
+
+impl Resolution {
+    const ALL: [Resolution; 3] = [Resolution(1), Resolution(3), Resolution(6)];
+}
+
+impl Default for Resolution {
+    fn default() -> Self {
+        Self(3)
+    }
+}
+
+impl fmt::Display for Resolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "1/{} octave", self.0)
+    }
+}
+
+impl View {
+    pub fn new(config: &measurement::Config) -> Self {
+        Self {
+            backend: Backend::Connecting(None),
+            out_port: config.out_port.clone(),
+            in_port: config.in_port.clone(),
+            volume: config.volume,
+            trim: config
+                .out_port
+                .as_ref()
+                .and_then(|port| config.output_trims.get(port))
+                .copied()
+                .unwrap_or_default(),
+            resolution: Resolution::default(),
+            play_noise: false,
+            state: State::Idle,
+            cache: canvas::Cache::new(),
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::AudioBackend(audio::Event::Ready(backend, receiver)) => {
+                let Some(receiver) = Arc::into_inner(receiver) else {
+                    return Action::None;
+                };
+
+                let mut tasks =
+                    vec![Task::stream(ReceiverStream::new(receiver)).map(Message::Notification)];
+
+                if let Some(port) = self.out_port.clone() {
+                    tasks.push(Task::future(backend.connect_out_port(port)).discard());
+                }
+
+                if let Some(port) = self.in_port.clone() {
+                    tasks.push(Task::future(backend.connect_in_port(port)).discard());
+                }
+
+                backend.set_volume(self.volume);
+                backend.set_trim(self.trim);
+
+                self.backend = Backend::Connected(backend);
+
+                Action::Task(Task::batch(tasks))
+            }
+            Message::AudioBackend(audio::Event::Error { err, retry_tx, .. }) => {
+                self.backend = Backend::Connecting(Some((err, retry_tx)));
+                self.state = State::Idle;
+
+                Action::None
+            }
+            Message::Notification(_) => Action::None,
+            Message::ResolutionSelected(resolution) => {
+                self.resolution = resolution;
+                Action::None
+            }
+            Message::PlayNoiseToggled(play_noise) => {
+                self.play_noise = play_noise;
+                Action::None
+            }
+            Message::Start => {
+                let Backend::Connected(backend) = &self.backend else {
+                    return Action::None;
+                };
+
+                log::info!(
+                    "Real-time analyzer started with {}{}",
+                    self.resolution,
+                    if self.play_noise {
+                        ", playing pink noise"
+                    } else {
+                        ""
+                    }
+                );
+
+                let (loudness, rta) = backend.run_rta(self.resolution.0, self.play_noise);
+
+                let (task, handle) = Task::batch([
+                    Task::stream(ReceiverStream::new(loudness)).map(Message::Loudness),
+                    Task::stream(ReceiverStream::new(rta)).map(Message::Updated),
+                ])
+                .abortable();
+
+                self.state = State::Running {
+                    loudness: audio::Loudness::default(),
+                    rta: None,
+                    _handle: handle.abort_on_drop(),
+                };
+                self.cache.clear();
+
+                Action::Task(task)
+            }
+            Message::Loudness(new_loudness) => {
+                if let State::Running { loudness, .. } = &mut self.state {
+                    *loudness = new_loudness;
+                }
+
+                Action::None
+            }
+            Message::Updated(new_rta) => {
+                if let State::Running { rta, .. } = &mut self.state {
+                    *rta = Some(new_rta);
+                    self.cache.clear();
+                }
+
+                Action::None
+            }
+            Message::Stop => {
+                if let Backend::Connected(backend) = &self.backend {
+                    backend.stop();
+                }
+
+                // dropping the handle ends the analysis, the last levels are kept
+                self.state = match std::mem::take(&mut self.state) {
+                    State::Running { rta: Some(rta), .. } => State::Stopped(rta),
+                    State::Running { .. } => State::Idle,
+                    state => state,
+                };
+
+                Action::None
+            }
+            Message::Close => {
+                if let State::Running { .. } = self.state
+                    && let Backend::Connected(backend) = &self.backend
+                {
+                    backend.stop();
+                }
+
+                Action::Close
+            }
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let header = column![text("Real-time analyzer").size(20), rule::horizontal(1.0)].spacing(4);
+
+        let is_running = matches!(self.state, State::Running { .. });
+
+        let rta = match &self.state {
+            State::Running { rta, .. } => rta.as_ref(),
+            State::Stopped(rta) => Some(rta),
+            State::Idle => None,
+        };
+
+        let status: Element<_> = match (&self.backend, &self.state) {
+            (Backend::Connecting(None), _) => text("Connecting to the audio server ...").into(),
+            (Backend::Connecting(Some((err, _))), _) => text!("Audio server not available: {err}")
+                .style(text::danger)
+                .into(),
+            (Backend::Connected(_), State::Running { loudness, .. }) => text!(
+                "Level {:.1} dBFS RMS, {:.1} dBFS peak",
+                loudness.rms,
+                loudness.peak
+            )
+            .into(),
+            (Backend::Connected(_), _) => row![
+                text("Bands"),
+                pick_list(
+                    Some(self.resolution),
+                    Resolution::ALL,
+                    Resolution::to_string
+                )
+                .on_select(Message::ResolutionSelected),
+                checkbox(self.play_noise)
+                    .label("Play pink noise")
+                    .on_toggle(Message::PlayNoiseToggled),
+            ]
+            .spacing(10)
+            .align_y(Center)
+            .into(),
+        };
+
+        let chart = column![
+            canvas(Rta::new(rta, &self.cache)).width(Fill).height(260),
+            text("Band levels in dBFS, the peaks are held until the analyzer is started again.")
+                .size(12),
+        ]
+        .spacing(3);
+
+        let action = if is_running {
+            button("Stop").style(button::danger).on_press(Message::Stop)
+        } else {
+            button("Start").style(button::success).on_press_maybe(
+                matches!(self.backend, Backend::Connected(_)).then_some(Message::Start),
+            )
+        };
+
+        let footer = row![
+            button("Close")
+                .style(button::secondary)
+                .on_press(Message::Close),
+            right(action),
+        ];
+
+        container(column![header, chart, status, footer].spacing(18))
+            .style(container::bordered_box)
+            .padding(18)
+            .width(Fill)
+            .max_width(800)
+            .into()
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        Subscription::run(audio::run).map(Message::AudioBackend)
+    }
+}
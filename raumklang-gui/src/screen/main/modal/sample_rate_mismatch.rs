@@ -0,0 +1,50 @@
+use iced::{
+    Element,
+    widget::{button, column, container, row, space, text},
+};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Remove,
+    Resample,
+}
+
+/// Lists the measurements, that were recorded with another sample rate than
+/// the loopback. They have to be resampled or removed, before their impulse
+/// responses can be computed.
+pub fn sample_rate_mismatch<'a>(
+    sample_rate: u32,
+    measurements: impl IntoIterator<Item = (&'a str, u32)>,
+) -> Element<'a, Message> {
+    let measurements = column(
+        measurements
+            .into_iter()
+            .map(|(name, rate)| text!("{name}: {rate} Hz").into()),
+    )
+    .spacing(2);
+
+    container(
+        column![
+            text("Sample rate mismatch!").size(18),
+            text!(
+                "The loopback was recorded with {sample_rate} Hz, but these measurements weren't:"
+            ),
+            measurements,
+            row![
+                space::horizontal(),
+                button("Remove")
+                    .style(button::danger)
+                    .on_press(Message::Remove),
+                button(text!("Resample to {sample_rate} Hz"))
+                    .style(button::success)
+                    .on_press(Message::Resample)
+            ]
+            .spacing(5)
+        ]
+        .spacing(10),
+    )
+    .padding(20)
+    .width(400)
+    .style(container::bordered_box)
+    .into()
+}
@@ -14,7 +14,7 @@ use std::{
     sync::Arc,
 };
 
-use crate::data::project::{self, Operation};
+use crate::data::project::{self, Operation, WavFormat};
 
 #[derive(Debug, Clone)]
 pub struct View {
@@ -23,6 +23,7 @@ pub struct View {
     create_subdir: bool,
     measurement_operation: Operation,
     export_from_memory: bool,
+    wav_format: WavFormat,
     path_error: Result<(), Error>,
 }
 
@@ -34,6 +35,7 @@ pub enum Message {
 
     ChangeOperation(Operation),
     ToggleExportFromMemory(bool),
+    ChangeWavFormat(WavFormat),
 
     StoreDirectoryCheck(Result<(), Error>),
 
@@ -45,19 +47,31 @@ pub enum Action {
     None,
     Cancel,
     Task(Task<Message>),
-    Save(PathBuf, project::Operation, bool),
+    Save(PathBuf, project::Operation, bool, WavFormat),
 }
 
 impl View {
-    pub fn new(measurement_operation: Operation, export_from_memory: bool) -> Self {
-        Self {
-            base_path: PathBuf::new(),
+    pub fn new(
+        measurement_operation: Operation,
+        export_from_memory: bool,
+        wav_format: WavFormat,
+    ) -> Self {
+        let mut view = Self {
+            base_path: suggested_path(),
             file_path_str: String::new(),
             create_subdir: true,
             measurement_operation,
             export_from_memory,
+            wav_format,
             path_error: Ok(()),
-        }
+        };
+
+        view.file_path_str = view
+            .compute_final_path(view.create_subdir)
+            .to_string_lossy()
+            .to_string();
+
+        view
     }
 
     pub fn update(&mut self, msg: Message) -> Action {
@@ -105,11 +119,16 @@ impl View {
                 self.export_from_memory = state;
                 Action::None
             }
+            Message::ChangeWavFormat(format) => {
+                self.wav_format = format;
+                Action::None
+            }
             Message::Cancel => Action::Cancel,
             Message::Save => Action::Save(
                 PathBuf::from(&self.file_path_str),
                 self.measurement_operation,
                 self.export_from_memory,
+                self.wav_format,
             ),
         }
     }
@@ -180,7 +199,19 @@ impl View {
                     .label("Export in-memory measurements.")
                     .on_toggle(Message::ToggleExportFromMemory);
 
-                column![measurement_file_operation, export_in_memory_measurements].spacing(5)
+                let wav_format = column![
+                    text("Format of exported WAV files"),
+                    pick_list(Some(&self.wav_format), WavFormat::ALL, WavFormat::to_string)
+                        .on_select(Message::ChangeWavFormat)
+                ]
+                .spacing(10);
+
+                column![
+                    measurement_file_operation,
+                    export_in_memory_measurements,
+                    wav_format
+                ]
+                .spacing(5)
             };
 
             column![file_path_picker, measurement_settings].spacing(20)
@@ -224,6 +255,17 @@ impl View {
     }
 }
 
+/// Date based project file in the documents directory of the user.
+fn suggested_path() -> PathBuf {
+    let directory = directories::UserDirs::new()
+        .and_then(|dirs| dirs.document_dir().map(Path::to_path_buf))
+        .unwrap_or_default();
+
+    let name = chrono::Local::now().format("raumklang-%Y-%m-%d_%H%M.json");
+
+    directory.join(name.to_string())
+}
+
 async fn check_directory(path: PathBuf) -> Result<(), Error> {
     if fs::try_exists(&path).await? && fs::read_dir(&path).await?.next_entry().await?.is_some() {
         return Err(Error::DirectoryNotEmpty(path.into()));
@@ -0,0 +1,192 @@
+use crate::{
+    audio,
+    data::{directory, logging},
+    log,
+};
+
+use iced::{
+    Alignment::Center,
+    Element,
+    Length::Fill,
+    widget::{button, checkbox, column, container, pick_list, row, scrollable, space, text},
+};
+use tracing::Level;
+
+use std::{io, path::PathBuf, sync::Arc};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Refresh,
+    Export,
+    LevelSelected(logging::Level),
+    WriteFileToggled(bool),
+    CopyDiagnosticInfo,
+    Close,
+}
+
+pub enum Action {
+    None,
+    Export(Arc<str>),
+    SettingsChanged(logging::Settings),
+    CopyDiagnosticInfo,
+    Close,
+}
+
+/// Number of log entries included in the diagnostic info.
+const DIAGNOSTIC_ENTRIES: usize = 200;
+
+/// Actions of the current session with their timestamps, e.g. to document
+/// a measurement session.
+#[derive(Debug)]
+pub struct View {
+    entries: Vec<log::Entry>,
+    settings: logging::Settings,
+}
+
+impl View {
+    pub fn new() -> Self {
+        Self {
+            entries: log::session(),
+            settings: log::settings(),
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::Refresh => {
+                self.entries = log::session();
+                Action::None
+            }
+            Message::Export => Action::Export(self.to_text().into()),
+            Message::LevelSelected(level) => {
+                self.settings.level = level;
+                Action::SettingsChanged(self.settings)
+            }
+            Message::WriteFileToggled(write_file) => {
+                self.settings.write_file = write_file;
+                Action::SettingsChanged(self.settings)
+            }
+            Message::CopyDiagnosticInfo => Action::CopyDiagnosticInfo,
+            Message::Close => Action::Close,
+        }
+    }
+
+    fn to_text(&self) -> String {
+        self.entries
+            .iter()
+            .map(|entry| format!("{entry}\n"))
+            .collect()
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let entries = column(self.entries.iter().map(|entry| {
+            let level = entry.level;
+
+            row![
+                text!("{}", entry.time.format("%H:%M:%S")).size(12),
+                text!("{level:>5}").size(12).style(move |theme| {
+                    let mut style = text::default(theme);
+                    let palette = theme.extended_palette();
+
+                    style.color = match level {
+                        Level::ERROR => Some(palette.danger.base.color),
+                        Level::WARN => Some(palette.warning.base.color),
+                        _ => None,
+                    };
+
+                    style
+                }),
+                text(&entry.message).size(12),
+            ]
+            .spacing(8)
+            .into()
+        }))
+        .spacing(2);
+
+        let settings = row![
+            text("Level"),
+            pick_list(
+                Some(&self.settings.level),
+                logging::Level::ALL,
+                logging::Level::to_string
+            )
+            .on_select(Message::LevelSelected),
+            checkbox(self.settings.write_file)
+                .label("Write log files")
+                .on_toggle(Message::WriteFileToggled),
+            space::horizontal(),
+            button("Copy diagnostic info")
+                .style(button::secondary)
+                .on_press(Message::CopyDiagnosticInfo),
+        ]
+        .spacing(8)
+        .align_y(Center);
+
+        container(
+            column![
+                text("Session log").size(18),
+                settings,
+                container(scrollable(entries).anchor_bottom().width(Fill))
+                    .style(container::bordered_box)
+                    .padding(5)
+                    .height(400),
+                row![
+                    button("Refresh")
+                        .style(button::secondary)
+                        .on_press(Message::Refresh),
+                    space::horizontal(),
+                    button("Export ...")
+                        .style(button::secondary)
+                        .on_press(Message::Export),
+                    button("Close").on_press(Message::Close),
+                ]
+                .spacing(5)
+            ]
+            .spacing(10),
+        )
+        .padding(20)
+        .width(700)
+        .style(container::bordered_box)
+        .into()
+    }
+}
+
+/// Collects information for bug reports: the versions, the state of the
+/// audio server and the recent log entries.
+pub async fn diagnostic_info() -> String {
+    let audio = tokio::task::spawn_blocking(audio::diagnostics)
+        .await
+        .unwrap_or_default();
+
+    let settings = log::settings();
+    let log_files = if settings.write_file {
+        format!("{:?}", directory::logs())
+    } else {
+        "disabled".to_string()
+    };
+
+    let entries = log::session();
+    let recent = &entries[entries.len().saturating_sub(DIAGNOSTIC_ENTRIES)..];
+
+    let mut info = format!(
+        "Raumklang {}\nOS: {} ({})\n{audio}\nLog level: {}, log files: {log_files}\n\nRecent log entries:\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        settings.level,
+    );
+
+    for entry in recent {
+        info.push_str(&format!("{entry}\n"));
+    }
+
+    info
+}
+
+pub async fn export(path: PathBuf, log: Arc<str>) -> Result<PathBuf, io::ErrorKind> {
+    tokio::fs::write(&path, log.as_bytes())
+        .await
+        .map_err(|err| err.kind())?;
+
+    Ok(path)
+}
@@ -0,0 +1,305 @@
+use crate::{
+    audio,
+    data::{audio::InPort, measurement},
+    log,
+    widget::{number_input, strip_chart::StripChart},
+};
+
+use raumklang_core::spl::{Record, TimeWeighting, Weighting};
+
+use iced::{
+    Alignment::Center,
+    Element,
+    Length::Fill,
+    Subscription, Task, task,
+    widget::{button, canvas, column, container, pick_list, right, row, rule, text},
+};
+use tokio_stream::wrappers::ReceiverStream;
+
+use std::{collections::VecDeque, sync::Arc, time::Duration};
+
+/// Time span of the strip chart.
+const HISTORY: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    AudioBackend(audio::Event),
+    Notification(audio::Notification),
+    WeightingSelected(Weighting),
+    TimeWeightingSelected(TimeWeighting),
+    CalibrationChanged(String),
+    Start,
+    Loudness(audio::Loudness),
+    Record(Record),
+    Stop,
+    Close,
+}
+
+pub enum Action {
+    None,
+    Task(Task<Message>),
+    Close,
+}
+
+/// Sound pressure level meter of the measurement input, shows the levels of
+/// the last two minutes as strip chart.
+#[derive(Debug)]
+pub struct View {
+    backend: Backend,
+    in_port: Option<InPort>,
+    weighting: Weighting,
+    time_weighting: TimeWeighting,
+    /// Sound pressure level in dB that corresponds to 0 dBFS.
+    calibration: String,
+    records: VecDeque<Record>,
+    state: State,
+    cache: canvas::Cache,
+}
+
+#[derive(Debug)]
+enum Backend {
+    Connecting(Option<(audio::Error, std::sync::mpsc::SyncSender<()>)>),
+    Connected(Arc<dyn audio::Backend>),
+}
+
+#[derive(Debug, Default)]
+enum State {
+    #[default]
+    Idle,
+    Running {
+        loudness: audio::Loudness,
+        _handle: task::Handle,
+    },
+    Stopped,
+}
+
+impl View {
+    pub fn new(config: &measurement::Config) -> Self {
+        Self {
+            backend: Backend::Connecting(None),
+            in_port: config.in_port.clone(),
+            weighting: Weighting::A,
+            time_weighting: TimeWeighting::Fast,
+            calibration: "120".to_string(),
+            records: VecDeque::new(),
+            state: State::Idle,
+            cache: canvas::Cache::new(),
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::AudioBackend(audio::Event::Ready(backend, receiver)) => {
+                let Some(receiver) = Arc::into_inner(receiver) else {
+                    return Action::None;
+                };
+
+                let mut tasks =
+                    vec![Task::stream(ReceiverStream::new(receiver)).map(Message::Notification)];
+
+                if let Some(port) = self.in_port.clone() {
+                    tasks.push(Task::future(backend.connect_in_port(port)).discard());
+                }
+
+                self.backend = Backend::Connected(backend);
+
+                Action::Task(Task::batch(tasks))
+            }
+            Message::AudioBackend(audio::Event::Error { err, retry_tx, .. }) => {
+                self.backend = Backend::Connecting(Some((err, retry_tx)));
+                self.state = State::Idle;
+
+                Action::None
+            }
+            Message::Notification(_) => Action::None,
+            Message::WeightingSelected(weighting) => {
+                self.weighting = weighting;
+                Action::None
+            }
+            Message::TimeWeightingSelected(time_weighting) => {
+                self.time_weighting = time_weighting;
+                Action::None
+            }
+            Message::CalibrationChanged(calibration) => {
+                self.calibration = calibration;
+                Action::None
+            }
+            Message::Start => {
+                let Backend::Connected(backend) = &self.backend else {
+                    return Action::None;
+                };
+
+                let Ok(calibration) = self.calibration.parse() else {
+                    return Action::None;
+                };
+
+                log::info!(
+                    "Sound pressure level meter started with {}, {} and {calibration} dB at 0 dBFS",
+                    self.weighting,
+                    self.time_weighting,
+                );
+
+                let (loudness, records) =
+                    backend.run_spl(self.weighting, self.time_weighting, calibration);
+
+                let (task, handle) = Task::batch([
+                    Task::stream(ReceiverStream::new(loudness)).map(Message::Loudness),
+                    Task::stream(ReceiverStream::new(records)).map(Message::Record),
+                ])
+                .abortable();
+
+                self.state = State::Running {
+                    loudness: audio::Loudness::default(),
+                    _handle: handle.abort_on_drop(),
+                };
+                self.records.clear();
+                self.cache.clear();
+
+                Action::Task(task)
+            }
+            Message::Loudness(new_loudness) => {
+                if let State::Running { loudness, .. } = &mut self.state {
+                    *loudness = new_loudness;
+                }
+
+                Action::None
+            }
+            Message::Record(record) => {
+                if matches!(self.state, State::Running { .. }) {
+                    if self.records.len() >= capacity() {
+                        self.records.pop_front();
+                    }
+
+                    self.records.push_back(record);
+                    self.cache.clear();
+                }
+
+                Action::None
+            }
+            Message::Stop => {
+                if let Backend::Connected(backend) = &self.backend {
+                    backend.stop();
+                }
+
+                // dropping the handle ends the logging, the chart is kept
+                self.state = State::Stopped;
+
+                Action::None
+            }
+            Message::Close => {
+                if let State::Running { .. } = self.state
+                    && let Backend::Connected(backend) = &self.backend
+                {
+                    backend.stop();
+                }
+
+                Action::Close
+            }
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let header =
+            column![text("Sound pressure level").size(20), rule::horizontal(1.0)].spacing(4);
+
+        let is_running = matches!(self.state, State::Running { .. });
+        let calibration = self.calibration.parse::<f32>();
+
+        let status: Element<_> = match (&self.backend, self.records.back()) {
+            (Backend::Connecting(None), _) => text("Connecting to the audio server ...").into(),
+            (Backend::Connecting(Some((err, _))), _) => text!("Audio server not available: {err}")
+                .style(text::danger)
+                .into(),
+            (Backend::Connected(_), Some(record)) => text!(
+                "Leq {:.1} {}, Lmax {:.1}, L10 {:.1}, L50 {:.1}, L90 {:.1}",
+                record.leq,
+                self.weighting,
+                record.lmax,
+                record.l10,
+                record.l50,
+                record.l90
+            )
+            .into(),
+            (Backend::Connected(_), None) => match &self.state {
+                State::Running { loudness, .. } => {
+                    text!("Input level {:.1} dBFS RMS", loudness.rms).into()
+                }
+                State::Idle | State::Stopped => {
+                    text("Press start to log the level of the measurement input.").into()
+                }
+            },
+        };
+
+        let settings = row![
+            text("Weighting"),
+            pick_list(Some(self.weighting), Weighting::ALL, Weighting::to_string)
+                .on_select(Message::WeightingSelected),
+            text("Time weighting"),
+            pick_list(
+                Some(self.time_weighting),
+                TimeWeighting::ALL,
+                TimeWeighting::to_string
+            )
+            .on_select(Message::TimeWeightingSelected),
+            text("0 dBFS"),
+            number_input(
+                &self.calibration,
+                calibration.as_ref().err(),
+                Message::CalibrationChanged
+            ),
+            text("dB"),
+        ]
+        .spacing(10)
+        .align_y(Center);
+
+        let chart = column![
+            canvas(StripChart::new(
+                &self.records,
+                capacity(),
+                audio::SPL_INTERVAL,
+                &self.cache
+            ))
+            .width(Fill)
+            .height(260),
+            text("Leq and Lmax of the last two minutes.").size(12),
+        ]
+        .spacing(3);
+
+        let action = if is_running {
+            button("Stop").style(button::danger).on_press(Message::Stop)
+        } else {
+            button("Start").style(button::success).on_press_maybe(
+                (matches!(self.backend, Backend::Connected(_)) && calibration.is_ok())
+                    .then_some(Message::Start),
+            )
+        };
+
+        let footer = row![
+            button("Close")
+                .style(button::secondary)
+                .on_press(Message::Close),
+            right(action),
+        ];
+
+        let mut content = column![header, chart, status].spacing(18);
+        if !is_running {
+            content = content.push(settings);
+        }
+
+        container(content.push(footer))
+            .style(container::bordered_box)
+            .padding(18)
+            .width(Fill)
+            .max_width(800)
+            .into()
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        Subscription::run(audio::run).map(Message::AudioBackend)
+    }
+}
+
+/// Number of records in the strip chart.
+fn capacity() -> usize {
+    (HISTORY.as_secs_f32() / audio::SPL_INTERVAL.as_secs_f32()).round() as usize
+}
@@ -0,0 +1,536 @@
+use crate::{
+    audio,
+    data::{audio::OutPort, measurement},
+    log,
+};
+
+use raumklang_core::{
+    alignment::{self, Alignment, SubAlignment, ToneResponses},
+    dbfs,
+};
+
+use iced::{
+    Alignment::Center,
+    Element,
+    Length::Fill,
+    Subscription, Task, task,
+    widget::{button, column, container, pick_list, right, row, rule, text},
+};
+use rustfft::num_complex::Complex32;
+use tokio_stream::wrappers::ReceiverStream;
+
+use std::{fmt, sync::Arc, time::Duration};
+
+/// How long the tone is played through each combination of speakers.
+const TONE_LENGTH: Duration = Duration::from_secs(3);
+
+/// Responses below this level are taken as silence, e.g. the speaker is off.
+const MIN_LEVEL: f32 = -60.0;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    AudioBackend(audio::Event),
+    Notification(audio::Notification),
+    MainsSelected(OutPort),
+    SubSelected(OutPort),
+    FrequencySelected(Frequency),
+    Start,
+    RecordingChunk(Box<[f32]>),
+    RecordingFinished,
+    Measured(Step, Option<Complex32>),
+    Stop,
+    Close,
+}
+
+pub enum Action {
+    None,
+    Task(Task<Message>),
+    Close,
+}
+
+/// Plays a tone at the crossover frequency through the mains, then the sub
+/// and then both, and recommends the delay and polarity of the sub, that
+/// sum both best at the listening position.
+#[derive(Debug)]
+pub struct View {
+    backend: Backend,
+    /// Connected again, when the alignment is finished.
+    out_port: Option<OutPort>,
+    out_ports: Vec<OutPort>,
+    mains: Option<OutPort>,
+    sub: Option<OutPort>,
+    frequency: Frequency,
+    volume: f32,
+    /// Responses of the finished steps, in the order they are played.
+    responses: Vec<Complex32>,
+    state: State,
+}
+
+#[derive(Debug)]
+enum Backend {
+    Connecting(Option<(audio::Error, std::sync::mpsc::SyncSender<()>)>),
+    Connected(Arc<dyn audio::Backend>),
+}
+
+#[derive(Debug, Default)]
+enum State {
+    #[default]
+    Idle,
+    Playing {
+        step: Step,
+        recording: Vec<f32>,
+        _handle: task::Handle,
+    },
+    Analysing(Step),
+    Finished(ToneResponses, Alignment),
+    /// Nothing was picked up in this step.
+    Failed(Step),
+}
+
+/// The speakers the tone is played through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    Mains,
+    Sub,
+    Both,
+}
+
+impl Step {
+    const ALL: [Step; 3] = [Step::Mains, Step::Sub, Step::Both];
+
+    fn next(self) -> Option<Self> {
+        match self {
+            Step::Mains => Some(Step::Sub),
+            Step::Sub => Some(Step::Both),
+            Step::Both => None,
+        }
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Step::Mains => "Mains",
+            Step::Sub => "Subwoofer",
+            Step::Both => "Both",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+/// Crossover frequency between the mains and the sub in Hz.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Frequency(u16);
+
+impl Frequency {
+    const ALL: [Frequency; 5] = [
+        Frequency(60),
+        Frequency(70),
+        Frequency(80),
+        Frequency(100),
+        Frequency(120),
+    ];
+}
+
+impl Default for Frequency {
+    fn default() -> Self {
+        Self(80)
+    }
+}
+
+impl fmt::Display for Frequency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} Hz", self.0)
+    }
+}
+
+impl View {
+    pub fn new(config: &measurement::Config) -> Self {
+        Self {
+            backend: Backend::Connecting(None),
+            out_port: config.out_port.clone(),
+            out_ports: vec![],
+            mains: config.out_port.clone(),
+            sub: None,
+            frequency: Frequency::default(),
+            volume: config.volume,
+            responses: vec![],
+            state: State::Idle,
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::AudioBackend(audio::Event::Ready(backend, receiver)) => {
+                let Some(receiver) = Arc::into_inner(receiver) else {
+                    return Action::None;
+                };
+
+                backend.set_volume(self.volume);
+                self.out_ports = backend.out_ports().to_vec();
+                self.backend = Backend::Connected(backend);
+
+                Action::Task(Task::stream(ReceiverStream::new(receiver)).map(Message::Notification))
+            }
+            Message::AudioBackend(audio::Event::Error { err, retry_tx, .. }) => {
+                self.backend = Backend::Connecting(Some((err, retry_tx)));
+                self.state = State::Idle;
+
+                Action::None
+            }
+            Message::Notification(audio::Notification::PortsChanged { out_ports, .. }) => {
+                self.mains = self.mains.take().filter(|port| out_ports.contains(port));
+                self.sub = self.sub.take().filter(|port| out_ports.contains(port));
+                self.out_ports = out_ports;
+
+                Action::None
+            }
+            Message::Notification(_) => Action::None,
+            // the selection is kept, while the tone is played
+            Message::MainsSelected(_) | Message::SubSelected(_) | Message::FrequencySelected(_)
+                if self.is_running() =>
+            {
+                Action::None
+            }
+            Message::MainsSelected(port) => {
+                self.mains = Some(port);
+                Action::None
+            }
+            Message::SubSelected(port) => {
+                self.sub = Some(port);
+                Action::None
+            }
+            Message::FrequencySelected(frequency) => {
+                self.frequency = frequency;
+                Action::None
+            }
+            Message::Start => {
+                self.responses.clear();
+                self.play(Step::Mains)
+            }
+            Message::RecordingChunk(chunk) => {
+                if let State::Playing { recording, .. } = &mut self.state {
+                    recording.extend_from_slice(&chunk);
+                }
+
+                Action::None
+            }
+            Message::RecordingFinished => {
+                let (Backend::Connected(backend), State::Playing { .. }) =
+                    (&self.backend, &self.state)
+                else {
+                    return Action::None;
+                };
+
+                let State::Playing {
+                    step, recording, ..
+                } = std::mem::take(&mut self.state)
+                else {
+                    return Action::None;
+                };
+
+                self.state = State::Analysing(step);
+                let sample_rate = u32::from(backend.sample_rate());
+                let frequency = f32::from(self.frequency.0);
+
+                Action::Task(Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || {
+                            let tone = alignment::tone(frequency, TONE_LENGTH, sample_rate);
+                            alignment::tone_response(&tone, &recording, frequency, sample_rate)
+                        })
+                        .await
+                        .ok()
+                        .flatten()
+                    },
+                    move |response| Message::Measured(step, response),
+                ))
+            }
+            Message::Measured(step, response) => {
+                let response = response.filter(|response| dbfs(response.norm()) > MIN_LEVEL);
+
+                let Some(response) = response else {
+                    log::warn!("Sub alignment: nothing picked up from {step}");
+                    self.state = State::Failed(step);
+
+                    return self.reconnect();
+                };
+
+                log::info!(
+                    "Sub alignment: {step} at {}, {:.1} dB, {:.0}°",
+                    self.frequency,
+                    dbfs(response.norm()),
+                    response.arg().to_degrees()
+                );
+                self.responses.push(response);
+
+                if let Some(next) = step.next() {
+                    return self.play(next);
+                }
+
+                let Backend::Connected(backend) = &self.backend else {
+                    return Action::None;
+                };
+
+                let [mains, sub, both] = self.responses[..] else {
+                    return Action::None;
+                };
+
+                let responses = ToneResponses { mains, sub, both };
+                let alignment = SubAlignment::new(f32::from(self.frequency.0)).find_at_crossover(
+                    mains,
+                    sub,
+                    u32::from(backend.sample_rate()),
+                );
+
+                self.state = State::Finished(responses, alignment);
+
+                self.reconnect()
+            }
+            Message::Stop => {
+                let State::Playing { step, .. } = self.state else {
+                    return Action::None;
+                };
+
+                if let Backend::Connected(backend) = &self.backend {
+                    backend.stop();
+                }
+
+                log::info!("Sub alignment stopped while playing through {step}");
+
+                // dropping the handle ends the recording
+                self.state = State::Idle;
+                self.responses.clear();
+
+                self.reconnect()
+            }
+            Message::Close => {
+                if let State::Playing { .. } = self.state
+                    && let Backend::Connected(backend) = &self.backend
+                {
+                    backend.stop();
+                }
+
+                self.state = State::Idle;
+                Action::Close
+            }
+        }
+    }
+
+    /// Plays the tone through the speakers of `step`.
+    fn play(&mut self, step: Step) -> Action {
+        let Backend::Connected(backend) = &self.backend else {
+            return Action::None;
+        };
+
+        let (Some(mains), Some(sub)) = (self.mains.clone(), self.sub.clone()) else {
+            return Action::None;
+        };
+
+        let ports = match step {
+            Step::Mains => vec![mains],
+            Step::Sub => vec![sub],
+            Step::Both => vec![mains, sub],
+        };
+
+        log::info!("Sub alignment: playing {} through {step}", self.frequency);
+
+        let tone = alignment::tone(
+            f32::from(self.frequency.0),
+            TONE_LENGTH,
+            u32::from(backend.sample_rate()),
+        );
+
+        let backend = backend.clone();
+        let play = Task::future(backend.connect_out_ports(ports)).then(move |()| {
+            let (loudness, mut data) = backend.run_signal(tone.clone());
+
+            let recording = iced::task::sipper(async move |mut progress| {
+                while let Some(chunk) = data.recv().await {
+                    progress.send(chunk).await;
+                }
+            });
+
+            // the meter is not shown, but stops the recording once dropped
+            Task::batch([
+                Task::stream(ReceiverStream::new(loudness)).discard(),
+                Task::sip(recording, Message::RecordingChunk, |_| {
+                    Message::RecordingFinished
+                }),
+            ])
+        });
+
+        let (play, handle) = play.abortable();
+
+        self.state = State::Playing {
+            step,
+            recording: vec![],
+            _handle: handle.abort_on_drop(),
+        };
+
+        Action::Task(play)
+    }
+
+    fn is_running(&self) -> bool {
+        matches!(self.state, State::Playing { .. } | State::Analysing(_))
+    }
+
+    /// Connects the original output again.
+    fn reconnect(&self) -> Action {
+        match (&self.backend, self.out_port.clone()) {
+            (Backend::Connected(backend), Some(port)) => {
+                Action::Task(Task::future(backend.connect_out_port(port)).discard())
+            }
+            _ => Action::None,
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let header =
+            column![text("Subwoofer alignment").size(20), rule::horizontal(1.0)].spacing(4);
+
+        let content: Element<_> = match &self.backend {
+            Backend::Connecting(None) => text("Connecting to the audio server ...").into(),
+            Backend::Connecting(Some((err, _))) => text!("Audio server not available: {err}")
+                .style(text::danger)
+                .into(),
+            Backend::Connected(_) => self.setup(),
+        };
+
+        let is_running = self.is_running();
+        let can_start = !is_running
+            && matches!(self.backend, Backend::Connected(_))
+            && self.mains.is_some()
+            && self.sub.is_some()
+            && self.mains != self.sub;
+
+        let footer = row![
+            button("Close")
+                .style(button::secondary)
+                .on_press(Message::Close),
+            right(if let State::Playing { .. } = self.state {
+                button("Stop").style(button::danger).on_press(Message::Stop)
+            } else {
+                button(if is_running { "Measuring ..." } else { "Start" })
+                    .style(button::success)
+                    .on_press_maybe(can_start.then_some(Message::Start))
+            }),
+        ];
+
+        container(column![header, content, self.result(), footer].spacing(18))
+            .style(container::bordered_box)
+            .padding(18)
+            .width(700)
+            .into()
+    }
+
+    fn setup(&self) -> Element<'_, Message> {
+        let steps = Step::ALL.into_iter().enumerate().map(|(index, step)| {
+            let status: Element<_> = match (&self.state, self.responses.get(index)) {
+                (State::Playing { step: playing, .. }, _) if *playing == step => {
+                    text("Playing ...").into()
+                }
+                (State::Analysing(analysing), _) if *analysing == step => {
+                    text("Analysing ...").into()
+                }
+                (State::Failed(failed), _) if *failed == step => {
+                    text("Nothing picked up").style(text::danger).into()
+                }
+                (_, Some(response)) => text!(
+                    "{:.1} dB, {:.0}°",
+                    dbfs(response.norm()),
+                    response.arg().to_degrees()
+                )
+                .into(),
+                (_, None) => text("-").into(),
+            };
+
+            row![text!("{step}").width(160), status].spacing(10).into()
+        });
+
+        column![
+            text(
+                "Place the microphone at the listening position. A tone at the \
+                 crossover frequency is played through the mains, then the sub \
+                 and then both, the delay and polarity of the sub, that sum both \
+                 best, are recommended."
+            ),
+            port_select(
+                "Mains",
+                self.mains.as_ref(),
+                &self.out_ports,
+                Message::MainsSelected
+            ),
+            port_select(
+                "Subwoofer",
+                self.sub.as_ref(),
+                &self.out_ports,
+                Message::SubSelected
+            ),
+            row![
+                text("Crossover").width(160),
+                pick_list(Some(self.frequency), Frequency::ALL, Frequency::to_string)
+                    .on_select(Message::FrequencySelected),
+            ]
+            .spacing(10)
+            .align_y(Center),
+            column(steps).spacing(6),
+        ]
+        .spacing(12)
+        .into()
+    }
+
+    fn result(&self) -> Element<'_, Message> {
+        let (State::Finished(responses, alignment), Backend::Connected(backend)) =
+            (&self.state, &self.backend)
+        else {
+            return column![].into();
+        };
+
+        let delay = alignment.delay_ms(u32::from(backend.sample_rate()));
+        let delay = if delay < 0.0 {
+            format!("Delay the mains by {:.2} ms", delay.abs())
+        } else {
+            format!("Delay the sub by {delay:.2} ms")
+        };
+
+        column![
+            text!(
+                "Summation at {} as set up: {:.1} dB",
+                self.frequency,
+                responses.efficiency()
+            ),
+            text!(
+                "{delay}, {} polarity of the sub, for a summation of {:.1} dB.",
+                alignment.polarity,
+                alignment.efficiency
+            )
+            .style(text::success),
+        ]
+        .spacing(6)
+        .into()
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        Subscription::run(audio::run).map(Message::AudioBackend)
+    }
+}
+
+fn port_select<'a>(
+    label: &'a str,
+    selected: Option<&'a OutPort>,
+    ports: &'a [OutPort],
+    on_select: fn(OutPort) -> Message,
+) -> Element<'a, Message> {
+    row![
+        text(label).width(160),
+        pick_list(selected, ports, OutPort::to_string)
+            .placeholder("Output port ...")
+            .on_select(on_select)
+            .width(Fill),
+    ]
+    .spacing(10)
+    .align_y(Center)
+    .into()
+}
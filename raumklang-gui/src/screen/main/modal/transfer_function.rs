@@ -0,0 +1,269 @@
+use crate::{
+    audio,
+    data::{
+        audio::{InPort, OutPort, Trim},
+        measurement,
+    },
+    log,
+    widget::transfer_function::TransferFunction,
+};
+
+use iced::{
+    Element,
+    Length::Fill,
+    Subscription, Task, task,
+    widget::{button, canvas, column, container, right, row, rule, text},
+};
+use tokio_stream::wrappers::ReceiverStream;
+
+use std::sync::Arc;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    AudioBackend(audio::Event),
+    Notification(audio::Notification),
+    Start,
+    Loudness(audio::Loudness),
+    Updated(audio::TransferFunction),
+    Stop,
+    Close,
+}
+
+pub enum Action {
+    None,
+    Task(Task<Message>),
+    Close,
+}
+
+/// Plays pink noise and shows the transfer function between the played and
+/// the recorded signal live, e.g. while speakers are placed or equalized.
+#[derive(Debug)]
+pub struct View {
+    backend: Backend,
+    out_port: Option<OutPort>,
+    in_port: Option<InPort>,
+    volume: f32,
+    trim: Trim,
+    state: State,
+    cache: canvas::Cache,
+}
+
+#[derive(Debug)]
+enum Backend {
+    Connecting(Option<(audio::Error, std::sync::mpsc::SyncSender<()>)>),
+    Connected(Arc<dyn audio::Backend>),
+}
+
+#[derive(Debug, Default)]
+enum State {
+    #[default]
+    Idle,
+    Running {
+        loudness: audio::Loudness,
+        transfer_function: Option<audio::TransferFunction>,
+        _handle: task::Handle,
+    },
+    Stopped(audio::TransferFunction),
+}
+
+impl View {
+    pub fn new(config: &measurement::Config) -> Self {
+        Self {
+            backend: Backend::Connecting(None),
+            out_port: config.out_port.clone(),
+            in_port: config.in_port.clone(),
+            volume: config.volume,
+            trim: config
+                .out_port
+                .as_ref()
+                .and_then(|port| config.output_trims.get(port))
+                .copied()
+                .unwrap_or_default(),
+            state: State::Idle,
+            cache: canvas::Cache::new(),
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::AudioBackend(audio::Event::Ready(backend, receiver)) => {
+                let Some(receiver) = Arc::into_inner(receiver) else {
+                    return Action::None;
+                };
+
+                let mut tasks =
+                    vec![Task::stream(ReceiverStream::new(receiver)).map(Message::Notification)];
+
+                if let Some(port) = self.out_port.clone() {
+                    tasks.push(Task::future(backend.connect_out_port(port)).discard());
+                }
+
+                if let Some(port) = self.in_port.clone() {
+                    tasks.push(Task::future(backend.connect_in_port(port)).discard());
+                }
+
+                backend.set_volume(self.volume);
+                backend.set_trim(self.trim);
+
+                self.backend = Backend::Connected(backend);
+
+                Action::Task(Task::batch(tasks))
+            }
+            Message::AudioBackend(audio::Event::Error { err, retry_tx, .. }) => {
+                self.backend = Backend::Connecting(Some((err, retry_tx)));
+                self.state = State::Idle;
+
+                Action::None
+            }
+            Message::Notification(_) => Action::None,
+            Message::Start => {
+                let Backend::Connected(backend) = &self.backend else {
+                    return Action::None;
+                };
+
+                log::info!("Live transfer function started");
+
+                let (loudness, transfer_function) = backend.run_transfer_function();
+
+                let (task, handle) = Task::batch([
+                    Task::stream(ReceiverStream::new(loudness)).map(Message::Loudness),
+                    Task::stream(ReceiverStream::new(transfer_function)).map(Message::Updated),
+                ])
+                .abortable();
+
+                self.state = State::Running {
+                    loudness: audio::Loudness::default(),
+                    transfer_function: None,
+                    _handle: handle.abort_on_drop(),
+                };
+                self.cache.clear();
+
+                Action::Task(task)
+            }
+            Message::Loudness(new_loudness) => {
+                if let State::Running { loudness, .. } = &mut self.state {
+                    *loudness = new_loudness;
+                }
+
+                Action::None
+            }
+            Message::Updated(new_transfer_function) => {
+                if let State::Running {
+                    transfer_function, ..
+                } = &mut self.state
+                {
+                    *transfer_function = Some(new_transfer_function);
+                    self.cache.clear();
+                }
+
+                Action::None
+            }
+            Message::Stop => {
+                if let Backend::Connected(backend) = &self.backend {
+                    backend.stop();
+                }
+
+                // dropping the handle ends the analysis, the last result is kept
+                self.state = match std::mem::take(&mut self.state) {
+                    State::Running {
+                        transfer_function: Some(transfer_function),
+                        ..
+                    } => State::Stopped(transfer_function),
+                    State::Running { .. } => State::Idle,
+                    state => state,
+                };
+
+                Action::None
+            }
+            Message::Close => {
+                if let State::Running { .. } = self.state
+                    && let Backend::Connected(backend) = &self.backend
+                {
+                    backend.stop();
+                }
+
+                Action::Close
+            }
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let header = column![
+            text("Live transfer function").size(20),
+            rule::horizontal(1.0)
+        ]
+        .spacing(4);
+
+        let transfer_function = match &self.state {
+            State::Running {
+                transfer_function, ..
+            } => transfer_function.as_ref(),
+            State::Stopped(transfer_function) => Some(transfer_function),
+            State::Idle => None,
+        };
+
+        let status: Element<_> = match (&self.backend, &self.state, transfer_function) {
+            (Backend::Connecting(None), _, _) => text("Connecting to the audio server ...").into(),
+            (Backend::Connecting(Some((err, _))), _, _) => {
+                text!("Audio server not available: {err}")
+                    .style(text::danger)
+                    .into()
+            }
+            (Backend::Connected(_), State::Idle, _) => {
+                text("Press start to play pink noise through the measurement output.").into()
+            }
+            (Backend::Connected(_), _, None) => text("Estimating the delay ...").into(),
+            (Backend::Connected(_), state, Some(transfer_function)) => {
+                let level = match state {
+                    State::Running { loudness, .. } => {
+                        format!(", level {:.1} dBFS RMS", loudness.rms)
+                    }
+                    _ => String::new(),
+                };
+
+                text!(
+                    "Delay {} ms, {} frames averaged{level}",
+                    transfer_function.delay.as_millis(),
+                    transfer_function.frames,
+                )
+                .into()
+            }
+        };
+
+        let chart = column![
+            canvas(TransferFunction::new(transfer_function, &self.cache))
+                .width(Fill)
+                .height(260),
+            text("Level relative to the played noise, the coherence below spans the full height.")
+                .size(12),
+        ]
+        .spacing(3);
+
+        let action = match &self.state {
+            State::Running { .. } => button("Stop").style(button::danger).on_press(Message::Stop),
+            State::Idle | State::Stopped(_) => {
+                button("Start").style(button::success).on_press_maybe(
+                    matches!(self.backend, Backend::Connected(_)).then_some(Message::Start),
+                )
+            }
+        };
+
+        let footer = row![
+            button("Close")
+                .style(button::secondary)
+                .on_press(Message::Close),
+            right(action),
+        ];
+
+        container(column![header, chart, status, footer].spacing(18))
+            .style(container::bordered_box)
+            .padding(18)
+            .width(Fill)
+            .max_width(800)
+            .into()
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        Subscription::run(audio::run).map(Message::AudioBackend)
+    }
+}
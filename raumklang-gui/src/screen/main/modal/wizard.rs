@@ -0,0 +1,404 @@
+use crate::{
+    audio,
+    data::audio::{InPort, OutPort},
+};
+
+use iced::{
+    Alignment::Center,
+    Element,
+    Length::Fill,
+    Task,
+    widget::{button, column, container, pick_list, right, row, rule, space, text},
+};
+
+use std::{fmt, path::PathBuf, time::Duration};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    BackendSelected(Backend),
+    CheckBackend,
+    BackendChecked(Result<audio::Probe, audio::Error>),
+    OutPortSelected(OutPort),
+    InPortSelected(InPort),
+    RecordLoopback,
+    LoadCalibration,
+    RunTestSweep,
+    Next,
+    Back,
+    Close,
+}
+
+pub enum Action {
+    None,
+    Task(Task<Message>),
+    /// The ports are selected and should be used for all recordings.
+    PortsSelected(OutPort, InPort),
+    RecordLoopback,
+    LoadCalibration,
+    RunTestSweep,
+    /// The wizard was finished or skipped, it is not shown on start anymore.
+    Close,
+}
+
+/// Guides new users through the setup of a measurement session.
+#[derive(Debug)]
+pub struct Wizard {
+    step: Step,
+    backend: Backend,
+    probe: Option<Result<audio::Probe, audio::Error>>,
+    out_port: Option<OutPort>,
+    in_port: Option<InPort>,
+    latency: Option<Duration>,
+    calibration: Option<PathBuf>,
+    test_sweep_recorded: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Step {
+    Backend,
+    Ports,
+    Loopback,
+    Calibration,
+    TestSweep,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Backend {
+    #[default]
+    Jack,
+}
+
+impl Backend {
+    const ALL: [Backend; 1] = [Backend::Jack];
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Backend::Jack => write!(f, "JACK (or PipeWire's JACK server)"),
+        }
+    }
+}
+
+impl Wizard {
+    pub fn new(out_port: Option<OutPort>, in_port: Option<InPort>) -> (Self, Task<Message>) {
+        let wizard = Self {
+            step: Step::Backend,
+            backend: Backend::default(),
+            probe: None,
+            out_port,
+            in_port,
+            latency: None,
+            calibration: None,
+            test_sweep_recorded: false,
+        };
+
+        (wizard, probe())
+    }
+
+    /// Called once the loopback is recorded, with the estimated latency.
+    pub fn loopback_recorded(&mut self, latency: Duration) {
+        self.latency = Some(latency);
+    }
+
+    pub fn calibration_loaded(&mut self, path: PathBuf) {
+        self.calibration = Some(path);
+    }
+
+    pub fn test_sweep_recorded(&mut self) {
+        self.test_sweep_recorded = true;
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::BackendSelected(backend) => {
+                self.backend = backend;
+                Action::Task(probe())
+            }
+            Message::CheckBackend => {
+                self.probe = None;
+                Action::Task(probe())
+            }
+            Message::BackendChecked(probe) => {
+                if let Ok(probe) = &probe {
+                    // forget ports, that don't exist anymore
+                    self.out_port = self
+                        .out_port
+                        .take()
+                        .filter(|port| probe.out_ports.contains(port));
+                    self.in_port = self
+                        .in_port
+                        .take()
+                        .filter(|port| probe.in_ports.contains(port));
+                }
+
+                self.probe = Some(probe);
+                Action::None
+            }
+            Message::OutPortSelected(port) => {
+                self.out_port = Some(port);
+                Action::None
+            }
+            Message::InPortSelected(port) => {
+                self.in_port = Some(port);
+                Action::None
+            }
+            Message::RecordLoopback => Action::RecordLoopback,
+            Message::LoadCalibration => Action::LoadCalibration,
+            Message::RunTestSweep => Action::RunTestSweep,
+            Message::Next => match self.step {
+                Step::Backend => {
+                    self.step = Step::Ports;
+                    Action::None
+                }
+                Step::Ports => {
+                    let (Some(out_port), Some(in_port)) = (&self.out_port, &self.in_port) else {
+                        return Action::None;
+                    };
+
+                    self.step = Step::Loopback;
+                    Action::PortsSelected(out_port.clone(), in_port.clone())
+                }
+                Step::Loopback => {
+                    self.step = Step::Calibration;
+                    Action::None
+                }
+                Step::Calibration => {
+                    self.step = Step::TestSweep;
+                    Action::None
+                }
+                Step::TestSweep => Action::Close,
+            },
+            Message::Back => {
+                self.step = match self.step {
+                    Step::Backend | Step::Ports => Step::Backend,
+                    Step::Loopback => Step::Ports,
+                    Step::Calibration => Step::Loopback,
+                    Step::TestSweep => Step::Calibration,
+                };
+
+                Action::None
+            }
+            Message::Close => Action::Close,
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let (title, content, can_continue): (_, Element<_>, _) = match self.step {
+            Step::Backend => (
+                "Audio backend",
+                self.backend_step(),
+                self.is_backend_ready(),
+            ),
+            Step::Ports => (
+                "Ports",
+                self.ports_step(),
+                self.out_port.is_some() && self.in_port.is_some(),
+            ),
+            Step::Loopback => ("Loopback", self.loopback_step(), self.latency.is_some()),
+            Step::Calibration => ("Microphone calibration", self.calibration_step(), true),
+            Step::TestSweep => ("First measurement", self.test_sweep_step(), true),
+        };
+
+        let position = self.step as usize + 1;
+
+        let header = column![
+            row![
+                text!("Setup - {title}").size(20),
+                space::horizontal(),
+                text!("Step {position} of 5").size(14)
+            ]
+            .align_y(Center),
+            rule::horizontal(1.0),
+        ]
+        .spacing(4);
+
+        let next = match self.step {
+            Step::TestSweep => "Finish",
+            Step::Calibration if self.calibration.is_none() => "Skip",
+            _ => "Next",
+        };
+
+        let footer = row![
+            button("Close")
+                .style(button::secondary)
+                .on_press(Message::Close),
+            right(
+                row![
+                    button("Back")
+                        .style(button::secondary)
+                        .on_press_maybe((self.step > Step::Backend).then_some(Message::Back)),
+                    button(next)
+                        .style(button::success)
+                        .on_press_maybe(can_continue.then_some(Message::Next)),
+                ]
+                .spacing(6)
+            ),
+        ];
+
+        container(column![header, content, footer].spacing(18))
+            .style(container::bordered_box)
+            .padding(18)
+            .width(600)
+            .into()
+    }
+
+    fn is_backend_ready(&self) -> bool {
+        matches!(self.probe, Some(Ok(_)))
+    }
+
+    fn backend_step(&self) -> Element<'_, Message> {
+        let status: Element<_> = match &self.probe {
+            None => text("Connecting ...").into(),
+            Some(Ok(probe)) => text!(
+                "Connected, running at {} Hz with a buffer size of {} frames.",
+                probe.sample_rate,
+                probe.buffer_size
+            )
+            .style(text::success)
+            .into(),
+            Some(Err(err)) => column![
+                text!("Not available: {err}").style(text::danger),
+                text("Start the audio server and check again."),
+            ]
+            .spacing(6)
+            .into(),
+        };
+
+        column![
+            text(
+                "Raumklang plays the measurement signal and records the response \
+                 through an audio server."
+            ),
+            row![
+                text("Backend"),
+                pick_list(Some(self.backend), Backend::ALL, Backend::to_string)
+                    .on_select(Message::BackendSelected),
+                button("Check again")
+                    .style(button::secondary)
+                    .on_press(Message::CheckBackend),
+            ]
+            .spacing(10)
+            .align_y(Center),
+            status,
+        ]
+        .spacing(12)
+        .into()
+    }
+
+    fn ports_step(&self) -> Element<'_, Message> {
+        let (out_ports, in_ports) = match &self.probe {
+            Some(Ok(probe)) => (probe.out_ports.clone(), probe.in_ports.clone()),
+            _ => (vec![], vec![]),
+        };
+
+        column![
+            text(
+                "Select the output, that feeds the amplifier or speaker, and the \
+                 input of the measurement microphone."
+            ),
+            row![
+                text("Out").width(40),
+                pick_list(self.out_port.as_ref(), out_ports, OutPort::to_string)
+                    .placeholder("Output port ...")
+                    .on_select(Message::OutPortSelected)
+                    .width(Fill),
+            ]
+            .spacing(10)
+            .align_y(Center),
+            row![
+                text("In").width(40),
+                pick_list(self.in_port.as_ref(), in_ports, InPort::to_string)
+                    .placeholder("Input port ...")
+                    .on_select(Message::InPortSelected)
+                    .width(Fill),
+            ]
+            .spacing(10)
+            .align_y(Center),
+        ]
+        .spacing(12)
+        .into()
+    }
+
+    fn loopback_step(&self) -> Element<'_, Message> {
+        let result = self.latency.map(|latency| {
+            text!(
+                "Loopback recorded, the round trip latency is {:.1} ms.",
+                latency.as_secs_f32() * 1000.0
+            )
+            .style(text::success)
+        });
+
+        column![
+            text(
+                "The loopback is a reference recording of the measurement signal. \
+                 Connect the output of your interface directly to an input and \
+                 record it. It is used to compute the impulse responses and to \
+                 estimate the latency of your interface."
+            ),
+            button(if self.latency.is_some() {
+                "Record again ..."
+            } else {
+                "Record loopback ..."
+            })
+            .on_press(Message::RecordLoopback),
+        ]
+        .push(result)
+        .spacing(12)
+        .into()
+    }
+
+    fn calibration_step(&self) -> Element<'_, Message> {
+        let loaded = self.calibration.as_ref().map(|path| {
+            text!("Using {}", path.display())
+                .style(text::success)
+                .size(14)
+        });
+
+        column![
+            text(
+                "Measurement microphones usually come with a calibration file, \
+                 that corrects their frequency response. It is applied to all \
+                 frequency responses of the project. You can skip this step, if \
+                 you don't have one."
+            ),
+            button("Load calibration file ...").on_press(Message::LoadCalibration),
+        ]
+        .push(loaded)
+        .spacing(12)
+        .into()
+    }
+
+    fn test_sweep_step(&self) -> Element<'_, Message> {
+        let result = self.test_sweep_recorded.then(|| {
+            text(
+                "Your first measurement is recorded, you find its analyses in the \
+                 tabs of the main window.",
+            )
+            .style(text::success)
+        });
+
+        column![
+            text(
+                "Place the microphone at the listening position and run a first \
+                 sweep. Adjust the volume during the loudness test, until the \
+                 level is in the green range."
+            ),
+            button("Run test sweep ...").on_press(Message::RunTestSweep),
+        ]
+        .push(result)
+        .spacing(12)
+        .into()
+    }
+}
+
+fn probe() -> Task<Message> {
+    Task::perform(
+        async {
+            tokio::task::spawn_blocking(audio::probe)
+                .await
+                .expect("probing the audio server")
+        },
+        Message::BackendChecked,
+    )
+}
@@ -2,13 +2,14 @@ use crate::{
     audio,
     data::{
         self, SampleRate,
-        audio::{InPort, OutPort},
-        measurement::{self, config},
+        audio::{Connections, InPort, OutPort, Trim},
+        measurement::{self, config, name, template},
         recording::{self, volume},
     },
-    log,
+    log, remote,
     screen::main::chart::{self},
-    widget::{RmsPeakMeter, meter},
+    unit,
+    widget::{RmsPeakMeter, meter, spectrum},
 };
 
 use iced::{
@@ -19,13 +20,22 @@ use iced::{
     alignment::{Horizontal, Vertical},
     task, time,
     widget::{
-        self, Button, button, canvas, center, column, container, pick_list, right, row, rule,
-        slider, space, text, text_input,
+        self, Button, button, canvas, center, checkbox, column, container, pick_list, right, row,
+        rule, slider, space, text, text_input,
     },
 };
 use tokio_stream::wrappers::ReceiverStream;
 
-use std::{fmt, sync::Arc, time::Duration};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    path::PathBuf,
+    sync::{
+        Arc,
+        atomic::{self, AtomicUsize},
+    },
+    time::Duration,
+};
 
 #[derive(Debug)]
 pub struct Recording {
@@ -35,16 +45,35 @@ pub struct Recording {
     backend: Backend,
     selected_in_port: Option<InPort>,
     selected_out_port: Option<OutPort>,
+    output_trims: BTreeMap<OutPort, Trim>,
     start_frequency: String,
     end_frequency: String,
     duration: String,
+    name_template: String,
+    capture_buffer: String,
+    /// Silence before the signal in milliseconds.
+    pre_roll: String,
+    templates: measurement::Templates,
+    template_name: String,
+    /// Expected RT60 in seconds, takes precedence over the room volume.
+    reverberation_time: String,
+    /// Room volume in m³, to estimate the RT60.
+    room_volume: String,
+    /// Spectrum of the signal, that is sent to the output port.
+    output_spectrum: Option<audio::Spectrum>,
     cache: canvas::Cache,
+    spectrum_cache: canvas::Cache,
 }
 
 #[derive(Debug, Default)]
 pub enum State {
     #[default]
     Setup,
+    /// Connections are confirmed, before the measurement is started.
+    Preflight {
+        config: measurement::SignalConfig,
+        connections: Connections,
+    },
     LoudnessTest {
         config: measurement::SignalConfig,
         loudness: audio::Loudness,
@@ -62,6 +91,9 @@ pub struct Measurement {
     config: measurement::SignalConfig,
 
     finished: bool,
+    truncation: Option<raumklang_core::TruncatedRecording>,
+    levels: Option<raumklang_core::loudness::LevelReport>,
+    dropped_frames: Arc<AtomicUsize>,
     cache: canvas::Cache,
     _stream_handle: task::Handle,
 }
@@ -75,7 +107,12 @@ pub enum Kind {
 #[derive(Debug)]
 enum Backend {
     Connecting(Option<Retry>),
-    Connected { backend: audio::Backend },
+    Connected {
+        backend: Arc<dyn audio::Backend>,
+        /// Ports of the backend, kept up to date by the notifications.
+        in_ports: Vec<InPort>,
+        out_ports: Vec<OutPort>,
+    },
 }
 
 #[derive(Debug)]
@@ -90,13 +127,33 @@ struct Retry {
 pub enum Message {
     OutPortSelected(OutPort),
     InPortSelected(InPort),
+    TrimChanged(f32),
+    MuteToggled(bool),
     StartFrequencyChanged(String),
     EndFrequencyChanged(String),
     DurationChanged(String),
+    NameTemplateChanged(String),
+    CaptureBufferChanged(String),
+    PreRollChanged(String),
+    ReverberationTimeChanged(String),
+    RoomVolumeChanged(String),
+    ImportConfig,
+    ConfigImported(std::result::Result<measurement::Config, config::ExchangeError>),
+    ExportConfig,
+    ConfigExported(std::result::Result<PathBuf, config::ExchangeError>),
+    TemplatesLoaded(std::result::Result<measurement::Templates, template::Error>),
+    TemplateSelected(raumklang_core::template::Template),
+    TemplateNameChanged(String),
+    SaveTemplate,
+    TemplatesSaved(std::result::Result<(), template::Error>),
+
+    CheckConnections(data::measurement::SignalConfig),
+    ConnectionsChecked(data::measurement::SignalConfig, Option<Connections>),
 
     VolumeChanged(f32),
     TestOk(recording::Volume),
     RmsChanged(audio::Loudness),
+    OutputSpectrumChanged(audio::Spectrum),
     RunTest(data::measurement::SignalConfig),
 
     AudioBackend(audio::Event),
@@ -110,9 +167,13 @@ pub enum Message {
 
     Back,
     Cancel,
+    /// Fades out the playback and aborts the running test or measurement.
+    StopAudio,
     RetryNow,
     Decline,
     Accept,
+
+    Trigger(remote::Trigger),
 }
 
 pub enum Action {
@@ -124,7 +185,10 @@ pub enum Action {
 
 pub enum Result {
     Loopback(raumklang_core::Loopback),
-    Measurement(raumklang_core::Measurement),
+    Measurement(
+        raumklang_core::Measurement,
+        Option<raumklang_core::loudness::LevelReport>,
+    ),
 }
 
 impl Recording {
@@ -136,14 +200,24 @@ impl Recording {
 
             selected_in_port: config.in_port,
             selected_out_port: config.out_port,
+            output_trims: config.output_trims,
 
             start_frequency: format!("{}", config.signal.start_frequency()),
             end_frequency: format!("{}", config.signal.end_frequency()),
-            duration: format!("{}", config.signal.duration().into_inner().as_secs()),
-
-            volume: 0.5,
+            duration: format!("{}", config.signal.duration().into_inner().as_secs_f32()),
+            name_template: config.name_template.as_str().to_string(),
+            capture_buffer: config.capture_buffer.to_string(),
+            pre_roll: config.pre_roll.as_millis().to_string(),
+            templates: measurement::Templates::default(),
+            template_name: String::new(),
+            reverberation_time: String::new(),
+            room_volume: String::new(),
+
+            volume: config.volume,
+            output_spectrum: None,
 
             cache: canvas::Cache::new(),
+            spectrum_cache: canvas::Cache::new(),
         }
     }
 
@@ -159,19 +233,21 @@ impl Recording {
 
                         if let Some(port) = self.selected_out_port.as_ref() {
                             tasks.push(
-                                Task::future(backend.clone().connect_out_port(port.clone()))
-                                    .discard(),
+                                Task::future(backend.connect_out_port(port.clone())).discard(),
                             )
                         }
 
                         if let Some(port) = self.selected_in_port.as_ref() {
                             tasks.push(
-                                Task::future(backend.clone().connect_in_port(port.clone()))
-                                    .discard(),
+                                Task::future(backend.connect_in_port(port.clone())).discard(),
                             );
                         }
 
-                        self.backend = Backend::Connected { backend };
+                        self.backend = Backend::Connected {
+                            in_ports: backend.in_ports().to_vec(),
+                            out_ports: backend.out_ports().to_vec(),
+                            backend,
+                        };
 
                         Action::Task(Task::batch(tasks))
                     } else {
@@ -199,7 +275,12 @@ impl Recording {
                 match notification {
                     audio::Notification::OutPortConnected(port) => {
                         log::debug!("out port {port} connected");
-                        self.selected_out_port = Some(port)
+                        let trim = self.output_trims.get(&port).copied().unwrap_or_default();
+                        self.selected_out_port = Some(port);
+
+                        if let Backend::Connected { backend, .. } = &self.backend {
+                            backend.set_trim(trim);
+                        }
                     }
                     audio::Notification::OutPortDisconnected => {
                         log::debug!("out port disconnected");
@@ -213,6 +294,23 @@ impl Recording {
                         log::debug!("in port disconnected");
                         self.selected_in_port = None
                     }
+                    audio::Notification::PortsChanged {
+                        in_ports,
+                        out_ports,
+                    } => {
+                        if let Backend::Connected {
+                            in_ports: ins,
+                            out_ports: outs,
+                            ..
+                        } = &mut self.backend
+                        {
+                            *ins = in_ports;
+                            *outs = out_ports;
+                        }
+                    }
+                    audio::Notification::Trigger(trigger) => {
+                        return self.update(Message::Trigger(trigger));
+                    }
                 }
 
                 Action::None
@@ -222,15 +320,94 @@ impl Recording {
                     return Action::None;
                 };
 
-                Action::Task(Task::future(backend.clone().connect_out_port(port)).discard())
+                Action::Task(Task::future(backend.connect_out_port(port)).discard())
             }
             Message::InPortSelected(port) => {
                 let Backend::Connected { backend, .. } = &self.backend else {
                     return Action::None;
                 };
 
-                Action::Task(Task::future(backend.clone().connect_in_port(port)).discard())
+                Action::Task(Task::future(backend.connect_in_port(port)).discard())
+            }
+            Message::ImportConfig => {
+                Action::Task(Task::future(pick_config_file()).and_then(|path| {
+                    Task::perform(measurement::Config::import(path), Message::ConfigImported)
+                }))
+            }
+            Message::ConfigImported(Ok(config)) => {
+                log::info!("Recording configuration imported");
+                self.apply_config(config)
+            }
+            Message::ConfigImported(Err(err)) => {
+                log::error!("Could not import recording configuration: {err}");
+                Action::None
+            }
+            Message::ExportConfig => {
+                let Some(config) = self.config() else {
+                    return Action::None;
+                };
+
+                Action::Task(
+                    Task::future(choose_config_file_path()).and_then(move |path| {
+                        Task::perform(config.clone().export(path), Message::ConfigExported)
+                    }),
+                )
+            }
+            Message::ConfigExported(Ok(path)) => {
+                log::info!("Recording configuration exported to {path:?}");
+                Action::None
+            }
+            Message::ConfigExported(Err(err)) => {
+                log::error!("Could not export recording configuration: {err}");
+                Action::None
+            }
+            Message::TemplatesLoaded(Ok(templates)) => {
+                self.templates = templates;
+                Action::None
+            }
+            Message::TemplatesLoaded(Err(err)) => {
+                log::error!("Could not load measurement templates: {err}");
+                Action::None
+            }
+            Message::TemplateSelected(template) => {
+                log::info!("Measurement template {} selected", template.name);
+
+                self.start_frequency = template.start_frequency.to_string();
+                self.end_frequency = template.end_frequency.to_string();
+                self.duration = template.duration.to_string();
+                self.volume = template.volume();
+                self.template_name = template.name;
+
+                if let Backend::Connected { backend, .. } = &self.backend {
+                    backend.set_volume(self.volume);
+                }
+
+                Action::None
+            }
+            Message::TemplateNameChanged(name) => {
+                self.template_name = name;
+                Action::None
+            }
+            Message::SaveTemplate => {
+                let name = self.template_name.trim().to_string();
+                let Some(config) = self.config().filter(|_| !name.is_empty()) else {
+                    return Action::None;
+                };
+
+                self.templates.insert(template::from_config(name, &config));
+
+                Action::Task(Task::perform(
+                    self.templates.clone().save(),
+                    Message::TemplatesSaved,
+                ))
+            }
+            Message::TemplatesSaved(Ok(())) => Action::None,
+            Message::TemplatesSaved(Err(err)) => {
+                log::error!("Could not save measurement templates: {err}");
+                Action::None
             }
+            Message::TrimChanged(gain) => self.update_trim(|trim| trim.gain = gain),
+            Message::MuteToggled(muted) => self.update_trim(|trim| trim.muted = muted),
             Message::RetryTick(instant) => {
                 let Backend::Connecting(Some(retry)) = &mut self.backend else {
                     return Action::None;
@@ -246,8 +423,9 @@ impl Recording {
                 };
 
                 self.volume = volume;
+                backend.set_volume(volume);
 
-                Action::Task(Task::future(backend.clone().set_volume(volume)).discard())
+                Action::None
             }
             Message::RmsChanged(new_loudness) => {
                 if let State::LoudnessTest { loudness, .. } = &mut self.state {
@@ -262,48 +440,108 @@ impl Recording {
 
                 Action::None
             }
+            Message::OutputSpectrumChanged(spectrum) => {
+                self.output_spectrum = Some(spectrum);
+                self.spectrum_cache.clear();
+
+                Action::None
+            }
+            Message::CheckConnections(signal_config) => {
+                let Backend::Connected { backend, .. } = &self.backend else {
+                    return Action::None;
+                };
+
+                Action::Task(Task::perform(backend.connections(), move |connections| {
+                    Message::ConnectionsChecked(signal_config, connections)
+                }))
+            }
+            Message::ConnectionsChecked(config, connections) => {
+                match connections {
+                    Some(connections) => {
+                        for issue in connections.issues(matches!(self.kind, Kind::Loopback)) {
+                            log::warn!("{issue}");
+                        }
+
+                        for issue in self.sweep_issues() {
+                            log::warn!("{issue}");
+                        }
+
+                        self.state = State::Preflight {
+                            config,
+                            connections,
+                        };
+                    }
+                    None => log::error!("querying the port connections failed"),
+                }
+
+                Action::None
+            }
             Message::RunTest(signal_config) => {
-                let Backend::Connected { backend } = &mut self.backend else {
+                let Backend::Connected { backend, .. } = &self.backend else {
                     return Action::None;
                 };
 
+                log::info!("Loudness test started at volume {:.2}", self.volume);
+
                 // FIXME duration not used
                 let duration = Duration::from_secs(3);
-                let rms_receiver = backend.run_test(duration);
+                let (rms_receiver, spectrum_receiver) = backend.run_test(duration);
 
-                let (recv, handle) = Task::stream(ReceiverStream::new(rms_receiver))
-                    .map(Message::RmsChanged)
-                    .abortable();
+                let (recv, handle) = Task::batch([
+                    Task::stream(ReceiverStream::new(rms_receiver)).map(Message::RmsChanged),
+                    Task::stream(ReceiverStream::new(spectrum_receiver))
+                        .map(Message::OutputSpectrumChanged),
+                ])
+                .abortable();
 
                 let handle = handle.abort_on_drop();
 
+                self.output_spectrum = None;
+                self.spectrum_cache.clear();
+
                 self.state = State::LoudnessTest {
                     config: signal_config,
                     loudness: audio::Loudness::default(),
                     _stream_handle: handle,
                 };
 
-                Action::Task(Task::batch([
-                    Task::future(backend.clone().set_volume(self.volume)).discard(),
-                    recv,
-                ]))
+                backend.set_volume(self.volume);
+
+                Action::Task(recv)
             }
             Message::TestOk(_volume) => {
-                let Backend::Connected { backend } = &self.backend else {
+                let Backend::Connected { backend, .. } = &self.backend else {
                     return Action::None;
                 };
 
                 let State::LoudnessTest {
                     config,
+                    loudness,
                     _stream_handle,
-                    ..
                 } = std::mem::take(&mut self.state)
                 else {
                     return Action::None;
                 };
 
-                let (loudness_receiver, mut data_receiver) =
-                    backend.run_measurement(config.clone());
+                log::info!(
+                    "Measurement started: sweep from {} Hz to {} Hz in {:.1} s, volume {:.2}, RMS {:.1} dBFS, peak {:.1} dBFS",
+                    config.start_frequency(),
+                    config.end_frequency(),
+                    config.duration().into_inner().as_secs_f32(),
+                    self.volume,
+                    loudness.rms,
+                    loudness.peak
+                );
+
+                let capture_buffer = parse_capture_buffer(&self.capture_buffer)
+                    .unwrap_or(config::DEFAULT_CAPTURE_BUFFER);
+                let pre_roll = parse_pre_roll(&self.pre_roll).unwrap_or_default();
+
+                let (loudness_receiver, spectrum_receiver, mut data_receiver, dropped_frames) =
+                    backend.run_measurement(config, capture_buffer, pre_roll);
+
+                self.output_spectrum = None;
+                self.spectrum_cache.clear();
 
                 let measurement_sipper = iced::task::sipper(async move |mut progress| {
                     while let Some(data) = data_receiver.recv().await {
@@ -323,11 +561,16 @@ impl Recording {
                     cache: canvas::Cache::new(),
                     _stream_handle: handle,
                     finished: false,
+                    truncation: None,
+                    levels: None,
+                    dropped_frames,
                     config,
                 };
 
                 let task = Task::batch(vec![
                     Task::stream(ReceiverStream::new(loudness_receiver)).map(Message::RmsChanged),
+                    Task::stream(ReceiverStream::new(spectrum_receiver))
+                        .map(Message::OutputSpectrumChanged),
                     sipper,
                 ]);
 
@@ -344,17 +587,69 @@ impl Recording {
                 Action::None
             }
             Message::RecordingFinished => {
+                let Backend::Connected { backend, .. } = &self.backend else {
+                    return Action::None;
+                };
+
                 if let State::Measurement(measurement) = &mut self.state {
                     measurement.finished = true;
+                    log::info!(
+                        "Measurement finished: {:.1} s recorded",
+                        measurement.data.len() as f32 / u32::from(backend.sample_rate()) as f32
+                    );
+
+                    let stimulus = audio::stimulus_len(&measurement.config, backend.sample_rate());
+                    measurement.truncation = raumklang_core::check_recording_length(
+                        stimulus,
+                        audio::decay_tail_len(backend.sample_rate()),
+                        measurement.data.len(),
+                    )
+                    .err();
+
+                    if let Some(truncation) = measurement.truncation {
+                        log::warn!("{truncation}");
+                    }
+
+                    let levels = audio::level_report(
+                        &measurement.config,
+                        &measurement.data,
+                        backend.sample_rate().into(),
+                    );
+                    log::info!(
+                        "Crest factor {:.1} dB (stimulus {:.1} dB), peak {:.1} dBFS, headroom {:.1} dB",
+                        levels.crest_factor,
+                        levels.stimulus_crest_factor,
+                        levels.peak,
+                        levels.headroom()
+                    );
+                    measurement.levels = Some(levels);
+
+                    let dropped = measurement.dropped_frames.load(atomic::Ordering::Relaxed);
+                    if dropped > 0 {
+                        log::warn!("{dropped} frames dropped, the capture buffer overflowed");
+                    }
                 };
                 Action::None
             }
             Message::Cancel => Action::Cancel,
+            Message::StopAudio => {
+                if let Backend::Connected { backend, .. } = &self.backend {
+                    backend.stop();
+                }
+
+                if !self.is_playing() {
+                    return Action::None;
+                }
+
+                log::warn!("Audio stopped, the running recording was aborted");
+                self.update(Message::Back)
+            }
             Message::Back => {
                 let state = std::mem::take(&mut self.state);
 
                 self.state = match state {
                     State::Setup => state,
+                    State::Preflight { .. } => State::Setup,
                     State::LoudnessTest { .. } => State::Setup,
                     State::Measurement(_measurement) => State::Setup,
                 };
@@ -382,16 +677,37 @@ impl Recording {
                 self.duration = duration;
                 Action::None
             }
+            Message::NameTemplateChanged(template) => {
+                self.name_template = template;
+                Action::None
+            }
+            Message::CaptureBufferChanged(capture_buffer) => {
+                self.capture_buffer = capture_buffer;
+                Action::None
+            }
+            Message::PreRollChanged(pre_roll) => {
+                self.pre_roll = pre_roll;
+                Action::None
+            }
+            Message::ReverberationTimeChanged(reverberation_time) => {
+                self.reverberation_time = reverberation_time;
+                Action::None
+            }
+            Message::RoomVolumeChanged(room_volume) => {
+                self.room_volume = room_volume;
+                Action::None
+            }
             Message::Chart(_interaction) => {
                 // no interaction needed at this point
                 Action::None
             }
             Message::Decline => {
+                log::info!("Measurement declined");
                 self.state = State::Setup;
                 Action::None
             }
             Message::Accept => {
-                let Backend::Connected { backend } = &self.backend else {
+                let Backend::Connected { backend, .. } = &self.backend else {
                     return Action::None;
                 };
 
@@ -400,38 +716,234 @@ impl Recording {
                 };
 
                 let signal = measurement.data;
-                let signal = raumklang_core::Measurement::new(backend.sample_rate.into(), signal);
+                let mut signal =
+                    raumklang_core::Measurement::new(backend.sample_rate().into(), signal);
+
+                // pad with silence, so that the impulse response is not cut
+                // at an arbitrary position
+                if let Some(truncation) = measurement.truncation {
+                    signal.pad_to(truncation.expected);
+                }
                 let result = match self.kind {
                     Kind::Loopback => Result::Loopback(raumklang_core::Loopback::new(signal)),
-                    Kind::Measurement => Result::Measurement(signal),
+                    Kind::Measurement => Result::Measurement(signal, measurement.levels),
                 };
 
                 let config = measurement::Config {
                     out_port: self.selected_out_port.take(),
                     in_port: self.selected_in_port.take(),
                     signal: measurement.config,
+                    volume: self.volume,
+                    name_template: name::Template::new(self.name_template.clone()),
+                    capture_buffer: parse_capture_buffer(&self.capture_buffer)
+                        .unwrap_or(config::DEFAULT_CAPTURE_BUFFER),
+                    pre_roll: parse_pre_roll(&self.pre_roll).unwrap_or_default(),
+                    output_trims: std::mem::take(&mut self.output_trims),
                 };
 
                 Action::Finished(config, result)
             }
+            Message::Trigger(trigger) => {
+                log::debug!("remote trigger: {trigger:?}");
+
+                let message = match (trigger, &self.state) {
+                    (remote::Trigger::Abort, _) => Some(Message::Cancel),
+                    (remote::Trigger::Next, State::Setup) => self.start(),
+                    (remote::Trigger::Next, State::Preflight { config, .. }) => {
+                        Some(Message::RunTest(*config))
+                    }
+                    (remote::Trigger::Next, State::LoudnessTest { loudness, .. }) => {
+                        recording::Volume::new(self.volume, loudness)
+                            .ok()
+                            .map(Message::TestOk)
+                    }
+                    (remote::Trigger::Next, State::Measurement(measurement)) => {
+                        measurement.finished.then_some(Message::Accept)
+                    }
+                    (remote::Trigger::Back, State::Setup) => None,
+                    (remote::Trigger::Back, State::Preflight { .. }) => Some(Message::Back),
+                    (remote::Trigger::Back, State::LoudnessTest { .. }) => Some(Message::Back),
+                    (remote::Trigger::Back, State::Measurement(measurement)) => {
+                        Some(match measurement.finished {
+                            true => Message::Decline,
+                            false => Message::Back,
+                        })
+                    }
+                };
+
+                match message {
+                    Some(message) => self.update(message),
+                    None => Action::None,
+                }
+            }
+        }
+    }
+
+    fn update_trim(&mut self, f: impl FnOnce(&mut Trim)) -> Action {
+        let (Backend::Connected { backend, .. }, Some(port)) =
+            (&self.backend, self.selected_out_port.as_ref())
+        else {
+            return Action::None;
+        };
+
+        let trim = self.output_trims.entry(port.clone()).or_default();
+        f(trim);
+
+        backend.set_trim(*trim);
+
+        Action::None
+    }
+
+    /// The configuration as currently entered, if all fields are valid.
+    fn config(&self) -> Option<measurement::Config> {
+        let range =
+            config::FrequencyRange::from_strings(&self.start_frequency, &self.end_frequency)
+                .ok()?;
+        let duration = config::Duration::from_string(&self.duration).ok()?;
+        let capture_buffer = parse_capture_buffer(&self.capture_buffer).ok()?;
+        let pre_roll = parse_pre_roll(&self.pre_roll).ok()?;
+
+        Some(measurement::Config {
+            out_port: self.selected_out_port.clone(),
+            in_port: self.selected_in_port.clone(),
+            signal: measurement::SignalConfig::new(range, duration),
+            volume: self.volume,
+            name_template: name::Template::new(self.name_template.clone()),
+            capture_buffer,
+            pre_roll,
+            output_trims: self.output_trims.clone(),
+        })
+    }
+
+    fn apply_config(&mut self, config: measurement::Config) -> Action {
+        self.start_frequency = format!("{}", config.signal.start_frequency());
+        self.end_frequency = format!("{}", config.signal.end_frequency());
+        self.duration = format!("{}", config.signal.duration().into_inner().as_secs_f32());
+        self.name_template = config.name_template.as_str().to_string();
+        self.capture_buffer = config.capture_buffer.to_string();
+        self.pre_roll = config.pre_roll.as_millis().to_string();
+        self.output_trims = config.output_trims;
+        self.volume = config.volume;
+
+        let Backend::Connected { backend, .. } = &self.backend else {
+            self.selected_out_port = config.out_port;
+            self.selected_in_port = config.in_port;
+
+            return Action::None;
+        };
+
+        backend.set_volume(self.volume);
+
+        // the ports are selected, once the backend reports the connections
+        let mut tasks = vec![];
+
+        if let Some(port) = config.out_port {
+            tasks.push(Task::future(backend.connect_out_port(port)).discard());
+        }
+
+        if let Some(port) = config.in_port {
+            tasks.push(Task::future(backend.connect_in_port(port)).discard());
         }
+
+        Action::Task(Task::batch(tasks))
+    }
+
+    /// Message that starts the loudness test, if the setup is complete.
+    fn start(&self) -> Option<Message> {
+        let range =
+            config::FrequencyRange::from_strings(&self.start_frequency, &self.end_frequency);
+        let duration = config::Duration::from_string(&self.duration);
+
+        let (Ok(range), Ok(duration), Ok(_), Ok(_)) = (
+            range,
+            duration,
+            parse_capture_buffer(&self.capture_buffer),
+            parse_pre_roll(&self.pre_roll),
+        ) else {
+            return None;
+        };
+
+        self.selected_out_port
+            .as_ref()
+            .and(self.selected_in_port.as_ref())
+            .map(|_| {
+                Message::CheckConnections(data::measurement::SignalConfig::new(range, duration))
+            })
+    }
+
+    /// RT60 in seconds, that is expected in the measured room.
+    fn expected_reverberation_time(&self) -> Option<f32> {
+        parse_optional(&self.reverberation_time)
+            .ok()
+            .flatten()
+            .or_else(|| {
+                parse_optional(&self.room_volume)
+                    .ok()
+                    .flatten()
+                    .map(raumklang_core::decay::expected_reverberation_time)
+            })
+    }
+
+    /// Checks, if the sweep and the silence after it are long enough to
+    /// record the whole decay of the room.
+    fn sweep_issues(&self) -> Vec<raumklang_core::decay::SweepIssue> {
+        let (Kind::Measurement, Some(reverberation_time), Ok(duration)) = (
+            &self.kind,
+            self.expected_reverberation_time(),
+            config::Duration::from_string(&self.duration),
+        ) else {
+            return vec![];
+        };
+
+        raumklang_core::decay::check_sweep(
+            duration.into_inner(),
+            audio::DECAY_TAIL,
+            reverberation_time,
+        )
     }
 
     pub fn view<'a>(&'a self) -> Element<'a, Message> {
         let page = match &self.backend {
             Backend::Connecting(retry) => self.retry(retry.as_ref()),
-            Backend::Connected { backend } => match &self.state {
-                State::Setup => self.setup(backend),
+            Backend::Connected {
+                backend,
+                in_ports,
+                out_ports,
+            } => match &self.state {
+                State::Setup => self.setup(in_ports, out_ports, backend.sample_rate()),
+                State::Preflight {
+                    config,
+                    connections,
+                } => self.preflight(config, connections, backend.sample_rate()),
                 State::LoudnessTest { loudness, .. } => {
-                    self.loudness_test(loudness, backend.sample_rate)
+                    self.loudness_test(loudness, backend.sample_rate())
                 }
                 State::Measurement(measurement) => {
-                    self.measurement(measurement, backend.sample_rate)
+                    self.measurement(measurement, backend.sample_rate())
                 }
             },
         };
 
-        container(page).width(600.0).into()
+        let toolbar = self.is_playing().then(|| {
+            right(
+                button("Stop audio (Esc)")
+                    .style(button::danger)
+                    .on_press(Message::StopAudio),
+            )
+        });
+
+        container(column![].push(toolbar).push(page).spacing(6))
+            .width(600.0)
+            .into()
+    }
+
+    /// Whether a test signal or the measurement signal is played.
+    pub fn is_playing(&self) -> bool {
+        match &self.state {
+            State::LoudnessTest { .. } => true,
+            State::Measurement(measurement) => !measurement.finished,
+            State::Setup | State::Preflight { .. } => false,
+        }
     }
 
     pub fn subscription(&self) -> Subscription<Message> {
@@ -446,11 +958,18 @@ impl Recording {
         Subscription::batch(subscriptions)
     }
 
-    fn setup<'a>(&'a self, backend: &'a audio::Backend) -> Element<'a, Message> {
+    fn setup<'a>(
+        &'a self,
+        in_ports: &'a [InPort],
+        out_ports: &'a [OutPort],
+        sample_rate: SampleRate,
+    ) -> Element<'a, Message> {
         let range =
             config::FrequencyRange::from_strings(&self.start_frequency, &self.end_frequency);
 
         let duration = config::Duration::from_string(&self.duration);
+        let capture_buffer = parse_capture_buffer(&self.capture_buffer);
+        let pre_roll = parse_pre_roll(&self.pre_roll);
 
         let ports = {
             field_group(
@@ -460,7 +979,7 @@ impl Recording {
                         text("Out"),
                         pick_list(
                             self.selected_out_port.as_ref(),
-                            backend.out_ports.as_slice(),
+                            out_ports,
                             OutPort::to_string
                         )
                         .on_select(Message::OutPortSelected)
@@ -471,21 +990,37 @@ impl Recording {
                             base
                         })
                     ]
+                    .push(self.selected_out_port.as_ref().map(|port| {
+                        let trim = self.output_trims.get(port).copied().unwrap_or_default();
+
+                        row![
+                            text("Trim"),
+                            slider(
+                                Trim::MIN_GAIN..=Trim::MAX_GAIN,
+                                trim.gain,
+                                Message::TrimChanged
+                            )
+                            .step(0.5),
+                            text(unit::signed_level(trim.gain, 1)).width(60),
+                            checkbox(trim.muted)
+                                .label("Mute")
+                                .on_toggle(Message::MuteToggled),
+                        ]
+                        .spacing(8)
+                        .align_y(Center)
+                    }))
                     .spacing(6),
                     column![
                         text("In"),
-                        pick_list(
-                            self.selected_in_port.as_ref(),
-                            backend.in_ports.as_slice(),
-                            InPort::to_string
-                        )
-                        .on_select(Message::InPortSelected)
-                        .style(|t, s| {
-                            let mut base = pick_list::default(t, s);
-                            base.background =
-                                iced::Background::Color(t.extended_palette().background.base.color);
-                            base
-                        })
+                        pick_list(self.selected_in_port.as_ref(), in_ports, InPort::to_string)
+                            .on_select(Message::InPortSelected)
+                            .style(|t, s| {
+                                let mut base = pick_list::default(t, s);
+                                base.background = iced::Background::Color(
+                                    t.extended_palette().background.base.color,
+                                );
+                                base
+                            })
                     ]
                     .spacing(6),
                 ]
@@ -520,28 +1055,111 @@ impl Recording {
                     duration.as_ref().err()
                 )
             ]
-            .spacing(8)
-        };
-
-        let ports_selected = self
-            .selected_out_port
-            .as_ref()
-            .and(self.selected_in_port.as_ref());
+            .push(matches!(self.kind, Kind::Measurement).then(|| {
+                field_group(
+                    "Name",
+                    column![
+                        text_input("{channel}-{position}-{date}", &self.name_template)
+                            .on_input(Message::NameTemplateChanged),
+                        text("Placeholders: {channel}, {position}, {date}, {time}").size(12)
+                    ]
+                    .spacing(4),
+                    None::<&String>,
+                )
+            }))
+            .push(matches!(self.kind, Kind::Measurement).then(|| {
+                let reverberation_time = parse_optional(&self.reverberation_time);
+                let room_volume = parse_optional(&self.room_volume);
 
-        let signal_config = if let (Ok(range), Ok(duration)) = (range, duration) {
-            Some(data::measurement::SignalConfig::new(range, duration))
-        } else {
-            None
+                field_group(
+                    "Expected reverb time",
+                    column![
+                        row![
+                            number_input(&self.reverberation_time, reverberation_time.is_ok())
+                                .label("RT60")
+                                .unit("s")
+                                .on_input(Message::ReverberationTimeChanged),
+                            number_input(&self.room_volume, room_volume.is_ok())
+                                .label("or room")
+                                .unit("m³")
+                                .on_input(Message::RoomVolumeChanged)
+                        ]
+                        .spacing(8)
+                        .align_y(Center),
+                    ]
+                    .extend(
+                        self.sweep_issues()
+                            .into_iter()
+                            .map(|issue| warning(issue.to_string())),
+                    )
+                    .spacing(4),
+                    reverberation_time.and(room_volume).err().as_ref(),
+                )
+            }))
+            .spacing(8)
         };
 
         let start_btn = button("Start")
             .style(button::success)
-            .on_press_maybe(ports_selected.and(signal_config).map(Message::RunTest));
+            .on_press_maybe(self.start());
+
+        let can_save_template = !self.template_name.trim().is_empty() && self.config().is_some();
+
+        let exchange = row![
+            pick_list(
+                None::<&raumklang_core::template::Template>,
+                self.templates.as_slice(),
+                raumklang_core::template::Template::to_string
+            )
+            .placeholder("Templates")
+            .on_select(Message::TemplateSelected),
+            text_input("Template name", &self.template_name)
+                .on_input(Message::TemplateNameChanged)
+                .on_submit(Message::SaveTemplate)
+                .width(140),
+            button(text("Save").size(12))
+                .style(button::secondary)
+                .on_press_maybe(can_save_template.then_some(Message::SaveTemplate)),
+            space::horizontal(),
+            button(text("Import ...").size(12))
+                .style(button::secondary)
+                .on_press(Message::ImportConfig),
+            button(text("Export ...").size(12))
+                .style(button::secondary)
+                .on_press_maybe(self.config().map(|_| Message::ExportConfig)),
+        ]
+        .spacing(6)
+        .align_y(Center);
 
         page(
             "Setup",
-            Some(backend.sample_rate),
-            row![ports, signal].spacing(8),
+            Some(sample_rate),
+            column![
+                row![
+                    column![
+                        ports,
+                        field_group(
+                            "Capture buffer",
+                            number_input(&self.capture_buffer, capture_buffer.is_ok())
+                                .unit("frames")
+                                .on_input(Message::CaptureBufferChanged),
+                            capture_buffer.as_ref().err()
+                        ),
+                        field_group(
+                            "Pre-roll",
+                            number_input(&self.pre_roll, pre_roll.is_ok())
+                                .unit("ms")
+                                .on_input(Message::PreRollChanged),
+                            pre_roll.as_ref().err()
+                        )
+                    ]
+                    .spacing(8),
+                    signal
+                ]
+                .spacing(8),
+                exchange
+            ]
+            .spacing(8),
             button("Cancel")
                 .style(button::danger)
                 .on_press(Message::Cancel),
@@ -550,6 +1168,87 @@ impl Recording {
         )
     }
 
+    fn preflight<'a>(
+        &'a self,
+        config: &'a measurement::SignalConfig,
+        connections: &'a Connections,
+        sample_rate: SampleRate,
+    ) -> Element<'a, Message> {
+        let issues = connections.issues(matches!(self.kind, Kind::Loopback));
+
+        let output = column(connections.playback.iter().map(|playback| {
+            column![text!("measurement_out → {}", playback.port)]
+                .extend(playback.shared_with.iter().map(|source| {
+                    text!("{source} → {}", playback.port)
+                        .size(12)
+                        .style(text::secondary)
+                        .into()
+                }))
+                .spacing(2)
+                .into()
+        }))
+        .spacing(6);
+
+        let input = column(
+            connections
+                .capture
+                .iter()
+                .map(|source| text!("{source} → measurement_in").into()),
+        )
+        .spacing(6);
+
+        let content = column![
+            field_group("Output", output, None::<&String>),
+            field_group("Input", input, None::<&String>),
+        ]
+        .push(
+            (!issues.is_empty())
+                .then(|| column(issues.iter().map(|issue| warning(issue.to_string()))).spacing(4)),
+        )
+        .push({
+            let issues = self.sweep_issues();
+
+            (!issues.is_empty())
+                .then(|| column(issues.iter().map(|issue| warning(issue.to_string()))).spacing(4))
+        })
+        .spacing(8);
+
+        page(
+            "Check connections",
+            Some(sample_rate),
+            content,
+            button("Cancel")
+                .style(button::danger)
+                .on_press(Message::Cancel),
+            Some(
+                button("Back")
+                    .style(button::secondary)
+                    .on_press(Message::Back),
+            ),
+            Some(
+                button("Continue")
+                    .style(button::success)
+                    .on_press(Message::RunTest(*config)),
+            ),
+        )
+    }
+
+    /// Spectrum of the played signal, `range` is the expected frequency range.
+    fn output_spectrum(&self, range: Option<(f32, f32)>) -> Element<'_, Message> {
+        let spectrum = spectrum::Spectrum::new(self.output_spectrum.as_ref(), &self.spectrum_cache);
+        let spectrum = match range {
+            Some((start, end)) => spectrum.range(start, end),
+            None => spectrum,
+        };
+
+        column![
+            text("Output spectrum").size(12),
+            canvas(spectrum).width(Fill).height(120),
+        ]
+        .spacing(3)
+        .into()
+    }
+
     fn loudness_test(
         &self,
         loudness: &audio::Loudness,
@@ -597,6 +1296,7 @@ impl Recording {
                 )
                 .center_x(Fill),
                 slider(0.0..=1.0, self.volume, Message::VolumeChanged).step(0.01),
+                self.output_spectrum(None),
             ]
             .spacing(10)
         ]
@@ -658,8 +1358,43 @@ impl Recording {
                 center(
                     chart::record_waveform(sample_rate, &measurement.data, &measurement.cache)
                         .map(Message::Chart),
-                )
+                ),
+                self.output_spectrum(Some((
+                    measurement.config.start_frequency().into(),
+                    measurement.config.end_frequency().into(),
+                ))),
             ]
+            .push(measurement.levels.map(|levels| {
+                text!(
+                    "Crest factor {:.1} dB (stimulus {:.1} dB), peak {:.1} dBFS, \
+                     headroom {:.1} dB",
+                    levels.crest_factor,
+                    levels.stimulus_crest_factor,
+                    levels.peak,
+                    levels.headroom()
+                )
+                .size(12)
+            }))
+            .push(measurement.truncation.map(|truncation| {
+                let missing = truncation.missing as f32 / f32::from(sample_rate) * 1000.0;
+
+                text!(
+                    "Recording ended {missing:.0} ms early, the decay of the impulse \
+                     response might be incomplete."
+                )
+                .style(text::warning)
+            }))
+            .push({
+                let dropped = measurement.dropped_frames.load(atomic::Ordering::Relaxed);
+
+                (dropped > 0).then(|| {
+                    text!(
+                        "{dropped} frames were dropped, because the capture buffer \
+                         overflowed. Try a larger capture buffer."
+                    )
+                    .style(text::warning)
+                })
+            })
             .height(500)
             .spacing(12)
             .padding(10)
@@ -741,6 +1476,68 @@ impl Recording {
     }
 }
 
+async fn pick_config_file() -> Option<PathBuf> {
+    let handle = rfd::AsyncFileDialog::new()
+        .set_title("Import recording configuration ...")
+        .add_filter("toml", &["toml"])
+        .add_filter("all", &["*"])
+        .pick_file()
+        .await?;
+
+    Some(handle.path().to_path_buf())
+}
+
+async fn choose_config_file_path() -> Option<PathBuf> {
+    let handle = rfd::AsyncFileDialog::new()
+        .set_title("Export recording configuration ...")
+        .set_file_name("recording.toml")
+        .add_filter("toml", &["toml"])
+        .save_file()
+        .await?;
+
+    Some(handle.path().to_path_buf())
+}
+
+fn parse_capture_buffer(capture_buffer: &str) -> std::result::Result<usize, &'static str> {
+    match capture_buffer.parse() {
+        Ok(size) if size > 0 => Ok(size),
+        _ => Err("needs to be a positive number"),
+    }
+}
+
+/// Silence before the signal, the device warm-up time.
+fn parse_pre_roll(pre_roll: &str) -> std::result::Result<Duration, &'static str> {
+    match pre_roll.trim().parse() {
+        Ok(millis) => Ok(Duration::from_millis(millis)),
+        _ => Err("needs to be a whole number"),
+    }
+}
+
+/// An empty field is valid and means, that the value is unknown.
+fn parse_optional(value: &str) -> std::result::Result<Option<f32>, &'static str> {
+    if value.trim().is_empty() {
+        return Ok(None);
+    }
+
+    match value.parse() {
+        Ok(value) if value > 0.0 => Ok(Some(value)),
+        _ => Err("needs to be a positive number"),
+    }
+}
+
+fn warning<'a, Message>(content: String) -> Element<'a, Message>
+where
+    Message: 'a,
+{
+    text(content)
+        .style(|theme| {
+            let mut style = text::default(theme);
+            style.color = Some(theme.extended_palette().warning.base.color);
+            style
+        })
+        .into()
+}
+
 fn field_group<'a, Message>(
     label: &'a str,
     content: impl Into<Element<'a, Message>>,
@@ -897,3 +1694,155 @@ where
         .padding(18)
         .into()
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::audio::mock::{Call, Mock};
+
+    fn config() -> measurement::Config {
+        let out_port = OutPort::new("system:playback_1".to_string());
+
+        measurement::Config {
+            out_port: Some(out_port.clone()),
+            in_port: Some(InPort::new("system:capture_1".to_string())),
+            output_trims: BTreeMap::from([(
+                out_port,
+                Trim {
+                    gain: -6.0,
+                    muted: false,
+                },
+            )]),
+            ..measurement::Config::default()
+        }
+    }
+
+    fn connected(mock: &Arc<Mock>) -> Recording {
+        let mut recording = Recording::new(Kind::Measurement, config());
+
+        let (event, _notifications) = mock.ready();
+        let _ = recording.update(Message::AudioBackend(event));
+
+        recording
+    }
+
+    fn mock() -> Arc<Mock> {
+        Mock::new(
+            vec![InPort::new("system:capture_1".to_string())],
+            vec![OutPort::new("system:playback_1".to_string())],
+        )
+    }
+
+    #[test]
+    fn ready_backend_connects_the_configured_ports() {
+        let mock = mock();
+        let recording = connected(&mock);
+
+        assert!(matches!(recording.backend, Backend::Connected { .. }));
+        assert_eq!(
+            mock.calls(),
+            [
+                Call::ConnectOutPort(OutPort::new("system:playback_1".to_string())),
+                Call::ConnectInPort(InPort::new("system:capture_1".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn connected_out_port_applies_its_trim() {
+        let mock = mock();
+        let mut recording = connected(&mock);
+        mock.calls();
+
+        let port = OutPort::new("system:playback_1".to_string());
+        let _ = recording.update(Message::JackNotification(
+            audio::Notification::OutPortConnected(port),
+        ));
+
+        assert_eq!(
+            mock.calls(),
+            [Call::SetTrim(Trim {
+                gain: -6.0,
+                muted: false
+            })]
+        );
+    }
+
+    #[test]
+    fn changed_ports_are_offered() {
+        let mock = mock();
+        let mut recording = connected(&mock);
+
+        let out_ports = vec![
+            OutPort::new("system:playback_1".to_string()),
+            OutPort::new("usb:playback_1".to_string()),
+        ];
+        let _ = recording.update(Message::JackNotification(
+            audio::Notification::PortsChanged {
+                in_ports: vec![],
+                out_ports: out_ports.clone(),
+            },
+        ));
+
+        let Backend::Connected {
+            in_ports,
+            out_ports: offered,
+            ..
+        } = &recording.backend
+        else {
+            panic!("backend is not connected");
+        };
+        assert!(in_ports.is_empty());
+        assert_eq!(offered, &out_ports);
+    }
+
+    #[test]
+    fn stop_audio_aborts_the_loudness_test() {
+        let mock = mock();
+        let mut recording = connected(&mock);
+        mock.calls();
+
+        let _ = recording.update(Message::RunTest(measurement::SignalConfig::default()));
+
+        assert!(recording.is_playing());
+        assert_eq!(
+            mock.calls(),
+            [Call::RunTest, Call::SetVolume(recording.volume)]
+        );
+
+        let _ = recording.update(Message::StopAudio);
+
+        assert!(!recording.is_playing());
+        assert!(matches!(recording.state, State::Setup));
+        assert_eq!(mock.calls(), [Call::Stop]);
+    }
+
+    #[test]
+    fn next_trigger_starts_the_measurement_at_a_valid_loudness() {
+        let mock = mock();
+        let mut recording = connected(&mock);
+
+        let _ = recording.update(Message::RunTest(measurement::SignalConfig::default()));
+        let _ = recording.update(Message::RmsChanged(audio::Loudness {
+            rms: -12.0,
+            peak: -6.0,
+        }));
+        mock.calls();
+
+        let _ = recording.update(Message::Trigger(remote::Trigger::Next));
+
+        assert!(matches!(recording.state, State::Measurement(_)));
+        assert_eq!(mock.calls(), [Call::RunMeasurement]);
+    }
+
+    #[test]
+    fn abort_trigger_cancels_the_recording() {
+        let mock = mock();
+        let mut recording = connected(&mock);
+
+        let action = recording.update(Message::Trigger(remote::Trigger::Abort));
+
+        assert!(matches!(action, Action::Cancel));
+    }
+}
@@ -0,0 +1,188 @@
+use std::{collections::BTreeMap, fmt};
+
+use iced::{
+    Alignment::Center,
+    Element,
+    Length::Fill,
+    widget::{button, canvas, center, column, container, pick_list, row, space, text},
+};
+use iced_aksel::axis::MarkerPosition;
+
+use crate::{
+    data,
+    screen::main::{
+        AxisId, DB_AXIS_ID, FREQ_AXIS_ID, chart, format_db_label, format_frequency_label,
+        modal::operation,
+    },
+    ui::{Analysis, measurement},
+};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    ViewSelected(View),
+    MeasurementSelected(operation::Operand),
+    Spectrogram(chart::spectrogram::Interaction),
+    Close,
+}
+
+/// Second analysis view, that is shown next to the active tab with a
+/// selection of its own.
+#[derive(Debug, Default)]
+pub struct Split {
+    pub view: View,
+    pub selected: Option<measurement::Id>,
+    zoom: chart::Zoom,
+    offset: chart::Offset,
+    pub cache: canvas::Cache,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum View {
+    #[default]
+    FrequencyResponse,
+    SpectralDecay,
+    Spectrogram,
+}
+
+impl View {
+    pub const ALL: [View; 3] = [
+        View::FrequencyResponse,
+        View::SpectralDecay,
+        View::Spectrogram,
+    ];
+}
+
+impl fmt::Display for View {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                View::FrequencyResponse => "Frequency Response",
+                View::SpectralDecay => "Spectral Decay",
+                View::Spectrogram => "Spectrogram",
+            }
+        )
+    }
+}
+
+impl Split {
+    /// Handles the interactions, that only concern the split view itself.
+    pub fn update(&mut self, message: &Message) {
+        match message {
+            Message::ViewSelected(view) => self.view = *view,
+            Message::MeasurementSelected(operand) => self.selected = Some(operand.id),
+            Message::Spectrogram(chart::spectrogram::Interaction::ZoomChanged(zoom)) => {
+                self.zoom = *zoom
+            }
+            Message::Spectrogram(chart::spectrogram::Interaction::OffsetChanged(offset)) => {
+                self.offset = *offset
+            }
+            Message::Close => {}
+        }
+
+        self.cache.clear();
+    }
+
+    pub fn view<'a>(
+        &'a self,
+        measurements: &'a measurement::List,
+        analyses: &'a BTreeMap<measurement::Id, Analysis>,
+        fr_state: &'a iced_aksel::State<AxisId, f32>,
+        normalization: data::spectrogram::Normalization,
+        level_offset: f32,
+        colormap: data::chart::Colormap,
+        time_unit: data::chart::TimeSeriesUnit,
+    ) -> Element<'a, Message> {
+        let operands: Vec<_> = measurements
+            .loaded()
+            .map(|m| operation::Operand {
+                id: m.id(),
+                name: m.name.clone(),
+            })
+            .collect();
+
+        let selected = self
+            .selected
+            .and_then(|id| operands.iter().find(|operand| operand.id == id))
+            .cloned();
+        let is_selected = selected.is_some();
+
+        let header = row![
+            pick_list(Some(self.view), View::ALL, View::to_string).on_select(Message::ViewSelected),
+            pick_list(selected, operands, operation::Operand::to_string)
+                .placeholder("Measurement ...")
+                .on_select(Message::MeasurementSelected),
+            space::horizontal(),
+            button("Close")
+                .style(button::secondary)
+                .on_press(Message::Close),
+        ]
+        .spacing(10)
+        .align_y(Center);
+
+        let analysis = self.selected.and_then(|id| analyses.get(&id));
+
+        let chart = || {
+            iced_aksel::Chart::new(fr_state)
+                .style(Box::new(|theme| {
+                    let mut base = iced_aksel::style::default(theme);
+                    let palette = theme.extended_palette();
+
+                    base.axis.label.color = palette.secondary.base.color;
+                    base.axis.tick.color = palette.secondary.base.color;
+                    base.axis.spine.color = palette.secondary.base.color;
+                    base.axis.grid.color = palette.background.weaker.color;
+
+                    base
+                }))
+                .marker(&FREQ_AXIS_ID, MarkerPosition::Cursor, |ctx| {
+                    Some(ctx.marker(format_frequency_label(ctx.value)))
+                })
+                .marker(&DB_AXIS_ID, MarkerPosition::Cursor, |ctx| {
+                    Some(ctx.marker(format_db_label(ctx.value)))
+                })
+        };
+
+        let content: Option<Element<'a, Message>> = match self.view {
+            View::FrequencyResponse => analysis
+                .map(|a| &a.frequency_response)
+                .filter(|fr| fr.result().is_some())
+                .map(|fr| chart().plot_data(fr, FREQ_AXIS_ID, DB_AXIS_ID).into()),
+            View::SpectralDecay => analysis
+                .map(|a| &a.spectral_decay)
+                .filter(|decay| decay.result().is_some())
+                .map(|decay| chart().plot_data(decay, FREQ_AXIS_ID, DB_AXIS_ID).into()),
+            View::Spectrogram => analysis.and_then(|a| a.spectrogram.result()).map(|data| {
+                chart::spectrogram(
+                    data,
+                    &self.cache,
+                    self.zoom,
+                    self.offset,
+                    normalization,
+                    level_offset,
+                    colormap,
+                    time_unit,
+                )
+                .map(Message::Spectrogram)
+            }),
+        };
+
+        let content = content.unwrap_or_else(|| {
+            let hint = if is_selected {
+                "Computing ..."
+            } else {
+                "Please select a measurement."
+            };
+
+            center(text(hint).size(18)).into()
+        });
+
+        container(column![header, content].spacing(10))
+            .padding(6)
+            .width(Fill)
+            .height(Fill)
+            .style(container::bordered_box)
+            .into()
+    }
+}
@@ -16,6 +16,7 @@ pub enum Tab {
         cache: canvas::Cache,
     },
     Spectrograms,
+    Crossover,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -25,4 +26,5 @@ pub enum Id {
     FrequencyResponses,
     SpectralDecays,
     Spectrograms,
+    Crossover,
 }
@@ -1,11 +1,15 @@
 pub mod analysis;
+pub mod curve;
 pub mod frequency_response;
+pub mod help;
 pub mod impulse_response;
 pub mod measurement;
+pub mod quality;
 pub mod spectral_decay;
 pub mod spectrogram;
 
 pub use analysis::Analysis;
+pub use curve::Curve;
 pub use frequency_response::FrequencyResponse;
 pub use impulse_response::ImpulseResponse;
 pub use measurement::{Loopback, Measurement};
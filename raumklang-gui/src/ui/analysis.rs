@@ -1,14 +1,22 @@
-use crate::ui::{
-    FrequencyResponse, ImpulseResponse, impulse_response, spectral_decay::SpectralDecay,
-    spectrogram::Spectrogram,
+use crate::{
+    data::{self, Samples, Window},
+    ui::{
+        FrequencyResponse, ImpulseResponse, impulse_response, spectral_decay::SpectralDecay,
+        spectrogram::Spectrogram,
+    },
 };
 
+use std::sync::Arc;
+
 #[derive(Debug, Clone, Default)]
 pub struct Analysis {
     pub impulse_response: impulse_response::State,
+    pub windowed_impulse_response: Option<Arc<raumklang_core::WindowedImpulseResponse>>,
     pub frequency_response: FrequencyResponse,
     pub spectral_decay: SpectralDecay,
     pub spectrogram: Spectrogram,
+    /// `None`, if denoising is turned off.
+    pub denoised: Option<impulse_response::Denoised>,
 }
 
 impl Analysis {
@@ -16,6 +24,26 @@ impl Analysis {
         self.impulse_response.result()
     }
 
+    /// The impulse response with the analysis `window` applied. It is computed
+    /// once, so that all analyses in the frequency domain use the same data.
+    pub(crate) fn windowed_impulse_response(
+        &mut self,
+        window: &Window<Samples>,
+        time_shift: isize,
+    ) -> Option<Arc<raumklang_core::WindowedImpulseResponse>> {
+        if self.windowed_impulse_response.is_none() {
+            let impulse_response = self.impulse_response.result()?;
+
+            self.windowed_impulse_response = Some(Arc::new(data::impulse_response::windowed(
+                &impulse_response.data,
+                window,
+                time_shift,
+            )));
+        }
+
+        self.windowed_impulse_response.clone()
+    }
+
     pub(crate) fn frequency_response_mut(&mut self) -> &mut FrequencyResponse {
         &mut self.frequency_response
     }
@@ -0,0 +1,398 @@
+use iced_aksel::{Measure, Plot, PlotData, PlotPoint, Stroke, shape};
+
+use crate::{data, ui::frequency_response::SpectrumLayer};
+
+/// Columns per decade of the logarithmic frequency axis, that points are
+/// reduced to before drawing. Enough for a few pixels per column, when a
+/// single octave is zoomed to the full width.
+const COLUMNS_PER_DECADE: f32 = 2_400.0;
+
+/// A single line in the frequency response chart that is not bound to a
+/// measurement, e.g. an imported compensation curve.
+#[derive(Debug, Clone)]
+pub struct Curve {
+    pub color: iced::Color,
+    pub points: Vec<PlotPoint<f32>>,
+}
+
+impl Curve {
+    pub fn new(color: iced::Color, points: impl IntoIterator<Item = PlotPoint<f32>>) -> Self {
+        Self {
+            color,
+            points: points.into_iter().collect(),
+        }
+    }
+}
+
+impl PlotData<f32> for Curve {
+    fn draw(&self, plot: &mut Plot<f32>, _theme: &iced::Theme) {
+        if self.points.len() < 2 {
+            return;
+        }
+
+        let line_stroke = Stroke::new(self.color, Measure::Screen(2.0));
+        plot.add_shape(shape::Polyline::new(decimate(&self.points), line_stroke));
+    }
+}
+
+/// A shaded area between a `lower` and an `upper` curve around a `center`
+/// line, e.g. the spread of several measurements around their mean.
+#[derive(Debug, Clone)]
+pub struct Band {
+    pub color: iced::Color,
+    pub center: Vec<PlotPoint<f32>>,
+    pub lower: Vec<PlotPoint<f32>>,
+    pub upper: Vec<PlotPoint<f32>>,
+}
+
+impl PlotData<f32> for Band {
+    fn draw(&self, plot: &mut Plot<f32>, _theme: &iced::Theme) {
+        if self.center.len() < 2 {
+            return;
+        }
+
+        let outline = self
+            .upper
+            .iter()
+            .chain(self.lower.iter().rev())
+            .copied()
+            .collect();
+        plot.add_shape(shape::Area::new(outline).fill(self.color.scale_alpha(0.25)));
+
+        let line_stroke = Stroke::new(self.color, Measure::Screen(2.0));
+        plot.add_shape(shape::Polyline::new(self.center.clone(), line_stroke));
+    }
+}
+
+/// Levels of several curves at a single frequency.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Statistics {
+    pub frequency: f32,
+    pub mean: f32,
+    pub min: f32,
+    pub max: f32,
+    /// Standard deviation of the levels in dB
+    pub deviation: f32,
+}
+
+/// Statistics of the `curves` at 48 points per octave from 20 Hz to
+/// 20 kHz, frequencies that are not covered by all curves are skipped.
+pub fn statistics(curves: &[&SpectrumLayer]) -> Vec<Statistics> {
+    if curves.is_empty() {
+        return vec![];
+    }
+
+    (0..)
+        .map(|i| 20.0 * 2f32.powf(i as f32 / 48.0))
+        .take_while(|&frequency| frequency <= 20_000.0)
+        .filter_map(|frequency| {
+            let levels: Vec<f32> = curves
+                .iter()
+                .map(|curve| curve.level_at(frequency))
+                .collect::<Option<_>>()?;
+
+            let count = levels.len() as f32;
+            let mean = levels.iter().sum::<f32>() / count;
+            let variance = levels.iter().map(|l| (l - mean).powi(2)).sum::<f32>() / count;
+
+            Some(Statistics {
+                frequency,
+                mean,
+                min: levels.iter().copied().fold(f32::INFINITY, f32::min),
+                max: levels.iter().copied().fold(f32::NEG_INFINITY, f32::max),
+                deviation: variance.sqrt(),
+            })
+        })
+        .collect()
+}
+
+/// Deviation of a frequency response from a target curve.
+#[derive(Debug, Clone)]
+pub struct TargetError {
+    /// Measured minus target level, after matching their average levels
+    pub curve: Curve,
+    /// RMS error in dB per octave band, by the band's center frequency
+    pub bands: Vec<(f32, f32)>,
+    /// Frequency and level of the largest deviation
+    pub max: Option<PlotPoint<f32>>,
+}
+
+/// Compares `measured` to `target` at 48 points per octave within the range
+/// of the target. Only the shape is compared, as the levels of measurements
+/// are arbitrary, the average difference is removed.
+pub fn target_error(
+    color: iced::Color,
+    measured: &SpectrumLayer,
+    target: &data::curve::Curve,
+) -> TargetError {
+    let (levels, offset) = aligned_levels(measured, target);
+
+    let points: Vec<_> = levels
+        .iter()
+        .map(|level| PlotPoint::new(level.frequency, level.measured - level.target - offset))
+        .collect();
+
+    let bands = raumklang_core::bands::fractional_octave(1, 31.5, 16_000.0)
+        .into_iter()
+        .filter_map(|band| {
+            let errors: Vec<f32> = points
+                .iter()
+                .filter(|p| band.contains(p.x))
+                .map(|p| p.y)
+                .collect();
+
+            if errors.is_empty() {
+                return None;
+            }
+
+            let rms = (errors.iter().map(|e| e * e).sum::<f32>() / errors.len() as f32).sqrt();
+            Some((band.center, rms))
+        })
+        .collect();
+
+    let max = points
+        .iter()
+        .copied()
+        .max_by(|a, b| a.y.abs().total_cmp(&b.y.abs()));
+
+    TargetError {
+        curve: Curve::new(color, points),
+        bands,
+        max,
+    }
+}
+
+/// How far a correction filter may boost or cut the measured response, and
+/// up to which frequency it acts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CorrectionLimits {
+    /// Maximum boost in dB
+    pub max_boost: f32,
+    /// Maximum cut in dB
+    pub max_cut: f32,
+    /// Highest corrected frequency in Hz
+    pub upper_frequency: f32,
+}
+
+impl Default for CorrectionLimits {
+    fn default() -> Self {
+        Self {
+            max_boost: 6.0,
+            max_cut: 12.0,
+            upper_frequency: 500.0,
+        }
+    }
+}
+
+/// The range, a correction within the `limits` can move the `measured`
+/// response to, around the target at the level of the measurement. Where
+/// the target leaves the band, the correction can't reach it.
+pub fn correction_envelope(
+    color: iced::Color,
+    measured: &SpectrumLayer,
+    target: &data::curve::Curve,
+    limits: &CorrectionLimits,
+) -> Band {
+    let (levels, offset) = aligned_levels(measured, target);
+    let levels: Vec<_> = levels
+        .into_iter()
+        .filter(|level| level.frequency <= limits.upper_frequency)
+        .collect();
+
+    Band {
+        color,
+        center: levels
+            .iter()
+            .map(|level| PlotPoint::new(level.frequency, level.target + offset))
+            .collect(),
+        lower: levels
+            .iter()
+            .map(|level| PlotPoint::new(level.frequency, level.measured - limits.max_cut))
+            .collect(),
+        upper: levels
+            .iter()
+            .map(|level| PlotPoint::new(level.frequency, level.measured + limits.max_boost))
+            .collect(),
+    }
+}
+
+struct Levels {
+    frequency: f32,
+    measured: f32,
+    target: f32,
+}
+
+/// Levels of `measured` and `target` at 48 points per octave within the
+/// range of the target, and the average difference between them.
+fn aligned_levels(measured: &SpectrumLayer, target: &data::curve::Curve) -> (Vec<Levels>, f32) {
+    let (Some(first), Some(last)) = (target.0.first(), target.0.last()) else {
+        return (vec![], 0.0);
+    };
+
+    let levels: Vec<_> = (0..)
+        .map(|i| 20.0 * 2f32.powf(i as f32 / 48.0))
+        .take_while(|&frequency| frequency <= 20_000.0)
+        .filter(|frequency| (first.0..=last.0).contains(frequency))
+        .filter_map(|frequency| {
+            Some(Levels {
+                frequency,
+                measured: measured.level_at(frequency)?,
+                target: target.level_at(frequency),
+            })
+        })
+        .collect();
+
+    let offset = levels
+        .iter()
+        .map(|level| level.measured - level.target)
+        .sum::<f32>()
+        / levels.len().max(1) as f32;
+
+    (levels, offset)
+}
+
+/// Level difference `a - b` at the frequencies of `a`, points of `b` are
+/// matched by the closest frequency.
+pub fn difference(a: &[PlotPoint<f32>], b: &[PlotPoint<f32>]) -> Vec<PlotPoint<f32>> {
+    if b.is_empty() {
+        return vec![];
+    }
+
+    a.iter()
+        .map(|p| {
+            let index = b.partition_point(|q| q.x < p.x).min(b.len() - 1);
+            PlotPoint::new(p.x, p.y - b[index].y)
+        })
+        .collect()
+}
+
+/// Reduces dense series, e.g. unsmoothed responses of long FFTs, to the
+/// minimum and maximum of each column of the logarithmic frequency axis.
+/// The points are expected to be sorted by frequency.
+pub fn decimate(points: &[PlotPoint<f32>]) -> Vec<PlotPoint<f32>> {
+    let column =
+        |p: &PlotPoint<f32>| (p.x.max(f32::MIN_POSITIVE).log10() * COLUMNS_PER_DECADE) as i64;
+
+    let mut decimated = Vec::with_capacity(points.len().min(8_192));
+    let mut rest = points;
+
+    while let Some(first) = rest.first() {
+        let len = rest.partition_point(|p| column(p) == column(first));
+        let (points, next) = rest.split_at(len.max(1));
+        rest = next;
+
+        if points.len() <= 2 {
+            decimated.extend_from_slice(points);
+            continue;
+        }
+
+        let (min, max) = points
+            .iter()
+            .enumerate()
+            .fold((0, 0), |(min, max), (i, p)| {
+                (
+                    if p.y < points[min].y { i } else { min },
+                    if p.y > points[max].y { i } else { max },
+                )
+            });
+
+        // keep the order, so that the line doesn't jump back
+        let (a, b) = if min <= max { (min, max) } else { (max, min) };
+        decimated.push(points[a]);
+        if b != a {
+            decimated.push(points[b]);
+        }
+    }
+
+    decimated
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn statistics_of_two_curves() {
+        let curve = |level| {
+            SpectrumLayer(vec![
+                PlotPoint::new(10.0, level),
+                PlotPoint::new(30_000.0, level),
+            ])
+        };
+        let (a, b) = (curve(-6.0), curve(-2.0));
+
+        let statistics = statistics(&[&a, &b]);
+
+        assert_eq!(statistics.len(), 479);
+        assert!(
+            statistics.iter().all(|s| {
+                s.mean == -4.0 && s.min == -6.0 && s.max == -2.0 && s.deviation == 2.0
+            })
+        );
+    }
+
+    #[test]
+    fn target_error_ignores_the_level() {
+        let measured = SpectrumLayer(vec![
+            PlotPoint::new(10.0, -40.0),
+            PlotPoint::new(1_000.0, -40.0),
+            PlotPoint::new(1_000.0, -34.0),
+            PlotPoint::new(30_000.0, -34.0),
+        ]);
+        let target = data::curve::Curve(vec![(100.0, 0.0), (10_000.0, 0.0)]);
+
+        let error = target_error(iced::Color::WHITE, &measured, &target);
+
+        // the measurement steps up by 6 dB at 1 kHz, half of the points are below
+        assert!(
+            error
+                .curve
+                .points
+                .iter()
+                .all(|p| (p.y.abs() - 3.0).abs() < 0.1)
+        );
+        assert!(error.bands.iter().all(|(_, rms)| (rms - 3.0).abs() < 0.1));
+        assert_eq!(error.bands.len(), 7);
+        assert!(error.max.is_some_and(|max| (max.y.abs() - 3.0).abs() < 0.1));
+    }
+
+    #[test]
+    fn correction_envelope_ends_at_the_upper_frequency() {
+        let measured = SpectrumLayer(vec![
+            PlotPoint::new(10.0, -40.0),
+            PlotPoint::new(30_000.0, -40.0),
+        ]);
+        let target = data::curve::Curve(vec![(20.0, 0.0), (20_000.0, 0.0)]);
+        let limits = CorrectionLimits::default();
+
+        let envelope = correction_envelope(iced::Color::WHITE, &measured, &target, &limits);
+
+        assert!(
+            envelope
+                .center
+                .iter()
+                .all(|p| p.x <= limits.upper_frequency)
+        );
+        assert!(envelope.center.iter().all(|p| p.y == -40.0));
+        assert!(envelope.upper.iter().all(|p| p.y == -34.0));
+        assert!(envelope.lower.iter().all(|p| p.y == -52.0));
+    }
+
+    #[test]
+    fn decimation_keeps_extremes() {
+        let points: Vec<_> = (0..1_000)
+            .map(|i| {
+                PlotPoint::new(
+                    1_000.0 + i as f32 * 0.01,
+                    if i == 500 { 6.0 } else { -(i as f32) },
+                )
+            })
+            .collect();
+
+        let decimated = decimate(&points);
+
+        assert!(decimated.len() < 100);
+        assert!(decimated.iter().any(|p| p.y == 6.0));
+        assert!(decimated.iter().any(|p| p.y == -999.0));
+    }
+}
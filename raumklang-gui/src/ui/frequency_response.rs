@@ -1,4 +1,5 @@
-use crate::data::{SampleRate, smooth_fractional_octave};
+use crate::data::{SampleRate, frequency_response::Grid, smooth_fractional_octave};
+use crate::ui::curve::decimate;
 use crate::widget::sidebar;
 use crate::{data, icon};
 
@@ -7,21 +8,31 @@ use iced::widget::stack;
 use iced::widget::text::IntoFragment;
 use iced::{
     Element, Length,
-    widget::{column, container, row, text, toggler},
+    widget::{button, column, container, row, text, toggler, tooltip},
 };
 
 use iced_aksel::{Measure, Plot, PlotData, PlotPoint, Stroke, shape};
 use rand::Rng as _;
-use raumklang_core::dbfs;
+use raumklang_core::{bands::Band, dbfs};
 
 #[derive(Debug, Clone)]
 pub struct FrequencyResponse {
     pub color: iced::Color,
     pub is_shown: bool,
+    pub emphasis: Emphasis,
 
     pub state: State,
 }
 
+/// How a curve stands out from the others, while one of them is hovered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Emphasis {
+    #[default]
+    Normal,
+    Highlighted,
+    Dimmed,
+}
+
 #[derive(Debug, Clone)]
 pub enum State {
     None,
@@ -35,6 +46,11 @@ pub struct Data {
     pub origin: data::FrequencyResponse,
     base_smoothed: SpectrumLayer,
     pub smoothed: Option<SpectrumLayer>,
+    /// Levels of fractional octave bands, that are drawn as bars instead of
+    /// the curve.
+    pub bands: Option<Vec<(Band, f32)>>,
+    /// Phase in degrees, that is drawn instead of the curve.
+    pub phase: Option<SpectrumLayer>,
 }
 
 #[derive(Debug, Clone)]
@@ -47,6 +63,7 @@ impl FrequencyResponse {
         Self {
             color,
             is_shown: true,
+            emphasis: Emphasis::Normal,
 
             state: State::None,
         }
@@ -55,7 +72,9 @@ impl FrequencyResponse {
     pub fn view<'a, Message>(
         &'a self,
         measurement_name: &'a str,
+        is_soloed: bool,
         on_toggle: impl Fn(bool) -> Message + 'a,
+        on_solo: Message,
     ) -> Element<'a, Message>
     where
         Message: Clone + 'a,
@@ -80,10 +99,24 @@ impl FrequencyResponse {
             .width(Length::Fill)
             .clip(true);
 
+            let solo = tooltip(
+                sidebar::button(text("S"))
+                    .style(if is_soloed {
+                        button::primary
+                    } else {
+                        button::secondary
+                    })
+                    .on_press(on_solo),
+                container(text("Show only this"))
+                    .padding(5)
+                    .style(container::bordered_box),
+                tooltip::Position::Bottom,
+            );
+
             let switch =
                 container(toggler(self.is_shown).on_toggle(on_toggle)).align_right(Length::Shrink);
 
-            row![color_dot, content, switch]
+            row![color_dot, content, solo, switch]
                 .align_y(Alignment::Center)
                 .spacing(10)
                 .padding(20)
@@ -119,9 +152,7 @@ impl FrequencyResponse {
     pub fn set_result(&mut self, fr: data::FrequencyResponse) {
         let data = smooth_fractional_octave(&fr.data, 48);
 
-        let sample_rate = fr.sample_rate;
-        let len = fr.data.len() * 2 + 1;
-        let resolution = sample_rate as f32 / len as f32;
+        let resolution = fr.resolution();
 
         // TODO: move computation into `SpectrumLayer` contructor?
         let base_smoothed = data
@@ -141,9 +172,53 @@ impl FrequencyResponse {
             origin: fr,
             base_smoothed: SpectrumLayer(base_smoothed),
             smoothed: None,
+            bands: None,
+            phase: None,
         })
     }
 
+    /// Computes the levels of the fractional octave bands, `None` draws the
+    /// curve again.
+    pub fn set_band_fraction(&mut self, fraction: Option<u8>) {
+        let State::Computed(data) = &mut self.state else {
+            return;
+        };
+
+        data.bands = fraction.map(|fraction| data.origin.band_levels(fraction));
+    }
+
+    /// Computes the phase curve, if it is `shown` instead of the levels.
+    pub fn set_phase_shown(&mut self, shown: bool) {
+        let State::Computed(data) = &mut self.state else {
+            return;
+        };
+
+        let points = if shown {
+            data.origin.phase_points(Grid::Raw)
+        } else {
+            None
+        };
+
+        data.phase = points.map(|points| {
+            SpectrumLayer(
+                points
+                    .into_iter()
+                    .map(|(frequency, phase)| PlotPoint::new(frequency, phase))
+                    .filter(|p| p.x > 0.0)
+                    .collect(),
+            )
+        });
+    }
+
+    /// The currently shown curve, smoothed if requested.
+    pub fn curve(&self) -> Option<&SpectrumLayer> {
+        let State::Computed(data) = &self.state else {
+            return None;
+        };
+
+        Some(data.smoothed.as_ref().unwrap_or(&data.base_smoothed))
+    }
+
     pub fn reset_smoothing(&mut self) {
         let State::Computed(data) = &mut self.state else {
             return;
@@ -194,6 +269,21 @@ where
 }
 
 impl SpectrumLayer {
+    /// Level at `frequency` in dB, linearly interpolated between the points.
+    pub fn level_at(&self, frequency: f32) -> Option<f32> {
+        let index = self.0.partition_point(|p| p.x < frequency);
+
+        let upper = self.0.get(index)?;
+        if index == 0 {
+            return (upper.x == frequency).then_some(upper.y);
+        }
+
+        let lower = self.0[index - 1];
+        let t = (frequency - lower.x) / (upper.x - lower.x);
+
+        Some(lower.y + t * (upper.y - lower.y))
+    }
+
     pub fn new<I>(data: I, sample_rate: SampleRate) -> Self
     where
         I: IntoIterator<Item = f32>,
@@ -201,7 +291,7 @@ impl SpectrumLayer {
     {
         let data = data.into_iter();
 
-        let len = data.clone().count() * 2 + 1;
+        let len = raumklang_core::FrequencyResponse::fft_len(data.clone().count());
         let resolution = f32::from(sample_rate) / len as f32;
 
         let curve = data
@@ -223,26 +313,81 @@ impl PlotData<f32> for FrequencyResponse {
             return;
         };
 
+        let (fill_alpha, line_alpha, line_width) = match self.emphasis {
+            Emphasis::Normal => (0.1, 0.8, 1.0),
+            Emphasis::Highlighted => (0.15, 1.0, 2.5),
+            Emphasis::Dimmed => (0.03, 0.25, 1.0),
+        };
+
+        let line_stroke = Stroke::new(
+            self.color.scale_alpha(line_alpha),
+            Measure::Screen(line_width),
+        );
+
+        if let Some(phase) = fr.phase.as_ref() {
+            if phase.0.len() >= 2 {
+                plot.add_shape(shape::Polyline::new(decimate(&phase.0), line_stroke));
+            }
+
+            return;
+        }
+
+        if let Some(bands) = fr.bands.as_ref() {
+            for (band, level) in bands {
+                let level = level.clamp(MIN_DB, 12.0);
+
+                let outline = vec![
+                    PlotPoint::new(band.lower, MIN_DB),
+                    PlotPoint::new(band.lower, level),
+                    PlotPoint::new(band.upper, level),
+                    PlotPoint::new(band.upper, MIN_DB),
+                ];
+
+                plot.add_shape(
+                    shape::Area::new(outline.clone())
+                        .fill(self.color.scale_alpha(fill_alpha * 2.0)),
+                );
+                plot.add_shape(shape::Polyline::new(outline, line_stroke));
+            }
+
+            return;
+        }
+
         if fr.base_smoothed.0.len() < 2 {
             return;
         }
 
+        let base = decimate(&fr.base_smoothed.0);
+
         // TODO: consider pre-computing the area, too
-        let mut fill_points = Vec::with_capacity(fr.base_smoothed.0.len() + 2);
+        let mut fill_points = Vec::with_capacity(base.len() + 2);
         fill_points.push(PlotPoint::new(MIN_FREQ, MIN_DB));
-        fill_points.extend(fr.base_smoothed.0.iter().copied());
+        fill_points.extend(base.iter().copied());
         fill_points.push(PlotPoint::new(MAX_FREQ, MIN_DB));
 
-        plot.add_shape(shape::Area::new(fill_points).fill(self.color.scale_alpha(0.1)));
-
-        let line_stroke = Stroke::new(self.color.scale_alpha(0.8), Measure::Screen(1.0));
+        plot.add_shape(shape::Area::new(fill_points).fill(self.color.scale_alpha(fill_alpha)));
         if let Some(smoothed) = fr.smoothed.as_ref() {
-            plot.add_shape(shape::Polyline::new(smoothed.0.clone(), line_stroke));
+            plot.add_shape(shape::Polyline::new(decimate(&smoothed.0), line_stroke));
         } else {
-            plot.add_shape(shape::Polyline::new(
-                fr.base_smoothed.0.clone(),
-                line_stroke,
-            ));
+            plot.add_shape(shape::Polyline::new(base, line_stroke));
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn level_is_interpolated_between_points() {
+        let layer = SpectrumLayer(vec![
+            PlotPoint::new(100.0, -10.0),
+            PlotPoint::new(200.0, -20.0),
+        ]);
+
+        assert_eq!(layer.level_at(150.0), Some(-15.0));
+        assert_eq!(layer.level_at(100.0), Some(-10.0));
+        assert_eq!(layer.level_at(50.0), None);
+        assert_eq!(layer.level_at(250.0), None);
+    }
+}
@@ -0,0 +1,239 @@
+use iced::{
+    Color, Element, Point, Rectangle, Renderer, Theme,
+    advanced::mouse,
+    widget::{
+        canvas::{self, Path, Stroke},
+        column, container, text, tooltip,
+    },
+};
+
+/// Terms of the analyses, that are explained in place for users without a
+/// background in signal processing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topic {
+    Window,
+    Gating,
+    Smoothing,
+    Regularization,
+    SpectralDecay,
+    Rt60,
+    Spectrogram,
+}
+
+impl Topic {
+    pub fn title(&self) -> &'static str {
+        match self {
+            Topic::Window => "Window",
+            Topic::Gating => "Gating",
+            Topic::Smoothing => "Smoothing",
+            Topic::Regularization => "Deconvolution and regularization",
+            Topic::SpectralDecay => "Spectral decay",
+            Topic::Rt60 => "Reverberation time (RT60)",
+            Topic::Spectrogram => "Spectrogram",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Topic::Window => {
+                "Selects the part of the impulse response, that is used for all \
+                 analyses. The edges are faded in and out, to avoid artifacts \
+                 from cutting the signal abruptly."
+            }
+            Topic::Gating => {
+                "Ends the window early, before the first reflections arrive. This \
+                 shows the direct sound of the speaker without the room, but the \
+                 shorter the gate the less detail is left at low frequencies."
+            }
+            Topic::Smoothing => {
+                "Averages the frequency response over a fraction of an octave, \
+                 e.g. 1/6. Narrow peaks and dips are hidden, so that the overall \
+                 tonal balance is easier to see."
+            }
+            Topic::Regularization => {
+                "The impulse response is computed by dividing the recorded \
+                 response by the loopback. Outside of the sweep range, the \
+                 loopback has almost no energy and the division amplifies noise. \
+                 Regularization limits the gain there."
+            }
+            Topic::SpectralDecay => {
+                "Frequency responses of successive, later parts of the impulse \
+                 response. It shows, which frequencies ring longer than others, \
+                 e.g. because of room modes."
+            }
+            Topic::Rt60 => {
+                "Time until the sound in the room decays by 60 dB. Long times \
+                 sound reverberant, short times dry. The decay is usually \
+                 measured over the first 20 or 30 dB and extrapolated."
+            }
+            Topic::Spectrogram => {
+                "Level over time and frequency, brighter colors are louder. The \
+                 normalization selects, whether the levels are relative to the \
+                 loudest point or to each point in time."
+            }
+        }
+    }
+}
+
+/// Small "?" marker, that explains the `topic` on hover.
+pub fn info<'a, Message: 'a>(topic: Topic) -> Element<'a, Message> {
+    let diagram = Diagram(topic).has_curves().then(|| {
+        canvas::Canvas::new(Diagram(topic))
+            .width(DIAGRAM_WIDTH)
+            .height(DIAGRAM_HEIGHT)
+    });
+
+    let popover = container(
+        column![
+            text(topic.title()).size(14),
+            text(topic.description()).size(12),
+        ]
+        .push(diagram)
+        .spacing(6),
+    )
+    .padding(8)
+    .max_width(320)
+    .style(container::bordered_box);
+
+    tooltip(
+        container(text("?").size(12))
+            .padding([0, 5])
+            .style(container::rounded_box),
+        popover,
+        tooltip::Position::Bottom,
+    )
+    .into()
+}
+
+const DIAGRAM_WIDTH: f32 = 220.0;
+const DIAGRAM_HEIGHT: f32 = 80.0;
+const POINTS: usize = 100;
+
+#[derive(Debug, Clone, Copy)]
+enum Style {
+    Signal,
+    Muted,
+    Result,
+    Danger,
+    Marker,
+}
+
+/// Sketch of a topic, drawn from curves in unit coordinates.
+struct Diagram(Topic);
+
+impl Diagram {
+    fn has_curves(&self) -> bool {
+        !self.curves().is_empty()
+    }
+
+    fn curves(&self) -> Vec<(Style, Vec<(f32, f32)>)> {
+        let sample = |f: &dyn Fn(f32) -> f32| {
+            (0..=POINTS)
+                .map(|i| {
+                    let x = i as f32 / POINTS as f32;
+                    (x, f(x))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        const GATE: f32 = 0.3;
+
+        let impulse = |x: f32| 0.5 + 0.45 * (-6.0 * x).exp() * (60.0 * x).cos();
+
+        match self.0 {
+            Topic::Window => vec![
+                (Style::Signal, sample(&impulse)),
+                (
+                    Style::Marker,
+                    sample(&|x| {
+                        let fade_in = ((x - 0.02) / 0.1).clamp(0.0, 1.0);
+                        let fade_out = ((0.9 - x) / 0.3).clamp(0.0, 1.0);
+                        let hann = |t: f32| 0.5 - 0.5 * (std::f32::consts::PI * t).cos();
+
+                        0.05 + 0.9 * hann(fade_in) * hann(fade_out)
+                    }),
+                ),
+            ],
+            Topic::Gating => {
+                let (direct, reflections): (Vec<_>, Vec<_>) =
+                    sample(&impulse).into_iter().partition(|(x, _)| *x <= GATE);
+
+                vec![
+                    (Style::Signal, direct),
+                    (Style::Muted, reflections),
+                    (Style::Marker, vec![(GATE, 0.0), (GATE, 1.0)]),
+                ]
+            }
+            Topic::Smoothing => vec![
+                (
+                    Style::Muted,
+                    sample(&|x| {
+                        0.5 + 0.3 * (x - 0.5) + 0.15 * (40.0 * x).sin() + 0.08 * (97.0 * x).sin()
+                    }),
+                ),
+                (Style::Result, sample(&|x| 0.5 + 0.3 * (x - 0.5))),
+            ],
+            Topic::Regularization => {
+                let edges = |x: f32| ((0.2 - x).max(0.0) + (x - 0.8).max(0.0)) * 5.0;
+
+                vec![
+                    (Style::Danger, sample(&|x| 0.3 + 0.7 * edges(x))),
+                    (Style::Result, sample(&|x| 0.3 + 0.7 * edges(x).min(0.3))),
+                ]
+            }
+            Topic::Rt60 => vec![
+                (Style::Marker, vec![(0.0, 0.9), (1.0, 0.9)]),
+                (Style::Marker, vec![(0.0, 0.1), (1.0, 0.1)]),
+                (
+                    Style::Signal,
+                    sample(&|x| 0.9 - 0.8 * x + 0.03 * (50.0 * x).sin() * x),
+                ),
+            ],
+            Topic::SpectralDecay | Topic::Spectrogram => vec![],
+        }
+    }
+}
+
+impl<Message> canvas::Program<Message> for Diagram {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<canvas::Geometry<Renderer>> {
+        let palette = theme.extended_palette();
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+
+        let to_point = |(x, y): (f32, f32)| Point::new(x * bounds.width, (1.0 - y) * bounds.height);
+
+        for (style, points) in self.curves() {
+            let path = Path::new(|builder| {
+                let mut points = points.iter().copied().map(to_point);
+
+                if let Some(first) = points.next() {
+                    builder.move_to(first);
+                }
+
+                for point in points {
+                    builder.line_to(point);
+                }
+            });
+
+            let color: Color = match style {
+                Style::Signal => palette.primary.base.color,
+                Style::Muted => palette.background.strong.color,
+                Style::Result => palette.success.base.color,
+                Style::Danger => palette.danger.base.color,
+                Style::Marker => palette.secondary.base.color,
+            };
+
+            frame.stroke(&path, Stroke::default().with_width(1.5).with_color(color));
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
@@ -13,6 +13,7 @@ use iced::{
     task::Sipper,
     widget::{button, column, right, row, rule, text},
 };
+use raumklang_core::decay::DecayTimes;
 
 #[derive(Debug, Clone)]
 pub enum Message {
@@ -50,12 +51,32 @@ impl State {
 
     pub(crate) fn compute(
         &self,
+        subject: data::scheduler::Subject,
         loopback: &raumklang_core::Loopback,
         measurement: &raumklang_core::Measurement,
+        method: raumklang_core::DeconvolutionMethod,
     ) -> Option<impl Sipper<data::ImpulseResponse, data::ImpulseResponse> + use<>> {
         match self {
             State::Computing(impulse_response) => {
-                impulse_response.clone().compute(loopback, measurement)
+                impulse_response
+                    .clone()
+                    .compute(subject, loopback, measurement, method)
+            }
+            State::Computed(_) => None,
+        }
+    }
+
+    pub(crate) fn compute_with<F>(
+        &self,
+        subject: data::scheduler::Subject,
+        compute: F,
+    ) -> Option<impl Sipper<data::ImpulseResponse, data::ImpulseResponse> + use<F>>
+    where
+        F: FnOnce() -> raumklang_core::ImpulseResponse + Send + 'static,
+    {
+        match self {
+            State::Computing(impulse_response) => {
+                impulse_response.clone().compute_with(subject, compute)
             }
             State::Computed(_) => None,
         }
@@ -67,12 +88,27 @@ pub struct ImpulseResponse {
     pub sample_rate: SampleRate,
     pub normalized: Vec<f32>,
     pub data: raumklang_core::ImpulseResponse,
+    /// Clock drift between playback and capture in ppm, already corrected.
+    pub drift: Option<f32>,
+    pub noise_floor_margin: f32,
+    pub decay_times: DecayTimes,
+}
+
+/// The impulse response with the recorded noise subtracted.
+#[derive(Debug, Clone)]
+pub enum Denoised {
+    Computing,
+    Computed(Box<ImpulseResponse>),
+    /// There is not enough silence in front of the measurement signal.
+    Failed,
 }
 
 impl ImpulseResponse {
     pub fn from_data(data: &data::ImpulseResponse) -> Option<Self> {
-        let impulse_response = data.result()?;
+        Some(Self::new(data.result()?.clone(), data.drift()))
+    }
 
+    pub fn new(impulse_response: raumklang_core::ImpulseResponse, drift: Option<f32>) -> Self {
         let max = impulse_response
             .data
             .iter()
@@ -80,18 +116,42 @@ impl ImpulseResponse {
             .max_by(f32::total_cmp)
             .unwrap();
 
-        let normalized = impulse_response
+        let normalized: Vec<f32> = impulse_response
             .data
             .iter()
             .map(|s| s.re)
             .map(|s| s / max.abs())
             .collect();
 
-        Some(Self {
+        // the decay starts at the direct sound
+        let causal = &normalized[..normalized.len() / 2];
+        let peak = causal
+            .iter()
+            .map(|s| s.abs())
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map_or(0, |(position, _)| position);
+        let decay_times = DecayTimes::new(&causal[peak..], impulse_response.sample_rate);
+
+        Self {
             sample_rate: SampleRate::new(impulse_response.sample_rate),
+            noise_floor_margin: data::quality::noise_floor_margin(&impulse_response),
             normalized,
-            data: impulse_response.clone(),
-        })
+            data: impulse_response,
+            drift,
+            decay_times,
+        }
+    }
+
+    /// First half of the deconvolution result, starting at time zero.
+    pub fn causal(&self) -> &[f32] {
+        &self.normalized[..self.normalized.len() / 2]
+    }
+
+    /// Second half of the deconvolution result, it belongs in front of time
+    /// zero and contains e.g. the harmonic distortion products of log sweeps.
+    pub fn acausal(&self) -> &[f32] {
+        &self.normalized[self.normalized.len() / 2..]
     }
 }
 
@@ -105,15 +165,20 @@ pub fn view<'a>(
     name: &'a str,
     date_time: SystemTime,
     progress: Option<impulse_response::Progress>,
+    drift: Option<f32>,
+    quality: Option<data::Quality>,
     active: bool,
 ) -> Element<'a, Message> {
     let entry = {
         let dt: DateTime<Utc> = date_time.into();
         let ir_btn = button(
             column![
-                text(name).size(16).wrapping(text::Wrapping::WordOrGlyph),
+                row![text(name).size(16).wrapping(text::Wrapping::WordOrGlyph)]
+                    .push(quality.map(super::quality::badge))
+                    .spacing(6),
                 text!("{}", dt.format("%x %X")).size(10)
             ]
+            .push(drift.map(|ppm| text!("Clock drift: {ppm:+.1} ppm").size(10)))
             .clip(true)
             .spacing(6),
         )
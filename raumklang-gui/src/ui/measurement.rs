@@ -4,26 +4,30 @@ pub use loopback::Loopback;
 
 use chrono::{DateTime, Utc};
 use iced::{
-    Element,
+    Element, Font,
     Length::{Fill, Shrink},
+    alignment::Vertical,
+    task::{Sipper, sipper},
     widget::{button, column, container, right, row, rule, text, tooltip},
 };
 
 use std::{
-    fmt::Display,
+    hash::{DefaultHasher, Hash, Hasher},
     path::{Path, PathBuf},
-    sync::{
-        Arc,
-        atomic::{self, AtomicUsize},
-    },
+    sync::Arc,
+    time::SystemTime,
 };
 
-use crate::{icon, widget::sidebar};
+use raumklang_core::store::Store;
+
+use crate::{data, icon, unit, widget::sidebar};
 
 #[derive(Debug, Clone)]
 pub enum Message {
     Select(Selected),
     Remove(Id),
+    Reload(Id),
+    RemoveDcOffset(Id),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
@@ -37,11 +41,26 @@ pub struct Measurement {
     id: Id,
     pub name: String,
     pub path: Option<PathBuf>,
+    /// Delay in samples applied before analysis, negative values advance.
+    pub time_shift: isize,
+    /// Playback level in dB relative to full volume, including the output
+    /// trim, if the measurement was recorded by us.
+    pub playback_level: Option<f32>,
+    /// Levels of the recording, if it was recorded by us.
+    pub levels: Option<raumklang_core::loudness::LevelReport>,
+    pub source: data::project::Source,
+    quality: Option<data::Quality>,
+    info: Option<data::measurement::Info>,
+    /// Detects the same recording loaded from different files.
+    fingerprint: Option<u64>,
+    /// The file was modified by another program after it was loaded.
+    changed_on_disk: bool,
     state: State,
+    /// The recording as it was loaded, if it is preprocessed.
+    original: Option<Arc<raumklang_core::Measurement>>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct Id(usize);
+pub type Id = raumklang_core::store::Id<Measurement>;
 
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone)]
@@ -56,8 +75,11 @@ impl Measurement {
         path: Option<PathBuf>,
         signal: Option<raumklang_core::Measurement>,
     ) -> Self {
-        static ID: AtomicUsize = AtomicUsize::new(0);
-        let id = Id(ID.fetch_add(1, atomic::Ordering::Relaxed));
+        let id = Id::unique();
+
+        let quality = signal.as_ref().map(data::Quality::from_signal);
+        let info = signal.as_ref().map(data::measurement::Info::from_signal);
+        let fingerprint = signal.as_ref().map(fingerprint);
 
         let state = match signal {
             Some(signal) => State::Loaded(Arc::new(signal)),
@@ -68,43 +90,129 @@ impl Measurement {
             id,
             name,
             path,
+            time_shift: 0,
+            playback_level: None,
+            levels: None,
+            source: data::project::Source::Recording,
+            quality,
+            info,
+            fingerprint,
+            changed_on_disk: false,
             state,
+            original: None,
         }
     }
 
     pub async fn from_file(path: impl AsRef<Path>) -> Self {
+        Self::load(path.as_ref().to_path_buf()).await
+    }
+
+    /// Loads the file at `path` in the background and reports the fraction
+    /// of the samples, that were read.
+    pub fn load(path: PathBuf) -> impl Sipper<Self, f32> {
+        sipper(async move |mut progress| {
+            let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+
+            let read = tokio::task::spawn_blocking({
+                let path = path.clone();
+                move || {
+                    raumklang_core::Measurement::from_file_with_progress(path, 0, |fraction| {
+                        let _ = sender.send(fraction);
+                    })
+                }
+            });
+
+            while let Some(fraction) = receiver.recv().await {
+                progress.send(fraction).await;
+            }
+
+            let signal = read.await.ok().and_then(Result::ok);
+
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_os_string().into_string().ok())
+                .unwrap_or("Unknown".to_string());
+
+            Self::new(name, Some(path), signal)
+        })
+    }
+
+    /// Imports an impulse response WAV file or a measurement text export of
+    /// REW, text exports are turned into an impulse response with
+    /// `sample_rate`.
+    pub async fn from_rew(path: impl AsRef<Path>, sample_rate: u32) -> Self {
         let path = path.as_ref();
 
-        let name = path
+        let file_name = path
             .file_name()
             .and_then(|n| n.to_os_string().into_string().ok())
             .unwrap_or("Unknown".to_string());
 
-        let signal = raumklang_core::Measurement::from_file(path).ok();
+        let is_text = path
+            .extension()
+            .is_some_and(|extension| !extension.eq_ignore_ascii_case("wav"));
+
+        let (name, signal) = if is_text {
+            let export = tokio::fs::read_to_string(path)
+                .await
+                .ok()
+                .and_then(|content| raumklang_core::rew::TextExport::parse(&content).ok());
+
+            match export {
+                Some(export) => {
+                    let len = sample_rate.next_power_of_two() as usize;
+                    let impulse_response = export.impulse_response(sample_rate, len);
+                    let data = impulse_response.data.iter().map(|s| s.re).collect();
+
+                    (
+                        export.name.unwrap_or(file_name),
+                        Some(raumklang_core::Measurement::new(sample_rate, data)),
+                    )
+                }
+                None => (file_name, None),
+            }
+        } else {
+            (file_name, raumklang_core::Measurement::from_file(path).ok())
+        };
 
-        let path = Some(path.to_path_buf());
-        Self::new(name, path, signal)
+        Self {
+            source: data::project::Source::Rew,
+            ..Self::new(name, Some(path.to_path_buf()), signal)
+        }
+    }
+
+    /// The impulse response of an imported measurement, it doesn't need to
+    /// be deconvolved with the loopback.
+    pub fn imported_impulse_response(&self) -> Option<data::ImpulseResponse> {
+        match self.source {
+            data::project::Source::Recording => None,
+            data::project::Source::Rew => Some(data::ImpulseResponse::imported(
+                raumklang_core::rew::impulse_response(self.signal()?),
+            )),
+        }
     }
 
     // TODO error handling
-    pub fn save(self, path: impl AsRef<Path>) -> impl Future<Output = Option<PathBuf>> {
+    pub fn save(
+        self,
+        path: impl AsRef<Path>,
+        format: raumklang_core::wav::Format,
+        metadata: raumklang_core::wav::Metadata,
+    ) -> impl Future<Output = Option<PathBuf>> {
         let path = path.as_ref().to_path_buf();
         async move {
             tokio::task::spawn_blocking(move || {
                 let signal = self.signal()?;
 
-                let spec = hound::WavSpec {
-                    channels: 1,
-                    sample_rate: signal.sample_rate(),
-                    bits_per_sample: 32,
-                    sample_format: hound::SampleFormat::Float,
-                };
-
-                let mut writer = hound::WavWriter::create(&path, spec).unwrap();
-                for s in signal.iter() {
-                    writer.write_sample(*s).unwrap();
-                }
-                writer.finalize().unwrap();
+                raumklang_core::wav::write(
+                    &path,
+                    signal.sample_rate(),
+                    1,
+                    signal.iter().copied(),
+                    format,
+                    Some(&metadata),
+                )
+                .unwrap();
 
                 Some(path)
             })
@@ -113,49 +221,91 @@ impl Measurement {
         }
     }
 
-    pub fn view(&self, active: bool) -> Element<'_, Message> {
+    /// Shows the entry in the sidebar, the sample rate is highlighted, if it
+    /// differs from the `expected_sample_rate` of the loopback.
+    pub fn view(
+        &self,
+        active: bool,
+        quality: Option<data::Quality>,
+        expected_sample_rate: Option<u32>,
+    ) -> Element<'_, Message> {
         let info: Element<_> = match &self.signal() {
             Some(signal) => {
                 let dt: DateTime<Utc> = signal.modified.into();
+
+                let sample_rate = signal.sample_rate();
+                let is_mismatch = expected_sample_rate.is_some_and(|rate| rate != sample_rate);
+
                 column![
                     text("Last modified:").size(10),
-                    text!("{}", dt.format("%x %X")).size(10)
+                    text!("{}", dt.format("%x %X")).size(10),
+                    text!("{sample_rate} Hz")
+                        .size(10)
+                        .style(move |theme| if is_mismatch {
+                            text::danger(theme)
+                        } else {
+                            text::Style::default()
+                        })
                 ]
+                .push(
+                    self.playback_level
+                        .map(|level| text!("Playback {}", unit::signed_level(level, 1)).size(10)),
+                )
+                .push(self.levels.map(|levels| {
+                    text!(
+                        "Headroom {:.1} dB, crest factor {:.1} dB",
+                        levels.headroom(),
+                        levels.crest_factor
+                    )
+                    .size(10)
+                }))
+                .push(
+                    self.changed_on_disk
+                        .then(|| text("Changed on disk").size(10).style(text::warning)),
+                )
                 .into()
             }
             None => text("Offline").style(text::danger).into(),
         };
 
-        let measurement_btn = button(
-            column![text(&self.name).wrapping(text::Wrapping::WordOrGlyph), info].spacing(5),
-        )
-        .on_press_maybe(
-            self.is_loaded()
-                .then_some(Selected::Measurement(self.id))
-                .map(Message::Select),
-        )
-        .style(move |theme, status| {
-            let background = theme.extended_palette().background;
-            let base = button::subtle(theme, status);
-
-            if active {
-                base.with_background(background.weak.color)
-            } else {
-                base
-            }
-        })
-        .width(Fill)
-        .clip(true);
+        let title = row![text(&self.name).wrapping(text::Wrapping::WordOrGlyph)]
+            .push(quality.map(super::quality::badge))
+            .spacing(6);
+
+        let measurement_btn = button(column![title, info].spacing(5))
+            .on_press_maybe(
+                self.is_loaded()
+                    .then_some(Selected::Measurement(self.id))
+                    .map(Message::Select),
+            )
+            .style(move |theme, status| {
+                let background = theme.extended_palette().background;
+                let base = button::subtle(theme, status);
+
+                if active {
+                    base.with_background(background.weak.color)
+                } else {
+                    base
+                }
+            })
+            .width(Fill)
+            .clip(true);
 
         let delete_btn = sidebar::button(icon::delete())
             .style(button::danger)
             .on_press_with(move || Message::Remove(self.id));
 
-        let content = row![
-            measurement_btn,
-            rule::vertical(1.0),
-            right(delete_btn).width(Shrink).padding([0, 6])
-        ];
+        let reload_btn = self.changed_on_disk.then(|| {
+            tooltip(
+                sidebar::button(icon::reset()).on_press_with(move || Message::Reload(self.id)),
+                "Reload from disk",
+                tooltip::Position::Bottom,
+            )
+        });
+
+        let content = row![measurement_btn, rule::vertical(1.0)]
+            .push(reload_btn.map(|btn| right(btn).width(Shrink).padding([0, 6])))
+            .push(right(delete_btn).width(Shrink).padding([0, 6]));
 
         let file_path = self
             .path
@@ -180,6 +330,121 @@ impl Measurement {
         }
     }
 
+    /// Quality derived from the recording only, see [`data::Quality::with_impulse_response`].
+    pub fn quality(&self) -> Option<data::Quality> {
+        self.quality
+    }
+
+    /// Replaces the recording, e.g. by a resampled one.
+    pub fn set_signal(&mut self, signal: Arc<raumklang_core::Measurement>) {
+        self.quality = Some(data::Quality::from_signal(&signal));
+        self.info = Some(data::measurement::Info::from_signal(&signal));
+        self.fingerprint = Some(fingerprint(&signal));
+        self.state = State::Loaded(signal);
+        self.original = None;
+    }
+
+    /// Applies the `preprocessing` to the recording as it was loaded, it
+    /// replaces any previous preprocessing.
+    pub fn preprocess(&mut self, preprocessing: raumklang_core::preprocess::Preprocessing) {
+        let State::Loaded(signal) = &self.state else {
+            return;
+        };
+
+        let original = self.original.take().unwrap_or_else(|| signal.clone());
+
+        let signal = if preprocessing.is_enabled() {
+            self.original = Some(original.clone());
+            Arc::new(preprocessing.apply(&original))
+        } else {
+            original
+        };
+
+        self.quality = Some(data::Quality::from_signal(&signal));
+        self.info = Some(data::measurement::Info::from_signal(&signal));
+        self.state = State::Loaded(signal);
+    }
+
+    /// Takes the recording of `reloaded`, while the settings, e.g. the name
+    /// and the time shift, are kept.
+    pub fn reload(&mut self, reloaded: Measurement) {
+        self.path = reloaded.path;
+        self.quality = reloaded.quality;
+        self.info = reloaded.info;
+        self.fingerprint = reloaded.fingerprint;
+        self.changed_on_disk = false;
+        self.state = reloaded.state;
+        self.original = None;
+    }
+
+    /// Shows the properties of the recording, with the option to remove
+    /// its DC offset.
+    pub fn info_panel(&self) -> Option<Element<'_, Message>> {
+        let info = self.info?;
+
+        let entry = |label, value: String| {
+            column![text(label).size(10), text(value).font(Font::MONOSPACE)].spacing(2)
+        };
+
+        let dc_offset = row![
+            entry("DC offset", format!("{:.2e}", info.dc_offset)),
+            button(text("Remove").size(12))
+                .style(button::secondary)
+                .on_press_maybe(
+                    info.has_dc_offset()
+                        .then_some(Message::RemoveDcOffset(self.id))
+                ),
+        ]
+        .spacing(6)
+        .align_y(Vertical::Bottom);
+
+        let file = self
+            .path
+            .as_ref()
+            .map_or_else(|| "-".to_string(), |path| path.display().to_string());
+        let hash = self
+            .fingerprint
+            .map_or_else(|| "-".to_string(), |hash| format!("{hash:016x}"));
+
+        let content = column![
+            row![
+                entry(
+                    "Duration",
+                    unit::duration_ms(info.duration().as_secs_f32() * 1000.0)
+                ),
+                entry("Samples", info.samples.to_string()),
+                entry("Sample rate", format!("{} Hz", info.sample_rate)),
+                entry("Peak", format!("{} FS", unit::level(info.peak, 1))),
+                entry("RMS", format!("{} FS", unit::level(info.rms, 1))),
+                dc_offset,
+            ]
+            .spacing(20),
+            row![entry("File", file).width(Fill), entry("Hash", hash),].spacing(20),
+        ]
+        .spacing(8);
+
+        Some(
+            container(content)
+                .padding(8)
+                .width(Fill)
+                .style(container::bordered_box)
+                .into(),
+        )
+    }
+
+    pub fn is_changed_on_disk(&self) -> bool {
+        self.changed_on_disk
+    }
+
+    pub fn mark_changed_on_disk(&mut self) {
+        self.changed_on_disk = true;
+    }
+
+    /// Whether both measurements contain the same recording.
+    pub fn is_duplicate_of(&self, other: &Measurement) -> bool {
+        self.fingerprint.is_some() && self.fingerprint == other.fingerprint
+    }
+
     pub fn signal(&self) -> Option<&Arc<raumklang_core::Measurement>> {
         match &self.state {
             State::NotLoaded => None,
@@ -187,40 +452,109 @@ impl Measurement {
         }
     }
 
+    /// Metadata of exported WAV files, the `recording` stimulus is only
+    /// kept, if the measurement was recorded by us.
+    pub fn wav_metadata(
+        &self,
+        recording: &raumklang_core::wav::Metadata,
+    ) -> raumklang_core::wav::Metadata {
+        raumklang_core::wav::Metadata {
+            description: self.name.clone(),
+            date: self.signal().map(|signal| signal.modified),
+            stimulus: recording
+                .stimulus
+                .clone()
+                .filter(|_| self.playback_level.is_some())
+                .map(|stimulus| raumklang_core::wav::Stimulus {
+                    level: self.playback_level,
+                    ..stimulus
+                }),
+            ..recording.clone()
+        }
+    }
+
     pub(crate) fn id(&self) -> Id {
         self.id
     }
+
+    /// Whether all terms of the `filter` occur in the name or the file path,
+    /// ignoring case. The path covers tags and channels that are encoded in
+    /// the folder structure.
+    pub fn matches(&self, filter: &str) -> bool {
+        let name = self.name.to_lowercase();
+        let path = self
+            .path
+            .as_ref()
+            .map(|path| path.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        filter
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .all(|term| name.contains(&term) || path.contains(&term))
+    }
 }
 
 #[derive(Debug, Default, Clone)]
-pub struct List(Vec<Measurement>);
+pub struct List(Store<Measurement>);
+
+/// Returns the measurements, whose file was modified after it was loaded.
+pub async fn changed_on_disk(files: Vec<(Id, PathBuf, SystemTime)>) -> Vec<Id> {
+    let mut changed = vec![];
+
+    for (id, path, loaded) in files {
+        let modified = tokio::fs::metadata(&path)
+            .await
+            .and_then(|metadata| metadata.modified());
+
+        if modified.is_ok_and(|modified| modified > loaded) {
+            changed.push(id);
+        }
+    }
+
+    changed
+}
+
+/// Hashes the sample rate, the length and a subset of the samples, which is
+/// enough to tell recordings apart.
+fn fingerprint(signal: &raumklang_core::Measurement) -> u64 {
+    const SAMPLES: usize = 4096;
+
+    let mut hasher = DefaultHasher::new();
+    signal.sample_rate().hash(&mut hasher);
+    signal.duration().hash(&mut hasher);
+
+    let step = (signal.duration() / SAMPLES).max(1);
+    for sample in signal.iter().step_by(step) {
+        sample.to_bits().hash(&mut hasher);
+    }
+
+    hasher.finish()
+}
 
 impl List {
     pub fn iter(&self) -> impl Iterator<Item = &Measurement> + Clone {
-        self.0.iter()
+        self.0.values()
     }
 
     pub fn loaded(&self) -> impl Iterator<Item = &Measurement> {
-        self.0.iter().filter(|m| m.is_loaded())
+        self.0.values().filter(|m| m.is_loaded())
     }
 
     pub fn push(&mut self, measurement: Measurement) {
-        self.0.push(measurement);
+        self.0.insert_with_id(measurement.id, measurement);
     }
 
     pub fn remove(&mut self, id: Id) -> Option<Measurement> {
-        let index = self
-            .0
-            .iter()
-            .enumerate()
-            .find(|(_, m)| m.id == id)
-            .map(|(i, _)| i)?;
-
-        Some(self.0.remove(index))
+        self.0.remove(id)
     }
 
     pub fn get(&self, id: Id) -> Option<&Measurement> {
-        self.0.iter().find(|m| m.id == id)
+        self.0.get(id)
+    }
+
+    pub fn get_mut(&mut self, id: Id) -> Option<&mut Measurement> {
+        self.0.get_mut(id)
     }
 
     pub fn is_empty(&self) -> bool {
@@ -228,8 +562,22 @@ impl List {
     }
 }
 
-impl Display for Id {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn filter_matches_name_and_path() {
+        let measurement = Measurement::new(
+            "Sweep 1".to_string(),
+            Some(PathBuf::from("/session/left/sweep_1.wav")),
+            None,
+        );
+
+        assert!(measurement.matches(""));
+        assert!(measurement.matches("sweep"));
+        assert!(measurement.matches("LEFT sweep"));
+        assert!(!measurement.matches("right"));
+        assert!(!measurement.matches("sweep right"));
     }
 }
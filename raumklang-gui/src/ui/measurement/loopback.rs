@@ -49,7 +49,14 @@ impl Loopback {
             .and_then(|n| n.to_os_string().into_string().ok())
             .unwrap_or("Unknown".to_string());
 
-        let state = match raumklang_core::Loopback::from_file(path) {
+        let loaded = tokio::task::spawn_blocking({
+            let path = path.to_path_buf();
+            move || raumklang_core::Loopback::from_file(path)
+        })
+        .await
+        .unwrap();
+
+        let state = match loaded {
             Ok(inner) => State::Loaded(inner),
             Err(err) => State::NotLoaded(Arc::new(err)),
         };
@@ -67,7 +74,8 @@ impl Loopback {
                 let dt: DateTime<Utc> = loopback.as_ref().modified.into();
                 column![
                     text("Last modified:").size(10),
-                    text!("{}", dt.format("%x %X")).size(10)
+                    text!("{}", dt.format("%x %X")).size(10),
+                    text!("{} Hz", loopback.sample_rate()).size(10)
                 ]
                 .into()
             }
@@ -117,24 +125,26 @@ impl Loopback {
 
     // TODO error handling
     // FIXME duplicate code with measurement
-    pub fn save(self, path: impl AsRef<Path>) -> impl Future<Output = Option<PathBuf>> {
+    pub fn save(
+        self,
+        path: impl AsRef<Path>,
+        format: raumklang_core::wav::Format,
+        metadata: raumklang_core::wav::Metadata,
+    ) -> impl Future<Output = Option<PathBuf>> {
         let path = path.as_ref().to_path_buf();
         async move {
             tokio::task::spawn_blocking(move || {
                 let signal = self.loaded()?;
 
-                let spec = hound::WavSpec {
-                    channels: 1,
-                    sample_rate: signal.sample_rate(),
-                    bits_per_sample: 32,
-                    sample_format: hound::SampleFormat::Float,
-                };
-
-                let mut writer = hound::WavWriter::create(&path, spec).unwrap();
-                for s in signal.iter() {
-                    writer.write_sample(*s).unwrap();
-                }
-                writer.finalize().unwrap();
+                raumklang_core::wav::write(
+                    &path,
+                    signal.sample_rate(),
+                    1,
+                    signal.iter().copied(),
+                    format,
+                    Some(&metadata),
+                )
+                .unwrap();
 
                 Some(path)
             })
@@ -0,0 +1,38 @@
+use crate::data::{Quality, quality::Rating};
+
+use iced::{
+    Element,
+    widget::{column, container, text, tooltip},
+};
+
+/// Colored dot, that shows the rating of a measurement and explains what
+/// degraded it in a tooltip.
+pub fn badge<'a, Message: 'a>(quality: Quality) -> Element<'a, Message> {
+    let rating = quality.rating();
+
+    let dot = text("●").size(12).style(match rating {
+        Rating::Good => text::success,
+        Rating::Fair => text::warning,
+        Rating::Poor => text::danger,
+    });
+
+    let issues = quality.issues();
+    let details = if issues.is_empty() {
+        column![text("No issues found").size(12)]
+    } else {
+        column(
+            issues
+                .into_iter()
+                .map(|(_, issue)| text(issue).size(12).into()),
+        )
+    };
+
+    tooltip(
+        dot,
+        container(column![text!("Quality: {rating}").size(14), details].spacing(4))
+            .padding(5)
+            .style(container::bordered_box),
+        tooltip::Position::Right,
+    )
+    .into()
+}
@@ -1,14 +1,14 @@
 use iced_aksel::{Measure, Plot, PlotData, Stroke, shape};
 
 use crate::{
-    data::{self, SampleRate},
-    ui::{frequency_response::SpectrumLayer, impulse_response},
+    data::{self, SampleRate, chart::Colormap},
+    ui::{curve::decimate, frequency_response::SpectrumLayer},
 };
 
-use std::future::Future;
+use std::{future::Future, sync::Arc};
 
 #[derive(Debug, Clone, Default)]
-pub struct SpectralDecay(State);
+pub struct SpectralDecay(State, Colormap);
 
 #[derive(Debug, Clone, Default)]
 enum State {
@@ -38,17 +38,18 @@ impl SpectralDecay {
 
     pub fn compute(
         &mut self,
-        impulse_response: &impulse_response::State,
+        subject: data::scheduler::Subject,
+        impulse_response: Option<Arc<raumklang_core::WindowedImpulseResponse>>,
         config: data::spectral_decay::Config,
     ) -> Option<impl Future<Output = data::SpectralDecay> + use<>> {
         if self.result().is_some() {
             return None;
         }
 
-        if let Some(impulse_response) = impulse_response.result() {
+        if let Some(impulse_response) = impulse_response {
             self.0 = State::Computing;
 
-            let computation = data::spectral_decay::compute(impulse_response.data.clone(), config);
+            let computation = data::spectral_decay::compute(subject, impulse_response, config);
 
             Some(computation)
         } else {
@@ -66,6 +67,10 @@ impl SpectralDecay {
         self.0 = State::Computed(spectral_decay);
     }
 
+    pub fn set_colormap(&mut self, colormap: Colormap) {
+        self.1 = colormap;
+    }
+
     pub fn reset(&mut self) {
         self.0 = State::None
     }
@@ -89,13 +94,12 @@ impl PlotData<f32> for SpectralDecay {
             return;
         }
 
-        let gradient = colorous::MAGMA;
         for (i, fr) in sd.iter().enumerate() {
-            let color = gradient.eval_rational(i, sd.len());
+            let color = self.1.eval_rational(i, sd.len());
             let color = iced::Color::from_rgb8(color.r, color.g, color.b);
 
             let line_stroke = Stroke::new(color.scale_alpha(0.8), Measure::Screen(1.0));
-            plot.add_shape(shape::Polyline::new(fr.0.clone(), line_stroke));
+            plot.add_shape(shape::Polyline::new(decimate(&fr.0), line_stroke));
         }
     }
 }
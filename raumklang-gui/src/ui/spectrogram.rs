@@ -1,4 +1,4 @@
-use std::future::Future;
+use std::{future::Future, sync::Arc};
 
 use crate::data::{self, spectrogram};
 
@@ -34,18 +34,18 @@ impl Spectrogram {
 
     pub fn compute(
         &mut self,
-        impulse_response: &super::impulse_response::State,
+        subject: data::scheduler::Subject,
+        impulse_response: Option<Arc<raumklang_core::WindowedImpulseResponse>>,
         config: &spectrogram::Config,
     ) -> Option<impl Future<Output = data::Spectrogram> + use<>> {
         if self.result().is_some() {
             return None;
         }
 
-        if let Some(impulse_response) = impulse_response.result() {
+        if let Some(impulse_response) = impulse_response {
             self.0 = State::Computing;
 
-            let computation =
-                data::spectrogram::compute(impulse_response.data.clone(), config.clone());
+            let computation = data::spectrogram::compute(subject, impulse_response, config.clone());
 
             Some(computation)
         } else {
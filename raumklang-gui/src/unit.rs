@@ -0,0 +1,177 @@
+//! Formats values with their units for charts, panels and exports, using
+//! the decimal separator of the user's locale.
+
+use crate::data::chart::TimeSeriesUnit;
+
+use std::sync::LazyLock;
+
+/// Languages, that separate decimals with a comma.
+const COMMA_LANGUAGES: [&str; 24] = [
+    "bg", "ca", "cs", "da", "de", "el", "es", "et", "fi", "fr", "hr", "hu", "id", "it", "lt", "lv",
+    "nb", "nl", "nn", "pl", "pt", "ro", "ru", "sv",
+];
+
+static CURRENT: LazyLock<Locale> = LazyLock::new(Locale::from_env);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Locale {
+    pub decimal_separator: char,
+}
+
+impl Locale {
+    pub const POSIX: Self = Self {
+        decimal_separator: '.',
+    };
+
+    pub fn current() -> Self {
+        *CURRENT
+    }
+
+    /// Derives the locale from `LC_ALL`, `LC_NUMERIC` or `LANG`, e.g.
+    /// `de_DE.UTF-8`.
+    fn from_env() -> Self {
+        let name = ["LC_ALL", "LC_NUMERIC", "LANG"]
+            .into_iter()
+            .filter_map(|key| std::env::var(key).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default();
+
+        Self::from_name(&name)
+    }
+
+    fn from_name(name: &str) -> Self {
+        let language = name
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        if COMMA_LANGUAGES.contains(&language.as_str()) {
+            Self {
+                decimal_separator: ',',
+            }
+        } else {
+            Self::POSIX
+        }
+    }
+
+    /// Separates the columns of CSV files, a semicolon if the comma is
+    /// taken by the decimals.
+    pub fn csv_delimiter(&self) -> char {
+        if self.decimal_separator == ',' {
+            ';'
+        } else {
+            ','
+        }
+    }
+
+    pub fn number(&self, value: f32, precision: usize) -> String {
+        self.localize(format!("{value:.precision$}"))
+    }
+
+    pub fn signed_number(&self, value: f32, precision: usize) -> String {
+        self.localize(format!("{value:+.precision$}"))
+    }
+
+    /// Frequency in Hz, above 1 kHz in kHz.
+    pub fn frequency(&self, hz: f32) -> String {
+        match hz.abs() {
+            f if f >= 10_000.0 => format!("{} kHz", self.number(hz / 1000.0, 0)),
+            f if f >= 1_000.0 => format!("{} kHz", self.number(hz / 1000.0, 1)),
+            _ => format!("{} Hz", self.number(hz, 0)),
+        }
+    }
+
+    /// Time in milliseconds, from 1 s on in seconds. The precision decreases
+    /// with the magnitude.
+    pub fn duration_ms(&self, ms: f32) -> String {
+        match ms.abs() {
+            t if t >= 1_000.0 => format!("{} s", self.number(ms / 1000.0, 2)),
+            t if t >= 100.0 => format!("{} ms", self.number(ms, 0)),
+            t if t >= 10.0 => format!("{} ms", self.number(ms, 1)),
+            _ => format!("{} ms", self.number(ms, 2)),
+        }
+    }
+
+    /// Position on a time axis, which is already converted into `time_unit`.
+    pub fn time(&self, value: f32, time_unit: TimeSeriesUnit) -> String {
+        let precision = match time_unit {
+            TimeSeriesUnit::Samples => 0,
+            TimeSeriesUnit::Milliseconds => return self.duration_ms(value),
+            TimeSeriesUnit::Seconds => 3,
+            TimeSeriesUnit::Meters => 2,
+        };
+
+        format!("{} {}", self.number(value, precision), time_unit.symbol())
+    }
+
+    pub fn level(&self, db: f32, precision: usize) -> String {
+        format!("{} dB", self.number(db, precision))
+    }
+
+    pub fn signed_level(&self, db: f32, precision: usize) -> String {
+        format!("{} dB", self.signed_number(db, precision))
+    }
+
+    fn localize(&self, s: String) -> String {
+        if self.decimal_separator == '.' {
+            s
+        } else {
+            s.replace('.', &self.decimal_separator.to_string())
+        }
+    }
+}
+
+pub fn frequency(hz: f32) -> String {
+    Locale::current().frequency(hz)
+}
+
+pub fn duration_ms(ms: f32) -> String {
+    Locale::current().duration_ms(ms)
+}
+
+pub fn time(value: f32, time_unit: TimeSeriesUnit) -> String {
+    Locale::current().time(value, time_unit)
+}
+
+pub fn level(db: f32, precision: usize) -> String {
+    Locale::current().level(db, precision)
+}
+
+pub fn signed_level(db: f32, precision: usize) -> String {
+    Locale::current().signed_level(db, precision)
+}
+
+pub fn signed_number(value: f32, precision: usize) -> String {
+    Locale::current().signed_number(value, precision)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn locale_from_name() {
+        assert_eq!(Locale::from_name("de_DE.UTF-8").decimal_separator, ',');
+        assert_eq!(Locale::from_name("en_US.UTF-8"), Locale::POSIX);
+        assert_eq!(Locale::from_name("C"), Locale::POSIX);
+        assert_eq!(Locale::from_name(""), Locale::POSIX);
+    }
+
+    #[test]
+    fn units_are_scaled() {
+        let locale = Locale {
+            decimal_separator: ',',
+        };
+
+        assert_eq!(locale.frequency(63.0), "63 Hz");
+        assert_eq!(locale.frequency(2_500.0), "2,5 kHz");
+        assert_eq!(locale.frequency(16_000.0), "16 kHz");
+        assert_eq!(locale.duration_ms(4.5), "4,50 ms");
+        assert_eq!(locale.duration_ms(250.0), "250 ms");
+        assert_eq!(locale.duration_ms(1_500.0), "1,50 s");
+        assert_eq!(locale.time(0.25, TimeSeriesUnit::Seconds), "0,250 s");
+        assert_eq!(locale.time(441.0, TimeSeriesUnit::Samples), "441 samples");
+        assert_eq!(locale.signed_level(-3.24, 1), "-3,2 dB");
+    }
+}
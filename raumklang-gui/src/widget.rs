@@ -1,5 +1,9 @@
 pub mod meter;
+pub mod rta;
 pub mod sidebar;
+pub mod spectrum;
+pub mod strip_chart;
+pub mod transfer_function;
 
 pub use meter::RmsPeakMeter;
 
@@ -0,0 +1,109 @@
+use crate::audio;
+
+use iced::{
+    Font, Pixels, Point, Rectangle, Renderer, Size, Theme,
+    advanced::mouse,
+    widget::{
+        canvas,
+        text::{Alignment, Ellipsis, LineHeight, Shaping, Wrapping},
+    },
+};
+
+const MIN_FREQUENCY: f32 = 17.0;
+const MAX_FREQUENCY: f32 = 22_000.0;
+const MIN_LEVEL: f32 = -100.0;
+
+/// Bars of the band levels of an [`audio::Rta`], the peaks are marked on top.
+pub struct Rta<'a> {
+    rta: Option<&'a audio::Rta>,
+    cache: &'a canvas::Cache,
+}
+
+impl<'a> Rta<'a> {
+    pub fn new(rta: Option<&'a audio::Rta>, cache: &'a canvas::Cache) -> Self {
+        Self { rta, cache }
+    }
+}
+
+impl<'a, Message> canvas::Program<Message> for Rta<'a> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<canvas::Geometry<Renderer>> {
+        let font_size = Pixels::from(10);
+        let label_height = 14.0;
+
+        let geometry = self.cache.draw(renderer, bounds.size(), |frame| {
+            let palette = theme.extended_palette();
+
+            let width = bounds.width;
+            let height = bounds.height - label_height;
+
+            let log_range = (MAX_FREQUENCY / MIN_FREQUENCY).log10();
+            let x = |freq: f32| (freq / MIN_FREQUENCY).log10() / log_range * width;
+            let y = |level: f32| level.clamp(MIN_LEVEL, 0.0) / MIN_LEVEL * height;
+
+            frame.fill_rectangle(
+                Point::ORIGIN,
+                Size::new(width, height),
+                palette.background.weak.color,
+            );
+
+            for level in [-20.0, -40.0, -60.0, -80.0] {
+                frame.fill_rectangle(
+                    Point::new(0.0, y(level)),
+                    Size::new(width, 1.0),
+                    palette.background.strong.color,
+                );
+            }
+
+            for (freq, label) in [(100.0, "100"), (1_000.0, "1k"), (10_000.0, "10k")] {
+                frame.fill_text(canvas::Text {
+                    content: label.to_string(),
+                    position: Point::new(x(freq), height + label_height / 2.0),
+                    color: palette.background.base.text,
+                    size: font_size,
+                    font: Font::MONOSPACE,
+                    align_x: Alignment::Center,
+                    align_y: iced::alignment::Vertical::Center,
+                    max_width: f32::INFINITY,
+                    line_height: LineHeight::default(),
+                    shaping: Shaping::Basic,
+                    ellipsis: Ellipsis::default(),
+                    wrapping: Wrapping::default(),
+                });
+            }
+
+            let Some(rta) = self.rta else {
+                return;
+            };
+
+            for band in rta.bands.iter() {
+                let left = x(band.band.lower) + 1.0;
+                let right = x(band.band.upper) - 1.0;
+                let bar_width = (right - left).max(1.0);
+
+                let top = y(band.level);
+                frame.fill_rectangle(
+                    Point::new(left, top),
+                    Size::new(bar_width, height - top),
+                    palette.success.base.color,
+                );
+
+                frame.fill_rectangle(
+                    Point::new(left, y(band.peak)),
+                    Size::new(bar_width, 2.0),
+                    palette.warning.base.color,
+                );
+            }
+        });
+
+        vec![geometry]
+    }
+}
@@ -0,0 +1,145 @@
+use crate::audio;
+
+use iced::{
+    Font, Pixels, Point, Rectangle, Renderer, Size, Theme,
+    advanced::mouse,
+    widget::{
+        canvas::{self, Path, Stroke},
+        text::{Alignment, Ellipsis, LineHeight, Shaping, Wrapping},
+    },
+};
+
+const MIN_FREQUENCY: f32 = 20.0;
+const MIN_LEVEL: f32 = -100.0;
+
+/// Live display of a [`audio::Spectrum`] with a logarithmic frequency axis.
+pub struct Spectrum<'a> {
+    spectrum: Option<&'a audio::Spectrum>,
+    /// Frequency range, that is expected to be played.
+    range: Option<(f32, f32)>,
+    cache: &'a canvas::Cache,
+}
+
+impl<'a> Spectrum<'a> {
+    pub fn new(spectrum: Option<&'a audio::Spectrum>, cache: &'a canvas::Cache) -> Self {
+        Self {
+            spectrum,
+            range: None,
+            cache,
+        }
+    }
+
+    pub fn range(mut self, start: f32, end: f32) -> Self {
+        self.range = Some((start, end));
+        self
+    }
+}
+
+impl<'a, Message> canvas::Program<Message> for Spectrum<'a> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<canvas::Geometry<Renderer>> {
+        let font_size = Pixels::from(10);
+        let label_height = 14.0;
+
+        let geometry = self.cache.draw(renderer, bounds.size(), |frame| {
+            let palette = theme.extended_palette();
+
+            let width = bounds.width;
+            let height = bounds.height - label_height;
+
+            let max_frequency = self
+                .spectrum
+                .map_or(24_000.0, |spectrum| spectrum.sample_rate as f32 / 2.0);
+
+            let log_range = (max_frequency / MIN_FREQUENCY).log10();
+            let x = |freq: f32| (freq / MIN_FREQUENCY).log10() / log_range * width;
+            let y = |level: f32| level.clamp(MIN_LEVEL, 0.0) / MIN_LEVEL * height;
+
+            frame.fill_rectangle(
+                Point::ORIGIN,
+                Size::new(width, height),
+                palette.background.weak.color,
+            );
+
+            if let Some((start, end)) = self.range {
+                frame.fill_rectangle(
+                    Point::new(x(start), 0.0),
+                    Size::new(x(end) - x(start), height),
+                    palette.primary.weak.color.scale_alpha(0.3),
+                );
+            }
+
+            for level in [-20.0, -40.0, -60.0, -80.0] {
+                frame.fill_rectangle(
+                    Point::new(0.0, y(level)),
+                    Size::new(width, 1.0),
+                    palette.background.strong.color,
+                );
+            }
+
+            for (freq, label) in [(100.0, "100"), (1_000.0, "1k"), (10_000.0, "10k")] {
+                let pos = x(freq);
+
+                frame.fill_rectangle(
+                    Point::new(pos, 0.0),
+                    Size::new(1.0, height),
+                    palette.background.strong.color,
+                );
+
+                frame.fill_text(canvas::Text {
+                    content: label.to_string(),
+                    position: Point::new(pos, height + label_height / 2.0),
+                    color: palette.background.base.text,
+                    size: font_size,
+                    font: Font::MONOSPACE,
+                    align_x: Alignment::Center,
+                    align_y: iced::alignment::Vertical::Center,
+                    max_width: f32::INFINITY,
+                    line_height: LineHeight::default(),
+                    shaping: Shaping::Basic,
+                    ellipsis: Ellipsis::default(),
+                    wrapping: Wrapping::default(),
+                });
+            }
+
+            let Some(spectrum) = self.spectrum else {
+                return;
+            };
+
+            let path = Path::new(|builder| {
+                let points = spectrum
+                    .levels
+                    .iter()
+                    .enumerate()
+                    .map(|(bin, level)| (spectrum.frequency(bin), *level))
+                    .skip_while(|(freq, _)| *freq < MIN_FREQUENCY)
+                    .map(|(freq, level)| Point::new(x(freq), y(level)));
+
+                for (i, point) in points.enumerate() {
+                    if i == 0 {
+                        builder.move_to(point);
+                    } else {
+                        builder.line_to(point);
+                    }
+                }
+            });
+
+            frame.stroke(
+                &path,
+                Stroke::default()
+                    .with_width(1.5)
+                    .with_color(palette.success.base.color),
+            );
+        });
+
+        vec![geometry]
+    }
+}
@@ -0,0 +1,179 @@
+use raumklang_core::spl::Record;
+
+use iced::{
+    Font, Pixels, Point, Rectangle, Renderer, Size, Theme,
+    advanced::mouse,
+    widget::{
+        canvas::{self, Path, Stroke},
+        text::{Alignment, Ellipsis, LineHeight, Shaping, Wrapping},
+    },
+};
+
+use std::{collections::VecDeque, time::Duration};
+
+/// Range of the level axis in dB, it follows the maximum level.
+const LEVEL_RANGE: f32 = 60.0;
+
+/// Sound pressure levels over time, the latest record on the right. The
+/// `Leq` of every record is drawn on top of its `Lmax`.
+pub struct StripChart<'a> {
+    records: &'a VecDeque<Record>,
+    /// Number of records that fit into the chart.
+    capacity: usize,
+    interval: Duration,
+    cache: &'a canvas::Cache,
+}
+
+impl<'a> StripChart<'a> {
+    pub fn new(
+        records: &'a VecDeque<Record>,
+        capacity: usize,
+        interval: Duration,
+        cache: &'a canvas::Cache,
+    ) -> Self {
+        Self {
+            records,
+            capacity,
+            interval,
+            cache,
+        }
+    }
+}
+
+impl<'a, Message> canvas::Program<Message> for StripChart<'a> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<canvas::Geometry<Renderer>> {
+        let font_size = Pixels::from(10);
+        let label_height = 14.0;
+
+        let geometry = self.cache.draw(renderer, bounds.size(), |frame| {
+            let palette = theme.extended_palette();
+
+            let width = bounds.width;
+            let height = bounds.height - label_height;
+
+            let max_level = self
+                .records
+                .iter()
+                .map(|record| record.lmax)
+                .filter(|level| level.is_finite())
+                .max_by(f32::total_cmp)
+                .map_or(100.0, |level| (level / 10.0).ceil() * 10.0 + 10.0);
+
+            let step = width / self.capacity.saturating_sub(1).max(1) as f32;
+            let offset = self.capacity.saturating_sub(self.records.len());
+            let x = |index: usize| (offset + index) as f32 * step;
+            let y = |level: f32| (max_level - level).clamp(0.0, LEVEL_RANGE) / LEVEL_RANGE * height;
+
+            let label = |content: String, position, align_x| canvas::Text {
+                content,
+                position,
+                color: palette.background.base.text,
+                size: font_size,
+                font: Font::MONOSPACE,
+                align_x,
+                align_y: iced::alignment::Vertical::Center,
+                max_width: f32::INFINITY,
+                line_height: LineHeight::default(),
+                shaping: Shaping::Basic,
+                ellipsis: Ellipsis::default(),
+                wrapping: Wrapping::default(),
+            };
+
+            frame.fill_rectangle(
+                Point::ORIGIN,
+                Size::new(width, height),
+                palette.background.weak.color,
+            );
+
+            for step in 1..6 {
+                let level = max_level - step as f32 * 10.0;
+
+                frame.fill_rectangle(
+                    Point::new(0.0, y(level)),
+                    Size::new(width, 1.0),
+                    palette.background.strong.color,
+                );
+
+                frame.fill_text(label(
+                    format!("{level:.0} dB"),
+                    Point::new(4.0, y(level) - font_size.0),
+                    Alignment::Left,
+                ));
+            }
+
+            // time labels relative to now, every 10 seconds
+            let records_per_label = (10.0 / self.interval.as_secs_f32()).round() as usize;
+            for (i, index) in (0..self.capacity)
+                .rev()
+                .step_by(records_per_label.max(1))
+                .enumerate()
+                .skip(1)
+            {
+                let pos = index as f32 * step;
+
+                frame.fill_rectangle(
+                    Point::new(pos, 0.0),
+                    Size::new(1.0, height),
+                    palette.background.strong.color,
+                );
+
+                frame.fill_text(label(
+                    format!("-{} s", i * 10),
+                    Point::new(pos, height + label_height / 2.0),
+                    Alignment::Center,
+                ));
+            }
+
+            let lmax = self
+                .records
+                .iter()
+                .enumerate()
+                .map(|(i, record)| Point::new(x(i), y(record.lmax)));
+
+            let leq = self
+                .records
+                .iter()
+                .enumerate()
+                .map(|(i, record)| Point::new(x(i), y(record.leq)));
+
+            frame.stroke(
+                &curve(lmax),
+                Stroke::default()
+                    .with_width(1.0)
+                    .with_color(palette.warning.base.color),
+            );
+
+            frame.stroke(
+                &curve(leq),
+                Stroke::default()
+                    .with_width(1.5)
+                    .with_color(palette.success.base.color),
+            );
+        });
+
+        vec![geometry]
+    }
+}
+
+fn curve(points: impl Iterator<Item = Point>) -> Path {
+    Path::new(|builder| {
+        let points = points.filter(|point| point.y.is_finite());
+
+        for (i, point) in points.enumerate() {
+            if i == 0 {
+                builder.move_to(point);
+            } else {
+                builder.line_to(point);
+            }
+        }
+    })
+}
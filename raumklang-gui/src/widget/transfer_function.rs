@@ -0,0 +1,170 @@
+use crate::audio;
+
+use iced::{
+    Font, Pixels, Point, Rectangle, Renderer, Size, Theme,
+    advanced::mouse,
+    widget::{
+        canvas::{self, Path, Stroke},
+        text::{Alignment, Ellipsis, LineHeight, Shaping, Wrapping},
+    },
+};
+
+const MIN_FREQUENCY: f32 = 20.0;
+const MAX_FREQUENCY: f32 = 20_000.0;
+/// Range of the level axis in dB, it follows the maximum level.
+const LEVEL_RANGE: f32 = 60.0;
+
+/// Live display of a [`audio::TransferFunction`], the level on top of the
+/// coherence, which spans the full height.
+pub struct TransferFunction<'a> {
+    transfer_function: Option<&'a audio::TransferFunction>,
+    cache: &'a canvas::Cache,
+}
+
+impl<'a> TransferFunction<'a> {
+    pub fn new(
+        transfer_function: Option<&'a audio::TransferFunction>,
+        cache: &'a canvas::Cache,
+    ) -> Self {
+        Self {
+            transfer_function,
+            cache,
+        }
+    }
+}
+
+impl<'a, Message> canvas::Program<Message> for TransferFunction<'a> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<canvas::Geometry<Renderer>> {
+        let font_size = Pixels::from(10);
+        let label_height = 14.0;
+
+        let geometry = self.cache.draw(renderer, bounds.size(), |frame| {
+            let palette = theme.extended_palette();
+
+            let width = bounds.width;
+            let height = bounds.height - label_height;
+
+            let max_level = self
+                .transfer_function
+                .and_then(|transfer_function| {
+                    transfer_function
+                        .points
+                        .iter()
+                        .map(|point| point.level)
+                        .filter(|level| level.is_finite())
+                        .max_by(f32::total_cmp)
+                })
+                .map_or(0.0, |level| (level / 10.0).ceil() * 10.0 + 10.0);
+
+            let log_range = (MAX_FREQUENCY / MIN_FREQUENCY).log10();
+            let x = |freq: f32| (freq / MIN_FREQUENCY).log10() / log_range * width;
+            let y = |level: f32| (max_level - level).clamp(0.0, LEVEL_RANGE) / LEVEL_RANGE * height;
+
+            let label = |content: String, position, align_x| canvas::Text {
+                content,
+                position,
+                color: palette.background.base.text,
+                size: font_size,
+                font: Font::MONOSPACE,
+                align_x,
+                align_y: iced::alignment::Vertical::Center,
+                max_width: f32::INFINITY,
+                line_height: LineHeight::default(),
+                shaping: Shaping::Basic,
+                ellipsis: Ellipsis::default(),
+                wrapping: Wrapping::default(),
+            };
+
+            frame.fill_rectangle(
+                Point::ORIGIN,
+                Size::new(width, height),
+                palette.background.weak.color,
+            );
+
+            for step in 1..6 {
+                let level = max_level - step as f32 * 10.0;
+
+                frame.fill_rectangle(
+                    Point::new(0.0, y(level)),
+                    Size::new(width, 1.0),
+                    palette.background.strong.color,
+                );
+
+                frame.fill_text(label(
+                    format!("{level:.0} dB"),
+                    Point::new(4.0, y(level) - font_size.0),
+                    Alignment::Left,
+                ));
+            }
+
+            for (freq, text) in [(100.0, "100"), (1_000.0, "1k"), (10_000.0, "10k")] {
+                let pos = x(freq);
+
+                frame.fill_rectangle(
+                    Point::new(pos, 0.0),
+                    Size::new(1.0, height),
+                    palette.background.strong.color,
+                );
+
+                frame.fill_text(label(
+                    text.to_string(),
+                    Point::new(pos, height + label_height / 2.0),
+                    Alignment::Center,
+                ));
+            }
+
+            let Some(transfer_function) = self.transfer_function else {
+                return;
+            };
+
+            let coherence = transfer_function.points.iter().map(|point| {
+                let coherence = point.coherence.clamp(0.0, 1.0);
+                Point::new(x(point.frequency), (1.0 - coherence) * height)
+            });
+
+            let levels = transfer_function
+                .points
+                .iter()
+                .map(|point| Point::new(x(point.frequency), y(point.level)));
+
+            frame.stroke(
+                &curve(coherence),
+                Stroke::default()
+                    .with_width(1.0)
+                    .with_color(palette.secondary.base.color),
+            );
+
+            frame.stroke(
+                &curve(levels),
+                Stroke::default()
+                    .with_width(1.5)
+                    .with_color(palette.success.base.color),
+            );
+        });
+
+        vec![geometry]
+    }
+}
+
+fn curve(points: impl Iterator<Item = Point>) -> Path {
+    Path::new(|builder| {
+        let points = points.filter(|point| point.y.is_finite());
+
+        for (i, point) in points.enumerate() {
+            if i == 0 {
+                builder.move_to(point);
+            } else {
+                builder.line_to(point);
+            }
+        }
+    })
+}